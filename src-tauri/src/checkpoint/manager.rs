@@ -1,16 +1,17 @@
 use anyhow::{Context, Result};
 use chrono::{DateTime, TimeZone, Utc};
 use log;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
 use super::{
     storage::{self, CheckpointStorage},
     Checkpoint, CheckpointMetadata, CheckpointPaths, CheckpointResult, CheckpointStrategy,
-    FileSnapshot, FileState, FileTracker, SessionTimeline,
+    FileSnapshot, FileState, FileTracker, RestoreMode, SessionTimeline, TimelineFork, TimelineNode,
 };
 
 /// Manages checkpoint operations for a session
@@ -22,6 +23,12 @@ pub struct CheckpointManager {
     pub storage: Arc<CheckpointStorage>,
     timeline: Arc<RwLock<SessionTimeline>>,
     current_messages: Arc<RwLock<Vec<String>>>, // JSONL messages
+    /// Tokens streamed since the last checkpoint, for the token threshold strategy
+    tokens_since_checkpoint: Arc<AtomicU64>,
+    /// Streamed assistant messages whose usage is already counted
+    counted_message_ids: Arc<RwLock<HashSet<String>>>,
+    /// A matching tool call is waiting for its result before the checkpoint is taken
+    pending_tool_checkpoint: Arc<AtomicBool>,
 }
 
 impl CheckpointManager {
@@ -57,9 +64,21 @@ impl CheckpointManager {
             storage,
             timeline: Arc::new(RwLock::new(timeline)),
             current_messages: Arc::new(RwLock::new(Vec::new())),
+            tokens_since_checkpoint: Arc::new(AtomicU64::new(0)),
+            counted_message_ids: Arc::new(RwLock::new(HashSet::new())),
+            pending_tool_checkpoint: Arc::new(AtomicBool::new(false)),
         })
     }
 
+    /// Replace the tracked messages with a transcript read from disk
+    pub async fn set_messages(&self, messages: Vec<String>) -> Result<()> {
+        self.current_messages.write().await.clear();
+        for message in messages {
+            self.track_message(message).await?;
+        }
+        Ok(())
+    }
+
     /// Track a new message in the session
     pub async fn track_message(&self, jsonl_message: String) -> Result<()> {
         let mut messages = self.current_messages.write().await;
@@ -238,6 +257,14 @@ impl CheckpointManager {
         let checkpoint_id = storage::CheckpointStorage::generate_checkpoint_id();
 
         // Create file snapshots
+        let file_changes = self
+            .file_tracker
+            .read()
+            .await
+            .tracked_files
+            .values()
+            .filter(|state| state.is_modified)
+            .count();
         let file_snapshots = self.create_file_snapshots(&checkpoint_id).await?;
 
         // Generate checkpoint struct
@@ -261,7 +288,7 @@ impl CheckpointManager {
                 total_tokens,
                 model_used,
                 user_prompt,
-                file_changes: file_snapshots.len(),
+                file_changes,
                 snapshot_size: storage::CheckpointStorage::estimate_checkpoint_size(
                     &messages.join("\n"),
                     &file_snapshots,
@@ -292,6 +319,11 @@ impl CheckpointManager {
         let mut timeline = self.timeline.write().await;
         timeline.current_checkpoint_id = Some(checkpoint_id);
 
+        // Restart the counters of the automatic strategies
+        self.tokens_since_checkpoint.store(0, Ordering::Relaxed);
+        self.pending_tool_checkpoint.store(false, Ordering::Relaxed);
+        self.counted_message_ids.write().await.clear();
+
         // Reset file tracker
         let mut tracker = self.file_tracker.write().await;
         for (_, state) in tracker.tracked_files.iter_mut() {
@@ -403,8 +435,9 @@ impl CheckpointManager {
         let mut snapshots = Vec::new();
 
         for (rel_path, state) in &tracker.tracked_files {
-            // Skip files that haven't been modified
-            if !state.is_modified {
+            // Every existing file is snapshot so that restoring a checkpoint is
+            // complete; unchanged contents are shared through the content pool
+            if !state.is_modified && !state.exists {
                 continue;
             }
 
@@ -449,12 +482,69 @@ impl CheckpointManager {
     }
 
     /// Restore a checkpoint
-    pub async fn restore_checkpoint(&self, checkpoint_id: &str) -> Result<CheckpointResult> {
+    pub async fn restore_checkpoint(
+        &self,
+        checkpoint_id: &str,
+        mode: RestoreMode,
+    ) -> Result<CheckpointResult> {
         // Load checkpoint data
         let (checkpoint, file_snapshots, messages) =
             self.storage
                 .load_checkpoint(&self.project_id, &self.session_id, checkpoint_id)?;
 
+        let result = self
+            .apply_checkpoint(checkpoint, file_snapshots, messages, mode)
+            .await?;
+        if !mode.restores_conversation() {
+            // The conversation, and with it the timeline position, stays where it is
+            return Ok(result);
+        }
+
+        // Persist the new position so later checkpoints branch from here
+        let mut timeline = self.timeline.write().await;
+        timeline.current_checkpoint_id = Some(checkpoint_id.to_string());
+        let paths =
+            CheckpointPaths::new(&self.storage.claude_dir, &self.project_id, &self.session_id);
+        self.storage
+            .save_timeline(&paths.timeline_file, &timeline)?;
+
+        Ok(result)
+    }
+
+    /// Rewind the project files and/or tracked messages to a loaded checkpoint
+    async fn apply_checkpoint(
+        &self,
+        checkpoint: Checkpoint,
+        file_snapshots: Vec<FileSnapshot>,
+        messages: String,
+        mode: RestoreMode,
+    ) -> Result<CheckpointResult> {
+        let (files_processed, warnings) = if mode.restores_files() {
+            self.restore_files(&file_snapshots).await
+        } else {
+            (0, Vec::new())
+        };
+
+        if mode.restores_conversation() {
+            // Update current messages
+            let mut current_messages = self.current_messages.write().await;
+            current_messages.clear();
+            for line in messages.lines() {
+                current_messages.push(line.to_string());
+            }
+        }
+
+        Ok(CheckpointResult {
+            checkpoint: checkpoint.clone(),
+            files_processed,
+            warnings,
+        })
+    }
+
+    /// Rewind the project files to a checkpoint's snapshots
+    ///
+    /// Returns the number of files written or deleted and the failures.
+    async fn restore_files(&self, file_snapshots: &[FileSnapshot]) -> (usize, Vec<String>) {
         // First, collect all files currently in the project to handle deletions
         fn collect_all_project_files(
             dir: &std::path::Path,
@@ -488,7 +578,7 @@ impl CheckpointManager {
 
         // Create a set of files that should exist after restore
         let mut checkpoint_files = std::collections::HashSet::new();
-        for snapshot in &file_snapshots {
+        for snapshot in file_snapshots {
             if !snapshot.is_deleted {
                 checkpoint_files.insert(snapshot.file_path.clone());
             }
@@ -552,7 +642,7 @@ impl CheckpointManager {
         let _ = remove_empty_dirs(&self.project_path, &self.project_path);
 
         // Restore files from checkpoint
-        for snapshot in &file_snapshots {
+        for snapshot in file_snapshots {
             match self.restore_file_snapshot(snapshot).await {
                 Ok(_) => files_processed += 1,
                 Err(e) => warnings.push(format!(
@@ -563,21 +653,10 @@ impl CheckpointManager {
             }
         }
 
-        // Update current messages
-        let mut current_messages = self.current_messages.write().await;
-        current_messages.clear();
-        for line in messages.lines() {
-            current_messages.push(line.to_string());
-        }
-
-        // Update timeline
-        let mut timeline = self.timeline.write().await;
-        timeline.current_checkpoint_id = Some(checkpoint_id.to_string());
-
         // Update file tracker
         let mut tracker = self.file_tracker.write().await;
         tracker.tracked_files.clear();
-        for snapshot in &file_snapshots {
+        for snapshot in file_snapshots {
             if !snapshot.is_deleted {
                 tracker.tracked_files.insert(
                     snapshot.file_path.clone(),
@@ -591,11 +670,7 @@ impl CheckpointManager {
             }
         }
 
-        Ok(CheckpointResult {
-            checkpoint: checkpoint.clone(),
-            files_processed,
-            warnings,
-        })
+        (files_processed, warnings)
     }
 
     /// Restore a single file from snapshot
@@ -629,6 +704,15 @@ impl CheckpointManager {
         Ok(())
     }
 
+    /// Reload the timeline after it was changed on disk, e.g. by a cleanup
+    pub async fn reload_timeline(&self) -> Result<()> {
+        let paths =
+            CheckpointPaths::new(&self.storage.claude_dir, &self.project_id, &self.session_id);
+        let timeline = self.storage.load_timeline(&paths.timeline_file)?;
+        *self.timeline.write().await = timeline;
+        Ok(())
+    }
+
     /// Get the current timeline
     pub async fn get_timeline(&self) -> SessionTimeline {
         self.timeline.read().await.clone()
@@ -657,91 +741,183 @@ impl CheckpointManager {
         }
     }
 
-    /// Fork from a checkpoint
+    /// Fork this session from a checkpoint of `source_session_id`
+    ///
+    /// The files and messages of the checkpoint are restored and a new checkpoint
+    /// is created on top of it. Forking within the same session adds a branch to
+    /// its timeline; forking into a new session starts a timeline that records
+    /// where it came from.
     pub async fn fork_from_checkpoint(
         &self,
+        source_session_id: &str,
         checkpoint_id: &str,
         description: Option<String>,
     ) -> Result<CheckpointResult> {
         // Load the checkpoint to fork from
-        let (_base_checkpoint, _, _) =
+        let (base_checkpoint, file_snapshots, messages) =
             self.storage
-                .load_checkpoint(&self.project_id, &self.session_id, checkpoint_id)?;
+                .load_checkpoint(&self.project_id, source_session_id, checkpoint_id)?;
 
         // Restore to that checkpoint first
-        self.restore_checkpoint(checkpoint_id).await?;
+        self.apply_checkpoint(base_checkpoint, file_snapshots, messages, RestoreMode::Full)
+            .await?;
+
+        let is_new_branch = source_session_id == self.session_id;
+        if !is_new_branch {
+            let mut timeline = self.timeline.write().await;
+            timeline.forked_from = Some(TimelineFork {
+                session_id: source_session_id.to_string(),
+                checkpoint_id: checkpoint_id.to_string(),
+            });
+            timeline.current_checkpoint_id = None;
+            let paths =
+                CheckpointPaths::new(&self.storage.claude_dir, &self.project_id, &self.session_id);
+            self.storage
+                .save_timeline(&paths.timeline_file, &timeline)?;
+        }
 
         // Create a new checkpoint with the fork
         let fork_description =
             description.unwrap_or_else(|| format!("Fork from checkpoint {}", &checkpoint_id[..8]));
 
-        self.create_checkpoint(Some(fork_description), Some(checkpoint_id.to_string()))
-            .await
+        self.create_checkpoint(
+            Some(fork_description),
+            is_new_branch.then(|| checkpoint_id.to_string()),
+        )
+        .await
+    }
+
+    /// Checkpoints from the root of the timeline down to `checkpoint_id`
+    pub async fn checkpoint_path(&self, checkpoint_id: &str) -> Option<Vec<Checkpoint>> {
+        fn walk(node: &TimelineNode, target: &str, path: &mut Vec<Checkpoint>) -> bool {
+            path.push(node.checkpoint.clone());
+            if node.checkpoint.id == target
+                || node.children.iter().any(|child| walk(child, target, path))
+            {
+                return true;
+            }
+            path.pop();
+            false
+        }
+
+        let timeline = self.timeline.read().await;
+        let mut path = Vec::new();
+        let root = timeline.root_node.as_ref()?;
+        walk(root, checkpoint_id, &mut path).then_some(path)
     }
 
     /// Check if auto-checkpoint should be triggered
     pub async fn should_auto_checkpoint(&self, message: &str) -> bool {
-        let timeline = self.timeline.read().await;
+        let (enabled, strategy, token_threshold) = {
+            let timeline = self.timeline.read().await;
+            (
+                timeline.auto_checkpoint_enabled,
+                timeline.checkpoint_strategy.clone(),
+                timeline.token_threshold,
+            )
+        };
+        if !enabled {
+            return false;
+        }
+        let Ok(msg) = serde_json::from_str::<serde_json::Value>(message) else {
+            return false;
+        };
+
+        match strategy {
+            CheckpointStrategy::Manual => false,
+            // Check if message is a user prompt
+            CheckpointStrategy::PerPrompt => is_user_prompt(&msg),
+            // Check if message contains tool use
+            CheckpointStrategy::PerToolUse => has_tool_use(&msg, false),
+            // Smart strategy: checkpoint after destructive operations
+            CheckpointStrategy::Smart => has_tool_use(&msg, true),
+            CheckpointStrategy::TokenThreshold => {
+                self.tokens_since_checkpoint.load(Ordering::Relaxed) >= token_threshold
+            }
+        }
+    }
 
-        if !timeline.auto_checkpoint_enabled {
+    /// Feed one event of a streamed execution; returns true when a checkpoint is due now
+    ///
+    /// Prompt checkpoints are taken when an execution starts, before it changes
+    /// anything. Tool checkpoints wait for the tool's result so they capture its
+    /// effect.
+    pub async fn observe_stream_event(&self, line: &str) -> bool {
+        let (enabled, strategy, token_threshold) = {
+            let timeline = self.timeline.read().await;
+            (
+                timeline.auto_checkpoint_enabled,
+                timeline.checkpoint_strategy.clone(),
+                timeline.token_threshold,
+            )
+        };
+        if !enabled {
             return false;
         }
+        let Ok(msg) = serde_json::from_str::<serde_json::Value>(line) else {
+            return false;
+        };
+        self.count_stream_tokens(&msg).await;
 
-        match timeline.checkpoint_strategy {
+        let is_tool_result = msg.get("type").and_then(|t| t.as_str()) == Some("user");
+        match strategy {
             CheckpointStrategy::Manual => false,
             CheckpointStrategy::PerPrompt => {
-                // Check if message is a user prompt
-                if let Ok(msg) = serde_json::from_str::<serde_json::Value>(message) {
-                    msg.get("type").and_then(|t| t.as_str()) == Some("user")
-                } else {
-                    false
-                }
+                msg.get("type").and_then(|t| t.as_str()) == Some("system")
+                    && msg.get("subtype").and_then(|t| t.as_str()) == Some("init")
             }
-            CheckpointStrategy::PerToolUse => {
-                // Check if message contains tool use
-                if let Ok(msg) = serde_json::from_str::<serde_json::Value>(message) {
-                    if let Some(content) = msg
-                        .get("message")
-                        .and_then(|m| m.get("content"))
-                        .and_then(|c| c.as_array())
-                    {
-                        content.iter().any(|item| {
-                            item.get("type").and_then(|t| t.as_str()) == Some("tool_use")
-                        })
-                    } else {
-                        false
-                    }
-                } else {
-                    false
+            CheckpointStrategy::PerToolUse | CheckpointStrategy::Smart => {
+                if is_tool_result {
+                    return self.pending_tool_checkpoint.swap(false, Ordering::Relaxed);
                 }
-            }
-            CheckpointStrategy::Smart => {
-                // Smart strategy: checkpoint after destructive operations
-                if let Ok(msg) = serde_json::from_str::<serde_json::Value>(message) {
-                    if let Some(content) = msg
-                        .get("message")
-                        .and_then(|m| m.get("content"))
-                        .and_then(|c| c.as_array())
-                    {
-                        content.iter().any(|item| {
-                            if item.get("type").and_then(|t| t.as_str()) == Some("tool_use") {
-                                let tool_name =
-                                    item.get("name").and_then(|n| n.as_str()).unwrap_or("");
-                                matches!(
-                                    tool_name.to_lowercase().as_str(),
-                                    "write" | "edit" | "multiedit" | "bash" | "rm" | "delete"
-                                )
-                            } else {
-                                false
-                            }
-                        })
-                    } else {
-                        false
-                    }
-                } else {
-                    false
+                let destructive_only = matches!(strategy, CheckpointStrategy::Smart);
+                if has_tool_use(&msg, destructive_only) {
+                    self.pending_tool_checkpoint.store(true, Ordering::Relaxed);
                 }
+                false
             }
+            // Reset right away, so events streamed while the checkpoint is being taken
+            // don't trigger another one
+            CheckpointStrategy::TokenThreshold => self
+                .tokens_since_checkpoint
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |tokens| {
+                    (tokens >= token_threshold).then_some(0)
+                })
+                .is_ok(),
+        }
+    }
+
+    /// Add the usage of a streamed assistant message, once per message ID
+    async fn count_stream_tokens(&self, msg: &serde_json::Value) {
+        if msg.get("type").and_then(|t| t.as_str()) != Some("assistant") {
+            return;
+        }
+        let Some(message) = msg.get("message") else {
+            return;
+        };
+        if let Some(id) = message.get("id").and_then(|id| id.as_str()) {
+            // The CLI repeats a message's usage for every content block
+            if !self
+                .counted_message_ids
+                .write()
+                .await
+                .insert(id.to_string())
+            {
+                return;
+            }
+        }
+        if let Some(usage) = message.get("usage") {
+            // Cache reads repeat the whole context on every turn, so they don't count
+            let tokens: u64 = [
+                "input_tokens",
+                "output_tokens",
+                "cache_creation_input_tokens",
+            ]
+            .iter()
+            .filter_map(|key| usage.get(*key).and_then(|t| t.as_u64()))
+            .sum();
+            self.tokens_since_checkpoint
+                .fetch_add(tokens, Ordering::Relaxed);
         }
     }
 
@@ -750,10 +926,14 @@ impl CheckpointManager {
         &self,
         auto_checkpoint_enabled: bool,
         checkpoint_strategy: CheckpointStrategy,
+        token_threshold: Option<u64>,
     ) -> Result<()> {
         let mut timeline = self.timeline.write().await;
         timeline.auto_checkpoint_enabled = auto_checkpoint_enabled;
         timeline.checkpoint_strategy = checkpoint_strategy;
+        if let Some(token_threshold) = token_threshold {
+            timeline.token_threshold = token_threshold;
+        }
 
         // Save updated timeline
         let claude_dir = self.storage.claude_dir.clone();
@@ -785,3 +965,39 @@ impl CheckpointManager {
             .max()
     }
 }
+
+/// Whether a transcript message is a prompt typed by the user rather than a tool result
+fn is_user_prompt(msg: &serde_json::Value) -> bool {
+    if msg.get("type").and_then(|t| t.as_str()) != Some("user") {
+        return false;
+    }
+    match msg.get("message").and_then(|m| m.get("content")) {
+        Some(serde_json::Value::String(_)) => true,
+        Some(serde_json::Value::Array(content)) => content
+            .iter()
+            .any(|item| item.get("type").and_then(|t| t.as_str()) == Some("text")),
+        _ => false,
+    }
+}
+
+/// Whether a message calls a tool; with `destructive_only`, one that can change files
+fn has_tool_use(msg: &serde_json::Value, destructive_only: bool) -> bool {
+    let Some(content) = msg
+        .get("message")
+        .and_then(|m| m.get("content"))
+        .and_then(|c| c.as_array())
+    else {
+        return false;
+    };
+    content.iter().any(|item| {
+        if item.get("type").and_then(|t| t.as_str()) != Some("tool_use") {
+            return false;
+        }
+        let tool_name = item.get("name").and_then(|n| n.as_str()).unwrap_or("");
+        !destructive_only
+            || matches!(
+                tool_name.to_lowercase().as_str(),
+                "write" | "edit" | "multiedit" | "bash" | "rm" | "delete"
+            )
+    })
+}
@@ -1,14 +1,14 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 
 pub mod manager;
 pub mod state;
 pub mod storage;
 
 /// Represents a checkpoint in the session timeline
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
 #[serde(rename_all = "camelCase")]
 pub struct Checkpoint {
     /// Unique identifier for the checkpoint
@@ -30,7 +30,7 @@ pub struct Checkpoint {
 }
 
 /// Metadata associated with a checkpoint
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
 #[serde(rename_all = "camelCase")]
 pub struct CheckpointMetadata {
     /// Total tokens used up to this point
@@ -46,7 +46,7 @@ pub struct CheckpointMetadata {
 }
 
 /// Represents a snapshot of a file at a checkpoint
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
 #[serde(rename_all = "camelCase")]
 pub struct FileSnapshot {
     /// Checkpoint this snapshot belongs to
@@ -66,7 +66,7 @@ pub struct FileSnapshot {
 }
 
 /// Represents a node in the timeline tree
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
 #[serde(rename_all = "camelCase")]
 pub struct TimelineNode {
     /// The checkpoint at this node
@@ -78,7 +78,7 @@ pub struct TimelineNode {
 }
 
 /// The complete timeline for a session
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
 #[serde(rename_all = "camelCase")]
 pub struct SessionTimeline {
     /// Session ID this timeline belongs to
@@ -91,12 +91,26 @@ pub struct SessionTimeline {
     pub auto_checkpoint_enabled: bool,
     /// Strategy for automatic checkpoints
     pub checkpoint_strategy: CheckpointStrategy,
+    /// Tokens between checkpoints of the token threshold strategy
+    #[serde(default = "default_token_threshold")]
+    pub token_threshold: u64,
     /// Total number of checkpoints in timeline
     pub total_checkpoints: usize,
+    /// Checkpoint of another session this timeline was forked from
+    #[serde(default)]
+    pub forked_from: Option<TimelineFork>,
+}
+
+/// Origin of a session forked from another session's checkpoint
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineFork {
+    pub session_id: String,
+    pub checkpoint_id: String,
 }
 
 /// Strategy for automatic checkpoint creation
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
 #[serde(rename_all = "snake_case")]
 pub enum CheckpointStrategy {
     /// Only create checkpoints manually
@@ -107,6 +121,93 @@ pub enum CheckpointStrategy {
     PerToolUse,
     /// Create checkpoint after destructive operations
     Smart,
+    /// Create checkpoint once a number of tokens was used since the last one
+    TokenThreshold,
+}
+
+/// What a restore rewinds
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum RestoreMode {
+    /// Files and conversation
+    #[default]
+    Full,
+    /// Only the files; the conversation continues from where it is
+    FilesOnly,
+    /// Only the conversation position; the current files are kept
+    ConversationOnly,
+}
+
+impl RestoreMode {
+    pub fn restores_files(self) -> bool {
+        self != RestoreMode::ConversationOnly
+    }
+
+    pub fn restores_conversation(self) -> bool {
+        self != RestoreMode::FilesOnly
+    }
+}
+
+/// Which checkpoints of a session survive a cleanup
+///
+/// A checkpoint is kept when either rule keeps it; the current checkpoint is
+/// always kept. Without any rule nothing is removed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionPolicy {
+    /// Keep this many of the newest checkpoints
+    pub keep_last: Option<usize>,
+    /// Keep the newest checkpoint of each of the last this many days that have checkpoints
+    pub keep_daily: Option<usize>,
+    /// Apply the policy after every automatic checkpoint
+    #[serde(default)]
+    pub auto_cleanup: bool,
+}
+
+impl RetentionPolicy {
+    /// IDs of the checkpoints the policy removes
+    pub fn checkpoints_to_remove(
+        &self,
+        checkpoints: &[Checkpoint],
+        current_checkpoint_id: Option<&str>,
+    ) -> Vec<String> {
+        if self.keep_last.is_none() && self.keep_daily.is_none() {
+            return Vec::new();
+        }
+
+        let mut newest_first: Vec<&Checkpoint> = checkpoints.iter().collect();
+        newest_first.sort_by_key(|c| std::cmp::Reverse(c.timestamp));
+
+        let mut keep: HashSet<&str> = current_checkpoint_id.into_iter().collect();
+        if let Some(keep_last) = self.keep_last {
+            keep.extend(newest_first.iter().take(keep_last).map(|c| c.id.as_str()));
+        }
+        if let Some(keep_daily) = self.keep_daily {
+            let mut days = HashSet::new();
+            for checkpoint in &newest_first {
+                let day = checkpoint.timestamp.date_naive();
+                if days.len() < keep_daily && days.insert(day) {
+                    keep.insert(checkpoint.id.as_str());
+                }
+            }
+        }
+
+        newest_first
+            .into_iter()
+            .filter(|c| !keep.contains(c.id.as_str()))
+            .map(|c| c.id.clone())
+            .collect()
+    }
+}
+
+/// Outcome of a checkpoint cleanup
+#[derive(Debug, Clone, Default, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct CleanupReport {
+    pub removed_checkpoints: usize,
+    /// Snapshot contents no checkpoint references anymore
+    pub removed_content_files: usize,
+    pub reclaimed_bytes: u64,
 }
 
 /// Tracks the state of files for checkpointing
@@ -130,7 +231,7 @@ pub struct FileState {
 }
 
 /// Result of a checkpoint operation
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
 pub struct CheckpointResult {
     /// The created/restored checkpoint
     pub checkpoint: Checkpoint,
@@ -141,7 +242,7 @@ pub struct CheckpointResult {
 }
 
 /// Diff between two checkpoints
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, specta::Type)]
 pub struct CheckpointDiff {
     /// Source checkpoint ID
     pub from_checkpoint_id: String,
@@ -158,7 +259,7 @@ pub struct CheckpointDiff {
 }
 
 /// Diff for a single file
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, specta::Type)]
 pub struct FileDiff {
     /// File path
     pub path: PathBuf,
@@ -176,6 +277,10 @@ impl Default for CheckpointStrategy {
     }
 }
 
+fn default_token_threshold() -> u64 {
+    50_000
+}
+
 impl SessionTimeline {
     /// Create a new empty timeline
     pub fn new(session_id: String) -> Self {
@@ -185,7 +290,9 @@ impl SessionTimeline {
             current_checkpoint_id: None,
             auto_checkpoint_enabled: false,
             checkpoint_strategy: CheckpointStrategy::default(),
+            token_threshold: default_token_threshold(),
             total_checkpoints: 0,
+            forked_from: None,
         }
     }
 
@@ -196,6 +303,45 @@ impl SessionTimeline {
             .and_then(|root| Self::find_in_tree(root, checkpoint_id))
     }
 
+    /// Remove a checkpoint from the tree, attaching its children to its parent
+    ///
+    /// A root with several children can't be removed; `false` is returned then
+    /// and when the checkpoint isn't in the tree.
+    pub fn remove_checkpoint_node(&mut self, checkpoint_id: &str) -> bool {
+        let Some(root) = self.root_node.as_mut() else {
+            return false;
+        };
+        if root.checkpoint.id != checkpoint_id {
+            return Self::remove_from_tree(root, checkpoint_id);
+        }
+        if root.children.len() > 1 {
+            return false;
+        }
+        self.root_node = root.children.pop().map(|mut child| {
+            child.checkpoint.parent_checkpoint_id = None;
+            child
+        });
+        true
+    }
+
+    fn remove_from_tree(node: &mut TimelineNode, checkpoint_id: &str) -> bool {
+        if let Some(index) = node
+            .children
+            .iter()
+            .position(|child| child.checkpoint.id == checkpoint_id)
+        {
+            let removed = node.children.remove(index);
+            for mut child in removed.children {
+                child.checkpoint.parent_checkpoint_id = Some(node.checkpoint.id.clone());
+                node.children.push(child);
+            }
+            return true;
+        }
+        node.children
+            .iter_mut()
+            .any(|child| Self::remove_from_tree(child, checkpoint_id))
+    }
+
     fn find_in_tree<'a>(node: &'a TimelineNode, checkpoint_id: &str) -> Option<&'a TimelineNode> {
         if node.checkpoint.id == checkpoint_id {
             return Some(node);
@@ -211,6 +357,41 @@ impl SessionTimeline {
     }
 }
 
+/// Root of all checkpoint data, `~/.claude/timelines`, with one directory per project
+pub fn timelines_dir(claude_dir: &Path) -> PathBuf {
+    claude_dir.join("timelines")
+}
+
+/// Move timelines from the old `projects/<project>/.timelines/<session>` layout to
+/// `timelines/<project>/<session>`, returning how many sessions were moved
+///
+/// A session that already exists in the new layout is left where it is.
+pub fn migrate_legacy_timelines(claude_dir: &Path) -> std::io::Result<usize> {
+    let Ok(projects) = std::fs::read_dir(claude_dir.join("projects")) else {
+        return Ok(0);
+    };
+    let mut moved = 0;
+    for project in projects.filter_map(|entry| entry.ok()) {
+        let legacy_dir = project.path().join(".timelines");
+        let Ok(sessions) = std::fs::read_dir(&legacy_dir) else {
+            continue;
+        };
+        let project_dir = timelines_dir(claude_dir).join(project.file_name());
+        for session in sessions.filter_map(|entry| entry.ok()) {
+            let target = project_dir.join(session.file_name());
+            if !session.path().is_dir() || target.exists() {
+                continue;
+            }
+            std::fs::create_dir_all(&project_dir)?;
+            std::fs::rename(session.path(), &target)?;
+            moved += 1;
+        }
+        // Only succeeds once every session has been moved
+        let _ = std::fs::remove_dir(&legacy_dir);
+    }
+    Ok(moved)
+}
+
 /// Checkpoint storage paths
 pub struct CheckpointPaths {
     pub timeline_file: PathBuf,
@@ -220,11 +401,7 @@ pub struct CheckpointPaths {
 
 impl CheckpointPaths {
     pub fn new(claude_dir: &PathBuf, project_id: &str, session_id: &str) -> Self {
-        let base_dir = claude_dir
-            .join("projects")
-            .join(project_id)
-            .join(".timelines")
-            .join(session_id);
+        let base_dir = timelines_dir(claude_dir).join(project_id).join(session_id);
 
         Self {
             timeline_file: base_dir.join("timeline.json"),
@@ -260,3 +437,30 @@ impl CheckpointPaths {
             .join(format!("{}.json", safe_filename))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn legacy_timelines_move_to_the_timelines_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let legacy = dir.path().join("projects/-repo/.timelines");
+        fs::create_dir_all(legacy.join("old/checkpoints")).unwrap();
+        fs::write(legacy.join("old/timeline.json"), "{\"legacy\":true}").unwrap();
+        fs::create_dir_all(legacy.join("both")).unwrap();
+        let paths = CheckpointPaths::new(&dir.path().to_path_buf(), "-repo", "both");
+        fs::create_dir_all(&paths.checkpoints_dir).unwrap();
+
+        assert_eq!(migrate_legacy_timelines(dir.path()).unwrap(), 1);
+        let moved = CheckpointPaths::new(&dir.path().to_path_buf(), "-repo", "old");
+        assert_eq!(
+            fs::read_to_string(moved.timeline_file).unwrap(),
+            "{\"legacy\":true}"
+        );
+        // A session present in both layouts keeps its new data; the old copy stays behind
+        assert!(legacy.join("both").exists());
+        assert!(!legacy.join("old").exists());
+    }
+}
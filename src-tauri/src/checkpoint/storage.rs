@@ -1,14 +1,41 @@
 use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use similar::{ChangeTag, TextDiff};
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
+use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
 use uuid::Uuid;
 use zstd::stream::{decode_all, encode_all};
 
 use super::{
-    Checkpoint, CheckpointPaths, CheckpointResult, FileSnapshot, SessionTimeline, TimelineNode,
+    Checkpoint, CheckpointDiff, CheckpointPaths, CheckpointResult, CleanupReport, FileDiff,
+    FileSnapshot, RetentionPolicy, SessionTimeline, TimelineNode,
 };
 
+/// Checkpoint archive format version written by this build
+const ARCHIVE_VERSION: u32 = 1;
+
+/// `checkpoint.json` of an exported checkpoint archive
+#[derive(Serialize, Deserialize)]
+struct ArchiveManifest {
+    version: u32,
+    checkpoint: Checkpoint,
+    files: Vec<ArchiveFile>,
+}
+
+/// A file of an exported checkpoint; its content is stored under `files/`
+#[derive(Serialize, Deserialize)]
+struct ArchiveFile {
+    path: PathBuf,
+    is_deleted: bool,
+    permissions: Option<u32>,
+}
+
+/// Reference counts of the content pool, stored next to the contents
+const REFCOUNTS_FILE: &str = "refcounts.json";
+
 /// Manages checkpoint storage operations
 pub struct CheckpointStorage {
     pub claude_dir: PathBuf,
@@ -74,9 +101,15 @@ impl CheckpointStorage {
         let mut warnings = Vec::new();
         let mut files_processed = 0;
 
+        let mut refcounts = Self::load_refcounts(&paths);
         for snapshot in &file_snapshots {
             match self.save_file_snapshot(&paths, snapshot) {
-                Ok(_) => files_processed += 1,
+                Ok(_) => {
+                    files_processed += 1;
+                    if !snapshot.is_deleted {
+                        *refcounts.entry(snapshot.hash.clone()).or_insert(0) += 1;
+                    }
+                }
                 Err(e) => warnings.push(format!(
                     "Failed to save {}: {}",
                     snapshot.file_path.display(),
@@ -84,6 +117,7 @@ impl CheckpointStorage {
                 )),
             }
         }
+        Self::save_refcounts(&paths, &refcounts)?;
 
         // Update timeline
         self.update_timeline_with_checkpoint(&paths.timeline_file, checkpoint, &file_snapshots)?;
@@ -106,7 +140,7 @@ impl CheckpointStorage {
         let content_file = content_pool_dir.join(&snapshot.hash);
 
         // Only write the content if it doesn't already exist
-        if !content_file.exists() {
+        if !snapshot.is_deleted && !content_file.exists() {
             // Compress and save file content
             let compressed_content =
                 encode_all(snapshot.content.as_bytes(), self.compression_level)
@@ -237,6 +271,223 @@ impl CheckpointStorage {
         Ok(snapshots)
     }
 
+    /// Compare the files of two checkpoints of a session
+    ///
+    /// Modified files carry a unified diff from `from_checkpoint_id` to `to_checkpoint_id`.
+    pub fn diff_checkpoints(
+        &self,
+        project_id: &str,
+        session_id: &str,
+        from_checkpoint_id: &str,
+        to_checkpoint_id: &str,
+    ) -> Result<CheckpointDiff> {
+        let (from_checkpoint, from_files, _) = self
+            .load_checkpoint(project_id, session_id, from_checkpoint_id)
+            .context("Failed to load source checkpoint")?;
+        let (to_checkpoint, to_files, _) = self
+            .load_checkpoint(project_id, session_id, to_checkpoint_id)
+            .context("Failed to load target checkpoint")?;
+
+        // Files marked deleted don't exist at that checkpoint
+        let existing = |files: Vec<FileSnapshot>| -> BTreeMap<PathBuf, FileSnapshot> {
+            files
+                .into_iter()
+                .filter(|file| !file.is_deleted)
+                .map(|file| (file.file_path.clone(), file))
+                .collect()
+        };
+        let from_map = existing(from_files);
+        let to_map = existing(to_files);
+
+        let mut modified_files = Vec::new();
+        let mut deleted_files = Vec::new();
+        for (path, from_file) in &from_map {
+            match to_map.get(path) {
+                Some(to_file) if to_file.hash != from_file.hash => {
+                    modified_files.push(Self::file_diff(path, from_file, to_file));
+                }
+                Some(_) => {}
+                None => deleted_files.push(path.clone()),
+            }
+        }
+        let added_files = to_map
+            .keys()
+            .filter(|path| !from_map.contains_key(*path))
+            .cloned()
+            .collect();
+
+        Ok(CheckpointDiff {
+            from_checkpoint_id: from_checkpoint_id.to_string(),
+            to_checkpoint_id: to_checkpoint_id.to_string(),
+            modified_files,
+            added_files,
+            deleted_files,
+            token_delta: to_checkpoint.metadata.total_tokens as i64
+                - from_checkpoint.metadata.total_tokens as i64,
+        })
+    }
+
+    /// Unified diff and line counts of one changed file
+    fn file_diff(path: &Path, from_file: &FileSnapshot, to_file: &FileSnapshot) -> FileDiff {
+        let diff = TextDiff::from_lines(&from_file.content, &to_file.content);
+        let mut additions = 0;
+        let mut deletions = 0;
+        for change in diff.iter_all_changes() {
+            match change.tag() {
+                ChangeTag::Insert => additions += 1,
+                ChangeTag::Delete => deletions += 1,
+                ChangeTag::Equal => {}
+            }
+        }
+        let name = path.to_string_lossy();
+        FileDiff {
+            path: path.to_path_buf(),
+            additions,
+            deletions,
+            diff_content: Some(
+                diff.unified_diff()
+                    .context_radius(3)
+                    .header(&format!("a/{}", name), &format!("b/{}", name))
+                    .to_string(),
+            ),
+        }
+    }
+
+    /// Write a checkpoint's metadata, transcript slice and files to a zip archive
+    pub fn export_checkpoint_archive(
+        &self,
+        project_id: &str,
+        session_id: &str,
+        checkpoint_id: &str,
+        archive_path: &Path,
+    ) -> Result<()> {
+        let (checkpoint, file_snapshots, messages) =
+            self.load_checkpoint(project_id, session_id, checkpoint_id)?;
+
+        let file = fs::File::create(archive_path).context("Failed to create archive")?;
+        let mut zip = zip::ZipWriter::new(file);
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Deflated);
+
+        let manifest = ArchiveManifest {
+            version: ARCHIVE_VERSION,
+            checkpoint,
+            files: file_snapshots
+                .iter()
+                .map(|snapshot| ArchiveFile {
+                    path: snapshot.file_path.clone(),
+                    is_deleted: snapshot.is_deleted,
+                    permissions: snapshot.permissions,
+                })
+                .collect(),
+        };
+        zip.start_file("checkpoint.json", options)?;
+        zip.write_all(serde_json::to_string_pretty(&manifest)?.as_bytes())?;
+        zip.start_file("messages.jsonl", options)?;
+        zip.write_all(messages.as_bytes())?;
+        for snapshot in file_snapshots.iter().filter(|s| !s.is_deleted) {
+            let name = snapshot.file_path.to_string_lossy().replace('\\', "/");
+            zip.start_file(format!("files/{}", name), options)?;
+            zip.write_all(snapshot.content.as_bytes())?;
+        }
+        zip.finish().context("Failed to finish archive")?;
+        Ok(())
+    }
+
+    /// Add the checkpoint of an exported archive to a session
+    ///
+    /// The checkpoint gets a new ID and is attached below the session's
+    /// current checkpoint; restore it to apply its files.
+    pub fn import_checkpoint_archive(
+        &self,
+        project_id: &str,
+        session_id: &str,
+        archive_path: &Path,
+    ) -> Result<CheckpointResult> {
+        let file = fs::File::open(archive_path).context("Failed to open archive")?;
+        let mut zip = zip::ZipArchive::new(file).context("Not a checkpoint archive")?;
+        let read_entry = |zip: &mut zip::ZipArchive<fs::File>, name: &str| -> Result<String> {
+            let mut content = String::new();
+            zip.by_name(name)
+                .with_context(|| format!("Archive is missing {}", name))?
+                .read_to_string(&mut content)
+                .with_context(|| format!("Failed to read {} from archive", name))?;
+            Ok(content)
+        };
+
+        let manifest: ArchiveManifest =
+            serde_json::from_str(&read_entry(&mut zip, "checkpoint.json")?)
+                .context("Invalid checkpoint manifest")?;
+        if manifest.version > ARCHIVE_VERSION {
+            anyhow::bail!(
+                "Checkpoint archive version {} is newer than this version of Claudia supports",
+                manifest.version
+            );
+        }
+        let messages = read_entry(&mut zip, "messages.jsonl")?;
+
+        self.init_storage(project_id, session_id)?;
+        let paths = CheckpointPaths::new(&self.claude_dir, project_id, session_id);
+        let timeline = self.load_timeline(&paths.timeline_file)?;
+
+        let checkpoint_id = Self::generate_checkpoint_id();
+        let mut file_snapshots = Vec::new();
+        for file in manifest.files {
+            // Restoring joins the path to the project, so it must stay inside it
+            if !file
+                .path
+                .components()
+                .all(|c| matches!(c, std::path::Component::Normal(_)))
+            {
+                anyhow::bail!("Archive contains an unsafe path: {}", file.path.display());
+            }
+            let content = if file.is_deleted {
+                String::new()
+            } else {
+                let name = file.path.to_string_lossy().replace('\\', "/");
+                read_entry(&mut zip, &format!("files/{}", name))?
+            };
+            file_snapshots.push(FileSnapshot {
+                checkpoint_id: checkpoint_id.clone(),
+                hash: if file.is_deleted {
+                    String::new()
+                } else {
+                    Self::calculate_file_hash(&content)
+                },
+                size: content.len() as u64,
+                file_path: file.path,
+                content,
+                is_deleted: file.is_deleted,
+                permissions: file.permissions,
+            });
+        }
+
+        let checkpoint = Checkpoint {
+            id: checkpoint_id,
+            session_id: session_id.to_string(),
+            project_id: project_id.to_string(),
+            timestamp: chrono::Utc::now(),
+            parent_checkpoint_id: timeline.current_checkpoint_id.clone().or_else(|| {
+                timeline
+                    .root_node
+                    .as_ref()
+                    .map(|root| root.checkpoint.id.clone())
+            }),
+            description: Some(match manifest.checkpoint.description {
+                Some(description) => format!("Imported: {}", description),
+                None => "Imported checkpoint".to_string(),
+            }),
+            ..manifest.checkpoint
+        };
+        self.save_checkpoint(
+            project_id,
+            session_id,
+            &checkpoint,
+            file_snapshots,
+            &messages,
+        )
+    }
+
     /// Save timeline to disk
     pub fn save_timeline(&self, timeline_path: &Path, timeline: &SessionTimeline) -> Result<()> {
         let timeline_json =
@@ -377,6 +628,64 @@ impl CheckpointStorage {
         Ok(removed_count)
     }
 
+    /// Remove the checkpoints a retention policy doesn't keep, and their unreferenced content
+    pub fn apply_retention(
+        &self,
+        project_id: &str,
+        session_id: &str,
+        policy: &RetentionPolicy,
+    ) -> Result<CleanupReport> {
+        let paths = CheckpointPaths::new(&self.claude_dir, project_id, session_id);
+        let mut timeline = self.load_timeline(&paths.timeline_file)?;
+
+        let mut checkpoints = Vec::new();
+        if let Some(root) = &timeline.root_node {
+            Self::collect_checkpoints(root, &mut checkpoints);
+        }
+        let to_remove =
+            policy.checkpoints_to_remove(&checkpoints, timeline.current_checkpoint_id.as_deref());
+        if to_remove.is_empty() {
+            return Ok(CleanupReport::default());
+        }
+
+        let size_before = Self::session_storage_size(&paths);
+        let mut report = CleanupReport::default();
+        for checkpoint_id in &to_remove {
+            if !timeline.remove_checkpoint_node(checkpoint_id) {
+                continue;
+            }
+            match self.remove_checkpoint(&paths, checkpoint_id) {
+                Ok(removed_content) => {
+                    report.removed_checkpoints += 1;
+                    report.removed_content_files += removed_content;
+                }
+                Err(e) => log::warn!("Failed to remove checkpoint {}: {}", checkpoint_id, e),
+            }
+        }
+        timeline.total_checkpoints = timeline
+            .total_checkpoints
+            .saturating_sub(report.removed_checkpoints);
+        self.save_timeline(&paths.timeline_file, &timeline)?;
+
+        report.removed_content_files += self.garbage_collect_content(project_id, session_id)?;
+        report.reclaimed_bytes = size_before.saturating_sub(Self::session_storage_size(&paths));
+        Ok(report)
+    }
+
+    /// Bytes used by a session's checkpoints on disk
+    fn session_storage_size(paths: &CheckpointPaths) -> u64 {
+        let Some(base_dir) = paths.timeline_file.parent() else {
+            return 0;
+        };
+        walkdir::WalkDir::new(base_dir)
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| entry.metadata().ok())
+            .filter(|metadata| metadata.is_file())
+            .map(|metadata| metadata.len())
+            .sum()
+    }
+
     /// Collect all checkpoints from the tree in order
     fn collect_checkpoints(node: &TimelineNode, checkpoints: &mut Vec<Checkpoint>) {
         checkpoints.push(node.checkpoint.clone());
@@ -386,75 +695,344 @@ impl CheckpointStorage {
     }
 
     /// Remove a checkpoint and its associated files
-    fn remove_checkpoint(&self, paths: &CheckpointPaths, checkpoint_id: &str) -> Result<()> {
+    ///
+    /// Content no other checkpoint references is dropped from the pool; returns
+    /// how many content files were removed.
+    fn remove_checkpoint(&self, paths: &CheckpointPaths, checkpoint_id: &str) -> Result<usize> {
         // Remove checkpoint metadata directory
         let checkpoint_dir = paths.checkpoint_dir(checkpoint_id);
         if checkpoint_dir.exists() {
             fs::remove_dir_all(&checkpoint_dir).context("Failed to remove checkpoint directory")?;
         }
 
-        // Remove file references for this checkpoint
+        // Load the counts while the checkpoint's references still exist, so counts that
+        // have to be rebuilt include them, then release the references
+        let mut refcounts = Self::load_refcounts(paths);
         let refs_dir = paths.files_dir.join("refs").join(checkpoint_id);
+        let hashes = Self::referenced_hashes(&refs_dir);
         if refs_dir.exists() {
             fs::remove_dir_all(&refs_dir).context("Failed to remove file references")?;
         }
 
-        // Note: We don't remove content from the pool here as it might be
-        // referenced by other checkpoints. Use garbage_collect_content() for that.
+        let content_pool_dir = paths.files_dir.join("content_pool");
+        let mut removed_content = 0;
+        for hash in hashes {
+            let Some(count) = refcounts.get_mut(&hash) else {
+                // Not counted; garbage_collect_content recounts and removes it if unused
+                continue;
+            };
+            *count = count.saturating_sub(1);
+            if *count == 0 {
+                refcounts.remove(&hash);
+                if fs::remove_file(content_pool_dir.join(&hash)).is_ok() {
+                    removed_content += 1;
+                }
+            }
+        }
+        Self::save_refcounts(paths, &refcounts)?;
+
+        Ok(removed_content)
+    }
 
-        Ok(())
+    /// Hashes referenced by the file references in a checkpoint's refs directory
+    fn referenced_hashes(refs_dir: &Path) -> Vec<String> {
+        let Ok(entries) = fs::read_dir(refs_dir) else {
+            return Vec::new();
+        };
+        entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("json"))
+            .filter_map(|path| fs::read_to_string(path).ok())
+            .filter_map(|json| serde_json::from_str::<serde_json::Value>(&json).ok())
+            .filter(|reference| !reference["is_deleted"].as_bool().unwrap_or(false))
+            .filter_map(|reference| reference["hash"].as_str().map(str::to_string))
+            .collect()
+    }
+
+    /// Number of file references to each content in the pool
+    ///
+    /// Pools written before counts were kept, or whose counts file was lost, are
+    /// recounted from every checkpoint's references.
+    fn load_refcounts(paths: &CheckpointPaths) -> HashMap<String, u64> {
+        fs::read_to_string(paths.files_dir.join("content_pool").join(REFCOUNTS_FILE))
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_else(|| Self::count_references(&paths.files_dir.join("refs")))
+    }
+
+    /// Count the references of every checkpoint below `refs_dir`
+    fn count_references(refs_dir: &Path) -> HashMap<String, u64> {
+        let mut refcounts: HashMap<String, u64> = HashMap::new();
+        let Ok(entries) = fs::read_dir(refs_dir) else {
+            return refcounts;
+        };
+        for checkpoint_dir in entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()) {
+            if checkpoint_dir.is_dir() {
+                for hash in Self::referenced_hashes(&checkpoint_dir) {
+                    *refcounts.entry(hash).or_insert(0) += 1;
+                }
+            }
+        }
+        refcounts
+    }
+
+    fn save_refcounts(paths: &CheckpointPaths, refcounts: &HashMap<String, u64>) -> Result<()> {
+        let content_pool_dir = paths.files_dir.join("content_pool");
+        fs::create_dir_all(&content_pool_dir).context("Failed to create content pool directory")?;
+        fs::write(
+            content_pool_dir.join(REFCOUNTS_FILE),
+            serde_json::to_string(refcounts)?,
+        )
+        .context("Failed to write content reference counts")
     }
 
     /// Garbage collect unreferenced content from the content pool
+    ///
+    /// Recounts the references of every checkpoint, so it also repairs the
+    /// reference counts of pools written before they were kept.
     pub fn garbage_collect_content(&self, project_id: &str, session_id: &str) -> Result<usize> {
         let paths = CheckpointPaths::new(&self.claude_dir, project_id, session_id);
         let content_pool_dir = paths.files_dir.join("content_pool");
-        let refs_dir = paths.files_dir.join("refs");
 
         if !content_pool_dir.exists() {
             return Ok(0);
         }
 
-        // Collect all referenced hashes
-        let mut referenced_hashes = std::collections::HashSet::new();
-
-        if refs_dir.exists() {
-            for checkpoint_entry in fs::read_dir(&refs_dir)? {
-                let checkpoint_dir = checkpoint_entry?.path();
-                if checkpoint_dir.is_dir() {
-                    for ref_entry in fs::read_dir(&checkpoint_dir)? {
-                        let ref_path = ref_entry?.path();
-                        if ref_path.extension().and_then(|e| e.to_str()) == Some("json") {
-                            if let Ok(ref_json) = fs::read_to_string(&ref_path) {
-                                if let Ok(ref_metadata) =
-                                    serde_json::from_str::<serde_json::Value>(&ref_json)
-                                {
-                                    if let Some(hash) = ref_metadata["hash"].as_str() {
-                                        referenced_hashes.insert(hash.to_string());
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
+        let refcounts = Self::count_references(&paths.files_dir.join("refs"));
 
         // Remove unreferenced content
         let mut removed_count = 0;
         for entry in fs::read_dir(&content_pool_dir)? {
             let content_file = entry?.path();
-            if content_file.is_file() {
-                if let Some(hash) = content_file.file_name().and_then(|n| n.to_str()) {
-                    if !referenced_hashes.contains(hash) {
-                        if fs::remove_file(&content_file).is_ok() {
-                            removed_count += 1;
-                        }
-                    }
-                }
+            if !content_file.is_file() {
+                continue;
+            }
+            let Some(hash) = content_file.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            if hash != REFCOUNTS_FILE
+                && !refcounts.contains_key(hash)
+                && fs::remove_file(&content_file).is_ok()
+            {
+                removed_count += 1;
             }
         }
+        Self::save_refcounts(&paths, &refcounts)?;
 
         Ok(removed_count)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checkpoint::CheckpointMetadata;
+
+    fn snapshot(checkpoint_id: &str, path: &str, content: &str) -> FileSnapshot {
+        FileSnapshot {
+            checkpoint_id: checkpoint_id.to_string(),
+            file_path: PathBuf::from(path),
+            content: content.to_string(),
+            hash: CheckpointStorage::calculate_file_hash(content),
+            is_deleted: false,
+            permissions: None,
+            size: content.len() as u64,
+        }
+    }
+
+    fn checkpoint(id: &str, parent: Option<&str>, total_tokens: u64) -> Checkpoint {
+        Checkpoint {
+            id: id.to_string(),
+            session_id: "session".to_string(),
+            project_id: "project".to_string(),
+            message_index: 0,
+            timestamp: chrono::Utc::now(),
+            description: None,
+            parent_checkpoint_id: parent.map(str::to_string),
+            metadata: CheckpointMetadata {
+                total_tokens,
+                model_used: "sonnet".to_string(),
+                user_prompt: String::new(),
+                file_changes: 0,
+                snapshot_size: 0,
+            },
+        }
+    }
+
+    #[test]
+    fn diffs_files_between_checkpoints() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = CheckpointStorage::new(dir.path().to_path_buf());
+        storage.init_storage("project", "session").unwrap();
+
+        storage
+            .save_checkpoint(
+                "project",
+                "session",
+                &checkpoint("a", None, 100),
+                vec![
+                    snapshot("a", "main.rs", "fn main() {}\n"),
+                    snapshot("a", "old.rs", "old\n"),
+                ],
+                "",
+            )
+            .unwrap();
+        storage
+            .save_checkpoint(
+                "project",
+                "session",
+                &checkpoint("b", Some("a"), 250),
+                vec![
+                    snapshot("b", "main.rs", "fn main() {\n    run();\n}\n"),
+                    snapshot("b", "new.rs", "new\n"),
+                ],
+                "",
+            )
+            .unwrap();
+
+        let diff = storage
+            .diff_checkpoints("project", "session", "a", "b")
+            .unwrap();
+        assert_eq!(diff.added_files, vec![PathBuf::from("new.rs")]);
+        assert_eq!(diff.deleted_files, vec![PathBuf::from("old.rs")]);
+        assert_eq!(diff.token_delta, 150);
+        let modified = &diff.modified_files[0];
+        assert_eq!((modified.additions, modified.deletions), (3, 1));
+        assert!(modified
+            .diff_content
+            .as_deref()
+            .unwrap()
+            .contains("+    run();"));
+    }
+
+    #[test]
+    fn retention_removes_old_checkpoints_and_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = CheckpointStorage::new(dir.path().to_path_buf());
+        storage.init_storage("project", "session").unwrap();
+        let checkpoints = [
+            ("a", None, "one"),
+            ("b", Some("a"), "two"),
+            ("c", Some("b"), "two"),
+        ];
+        for (age, (id, parent, content)) in checkpoints.into_iter().rev().enumerate().rev() {
+            let mut checkpoint = checkpoint(id, parent, 0);
+            checkpoint.timestamp -= chrono::Duration::hours(age as i64);
+            storage
+                .save_checkpoint(
+                    "project",
+                    "session",
+                    &checkpoint,
+                    vec![snapshot(id, "main.rs", content)],
+                    "",
+                )
+                .unwrap();
+        }
+
+        let policy = RetentionPolicy {
+            keep_last: Some(1),
+            ..Default::default()
+        };
+        let report = storage
+            .apply_retention("project", "session", &policy)
+            .unwrap();
+        assert_eq!(report.removed_checkpoints, 2);
+        // "two" is still referenced by the kept checkpoint
+        assert_eq!(report.removed_content_files, 1);
+        assert!(report.reclaimed_bytes > 0);
+
+        let paths = CheckpointPaths::new(&dir.path().to_path_buf(), "project", "session");
+        let timeline = storage.load_timeline(&paths.timeline_file).unwrap();
+        let root = timeline.root_node.unwrap();
+        assert_eq!(root.checkpoint.id, "c");
+        assert!(root.children.is_empty());
+    }
+
+    #[test]
+    fn identical_contents_are_stored_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = CheckpointStorage::new(dir.path().to_path_buf());
+        storage.init_storage("project", "session").unwrap();
+        for (id, parent) in [("a", None), ("b", Some("a"))] {
+            storage
+                .save_checkpoint(
+                    "project",
+                    "session",
+                    &checkpoint(id, parent, 0),
+                    vec![snapshot(id, "lib.rs", "shared")],
+                    "",
+                )
+                .unwrap();
+        }
+
+        let paths = CheckpointPaths::new(&dir.path().to_path_buf(), "project", "session");
+        let hash = CheckpointStorage::calculate_file_hash("shared");
+        assert_eq!(CheckpointStorage::load_refcounts(&paths)[&hash], 2);
+
+        // The content survives until its last reference is gone
+        assert_eq!(storage.remove_checkpoint(&paths, "a").unwrap(), 0);
+        assert_eq!(storage.remove_checkpoint(&paths, "b").unwrap(), 1);
+        assert!(!paths.files_dir.join("content_pool").join(&hash).exists());
+    }
+
+    #[test]
+    fn lost_refcounts_are_rebuilt_from_references() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = CheckpointStorage::new(dir.path().to_path_buf());
+        storage.init_storage("project", "session").unwrap();
+        for (id, parent) in [("a", None), ("b", Some("a"))] {
+            storage
+                .save_checkpoint(
+                    "project",
+                    "session",
+                    &checkpoint(id, parent, 0),
+                    vec![snapshot(id, "lib.rs", "shared")],
+                    "",
+                )
+                .unwrap();
+        }
+        let paths = CheckpointPaths::new(&dir.path().to_path_buf(), "project", "session");
+        let content_pool_dir = paths.files_dir.join("content_pool");
+        fs::remove_file(content_pool_dir.join(REFCOUNTS_FILE)).unwrap();
+
+        // Removing one checkpoint must not drop content the other still uses
+        assert_eq!(storage.remove_checkpoint(&paths, "a").unwrap(), 0);
+        let hash = CheckpointStorage::calculate_file_hash("shared");
+        assert!(content_pool_dir.join(&hash).exists());
+        assert_eq!(CheckpointStorage::load_refcounts(&paths)[&hash], 1);
+    }
+
+    #[test]
+    fn exported_checkpoint_imports_into_another_session() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = CheckpointStorage::new(dir.path().to_path_buf());
+        storage.init_storage("project", "session").unwrap();
+        storage
+            .save_checkpoint(
+                "project",
+                "session",
+                &checkpoint("a", None, 10),
+                vec![snapshot("a", "src/main.rs", "fn main() {}")],
+                "{\"type\":\"user\"}",
+            )
+            .unwrap();
+
+        let archive = dir.path().join("checkpoint.zip");
+        storage
+            .export_checkpoint_archive("project", "session", "a", &archive)
+            .unwrap();
+        let imported = storage
+            .import_checkpoint_archive("other", "copy", &archive)
+            .unwrap();
+
+        let (checkpoint, files, messages) = storage
+            .load_checkpoint("other", "copy", &imported.checkpoint.id)
+            .unwrap();
+        assert_eq!(checkpoint.session_id, "copy");
+        assert_eq!(checkpoint.metadata.total_tokens, 10);
+        assert_eq!(files[0].file_path, PathBuf::from("src/main.rs"));
+        assert_eq!(files[0].content, "fn main() {}");
+        assert_eq!(messages, "{\"type\":\"user\"}");
+    }
+}
@@ -4,12 +4,14 @@ use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 /// Shared module for detecting Claude Code binary installations
 /// Supports NVM installations, aliased paths, version-based selection, and bundled sidecars
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use tauri::Manager;
 
+use crate::commands::agents::get_setting;
+
 /// Type of Claude installation
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, specta::Type)]
 pub enum InstallationType {
     /// Bundled sidecar binary (preferred)
     Bundled,
@@ -20,7 +22,7 @@ pub enum InstallationType {
 }
 
 /// Represents a Claude installation with metadata
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
 pub struct ClaudeInstallation {
     /// Full path to the Claude binary (or "claude-code" for sidecar)
     pub path: String,
@@ -32,6 +34,105 @@ pub struct ClaudeInstallation {
     pub installation_type: InstallationType,
 }
 
+/// Whether the pinned Claude installation can still be launched
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct ClaudeBinaryStatus {
+    /// The pinned path, `None` when no installation is pinned
+    pub path: Option<String>,
+    pub valid: bool,
+    pub version: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Check that a binary exists, is executable and answers `--version`; returns its version
+pub fn validate_claude_binary(path: &str) -> Result<Option<String>, String> {
+    // The bundled sidecar is resolved by Tauri at spawn time
+    if path == "claude-code" {
+        return Ok(None);
+    }
+
+    let path_buf = PathBuf::from(path);
+    if !path_buf.is_file() {
+        return Err(format!("File does not exist: {}", path));
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let metadata = std::fs::metadata(&path_buf)
+            .map_err(|e| format!("Failed to read file metadata: {}", e))?;
+        if metadata.permissions().mode() & 0o111 == 0 {
+            return Err(format!("File is not executable: {}", path));
+        }
+    }
+
+    let output = create_command_with_env(path)
+        .arg("--version")
+        .output()
+        .map_err(|e| format!("Failed to run {}: {}", path, e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{} --version failed: {}",
+            path,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(extract_version_from_output(&output.stdout))
+}
+
+/// Validate the pinned installation; unpinned setups are always valid
+pub fn pinned_binary_status(stored_path: Option<String>) -> ClaudeBinaryStatus {
+    let Some(path) = stored_path else {
+        return ClaudeBinaryStatus {
+            path: None,
+            valid: true,
+            version: None,
+            error: None,
+        };
+    };
+    match validate_claude_binary(&path) {
+        Ok(version) => ClaudeBinaryStatus {
+            path: Some(path),
+            valid: true,
+            version,
+            error: None,
+        },
+        Err(error) => ClaudeBinaryStatus {
+            path: Some(path),
+            valid: false,
+            version: None,
+            error: Some(error),
+        },
+    }
+}
+
+/// Check the pinned installation at startup and tell the UI when it no longer works
+pub fn check_pinned_claude_binary(app: tauri::AppHandle) {
+    use tauri::Emitter;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let stored_path = {
+            let db = app.state::<crate::commands::agents::AgentDb>();
+            let Ok(conn) = db.0.lock() else {
+                return;
+            };
+            get_setting::<String>(&conn, "claude_binary_path")
+        };
+        let status = pinned_binary_status(stored_path);
+        if status.valid {
+            if let Some(path) = &status.path {
+                info!("Pinned Claude installation {} is usable", path);
+            }
+        } else {
+            warn!(
+                "Pinned Claude installation is unusable: {}",
+                status.error.as_deref().unwrap_or_default()
+            );
+            let _ = app.emit("claude-binary-invalid", &status);
+        }
+    });
+}
+
 /// Main function to find the Claude binary
 /// Checks database first for stored path and preference, then prioritizes accordingly
 pub fn find_claude_binary(app_handle: &tauri::AppHandle) -> Result<String, String> {
@@ -43,11 +144,9 @@ pub fn find_claude_binary(app_handle: &tauri::AppHandle) -> Result<String, Strin
         if db_path.exists() {
             if let Ok(conn) = rusqlite::Connection::open(&db_path) {
                 // Check for stored path first
-                if let Ok(stored_path) = conn.query_row(
-                    "SELECT value FROM app_settings WHERE key = 'claude_binary_path'",
-                    [],
-                    |row| row.get::<_, String>(0),
-                ) {
+                if let Some(stored_path) =
+                    get_setting::<String>(&conn, "claude_binary_path")
+                {
                     info!("Found stored claude path in database: {}", stored_path);
                     
                     // If it's a sidecar reference, return it directly
@@ -65,11 +164,8 @@ pub fn find_claude_binary(app_handle: &tauri::AppHandle) -> Result<String, Strin
                 }
                 
                 // Check user preference
-                let preference = conn.query_row(
-                    "SELECT value FROM app_settings WHERE key = 'claude_installation_preference'",
-                    [],
-                    |row| row.get::<_, String>(0),
-                ).unwrap_or_else(|_| "bundled".to_string());
+                let preference = get_setting::<String>(&conn, "claude_installation_preference")
+                    .unwrap_or_else(|| "bundled".to_string());
                 
                 info!("User preference for Claude installation: {}", preference);
                 
@@ -188,11 +284,14 @@ fn source_preference(installation: &ClaudeInstallation) -> u8 {
         "homebrew" => 2,
         "system" => 3,
         source if source.starts_with("nvm") => 4,
+        source if source.starts_with("fnm") || source.starts_with("asdf") => 4,
+        "volta" => 4,
         "local-bin" => 5,
         "claude-local" => 6,
         "npm-global" => 7,
         "yarn" | "yarn-global" => 8,
         "bun" => 9,
+        "pnpm" => 9,
         "node-modules" => 10,
         "home-bin" => 11,
         "PATH" => 12,
@@ -212,9 +311,17 @@ fn discover_system_installations() -> Vec<ClaudeInstallation> {
     // 2. Check NVM paths
     installations.extend(find_nvm_installations());
 
-    // 3. Check standard paths
+    // 3. Check fnm, asdf and volta node versions
+    if let Some(home) = dirs::home_dir() {
+        installations.extend(find_version_manager_installations(&home));
+    }
+
+    // 4. Check standard paths
     installations.extend(find_standard_installations());
 
+    // 5. Check every PATH entry, not just the first match
+    installations.extend(find_path_installations());
+
     // Remove duplicates by path
     let mut unique_paths = std::collections::HashSet::new();
     installations.retain(|install| unique_paths.insert(install.path.clone()));
@@ -270,11 +377,12 @@ fn try_which_command() -> Option<ClaudeInstallation> {
 fn find_nvm_installations() -> Vec<ClaudeInstallation> {
     let mut installations = Vec::new();
 
-    if let Ok(home) = std::env::var("HOME") {
-        let nvm_dir = PathBuf::from(&home)
-            .join(".nvm")
-            .join("versions")
-            .join("node");
+    let nvm_root = std::env::var("NVM_DIR")
+        .map(PathBuf::from)
+        .ok()
+        .or_else(|| std::env::var("HOME").ok().map(|home| PathBuf::from(home).join(".nvm")));
+    if let Some(nvm_root) = nvm_root {
+        let nvm_dir = nvm_root.join("versions").join("node");
 
         debug!("Checking NVM directory: {:?}", nvm_dir);
 
@@ -307,6 +415,88 @@ fn find_nvm_installations() -> Vec<ClaudeInstallation> {
     installations
 }
 
+/// Find Claude installed into node versions managed by fnm, asdf or volta
+fn find_version_manager_installations(home: &Path) -> Vec<ClaudeInstallation> {
+    let mut installations = Vec::new();
+
+    // (directory holding one subdirectory per node version, bin path inside it, source)
+    let mut version_dirs: Vec<(PathBuf, &str, &str)> = vec![
+        (
+            home.join(".local/share/fnm/node-versions"),
+            "installation/bin/claude",
+            "fnm",
+        ),
+        (home.join(".fnm/node-versions"), "installation/bin/claude", "fnm"),
+        (
+            home.join("Library/Application Support/fnm/node-versions"),
+            "installation/bin/claude",
+            "fnm",
+        ),
+        (home.join(".asdf/installs/nodejs"), "bin/claude", "asdf"),
+    ];
+    if let Ok(fnm_dir) = std::env::var("FNM_DIR") {
+        version_dirs.push((
+            PathBuf::from(fnm_dir).join("node-versions"),
+            "installation/bin/claude",
+            "fnm",
+        ));
+    }
+
+    for (dir, bin, source) in version_dirs {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let claude_path = entry.path().join(bin);
+            if !claude_path.is_file() {
+                continue;
+            }
+            let path_str = claude_path.to_string_lossy().to_string();
+            let node_version = entry.file_name().to_string_lossy().to_string();
+            debug!("Found Claude in {} node {}: {}", source, node_version, path_str);
+            installations.push(ClaudeInstallation {
+                version: get_claude_version(&path_str).ok().flatten(),
+                path: path_str,
+                source: format!("{} ({})", source, node_version),
+                installation_type: InstallationType::System,
+            });
+        }
+    }
+
+    let volta_path = home.join(".volta/bin/claude");
+    if volta_path.is_file() {
+        let path_str = volta_path.to_string_lossy().to_string();
+        installations.push(ClaudeInstallation {
+            version: get_claude_version(&path_str).ok().flatten(),
+            path: path_str,
+            source: "volta".to_string(),
+            installation_type: InstallationType::System,
+        });
+    }
+
+    installations
+}
+
+/// Find a `claude` binary in each directory of PATH
+fn find_path_installations() -> Vec<ClaudeInstallation> {
+    let Some(path_var) = std::env::var_os("PATH") else {
+        return Vec::new();
+    };
+    std::env::split_paths(&path_var)
+        .map(|dir| dir.join("claude"))
+        .filter(|candidate| candidate.is_file())
+        .map(|candidate| {
+            let path = candidate.to_string_lossy().to_string();
+            ClaudeInstallation {
+                version: get_claude_version(&path).ok().flatten(),
+                path,
+                source: "PATH".to_string(),
+                installation_type: InstallationType::System,
+            }
+        })
+        .collect()
+}
+
 /// Check standard installation paths
 fn find_standard_installations() -> Vec<ClaudeInstallation> {
     let mut installations = Vec::new();
@@ -318,6 +508,10 @@ fn find_standard_installations() -> Vec<ClaudeInstallation> {
             "/opt/homebrew/bin/claude".to_string(),
             "homebrew".to_string(),
         ),
+        (
+            "/home/linuxbrew/.linuxbrew/bin/claude".to_string(),
+            "homebrew".to_string(),
+        ),
         ("/usr/bin/claude".to_string(), "system".to_string()),
         ("/bin/claude".to_string(), "system".to_string()),
     ];
@@ -339,6 +533,11 @@ fn find_standard_installations() -> Vec<ClaudeInstallation> {
             ),
             (format!("{}/.yarn/bin/claude", home), "yarn".to_string()),
             (format!("{}/.bun/bin/claude", home), "bun".to_string()),
+            (
+                format!("{}/.local/share/pnpm/claude", home),
+                "pnpm".to_string(),
+            ),
+            (format!("{}/Library/pnpm/claude", home), "pnpm".to_string()),
             (format!("{}/bin/claude", home), "home-bin".to_string()),
             // Check common node_modules locations
             (
@@ -406,7 +605,7 @@ fn get_claude_version(path: &str) -> Result<Option<String>, String> {
 }
 
 /// Extract version string from command output
-fn extract_version_from_output(stdout: &[u8]) -> Option<String> {
+pub(crate) fn extract_version_from_output(stdout: &[u8]) -> Option<String> {
     let output_str = String::from_utf8_lossy(stdout);
     
     // Debug log the raw output
@@ -531,15 +730,21 @@ pub fn create_command_with_env(program: &str) -> Command {
         }
     }
 
-    // Add NVM support if the program is in an NVM directory
-    if program.contains("/.nvm/versions/node/") {
+    // Route traffic through the configured proxy
+    cmd.envs(crate::commands::proxy::proxy_env());
+
+    // Add node version manager support (nvm, fnm, asdf): Claude needs the node next to it
+    let is_node_version_bin = std::path::Path::new(program)
+        .parent()
+        .is_some_and(|dir| dir.join("node").is_file());
+    if program.contains("/.nvm/versions/node/") || is_node_version_bin {
         if let Some(node_bin_dir) = std::path::Path::new(program).parent() {
             // Ensure the Node.js bin directory is in PATH
             let current_path = std::env::var("PATH").unwrap_or_default();
             let node_bin_str = node_bin_dir.to_string_lossy();
             if !current_path.contains(&node_bin_str.as_ref()) {
                 let new_path = format!("{}:{}", node_bin_str, current_path);
-                debug!("Adding NVM bin directory to PATH: {}", node_bin_str);
+                debug!("Adding node bin directory to PATH: {}", node_bin_str);
                 cmd.env("PATH", new_path);
             }
         }
@@ -547,3 +752,29 @@ pub fn create_command_with_env(program: &str) -> Command {
 
     cmd
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_claude_in_node_version_managers() {
+        let home = tempfile::tempdir().unwrap();
+        let fnm_bin = home
+            .path()
+            .join(".local/share/fnm/node-versions/v22.1.0/installation/bin");
+        let asdf_bin = home.path().join(".asdf/installs/nodejs/20.11.0/bin");
+        for dir in [&fnm_bin, &asdf_bin] {
+            std::fs::create_dir_all(dir).unwrap();
+            std::fs::write(dir.join("claude"), "").unwrap();
+        }
+        std::fs::create_dir_all(home.path().join(".asdf/installs/nodejs/18.0.0/bin")).unwrap();
+
+        let mut sources: Vec<String> = find_version_manager_installations(home.path())
+            .into_iter()
+            .map(|i| i.source)
+            .collect();
+        sources.sort();
+        assert_eq!(sources, vec!["asdf (20.11.0)", "fnm (v22.1.0)"]);
+    }
+}
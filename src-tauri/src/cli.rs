@@ -0,0 +1,349 @@
+//! Headless command line mode
+//!
+//! `claudia agent run <name> --project <path>` and friends reuse the command layer
+//! without opening a window, so agents defined in the app can run from scripts and CI.
+//! The Tauri runtime still starts, so Linux needs a display (e.g. `xvfb-run`).
+
+use serde::Serialize;
+use std::time::Duration;
+use tauri::{AppHandle, Listener, Manager};
+
+use crate::commands::agents::{execute_agent, get_agent_run, list_agents, Agent};
+use crate::commands::usage::get_usage_stats;
+
+pub const USAGE: &str = "Usage:
+  claudia agent list [--json]
+  claudia agent run <name|id> --project <path> [--task <task>] [--model <model>]
+  claudia usage [--days <n>] [--json]
+  claudia help
+
+Without a command, Claudia starts the desktop app.";
+
+/// How often a running agent's status is checked
+const RUN_POLL_INTERVAL_MS: u64 = 500;
+
+/// Run statuses after which the run no longer changes
+const FINAL_RUN_STATUSES: &[&str] = &["completed", "failed", "cancelled", "budget_exceeded", "interrupted"];
+
+#[derive(Debug, PartialEq)]
+pub enum CliCommand {
+    Help,
+    AgentList {
+        json: bool,
+    },
+    AgentRun {
+        agent: String,
+        project: String,
+        task: Option<String>,
+        model: Option<String>,
+    },
+    Usage {
+        days: Option<u32>,
+        json: bool,
+    },
+}
+
+/// Parse the arguments after the program name
+///
+/// Returns `None` when the first argument isn't a CLI command, so arguments passed by
+/// the OS when launching the app don't keep the window from opening.
+pub fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Option<CliCommand>, String> {
+    let mut args = args.into_iter();
+    let command = match args.next().as_deref() {
+        Some("help" | "--help" | "-h") => CliCommand::Help,
+        Some("agent") => match args.next().as_deref() {
+            Some("list") => {
+                let options = parse_options(args, &["--json"], &[])?;
+                CliCommand::AgentList {
+                    json: options.has_flag("--json"),
+                }
+            }
+            Some("run") => {
+                let mut options = parse_options(args, &[], &["--project", "--task", "--model"])?;
+                let agent = match options.positional.as_slice() {
+                    [agent] => agent.clone(),
+                    [] => return Err("Missing the agent to run".to_string()),
+                    _ => return Err("Expected a single agent name or ID".to_string()),
+                };
+                CliCommand::AgentRun {
+                    agent,
+                    project: options
+                        .take_value("--project")
+                        .ok_or("Missing --project <path>")?,
+                    task: options.take_value("--task"),
+                    model: options.take_value("--model"),
+                }
+            }
+            Some(other) => return Err(format!("Unknown agent command '{}'", other)),
+            None => return Err("Missing agent command".to_string()),
+        },
+        Some("usage") => {
+            let mut options = parse_options(args, &["--json"], &["--days"])?;
+            let days = options
+                .take_value("--days")
+                .map(|days| {
+                    days.parse()
+                        .map_err(|_| format!("--days expects a number of days, got '{}'", days))
+                })
+                .transpose()?;
+            CliCommand::Usage {
+                days,
+                json: options.has_flag("--json"),
+            }
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(command))
+}
+
+#[derive(Default)]
+struct ParsedOptions {
+    flags: Vec<String>,
+    values: Vec<(String, String)>,
+    positional: Vec<String>,
+}
+
+impl ParsedOptions {
+    fn has_flag(&self, flag: &str) -> bool {
+        self.flags.iter().any(|f| f == flag)
+    }
+
+    fn take_value(&mut self, option: &str) -> Option<String> {
+        let index = self.values.iter().position(|(name, _)| name == option)?;
+        Some(self.values.remove(index).1)
+    }
+}
+
+/// Split arguments into known flags, `--option value` pairs and positional arguments
+fn parse_options<I: Iterator<Item = String>>(
+    mut args: I,
+    flags: &[&str],
+    valued: &[&str],
+) -> Result<ParsedOptions, String> {
+    let mut options = ParsedOptions::default();
+    while let Some(arg) = args.next() {
+        if flags.contains(&arg.as_str()) {
+            options.flags.push(arg);
+        } else if valued.contains(&arg.as_str()) {
+            let value = args
+                .next()
+                .ok_or_else(|| format!("{} expects a value", arg))?;
+            options.values.push((arg, value));
+        } else if arg.starts_with("--") {
+            return Err(format!("Unknown option '{}'", arg));
+        } else {
+            options.positional.push(arg);
+        }
+    }
+    Ok(options)
+}
+
+/// Run a command without opening a window and return the process exit code
+pub fn run(command: CliCommand, mut context: tauri::Context<tauri::Wry>) -> i32 {
+    if command == CliCommand::Help {
+        println!("{}", USAGE);
+        return 0;
+    }
+
+    context.config_mut().app.windows.clear();
+    let app = tauri::Builder::default()
+        .plugin(tauri_plugin_shell::init())
+        .plugin(tauri_plugin_notification::init())
+        .setup(move |app| {
+            crate::manage_app_state(app, false);
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let code = match execute(&handle, command).await {
+                    Ok(code) => code,
+                    Err(e) => {
+                        eprintln!("error: {}", e);
+                        1
+                    }
+                };
+                handle.exit(code);
+            });
+            Ok(())
+        })
+        .build(context);
+
+    match app {
+        Ok(app) => app.run_return(|_, _| {}),
+        Err(e) => {
+            eprintln!("error: Failed to start Claudia: {}", e);
+            1
+        }
+    }
+}
+
+async fn execute(app: &AppHandle, command: CliCommand) -> Result<i32, String> {
+    match command {
+        CliCommand::Help => {
+            println!("{}", USAGE);
+            Ok(0)
+        }
+        CliCommand::AgentList { json } => {
+            let agents = list_agents(app.state(), app.state()).await?;
+            if json {
+                print_json(&agents)?;
+            } else {
+                for agent in &agents {
+                    println!("{:>4}  {}  ({})", agent.id.unwrap_or_default(), agent.name, agent.model);
+                }
+            }
+            Ok(0)
+        }
+        CliCommand::AgentRun {
+            agent,
+            project,
+            task,
+            model,
+        } => run_agent(app, &agent, project, task, model).await,
+        CliCommand::Usage { days, json } => {
+            let handle = app.clone();
+            let stats = tokio::task::spawn_blocking(move || get_usage_stats(handle.state(), days))
+                .await
+                .map_err(|e| e.to_string())??;
+            if json {
+                print_json(&stats)?;
+            } else {
+                println!("Total cost:     ${:.4}", stats.total_cost);
+                println!("Total tokens:   {}", stats.total_tokens);
+                println!("Sessions:       {}", stats.total_sessions);
+                for model in &stats.by_model {
+                    println!("  {:<32} ${:>10.4}  {} tokens", model.model, model.total_cost, model.total_tokens);
+                }
+            }
+            Ok(0)
+        }
+    }
+}
+
+fn print_json<T: Serialize>(value: &T) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+    println!("{}", json);
+    Ok(())
+}
+
+/// Match an agent by ID or by case-insensitive name
+fn find_agent<'a>(agents: &'a [Agent], query: &str) -> Result<&'a Agent, String> {
+    if let Ok(id) = query.parse::<i64>() {
+        if let Some(agent) = agents.iter().find(|agent| agent.id == Some(id)) {
+            return Ok(agent);
+        }
+    }
+    let matches: Vec<&Agent> = agents
+        .iter()
+        .filter(|agent| agent.name.eq_ignore_ascii_case(query))
+        .collect();
+    match matches.as_slice() {
+        [agent] => Ok(agent),
+        [] => Err(format!("No agent named '{}'", query)),
+        _ => Err(format!("Several agents are named '{}', pass its ID instead", query)),
+    }
+}
+
+/// Start an agent, stream its output to stdout and wait until the run finishes
+///
+/// The exit code is 0 only when the run completed.
+async fn run_agent(
+    app: &AppHandle,
+    query: &str,
+    project: String,
+    task: Option<String>,
+    model: Option<String>,
+) -> Result<i32, String> {
+    let agents = list_agents(app.state(), app.state()).await?;
+    let agent = find_agent(&agents, query)?;
+    let agent_id = agent.id.ok_or("Agent has no ID")?;
+    let task = task
+        .or_else(|| agent.default_task.clone())
+        .ok_or_else(|| format!("Agent '{}' has no default task, pass --task", agent.name))?;
+    let project = std::fs::canonicalize(&project)
+        .map_err(|e| format!("Project path '{}' is not accessible: {}", project, e))?
+        .to_string_lossy()
+        .to_string();
+
+    // This process runs a single agent, so the app-wide events only carry its output
+    app.listen("agent-output", |event| {
+        if let Ok(line) = serde_json::from_str::<String>(event.payload()) {
+            println!("{}", line);
+        }
+    });
+    app.listen("agent-error", |event| {
+        if let Ok(line) = serde_json::from_str::<String>(event.payload()) {
+            eprintln!("{}", line);
+        }
+    });
+
+    let run_id = execute_agent(
+        app.clone(),
+        agent_id,
+        project,
+        task,
+        model,
+        None,
+        None,
+        app.state(),
+        app.state(),
+    )
+    .await?;
+
+    let mut last_status = String::new();
+    loop {
+        let run = get_agent_run(app.state(), run_id).await?;
+        if run.status != last_status {
+            match run.status.as_str() {
+                "queued" => eprintln!("Run {} is queued behind other runs in Claudia", run_id),
+                "retrying" => eprintln!("Run {} hit a transient error; Claudia will retry it", run_id),
+                _ => {}
+            }
+            last_status = run.status.clone();
+        }
+        if FINAL_RUN_STATUSES.contains(&run.status.as_str()) {
+            eprintln!("Run {} finished: {}", run_id, run.status);
+            return Ok(if run.status == "completed" { 0 } else { 1 });
+        }
+        tokio::time::sleep(Duration::from_millis(RUN_POLL_INTERVAL_MS)).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Option<CliCommand>, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn launches_the_gui_without_a_command() {
+        assert_eq!(parse(&[]), Ok(None));
+        assert_eq!(parse(&["-psn_0_12345"]), Ok(None));
+    }
+
+    #[test]
+    fn parses_agent_run() {
+        assert_eq!(
+            parse(&["agent", "run", "Reviewer", "--project", "/tmp/p", "--task", "check it"]),
+            Ok(Some(CliCommand::AgentRun {
+                agent: "Reviewer".to_string(),
+                project: "/tmp/p".to_string(),
+                task: Some("check it".to_string()),
+                model: None,
+            }))
+        );
+        assert!(parse(&["agent", "run", "Reviewer"]).is_err());
+        assert!(parse(&["agent", "run", "--project", "/tmp/p"]).is_err());
+        assert!(parse(&["agent", "run", "Reviewer", "--project"]).is_err());
+    }
+
+    #[test]
+    fn parses_usage_options() {
+        assert_eq!(
+            parse(&["usage", "--json", "--days", "7"]),
+            Ok(Some(CliCommand::Usage { days: Some(7), json: true }))
+        );
+        assert!(parse(&["usage", "--days", "week"]).is_err());
+        assert!(parse(&["usage", "--verbose"]).is_err());
+    }
+}
@@ -0,0 +1,272 @@
+use log::info;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use similar::TextDiff;
+use tauri::State;
+
+use super::agents::{get_agent, Agent, AgentDb};
+
+const VERSION_COLUMNS: &str = "id, agent_id, version, name, icon, system_prompt, default_task, model, enable_file_read, enable_file_write, enable_network, hooks, change_note, created_at";
+
+/// A saved state of an agent
+#[derive(Debug, Serialize, Deserialize, Clone, specta::Type)]
+pub struct AgentVersion {
+    pub id: i64,
+    pub agent_id: i64,
+    pub version: i64,
+    pub name: String,
+    pub icon: String,
+    pub system_prompt: String,
+    pub default_task: Option<String>,
+    pub model: String,
+    pub enable_file_read: bool,
+    pub enable_file_write: bool,
+    pub enable_network: bool,
+    pub hooks: Option<String>,
+    pub change_note: Option<String>,
+    pub created_at: String,
+}
+
+impl AgentVersion {
+    /// Field-by-field values, used for change detection and diffs
+    fn fields(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("name", self.name.clone()),
+            ("icon", self.icon.clone()),
+            ("model", self.model.clone()),
+            ("default_task", self.default_task.clone().unwrap_or_default()),
+            ("enable_file_read", self.enable_file_read.to_string()),
+            ("enable_file_write", self.enable_file_write.to_string()),
+            ("enable_network", self.enable_network.to_string()),
+            ("hooks", self.hooks.clone().unwrap_or_default()),
+            ("system_prompt", self.system_prompt.clone()),
+        ]
+    }
+}
+
+/// One field that differs between two versions
+#[derive(Debug, Serialize, Deserialize, specta::Type)]
+pub struct AgentFieldChange {
+    pub field: String,
+    pub old_value: String,
+    pub new_value: String,
+    /// Unified diff for multi-line fields such as the system prompt
+    pub diff: Option<String>,
+}
+
+/// Differences between two versions of an agent
+#[derive(Debug, Serialize, Deserialize, specta::Type)]
+pub struct AgentVersionDiff {
+    pub agent_id: i64,
+    pub from_version: i64,
+    pub to_version: i64,
+    pub changes: Vec<AgentFieldChange>,
+}
+
+fn row_to_version(row: &Row) -> rusqlite::Result<AgentVersion> {
+    Ok(AgentVersion {
+        id: row.get(0)?,
+        agent_id: row.get(1)?,
+        version: row.get(2)?,
+        name: row.get(3)?,
+        icon: row.get(4)?,
+        system_prompt: row.get(5)?,
+        default_task: row.get(6)?,
+        model: row.get(7)?,
+        enable_file_read: row.get(8)?,
+        enable_file_write: row.get(9)?,
+        enable_network: row.get(10)?,
+        hooks: row.get(11)?,
+        change_note: row.get(12)?,
+        created_at: row.get(13)?,
+    })
+}
+
+fn load_version(conn: &Connection, agent_id: i64, version: i64) -> Result<AgentVersion, String> {
+    conn.query_row(
+        &format!(
+            "SELECT {} FROM agent_versions WHERE agent_id = ?1 AND version = ?2",
+            VERSION_COLUMNS
+        ),
+        params![agent_id, version],
+        row_to_version,
+    )
+    .map_err(|e| format!("Version {} of agent {} not found: {}", version, agent_id, e))
+}
+
+/// Snapshot the agent's current state as a new version, unless it matches the latest one
+pub fn record_agent_version(conn: &Connection, agent_id: i64, change_note: &str) -> Result<Option<i64>, String> {
+    let latest = conn
+        .query_row(
+            &format!(
+                "SELECT {} FROM agent_versions WHERE agent_id = ?1 ORDER BY version DESC LIMIT 1",
+                VERSION_COLUMNS
+            ),
+            params![agent_id],
+            row_to_version,
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+
+    // Build the candidate from the agents row itself so nothing drifts from what was saved
+    let current = conn
+        .query_row(
+            "SELECT id, name, icon, system_prompt, default_task, model, enable_file_read, enable_file_write, enable_network, hooks FROM agents WHERE id = ?1",
+            params![agent_id],
+            |row| {
+                Ok(AgentVersion {
+                    id: 0,
+                    agent_id: row.get(0)?,
+                    version: 0,
+                    name: row.get(1)?,
+                    icon: row.get(2)?,
+                    system_prompt: row.get(3)?,
+                    default_task: row.get(4)?,
+                    model: row.get::<_, String>(5).unwrap_or_else(|_| "sonnet".to_string()),
+                    enable_file_read: row.get::<_, bool>(6).unwrap_or(true),
+                    enable_file_write: row.get::<_, bool>(7).unwrap_or(true),
+                    enable_network: row.get::<_, bool>(8).unwrap_or(false),
+                    hooks: row.get(9)?,
+                    change_note: None,
+                    created_at: String::new(),
+                })
+            },
+        )
+        .map_err(|e| format!("Failed to load agent {}: {}", agent_id, e))?;
+
+    if let Some(latest) = &latest {
+        if latest.fields() == current.fields() {
+            return Ok(None);
+        }
+    }
+
+    let version = latest.map(|v| v.version + 1).unwrap_or(1);
+    conn.execute(
+        "INSERT INTO agent_versions (agent_id, version, name, icon, system_prompt, default_task, model, enable_file_read, enable_file_write, enable_network, hooks, change_note) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+        params![
+            agent_id,
+            version,
+            current.name,
+            current.icon,
+            current.system_prompt,
+            current.default_task,
+            current.model,
+            current.enable_file_read,
+            current.enable_file_write,
+            current.enable_network,
+            current.hooks,
+            change_note
+        ],
+    )
+    .map_err(|e| format!("Failed to record agent version: {}", e))?;
+
+    Ok(Some(version))
+}
+
+/// List all versions of an agent, newest first
+#[tauri::command]
+#[specta::specta]
+pub async fn list_agent_versions(
+    db: State<'_, AgentDb>,
+    agent_id: i64,
+) -> Result<Vec<AgentVersion>, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM agent_versions WHERE agent_id = ?1 ORDER BY version DESC",
+            VERSION_COLUMNS
+        ))
+        .map_err(|e| e.to_string())?;
+
+    let versions = stmt
+        .query_map(params![agent_id], row_to_version)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(versions)
+}
+
+/// Diff two versions of an agent
+#[tauri::command]
+#[specta::specta]
+pub async fn diff_agent_versions(
+    db: State<'_, AgentDb>,
+    agent_id: i64,
+    from_version: i64,
+    to_version: i64,
+) -> Result<AgentVersionDiff, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let from = load_version(&conn, agent_id, from_version)?;
+    let to = load_version(&conn, agent_id, to_version)?;
+
+    let changes = from
+        .fields()
+        .into_iter()
+        .zip(to.fields())
+        .filter(|((_, old), (_, new))| old != new)
+        .map(|((field, old_value), (_, new_value))| {
+            let diff = if old_value.contains('\n') || new_value.contains('\n') {
+                Some(
+                    TextDiff::from_lines(&old_value, &new_value)
+                        .unified_diff()
+                        .context_radius(3)
+                        .header(&format!("v{}", from_version), &format!("v{}", to_version))
+                        .to_string(),
+                )
+            } else {
+                None
+            };
+            AgentFieldChange {
+                field: field.to_string(),
+                old_value,
+                new_value,
+                diff,
+            }
+        })
+        .collect();
+
+    Ok(AgentVersionDiff {
+        agent_id,
+        from_version,
+        to_version,
+        changes,
+    })
+}
+
+/// Restore an agent to an earlier version; the rollback itself becomes a new version
+#[tauri::command]
+#[specta::specta]
+pub async fn rollback_agent(
+    db: State<'_, AgentDb>,
+    agent_id: i64,
+    version: i64,
+) -> Result<Agent, String> {
+    {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        let target = load_version(&conn, agent_id, version)?;
+
+        record_agent_version(&conn, agent_id, "snapshot before rollback")?;
+        conn.execute(
+            "UPDATE agents SET name = ?1, icon = ?2, system_prompt = ?3, default_task = ?4, model = ?5, enable_file_read = ?6, enable_file_write = ?7, enable_network = ?8, hooks = ?9 WHERE id = ?10",
+            params![
+                target.name,
+                target.icon,
+                target.system_prompt,
+                target.default_task,
+                target.model,
+                target.enable_file_read,
+                target.enable_file_write,
+                target.enable_network,
+                target.hooks,
+                agent_id
+            ],
+        )
+        .map_err(|e| format!("Failed to roll back agent: {}", e))?;
+        record_agent_version(&conn, agent_id, &format!("rollback to v{}", version))?;
+
+        info!("Rolled back agent {} to version {}", agent_id, version);
+    }
+
+    get_agent(db, agent_id).await
+}
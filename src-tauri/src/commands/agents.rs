@@ -5,6 +5,7 @@ use log::{debug, error, info, warn};
 use regex;
 use reqwest;
 use rusqlite::{params, Connection, Result as SqliteResult};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 use std::io::{BufRead, BufReader};
@@ -22,7 +23,7 @@ fn find_claude_binary(app_handle: &AppHandle) -> Result<String, String> {
 }
 
 /// Represents a CC Agent stored in the database
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, specta::Type)]
 pub struct Agent {
     pub id: Option<i64>,
     pub name: String,
@@ -39,7 +40,7 @@ pub struct Agent {
 }
 
 /// Represents an agent execution run
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, specta::Type)]
 pub struct AgentRun {
     pub id: Option<i64>,
     pub agent_id: i64,
@@ -57,7 +58,7 @@ pub struct AgentRun {
 }
 
 /// Represents runtime metrics calculated from JSONL
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, specta::Type)]
 pub struct AgentRunMetrics {
     pub duration_ms: Option<i64>,
     pub total_tokens: Option<i64>,
@@ -66,7 +67,7 @@ pub struct AgentRunMetrics {
 }
 
 /// Combined agent run with real-time metrics
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, specta::Type)]
 pub struct AgentRunWithMetrics {
     #[serde(flatten)]
     pub run: AgentRun,
@@ -74,16 +75,22 @@ pub struct AgentRunWithMetrics {
     pub output: Option<String>, // Real-time JSONL content
 }
 
+/// Current version of the `.claudia.json` agent export format
+pub const AGENT_EXPORT_VERSION: u32 = 2;
+
 /// Agent export format
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, specta::Type)]
 pub struct AgentExport {
     pub version: u32,
     pub exported_at: String,
     pub agent: AgentData,
+    /// Provenance information, absent in version 1 exports
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<AgentExportMetadata>,
 }
 
 /// Agent data within export
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, specta::Type)]
 pub struct AgentData {
     pub name: String,
     pub icon: String,
@@ -91,11 +98,122 @@ pub struct AgentData {
     pub default_task: Option<String>,
     pub model: String,
     pub hooks: Option<String>,
+    /// Permissions, absent in version 1 exports (defaults match `create_agent`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub permissions: Option<AgentPermissions>,
+}
+
+/// Permission flags carried in an agent export
+#[derive(Debug, Serialize, Deserialize, Clone, specta::Type)]
+pub struct AgentPermissions {
+    pub enable_file_read: bool,
+    pub enable_file_write: bool,
+    pub enable_network: bool,
+}
+
+impl Default for AgentPermissions {
+    fn default() -> Self {
+        Self {
+            enable_file_read: true,
+            enable_file_write: true,
+            enable_network: false,
+        }
+    }
+}
+
+/// Metadata describing where an exported agent came from
+#[derive(Debug, Serialize, Deserialize, Clone, Default, specta::Type)]
+pub struct AgentExportMetadata {
+    pub app_version: Option<String>,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+impl AgentExport {
+    /// Parse and validate a `.claudia.json` document
+    pub fn parse(json_data: &str) -> Result<Self, String> {
+        let export_data: AgentExport =
+            serde_json::from_str(json_data).map_err(|e| format!("Invalid JSON format: {}", e))?;
+        export_data.validate()?;
+        Ok(export_data)
+    }
+
+    /// Check the export version and required agent fields
+    pub fn validate(&self) -> Result<(), String> {
+        if self.version == 0 || self.version > AGENT_EXPORT_VERSION {
+            return Err(format!(
+                "Unsupported export version: {}. This version of the app supports versions 1 to {}.",
+                self.version, AGENT_EXPORT_VERSION
+            ));
+        }
+
+        let agent = &self.agent;
+        if agent.name.trim().is_empty() {
+            return Err("Agent name must not be empty".to_string());
+        }
+        if agent.system_prompt.trim().is_empty() {
+            return Err("Agent system prompt must not be empty".to_string());
+        }
+        if agent.model.trim().is_empty() {
+            return Err("Agent model must not be empty".to_string());
+        }
+        if let Some(hooks) = &agent.hooks {
+            let parsed: JsonValue = serde_json::from_str(hooks)
+                .map_err(|e| format!("Agent hooks are not valid JSON: {}", e))?;
+            if !parsed.is_object() {
+                return Err("Agent hooks must be a JSON object".to_string());
+            }
+        }
+
+        Ok(())
+    }
 }
 
 /// Database connection state
 pub struct AgentDb(pub Mutex<Connection>);
 
+/// Read a setting from `app_settings`; `None` when it is unset or doesn't parse
+///
+/// Values are JSON, except strings, which are stored as the bare text.
+pub fn get_setting<T: DeserializeOwned>(conn: &Connection, key: &str) -> Option<T> {
+    let value: String = conn
+        .query_row(
+            "SELECT value FROM app_settings WHERE key = ?1",
+            params![key],
+            |row| row.get(0),
+        )
+        .ok()?;
+    serde_json::from_str(&value)
+        .or_else(|_| serde_json::from_value(JsonValue::String(value)))
+        .ok()
+}
+
+/// Save a setting to `app_settings`, see [`get_setting`] for the format
+pub fn set_setting<T: Serialize + ?Sized>(
+    conn: &Connection,
+    key: &str,
+    value: &T,
+) -> Result<(), String> {
+    let value = match serde_json::to_value(value).map_err(|e| e.to_string())? {
+        JsonValue::String(text) => text,
+        json => json.to_string(),
+    };
+    conn.execute(
+        "INSERT INTO app_settings (key, value) VALUES (?1, ?2)
+         ON CONFLICT(key) DO UPDATE SET value = ?2",
+        params![key, value],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Remove a setting from `app_settings`
+pub fn delete_setting(conn: &Connection, key: &str) -> Result<(), String> {
+    conn.execute("DELETE FROM app_settings WHERE key = ?1", params![key])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
 /// Real-time JSONL reading and processing functions
 impl AgentRunMetrics {
     /// Calculate metrics from JSONL content
@@ -322,6 +440,315 @@ pub fn init_database(app: &AppHandle) -> SqliteResult<Connection> {
     )?;
 
 
+    // Create agent_schedules table for recurring agent runs
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS agent_schedules (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            agent_id INTEGER NOT NULL,
+            name TEXT NOT NULL,
+            cron_expression TEXT NOT NULL,
+            project_paths TEXT NOT NULL DEFAULT '[]',
+            task TEXT,
+            model TEXT,
+            enabled BOOLEAN NOT NULL DEFAULT 1,
+            last_run_at TEXT,
+            next_run_at TEXT,
+            last_error TEXT,
+            created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (agent_id) REFERENCES agents(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TRIGGER IF NOT EXISTS update_agent_schedule_timestamp 
+         AFTER UPDATE ON agent_schedules 
+         FOR EACH ROW
+         BEGIN
+             UPDATE agent_schedules SET updated_at = CURRENT_TIMESTAMP WHERE id = NEW.id;
+         END",
+        [],
+    )?;
+
+    // Link runs back to the schedule that started them
+    let _ = conn.execute("ALTER TABLE agent_runs ADD COLUMN schedule_id INTEGER", []);
+
+    // Create agent_pipelines table; steps are stored as a JSON array
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS agent_pipelines (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            description TEXT,
+            steps TEXT NOT NULL DEFAULT '[]',
+            created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TRIGGER IF NOT EXISTS update_agent_pipeline_timestamp 
+         AFTER UPDATE ON agent_pipelines 
+         FOR EACH ROW
+         BEGIN
+             UPDATE agent_pipelines SET updated_at = CURRENT_TIMESTAMP WHERE id = NEW.id;
+         END",
+        [],
+    )?;
+
+    // Create agent_pipeline_runs table; steps are snapshotted so edits don't affect running pipelines
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS agent_pipeline_runs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            pipeline_id INTEGER NOT NULL,
+            pipeline_name TEXT NOT NULL,
+            project_path TEXT NOT NULL,
+            input TEXT,
+            steps TEXT NOT NULL,
+            status TEXT NOT NULL DEFAULT 'pending',
+            current_step INTEGER NOT NULL DEFAULT 0,
+            step_run_ids TEXT NOT NULL DEFAULT '[]',
+            step_outputs TEXT NOT NULL DEFAULT '[]',
+            error TEXT,
+            created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            completed_at TEXT,
+            FOREIGN KEY (pipeline_id) REFERENCES agent_pipelines(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    let _ = conn.execute("ALTER TABLE agent_runs ADD COLUMN pipeline_run_id INTEGER", []);
+
+    // Position of queued runs waiting for a free concurrency slot
+    let _ = conn.execute("ALTER TABLE agent_runs ADD COLUMN queue_position INTEGER", []);
+
+    // Create agent_run_artifacts table for files produced by runs
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS agent_run_artifacts (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            run_id INTEGER NOT NULL,
+            path TEXT NOT NULL,
+            change_type TEXT NOT NULL,
+            source TEXT NOT NULL,
+            size INTEGER,
+            sha256 TEXT,
+            collected_at TEXT NOT NULL,
+            FOREIGN KEY (run_id) REFERENCES agent_runs(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    let _ = conn.execute("ALTER TABLE agent_run_artifacts ADD COLUMN diff TEXT", []);
+
+    // `git status` of the project when a run started, to tell the run's changes apart
+    let _ = conn.execute("ALTER TABLE agent_runs ADD COLUMN git_baseline TEXT", []);
+
+    // Create agent_versions table with a snapshot of every saved agent state
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS agent_versions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            agent_id INTEGER NOT NULL,
+            version INTEGER NOT NULL,
+            name TEXT NOT NULL,
+            icon TEXT NOT NULL,
+            system_prompt TEXT NOT NULL,
+            default_task TEXT,
+            model TEXT NOT NULL,
+            enable_file_read BOOLEAN NOT NULL,
+            enable_file_write BOOLEAN NOT NULL,
+            enable_network BOOLEAN NOT NULL,
+            hooks TEXT,
+            change_note TEXT,
+            created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            UNIQUE (agent_id, version),
+            FOREIGN KEY (agent_id) REFERENCES agents(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // Bookkeeping for runs that were cut off by an app crash or restart
+    let _ = conn.execute("ALTER TABLE agent_runs ADD COLUMN interrupted_at TEXT", []);
+    let _ = conn.execute("ALTER TABLE agent_runs ADD COLUMN resume_count INTEGER NOT NULL DEFAULT 0", []);
+    let _ = conn.execute("ALTER TABLE agent_runs ADD COLUMN retry_count INTEGER NOT NULL DEFAULT 0", []);
+    let _ = conn.execute("ALTER TABLE agent_runs ADD COLUMN retry_at TEXT", []);
+
+    // Create agent_batches table for one task fanned out across several projects
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS agent_batches (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            agent_id INTEGER NOT NULL,
+            agent_name TEXT NOT NULL,
+            task TEXT NOT NULL,
+            model TEXT,
+            project_paths TEXT NOT NULL DEFAULT '[]',
+            start_errors TEXT,
+            created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (agent_id) REFERENCES agents(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    let _ = conn.execute("ALTER TABLE agent_runs ADD COLUMN batch_id INTEGER", []);
+
+    // "normal" or "plan" (read-only dry run), and the plan a run was approved from
+    let _ = conn.execute("ALTER TABLE agent_runs ADD COLUMN execution_mode TEXT NOT NULL DEFAULT 'normal'", []);
+    let _ = conn.execute("ALTER TABLE agent_runs ADD COLUMN plan_run_id INTEGER", []);
+
+    // Optional per-run spending limits
+    let _ = conn.execute("ALTER TABLE agent_runs ADD COLUMN max_cost_usd REAL", []);
+    let _ = conn.execute("ALTER TABLE agent_runs ADD COLUMN max_tokens INTEGER", []);
+
+    // Create agent_run_logs table with each run's output and lifecycle events
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS agent_run_logs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            run_id INTEGER NOT NULL,
+            stream TEXT NOT NULL,
+            message TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            FOREIGN KEY (run_id) REFERENCES agent_runs(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_agent_run_logs_run_id ON agent_run_logs(run_id)",
+        [],
+    )?;
+    let _ = conn.execute("ALTER TABLE agent_run_logs ADD COLUMN seq INTEGER", []);
+
+    // Create spawned_processes table with Claude processes that may outlive the app
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS spawned_processes (
+            pid INTEGER NOT NULL,
+            start_time INTEGER NOT NULL,
+            kind TEXT NOT NULL,
+            run_id INTEGER,
+            session_id TEXT,
+            project_path TEXT NOT NULL,
+            created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            PRIMARY KEY (pid, start_time)
+        )",
+        [],
+    )?;
+
+    // Create session_error_logs table with stderr of interactive sessions
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS session_error_logs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            session_id TEXT NOT NULL,
+            seq INTEGER NOT NULL,
+            message TEXT NOT NULL,
+            created_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_session_error_logs_session_id ON session_error_logs(session_id)",
+        [],
+    )?;
+
+    // Create mcp_server_logs table with output Claudia captured from MCP servers
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS mcp_server_logs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            server_name TEXT NOT NULL,
+            level TEXT NOT NULL,
+            message TEXT NOT NULL,
+            created_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_mcp_server_logs_server_name ON mcp_server_logs(server_name)",
+        [],
+    )?;
+
+    // Create mcp_server_health table with results of background health checks
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS mcp_server_health (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            server_name TEXT NOT NULL,
+            healthy BOOLEAN NOT NULL,
+            latency_ms INTEGER,
+            tool_count INTEGER,
+            error TEXT,
+            checked_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_mcp_server_health_server_name ON mcp_server_health(server_name)",
+        [],
+    )?;
+
+    // Create usage index tables so usage stats only parse newly appended transcript data
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS usage_index_files (
+            path TEXT PRIMARY KEY,
+            modified_ms INTEGER NOT NULL,
+            size INTEGER NOT NULL,
+            offset INTEGER NOT NULL,
+            project_path TEXT
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS usage_messages (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            file_path TEXT NOT NULL,
+            message_key TEXT UNIQUE,
+            timestamp TEXT NOT NULL,
+            model TEXT NOT NULL,
+            input_tokens INTEGER NOT NULL,
+            output_tokens INTEGER NOT NULL,
+            cache_creation_tokens INTEGER NOT NULL,
+            cache_read_tokens INTEGER NOT NULL,
+            reported_cost REAL,
+            session_id TEXT NOT NULL,
+            project_path TEXT NOT NULL
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_usage_messages_file_path ON usage_messages(file_path)",
+        [],
+    )?;
+
+    // Create per-project environment variables injected into Claude processes
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS project_env_vars (
+            project_path TEXT NOT NULL,
+            key TEXT NOT NULL,
+            value TEXT NOT NULL,
+            PRIMARY KEY (project_path, key)
+        )",
+        [],
+    )?;
+
+    // Create system prompt presets attachable to ad-hoc sessions
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS prompt_presets (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            content TEXT NOT NULL,
+            mode TEXT NOT NULL DEFAULT 'append',
+            created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+
+    // Create UI preferences table so the frontend can restore its state
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS ui_preferences (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL,
+            updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+
     // Create settings table for app-wide settings
     conn.execute(
         "CREATE TABLE IF NOT EXISTS app_settings (
@@ -344,13 +771,24 @@ pub fn init_database(app: &AppHandle) -> SqliteResult<Connection> {
         [],
     )?;
 
+    // Schema changes beyond the tables above are versioned migrations
+    super::migrations::run_migrations(&conn, super::migrations::MIGRATIONS)?;
+
     Ok(conn)
 }
 
 /// List all agents
 #[tauri::command]
-pub async fn list_agents(db: State<'_, AgentDb>) -> Result<Vec<Agent>, String> {
+#[specta::specta]
+pub async fn list_agents(
+    db: State<'_, AgentDb>,
+    cache: State<'_, super::listing_cache::ListingCache>,
+) -> Result<Vec<Agent>, String> {
     let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let stamp = super::listing_cache::agent_list_stamp(&conn).map_err(|e| e.to_string())?;
+    if let Some(agents) = cache.agents(&stamp) {
+        return Ok(agents);
+    }
 
     let mut stmt = conn
         .prepare("SELECT id, name, icon, system_prompt, default_task, model, enable_file_read, enable_file_write, enable_network, hooks, created_at, updated_at FROM agents ORDER BY created_at DESC")
@@ -379,11 +817,13 @@ pub async fn list_agents(db: State<'_, AgentDb>) -> Result<Vec<Agent>, String> {
         .collect::<Result<Vec<_>, _>>()
         .map_err(|e| e.to_string())?;
 
+    cache.store_agents(stamp, &agents);
     Ok(agents)
 }
 
 /// Create a new agent
 #[tauri::command]
+#[specta::specta]
 pub async fn create_agent(
     db: State<'_, AgentDb>,
     name: String,
@@ -409,6 +849,7 @@ pub async fn create_agent(
     .map_err(|e| e.to_string())?;
 
     let id = conn.last_insert_rowid();
+    super::agent_versions::record_agent_version(&conn, id, "created")?;
 
     // Fetch the created agent
     let agent = conn
@@ -437,8 +878,9 @@ pub async fn create_agent(
     Ok(agent)
 }
 
-/// Update an existing agent
+/// Update an existing agent; its permissions are left as they are when `permissions` is `None`
 #[tauri::command]
+#[specta::specta]
 pub async fn update_agent(
     db: State<'_, AgentDb>,
     id: i64,
@@ -447,9 +889,7 @@ pub async fn update_agent(
     system_prompt: String,
     default_task: Option<String>,
     model: Option<String>,
-    enable_file_read: Option<bool>,
-    enable_file_write: Option<bool>,
-    enable_network: Option<bool>,
+    permissions: Option<AgentPermissions>,
     hooks: Option<String>,
 ) -> Result<Agent, String> {
     let conn = db.0.lock().map_err(|e| e.to_string())?;
@@ -469,20 +909,17 @@ pub async fn update_agent(
     ];
     let mut param_count = 6;
 
-    if let Some(efr) = enable_file_read {
-        param_count += 1;
-        query.push_str(&format!(", enable_file_read = ?{}", param_count));
-        params_vec.push(Box::new(efr));
-    }
-    if let Some(efw) = enable_file_write {
-        param_count += 1;
-        query.push_str(&format!(", enable_file_write = ?{}", param_count));
-        params_vec.push(Box::new(efw));
-    }
-    if let Some(en) = enable_network {
-        param_count += 1;
-        query.push_str(&format!(", enable_network = ?{}", param_count));
-        params_vec.push(Box::new(en));
+    if let Some(permissions) = permissions {
+        query.push_str(&format!(
+            ", enable_file_read = ?{}, enable_file_write = ?{}, enable_network = ?{}",
+            param_count + 1,
+            param_count + 2,
+            param_count + 3
+        ));
+        param_count += 3;
+        params_vec.push(Box::new(permissions.enable_file_read));
+        params_vec.push(Box::new(permissions.enable_file_write));
+        params_vec.push(Box::new(permissions.enable_network));
     }
 
     param_count += 1;
@@ -494,6 +931,7 @@ pub async fn update_agent(
         rusqlite::params_from_iter(params_vec.iter().map(|p| p.as_ref())),
     )
     .map_err(|e| e.to_string())?;
+    super::agent_versions::record_agent_version(&conn, id, "updated")?;
 
     // Fetch the updated agent
     let agent = conn
@@ -524,17 +962,21 @@ pub async fn update_agent(
 
 /// Delete an agent
 #[tauri::command]
+#[specta::specta]
 pub async fn delete_agent(db: State<'_, AgentDb>, id: i64) -> Result<(), String> {
     let conn = db.0.lock().map_err(|e| e.to_string())?;
 
     conn.execute("DELETE FROM agents WHERE id = ?1", params![id])
         .map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM agent_versions WHERE agent_id = ?1", params![id])
+        .map_err(|e| e.to_string())?;
 
     Ok(())
 }
 
 /// Get a single agent by ID
 #[tauri::command]
+#[specta::specta]
 pub async fn get_agent(db: State<'_, AgentDb>, id: i64) -> Result<Agent, String> {
     let conn = db.0.lock().map_err(|e| e.to_string())?;
 
@@ -566,6 +1008,7 @@ pub async fn get_agent(db: State<'_, AgentDb>, id: i64) -> Result<Agent, String>
 
 /// List agent runs (optionally filtered by agent_id)
 #[tauri::command]
+#[specta::specta]
 pub async fn list_agent_runs(
     db: State<'_, AgentDb>,
     agent_id: Option<i64>,
@@ -620,6 +1063,7 @@ pub async fn list_agent_runs(
 
 /// Get a single agent run by ID
 #[tauri::command]
+#[specta::specta]
 pub async fn get_agent_run(db: State<'_, AgentDb>, id: i64) -> Result<AgentRun, String> {
     let conn = db.0.lock().map_err(|e| e.to_string())?;
 
@@ -653,6 +1097,7 @@ pub async fn get_agent_run(db: State<'_, AgentDb>, id: i64) -> Result<AgentRun,
 
 /// Get agent run with real-time metrics from JSONL
 #[tauri::command]
+#[specta::specta]
 pub async fn get_agent_run_with_real_time_metrics(
     db: State<'_, AgentDb>,
     id: i64,
@@ -663,6 +1108,7 @@ pub async fn get_agent_run_with_real_time_metrics(
 
 /// List agent runs with real-time metrics from JSONL
 #[tauri::command]
+#[specta::specta]
 pub async fn list_agent_runs_with_metrics(
     db: State<'_, AgentDb>,
     agent_id: Option<i64>,
@@ -680,23 +1126,164 @@ pub async fn list_agent_runs_with_metrics(
 
 /// Execute a CC agent with streaming output
 #[tauri::command]
+#[specta::specta]
+#[allow(clippy::too_many_arguments)]
 pub async fn execute_agent(
     app: AppHandle,
     agent_id: i64,
     project_path: String,
     task: String,
     model: Option<String>,
+    max_cost_usd: Option<f64>,
+    max_tokens: Option<u64>,
     db: State<'_, AgentDb>,
     registry: State<'_, crate::process::ProcessRegistryState>,
 ) -> Result<i64, String> {
     info!("Executing agent {} with task: {}", agent_id, task);
+    let budget = super::live_metrics::RunBudget {
+        max_cost_usd,
+        max_tokens,
+    };
+    launch_agent_run(app, agent_id, project_path, task, model, "normal", budget, None, db, registry).await
+}
 
+/// Create a run record in the given execution mode and start it, or queue it if the
+/// concurrency limit is reached
+///
+/// `schedule_id` is set for runs started by a schedule, before the process is spawned.
+#[allow(clippy::too_many_arguments)]
+pub async fn launch_agent_run(
+    app: AppHandle,
+    agent_id: i64,
+    project_path: String,
+    task: String,
+    model: Option<String>,
+    execution_mode: &str,
+    budget: super::live_metrics::RunBudget,
+    schedule_id: Option<i64>,
+    db: State<'_, AgentDb>,
+    registry: State<'_, crate::process::ProcessRegistryState>,
+) -> Result<i64, String> {
     // Get the agent from database
     let agent = get_agent(db.clone(), agent_id).await?;
     let execution_model = model.unwrap_or(agent.model.clone());
-    
-    // Create .claude/settings.json with agent hooks if it doesn't exist
-    if let Some(hooks_json) = &agent.hooks {
+
+    // Create a new run record, queueing it if the concurrency limit is reached
+    let (run_id, queued) = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        conn.execute(
+            "INSERT INTO agent_runs (agent_id, agent_name, agent_icon, task, model, project_path, session_id, execution_mode, max_cost_usd, max_tokens, schedule_id) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            params![
+                agent_id,
+                agent.name,
+                agent.icon,
+                task,
+                execution_model,
+                project_path,
+                "",
+                execution_mode,
+                budget.max_cost_usd,
+                budget.max_tokens.map(|tokens| tokens as i64),
+                schedule_id
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+        let run_id = conn.last_insert_rowid();
+
+        let queue = app.state::<super::run_queue::AgentRunQueueState>();
+        let queued = queue
+            .try_acquire_slot(&conn, run_id, false)
+            .and_then(|acquired| {
+                if !acquired {
+                    super::run_queue::enqueue_run(&conn, run_id)?;
+                }
+                Ok(!acquired)
+            });
+        match queued {
+            Ok(queued) => (run_id, queued),
+            Err(e) => {
+                // Neither started nor queued, so nothing would ever pick the run up
+                let _ = conn.execute(
+                    "UPDATE agent_runs SET status = 'failed', completed_at = CURRENT_TIMESTAMP WHERE id = ?1",
+                    params![run_id],
+                );
+                return Err(e);
+            }
+        }
+    };
+
+    if queued {
+        info!("Concurrency limit reached, queued agent run {}", run_id);
+        let _ = app.emit(&format!("agent-queued:{}", run_id), true);
+        let _ = app.emit("agent-queued", run_id);
+        return Ok(run_id);
+    }
+
+    if let Err(e) = start_agent_run(app.clone(), run_id, agent, project_path, task, execution_model, None, db.clone(), registry).await {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        let _ = conn.execute(
+            "UPDATE agent_runs SET status = 'failed', completed_at = CURRENT_TIMESTAMP WHERE id = ?1",
+            params![run_id],
+        );
+        return Err(e);
+    }
+
+    Ok(run_id)
+}
+
+/// Start a run that was previously queued, reading its parameters from the run record
+///
+/// Runs that already have a session ID were interrupted earlier and continue that session.
+pub async fn start_queued_agent_run(
+    app: AppHandle,
+    run_id: i64,
+    db: State<'_, AgentDb>,
+    registry: State<'_, crate::process::ProcessRegistryState>,
+) -> Result<i64, String> {
+    let run = get_agent_run(db.clone(), run_id).await?;
+    let agent = get_agent(db.clone(), run.agent_id).await?;
+    let resume_session = Some(run.session_id).filter(|sid| !sid.is_empty());
+
+    info!("Starting queued agent run {} ('{}')", run_id, agent.name);
+    start_agent_run(app, run_id, agent, run.project_path, run.task, run.model, resume_session, db, registry).await
+}
+
+/// Prepare the project and spawn Claude for an existing run record
+#[allow(clippy::too_many_arguments)]
+async fn start_agent_run(
+    app: AppHandle,
+    run_id: i64,
+    agent: Agent,
+    project_path: String,
+    task: String,
+    execution_model: String,
+    resume_session: Option<String>,
+    db: State<'_, AgentDb>,
+    registry: State<'_, crate::process::ProcessRegistryState>,
+) -> Result<i64, String> {
+    let agent_id = agent.id.unwrap_or_default();
+
+    // Plan-only runs may read the project but must not change it
+    let (plan_only, budget) = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        conn.query_row(
+            "SELECT execution_mode, max_cost_usd, max_tokens FROM agent_runs WHERE id = ?1",
+            params![run_id],
+            |row| {
+                Ok((
+                    row.get::<_, Option<String>>(0)?.as_deref() == Some("plan"),
+                    super::live_metrics::RunBudget {
+                        max_cost_usd: row.get(1)?,
+                        max_tokens: row.get::<_, Option<i64>>(2)?.map(|tokens| tokens as u64),
+                    },
+                ))
+            },
+        )
+        .map_err(|e| e.to_string())?
+    };
+
+    // Create .claude/settings.json with agent hooks if it doesn't exist (not for plan-only runs)
+    if let Some(hooks_json) = agent.hooks.as_ref().filter(|_| !plan_only) {
         let claude_dir = std::path::Path::new(&project_path).join(".claude");
         let settings_path = claude_dir.join("settings.json");
         
@@ -731,16 +1318,16 @@ pub async fn execute_agent(
         }
     }
 
-    // Create a new run record
-    let run_id = {
+    // Remember which files were already changed so artifacts only include this run's changes
+    // (a resumed run keeps the baseline from its first start)
+    let git_baseline = super::artifacts::git_changed_files(&project_path);
+    if let Ok(baseline_json) = serde_json::to_string(&git_baseline) {
         let conn = db.0.lock().map_err(|e| e.to_string())?;
-        conn.execute(
-            "INSERT INTO agent_runs (agent_id, agent_name, agent_icon, task, model, project_path, session_id) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-            params![agent_id, agent.name, agent.icon, task, execution_model, project_path, ""],
-        )
-        .map_err(|e| e.to_string())?;
-        conn.last_insert_rowid()
-    };
+        let _ = conn.execute(
+            "UPDATE agent_runs SET git_baseline = ?1 WHERE id = ?2 AND git_baseline IS NULL",
+            params![baseline_json, run_id],
+        );
+    }
 
     // Find Claude binary
     info!("Running agent '{}'", agent.name);
@@ -752,10 +1339,23 @@ pub async fn execute_agent(
         }
     };
 
-    // Build arguments
-    let args = vec![
+    // Build arguments, continuing the previous session if this run was interrupted
+    let mut args = Vec::new();
+    let prompt = match &resume_session {
+        Some(session_id) => {
+            info!("Resuming session {} for agent run {}", session_id, run_id);
+            args.push("--resume".to_string());
+            args.push(session_id.clone());
+            format!(
+                "Your previous run was interrupted before it finished. Continue where you left off with the original task:\n\n{}",
+                task
+            )
+        }
+        None => task.clone(),
+    };
+    args.extend([
         "-p".to_string(),
-        task.clone(),
+        prompt,
         "--system-prompt".to_string(),
         agent.system_prompt.clone(),
         "--model".to_string(),
@@ -763,15 +1363,55 @@ pub async fn execute_agent(
         "--output-format".to_string(),
         "stream-json".to_string(),
         "--verbose".to_string(),
-        "--dangerously-skip-permissions".to_string(),
-    ];
+    ]);
+    if plan_only {
+        args.extend(["--permission-mode".to_string(), "plan".to_string()]);
+    } else {
+        args.push("--dangerously-skip-permissions".to_string());
+    }
 
     // Execute based on whether we should use sidecar or system binary
     if should_use_sidecar(&claude_path) {
-        spawn_agent_sidecar(app, run_id, agent_id, agent.name.clone(), args, project_path, task, execution_model, db, registry).await
+        spawn_agent_sidecar(app, run_id, agent_id, agent.name.clone(), args, project_path, task, execution_model, budget, db, registry).await
     } else {
-        spawn_agent_system(app, run_id, agent_id, agent.name.clone(), claude_path, args, project_path, task, execution_model, db, registry).await
+        spawn_agent_system(app, run_id, agent_id, agent.name.clone(), claude_path, args, project_path, task, execution_model, budget, db, registry).await
+    }
+}
+
+/// Mark a run as over budget and terminate its process
+fn stop_run_over_budget(app: &AppHandle, db_path: &std::path::Path, run_id: i64, pid: u32, reason: &str) {
+    warn!("Stopping agent run {}: {}", run_id, reason);
+    if let Ok(conn) = Connection::open(db_path) {
+        let _ = conn.execute(
+            "UPDATE agent_runs SET status = 'budget_exceeded', completed_at = CURRENT_TIMESTAMP WHERE id = ?1",
+            params![run_id],
+        );
     }
+    super::live_metrics::terminate_process(pid);
+
+    let _ = app.emit(&format!("agent-budget-exceeded:{}", run_id), reason);
+    let _ = app.emit("agent-budget-exceeded", run_id);
+}
+
+/// Follow-up work once a run has finished, shared by the sidecar and system monitors
+fn on_agent_run_finished(
+    app: &AppHandle,
+    run_id: i64,
+    success: bool,
+    metrics: &super::live_metrics::AgentLiveMetrics,
+) {
+    super::artifacts::spawn_artifact_collection(app.clone(), run_id);
+    super::notifications::notify_agent_run_finished(app, run_id, success, metrics);
+    super::slack::notify_run_finished(app, run_id, success, metrics);
+    super::discord::notify_run_finished(app, run_id, success, metrics);
+    super::email::notify_run_finished(app, run_id, success, metrics);
+    super::telemetry::record_agent_run(app, run_id, success, metrics);
+    let event = if success {
+        super::webhooks::RunLifecycleEvent::Completed
+    } else {
+        super::webhooks::RunLifecycleEvent::Failed
+    };
+    super::webhooks::fire_run_event(app, run_id, event, Some(metrics));
 }
 
 /// Determines whether to use sidecar or system binary execution for agents
@@ -795,12 +1435,23 @@ fn create_agent_sidecar_command(
     
     // Set working directory
     sidecar_cmd = sidecar_cmd.current_dir(project_path);
+
+    // Expose keychain secrets referenced by MCP server configs or the environment profile
+    let project_env = super::env_profiles::project_env(app, project_path);
+    sidecar_cmd = sidecar_cmd.envs(super::secrets::secret_env(project_path, &project_env));
+
+    // Route traffic through the configured proxy
+    sidecar_cmd = sidecar_cmd.envs(super::proxy::proxy_env());
+
+    // Apply the project's environment profile
+    sidecar_cmd = sidecar_cmd.envs(project_env);
     
     Ok(sidecar_cmd)
 }
 
 /// Creates a system binary command for agent execution
 fn create_agent_system_command(
+    app: &AppHandle,
     claude_path: &str,
     args: Vec<String>,
     project_path: &str,
@@ -812,10 +1463,15 @@ fn create_agent_system_command(
         cmd.arg(arg);
     }
     
+    let project_env = super::env_profiles::project_env(app, project_path);
     cmd.current_dir(project_path)
+        .envs(super::secrets::secret_env(project_path, &project_env))
+        .envs(super::proxy::proxy_env())
+        .envs(project_env)
         .stdin(Stdio::null())
         .stdout(Stdio::piped())
         .stderr(Stdio::piped());
+    crate::process::tree::isolate(&mut cmd);
     
     cmd
 }
@@ -829,7 +1485,8 @@ async fn spawn_agent_sidecar(
     args: Vec<String>,
     project_path: String,
     _task: String,
-    _execution_model: String,
+    execution_model: String,
+    budget: super::live_metrics::RunBudget,
     db: State<'_, AgentDb>,
     registry: State<'_, crate::process::ProcessRegistryState>,
 ) -> Result<i64, String> {
@@ -837,15 +1494,20 @@ async fn spawn_agent_sidecar(
 
     // Create the sidecar command
     let sidecar_cmd = create_agent_sidecar_command(&app, args, &project_path)?;
+    let run_log = super::run_logs::RunLogger::start(app.clone(), run_id);
     
     // Spawn the sidecar process
-    let (mut rx, child) = sidecar_cmd
-        .spawn()
-        .map_err(|e| format!("Failed to spawn Claude sidecar: {}", e))?;
+    let (mut rx, child) = sidecar_cmd.spawn().map_err(|e| {
+        let message = format!("Failed to spawn Claude sidecar: {}", e);
+        run_log.lifecycle(&message);
+        message
+    })?;
 
     // Get the child PID for logging
     let pid = child.pid();
     info!("✅ Spawned Claude sidecar process with PID: {:?}", pid);
+    run_log.lifecycle(format!("Spawned Claude sidecar with PID {}", pid));
+    crate::process::tree::adopt(pid);
 
     // Update the database with PID and status
     let now = chrono::Utc::now().to_rfc3339();
@@ -856,7 +1518,12 @@ async fn spawn_agent_sidecar(
             params![pid as i64, now, run_id],
         ).map_err(|e| e.to_string())?;
         info!("📝 Updated database with running status and PID");
+        if let Err(e) = super::orphans::record_spawn(&conn, pid, "agent", Some(run_id), &project_path) {
+            warn!("{}", e);
+        }
+        super::processes::apply_background_priority(&conn, run_id, pid);
     }
+    super::webhooks::fire_run_event(&app, run_id, super::webhooks::RunLifecycleEvent::Started, None);
 
     // We'll extract the session ID from Claude's init message
     let session_id_holder: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(None));
@@ -877,6 +1544,11 @@ async fn spawn_agent_sidecar(
     let registry_clone = registry.0.clone();
     let first_output = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
     let first_output_clone = first_output.clone();
+    let live_metrics = super::live_metrics::LiveMetricsTracker::start(app.clone(), run_id, execution_model, budget);
+    let live_metrics_clone = live_metrics.clone();
+    let run_log_clone = run_log.clone();
+    let transient_errors = super::run_retry::TransientErrorDetector::default();
+    let transient_errors_clone = transient_errors.clone();
 
     let sidecar_task = tokio::spawn(async move {
         info!("📖 Starting to read Claude sidecar events...");
@@ -893,6 +1565,7 @@ async fn spawn_agent_sidecar(
                         if !first_output_clone.load(std::sync::atomic::Ordering::Relaxed) {
                             info!("🎉 First output received from Claude sidecar! Line: {}", line);
                             first_output_clone.store(true, std::sync::atomic::Ordering::Relaxed);
+                            run_log_clone.lifecycle("First output received");
                         }
 
                         if line_count <= 5 {
@@ -909,6 +1582,12 @@ async fn spawn_agent_sidecar(
 
                         // Also store in process registry for cross-session access
                         let _ = registry_clone.append_live_output(run_id, &line);
+                        let tagged = run_log_clone.stdout(&line);
+                        transient_errors_clone.observe(&line);
+                        if let Some(reason) = live_metrics_clone.observe_line(&line) {
+                            run_log_clone.lifecycle(&reason);
+                            stop_run_over_budget(&app_handle, &db_path_for_stream, run_id, pid, &reason);
+                        }
 
                         // Extract session ID from JSONL output
                         if let Ok(json) = serde_json::from_str::<JsonValue>(&line) {
@@ -945,6 +1624,7 @@ async fn spawn_agent_sidecar(
 
                         // Emit the line to the frontend with run_id for isolation
                         let _ = app_handle.emit(&format!("agent-output:{}", run_id), &line);
+                        let _ = app_handle.emit(&format!("agent-stream:{}", run_id), &tagged);
                         // Also emit to the generic event for backward compatibility
                         let _ = app_handle.emit("agent-output", &line);
                     }
@@ -953,18 +1633,26 @@ async fn spawn_agent_sidecar(
                     let line = String::from_utf8_lossy(&data).trim().to_string();
                     if !line.is_empty() {
                         error!("sidecar stderr: {}", line);
+                        transient_errors_clone.observe(&line);
+                        let tagged = run_log_clone.stderr(&line);
                         // Emit error lines to the frontend with run_id for isolation
                         let _ = app_handle.emit(&format!("agent-error:{}", run_id), &line);
+                        let _ = app_handle.emit(&format!("agent-stream:{}", run_id), &tagged);
                         // Also emit to the generic event for backward compatibility
                         let _ = app_handle.emit("agent-error", &line);
                     }
                 }
-                tauri_plugin_shell::process::CommandEvent::Terminated { .. } => {
+                tauri_plugin_shell::process::CommandEvent::Terminated(payload) => {
                     info!("📖 Claude sidecar process terminated");
+                    run_log_clone.lifecycle(format!(
+                        "Process exited with code {:?} (signal {:?})",
+                        payload.code, payload.signal
+                    ));
                     break;
                 }
                 tauri_plugin_shell::process::CommandEvent::Error(e) => {
                     error!("🔥 Claude sidecar error: {}", e);
+                    run_log_clone.lifecycle(format!("Sidecar error: {}", e));
                     break;
                 }
                 _ => {
@@ -996,6 +1684,7 @@ async fn spawn_agent_sidecar(
                 warn!("   2. Authentication issues (API key not found/invalid)");
                 warn!("   3. Network connectivity issues");
                 warn!("   4. Claude failed to initialize but didn't report an error");
+                run_log.lifecycle("No output after 30 seconds, marking run as failed");
 
                 // Update database with failed status
                 if let Ok(conn) = Connection::open(&db_path) {
@@ -1005,8 +1694,11 @@ async fn spawn_agent_sidecar(
                     );
                 }
 
+                let metrics = live_metrics.finish();
                 let _ = app.emit("agent-complete", false);
                 let _ = app.emit(&format!("agent-complete:{}", run_id), false);
+
+                on_agent_run_finished(&app, run_id, false, &metrics);
                 return;
             }
 
@@ -1024,11 +1716,26 @@ async fn spawn_agent_sidecar(
             String::new()
         };
 
+        // Resume later instead of finishing when the API was overloaded or rate limited
+        let retry = transient_errors.reason().and_then(|reason| {
+            super::run_retry::schedule_retry_in(&db_path, run_id, &extracted_session_id, &reason)
+        });
+        if let Some(retry) = retry {
+            live_metrics.finish();
+            run_log.lifecycle(format!(
+                "Transient error, retrying in {} s (attempt {} of {}): {}",
+                retry.delay_secs, retry.attempt, retry.max_attempts, retry.reason
+            ));
+            let _ = app.emit("agent-retrying", &retry);
+            let _ = app.emit(&format!("agent-retrying:{}", run_id), &retry);
+            return;
+        }
+
         // Update the run record with session ID and mark as completed
         if let Ok(conn) = Connection::open(&db_path) {
             info!("🔄 Updating database with extracted session ID: {}", extracted_session_id);
             match conn.execute(
-                "UPDATE agent_runs SET session_id = ?1, status = 'completed', completed_at = CURRENT_TIMESTAMP WHERE id = ?2",
+                "UPDATE agent_runs SET session_id = ?1, status = CASE WHEN status = 'budget_exceeded' THEN status ELSE 'completed' END, completed_at = COALESCE(completed_at, CURRENT_TIMESTAMP) WHERE id = ?2",
                 params![extracted_session_id, run_id],
             ) {
                 Ok(rows_affected) => {
@@ -1048,8 +1755,18 @@ async fn spawn_agent_sidecar(
 
         info!("✅ Claude sidecar execution monitoring complete");
 
+        let metrics = live_metrics.finish();
+        run_log.lifecycle(format!(
+            "Run completed: {} tokens, ${:.4}{}",
+            metrics.total_tokens,
+            metrics.estimated_cost_usd,
+            if metrics.is_error == Some(true) { ", result reported an error" } else { "" }
+        ));
         let _ = app.emit("agent-complete", true);
         let _ = app.emit(&format!("agent-complete:{}", run_id), true);
+
+        let success = metrics.is_error != Some(true) && !live_metrics.budget_exceeded();
+        on_agent_run_finished(&app, run_id, success, &metrics);
     });
 
     Ok(run_id)
@@ -1066,16 +1783,19 @@ async fn spawn_agent_system(
     project_path: String,
     task: String,
     execution_model: String,
+    budget: super::live_metrics::RunBudget,
     db: State<'_, AgentDb>,
     registry: State<'_, crate::process::ProcessRegistryState>,
 ) -> Result<i64, String> {
     // Build the command
-    let mut cmd = create_agent_system_command(&claude_path, args, &project_path);
+    let mut cmd = create_agent_system_command(&app, &claude_path, args, &project_path);
+    let run_log = super::run_logs::RunLogger::start(app.clone(), run_id);
 
     // Spawn the process
     info!("🚀 Spawning Claude system process...");
     let mut child = cmd.spawn().map_err(|e| {
         error!("❌ Failed to spawn Claude process: {}", e);
+        run_log.lifecycle(format!("Failed to spawn Claude: {}", e));
         format!("Failed to spawn Claude: {}", e)
     })?;
 
@@ -1085,6 +1805,8 @@ async fn spawn_agent_system(
     let pid = child.id().unwrap_or(0);
     let now = chrono::Utc::now().to_rfc3339();
     info!("✅ Claude process spawned successfully with PID: {}", pid);
+    run_log.lifecycle(format!("Spawned {} with PID {}", claude_path, pid));
+    crate::process::tree::adopt(pid);
 
     // Update the database with PID and status
     {
@@ -1094,7 +1816,12 @@ async fn spawn_agent_system(
             params![pid as i64, now, run_id],
         ).map_err(|e| e.to_string())?;
         info!("📝 Updated database with running status and PID");
+        if let Err(e) = super::orphans::record_spawn(&conn, pid, "agent", Some(run_id), &project_path) {
+            warn!("{}", e);
+        }
+        super::processes::apply_background_priority(&conn, run_id, pid);
     }
+    super::webhooks::fire_run_event(&app, run_id, super::webhooks::RunLifecycleEvent::Started, None);
 
     // Get stdout and stderr
     let stdout = child.stdout.take().ok_or("Failed to get stdout")?;
@@ -1125,6 +1852,11 @@ async fn spawn_agent_system(
     let first_output = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
     let first_output_clone = first_output.clone();
     let db_path_for_stdout = db_path.clone(); // Clone the db_path for the stdout task
+    let live_metrics = super::live_metrics::LiveMetricsTracker::start(app.clone(), run_id, execution_model.clone(), budget);
+    let live_metrics_clone = live_metrics.clone();
+    let run_log_stdout = run_log.clone();
+    let transient_errors = super::run_retry::TransientErrorDetector::default();
+    let transient_errors_stdout = transient_errors.clone();
 
     let stdout_task = tokio::spawn(async move {
         info!("📖 Starting to read Claude stdout...");
@@ -1141,6 +1873,7 @@ async fn spawn_agent_system(
                     line
                 );
                 first_output_clone.store(true, std::sync::atomic::Ordering::Relaxed);
+                run_log_stdout.lifecycle("First output received");
             }
 
             if line_count <= 5 {
@@ -1157,6 +1890,12 @@ async fn spawn_agent_system(
 
             // Also store in process registry for cross-session access
             let _ = registry_clone.append_live_output(run_id, &line);
+            let tagged = run_log_stdout.stdout(&line);
+            transient_errors_stdout.observe(&line);
+            if let Some(reason) = live_metrics_clone.observe_line(&line) {
+                run_log_stdout.lifecycle(&reason);
+                stop_run_over_budget(&app_handle, &db_path_for_stdout, run_id, pid, &reason);
+            }
 
             // Extract session ID from JSONL output
             if let Ok(json) = serde_json::from_str::<JsonValue>(&line) {
@@ -1193,6 +1932,7 @@ async fn spawn_agent_system(
 
             // Emit the line to the frontend with run_id for isolation
             let _ = app_handle.emit(&format!("agent-output:{}", run_id), &line);
+            let _ = app_handle.emit(&format!("agent-stream:{}", run_id), &tagged);
             // Also emit to the generic event for backward compatibility
             let _ = app_handle.emit("agent-output", &line);
         }
@@ -1206,6 +1946,8 @@ async fn spawn_agent_system(
     let app_handle_stderr = app.clone();
     let first_error = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
     let first_error_clone = first_error.clone();
+    let run_log_stderr = run_log.clone();
+    let transient_errors_stderr = transient_errors.clone();
 
     let stderr_task = tokio::spawn(async move {
         info!("📖 Starting to read Claude stderr...");
@@ -1222,8 +1964,11 @@ async fn spawn_agent_system(
             }
 
             error!("stderr[{}]: {}", error_count, line);
+            transient_errors_stderr.observe(&line);
+            let tagged = run_log_stderr.stderr(&line);
             // Emit error lines to the frontend with run_id for isolation
             let _ = app_handle_stderr.emit(&format!("agent-error:{}", run_id), &line);
+            let _ = app_handle_stderr.emit(&format!("agent-stream:{}", run_id), &tagged);
             // Also emit to the generic event for backward compatibility
             let _ = app_handle_stderr.emit("agent-error", &line);
         }
@@ -1278,6 +2023,7 @@ async fn spawn_agent_system(
                 warn!("   3. Claude failed to initialize but didn't report an error");
                 warn!("   4. Network connectivity issues");
                 warn!("   5. Authentication issues (API key not found/invalid)");
+                run_log.lifecycle("No output after 30 seconds, killing the process");
 
                 // Process timed out - kill it via PID
                 warn!(
@@ -1313,8 +2059,11 @@ async fn spawn_agent_system(
                     );
                 }
 
+                let metrics = live_metrics.finish();
                 let _ = app.emit("agent-complete", false);
                 let _ = app.emit(&format!("agent-complete:{}", run_id), false);
+
+                on_agent_run_finished(&app, run_id, false, &metrics);
                 return;
             }
 
@@ -1328,6 +2077,7 @@ async fn spawn_agent_system(
 
         let duration_ms = start_time.elapsed().as_millis() as i64;
         info!("⏱️ Process execution took {} ms", duration_ms);
+        run_log.lifecycle(format!("Output closed after {} ms", duration_ms));
 
         // Get the session ID that was extracted
         let extracted_session_id = if let Ok(sid) = session_id.lock() {
@@ -1339,11 +2089,26 @@ async fn spawn_agent_system(
         // Wait for process completion and update status
         info!("✅ Claude process execution monitoring complete");
 
+        // Resume later instead of finishing when the API was overloaded or rate limited
+        let retry = transient_errors.reason().and_then(|reason| {
+            super::run_retry::schedule_retry_in(&db_path_for_monitor, run_id, &extracted_session_id, &reason)
+        });
+        if let Some(retry) = retry {
+            live_metrics.finish();
+            run_log.lifecycle(format!(
+                "Transient error, retrying in {} s (attempt {} of {}): {}",
+                retry.delay_secs, retry.attempt, retry.max_attempts, retry.reason
+            ));
+            let _ = app.emit("agent-retrying", &retry);
+            let _ = app.emit(&format!("agent-retrying:{}", run_id), &retry);
+            return;
+        }
+
         // Update the run record with session ID and mark as completed - open a new connection
         if let Ok(conn) = Connection::open(&db_path_for_monitor) {
             info!("🔄 Updating database with extracted session ID: {}", extracted_session_id);
             match conn.execute(
-                "UPDATE agent_runs SET session_id = ?1, status = 'completed', completed_at = CURRENT_TIMESTAMP WHERE id = ?2",
+                "UPDATE agent_runs SET session_id = ?1, status = CASE WHEN status = 'budget_exceeded' THEN status ELSE 'completed' END, completed_at = COALESCE(completed_at, CURRENT_TIMESTAMP) WHERE id = ?2",
                 params![extracted_session_id, run_id],
             ) {
                 Ok(rows_affected) => {
@@ -1363,8 +2128,18 @@ async fn spawn_agent_system(
 
         // Cleanup will be handled by the cleanup_finished_processes function
 
+        let metrics = live_metrics.finish();
+        run_log.lifecycle(format!(
+            "Run completed: {} tokens, ${:.4}{}",
+            metrics.total_tokens,
+            metrics.estimated_cost_usd,
+            if metrics.is_error == Some(true) { ", result reported an error" } else { "" }
+        ));
         let _ = app.emit("agent-complete", true);
         let _ = app.emit(&format!("agent-complete:{}", run_id), true);
+
+        let success = metrics.is_error != Some(true) && !live_metrics.budget_exceeded();
+        on_agent_run_finished(&app, run_id, success, &metrics);
     });
 
     Ok(run_id)
@@ -1372,6 +2147,7 @@ async fn spawn_agent_system(
 
 /// List all currently running agent sessions
 #[tauri::command]
+#[specta::specta]
 pub async fn list_running_sessions(
     db: State<'_, AgentDb>,
     registry: State<'_, crate::process::ProcessRegistryState>,
@@ -1438,6 +2214,7 @@ pub async fn list_running_sessions(
 
 /// Kill a running agent session
 #[tauri::command]
+#[specta::specta]
 pub async fn kill_agent_session(
     app: AppHandle,
     db: State<'_, AgentDb>,
@@ -1496,6 +2273,7 @@ pub async fn kill_agent_session(
 
 /// Get the status of a specific agent session
 #[tauri::command]
+#[specta::specta]
 pub async fn get_session_status(
     db: State<'_, AgentDb>,
     run_id: i64,
@@ -1515,6 +2293,7 @@ pub async fn get_session_status(
 
 /// Cleanup finished processes and update their status
 #[tauri::command]
+#[specta::specta]
 pub async fn cleanup_finished_processes(db: State<'_, AgentDb>) -> Result<Vec<i64>, String> {
     let conn = db.0.lock().map_err(|e| e.to_string())?;
 
@@ -1581,6 +2360,7 @@ pub async fn cleanup_finished_processes(db: State<'_, AgentDb>) -> Result<Vec<i6
 
 /// Get live output from a running process
 #[tauri::command]
+#[specta::specta]
 pub async fn get_live_session_output(
     registry: State<'_, crate::process::ProcessRegistryState>,
     run_id: i64,
@@ -1590,6 +2370,7 @@ pub async fn get_live_session_output(
 
 /// Get real-time output for a running session by reading its JSONL file with live output fallback
 #[tauri::command]
+#[specta::specta]
 pub async fn get_session_output(
     db: State<'_, AgentDb>,
     registry: State<'_, crate::process::ProcessRegistryState>,
@@ -1673,6 +2454,7 @@ pub async fn get_session_output(
 
 /// Stream real-time session output by watching the JSONL file
 #[tauri::command]
+#[specta::specta]
 pub async fn stream_session_output(
     app: AppHandle,
     db: State<'_, AgentDb>,
@@ -1758,35 +2540,48 @@ pub async fn stream_session_output(
     Ok(())
 }
 
-/// Export a single agent to JSON format
+/// Export a single agent to the versioned `.claudia.json` format
 #[tauri::command]
+#[specta::specta]
 pub async fn export_agent(db: State<'_, AgentDb>, id: i64) -> Result<String, String> {
     let conn = db.0.lock().map_err(|e| e.to_string())?;
 
     // Fetch the agent
-    let agent = conn
+    let (agent, metadata) = conn
         .query_row(
-            "SELECT name, icon, system_prompt, default_task, model, hooks FROM agents WHERE id = ?1",
+            "SELECT name, icon, system_prompt, default_task, model, hooks, enable_file_read, enable_file_write, enable_network, created_at, updated_at FROM agents WHERE id = ?1",
             params![id],
             |row| {
-                Ok(serde_json::json!({
-                    "name": row.get::<_, String>(0)?,
-                    "icon": row.get::<_, String>(1)?,
-                    "system_prompt": row.get::<_, String>(2)?,
-                    "default_task": row.get::<_, Option<String>>(3)?,
-                    "model": row.get::<_, String>(4)?,
-                    "hooks": row.get::<_, Option<String>>(5)?
-                }))
+                let agent = AgentData {
+                    name: row.get(0)?,
+                    icon: row.get(1)?,
+                    system_prompt: row.get(2)?,
+                    default_task: row.get(3)?,
+                    model: row.get::<_, String>(4).unwrap_or_else(|_| "sonnet".to_string()),
+                    hooks: row.get(5)?,
+                    permissions: Some(AgentPermissions {
+                        enable_file_read: row.get::<_, bool>(6).unwrap_or(true),
+                        enable_file_write: row.get::<_, bool>(7).unwrap_or(true),
+                        enable_network: row.get::<_, bool>(8).unwrap_or(false),
+                    }),
+                };
+                let metadata = AgentExportMetadata {
+                    app_version: Some(env!("CARGO_PKG_VERSION").to_string()),
+                    created_at: row.get(9)?,
+                    updated_at: row.get(10)?,
+                };
+                Ok((agent, metadata))
             },
         )
         .map_err(|e| format!("Failed to fetch agent: {}", e))?;
 
     // Create the export wrapper
-    let export_data = serde_json::json!({
-        "version": 1,
-        "exported_at": chrono::Utc::now().to_rfc3339(),
-        "agent": agent
-    });
+    let export_data = AgentExport {
+        version: AGENT_EXPORT_VERSION,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        agent,
+        metadata: Some(metadata),
+    };
 
     // Convert to pretty JSON string
     serde_json::to_string_pretty(&export_data)
@@ -1795,6 +2590,7 @@ pub async fn export_agent(db: State<'_, AgentDb>, id: i64) -> Result<String, Str
 
 /// Export agent to file with native dialog
 #[tauri::command]
+#[specta::specta]
 pub async fn export_agent_to_file(
     db: State<'_, AgentDb>,
     id: i64,
@@ -1811,69 +2607,42 @@ pub async fn export_agent_to_file(
 
 /// Get the stored Claude binary path from settings
 #[tauri::command]
+#[specta::specta]
 pub async fn get_claude_binary_path(db: State<'_, AgentDb>) -> Result<Option<String>, String> {
     let conn = db.0.lock().map_err(|e| e.to_string())?;
-
-    match conn.query_row(
-        "SELECT value FROM app_settings WHERE key = 'claude_binary_path'",
-        [],
-        |row| row.get::<_, String>(0),
-    ) {
-        Ok(path) => Ok(Some(path)),
-        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-        Err(e) => Err(format!("Failed to get Claude binary path: {}", e)),
-    }
+    Ok(get_setting(&conn, "claude_binary_path"))
 }
 
 /// Set the Claude binary path in settings
 #[tauri::command]
+#[specta::specta]
 pub async fn set_claude_binary_path(db: State<'_, AgentDb>, path: String) -> Result<(), String> {
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
-
-    // Special handling for bundled sidecar reference
-    if path == "claude-code" {
-        // For bundled sidecar, we don't need to validate file existence
-        // as it's handled by Tauri's sidecar system
-        conn.execute(
-            "INSERT INTO app_settings (key, value) VALUES ('claude_binary_path', ?1)
-             ON CONFLICT(key) DO UPDATE SET value = ?1",
-            params![path],
-        )
-        .map_err(|e| format!("Failed to save Claude binary path: {}", e))?;
-        return Ok(());
-    }
-
-    // Validate that the path exists and is executable for system installations
-    let path_buf = std::path::PathBuf::from(&path);
-    if !path_buf.exists() {
-        return Err(format!("File does not exist: {}", path));
-    }
-
-    // Check if it's executable (on Unix systems)
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        let metadata = std::fs::metadata(&path_buf)
-            .map_err(|e| format!("Failed to read file metadata: {}", e))?;
-        let permissions = metadata.permissions();
-        if permissions.mode() & 0o111 == 0 {
-            return Err(format!("File is not executable: {}", path));
-        }
-    }
+    // Validate that the path exists, is executable and actually runs. The bundled
+    // sidecar is handled by Tauri's sidecar system and passes without checks
+    let version = crate::claude_binary::validate_claude_binary(&path)?;
+    info!("Pinning Claude installation {} (version {:?})", path, version);
 
     // Insert or update the setting
-    conn.execute(
-        "INSERT INTO app_settings (key, value) VALUES ('claude_binary_path', ?1)
-         ON CONFLICT(key) DO UPDATE SET value = ?1",
-        params![path],
-    )
-    .map_err(|e| format!("Failed to save Claude binary path: {}", e))?;
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    set_setting(&conn, "claude_binary_path", &path)
+        .map_err(|e| format!("Failed to save Claude binary path: {}", e))
+}
 
-    Ok(())
+/// Check whether the pinned Claude installation still exists and runs
+#[tauri::command]
+#[specta::specta]
+pub async fn get_claude_binary_status(
+    db: State<'_, AgentDb>,
+) -> Result<crate::claude_binary::ClaudeBinaryStatus, String> {
+    let stored_path = get_claude_binary_path(db).await?;
+    tokio::task::spawn_blocking(move || crate::claude_binary::pinned_binary_status(stored_path))
+        .await
+        .map_err(|e| e.to_string())
 }
 
 /// List all available Claude installations on the system
 #[tauri::command]
+#[specta::specta]
 pub async fn list_claude_installations(
     app: AppHandle,
 ) -> Result<Vec<crate::claude_binary::ClaudeInstallation>, String> {
@@ -2035,56 +2804,109 @@ fn create_command_with_env(program: &str) -> Command {
     tokio_cmd
 }
 
-/// Import an agent from JSON data
-#[tauri::command]
-pub async fn import_agent(db: State<'_, AgentDb>, json_data: String) -> Result<Agent, String> {
-    // Parse the JSON data
-    let export_data: AgentExport =
-        serde_json::from_str(&json_data).map_err(|e| format!("Invalid JSON format: {}", e))?;
+/// Pick a name for an imported agent that doesn't collide with an existing one
+fn resolve_import_name(conn: &Connection, name: &str) -> Result<String, String> {
+    let name_exists = |candidate: &str| -> Result<bool, String> {
+        conn.query_row(
+            "SELECT COUNT(*) FROM agents WHERE name = ?1",
+            params![candidate],
+            |row| row.get::<_, i64>(0),
+        )
+        .map(|count| count > 0)
+        .map_err(|e| e.to_string())
+    };
 
-    // Validate version
-    if export_data.version != 1 {
-        return Err(format!(
-            "Unsupported export version: {}. This version of the app only supports version 1.",
-            export_data.version
-        ));
+    if !name_exists(name)? {
+        return Ok(name.to_string());
     }
 
+    let mut candidate = format!("{} (Imported)", name);
+    let mut suffix = 2;
+    while name_exists(&candidate)? {
+        candidate = format!("{} (Imported {})", name, suffix);
+        suffix += 1;
+    }
+    Ok(candidate)
+}
+
+/// Import an agent from JSON data
+///
+/// `on_conflict` controls what happens when an agent with the same name exists:
+/// `"rename"` (default) imports under a suffixed name, `"replace"` overwrites the
+/// existing agent and `"skip"` returns the existing agent unchanged.
+#[tauri::command]
+#[specta::specta]
+pub async fn import_agent(
+    db: State<'_, AgentDb>,
+    json_data: String,
+    on_conflict: Option<String>,
+) -> Result<Agent, String> {
+    // Parse and validate the JSON data
+    let export_data = AgentExport::parse(&json_data)?;
+
     let agent_data = export_data.agent;
+    let permissions = agent_data.permissions.unwrap_or_default();
     let conn = db.0.lock().map_err(|e| e.to_string())?;
 
-    // Check if an agent with the same name already exists
-    let existing_count: i64 = conn
+    let existing_id: Option<i64> = conn
         .query_row(
-            "SELECT COUNT(*) FROM agents WHERE name = ?1",
+            "SELECT id FROM agents WHERE name = ?1 ORDER BY id LIMIT 1",
             params![agent_data.name],
             |row| row.get(0),
         )
-        .map_err(|e| e.to_string())?;
+        .ok();
 
-    // If agent with same name exists, append a suffix
-    let final_name = if existing_count > 0 {
-        format!("{} (Imported)", agent_data.name)
-    } else {
-        agent_data.name
+    let id = match (existing_id, on_conflict.as_deref().unwrap_or("rename")) {
+        (Some(existing_id), "skip") => {
+            info!("Agent '{}' already exists, skipping import", agent_data.name);
+            existing_id
+        }
+        (Some(existing_id), "replace") => {
+            info!("Replacing existing agent '{}' with imported version", agent_data.name);
+            conn.execute(
+                "UPDATE agents SET icon = ?1, system_prompt = ?2, default_task = ?3, model = ?4, enable_file_read = ?5, enable_file_write = ?6, enable_network = ?7, hooks = ?8 WHERE id = ?9",
+                params![
+                    agent_data.icon,
+                    agent_data.system_prompt,
+                    agent_data.default_task,
+                    agent_data.model,
+                    permissions.enable_file_read,
+                    permissions.enable_file_write,
+                    permissions.enable_network,
+                    agent_data.hooks,
+                    existing_id
+                ],
+            )
+            .map_err(|e| format!("Failed to update agent: {}", e))?;
+            super::agent_versions::record_agent_version(&conn, existing_id, "imported")?;
+            existing_id
+        }
+        (_, "rename" | "skip" | "replace") => {
+            let final_name = resolve_import_name(&conn, &agent_data.name)?;
+
+            // Create the agent
+            conn.execute(
+                "INSERT INTO agents (name, icon, system_prompt, default_task, model, enable_file_read, enable_file_write, enable_network, hooks) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                params![
+                    final_name,
+                    agent_data.icon,
+                    agent_data.system_prompt,
+                    agent_data.default_task,
+                    agent_data.model,
+                    permissions.enable_file_read,
+                    permissions.enable_file_write,
+                    permissions.enable_network,
+                    agent_data.hooks
+                ],
+            )
+            .map_err(|e| format!("Failed to create agent: {}", e))?;
+            let id = conn.last_insert_rowid();
+            super::agent_versions::record_agent_version(&conn, id, "imported")?;
+            id
+        }
+        (_, other) => return Err(format!("Unknown conflict strategy: {}", other)),
     };
 
-    // Create the agent
-    conn.execute(
-        "INSERT INTO agents (name, icon, system_prompt, default_task, model, enable_file_read, enable_file_write, enable_network, hooks) VALUES (?1, ?2, ?3, ?4, ?5, 1, 1, 0, ?6)",
-        params![
-            final_name,
-            agent_data.icon,
-            agent_data.system_prompt,
-            agent_data.default_task,
-            agent_data.model,
-            agent_data.hooks
-        ],
-    )
-    .map_err(|e| format!("Failed to create agent: {}", e))?;
-
-    let id = conn.last_insert_rowid();
-
     // Fetch the created agent
     let agent = conn
         .query_row(
@@ -2114,22 +2936,24 @@ pub async fn import_agent(db: State<'_, AgentDb>, json_data: String) -> Result<A
 
 /// Import agent from file
 #[tauri::command]
+#[specta::specta]
 pub async fn import_agent_from_file(
     db: State<'_, AgentDb>,
     file_path: String,
+    on_conflict: Option<String>,
 ) -> Result<Agent, String> {
     // Read the file
     let json_data =
         std::fs::read_to_string(&file_path).map_err(|e| format!("Failed to read file: {}", e))?;
 
     // Import the agent
-    import_agent(db, json_data).await
+    import_agent(db, json_data, on_conflict).await
 }
 
 // GitHub Agent Import functionality
 
 /// Represents a GitHub agent file from the API
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, specta::Type)]
 pub struct GitHubAgentFile {
     pub name: String,
     pub path: String,
@@ -2150,18 +2974,164 @@ struct GitHubApiResponse {
     file_type: String,
 }
 
-/// Fetch list of agents from GitHub repository
-#[tauri::command]
-pub async fn fetch_github_agents() -> Result<Vec<GitHubAgentFile>, String> {
-    info!("Fetching agents from GitHub repository...");
+/// Default repository and directory searched for shared agents
+const DEFAULT_GITHUB_AGENT_REPO: &str = "getAsterisk/claudia/cc_agents";
 
-    let client = reqwest::Client::new();
-    let url = "https://api.github.com/repos/getAsterisk/claudia/contents/cc_agents";
+/// A GitHub location holding `.claudia.json` agent files
+#[derive(Debug, Clone, PartialEq)]
+pub struct GitHubAgentRepo {
+    pub owner: String,
+    pub repo: String,
+    pub path: String,
+    pub git_ref: Option<String>,
+}
 
-    let response = client
+impl GitHubAgentRepo {
+    /// Parse `owner/repo[/path][@ref]`, also accepting a `https://github.com/` prefix
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let spec = spec
+            .trim()
+            .trim_start_matches("https://github.com/")
+            .trim_start_matches("github.com/")
+            .trim_end_matches('/');
+
+        let (location, git_ref) = match spec.split_once('@') {
+            Some((location, git_ref)) if !git_ref.is_empty() => {
+                (location, Some(git_ref.to_string()))
+            }
+            Some((location, _)) => (location, None),
+            None => (spec, None),
+        };
+
+        let mut parts = location.splitn(3, '/');
+        let owner = parts.next().unwrap_or_default();
+        let repo = parts.next().unwrap_or_default();
+        let path = parts.next().unwrap_or_default().trim_matches('/');
+
+        let valid = |s: &str| {
+            !s.is_empty()
+                && s.chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+        };
+        if !valid(owner) || !valid(repo) {
+            return Err(format!(
+                "Invalid GitHub repository '{}'. Expected owner/repo[/path][@ref]",
+                spec
+            ));
+        }
+
+        Ok(Self {
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+            path: path.to_string(),
+            git_ref,
+        })
+    }
+
+    /// GitHub contents API URL for the agent directory
+    fn contents_url(&self) -> String {
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/contents/{}",
+            self.owner, self.repo, self.path
+        );
+        match (&self.git_ref, reqwest::Url::parse(&url)) {
+            (Some(git_ref), Ok(mut parsed)) => {
+                parsed.query_pairs_mut().append_pair("ref", git_ref);
+                parsed.to_string()
+            }
+            _ => url,
+        }
+    }
+}
+
+/// Hosts `github_token` is sent to; other download URLs are fetched anonymously
+const GITHUB_TOKEN_HOSTS: [&str; 2] = ["api.github.com", "raw.githubusercontent.com"];
+
+fn is_github_host(url: &str) -> bool {
+    reqwest::Url::parse(url).is_ok_and(|url| {
+        url.scheme() == "https" && url.host_str().is_some_and(|host| GITHUB_TOKEN_HOSTS.contains(&host))
+    })
+}
+
+/// GitHub token from `GITHUB_TOKEN`, or from the GitHub CLI when it is logged in
+pub fn github_token() -> Option<String> {
+    if let Ok(token) = std::env::var("GITHUB_TOKEN") {
+        if !token.is_empty() {
+            return Some(token);
+        }
+    }
+    let output = crate::claude_binary::create_command_with_env("gh")
+        .args(["auth", "token"])
+        .output()
+        .ok()?;
+    let token = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !token.is_empty()).then_some(token)
+}
+
+/// Build a GitHub request, authenticating with `github_token` when the URL is on GitHub
+fn github_request(client: &reqwest::Client, url: &str, accept: &str) -> reqwest::RequestBuilder {
+    let request = client
         .get(url)
-        .header("Accept", "application/vnd.github+json")
-        .header("User-Agent", "Claudia-App")
+        .header("Accept", accept)
+        .header("User-Agent", "Claudia-App");
+
+    match github_token().filter(|_| is_github_host(url)) {
+        Some(token) => request.bearer_auth(token),
+        None => request,
+    }
+}
+
+/// Get the configured GitHub repository for agent imports
+#[tauri::command]
+#[specta::specta]
+pub async fn get_github_agent_repo(db: State<'_, AgentDb>) -> Result<String, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    Ok(get_setting(&conn, "github_agent_repo")
+        .unwrap_or_else(|| DEFAULT_GITHUB_AGENT_REPO.to_string()))
+}
+
+/// Set the GitHub repository used for agent imports (empty resets to the default)
+#[tauri::command]
+#[specta::specta]
+pub async fn set_github_agent_repo(db: State<'_, AgentDb>, repo: String) -> Result<(), String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+
+    if repo.trim().is_empty() {
+        return delete_setting(&conn, "github_agent_repo")
+            .map_err(|e| format!("Failed to reset GitHub agent repository: {}", e));
+    }
+
+    // Validate before storing
+    GitHubAgentRepo::parse(&repo)?;
+
+    set_setting(&conn, "github_agent_repo", repo.trim())
+        .map_err(|e| format!("Failed to save GitHub agent repository: {}", e))
+}
+
+/// Fetch list of agents from a GitHub repository
+///
+/// Uses `repo` when given, otherwise the repository configured with `set_github_agent_repo`.
+#[tauri::command]
+#[specta::specta]
+pub async fn fetch_github_agents(
+    db: State<'_, AgentDb>,
+    repo: Option<String>,
+) -> Result<Vec<GitHubAgentFile>, String> {
+    let repo_spec = match repo {
+        Some(repo) if !repo.trim().is_empty() => repo,
+        _ => get_github_agent_repo(db).await?,
+    };
+    let source = GitHubAgentRepo::parse(&repo_spec)?;
+
+    info!(
+        "Fetching agents from GitHub repository {}/{} ({})",
+        source.owner, source.repo, source.path
+    );
+
+    let client = super::proxy::http_client();
+    let url = source.contents_url();
+
+    let response = github_request(&client, &url, "application/vnd.github+json")
         .send()
         .await
         .map_err(|e| format!("Failed to fetch from GitHub: {}", e))?;
@@ -2198,14 +3168,12 @@ pub async fn fetch_github_agents() -> Result<Vec<GitHubAgentFile>, String> {
 
 /// Fetch and preview a specific agent from GitHub
 #[tauri::command]
+#[specta::specta]
 pub async fn fetch_github_agent_content(download_url: String) -> Result<AgentExport, String> {
     info!("Fetching agent content from: {}", download_url);
 
-    let client = reqwest::Client::new();
-    let response = client
-        .get(&download_url)
-        .header("Accept", "application/json")
-        .header("User-Agent", "Claudia-App")
+    let client = super::proxy::http_client();
+    let response = github_request(&client, &download_url, "application/json")
         .send()
         .await
         .map_err(|e| format!("Failed to download agent: {}", e))?;
@@ -2223,22 +3191,12 @@ pub async fn fetch_github_agent_content(download_url: String) -> Result<AgentExp
         .map_err(|e| format!("Failed to read response: {}", e))?;
 
     // Parse and validate the agent data
-    let export_data: AgentExport = serde_json::from_str(&json_text)
-        .map_err(|e| format!("Invalid agent JSON format: {}", e))?;
-
-    // Validate version
-    if export_data.version != 1 {
-        return Err(format!(
-            "Unsupported agent version: {}",
-            export_data.version
-        ));
-    }
-
-    Ok(export_data)
+    AgentExport::parse(&json_text)
 }
 
 /// Import an agent directly from GitHub
 #[tauri::command]
+#[specta::specta]
 pub async fn import_agent_from_github(
     db: State<'_, AgentDb>,
     download_url: String,
@@ -2253,12 +3211,61 @@ pub async fn import_agent_from_github(
         .map_err(|e| format!("Failed to serialize agent data: {}", e))?;
 
     // Import using existing function
-    import_agent(db, json_data).await
+    import_agent(db, json_data, None).await
+}
+
+/// Outcome of importing one agent from GitHub
+#[derive(Debug, Serialize, Deserialize, specta::Type)]
+pub struct GitHubImportResult {
+    pub download_url: String,
+    pub agent: Option<Agent>,
+    pub error: Option<String>,
+}
+
+/// Import several selected agents from GitHub, continuing past individual failures
+#[tauri::command]
+#[specta::specta]
+pub async fn import_agents_from_github(
+    db: State<'_, AgentDb>,
+    download_urls: Vec<String>,
+    on_conflict: Option<String>,
+) -> Result<Vec<GitHubImportResult>, String> {
+    info!("Importing {} agents from GitHub", download_urls.len());
+
+    let mut results = Vec::with_capacity(download_urls.len());
+    for download_url in download_urls {
+        let imported = match fetch_github_agent_content(download_url.clone()).await {
+            Ok(export_data) => match serde_json::to_string(&export_data) {
+                Ok(json_data) => import_agent(db.clone(), json_data, on_conflict.clone()).await,
+                Err(e) => Err(format!("Failed to serialize agent data: {}", e)),
+            },
+            Err(e) => Err(e),
+        };
+
+        match imported {
+            Ok(agent) => results.push(GitHubImportResult {
+                download_url,
+                agent: Some(agent),
+                error: None,
+            }),
+            Err(e) => {
+                warn!("Failed to import agent from {}: {}", download_url, e);
+                results.push(GitHubImportResult {
+                    download_url,
+                    agent: None,
+                    error: Some(e),
+                });
+            }
+        }
+    }
+
+    Ok(results)
 }
 
 /// Load agent session history from JSONL file
 /// Similar to Claude Code's load_session_history, but searches across all project directories
 #[tauri::command]
+#[specta::specta]
 pub async fn load_agent_session_history(
     session_id: String,
 ) -> Result<Vec<serde_json::Value>, String> {
@@ -2322,7 +3329,7 @@ pub async fn load_agent_session_history(
 }
 
 /// Structure for feature execution request
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, specta::Type)]
 pub struct FeatureExecutionRequest {
     pub directory: String,
     pub ticket: String,
@@ -2330,7 +3337,7 @@ pub struct FeatureExecutionRequest {
 }
 
 /// Structure for feature execution result
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, specta::Type)]
 pub struct FeatureExecutionResult {
     pub branch_names: Vec<String>,
     pub run_ids: Vec<i64>,
@@ -2384,6 +3391,7 @@ fn get_claude_terminal_command(app: &AppHandle, claude_binary: &str) -> String {
 
 /// Execute a feature with multiple agents working in parallel
 #[tauri::command]
+#[specta::specta]
 pub async fn execute_feature(
     app: AppHandle,
     request: FeatureExecutionRequest,
@@ -2455,6 +3463,8 @@ pub async fn execute_feature(
     let claude_cmd_for_issue = get_claude_terminal_command(&app, &claude_binary);
     
     // Run Claude to create GitHub issue
+    let issue_span = super::telemetry::Span::start("github.issue.create")
+        .attr("claudia.project.path", request.directory.clone());
     let output = Command::new(&claude_cmd_for_issue)
         .current_dir(&request.directory)
         .args(&[
@@ -2464,7 +3474,8 @@ pub async fn execute_feature(
         ])
         .output()
         .await
-        .map_err(|e| format!("Failed to create GitHub issue: {}", e))?;
+        .map_err(|e| format!("Failed to create GitHub issue: {}", e));
+    let output = issue_span.end_with(output)?;
     
     // Extract issue number from output
     let mut issue_number = None;
@@ -2690,3 +3701,57 @@ pub async fn execute_feature(
         issue_url,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_sends_the_token_to_github() {
+        assert!(is_github_host("https://api.github.com/repos/a/b/contents/"));
+        assert!(is_github_host("https://raw.githubusercontent.com/a/b/main/x.claudia.json"));
+        assert!(!is_github_host("http://api.github.com/repos/a/b"));
+        assert!(!is_github_host("https://api.github.com.evil.example/x"));
+        assert!(!is_github_host("https://example.com/?u=https://api.github.com"));
+
+        let repo = GitHubAgentRepo::parse("owner/repo/agents@feature/a&b=c#d").unwrap();
+        assert_eq!(
+            repo.contents_url(),
+            "https://api.github.com/repos/owner/repo/contents/agents?ref=feature%2Fa%26b%3Dc%23d"
+        );
+    }
+
+    #[test]
+    fn settings_round_trip_with_strings_stored_bare() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute(
+            "CREATE TABLE app_settings (key TEXT PRIMARY KEY, value TEXT NOT NULL)",
+            [],
+        )
+        .unwrap();
+        assert_eq!(get_setting::<u64>(&conn, "grace"), None);
+
+        set_setting(&conn, "grace", &1500u64).unwrap();
+        set_setting(&conn, "path", "/usr/local/bin/claude").unwrap();
+        set_setting(&conn, "days", &vec![1, 2]).unwrap();
+        let raw: String = conn
+            .query_row("SELECT value FROM app_settings WHERE key = 'path'", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(raw, "/usr/local/bin/claude");
+        assert_eq!(get_setting::<u64>(&conn, "grace"), Some(1500));
+        assert_eq!(
+            get_setting::<String>(&conn, "path").as_deref(),
+            Some("/usr/local/bin/claude")
+        );
+        assert_eq!(get_setting::<Vec<i32>>(&conn, "days"), Some(vec![1, 2]));
+        // Text that happens to be JSON still reads back as the string it is
+        set_setting(&conn, "path", "42").unwrap();
+        assert_eq!(get_setting::<String>(&conn, "path").as_deref(), Some("42"));
+        assert_eq!(get_setting::<Vec<i32>>(&conn, "path"), None);
+
+        delete_setting(&conn, "grace").unwrap();
+        assert_eq!(get_setting::<u64>(&conn, "grace"), None);
+    }
+}
@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+
+/// Version of the command API; bump it when a command or its types change incompatibly
+///
+/// The generated TypeScript bindings carry the same value as `COMMAND_API_VERSION`.
+pub const COMMAND_API_VERSION: u32 = 1;
+
+/// Oldest frontend API version this backend still serves
+const MIN_CLIENT_API_VERSION: u32 = 1;
+
+/// What the backend reports during the handshake
+#[derive(Debug, Serialize, Deserialize, Clone, specta::Type)]
+pub struct ApiVersion {
+    pub api_version: u32,
+    pub min_client_api_version: u32,
+    pub app_version: String,
+}
+
+fn check_client_version(client_api_version: u32) -> Result<ApiVersion, String> {
+    if client_api_version < MIN_CLIENT_API_VERSION || client_api_version > COMMAND_API_VERSION {
+        return Err(format!(
+            "The frontend speaks command API version {}, but this backend supports {} to {}; \
+             regenerate the bindings and rebuild",
+            client_api_version, MIN_CLIENT_API_VERSION, COMMAND_API_VERSION
+        ));
+    }
+    Ok(ApiVersion {
+        api_version: COMMAND_API_VERSION,
+        min_client_api_version: MIN_CLIENT_API_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+    })
+}
+
+/// Called by the frontend on startup with the version its bindings were generated for
+#[tauri::command]
+#[specta::specta]
+pub async fn api_handshake(client_api_version: u32) -> Result<ApiVersion, String> {
+    check_client_version(client_api_version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_other_api_versions() {
+        assert!(check_client_version(COMMAND_API_VERSION).is_ok());
+        assert!(check_client_version(COMMAND_API_VERSION + 1).is_err());
+        assert!(check_client_version(0).is_err());
+    }
+}
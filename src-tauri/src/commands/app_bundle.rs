@@ -0,0 +1,648 @@
+use argon2::Argon2;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, KeyInit, OsRng, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use log::{info, warn};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+use tauri::{AppHandle, State};
+
+use super::agents::{export_agent, import_agent, set_setting, AgentDb};
+use super::claude::get_claude_dir;
+use super::mcp::{configured_server_names, mcp_add_json, user_mcp_servers};
+use super::preferences::{read_preferences, write_preference};
+use super::scheduler::compute_next_run;
+use super::secrets::{load_secret_names, read_keychain_value, store_secret, write_keychain_value};
+
+/// Start of every bundle file, followed by the format version
+const MAGIC: &[u8] = b"CLAUDIA-BUNDLE";
+
+/// Bumped when the bundle contents change incompatibly
+const BUNDLE_VERSION: u8 = 1;
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// Shortest passphrase accepted when exporting
+const MIN_PASSPHRASE_LEN: usize = 8;
+
+/// Settings that describe this machine rather than the user's setup; never exported, and
+/// ignored in bundles that contain them
+///
+/// The local servers are here because of their bearer tokens, which each machine issues.
+const MACHINE_SETTINGS: &[&str] = &[
+    "claude_binary_path",
+    "claude_installation_preference",
+    "claudia_mcp_server",
+    "rest_api_server",
+    "usage_report_last_week",
+];
+
+/// Settings holding credentials, such as chat webhook URLs and trace exporter headers;
+/// exported only along with the secrets
+const SECRET_SETTINGS: &[&str] = &["discord_integration", "otlp_export", "slack_integration"];
+
+/// Keychain accounts of integrations, exported with the named secrets when asked for
+const INTEGRATION_ACCOUNTS: &[&str] = &[
+    super::jira::TOKEN_ACCOUNT,
+    super::linear::API_KEY_ACCOUNT,
+    super::email::PASSWORD_ACCOUNT,
+];
+
+/// A schedule, pointing at its agent by name since ids differ between machines
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+struct BundledSchedule {
+    agent_name: String,
+    name: String,
+    cron_expression: String,
+    project_paths: Vec<String>,
+    task: Option<String>,
+    model: Option<String>,
+    enabled: bool,
+}
+
+/// Everything in a bundle; encrypted as a whole
+#[derive(Debug, Serialize, Deserialize, Default, PartialEq)]
+struct AppBundle {
+    app_version: String,
+    created_at: String,
+    /// Agent exports as written by `export_agent`
+    agents: Vec<String>,
+    schedules: Vec<BundledSchedule>,
+    /// `app_settings` rows, including the Jira, Linear and other integration settings; see
+    /// `MACHINE_SETTINGS` and `SECRET_SETTINGS` for what is left out
+    settings: BTreeMap<String, String>,
+    preferences: HashMap<String, JsonValue>,
+    /// `settings*.json` files of `~/.claude` and their backups, by file name
+    claude_settings: BTreeMap<String, String>,
+    /// User-scope MCP servers; their env refers to secrets as `${NAME}`
+    mcp_servers: BTreeMap<String, JsonValue>,
+    /// Named secrets and integration tokens, only when exported with secrets
+    #[serde(default)]
+    secrets: BTreeMap<String, String>,
+}
+
+/// What a bundle contains
+#[derive(Debug, Serialize, Deserialize, Clone, specta::Type)]
+pub struct BundleSummary {
+    pub app_version: String,
+    pub created_at: String,
+    pub agents: usize,
+    pub schedules: usize,
+    pub settings: usize,
+    pub preferences: usize,
+    pub claude_settings_files: usize,
+    pub mcp_servers: usize,
+    pub secrets: usize,
+}
+
+impl From<&AppBundle> for BundleSummary {
+    fn from(bundle: &AppBundle) -> Self {
+        Self {
+            app_version: bundle.app_version.clone(),
+            created_at: bundle.created_at.clone(),
+            agents: bundle.agents.len(),
+            schedules: bundle.schedules.len(),
+            settings: bundle.settings.len(),
+            preferences: bundle.preferences.len(),
+            claude_settings_files: bundle.claude_settings.len(),
+            mcp_servers: bundle.mcp_servers.len(),
+            secrets: bundle.secrets.len(),
+        }
+    }
+}
+
+/// What an import changed
+#[derive(Debug, Serialize, Deserialize, Clone, Default, specta::Type)]
+pub struct BundleImportSummary {
+    pub agents_imported: usize,
+    /// Agents kept as they are because one with the same name exists
+    pub agents_skipped: usize,
+    pub schedules_imported: usize,
+    pub settings_applied: usize,
+    pub preferences_applied: usize,
+    pub claude_settings_files_restored: usize,
+    pub mcp_servers_added: usize,
+    pub secrets_restored: usize,
+    /// Parts that could not be imported, with the reason
+    pub errors: Vec<String>,
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<Key, String> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| format!("Failed to derive bundle key: {}", e))?;
+    Ok(key.into())
+}
+
+/// Header the ciphertext is bound to, so the version can't be swapped
+fn header() -> Vec<u8> {
+    let mut header = MAGIC.to_vec();
+    header.push(BUNDLE_VERSION);
+    header
+}
+
+/// `MAGIC | version | salt | nonce | ChaCha20-Poly1305(zstd(JSON))`
+fn encrypt_bundle(bundle: &AppBundle, passphrase: &str) -> Result<Vec<u8>, String> {
+    let json = serde_json::to_vec(bundle).map_err(|e| format!("Failed to serialize bundle: {}", e))?;
+    let compressed =
+        zstd::encode_all(json.as_slice(), 0).map_err(|e| format!("Failed to compress bundle: {}", e))?;
+
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    OsRng.fill_bytes(&mut salt);
+    OsRng.fill_bytes(&mut nonce);
+    let header = header();
+    let ciphertext = ChaCha20Poly1305::new(&derive_key(passphrase, &salt)?)
+        .encrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: &compressed,
+                aad: &header,
+            },
+        )
+        .map_err(|_| "Failed to encrypt bundle".to_string())?;
+
+    let mut out = header;
+    out.extend_from_slice(&salt);
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&ciphertext);
+    Ok(out)
+}
+
+fn decrypt_bundle(data: &[u8], passphrase: &str) -> Result<AppBundle, String> {
+    let rest = data
+        .strip_prefix(MAGIC)
+        .ok_or("Not a Claudia bundle")?;
+    let (&version, rest) = rest.split_first().ok_or("Bundle is truncated")?;
+    if version > BUNDLE_VERSION {
+        return Err(format!(
+            "Bundle version {} is newer than this version of Claudia supports",
+            version
+        ));
+    }
+    if rest.len() < SALT_LEN + NONCE_LEN {
+        return Err("Bundle is truncated".to_string());
+    }
+    let (salt, rest) = rest.split_at(SALT_LEN);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+    let compressed = ChaCha20Poly1305::new(&derive_key(passphrase, salt)?)
+        .decrypt(
+            Nonce::from_slice(nonce),
+            Payload {
+                msg: ciphertext,
+                aad: &data[..MAGIC.len() + 1],
+            },
+        )
+        .map_err(|_| "Wrong passphrase or damaged bundle".to_string())?;
+    let json = zstd::decode_all(compressed.as_slice())
+        .map_err(|e| format!("Failed to decompress bundle: {}", e))?;
+    serde_json::from_slice(&json).map_err(|e| format!("Invalid bundle contents: {}", e))
+}
+
+/// `app_settings` rows to export, leaving out machine settings and, unless
+/// `include_secrets` is set, settings with credentials
+fn collect_settings(
+    conn: &Connection,
+    include_secrets: bool,
+) -> Result<BTreeMap<String, String>, String> {
+    let mut stmt = conn
+        .prepare("SELECT key, value FROM app_settings")
+        .map_err(|e| e.to_string())?;
+    let mut settings = stmt
+        .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<BTreeMap<_, _>, _>>()
+        .map_err(|e| e.to_string())?;
+    settings.retain(|key, _| {
+        !MACHINE_SETTINGS.contains(&key.as_str())
+            && (include_secrets || !SECRET_SETTINGS.contains(&key.as_str()))
+    });
+    Ok(settings)
+}
+
+fn collect_database(
+    conn: &Connection,
+    bundle: &mut AppBundle,
+    include_secrets: bool,
+) -> Result<Vec<i64>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT a.name, s.name, s.cron_expression, s.project_paths, s.task, s.model, s.enabled
+             FROM agent_schedules s JOIN agents a ON a.id = s.agent_id
+             ORDER BY s.id",
+        )
+        .map_err(|e| e.to_string())?;
+    bundle.schedules = stmt
+        .query_map([], |row| {
+            let project_paths: String = row.get(3)?;
+            Ok(BundledSchedule {
+                agent_name: row.get(0)?,
+                name: row.get(1)?,
+                cron_expression: row.get(2)?,
+                project_paths: serde_json::from_str(&project_paths).unwrap_or_default(),
+                task: row.get(4)?,
+                model: row.get(5)?,
+                enabled: row.get(6)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    bundle.settings = collect_settings(conn, include_secrets)?;
+
+    bundle.preferences = read_preferences(conn, "")?;
+
+    let mut stmt = conn
+        .prepare("SELECT id FROM agents ORDER BY id")
+        .map_err(|e| e.to_string())?;
+    let agent_ids = stmt
+        .query_map([], |row| row.get(0))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(agent_ids)
+}
+
+fn collect_secrets() -> Result<BTreeMap<String, String>, String> {
+    let mut secrets = BTreeMap::new();
+    let names = load_secret_names()?;
+    for account in names.iter().map(String::as_str).chain(INTEGRATION_ACCOUNTS.iter().copied()) {
+        if let Some(value) = read_keychain_value(account)? {
+            secrets.insert(account.to_string(), value);
+        }
+    }
+    Ok(secrets)
+}
+
+/// Write the `~/.claude` settings files of a bundle, returning how many were written
+///
+/// Current settings files are backed up before being replaced; bundled backups are only
+/// written when missing.
+fn restore_claude_settings(
+    files: &BTreeMap<String, String>,
+    claude_dir: &Path,
+) -> Result<usize, String> {
+    let mut restored = 0;
+    for (name, content) in files {
+        if !name.starts_with("settings") || name.contains(['/', '\\']) || name.contains("..") {
+            return Err(format!("Bundle contains an unsafe settings file name: {}", name));
+        }
+        let target = claude_dir.join(name);
+        let is_backup = name.ends_with(".bak");
+        if is_backup && target.exists() {
+            continue;
+        }
+        if !is_backup {
+            if fs::read_to_string(&target).is_ok_and(|current| &current == content) {
+                continue;
+            }
+            super::settings_file::backup_settings(&target)?;
+        }
+        fs::create_dir_all(claude_dir)
+            .map_err(|e| format!("Failed to create {}: {}", claude_dir.display(), e))?;
+        fs::write(&target, content)
+            .map_err(|e| format!("Failed to write {}: {}", target.display(), e))?;
+        restored += 1;
+    }
+    Ok(restored)
+}
+
+/// Import bundled schedules for agents that exist by name, skipping ones already there
+fn import_schedules(
+    conn: &Connection,
+    schedules: &[BundledSchedule],
+    agent_ids: &HashMap<String, i64>,
+    summary: &mut BundleImportSummary,
+) {
+    for schedule in schedules {
+        let Some(&agent_id) = agent_ids.get(&schedule.agent_name) else {
+            summary.errors.push(format!(
+                "Schedule '{}': agent '{}' is not in the bundle",
+                schedule.name, schedule.agent_name
+            ));
+            continue;
+        };
+        let exists = conn
+            .query_row(
+                "SELECT COUNT(*) FROM agent_schedules WHERE agent_id = ?1 AND name = ?2",
+                params![agent_id, schedule.name],
+                |row| row.get::<_, i64>(0),
+            )
+            .unwrap_or(0)
+            > 0;
+        if exists {
+            continue;
+        }
+        let next_run_at = if schedule.enabled {
+            match compute_next_run(&schedule.cron_expression, chrono::Utc::now()) {
+                Ok(next) => next,
+                Err(e) => {
+                    summary.errors.push(format!("Schedule '{}': {}", schedule.name, e));
+                    continue;
+                }
+            }
+        } else {
+            None
+        };
+        let result = conn.execute(
+            "INSERT INTO agent_schedules (agent_id, name, cron_expression, project_paths, task, model, enabled, next_run_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                agent_id,
+                schedule.name,
+                schedule.cron_expression,
+                serde_json::to_string(&schedule.project_paths).unwrap_or_else(|_| "[]".to_string()),
+                schedule.task,
+                schedule.model,
+                schedule.enabled,
+                next_run_at
+            ],
+        );
+        match result {
+            Ok(_) => summary.schedules_imported += 1,
+            Err(e) => summary.errors.push(format!("Schedule '{}': {}", schedule.name, e)),
+        }
+    }
+}
+
+fn validate_passphrase(passphrase: &str) -> Result<(), String> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+        return Err(format!(
+            "The passphrase must be at least {} characters",
+            MIN_PASSPHRASE_LEN
+        ));
+    }
+    Ok(())
+}
+
+/// Export agents, schedules, settings, preferences, MCP servers and integration
+/// settings into one bundle encrypted with `passphrase`
+///
+/// Secrets stay in the keychain unless `include_secrets` is set, and so do settings that
+/// hold credentials, like chat webhook URLs.
+#[tauri::command]
+#[specta::specta]
+pub async fn export_app_bundle(
+    db: State<'_, AgentDb>,
+    bundle_path: String,
+    passphrase: String,
+    include_secrets: Option<bool>,
+) -> Result<BundleSummary, String> {
+    validate_passphrase(&passphrase)?;
+    let mut bundle = AppBundle {
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+        ..Default::default()
+    };
+    let include_secrets = include_secrets.unwrap_or(false);
+    let agent_ids = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        collect_database(&conn, &mut bundle, include_secrets)?
+    };
+    for id in agent_ids {
+        bundle.agents.push(export_agent(db.clone(), id).await?);
+    }
+
+    let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
+    for path in super::backup::settings_files(&claude_dir) {
+        let (Some(name), Ok(content)) = (
+            path.file_name().and_then(|n| n.to_str()),
+            fs::read_to_string(&path),
+        ) else {
+            continue;
+        };
+        bundle.claude_settings.insert(name.to_string(), content);
+    }
+    bundle.mcp_servers = user_mcp_servers()?.into_iter().collect();
+    if include_secrets {
+        bundle.secrets = tokio::task::spawn_blocking(collect_secrets)
+            .await
+            .map_err(|e| e.to_string())??;
+    }
+
+    let summary = BundleSummary::from(&bundle);
+    let data = tokio::task::spawn_blocking(move || encrypt_bundle(&bundle, &passphrase))
+        .await
+        .map_err(|e| e.to_string())??;
+    fs::write(&bundle_path, data).map_err(|e| format!("Failed to write bundle: {}", e))?;
+    info!(
+        "Exported app bundle {} ({} agents, {} schedules, {} secrets)",
+        bundle_path, summary.agents, summary.schedules, summary.secrets
+    );
+    Ok(summary)
+}
+
+async fn read_bundle(bundle_path: String, passphrase: String) -> Result<AppBundle, String> {
+    let data = fs::read(&bundle_path).map_err(|e| format!("Failed to read bundle: {}", e))?;
+    tokio::task::spawn_blocking(move || decrypt_bundle(&data, &passphrase))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Decrypt a bundle and report what it contains without importing it
+#[tauri::command]
+#[specta::specta]
+pub async fn inspect_app_bundle(
+    bundle_path: String,
+    passphrase: String,
+) -> Result<BundleSummary, String> {
+    let bundle = read_bundle(bundle_path, passphrase).await?;
+    Ok(BundleSummary::from(&bundle))
+}
+
+/// Import a bundle into this machine
+///
+/// Agents and schedules that already exist by name are kept; settings, preferences and
+/// `~/.claude` settings files are replaced by the bundled ones; MCP servers are added when
+/// missing. A part that fails is reported in the summary and the rest is still imported.
+#[tauri::command]
+#[specta::specta]
+pub async fn import_app_bundle(
+    app: AppHandle,
+    db: State<'_, AgentDb>,
+    bundle_path: String,
+    passphrase: String,
+) -> Result<BundleImportSummary, String> {
+    let bundle = read_bundle(bundle_path.clone(), passphrase).await?;
+    let mut summary = BundleImportSummary::default();
+
+    let mut agent_ids = HashMap::new();
+    for json in &bundle.agents {
+        let existed = {
+            let conn = db.0.lock().map_err(|e| e.to_string())?;
+            let name = serde_json::from_str::<JsonValue>(json)
+                .ok()
+                .and_then(|export| export["agent"]["name"].as_str().map(str::to_string))
+                .unwrap_or_default();
+            conn.query_row("SELECT COUNT(*) FROM agents WHERE name = ?1", params![name], |row| {
+                row.get::<_, i64>(0)
+            })
+            .unwrap_or(0)
+                > 0
+        };
+        match import_agent(db.clone(), json.clone(), Some("skip".to_string())).await {
+            Ok(agent) => {
+                if existed {
+                    summary.agents_skipped += 1;
+                } else {
+                    summary.agents_imported += 1;
+                }
+                if let Some(id) = agent.id {
+                    agent_ids.insert(agent.name, id);
+                }
+            }
+            Err(e) => summary.errors.push(format!("Agent: {}", e)),
+        }
+    }
+
+    {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        import_schedules(&conn, &bundle.schedules, &agent_ids, &mut summary);
+        let settings = bundle
+            .settings
+            .iter()
+            .filter(|(key, _)| !MACHINE_SETTINGS.contains(&key.as_str()));
+        for (key, value) in settings {
+            // Values are the stored text, which `set_setting` writes back unchanged
+            match set_setting(&conn, key, value) {
+                Ok(_) => summary.settings_applied += 1,
+                Err(e) => summary.errors.push(format!("Setting {}: {}", key, e)),
+            }
+        }
+        // Settings kept in memory pick up the imported values
+        super::proxy::init_proxy_settings(&conn);
+        super::telemetry::init_telemetry_settings(&conn);
+        super::processes::init_kill_grace_period(&conn);
+        for (key, value) in &bundle.preferences {
+            match write_preference(&conn, key, &value.to_string()) {
+                Ok(_) => summary.preferences_applied += 1,
+                Err(e) => summary.errors.push(format!("Preference {}: {}", key, e)),
+            }
+        }
+    }
+
+    let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
+    match restore_claude_settings(&bundle.claude_settings, &claude_dir) {
+        Ok(restored) => summary.claude_settings_files_restored = restored,
+        Err(e) => summary.errors.push(e),
+    }
+
+    // Secrets first, so MCP servers referring to them resolve once added
+    let secrets = bundle.secrets.clone();
+    let (restored, errors) = tokio::task::spawn_blocking(move || {
+        let mut restored = 0;
+        let mut errors = Vec::new();
+        for (account, value) in &secrets {
+            let result = if INTEGRATION_ACCOUNTS.contains(&account.as_str()) {
+                write_keychain_value(account, value)
+            } else {
+                store_secret(account, value)
+            };
+            match result {
+                Ok(()) => restored += 1,
+                Err(e) => errors.push(e),
+            }
+        }
+        (restored, errors)
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    summary.secrets_restored = restored;
+    summary.errors.extend(errors);
+
+    if !bundle.mcp_servers.is_empty() {
+        let existing = configured_server_names(&app).await;
+        for (name, config) in &bundle.mcp_servers {
+            if existing.contains(name) {
+                continue;
+            }
+            match mcp_add_json(app.clone(), name.clone(), config.to_string(), "user".to_string())
+                .await
+            {
+                Ok(result) if result.success => summary.mcp_servers_added += 1,
+                Ok(result) => summary
+                    .errors
+                    .push(format!("MCP server {}: {}", name, result.message)),
+                Err(e) => summary.errors.push(format!("MCP server {}: {}", name, e)),
+            }
+        }
+    }
+
+    if !summary.errors.is_empty() {
+        warn!(
+            "Imported app bundle {} with {} errors",
+            bundle_path,
+            summary.errors.len()
+        );
+    }
+    info!(
+        "Imported app bundle {} ({} agents, {} schedules, {} settings)",
+        bundle_path, summary.agents_imported, summary.schedules_imported, summary.settings_applied
+    );
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundle_round_trips_only_with_the_passphrase() {
+        let bundle = AppBundle {
+            app_version: "1.0.0".to_string(),
+            agents: vec!["{\"agent\":{}}".to_string()],
+            settings: BTreeMap::from([("proxy_settings".to_string(), "{}".to_string())]),
+            secrets: BTreeMap::from([("API_KEY".to_string(), "secret".to_string())]),
+            ..Default::default()
+        };
+        let data = encrypt_bundle(&bundle, "correct horse").unwrap();
+        assert!(data.starts_with(MAGIC));
+        assert!(!data.windows(6).any(|w| w == b"secret"));
+
+        assert_eq!(decrypt_bundle(&data, "correct horse").unwrap(), bundle);
+        assert!(decrypt_bundle(&data, "wrong horse").is_err());
+        assert!(decrypt_bundle(b"not a bundle", "correct horse").is_err());
+    }
+
+    #[test]
+    fn settings_restore_rejects_paths_and_keeps_existing_backups() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("settings.json.1.bak"), "local").unwrap();
+        let files = BTreeMap::from([
+            ("settings.json".to_string(), "{\"model\":\"opus\"}".to_string()),
+            ("settings.json.1.bak".to_string(), "bundled".to_string()),
+        ]);
+        assert_eq!(restore_claude_settings(&files, dir.path()).unwrap(), 1);
+        assert_eq!(
+            fs::read_to_string(dir.path().join("settings.json.1.bak")).unwrap(),
+            "local"
+        );
+
+        let unsafe_files = BTreeMap::from([("settings/../x".to_string(), String::new())]);
+        assert!(restore_claude_settings(&unsafe_files, dir.path()).is_err());
+    }
+
+    #[test]
+    fn credentials_are_exported_only_with_secrets() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE app_settings (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+             INSERT INTO app_settings VALUES
+               ('proxy_settings', '{}'),
+               ('rest_api_server', '{\"token\":\"t\"}'),
+               ('slack_integration', '{\"webhook_url\":\"https://hooks.slack.com/x\"}');",
+        )
+        .unwrap();
+        let keys = |include_secrets| -> Vec<String> {
+            collect_settings(&conn, include_secrets).unwrap().into_keys().collect()
+        };
+        assert_eq!(keys(false), vec!["proxy_settings"]);
+        assert_eq!(keys(true), vec!["proxy_settings", "slack_integration"]);
+    }
+}
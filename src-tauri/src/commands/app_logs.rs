@@ -0,0 +1,277 @@
+use log::{LevelFilter, Log, Metadata, Record};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Mutex;
+
+/// Name of the file being written; rotated files get `.1`, `.2`, … appended
+const LOG_FILE: &str = "claudia.log";
+
+/// Size at which the log file is rotated
+const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Rotated files kept besides the current one
+const MAX_ROTATED_FILES: usize = 4;
+
+/// Entries returned by `get_app_logs` when no limit is given
+const DEFAULT_LOG_LIMIT: usize = 1000;
+
+/// One line of the log file
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct LogEntry {
+    /// RFC 3339
+    pub timestamp: String,
+    pub level: String,
+    /// Module the entry came from, e.g. `claudia_lib::commands::scheduler`
+    pub target: String,
+    pub message: String,
+}
+
+pub fn log_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".claude").join("claudia").join("logs"))
+}
+
+fn rotated_path(dir: &Path, index: usize) -> PathBuf {
+    dir.join(format!("{}.{}", LOG_FILE, index))
+}
+
+struct RotatingFile {
+    dir: PathBuf,
+    file: File,
+    size: u64,
+}
+
+impl RotatingFile {
+    fn open(dir: PathBuf) -> std::io::Result<Self> {
+        fs::create_dir_all(&dir)?;
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dir.join(LOG_FILE))?;
+        let size = file.metadata()?.len();
+        Ok(Self { dir, file, size })
+    }
+
+    /// Shift `claudia.log.N` to `N + 1`, dropping the oldest, and start a new file
+    fn rotate(&mut self) -> std::io::Result<()> {
+        let _ = fs::remove_file(rotated_path(&self.dir, MAX_ROTATED_FILES));
+        for index in (1..MAX_ROTATED_FILES).rev() {
+            let _ = fs::rename(rotated_path(&self.dir, index), rotated_path(&self.dir, index + 1));
+        }
+        fs::rename(self.dir.join(LOG_FILE), rotated_path(&self.dir, 1))?;
+        *self = Self::open(self.dir.clone())?;
+        Ok(())
+    }
+
+    fn write_line(&mut self, line: &[u8]) {
+        if self.size + line.len() as u64 > MAX_LOG_BYTES && self.size > 0 {
+            if let Err(e) = self.rotate() {
+                eprintln!("Failed to rotate log file: {}", e);
+            }
+        }
+        if self.file.write_all(line).is_ok() {
+            self.size += line.len() as u64;
+        }
+    }
+}
+
+/// Logs to stderr as configured by `RUST_LOG`, and at info level and above to the log file
+struct AppLogger {
+    stderr: env_logger::Logger,
+    file: Option<Mutex<RotatingFile>>,
+}
+
+impl AppLogger {
+    fn file_enabled(&self, metadata: &Metadata) -> bool {
+        self.file.is_some() && metadata.level() <= LevelFilter::Info
+    }
+}
+
+impl Log for AppLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.stderr.enabled(metadata) || self.file_enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if self.stderr.matches(record) {
+            self.stderr.log(record);
+        }
+        let Some(file) = self.file.as_ref().filter(|_| self.file_enabled(record.metadata())) else {
+            return;
+        };
+        let entry = LogEntry {
+            timestamp: chrono::Local::now().to_rfc3339(),
+            level: record.level().to_string(),
+            target: record.target().to_string(),
+            message: record.args().to_string(),
+        };
+        let Ok(mut line) = serde_json::to_vec(&entry) else {
+            return;
+        };
+        line.push(b'\n');
+        if let Ok(mut file) = file.lock() {
+            file.write_line(&line);
+        }
+    }
+
+    fn flush(&self) {
+        self.stderr.flush();
+        if let Some(Ok(mut file)) = self.file.as_ref().map(|file| file.lock()) {
+            let _ = file.file.flush();
+        }
+    }
+}
+
+/// Install the logger; call once, before anything logs
+///
+/// If the log directory can't be created, logging continues on stderr only.
+pub fn init_logging() {
+    let stderr = env_logger::Builder::from_default_env().build();
+    let file = match log_dir().map(RotatingFile::open) {
+        Some(Ok(file)) => Some(Mutex::new(file)),
+        Some(Err(e)) => {
+            eprintln!("Logging to stderr only, failed to open the log file: {}", e);
+            None
+        }
+        None => None,
+    };
+    let max_level = if file.is_some() {
+        stderr.filter().max(LevelFilter::Info)
+    } else {
+        stderr.filter()
+    };
+    if log::set_boxed_logger(Box::new(AppLogger { stderr, file })).is_ok() {
+        log::set_max_level(max_level);
+    }
+}
+
+/// Log files oldest first, so entries come out in the order they were written
+fn log_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = (1..=MAX_ROTATED_FILES)
+        .rev()
+        .map(|index| rotated_path(dir, index))
+        .collect();
+    files.push(dir.join(LOG_FILE));
+    files.retain(|path| path.exists());
+    files
+}
+
+/// Entries at `min_level` or more severe, written at or after `since`
+fn read_logs(
+    dir: &Path,
+    min_level: LevelFilter,
+    since: Option<chrono::DateTime<chrono::FixedOffset>>,
+    limit: usize,
+) -> Vec<LogEntry> {
+    let mut entries = Vec::new();
+    for path in log_files(dir) {
+        let Ok(file) = File::open(&path) else {
+            continue;
+        };
+        for line in BufReader::new(file).lines().map_while(Result::ok) {
+            let Ok(entry) = serde_json::from_str::<LogEntry>(&line) else {
+                continue;
+            };
+            let level_matches = log::Level::from_str(&entry.level).is_ok_and(|level| level <= min_level);
+            let recent = since.is_none_or(|since| {
+                chrono::DateTime::parse_from_rfc3339(&entry.timestamp).is_ok_and(|time| time >= since)
+            });
+            if level_matches && recent {
+                entries.push(entry);
+            }
+        }
+    }
+    // The newest entries matter most when the limit cuts
+    let skip = entries.len().saturating_sub(limit);
+    entries.split_off(skip)
+}
+
+/// Read the app's log entries, oldest first
+///
+/// `level` is the least severe level to include ("error", "warn", "info"; default "info"),
+/// `since` an RFC 3339 timestamp, and `limit` caps the result to the newest entries.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_app_logs(
+    level: Option<String>,
+    since: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<LogEntry>, String> {
+    let min_level = match level.as_deref() {
+        Some(level) => LevelFilter::from_str(level).map_err(|_| format!("Unknown log level '{}'", level))?,
+        None => LevelFilter::Info,
+    };
+    let since = since
+        .as_deref()
+        .map(chrono::DateTime::parse_from_rfc3339)
+        .transpose()
+        .map_err(|e| format!("Invalid since timestamp: {}", e))?;
+    let dir = log_dir().ok_or("Could not find home directory")?;
+    let limit = limit.unwrap_or(DEFAULT_LOG_LIMIT);
+    tokio::task::spawn_blocking(move || read_logs(&dir, min_level, since, limit))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Open the log directory in the system file manager
+#[tauri::command]
+#[specta::specta]
+pub async fn open_log_directory() -> Result<String, String> {
+    let dir = log_dir().ok_or("Could not find home directory")?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let program = if cfg!(target_os = "macos") {
+        "open"
+    } else if cfg!(windows) {
+        "explorer"
+    } else {
+        "xdg-open"
+    };
+    std::process::Command::new(program)
+        .arg(&dir)
+        .spawn()
+        .map_err(|e| format!("Failed to open {}: {}", dir.display(), e))?;
+    Ok(dir.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotates_and_reads_across_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut file = RotatingFile::open(dir.path().to_path_buf()).unwrap();
+        let line = |level: &str, message: &str| {
+            let entry = LogEntry {
+                timestamp: "2026-01-01T10:00:00+00:00".to_string(),
+                level: level.to_string(),
+                target: "test".to_string(),
+                message: message.to_string(),
+            };
+            format!("{}\n", serde_json::to_string(&entry).unwrap())
+        };
+        file.write_line(line("INFO", "first").as_bytes());
+        file.rotate().unwrap();
+        file.write_line(line("ERROR", "second").as_bytes());
+        file.write_line(line("WARN", "third").as_bytes());
+        assert!(rotated_path(dir.path(), 1).exists());
+
+        let messages = |entries: Vec<LogEntry>| -> Vec<String> {
+            entries.into_iter().map(|e| e.message).collect()
+        };
+        assert_eq!(
+            messages(read_logs(dir.path(), LevelFilter::Info, None, 10)),
+            vec!["first", "second", "third"]
+        );
+        assert_eq!(
+            messages(read_logs(dir.path(), LevelFilter::Warn, None, 10)),
+            vec!["second", "third"]
+        );
+        assert_eq!(
+            messages(read_logs(dir.path(), LevelFilter::Info, None, 1)),
+            vec!["third"]
+        );
+    }
+}
@@ -0,0 +1,399 @@
+use log::{error, info, warn};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use super::agents::{get_agent_run, get_session_output, AgentDb};
+use crate::process::ProcessRegistryState;
+
+/// Tools whose input names a file they create or change
+const FILE_WRITING_TOOLS: &[&str] = &["Write", "Edit", "MultiEdit", "NotebookEdit"];
+
+/// A file created, modified or deleted by an agent run
+#[derive(Debug, Serialize, Deserialize, Clone, specta::Type)]
+pub struct RunArtifact {
+    pub id: Option<i64>,
+    pub run_id: i64,
+    pub path: String, // Relative to the project when inside it
+    pub change_type: String, // 'added', 'modified', 'deleted', 'renamed'
+    pub source: String,      // 'transcript', 'git' or 'both'
+    pub size: Option<i64>,
+    pub sha256: Option<String>,
+    pub collected_at: String,
+    /// Unified diff captured at collection time, only loaded on request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub diff: Option<String>,
+}
+
+/// Files inside the project reported by `git status`, as (path, change type)
+///
+/// Returns nothing when the project isn't a git repository.
+pub fn git_changed_files(project_path: &str) -> Vec<(String, String)> {
+    let output = match Command::new("git")
+        .args(["status", "--porcelain=v1", "--untracked-files=all"])
+        .current_dir(project_path)
+        .output()
+    {
+        Ok(output) if output.status.success() => output,
+        _ => return Vec::new(),
+    };
+
+    // Porcelain paths are relative to the repository root, which may be above the project
+    let prefix = Command::new("git")
+        .args(["rev-parse", "--show-prefix"])
+        .current_dir(project_path)
+        .output()
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+        .unwrap_or_default();
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| line.len() > 3)
+        .filter_map(|line| {
+            let (status, path) = line.split_at(2);
+            let path = path.trim();
+            // Renames are reported as "old -> new"
+            let path = path.rsplit(" -> ").next().unwrap_or(path);
+            let path = path.trim_matches('"').strip_prefix(prefix.as_str())?.to_string();
+
+            let change_type = if status == "??" || status.contains('A') {
+                "added"
+            } else if status.contains('D') {
+                "deleted"
+            } else if status.contains('R') {
+                "renamed"
+            } else {
+                "modified"
+            };
+            Some((path, change_type.to_string()))
+        })
+        .collect()
+}
+
+/// Files named by file-writing tool calls in a stream-json transcript
+fn transcript_files(jsonl_content: &str) -> Vec<String> {
+    let mut files = Vec::new();
+
+    for line in jsonl_content.lines() {
+        let Ok(json) = serde_json::from_str::<JsonValue>(line) else {
+            continue;
+        };
+        if json.get("type").and_then(|t| t.as_str()) != Some("assistant") {
+            continue;
+        }
+        let Some(content) = json.pointer("/message/content").and_then(|c| c.as_array()) else {
+            continue;
+        };
+
+        for item in content {
+            if item.get("type").and_then(|t| t.as_str()) != Some("tool_use") {
+                continue;
+            }
+            let name = item.get("name").and_then(|n| n.as_str()).unwrap_or_default();
+            if !FILE_WRITING_TOOLS.contains(&name) {
+                continue;
+            }
+            let path = item
+                .pointer("/input/file_path")
+                .or_else(|| item.pointer("/input/notebook_path"))
+                .and_then(|p| p.as_str());
+            if let Some(path) = path {
+                if !files.iter().any(|f| f == path) {
+                    files.push(path.to_string());
+                }
+            }
+        }
+    }
+
+    files
+}
+
+/// Express a path relative to the project when it lies inside it
+fn relative_to_project(project_path: &str, path: &str) -> String {
+    Path::new(path)
+        .strip_prefix(project_path)
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|_| path.to_string())
+}
+
+fn file_fingerprint(path: &Path) -> (Option<i64>, Option<String>) {
+    match std::fs::read(path) {
+        Ok(content) => {
+            let hash = Sha256::digest(&content);
+            let hex: String = hash.iter().map(|b| format!("{:02x}", b)).collect();
+            (Some(content.len() as i64), Some(hex))
+        }
+        Err(_) => (None, None),
+    }
+}
+
+/// Load a run's artifact manifest, optionally with the captured diffs
+pub fn load_artifacts(conn: &Connection, run_id: i64, include_diffs: bool) -> Result<Vec<RunArtifact>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, run_id, path, change_type, source, size, sha256, collected_at, diff FROM agent_run_artifacts WHERE run_id = ?1 ORDER BY path",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let artifacts = stmt
+        .query_map(params![run_id], |row| {
+            Ok(RunArtifact {
+                id: Some(row.get(0)?),
+                run_id: row.get(1)?,
+                path: row.get(2)?,
+                change_type: row.get(3)?,
+                source: row.get(4)?,
+                size: row.get(5)?,
+                sha256: row.get(6)?,
+                collected_at: row.get(7)?,
+                diff: if include_diffs { row.get(8)? } else { None },
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(artifacts)
+}
+
+/// Unified diff of one file against HEAD (or against nothing for new files)
+fn git_diff(project: &Path, path: &str, change_type: &str) -> Option<String> {
+    let args: Vec<&str> = if change_type == "added" {
+        vec!["diff", "--no-index", "--", "/dev/null", path]
+    } else {
+        vec!["diff", "HEAD", "--", path]
+    };
+
+    let output = Command::new("git")
+        .args(&args)
+        .current_dir(project)
+        .output()
+        .ok()?;
+    let diff = String::from_utf8_lossy(&output.stdout).to_string();
+    if diff.is_empty() {
+        None
+    } else {
+        Some(diff)
+    }
+}
+
+/// Build and store the artifact manifest of a run
+async fn collect_artifacts(app: &AppHandle, run_id: i64) -> Result<Vec<RunArtifact>, String> {
+    let run = get_agent_run(app.state::<AgentDb>(), run_id).await?;
+    let baseline: Vec<(String, String)> = {
+        let db = app.state::<AgentDb>();
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        conn.query_row(
+            "SELECT git_baseline FROM agent_runs WHERE id = ?1",
+            params![run_id],
+            |row| row.get::<_, Option<String>>(0),
+        )
+        .ok()
+        .flatten()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+    };
+
+    let output = get_session_output(
+        app.state::<AgentDb>(),
+        app.state::<ProcessRegistryState>(),
+        run_id,
+    )
+    .await
+    .unwrap_or_default();
+
+    // path -> (change type, source)
+    let mut changes: BTreeMap<String, (String, &str)> = BTreeMap::new();
+    for path in transcript_files(&output) {
+        let relative = relative_to_project(&run.project_path, &path);
+        let exists = Path::new(&run.project_path).join(&relative).exists();
+        let change_type = if exists { "modified" } else { "deleted" };
+        changes.insert(relative, (change_type.to_string(), "transcript"));
+    }
+    // Files that were already dirty before the run started are only kept if the transcript names them
+    for (path, change_type) in git_changed_files(&run.project_path) {
+        if baseline.contains(&(path.clone(), change_type.clone())) {
+            continue;
+        }
+        match changes.get_mut(&path) {
+            Some(entry) => *entry = (change_type, "both"),
+            None => {
+                changes.insert(path, (change_type, "git"));
+            }
+        }
+    }
+
+    let collected_at = chrono::Utc::now().to_rfc3339();
+    let artifacts = {
+        let db = app.state::<AgentDb>();
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+        tx.execute(
+            "DELETE FROM agent_run_artifacts WHERE run_id = ?1",
+            params![run_id],
+        )
+        .map_err(|e| e.to_string())?;
+
+        for (path, (change_type, source)) in &changes {
+            let (size, sha256) = file_fingerprint(&Path::new(&run.project_path).join(path));
+            let diff = git_diff(Path::new(&run.project_path), path, change_type);
+            tx.execute(
+                "INSERT INTO agent_run_artifacts (run_id, path, change_type, source, size, sha256, collected_at, diff) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![run_id, path, change_type, source, size, sha256, collected_at, diff],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+        tx.commit().map_err(|e| e.to_string())?;
+        load_artifacts(&conn, run_id, false)?
+    };
+
+    info!("Collected {} artifacts for run {}", artifacts.len(), run_id);
+    Ok(artifacts)
+}
+
+/// Collect artifacts in the background once a run has finished
+pub fn spawn_artifact_collection(app: AppHandle, run_id: i64) {
+    tauri::async_runtime::spawn(async move {
+        match collect_artifacts(&app, run_id).await {
+            Ok(artifacts) => {
+                let _ = app.emit(&format!("agent-artifacts:{}", run_id), &artifacts);
+            }
+            Err(e) => error!("Failed to collect artifacts for run {}: {}", run_id, e),
+        }
+    });
+}
+
+/// Get the stored artifact manifest of a run
+#[tauri::command]
+#[specta::specta]
+pub async fn get_run_artifacts(
+    db: State<'_, AgentDb>,
+    run_id: i64,
+) -> Result<Vec<RunArtifact>, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    load_artifacts(&conn, run_id, false)
+}
+
+/// Re-collect the artifact manifest of a run
+#[tauri::command]
+#[specta::specta]
+pub async fn collect_run_artifacts(app: AppHandle, run_id: i64) -> Result<Vec<RunArtifact>, String> {
+    collect_artifacts(&app, run_id).await
+}
+
+/// Export a run's artifacts as a `zip` archive or a git `patch`
+#[tauri::command]
+#[specta::specta]
+pub async fn export_run_artifacts(
+    db: State<'_, AgentDb>,
+    run_id: i64,
+    format: String,
+    output_path: String,
+) -> Result<String, String> {
+    let run = get_agent_run(db.clone(), run_id).await?;
+    let artifacts = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        load_artifacts(&conn, run_id, false)?
+    };
+    if artifacts.is_empty() {
+        return Err("This run has no collected artifacts".to_string());
+    }
+
+    let project = PathBuf::from(&run.project_path);
+    // Only files inside the project can be exported
+    let inside: Vec<&RunArtifact> = artifacts
+        .iter()
+        .filter(|a| Path::new(&a.path).is_relative())
+        .collect();
+    let skipped = artifacts.len() - inside.len();
+    if skipped > 0 {
+        warn!("Skipping {} artifacts outside the project for run {}", skipped, run_id);
+    }
+
+    match format.as_str() {
+        "zip" => write_zip(&project, &inside, &artifacts, &output_path)?,
+        "patch" => write_patch(&project, &inside, &output_path)?,
+        other => return Err(format!("Unsupported export format: {}", other)),
+    }
+
+    info!("Exported artifacts of run {} to {}", run_id, output_path);
+    Ok(output_path)
+}
+
+fn write_zip(
+    project: &Path,
+    artifacts: &[&RunArtifact],
+    manifest: &[RunArtifact],
+    output_path: &str,
+) -> Result<(), String> {
+    let file = std::fs::File::create(output_path)
+        .map_err(|e| format!("Failed to create archive: {}", e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+
+    for artifact in artifacts.iter().filter(|a| a.change_type != "deleted") {
+        let Ok(content) = std::fs::read(project.join(&artifact.path)) else {
+            warn!("Artifact {} no longer exists, skipping", artifact.path);
+            continue;
+        };
+        zip.start_file(artifact.path.replace('\\', "/"), options)
+            .map_err(|e| format!("Failed to add {} to archive: {}", artifact.path, e))?;
+        zip.write_all(&content)
+            .map_err(|e| format!("Failed to add {} to archive: {}", artifact.path, e))?;
+    }
+
+    let manifest_json = serde_json::to_string_pretty(manifest).map_err(|e| e.to_string())?;
+    zip.start_file("manifest.json", options)
+        .map_err(|e| format!("Failed to write manifest: {}", e))?;
+    zip.write_all(manifest_json.as_bytes())
+        .map_err(|e| format!("Failed to write manifest: {}", e))?;
+
+    zip.finish()
+        .map_err(|e| format!("Failed to finish archive: {}", e))?;
+    Ok(())
+}
+
+fn write_patch(project: &Path, artifacts: &[&RunArtifact], output_path: &str) -> Result<(), String> {
+    let is_repo = Command::new("git")
+        .args(["rev-parse", "--is-inside-work-tree"])
+        .current_dir(project)
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false);
+    if !is_repo {
+        return Err("Patch export requires the project to be a git repository".to_string());
+    }
+
+    let (added, tracked): (Vec<&RunArtifact>, Vec<&RunArtifact>) = artifacts
+        .iter()
+        .partition(|a| a.change_type == "added");
+
+    let mut patch = Vec::new();
+    if !tracked.is_empty() {
+        let output = Command::new("git")
+            .args(["diff", "--binary", "HEAD", "--"])
+            .args(tracked.iter().map(|a| a.path.as_str()))
+            .current_dir(project)
+            .output()
+            .map_err(|e| format!("Failed to run git diff: {}", e))?;
+        patch.extend_from_slice(&output.stdout);
+    }
+    // New files aren't known to git yet, so diff them against /dev/null
+    for artifact in added {
+        let output = Command::new("git")
+            .args(["diff", "--binary", "--no-index", "--", "/dev/null", &artifact.path])
+            .current_dir(project)
+            .output()
+            .map_err(|e| format!("Failed to run git diff: {}", e))?;
+        patch.extend_from_slice(&output.stdout);
+    }
+
+    std::fs::write(output_path, patch).map_err(|e| format!("Failed to write patch: {}", e))
+}
@@ -0,0 +1,403 @@
+use futures::future::BoxFuture;
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, State};
+use tokio::sync::Notify;
+
+/// Tasks that run at the same time; the rest wait in priority order
+const WORKER_COUNT: usize = 2;
+
+/// Finished tasks kept for `list_background_tasks`
+const MAX_FINISHED_TASKS: usize = 100;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, specta::Type)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskPriority {
+    Low,
+    Normal,
+    High,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, specta::Type)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskStatus {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl TaskStatus {
+    fn is_finished(self) -> bool {
+        matches!(self, Self::Completed | Self::Failed | Self::Cancelled)
+    }
+}
+
+/// A unit of background work, as reported by `background-task` events
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct BackgroundTask {
+    pub id: String,
+    /// What the task does, e.g. "mcp-health-check"; at most one task per kind is
+    /// pending when submitted with [`TaskQueue::submit_once`]
+    pub kind: String,
+    pub label: String,
+    pub priority: TaskPriority,
+    pub status: TaskStatus,
+    /// Fraction done from 0 to 1, when the task reports it
+    pub progress: Option<f32>,
+    pub message: Option<String>,
+    pub error: Option<String>,
+    pub created_at: String,
+    pub started_at: Option<String>,
+    pub finished_at: Option<String>,
+}
+
+type Job = Box<dyn FnOnce(TaskContext) -> BoxFuture<'static, Result<(), String>> + Send>;
+type Listener = Arc<dyn Fn(&BackgroundTask) + Send + Sync>;
+
+struct Entry {
+    task: BackgroundTask,
+    job: Option<Job>,
+    cancelled: Arc<AtomicBool>,
+    /// Submission order, so tasks of equal priority run first-in, first-out
+    order: u64,
+}
+
+/// Handle given to a running task to report progress and check for cancellation
+#[derive(Clone)]
+pub struct TaskContext {
+    id: String,
+    cancelled: Arc<AtomicBool>,
+    queue: Arc<TaskQueue>,
+}
+
+impl TaskContext {
+    /// Cancellation is cooperative: long tasks should check this between steps and return early
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    pub fn progress(&self, fraction: f32, message: impl Into<String>) {
+        let message = message.into();
+        self.queue.update(&self.id, |task| {
+            task.progress = Some(fraction.clamp(0.0, 1.0));
+            task.message = Some(message);
+        });
+    }
+}
+
+/// Central queue for background work with priorities, cancellation and progress events
+pub struct TaskQueue {
+    entries: Mutex<HashMap<String, Entry>>,
+    next_order: Mutex<u64>,
+    wake: Notify,
+    listener: Listener,
+}
+
+#[derive(Clone)]
+pub struct TaskQueueState(pub Arc<TaskQueue>);
+
+impl TaskQueue {
+    /// Create a queue that reports every task change to `listener`
+    pub fn new(listener: impl Fn(&BackgroundTask) + Send + Sync + 'static) -> Arc<Self> {
+        Arc::new(Self {
+            entries: Mutex::new(HashMap::new()),
+            next_order: Mutex::new(0),
+            wake: Notify::new(),
+            listener: Arc::new(listener),
+        })
+    }
+
+    /// Queue a task; returns its ID
+    pub fn submit<F, Fut>(
+        &self,
+        kind: &str,
+        label: impl Into<String>,
+        priority: TaskPriority,
+        job: F,
+    ) -> String
+    where
+        F: FnOnce(TaskContext) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<(), String>> + Send + 'static,
+    {
+        let id = uuid::Uuid::new_v4().to_string();
+        let task = BackgroundTask {
+            id: id.clone(),
+            kind: kind.to_string(),
+            label: label.into(),
+            priority,
+            status: TaskStatus::Queued,
+            progress: None,
+            message: None,
+            error: None,
+            created_at: chrono::Utc::now().to_rfc3339(),
+            started_at: None,
+            finished_at: None,
+        };
+        let order = {
+            let mut next = self.next_order.lock().unwrap_or_else(|e| e.into_inner());
+            *next += 1;
+            *next
+        };
+        let entry = Entry {
+            task: task.clone(),
+            job: Some(Box::new(move |ctx| Box::pin(job(ctx)))),
+            cancelled: Arc::new(AtomicBool::new(false)),
+            order,
+        };
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.insert(id.clone(), entry);
+            prune_finished(&mut entries);
+        }
+        (self.listener)(&task);
+        self.wake.notify_one();
+        id
+    }
+
+    /// Queue a task unless one of the same kind is still queued or running, whose ID is returned instead
+    pub fn submit_once<F, Fut>(
+        &self,
+        kind: &str,
+        label: impl Into<String>,
+        priority: TaskPriority,
+        job: F,
+    ) -> String
+    where
+        F: FnOnce(TaskContext) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<(), String>> + Send + 'static,
+    {
+        let pending = self.entries.lock().ok().and_then(|entries| {
+            entries
+                .values()
+                .find(|e| e.task.kind == kind && !e.task.status.is_finished())
+                .map(|e| e.task.id.clone())
+        });
+        pending.unwrap_or_else(|| self.submit(kind, label, priority, job))
+    }
+
+    /// Cancel a task: queued tasks never start, running tasks are asked to stop
+    pub fn cancel(&self, id: &str) -> Result<(), String> {
+        let mut entries = self.entries.lock().map_err(|e| e.to_string())?;
+        let entry = entries
+            .get_mut(id)
+            .ok_or_else(|| format!("Background task {} not found", id))?;
+        entry.cancelled.store(true, Ordering::Relaxed);
+        if entry.task.status == TaskStatus::Queued {
+            entry.job = None;
+            entry.task.status = TaskStatus::Cancelled;
+            entry.task.finished_at = Some(chrono::Utc::now().to_rfc3339());
+            let task = entry.task.clone();
+            drop(entries);
+            (self.listener)(&task);
+        }
+        Ok(())
+    }
+
+    /// Running and queued tasks in the order they run, followed by finished tasks, newest first
+    pub fn list(&self) -> Vec<BackgroundTask> {
+        let Ok(entries) = self.entries.lock() else {
+            return Vec::new();
+        };
+        let mut list: Vec<&Entry> = entries.values().collect();
+        list.sort_by_key(|e| {
+            let rank = match e.task.status {
+                TaskStatus::Running => 0,
+                TaskStatus::Queued => 1,
+                _ => 2,
+            };
+            let order = if rank == 2 {
+                u64::MAX - e.order
+            } else {
+                e.order
+            };
+            (rank, std::cmp::Reverse(e.task.priority), order)
+        });
+        list.into_iter().map(|e| e.task.clone()).collect()
+    }
+
+    fn update(&self, id: &str, change: impl FnOnce(&mut BackgroundTask)) {
+        let task = {
+            let Ok(mut entries) = self.entries.lock() else {
+                return;
+            };
+            let Some(entry) = entries.get_mut(id) else {
+                return;
+            };
+            change(&mut entry.task);
+            entry.task.clone()
+        };
+        (self.listener)(&task);
+    }
+
+    /// Take the highest priority queued task and mark it running
+    fn next(&self) -> Option<(String, Job, Arc<AtomicBool>)> {
+        let mut entries = self.entries.lock().ok()?;
+        let entry = entries
+            .values_mut()
+            .filter(|e| e.task.status == TaskStatus::Queued && e.job.is_some())
+            .max_by_key(|e| (e.task.priority, std::cmp::Reverse(e.order)))?;
+        entry.task.status = TaskStatus::Running;
+        entry.task.started_at = Some(chrono::Utc::now().to_rfc3339());
+        let job = entry.job.take()?;
+        let next = (entry.task.id.clone(), job, entry.cancelled.clone());
+        let task = entry.task.clone();
+        drop(entries);
+        (self.listener)(&task);
+        Some(next)
+    }
+
+    async fn run_worker(self: Arc<Self>) {
+        loop {
+            let Some((id, job, cancelled)) = self.next() else {
+                self.wake.notified().await;
+                continue;
+            };
+            let ctx = TaskContext {
+                id: id.clone(),
+                cancelled: cancelled.clone(),
+                queue: self.clone(),
+            };
+            // Run on its own task so a panicking job only fails itself
+            let result = match tokio::spawn(job(ctx)).await {
+                Ok(result) => result,
+                Err(e) => Err(format!("Task panicked: {}", e)),
+            };
+            self.update(&id, |task| {
+                task.finished_at = Some(chrono::Utc::now().to_rfc3339());
+                match result {
+                    Ok(()) if cancelled.load(Ordering::Relaxed) => {
+                        task.status = TaskStatus::Cancelled
+                    }
+                    Ok(()) => {
+                        task.status = TaskStatus::Completed;
+                        task.progress = Some(1.0);
+                    }
+                    Err(e) => {
+                        warn!("Background task '{}' failed: {}", task.label, e);
+                        task.status = TaskStatus::Failed;
+                        task.error = Some(e);
+                    }
+                }
+            });
+        }
+    }
+
+    /// Start the workers on the current Tokio runtime
+    pub fn start(self: &Arc<Self>) {
+        for _ in 0..WORKER_COUNT {
+            tokio::spawn(self.clone().run_worker());
+        }
+    }
+}
+
+/// Drop the oldest finished tasks beyond the history limit
+fn prune_finished(entries: &mut HashMap<String, Entry>) {
+    let mut finished: Vec<(u64, String)> = entries
+        .values()
+        .filter(|e| e.task.status.is_finished())
+        .map(|e| (e.order, e.task.id.clone()))
+        .collect();
+    if finished.len() <= MAX_FINISHED_TASKS {
+        return;
+    }
+    finished.sort();
+    for (_, id) in &finished[..finished.len() - MAX_FINISHED_TASKS] {
+        entries.remove(id);
+    }
+}
+
+/// Create the task queue that emits `background-task` and `background-task:{id}`
+pub fn create_task_queue(app: AppHandle) -> TaskQueueState {
+    TaskQueueState(TaskQueue::new(move |task| {
+        let _ = app.emit(&format!("background-task:{}", task.id), task);
+        let _ = app.emit("background-task", task);
+    }))
+}
+
+/// Start the background task workers
+pub fn start_task_workers(queue: TaskQueueState) {
+    tauri::async_runtime::spawn(async move {
+        info!("Starting {} background task workers", WORKER_COUNT);
+        queue.0.start();
+    });
+}
+
+/// List queued, running and recently finished background tasks
+#[tauri::command]
+#[specta::specta]
+pub async fn list_background_tasks(
+    queue: State<'_, TaskQueueState>,
+) -> Result<Vec<BackgroundTask>, String> {
+    Ok(queue.0.list())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn cancel_background_task(
+    queue: State<'_, TaskQueueState>,
+    id: String,
+) -> Result<(), String> {
+    queue.0.cancel(&id).inspect_err(|e| error!("{}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn runs_tasks_by_priority_and_honours_cancellation() {
+        let queue = TaskQueue::new(|_| {});
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let push = |label: &'static str| {
+            let order = order.clone();
+            move |_: TaskContext| async move {
+                order.lock().unwrap().push(label);
+                Ok(())
+            }
+        };
+        queue.submit("index", "low", TaskPriority::Low, push("low"));
+        let cancelled = queue.submit("index", "cancelled", TaskPriority::High, push("cancelled"));
+        queue.submit("index", "high", TaskPriority::High, push("high"));
+        queue.submit("index", "normal", TaskPriority::Normal, push("normal"));
+        let failing = queue.submit(
+            "index",
+            "failing",
+            TaskPriority::Low,
+            |ctx: TaskContext| async move {
+                ctx.progress(0.5, "halfway");
+                Err("disk full".to_string())
+            },
+        );
+        queue.cancel(&cancelled).unwrap();
+
+        // One worker, so the run order is deterministic
+        tokio::spawn(queue.clone().run_worker());
+        for _ in 0..100 {
+            if queue.list().iter().all(|t| t.status.is_finished()) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(*order.lock().unwrap(), vec!["high", "normal", "low"]);
+
+        let tasks = queue.list();
+        let status = |id: &str| tasks.iter().find(|t| t.id == id).unwrap().clone();
+        assert_eq!(status(&cancelled).status, TaskStatus::Cancelled);
+        let failed = status(&failing);
+        assert_eq!(failed.status, TaskStatus::Failed);
+        assert_eq!(failed.progress, Some(0.5));
+        assert_eq!(failed.error.as_deref(), Some("disk full"));
+
+        let first = queue.submit_once("refresh", "Refresh", TaskPriority::Low, push("a"));
+        assert_eq!(
+            queue.submit_once("refresh", "Refresh", TaskPriority::Low, push("b")),
+            first
+        );
+    }
+}
@@ -0,0 +1,436 @@
+use log::{info, warn};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+use tauri::{AppHandle, Manager, State};
+
+use super::agents::{export_agent, init_database, AgentDb};
+use super::claude::get_claude_dir;
+use super::listing_cache::ListingCache;
+use crate::checkpoint::timelines_dir;
+
+/// Bumped when the archive layout changes incompatibly
+const BACKUP_VERSION: u32 = 1;
+
+/// Entry holding the SQLite database
+const DB_ENTRY: &str = "agents.db";
+
+/// Prefix of entries that are stored relative to `~/.claude`
+const CLAUDE_PREFIX: &str = "claude/";
+
+/// Contents of a backup archive, stored as `manifest.json`
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct BackupManifest {
+    pub version: u32,
+    pub app_version: String,
+    pub created_at: String,
+    pub agents: usize,
+    pub settings_files: usize,
+    pub checkpoint_files: usize,
+}
+
+/// What a restore changed
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct RestoreSummary {
+    pub manifest: BackupManifest,
+    /// Files written below `~/.claude`; existing checkpoint files are kept as they are
+    pub files_restored: usize,
+    pub files_skipped: usize,
+    /// Where the database that was replaced was moved to
+    pub previous_database: Option<String>,
+}
+
+/// Settings files and their `.bak` backups in `~/.claude`
+pub(crate) fn settings_files(claude_dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(claude_dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.is_file()
+                && path.file_name().and_then(|n| n.to_str()).is_some_and(|n| {
+                    n.starts_with("settings") && (n.ends_with(".json") || n.ends_with(".bak"))
+                })
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// Timeline and checkpoint metadata files of every session, without file contents
+fn checkpoint_metadata_files(claude_dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = walkdir::WalkDir::new(timelines_dir(claude_dir))
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| entry.into_path())
+        .filter(|path| {
+            let name = path.file_name().and_then(|n| n.to_str());
+            matches!(name, Some("timeline.json" | "metadata.json"))
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// Zip entry name of a file below `~/.claude`
+fn claude_entry(claude_dir: &Path, path: &Path) -> Option<String> {
+    let rel = path.strip_prefix(claude_dir).ok()?;
+    Some(format!(
+        "{}{}",
+        CLAUDE_PREFIX,
+        rel.to_string_lossy().replace('\\', "/")
+    ))
+}
+
+/// Write the database snapshot, exported agents and `~/.claude` files to a zip archive
+fn write_archive(
+    archive_path: &Path,
+    db_snapshot: &Path,
+    agents: &[(String, String)],
+    claude_dir: &Path,
+) -> Result<BackupManifest, String> {
+    let settings = settings_files(claude_dir);
+    let checkpoints = checkpoint_metadata_files(claude_dir);
+    let manifest = BackupManifest {
+        version: BACKUP_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+        agents: agents.len(),
+        settings_files: settings.len(),
+        checkpoint_files: checkpoints.len(),
+    };
+
+    let file =
+        fs::File::create(archive_path).map_err(|e| format!("Failed to create backup: {}", e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+    let mut add = |name: &str, content: &[u8]| -> Result<(), String> {
+        zip.start_file(name, options)
+            .and_then(|_| zip.write_all(content).map_err(Into::into))
+            .map_err(|e| format!("Failed to write {} to backup: {}", name, e))
+    };
+
+    let manifest_json = serde_json::to_string_pretty(&manifest).map_err(|e| e.to_string())?;
+    add("manifest.json", manifest_json.as_bytes())?;
+    let db =
+        fs::read(db_snapshot).map_err(|e| format!("Failed to read database snapshot: {}", e))?;
+    add(DB_ENTRY, &db)?;
+    for (name, json) in agents {
+        add(&format!("agents/{}.claudia.json", name), json.as_bytes())?;
+    }
+    for path in settings.iter().chain(&checkpoints) {
+        let Some(name) = claude_entry(claude_dir, path) else {
+            continue;
+        };
+        let content =
+            fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        add(&name, &content)?;
+    }
+    zip.finish()
+        .map_err(|e| format!("Failed to finish backup: {}", e))?;
+    Ok(manifest)
+}
+
+fn open_archive(
+    archive_path: &Path,
+) -> Result<(zip::ZipArchive<fs::File>, BackupManifest), String> {
+    let file = fs::File::open(archive_path).map_err(|e| format!("Failed to open backup: {}", e))?;
+    let mut zip =
+        zip::ZipArchive::new(file).map_err(|_| "Not a Claudia backup archive".to_string())?;
+    let mut manifest_json = String::new();
+    zip.by_name("manifest.json")
+        .map_err(|_| "Backup is missing manifest.json".to_string())?
+        .read_to_string(&mut manifest_json)
+        .map_err(|e| format!("Failed to read backup manifest: {}", e))?;
+    let manifest: BackupManifest = serde_json::from_str(&manifest_json)
+        .map_err(|e| format!("Invalid backup manifest: {}", e))?;
+    if manifest.version > BACKUP_VERSION {
+        return Err(format!(
+            "Backup version {} is newer than this version of Claudia supports",
+            manifest.version
+        ));
+    }
+    Ok((zip, manifest))
+}
+
+/// Write the `~/.claude` entries of an archive, returning (restored, skipped)
+///
+/// Settings files are backed up and replaced; everything else is only written when missing
+/// so newer checkpoints on this machine are not overwritten.
+fn restore_claude_files(
+    zip: &mut zip::ZipArchive<fs::File>,
+    claude_dir: &Path,
+) -> Result<(usize, usize), String> {
+    let (mut restored, mut skipped) = (0, 0);
+    for index in 0..zip.len() {
+        let mut entry = zip
+            .by_index(index)
+            .map_err(|e| format!("Failed to read backup: {}", e))?;
+        let Some(rel) = entry.name().strip_prefix(CLAUDE_PREFIX).map(PathBuf::from) else {
+            continue;
+        };
+        if rel.as_os_str().is_empty()
+            || !rel.components().all(|c| matches!(c, Component::Normal(_)))
+        {
+            return Err(format!("Backup contains an unsafe path: {}", rel.display()));
+        }
+        let target = claude_dir.join(&rel);
+        let is_settings =
+            rel.components().count() == 1 && rel.extension().is_some_and(|ext| ext == "json");
+        if target.exists() && !is_settings {
+            skipped += 1;
+            continue;
+        }
+
+        let mut content = Vec::new();
+        entry
+            .read_to_end(&mut content)
+            .map_err(|e| format!("Failed to read {} from backup: {}", rel.display(), e))?;
+        if is_settings && fs::read(&target).is_ok_and(|current| current == content) {
+            skipped += 1;
+            continue;
+        }
+        if is_settings {
+            super::settings_file::backup_settings(&target)?;
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        fs::write(&target, content)
+            .map_err(|e| format!("Failed to write {}: {}", target.display(), e))?;
+        restored += 1;
+    }
+    Ok((restored, skipped))
+}
+
+fn database_path(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join("agents.db"))
+        .map_err(|e| format!("Failed to get app data dir: {}", e))
+}
+
+/// Back up the database, agent definitions, settings files and checkpoint metadata into one archive
+#[tauri::command]
+#[specta::specta]
+pub async fn create_backup(
+    app: AppHandle,
+    db: State<'_, AgentDb>,
+    archive_path: String,
+) -> Result<BackupManifest, String> {
+    let db_path = database_path(&app)?;
+    let snapshot = db_path.with_extension("db.backup");
+    let agent_ids: Vec<(i64, String)> = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        let _ = fs::remove_file(&snapshot);
+        // VACUUM INTO writes a consistent copy even while the database is in use
+        conn.execute("VACUUM INTO ?1", params![snapshot.to_string_lossy()])
+            .map_err(|e| format!("Failed to snapshot database: {}", e))?;
+        let mut stmt = conn
+            .prepare("SELECT id, name FROM agents ORDER BY id")
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?;
+        rows
+    };
+
+    let mut agents = Vec::new();
+    for (id, name) in agent_ids {
+        let safe_name: String = name
+            .chars()
+            .map(|c| {
+                if c.is_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        agents.push((
+            format!("{}-{}", id, safe_name),
+            export_agent(db.clone(), id).await?,
+        ));
+    }
+
+    let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
+    let result = write_archive(Path::new(&archive_path), &snapshot, &agents, &claude_dir);
+    let _ = fs::remove_file(&snapshot);
+    let manifest = result?;
+    info!(
+        "Created backup {} ({} agents, {} settings files, {} checkpoint files)",
+        archive_path, manifest.agents, manifest.settings_files, manifest.checkpoint_files
+    );
+    Ok(manifest)
+}
+
+/// Read the manifest of a backup archive without restoring it
+#[tauri::command]
+#[specta::specta]
+pub async fn inspect_backup(archive_path: String) -> Result<BackupManifest, String> {
+    open_archive(Path::new(&archive_path)).map(|(_, manifest)| manifest)
+}
+
+/// Move the closed database aside as `previous`, put `restored` in its place and open it
+///
+/// If the restored database can't be opened, the previous one is moved back.
+fn swap_database(
+    app: &AppHandle,
+    db_path: &Path,
+    restored: &Path,
+    previous: &Path,
+) -> Result<Connection, String> {
+    let _ = fs::remove_file(previous);
+    fs::rename(db_path, previous)
+        .map_err(|e| format!("Failed to move the current database aside: {}", e))?;
+    for suffix in ["db-wal", "db-shm"] {
+        let _ = fs::remove_file(db_path.with_extension(suffix));
+    }
+    fs::rename(restored, db_path).map_err(|e| format!("Failed to replace the database: {}", e))?;
+    init_database(app).map_err(|e| {
+        warn!(
+            "Restored database failed to open, putting the previous one back: {}",
+            e
+        );
+        let _ = fs::rename(previous, db_path);
+        format!("Failed to open the restored database: {}", e)
+    })
+}
+
+/// Replace the database with the one in a backup and restore its `~/.claude` files
+///
+/// The current database is kept next to it as `agents.db.before-restore`. Agent
+/// definitions are part of the database, so the exported agent files are not read.
+#[tauri::command]
+#[specta::specta]
+pub async fn restore_backup(
+    app: AppHandle,
+    db: State<'_, AgentDb>,
+    archive_path: String,
+) -> Result<RestoreSummary, String> {
+    let (mut zip, manifest) = open_archive(Path::new(&archive_path))?;
+    let db_path = database_path(&app)?;
+    let restored_db = db_path.with_extension("db.restore");
+
+    {
+        let mut entry = zip
+            .by_name(DB_ENTRY)
+            .map_err(|_| "Backup is missing agents.db".to_string())?;
+        let mut file = fs::File::create(&restored_db)
+            .map_err(|e| format!("Failed to extract database: {}", e))?;
+        std::io::copy(&mut entry, &mut file)
+            .map_err(|e| format!("Failed to extract database: {}", e))?;
+    }
+    let check: Result<String, String> = Connection::open(&restored_db)
+        .and_then(|conn| conn.query_row("PRAGMA integrity_check", [], |row| row.get(0)))
+        .map_err(|e| e.to_string());
+    if check.as_deref() != Ok("ok") {
+        let _ = fs::remove_file(&restored_db);
+        return Err(format!(
+            "The database in the backup is damaged: {}",
+            check.unwrap_or_else(|e| e)
+        ));
+    }
+
+    let previous = db_path.with_extension("db.before-restore");
+    {
+        let mut conn = db.0.lock().map_err(|e| e.to_string())?;
+        // Close the database file before moving it
+        *conn = Connection::open_in_memory().map_err(|e| e.to_string())?;
+        match swap_database(&app, &db_path, &restored_db, &previous) {
+            Ok(restored) => {
+                *conn = restored;
+                app.state::<ListingCache>().invalidate_all();
+            }
+            Err(e) => {
+                // Whichever step failed, the current database is back in place or still aside
+                if !db_path.exists() {
+                    let _ = fs::rename(&previous, &db_path);
+                }
+                let _ = fs::remove_file(&restored_db);
+                *conn = init_database(&app).map_err(|reopen| {
+                    format!("{}; reopening the current database failed too: {}", e, reopen)
+                })?;
+                return Err(e);
+            }
+        }
+    }
+
+    let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
+    let (files_restored, files_skipped) = restore_claude_files(&mut zip, &claude_dir)?;
+    info!(
+        "Restored backup {} ({} files restored, {} kept)",
+        archive_path, files_restored, files_skipped
+    );
+    Ok(RestoreSummary {
+        manifest,
+        files_restored,
+        files_skipped,
+        previous_database: Some(previous.to_string_lossy().to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backup_round_trips_claude_files_without_clobbering_checkpoints() {
+        let source = tempfile::tempdir().unwrap();
+        let timeline_dir = source.path().join("timelines/-repo/session-1");
+        fs::create_dir_all(source.path().join("projects/-repo")).unwrap();
+        fs::create_dir_all(timeline_dir.join("checkpoints/cp1")).unwrap();
+        fs::write(timeline_dir.join("timeline.json"), "{\"source\":true}").unwrap();
+        fs::write(timeline_dir.join("checkpoints/cp1/metadata.json"), "{}").unwrap();
+        fs::write(timeline_dir.join("checkpoints/cp1/messages.zst"), "large").unwrap();
+        fs::write(source.path().join("projects/-repo/session-1.jsonl"), "{}").unwrap();
+        fs::write(source.path().join("settings.json"), "{\"model\":\"opus\"}").unwrap();
+        fs::write(
+            source.path().join("settings.json.20250101-000000.000.bak"),
+            "{}",
+        )
+        .unwrap();
+        let snapshot = source.path().join("snapshot.db");
+        fs::write(&snapshot, "sqlite").unwrap();
+
+        let archive = source.path().join("backup.zip");
+        let agents = vec![("1-Reviewer".to_string(), "{}".to_string())];
+        let manifest = write_archive(&archive, &snapshot, &agents, source.path()).unwrap();
+        assert_eq!(
+            (
+                manifest.agents,
+                manifest.settings_files,
+                manifest.checkpoint_files
+            ),
+            (1, 2, 2)
+        );
+
+        let target = tempfile::tempdir().unwrap();
+        let existing = target.path().join("timelines/-repo/session-1");
+        fs::create_dir_all(&existing).unwrap();
+        fs::write(existing.join("timeline.json"), "{\"target\":true}").unwrap();
+        fs::write(target.path().join("settings.json"), "{}").unwrap();
+
+        let (mut zip, _) = open_archive(&archive).unwrap();
+        let (restored, skipped) = restore_claude_files(&mut zip, target.path()).unwrap();
+        assert_eq!((restored, skipped), (3, 1));
+        assert_eq!(
+            fs::read_to_string(existing.join("timeline.json")).unwrap(),
+            "{\"target\":true}"
+        );
+        assert_eq!(
+            fs::read_to_string(target.path().join("settings.json")).unwrap(),
+            "{\"model\":\"opus\"}"
+        );
+        assert!(existing.join("checkpoints/cp1/metadata.json").exists());
+        assert!(!existing.join("checkpoints/cp1/messages.zst").exists());
+    }
+}
@@ -0,0 +1,273 @@
+use log::{error, info};
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::{AppHandle, State};
+
+use super::agents::{execute_agent, get_agent, get_session_output, AgentDb, AgentRunMetrics};
+use super::pipelines::extract_run_result;
+use crate::process::ProcessRegistryState;
+
+/// The same agent task fanned out across several projects
+#[derive(Debug, Serialize, Deserialize, Clone, specta::Type)]
+pub struct AgentBatch {
+    pub id: i64,
+    pub agent_id: i64,
+    pub agent_name: String,
+    pub task: String,
+    pub model: Option<String>,
+    pub project_paths: Vec<String>,
+    pub created_at: String,
+}
+
+/// Outcome of one project in a batch
+#[derive(Debug, Serialize, Deserialize, specta::Type)]
+pub struct BatchProjectResult {
+    pub project_path: String,
+    pub run_id: Option<i64>,
+    /// Run status, or "not_started" if the run could not be created
+    pub status: String,
+    /// Why the run could not be started
+    pub start_error: Option<String>,
+    /// Final message of the run
+    pub result: Option<String>,
+    pub is_error: bool,
+    pub metrics: Option<AgentRunMetrics>,
+}
+
+/// A batch with the aggregated results of its runs
+#[derive(Debug, Serialize, Deserialize, specta::Type)]
+pub struct AgentBatchStatus {
+    pub batch: AgentBatch,
+    pub results: Vec<BatchProjectResult>,
+    pub succeeded: usize,
+    pub failed: usize,
+    /// Runs that are queued, pending or running
+    pub in_progress: usize,
+    pub total_cost_usd: f64,
+}
+
+fn row_to_batch(row: &rusqlite::Row) -> rusqlite::Result<AgentBatch> {
+    let project_paths: String = row.get(5)?;
+    Ok(AgentBatch {
+        id: row.get(0)?,
+        agent_id: row.get(1)?,
+        agent_name: row.get(2)?,
+        task: row.get(3)?,
+        model: row.get(4)?,
+        project_paths: serde_json::from_str(&project_paths).unwrap_or_default(),
+        created_at: row.get(6)?,
+    })
+}
+
+/// Run one agent task in several projects; runs past the concurrency limit are queued
+///
+/// The optional cost and token limits apply to each run separately.
+#[tauri::command]
+#[specta::specta]
+#[allow(clippy::too_many_arguments)]
+pub async fn execute_agent_batch(
+    app: AppHandle,
+    db: State<'_, AgentDb>,
+    registry: State<'_, ProcessRegistryState>,
+    agent_id: i64,
+    project_paths: Vec<String>,
+    task: String,
+    model: Option<String>,
+    max_cost_usd: Option<f64>,
+    max_tokens: Option<u64>,
+) -> Result<AgentBatchStatus, String> {
+    let mut seen = std::collections::HashSet::new();
+    let project_paths: Vec<String> = project_paths
+        .into_iter()
+        .filter(|path| seen.insert(path.clone()))
+        .collect();
+    if project_paths.is_empty() {
+        return Err("Select at least one project".to_string());
+    }
+
+    let agent = get_agent(db.clone(), agent_id).await?;
+    let batch_id = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        conn.execute(
+            "INSERT INTO agent_batches (agent_id, agent_name, task, model, project_paths) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                agent_id,
+                agent.name,
+                task,
+                model,
+                serde_json::to_string(&project_paths).map_err(|e| e.to_string())?
+            ],
+        )
+        .map_err(|e| format!("Failed to create batch: {}", e))?;
+        conn.last_insert_rowid()
+    };
+
+    let mut start_errors = HashMap::new();
+    for project_path in &project_paths {
+        let result = execute_agent(
+            app.clone(),
+            agent_id,
+            project_path.clone(),
+            task.clone(),
+            model.clone(),
+            max_cost_usd,
+            max_tokens,
+            db.clone(),
+            registry.clone(),
+        )
+        .await;
+
+        match result {
+            Ok(run_id) => {
+                let conn = db.0.lock().map_err(|e| e.to_string())?;
+                let _ = conn.execute(
+                    "UPDATE agent_runs SET batch_id = ?1 WHERE id = ?2",
+                    params![batch_id, run_id],
+                );
+            }
+            Err(e) => {
+                error!("Batch {} failed to start in {}: {}", batch_id, project_path, e);
+                start_errors.insert(project_path.clone(), e);
+            }
+        }
+    }
+
+    {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        conn.execute(
+            "UPDATE agent_batches SET start_errors = ?1 WHERE id = ?2",
+            params![
+                serde_json::to_string(&start_errors).map_err(|e| e.to_string())?,
+                batch_id
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    info!(
+        "Started batch {} of agent '{}' across {} projects",
+        batch_id,
+        agent.name,
+        project_paths.len()
+    );
+    get_agent_batch(db, registry, batch_id).await
+}
+
+/// List batches, newest first
+#[tauri::command]
+#[specta::specta]
+pub async fn list_agent_batches(db: State<'_, AgentDb>) -> Result<Vec<AgentBatch>, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, agent_id, agent_name, task, model, project_paths, created_at
+             FROM agent_batches ORDER BY created_at DESC, id DESC",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let batches = stmt
+        .query_map([], row_to_batch)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(batches)
+}
+
+/// Get a batch with per-project results
+#[tauri::command]
+#[specta::specta]
+pub async fn get_agent_batch(
+    db: State<'_, AgentDb>,
+    registry: State<'_, ProcessRegistryState>,
+    batch_id: i64,
+) -> Result<AgentBatchStatus, String> {
+    let (batch, start_errors, runs) = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        let (batch, start_errors) = conn
+            .query_row(
+                "SELECT id, agent_id, agent_name, task, model, project_paths, created_at, start_errors
+                 FROM agent_batches WHERE id = ?1",
+                params![batch_id],
+                |row| Ok((row_to_batch(row)?, row.get::<_, Option<String>>(7)?)),
+            )
+            .map_err(|e| format!("Batch {} not found: {}", batch_id, e))?;
+        let start_errors: HashMap<String, String> = start_errors
+            .and_then(|errors| serde_json::from_str(&errors).ok())
+            .unwrap_or_default();
+
+        let mut stmt = conn
+            .prepare("SELECT id, project_path, status FROM agent_runs WHERE batch_id = ?1 ORDER BY id")
+            .map_err(|e| e.to_string())?;
+        let runs = stmt
+            .query_map(params![batch_id], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                ))
+            })
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?;
+        (batch, start_errors, runs)
+    };
+
+    let mut results = Vec::new();
+    for project_path in &batch.project_paths {
+        let Some((run_id, _, status)) = runs.iter().find(|(_, path, _)| path == project_path) else {
+            results.push(BatchProjectResult {
+                project_path: project_path.clone(),
+                run_id: None,
+                status: "not_started".to_string(),
+                start_error: start_errors.get(project_path).cloned(),
+                result: None,
+                is_error: true,
+                metrics: None,
+            });
+            continue;
+        };
+
+        let output = get_session_output(db.clone(), registry.clone(), *run_id)
+            .await
+            .unwrap_or_default();
+        let run_result = extract_run_result(&output);
+        let is_error = match status.as_str() {
+            "completed" => run_result.as_ref().map(|(_, is_error)| *is_error).unwrap_or(true),
+            "queued" | "pending" | "running" => false,
+            _ => true,
+        };
+
+        results.push(BatchProjectResult {
+            project_path: project_path.clone(),
+            run_id: Some(*run_id),
+            status: status.clone(),
+            start_error: None,
+            result: run_result.map(|(text, _)| text),
+            is_error,
+            metrics: (!output.is_empty()).then(|| AgentRunMetrics::from_jsonl(&output)),
+        });
+    }
+
+    let in_progress = results
+        .iter()
+        .filter(|r| matches!(r.status.as_str(), "queued" | "pending" | "running"))
+        .count();
+    let failed = results.iter().filter(|r| r.is_error).count();
+    let succeeded = results.len() - in_progress - failed;
+    let total_cost_usd = results
+        .iter()
+        .filter_map(|r| r.metrics.as_ref().and_then(|m| m.cost_usd))
+        .sum();
+
+    Ok(AgentBatchStatus {
+        batch,
+        results,
+        succeeded,
+        failed,
+        in_progress,
+        total_cost_usd,
+    })
+}
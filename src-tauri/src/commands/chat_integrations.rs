@@ -0,0 +1,181 @@
+use log::{error, info, warn};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use std::time::Duration;
+
+use super::agents::{get_setting, set_setting};
+
+/// How long a chat service has to accept a message
+const POST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Per-agent settings of a chat integration that take precedence over the global ones
+#[derive(Debug, Serialize, Deserialize, Clone, Default, specta::Type)]
+pub struct ChatAgentOverride {
+    /// Post about this agent's runs even when they are off globally, or silence it
+    pub enabled: bool,
+    /// Post to another channel's webhook instead of the global one
+    #[serde(default)]
+    pub webhook_url: Option<String>,
+}
+
+/// Settings of a chat webhook integration (Slack, Discord), stored as JSON in app_settings
+#[derive(Debug, Serialize, Deserialize, Clone, Default, specta::Type)]
+pub struct ChatIntegrationSettings {
+    pub webhook_url: String,
+    /// Post when agent runs finish, unless an agent override says otherwise
+    pub notify_runs: bool,
+    /// Only post about runs that failed
+    #[serde(default)]
+    pub only_on_failure: bool,
+    /// Post when a usage budget threshold is crossed
+    #[serde(default)]
+    pub notify_budget_alerts: bool,
+    #[serde(default)]
+    pub agent_overrides: HashMap<i64, ChatAgentOverride>,
+}
+
+impl ChatIntegrationSettings {
+    /// Webhook to post a run of `agent_id` to, if its runs are posted at all
+    pub fn run_webhook(&self, agent_id: i64) -> Option<&str> {
+        let url = match self.agent_overrides.get(&agent_id) {
+            Some(o) if !o.enabled => return None,
+            Some(o) => o.webhook_url.as_deref().filter(|url| !url.is_empty()),
+            None if !self.notify_runs => return None,
+            None => None,
+        };
+        Some(url.unwrap_or(&self.webhook_url)).filter(|url| !url.is_empty())
+    }
+
+    /// Webhook for budget alerts, if they are posted
+    pub fn budget_webhook(&self) -> Option<&str> {
+        Some(self.webhook_url.as_str()).filter(|url| self.notify_budget_alerts && !url.is_empty())
+    }
+}
+
+pub fn load_chat_settings(conn: &Connection, key: &str) -> ChatIntegrationSettings {
+    get_setting(conn, key).unwrap_or_default()
+}
+
+/// Validate and store settings; `service` names the integration in errors
+pub fn save_chat_settings(
+    conn: &Connection,
+    key: &str,
+    service: &str,
+    settings: &ChatIntegrationSettings,
+) -> Result<(), String> {
+    validate_webhook_url(service, &settings.webhook_url)?;
+    for agent_override in settings.agent_overrides.values() {
+        validate_webhook_url(service, agent_override.webhook_url.as_deref().unwrap_or_default())?;
+    }
+    set_setting(conn, key, settings)
+        .map_err(|e| format!("Failed to save {} settings: {}", service, e))
+}
+
+/// Empty URLs are allowed and turn the integration off
+pub fn validate_webhook_url(service: &str, url: &str) -> Result<(), String> {
+    if url.is_empty() {
+        return Ok(());
+    }
+    let parsed = reqwest::Url::parse(url)
+        .map_err(|e| format!("Invalid {} webhook URL: {}", service, e))?;
+    if parsed.scheme() != "https" {
+        return Err(format!("{} webhook URLs must use https", service));
+    }
+    Ok(())
+}
+
+/// What chat messages say about a finished run
+pub struct FinishedRun {
+    pub run_id: i64,
+    pub agent_id: i64,
+    pub agent_name: String,
+    pub status: String,
+    pub project_path: String,
+}
+
+pub fn load_finished_run(conn: &Connection, run_id: i64, success: bool) -> Option<FinishedRun> {
+    let run = conn.query_row(
+        "SELECT agent_id, agent_name, status, project_path FROM agent_runs WHERE id = ?1",
+        params![run_id],
+        |row| {
+            Ok(FinishedRun {
+                run_id,
+                agent_id: row.get(0)?,
+                agent_name: row.get(1)?,
+                status: row.get(2)?,
+                project_path: row.get(3)?,
+            })
+        },
+    );
+    match run {
+        Ok(mut run) => {
+            // The run record may still say "running" when the monitor reports the outcome
+            if run.status == "pending" || run.status == "running" {
+                run.status = if success { "completed" } else { "failed" }.to_string();
+            }
+            Some(run)
+        }
+        Err(e) => {
+            warn!("Skipping chat message for agent run {}: {}", run_id, e);
+            None
+        }
+    }
+}
+
+pub async fn post_message(service: &str, webhook_url: &str, message: &JsonValue) -> Result<(), String> {
+    let response = super::proxy::http_client()
+        .post(webhook_url)
+        .timeout(POST_TIMEOUT)
+        .json(message)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to post to {}: {}", service, e))?;
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("{} answered {}: {}", service, status, body));
+    }
+    Ok(())
+}
+
+/// Post in the background, logging the outcome
+pub fn spawn_post(service: &'static str, webhook_url: String, message: JsonValue, what: String) {
+    tauri::async_runtime::spawn(async move {
+        match post_message(service, &webhook_url, &message).await {
+            Ok(()) => info!("Posted {} to {}", what, service),
+            Err(e) => error!("Failed to post {} to {}: {}", what, service, e),
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn agent_overrides_take_precedence() {
+        let mut settings = ChatIntegrationSettings {
+            webhook_url: "https://hooks.slack.com/global".to_string(),
+            notify_runs: true,
+            ..Default::default()
+        };
+        settings.agent_overrides.insert(
+            1,
+            ChatAgentOverride {
+                enabled: true,
+                webhook_url: Some("https://hooks.slack.com/agent".to_string()),
+            },
+        );
+        settings.agent_overrides.insert(2, ChatAgentOverride::default());
+
+        assert_eq!(settings.run_webhook(1), Some("https://hooks.slack.com/agent"));
+        assert_eq!(settings.run_webhook(2), None);
+        assert_eq!(settings.run_webhook(3), Some("https://hooks.slack.com/global"));
+
+        settings.notify_runs = false;
+        assert_eq!(settings.run_webhook(1), Some("https://hooks.slack.com/agent"));
+        assert_eq!(settings.run_webhook(3), None);
+    }
+}
@@ -0,0 +1,150 @@
+use rusqlite::Connection;
+use std::fs;
+use tauri::{AppHandle, Manager, State};
+
+use super::agents::{get_setting, set_setting, AgentDb};
+use super::claude::get_claude_dir;
+use crate::checkpoint::state::CheckpointState;
+use crate::checkpoint::storage::CheckpointStorage;
+use crate::checkpoint::{timelines_dir, CleanupReport, RetentionPolicy};
+
+fn load_retention(conn: &Connection) -> RetentionPolicy {
+    get_setting(conn, "checkpoint_retention").unwrap_or_default()
+}
+
+/// Every (project ID, session ID) that has a checkpoint timeline
+fn sessions_with_checkpoints(storage: &CheckpointStorage) -> Vec<(String, String)> {
+    let mut sessions = Vec::new();
+    let Ok(projects) = fs::read_dir(timelines_dir(&storage.claude_dir)) else {
+        return sessions;
+    };
+    for project in projects.filter_map(|entry| entry.ok()) {
+        let project_id = project.file_name().to_string_lossy().to_string();
+        let Ok(timelines) = fs::read_dir(project.path()) else {
+            continue;
+        };
+        for timeline in timelines.filter_map(|entry| entry.ok()) {
+            if timeline.path().is_dir() {
+                sessions.push((
+                    project_id.clone(),
+                    timeline.file_name().to_string_lossy().to_string(),
+                ));
+            }
+        }
+    }
+    sessions
+}
+
+/// Apply a retention policy to the given sessions and refresh their active managers
+async fn clean_sessions(
+    state: &CheckpointState,
+    storage: &CheckpointStorage,
+    sessions: Vec<(String, String)>,
+    policy: &RetentionPolicy,
+) -> Result<CleanupReport, String> {
+    let mut total = CleanupReport::default();
+    for (project_id, session_id) in sessions {
+        let report = storage
+            .apply_retention(&project_id, &session_id, policy)
+            .map_err(|e| format!("Failed to clean up session {}: {}", session_id, e))?;
+        if report.removed_checkpoints > 0 {
+            if let Some(manager) = state.get_manager(&session_id).await {
+                if let Err(e) = manager.reload_timeline().await {
+                    log::warn!("Failed to reload timeline of {}: {}", session_id, e);
+                }
+            }
+        }
+        total.removed_checkpoints += report.removed_checkpoints;
+        total.removed_content_files += report.removed_content_files;
+        total.reclaimed_bytes += report.reclaimed_bytes;
+    }
+    Ok(total)
+}
+
+/// Apply the saved policy to one session if it is set to clean up automatically
+pub async fn auto_cleanup_session(app: &AppHandle, project_id: &str, session_id: &str) {
+    let policy = {
+        let db = app.state::<AgentDb>();
+        let Ok(conn) = db.0.lock() else {
+            return;
+        };
+        load_retention(&conn)
+    };
+    if !policy.auto_cleanup {
+        return;
+    }
+    let Ok(claude_dir) = get_claude_dir() else {
+        return;
+    };
+    let storage = CheckpointStorage::new(claude_dir);
+    let sessions = vec![(project_id.to_string(), session_id.to_string())];
+    match clean_sessions(&app.state::<CheckpointState>(), &storage, sessions, &policy).await {
+        Ok(report) if report.removed_checkpoints > 0 => log::info!(
+            "Removed {} old checkpoints of {}, reclaiming {} bytes",
+            report.removed_checkpoints,
+            session_id,
+            report.reclaimed_bytes
+        ),
+        Ok(_) => {}
+        Err(e) => log::error!("{}", e),
+    }
+}
+
+/// Get the checkpoint retention policy
+#[tauri::command]
+#[specta::specta]
+pub async fn get_checkpoint_retention(db: State<'_, AgentDb>) -> Result<RetentionPolicy, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    Ok(load_retention(&conn))
+}
+
+/// Save the checkpoint retention policy
+#[tauri::command]
+#[specta::specta]
+pub async fn set_checkpoint_retention(
+    db: State<'_, AgentDb>,
+    policy: RetentionPolicy,
+) -> Result<(), String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    set_setting(&conn, "checkpoint_retention", &policy)
+        .map_err(|e| format!("Failed to save retention policy: {}", e))?;
+    Ok(())
+}
+
+/// Remove old checkpoints by the saved policy, or `policy` when given
+///
+/// Cleans one session when `session_id` is given, otherwise every session of
+/// `project_id`, or of all projects when that is omitted too.
+#[tauri::command]
+#[specta::specta]
+pub async fn cleanup_checkpoints(
+    db: State<'_, AgentDb>,
+    state: State<'_, CheckpointState>,
+    project_id: Option<String>,
+    session_id: Option<String>,
+    policy: Option<RetentionPolicy>,
+) -> Result<CleanupReport, String> {
+    let policy = match policy {
+        Some(policy) => policy,
+        None => {
+            let conn = db.0.lock().map_err(|e| e.to_string())?;
+            load_retention(&conn)
+        }
+    };
+    let storage = CheckpointStorage::new(get_claude_dir().map_err(|e| e.to_string())?);
+
+    let sessions = sessions_with_checkpoints(&storage)
+        .into_iter()
+        .filter(|(project, session)| {
+            project_id.as_ref().is_none_or(|id| id == project)
+                && session_id.as_ref().is_none_or(|id| id == session)
+        })
+        .collect();
+    let report = clean_sessions(&state, &storage, sessions, &policy).await?;
+    log::info!(
+        "Checkpoint cleanup removed {} checkpoints and reclaimed {} bytes",
+        report.removed_checkpoints,
+        report.reclaimed_bytes
+    );
+    Ok(report)
+}
@@ -2,17 +2,23 @@ use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::io::{BufRead, BufReader};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
 use std::sync::Arc;
 use std::time::SystemTime;
-use tauri::{AppHandle, Emitter, Manager};
+use tauri::{AppHandle, Emitter, Manager, State};
 use tokio::process::{Child, Command};
 use tokio::sync::Mutex;
 use tauri_plugin_shell::ShellExt;
 use tauri_plugin_shell::process::CommandEvent;
 use regex;
 
+use super::listing_cache::ListingCache;
+use super::live_metrics::{terminate_process, LiveMetricsTracker, RunBudget};
+use super::mcp::write_file_atomically;
+use super::run_logs::{OutputLine, OutputSequence, SessionErrorLog};
+use super::settings_file::write_settings;
+
 /// Global state to track current Claude process
 pub struct ClaudeProcessState {
     pub current_process: Arc<Mutex<Option<Child>>>,
@@ -27,7 +33,7 @@ impl Default for ClaudeProcessState {
 }
 
 /// Represents a project in the ~/.claude/projects directory
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
 pub struct Project {
     /// The project ID (derived from the directory name)
     pub id: String,
@@ -43,7 +49,7 @@ pub struct Project {
 }
 
 /// Represents a session with its metadata
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
 pub struct Session {
     /// The session ID (UUID)
     pub id: String,
@@ -59,6 +65,9 @@ pub struct Session {
     pub first_message: Option<String>,
     /// Timestamp of the first user message (if available)
     pub message_timestamp: Option<String>,
+    /// Whether the transcript has been compressed into the session archive
+    #[serde(default)]
+    pub archived: bool,
 }
 
 /// Represents a message entry in the JSONL file
@@ -79,7 +88,7 @@ struct MessageContent {
 }
 
 /// Represents the settings from ~/.claude/settings.json
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
 pub struct ClaudeSettings {
     #[serde(flatten)]
     pub data: serde_json::Value,
@@ -94,7 +103,7 @@ impl Default for ClaudeSettings {
 }
 
 /// Represents the Claude Code version status
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
 pub struct ClaudeVersionStatus {
     /// Whether Claude Code is installed and working
     pub is_installed: bool,
@@ -105,7 +114,7 @@ pub struct ClaudeVersionStatus {
 }
 
 /// Represents a CLAUDE.md file found in the project
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
 pub struct ClaudeMdFile {
     /// Relative path from the project root
     pub relative_path: String,
@@ -117,8 +126,19 @@ pub struct ClaudeMdFile {
     pub modified: u64,
 }
 
+/// A CLAUDE.md file and where it applies
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct ClaudeMdLocation {
+    /// "global" for ~/.claude/CLAUDE.md, otherwise "project"
+    pub scope: String,
+    /// Root of the project the file belongs to
+    pub project_path: Option<String>,
+    #[serde(flatten)]
+    pub file: ClaudeMdFile,
+}
+
 /// Represents a file or directory entry
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
 pub struct FileEntry {
     /// The name of the file or directory
     pub name: String,
@@ -139,7 +159,7 @@ fn find_claude_binary(app_handle: &AppHandle) -> Result<String, String> {
 }
 
 /// Gets the path to the ~/.claude directory
-fn get_claude_dir() -> Result<PathBuf> {
+pub(crate) fn get_claude_dir() -> Result<PathBuf> {
     dirs::home_dir()
         .context("Could not find home directory")?
         .join(".claude")
@@ -148,7 +168,7 @@ fn get_claude_dir() -> Result<PathBuf> {
 }
 
 /// Gets the actual project path by reading the cwd from the first JSONL entry
-fn get_project_path_from_sessions(project_dir: &PathBuf) -> Result<String, String> {
+pub(crate) fn get_project_path_from_sessions(project_dir: &Path) -> Result<String, String> {
     // Try to read any JSONL file in the directory
     let entries = fs::read_dir(project_dir)
         .map_err(|e| format!("Failed to read project directory: {}", e))?;
@@ -186,6 +206,17 @@ fn decode_project_path(encoded: &str) -> String {
     encoded.replace('-', "/")
 }
 
+/// Session id of a transcript file and whether it is archived
+fn session_file_id(path: &Path) -> Option<(&str, bool)> {
+    if let Some(session_id) = super::session_archive::archived_session_id(path) {
+        return Some((session_id, true));
+    }
+    if path.extension().and_then(|s| s.to_str()) == Some("jsonl") {
+        return path.file_stem().and_then(|s| s.to_str()).map(|id| (id, false));
+    }
+    None
+}
+
 /// Extracts the first valid user message from a JSONL file
 fn extract_first_user_message(jsonl_path: &PathBuf) -> (Option<String>, Option<String>) {
     let file = match fs::File::open(jsonl_path) {
@@ -193,7 +224,16 @@ fn extract_first_user_message(jsonl_path: &PathBuf) -> (Option<String>, Option<S
         Err(_) => return (None, None),
     };
 
-    let reader = BufReader::new(file);
+    // Archived transcripts are decompressed only as far as the first message
+    let archived = session_file_id(jsonl_path).is_some_and(|(_, archived)| archived);
+    let reader: Box<dyn BufRead> = if archived {
+        match zstd::stream::Decoder::new(file) {
+            Ok(decoder) => Box::new(BufReader::new(decoder)),
+            Err(_) => return (None, None),
+        }
+    } else {
+        Box::new(BufReader::new(file))
+    };
 
     for line in reader.lines() {
         if let Ok(line) = line {
@@ -290,12 +330,28 @@ fn create_sidecar_command(
     
     // Set working directory
     sidecar_cmd = sidecar_cmd.current_dir(project_path);
+
+    // Expose keychain secrets referenced by MCP server configs or the environment profile
+    let project_env = super::env_profiles::project_env(app, project_path);
+    sidecar_cmd = sidecar_cmd.envs(super::secrets::secret_env(project_path, &project_env));
+
+    // Route traffic through the configured proxy
+    sidecar_cmd = sidecar_cmd.envs(super::proxy::proxy_env());
+
+    // Apply the project's environment profile
+    sidecar_cmd = sidecar_cmd.envs(project_env);
     
     Ok(sidecar_cmd)
 }
 
+/// Emit a line tagged with its stream and sequence number as `claude-stream:{session_id}`
+fn emit_session_stream(app: &AppHandle, session_id: &str, line: &OutputLine) {
+    let _ = app.emit(&format!("claude-stream:{}", session_id), line);
+}
+
 /// Creates a system binary command with the given arguments
-fn create_system_command(
+pub(crate) fn create_system_command(
+    app: &AppHandle,
     claude_path: &str,
     args: Vec<String>,
     project_path: &str,
@@ -307,17 +363,87 @@ fn create_system_command(
         cmd.arg(arg);
     }
     
+    let project_env = super::env_profiles::project_env(app, project_path);
     cmd.current_dir(project_path)
+        .envs(super::secrets::secret_env(project_path, &project_env))
+        .envs(super::proxy::proxy_env())
+        .envs(project_env)
         .stdout(Stdio::piped())
         .stderr(Stdio::piped());
+    crate::process::tree::isolate(&mut cmd);
     
     cmd
 }
 
-/// Lists all projects in the ~/.claude/projects directory
-#[tauri::command]
-pub async fn list_projects() -> Result<Vec<Project>, String> {
-    log::info!("Listing projects from ~/.claude/projects");
+/// Reads one project directory: its real path, sessions and whether it is a git repository
+fn scan_project(path: &Path) -> Option<Project> {
+    let dir_name = path.file_name().and_then(|n| n.to_str())?;
+
+    // Get directory creation time
+    let metadata = match fs::metadata(path) {
+        Ok(metadata) => metadata,
+        Err(e) => {
+            log::warn!("Failed to read metadata of project {}: {}", dir_name, e);
+            return None;
+        }
+    };
+
+    let created_at = metadata
+        .created()
+        .or_else(|_| metadata.modified())
+        .unwrap_or(SystemTime::UNIX_EPOCH)
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+
+    // Get the actual project path from JSONL files
+    let project_path = match get_project_path_from_sessions(path) {
+        Ok(path) => path,
+        Err(e) => {
+            log::warn!("Failed to get project path from sessions for {}: {}, falling back to decode", dir_name, e);
+            decode_project_path(dir_name)
+        }
+    };
+    
+    // Check if it's a git repository
+    let is_git_repo = Path::new(&project_path).join(".git").exists();
+
+    // List all JSONL files (sessions) in this project directory
+    let mut sessions = Vec::new();
+    if let Ok(session_entries) = fs::read_dir(path) {
+        for session_entry in session_entries.flatten() {
+            let session_path = session_entry.path();
+            if session_path.is_file() {
+                if let Some((session_id, _)) = session_file_id(&session_path) {
+                    sessions.push(session_id.to_string());
+                }
+            }
+        }
+    }
+
+    Some(Project {
+        id: dir_name.to_string(),
+        path: project_path,
+        sessions,
+        created_at,
+        is_git_repo,
+    })
+}
+
+/// Payload of the `project-scanned` event
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct ProjectScanProgress {
+    pub project: Project,
+    pub scanned: usize,
+    pub total: usize,
+}
+
+/// Scan every project directory in parallel, calling `on_project` as each one finishes
+///
+/// Directories are read on rayon's thread pool, so call this from a blocking task.
+fn scan_projects(on_project: impl Fn(&Project, usize, usize) + Sync) -> Result<Vec<Project>, String> {
+    use rayon::prelude::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
 
     let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
     let projects_dir = claude_dir.join("projects");
@@ -327,71 +453,20 @@ pub async fn list_projects() -> Result<Vec<Project>, String> {
         return Ok(Vec::new());
     }
 
-    let mut projects = Vec::new();
-
     // Read all directories in the projects folder
-    let entries = fs::read_dir(&projects_dir)
-        .map_err(|e| format!("Failed to read projects directory: {}", e))?;
-
-    for entry in entries {
-        let entry = entry.map_err(|e| format!("Failed to read directory entry: {}", e))?;
-        let path = entry.path();
-
-        if path.is_dir() {
-            let dir_name = path
-                .file_name()
-                .and_then(|n| n.to_str())
-                .ok_or_else(|| "Invalid directory name".to_string())?;
-
-            // Get directory creation time
-            let metadata = fs::metadata(&path)
-                .map_err(|e| format!("Failed to read directory metadata: {}", e))?;
-
-            let created_at = metadata
-                .created()
-                .or_else(|_| metadata.modified())
-                .unwrap_or(SystemTime::UNIX_EPOCH)
-                .duration_since(SystemTime::UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_secs();
-
-            // Get the actual project path from JSONL files
-            let project_path = match get_project_path_from_sessions(&path) {
-                Ok(path) => path,
-                Err(e) => {
-                    log::warn!("Failed to get project path from sessions for {}: {}, falling back to decode", dir_name, e);
-                    decode_project_path(dir_name)
-                }
-            };
-            
-            // Check if it's a git repository
-            let is_git_repo = std::path::Path::new(&project_path).join(".git").exists();
-
-            // List all JSONL files (sessions) in this project directory
-            let mut sessions = Vec::new();
-            if let Ok(session_entries) = fs::read_dir(&path) {
-                for session_entry in session_entries.flatten() {
-                    let session_path = session_entry.path();
-                    if session_path.is_file()
-                        && session_path.extension().and_then(|s| s.to_str()) == Some("jsonl")
-                    {
-                        if let Some(session_id) = session_path.file_stem().and_then(|s| s.to_str())
-                        {
-                            sessions.push(session_id.to_string());
-                        }
-                    }
-                }
-            }
-
-            projects.push(Project {
-                id: dir_name.to_string(),
-                path: project_path,
-                sessions,
-                created_at,
-                is_git_repo,
-            });
-        }
-    }
+    let dirs: Vec<PathBuf> = fs::read_dir(&projects_dir)
+        .map_err(|e| format!("Failed to read projects directory: {}", e))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_dir())
+        .collect();
+
+    let total = dirs.len();
+    let scanned = AtomicUsize::new(0);
+    let mut projects: Vec<Project> = dirs
+        .par_iter()
+        .filter_map(|path| scan_project(path))
+        .inspect(|project| on_project(project, scanned.fetch_add(1, Ordering::Relaxed) + 1, total))
+        .collect();
 
     // Sort projects by creation time (newest first)
     projects.sort_by(|a, b| b.created_at.cmp(&a.created_at));
@@ -400,10 +475,59 @@ pub async fn list_projects() -> Result<Vec<Project>, String> {
     Ok(projects)
 }
 
+/// Lists all projects in the ~/.claude/projects directory
+#[tauri::command]
+#[specta::specta]
+pub async fn list_projects(cache: State<'_, ListingCache>) -> Result<Vec<Project>, String> {
+    if let Some(projects) = cache.projects() {
+        return Ok(projects);
+    }
+    log::info!("Listing projects from ~/.claude/projects");
+    let generation = cache.generation();
+    let projects = tokio::task::spawn_blocking(|| scan_projects(|_, _, _| {}))
+        .await
+        .map_err(|e| format!("Project scan failed: {}", e))??;
+    cache.store_projects(generation, &projects);
+    Ok(projects)
+}
+
+/// Lists all projects like `list_projects`, emitting `project-scanned` for each one as soon
+/// as it has been read so the project list can fill in progressively
+#[tauri::command]
+#[specta::specta]
+pub async fn scan_projects_streaming(app: AppHandle) -> Result<Vec<Project>, String> {
+    log::info!("Scanning projects from ~/.claude/projects");
+    let cache = app.state::<ListingCache>();
+    let generation = cache.generation();
+    let emitter = app.clone();
+    let projects = tokio::task::spawn_blocking(move || {
+        scan_projects(|project, scanned, total| {
+            let progress = ProjectScanProgress {
+                project: project.clone(),
+                scanned,
+                total,
+            };
+            let _ = emitter.emit("project-scanned", &progress);
+        })
+    })
+    .await
+    .map_err(|e| format!("Project scan failed: {}", e))??;
+    cache.store_projects(generation, &projects);
+    Ok(projects)
+}
+
 /// Gets sessions for a specific project
 #[tauri::command]
-pub async fn get_project_sessions(project_id: String) -> Result<Vec<Session>, String> {
+#[specta::specta]
+pub async fn get_project_sessions(
+    cache: State<'_, ListingCache>,
+    project_id: String,
+) -> Result<Vec<Session>, String> {
+    if let Some(sessions) = cache.sessions(&project_id) {
+        return Ok(sessions);
+    }
     log::info!("Getting sessions for project: {}", project_id);
+    let generation = cache.generation();
 
     let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
     let project_dir = claude_dir.join("projects").join(&project_id);
@@ -436,14 +560,15 @@ pub async fn get_project_sessions(project_id: String) -> Result<Vec<Session>, St
         let entry = entry.map_err(|e| format!("Failed to read directory entry: {}", e))?;
         let path = entry.path();
 
-        if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("jsonl") {
-            if let Some(session_id) = path.file_stem().and_then(|s| s.to_str()) {
+        if path.is_file() {
+            if let Some((session_id, archived)) = session_file_id(&path) {
                 // Get file creation time
                 let metadata = fs::metadata(&path)
                     .map_err(|e| format!("Failed to read file metadata: {}", e))?;
 
-                let created_at = metadata
-                    .created()
+                // An archive is newly created but carries the transcript's modification time
+                let created = if archived { metadata.modified() } else { metadata.created() };
+                let created_at = created
                     .or_else(|_| metadata.modified())
                     .unwrap_or(SystemTime::UNIX_EPOCH)
                     .duration_since(SystemTime::UNIX_EPOCH)
@@ -471,6 +596,7 @@ pub async fn get_project_sessions(project_id: String) -> Result<Vec<Session>, St
                     created_at,
                     first_message,
                     message_timestamp,
+                    archived,
                 });
             }
         }
@@ -484,11 +610,13 @@ pub async fn get_project_sessions(project_id: String) -> Result<Vec<Session>, St
         sessions.len(),
         project_id
     );
+    cache.store_sessions(generation, &project_id, &sessions);
     Ok(sessions)
 }
 
 /// Reads the Claude settings file
 #[tauri::command]
+#[specta::specta]
 pub async fn get_claude_settings() -> Result<ClaudeSettings, String> {
     log::info!("Reading Claude settings");
 
@@ -513,6 +641,7 @@ pub async fn get_claude_settings() -> Result<ClaudeSettings, String> {
 
 /// Opens a new Claude Code session by executing the claude command
 #[tauri::command]
+#[specta::specta]
 pub async fn open_new_session(app: AppHandle, path: Option<String>) -> Result<String, String> {
     log::info!("Opening new Claude Code session at path: {:?}", path);
 
@@ -555,6 +684,7 @@ pub async fn open_new_session(app: AppHandle, path: Option<String>) -> Result<St
 
 /// Reads the CLAUDE.md system prompt file
 #[tauri::command]
+#[specta::specta]
 pub async fn get_system_prompt() -> Result<String, String> {
     log::info!("Reading CLAUDE.md system prompt");
 
@@ -571,6 +701,7 @@ pub async fn get_system_prompt() -> Result<String, String> {
 
 /// Checks if Claude Code is installed and gets its version
 #[tauri::command]
+#[specta::specta]
 pub async fn check_claude_version(app: AppHandle) -> Result<ClaudeVersionStatus, String> {
     log::info!("Checking Claude Code version");
 
@@ -744,37 +875,36 @@ pub async fn check_claude_version(app: AppHandle) -> Result<ClaudeVersionStatus,
 
 /// Saves the CLAUDE.md system prompt file
 #[tauri::command]
+#[specta::specta]
 pub async fn save_system_prompt(content: String) -> Result<String, String> {
     log::info!("Saving CLAUDE.md system prompt");
 
     let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
     let claude_md_path = claude_dir.join("CLAUDE.md");
 
-    fs::write(&claude_md_path, content).map_err(|e| format!("Failed to write CLAUDE.md: {}", e))?;
+    write_file_atomically(&claude_md_path, &content)
+        .map_err(|e| format!("Failed to write CLAUDE.md: {}", e))?;
 
     Ok("System prompt saved successfully".to_string())
 }
 
 /// Saves the Claude settings file
 #[tauri::command]
+#[specta::specta]
 pub async fn save_claude_settings(settings: serde_json::Value) -> Result<String, String> {
     log::info!("Saving Claude settings");
 
     let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
     let settings_path = claude_dir.join("settings.json");
 
-    // Pretty print the JSON with 2-space indentation
-    let json_string = serde_json::to_string_pretty(&settings)
-        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
-
-    fs::write(&settings_path, json_string)
-        .map_err(|e| format!("Failed to write settings file: {}", e))?;
+    write_settings(&settings_path, &settings)?;
 
     Ok("Settings saved successfully".to_string())
 }
 
 /// Recursively finds all CLAUDE.md files in a project directory
 #[tauri::command]
+#[specta::specta]
 pub async fn find_claude_md_files(project_path: String) -> Result<Vec<ClaudeMdFile>, String> {
     log::info!("Finding CLAUDE.md files in project: {}", project_path);
 
@@ -793,6 +923,63 @@ pub async fn find_claude_md_files(project_path: String) -> Result<Vec<ClaudeMdFi
     Ok(claude_files)
 }
 
+/// Finds the global CLAUDE.md and the CLAUDE.md files of every known project
+#[tauri::command]
+#[specta::specta]
+pub async fn find_all_claude_md_files(
+    cache: State<'_, ListingCache>,
+) -> Result<Vec<ClaudeMdLocation>, String> {
+    let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
+    let mut locations = Vec::new();
+    let mut seen = std::collections::HashSet::new();
+
+    let global_path = claude_dir.join("CLAUDE.md");
+    if let Ok(metadata) = fs::metadata(&global_path) {
+        seen.insert(global_path.clone());
+        locations.push(ClaudeMdLocation {
+            scope: "global".to_string(),
+            project_path: None,
+            file: ClaudeMdFile {
+                relative_path: "CLAUDE.md".to_string(),
+                absolute_path: global_path.to_string_lossy().to_string(),
+                size: metadata.len(),
+                modified: metadata
+                    .modified()
+                    .unwrap_or(SystemTime::UNIX_EPOCH)
+                    .duration_since(SystemTime::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs(),
+            },
+        });
+    }
+
+    for project in list_projects(cache).await? {
+        let root = PathBuf::from(&project.path);
+        if !root.is_dir() {
+            continue;
+        }
+        let mut files = Vec::new();
+        if let Err(e) = find_claude_md_recursive(&root, &root, &mut files) {
+            log::warn!("Skipping CLAUDE.md files of {}: {}", project.path, e);
+            continue;
+        }
+        files.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
+        // Nested projects would otherwise list the same files twice
+        for file in files {
+            if seen.insert(PathBuf::from(&file.absolute_path)) {
+                locations.push(ClaudeMdLocation {
+                    scope: "project".to_string(),
+                    project_path: Some(project.path.clone()),
+                    file,
+                });
+            }
+        }
+    }
+
+    log::info!("Found {} CLAUDE.md files across all projects", locations.len());
+    Ok(locations)
+}
+
 /// Helper function to recursively find CLAUDE.md files
 fn find_claude_md_recursive(
     current_path: &PathBuf,
@@ -861,6 +1048,7 @@ fn find_claude_md_recursive(
 
 /// Reads a specific CLAUDE.md file by its absolute path
 #[tauri::command]
+#[specta::specta]
 pub async fn read_claude_md_file(file_path: String) -> Result<String, String> {
     log::info!("Reading CLAUDE.md file: {}", file_path);
 
@@ -874,10 +1062,18 @@ pub async fn read_claude_md_file(file_path: String) -> Result<String, String> {
 
 /// Saves a specific CLAUDE.md file by its absolute path
 #[tauri::command]
+#[specta::specta]
 pub async fn save_claude_md_file(file_path: String, content: String) -> Result<String, String> {
     log::info!("Saving CLAUDE.md file: {}", file_path);
 
     let path = PathBuf::from(&file_path);
+    let is_claude_md = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|name| name.eq_ignore_ascii_case("CLAUDE.md"));
+    if !is_claude_md {
+        return Err(format!("Not a CLAUDE.md file: {}", file_path));
+    }
 
     // Ensure the parent directory exists
     if let Some(parent) = path.parent() {
@@ -885,13 +1081,14 @@ pub async fn save_claude_md_file(file_path: String, content: String) -> Result<S
             .map_err(|e| format!("Failed to create parent directory: {}", e))?;
     }
 
-    fs::write(&path, content).map_err(|e| format!("Failed to write file: {}", e))?;
+    write_file_atomically(&path, &content).map_err(|e| format!("Failed to write file: {}", e))?;
 
     Ok("File saved successfully".to_string())
 }
 
 /// Loads the JSONL history for a specific session
 #[tauri::command]
+#[specta::specta]
 pub async fn load_session_history(
     session_id: String,
     project_id: String,
@@ -908,23 +1105,17 @@ pub async fn load_session_history(
         .join(&project_id)
         .join(format!("{}.jsonl", session_id));
 
-    if !session_path.exists() {
+    if !session_path.exists() && !super::session_archive::archived_path(&session_path).exists() {
         return Err(format!("Session file not found: {}", session_id));
     }
 
-    let file =
-        fs::File::open(&session_path).map_err(|e| format!("Failed to open session file: {}", e))?;
-
-    let reader = BufReader::new(file);
-    let mut messages = Vec::new();
+    let file = super::jsonl_reader::JsonlFile::open(&session_path)
+        .map_err(|e| format!("Failed to open session file: {}", e))?;
 
-    for line in reader.lines() {
-        if let Ok(line) = line {
-            if let Ok(json) = serde_json::from_str::<serde_json::Value>(&line) {
-                messages.push(json);
-            }
-        }
-    }
+    let messages = file
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(&line).ok())
+        .collect();
 
     Ok(messages)
 }
@@ -933,11 +1124,15 @@ pub async fn load_session_history(
 
 /// Execute a new interactive Claude Code session with streaming output
 #[tauri::command]
+#[specta::specta]
 pub async fn execute_claude_code(
     app: AppHandle,
     project_path: String,
     prompt: String,
     model: String,
+    max_cost_usd: Option<f64>,
+    max_tokens: Option<u64>,
+    prompt_preset_id: Option<i64>,
 ) -> Result<(), String> {
     log::info!(
         "Starting new Claude Code session in: {} with model: {}",
@@ -947,7 +1142,7 @@ pub async fn execute_claude_code(
 
     let claude_path = find_claude_binary(&app)?;
     
-    let args = vec![
+    let mut args = vec![
         "-p".to_string(),
         prompt.clone(),
         "--model".to_string(),
@@ -958,21 +1153,31 @@ pub async fn execute_claude_code(
         "--dangerously-skip-permissions".to_string(),
     ];
 
+    args.extend(super::prompt_presets::preset_args_for(&app, prompt_preset_id)?);
+
+    let budget = RunBudget {
+        max_cost_usd,
+        max_tokens,
+    };
     if should_use_sidecar(&claude_path) {
-        spawn_claude_sidecar(app, args, prompt, model, project_path).await
+        spawn_claude_sidecar(app, args, prompt, model, project_path, budget).await
     } else {
-        let cmd = create_system_command(&claude_path, args, &project_path);
-        spawn_claude_process(app, cmd, prompt, model, project_path).await
+        let cmd = create_system_command(&app, &claude_path, args, &project_path);
+        spawn_claude_process(app, cmd, prompt, model, project_path, budget).await
     }
 }
 
 /// Continue an existing Claude Code conversation with streaming output
 #[tauri::command]
+#[specta::specta]
 pub async fn continue_claude_code(
     app: AppHandle,
     project_path: String,
     prompt: String,
     model: String,
+    max_cost_usd: Option<f64>,
+    max_tokens: Option<u64>,
+    prompt_preset_id: Option<i64>,
 ) -> Result<(), String> {
     log::info!(
         "Continuing Claude Code conversation in: {} with model: {}",
@@ -982,7 +1187,7 @@ pub async fn continue_claude_code(
 
     let claude_path = find_claude_binary(&app)?;
     
-    let args = vec![
+    let mut args = vec![
         "-c".to_string(), // Continue flag
         "-p".to_string(),
         prompt.clone(),
@@ -994,22 +1199,33 @@ pub async fn continue_claude_code(
         "--dangerously-skip-permissions".to_string(),
     ];
 
+    args.extend(super::prompt_presets::preset_args_for(&app, prompt_preset_id)?);
+
+    let budget = RunBudget {
+        max_cost_usd,
+        max_tokens,
+    };
     if should_use_sidecar(&claude_path) {
-        spawn_claude_sidecar(app, args, prompt, model, project_path).await
+        spawn_claude_sidecar(app, args, prompt, model, project_path, budget).await
     } else {
-        let cmd = create_system_command(&claude_path, args, &project_path);
-        spawn_claude_process(app, cmd, prompt, model, project_path).await
+        let cmd = create_system_command(&app, &claude_path, args, &project_path);
+        spawn_claude_process(app, cmd, prompt, model, project_path, budget).await
     }
 }
 
 /// Resume an existing Claude Code session by ID with streaming output
 #[tauri::command]
+#[specta::specta]
+#[allow(clippy::too_many_arguments)]
 pub async fn resume_claude_code(
     app: AppHandle,
     project_path: String,
     session_id: String,
     prompt: String,
     model: String,
+    max_cost_usd: Option<f64>,
+    max_tokens: Option<u64>,
+    prompt_preset_id: Option<i64>,
 ) -> Result<(), String> {
     log::info!(
         "Resuming Claude Code session: {} in: {} with model: {}",
@@ -1019,8 +1235,10 @@ pub async fn resume_claude_code(
     );
 
     let claude_path = find_claude_binary(&app)?;
+    let db = app.state::<super::agents::AgentDb>();
+    super::session_archive::unarchive_for_resume(&db, &session_id)?;
     
-    let args = vec![
+    let mut args = vec![
         "--resume".to_string(),
         session_id.clone(),
         "-p".to_string(),
@@ -1033,16 +1251,23 @@ pub async fn resume_claude_code(
         "--dangerously-skip-permissions".to_string(),
     ];
 
+    args.extend(super::prompt_presets::preset_args_for(&app, prompt_preset_id)?);
+
+    let budget = RunBudget {
+        max_cost_usd,
+        max_tokens,
+    };
     if should_use_sidecar(&claude_path) {
-        spawn_claude_sidecar(app, args, prompt, model, project_path).await
+        spawn_claude_sidecar(app, args, prompt, model, project_path, budget).await
     } else {
-        let cmd = create_system_command(&claude_path, args, &project_path);
-        spawn_claude_process(app, cmd, prompt, model, project_path).await
+        let cmd = create_system_command(&app, &claude_path, args, &project_path);
+        spawn_claude_process(app, cmd, prompt, model, project_path, budget).await
     }
 }
 
 /// Cancel the currently running Claude Code execution
 #[tauri::command]
+#[specta::specta]
 pub async fn cancel_claude_execution(
     app: AppHandle,
     session_id: Option<String>,
@@ -1096,42 +1321,30 @@ pub async fn cancel_claude_execution(
             let pid = child.id();
             log::info!("Attempting to kill Claude process via ClaudeProcessState with PID: {:?}", pid);
 
-            // Kill the process
-            match child.kill().await {
-                Ok(_) => {
-                    log::info!("Successfully killed Claude process via ClaudeProcessState");
+            // Interrupt first so Claude can flush its session file, then escalate
+            let result = match pid {
+                Some(pid) => {
+                    crate::process::terminate::terminate(
+                        pid,
+                        crate::process::terminate::grace_period(),
+                    )
+                    .await
+                }
+                None => child
+                    .kill()
+                    .await
+                    .map(|_| crate::process::terminate::Termination::Forced)
+                    .map_err(|e| e.to_string()),
+            };
+            match result {
+                Ok(termination) => {
+                    log::info!("Stopped Claude process via ClaudeProcessState: {:?}", termination);
+                    // Reap the child so it does not linger as a zombie
+                    let _ = child.try_wait();
                     killed = true;
                 }
                 Err(e) => {
                     log::error!("Failed to kill Claude process via ClaudeProcessState: {}", e);
-                    
-                    // Method 3: If we have a PID, try system kill as last resort
-                    if let Some(pid) = pid {
-                        log::info!("Attempting system kill as last resort for PID: {}", pid);
-                        let kill_result = if cfg!(target_os = "windows") {
-                            std::process::Command::new("taskkill")
-                                .args(["/F", "/PID", &pid.to_string()])
-                                .output()
-                        } else {
-                            std::process::Command::new("kill")
-                                .args(["-KILL", &pid.to_string()])
-                                .output()
-                        };
-                        
-                        match kill_result {
-                            Ok(output) if output.status.success() => {
-                                log::info!("Successfully killed process via system command");
-                                killed = true;
-                            }
-                            Ok(output) => {
-                                let stderr = String::from_utf8_lossy(&output.stderr);
-                                log::error!("System kill failed: {}", stderr);
-                            }
-                            Err(e) => {
-                                log::error!("Failed to execute system kill command: {}", e);
-                            }
-                        }
-                    }
                 }
             }
             attempted_methods.push("claude_state");
@@ -1167,6 +1380,7 @@ pub async fn cancel_claude_execution(
 
 /// Get all running Claude sessions
 #[tauri::command]
+#[specta::specta]
 pub async fn list_running_claude_sessions(
     registry: tauri::State<'_, crate::process::ProcessRegistryState>,
 ) -> Result<Vec<crate::process::ProcessInfo>, String> {
@@ -1175,6 +1389,7 @@ pub async fn list_running_claude_sessions(
 
 /// Get live output from a Claude session
 #[tauri::command]
+#[specta::specta]
 pub async fn get_claude_session_output(
     registry: tauri::State<'_, crate::process::ProcessRegistryState>,
     session_id: String,
@@ -1188,7 +1403,7 @@ pub async fn get_claude_session_output(
 }
 
 /// Helper function to spawn Claude process and handle streaming
-async fn spawn_claude_process(app: AppHandle, mut cmd: Command, prompt: String, model: String, project_path: String) -> Result<(), String> {
+async fn spawn_claude_process(app: AppHandle, mut cmd: Command, prompt: String, model: String, project_path: String, budget: RunBudget) -> Result<(), String> {
     use tokio::io::{AsyncBufReadExt, BufReader};
     use std::sync::Mutex;
 
@@ -1207,6 +1422,8 @@ async fn spawn_claude_process(app: AppHandle, mut cmd: Command, prompt: String,
         "Spawned Claude process with PID: {:?}",
         pid
     );
+    crate::process::tree::adopt(pid);
+    super::orphans::record_session_spawn(&app, pid, &project_path);
 
     // Create readers first (before moving child)
     let stdout_reader = BufReader::new(stdout);
@@ -1237,10 +1454,21 @@ async fn spawn_claude_process(app: AppHandle, mut cmd: Command, prompt: String,
     let project_path_clone = project_path.clone();
     let prompt_clone = prompt.clone();
     let model_clone = model.clone();
+    let live_cost =
+        LiveMetricsTracker::start_session(app.clone(), model.clone(), budget, session_id_holder.clone());
+    let live_cost_clone = live_cost.clone();
+    let model_for_trace = model.clone();
+    let project_path_for_trace = project_path.clone();
+    let sequence = OutputSequence::default();
+    let stdout_sequence = sequence.clone();
     let stdout_task = tokio::spawn(async move {
         let mut lines = stdout_reader.lines();
         while let Ok(Some(line)) = lines.next_line().await {
             log::debug!("Claude stdout: {}", line);
+            let tagged = stdout_sequence.tag("stdout", &line);
+            if let Some(reason) = live_cost_clone.observe_line(&line) {
+                stop_session_over_budget(&app_handle, &session_id_holder_clone, pid, &reason);
+            }
             
             // Parse the line to check for init message with session ID
             if let Ok(msg) = serde_json::from_str::<serde_json::Value>(&line) {
@@ -1250,6 +1478,7 @@ async fn spawn_claude_process(app: AppHandle, mut cmd: Command, prompt: String,
                         if session_id_guard.is_none() {
                             *session_id_guard = Some(claude_session_id.to_string());
                             log::info!("Extracted Claude session ID: {}", claude_session_id);
+                            super::orphans::record_session_id(&app_handle, pid, claude_session_id);
                             
                             // Now register with ProcessRegistry using Claude's session ID
                             match registry_clone.register_claude_session(
@@ -1261,6 +1490,7 @@ async fn spawn_claude_process(app: AppHandle, mut cmd: Command, prompt: String,
                             ) {
                                 Ok(run_id) => {
                                     log::info!("Registered Claude session with run_id: {}", run_id);
+                                    live_cost_clone.attach_run(run_id);
                                     let mut run_id_guard = run_id_holder_clone.lock().unwrap();
                                     *run_id_guard = Some(run_id);
                                 }
@@ -1277,10 +1507,16 @@ async fn spawn_claude_process(app: AppHandle, mut cmd: Command, prompt: String,
             if let Some(run_id) = *run_id_holder_clone.lock().unwrap() {
                 let _ = registry_clone.append_live_output(run_id, &line);
             }
+
+            let session_id = session_id_holder_clone.lock().unwrap().clone();
+            if let Some(session_id) = session_id {
+                auto_checkpoint_on_event(&app_handle, &session_id, &project_path_clone, &line).await;
+            }
             
             // Emit the line to the frontend with session isolation if we have session ID
             if let Some(ref session_id) = *session_id_holder_clone.lock().unwrap() {
                 let _ = app_handle.emit(&format!("claude-output:{}", session_id), &line);
+                emit_session_stream(&app_handle, session_id, &tagged);
             }
             // Also emit to the generic event for backward compatibility
             let _ = app_handle.emit("claude-output", &line);
@@ -1291,15 +1527,24 @@ async fn spawn_claude_process(app: AppHandle, mut cmd: Command, prompt: String,
     let session_id_holder_clone2 = session_id_holder.clone();
     let stderr_task = tokio::spawn(async move {
         let mut lines = stderr_reader.lines();
+        let mut error_log = SessionErrorLog::new(app_handle_stderr.clone());
         while let Ok(Some(line)) = lines.next_line().await {
             log::error!("Claude stderr: {}", line);
+            let tagged = sequence.tag("stderr", &line);
+            let session_id = session_id_holder_clone2.lock().unwrap().clone();
             // Emit error lines to the frontend with session isolation if we have session ID
-            if let Some(ref session_id) = *session_id_holder_clone2.lock().unwrap() {
+            if let Some(ref session_id) = session_id {
                 let _ = app_handle_stderr.emit(&format!("claude-error:{}", session_id), &line);
             }
+            for tagged in error_log.record(session_id.as_deref(), tagged) {
+                if let Some(ref session_id) = session_id {
+                    emit_session_stream(&app_handle_stderr, session_id, &tagged);
+                }
+            }
             // Also emit to the generic event for backward compatibility
             let _ = app_handle_stderr.emit("claude-error", &line);
         }
+        error_log
     });
 
     // Wait for the process to complete
@@ -1310,7 +1555,17 @@ async fn spawn_claude_process(app: AppHandle, mut cmd: Command, prompt: String,
     let registry_clone2 = registry.0.clone();
     tokio::spawn(async move {
         let _ = stdout_task.await;
-        let _ = stderr_task.await;
+        if let Ok(mut error_log) = stderr_task.await {
+            // Stderr written before the init message is stored once the session ID is known
+            let session_id = session_id_holder_clone3.lock().unwrap().clone();
+            if let Some(session_id) = session_id {
+                for tagged in error_log.flush(&session_id) {
+                    emit_session_stream(&app_handle_wait, &session_id, &tagged);
+                }
+            }
+        }
+        let metrics = live_cost.finish();
+        super::telemetry::record_claude_execution(&model_for_trace, &project_path_for_trace, &metrics);
 
         // Get the child from the state to wait on it
         let mut current_process = claude_state_wait.lock().await;
@@ -1355,6 +1610,22 @@ async fn spawn_claude_process(app: AppHandle, mut cmd: Command, prompt: String,
     Ok(())
 }
 
+/// Terminate a session that went over its budget and tell the frontend why
+fn stop_session_over_budget(
+    app: &AppHandle,
+    session_id: &std::sync::Mutex<Option<String>>,
+    pid: u32,
+    reason: &str,
+) {
+    log::warn!("Stopping Claude session: {}", reason);
+    terminate_process(pid);
+
+    if let Some(ref session_id) = *session_id.lock().unwrap() {
+        let _ = app.emit(&format!("claude-budget-exceeded:{}", session_id), reason);
+    }
+    let _ = app.emit("claude-budget-exceeded", reason);
+}
+
 /// Helper function to spawn Claude sidecar process and handle streaming
 async fn spawn_claude_sidecar(
     app: AppHandle,
@@ -1362,6 +1633,7 @@ async fn spawn_claude_sidecar(
     prompt: String,
     model: String,
     project_path: String,
+    budget: RunBudget,
 ) -> Result<(), String> {
     use std::sync::Mutex;
 
@@ -1376,6 +1648,8 @@ async fn spawn_claude_sidecar(
     // Get the child PID for logging
     let pid = child.pid();
     log::info!("Spawned Claude sidecar process with PID: {:?}", pid);
+    crate::process::tree::adopt(pid);
+    super::orphans::record_session_spawn(&app, pid, &project_path);
 
     // We'll extract the session ID from Claude's init message
     let session_id_holder: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(None));
@@ -1392,8 +1666,14 @@ async fn spawn_claude_sidecar(
     let app_handle = app.clone();
     let session_id_holder_clone = session_id_holder.clone();
     let run_id_holder_clone = run_id_holder.clone();
+    let live_cost_clone =
+        LiveMetricsTracker::start_session(app.clone(), model.clone(), budget, session_id_holder.clone());
+    let model_for_trace = model.clone();
+    let project_path_for_trace = project_path.clone();
     
     tauri::async_runtime::spawn(async move {
+        let sequence = OutputSequence::default();
+        let mut error_log = SessionErrorLog::new(app_handle.clone());
         while let Some(event) = rx.recv().await {
             match event {
                 CommandEvent::Stdout(line_bytes) => {
@@ -1402,6 +1682,9 @@ async fn spawn_claude_sidecar(
                     
                     if !line_str.is_empty() {
                         log::debug!("Claude sidecar stdout: {}", line_str);
+                        if let Some(reason) = live_cost_clone.observe_line(line_str) {
+                            stop_session_over_budget(&app_handle, &session_id_holder_clone, pid, &reason);
+                        }
                         
                         // Parse the line to check for init message with session ID
                         if let Ok(msg) = serde_json::from_str::<serde_json::Value>(line_str) {
@@ -1411,6 +1694,7 @@ async fn spawn_claude_sidecar(
                                     if session_id_guard.is_none() {
                                         *session_id_guard = Some(claude_session_id.to_string());
                                         log::info!("Extracted Claude session ID: {}", claude_session_id);
+                                        super::orphans::record_session_id(&app_handle, pid, claude_session_id);
                                         
                                         // Register with ProcessRegistry using Claude's session ID
                                         match registry_clone.register_claude_session(
@@ -1422,6 +1706,7 @@ async fn spawn_claude_sidecar(
                                         ) {
                                             Ok(run_id) => {
                                                 log::info!("Registered Claude sidecar session with run_id: {}", run_id);
+                                                live_cost_clone.attach_run(run_id);
                                                 let mut run_id_guard = run_id_holder_clone.lock().unwrap();
                                                 *run_id_guard = Some(run_id);
                                             }
@@ -1438,10 +1723,17 @@ async fn spawn_claude_sidecar(
                         if let Some(run_id) = *run_id_holder_clone.lock().unwrap() {
                             let _ = registry_clone.append_live_output(run_id, line_str);
                         }
+
+                        let session_id = session_id_holder_clone.lock().unwrap().clone();
+                        if let Some(session_id) = session_id {
+                            auto_checkpoint_on_event(&app_handle, &session_id, &project_path_clone, line_str).await;
+                        }
                         
                         // Emit the line to the frontend with session isolation if we have session ID
+                        let tagged = sequence.tag("stdout", line_str);
                         if let Some(ref session_id) = *session_id_holder_clone.lock().unwrap() {
                             let _ = app_handle.emit(&format!("claude-output:{}", session_id), line_str);
+                            emit_session_stream(&app_handle, session_id, &tagged);
                         }
                         // Also emit to the generic event for backward compatibility
                         let _ = app_handle.emit("claude-output", line_str);
@@ -1453,11 +1745,18 @@ async fn spawn_claude_sidecar(
                     
                     if !line_str.is_empty() {
                         log::error!("Claude sidecar stderr: {}", line_str);
+                        let tagged = sequence.tag("stderr", line_str);
+                        let session_id = session_id_holder_clone.lock().unwrap().clone();
                         
                         // Emit error lines to the frontend with session isolation if we have session ID
-                        if let Some(ref session_id) = *session_id_holder_clone.lock().unwrap() {
+                        if let Some(ref session_id) = session_id {
                             let _ = app_handle.emit(&format!("claude-error:{}", session_id), line_str);
                         }
+                        for tagged in error_log.record(session_id.as_deref(), tagged) {
+                            if let Some(ref session_id) = session_id {
+                                emit_session_stream(&app_handle, session_id, &tagged);
+                            }
+                        }
                         // Also emit to the generic event for backward compatibility
                         let _ = app_handle.emit("claude-error", line_str);
                     }
@@ -1469,7 +1768,16 @@ async fn spawn_claude_sidecar(
                     tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
                     
                     let success = payload.code.unwrap_or(-1) == 0;
+
+                    let session_id = session_id_holder_clone.lock().unwrap().clone();
+                    if let Some(session_id) = session_id {
+                        for tagged in error_log.flush(&session_id) {
+                            emit_session_stream(&app_handle, &session_id, &tagged);
+                        }
+                    }
                     
+                    let metrics = live_cost_clone.finish();
+                    super::telemetry::record_claude_execution(&model_for_trace, &project_path_for_trace, &metrics);
                     if let Some(ref session_id) = *session_id_holder_clone.lock().unwrap() {
                         let _ = app_handle.emit(&format!("claude-complete:{}", session_id), success);
                     }
@@ -1496,6 +1804,7 @@ async fn spawn_claude_sidecar(
 
 /// Lists files and directories in a given path
 #[tauri::command]
+#[specta::specta]
 pub async fn list_directory_contents(directory_path: String) -> Result<Vec<FileEntry>, String> {
     log::info!("Listing directory contents: '{}'", directory_path);
 
@@ -1573,6 +1882,7 @@ pub async fn list_directory_contents(directory_path: String) -> Result<Vec<FileE
 
 /// Search for files and directories matching a pattern
 #[tauri::command]
+#[specta::specta]
 pub async fn search_files(base_path: String, query: String) -> Result<Vec<FileEntry>, String> {
     log::info!("Searching files in '{}' for: '{}'", base_path, query);
 
@@ -1688,8 +1998,74 @@ fn search_files_recursive(
     Ok(())
 }
 
+/// Read a session's JSONL transcript, up to and including `message_index`
+fn read_session_messages(
+    project_id: &str,
+    session_id: &str,
+    message_index: Option<usize>,
+) -> Result<Vec<String>, String> {
+    let session_path = get_claude_dir()
+        .map_err(|e| e.to_string())?
+        .join("projects")
+        .join(project_id)
+        .join(format!("{}.jsonl", session_id));
+    if !session_path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let file = fs::File::open(&session_path)
+        .map_err(|e| format!("Failed to open session file: {}", e))?;
+    let lines = BufReader::new(file).lines().map_while(Result::ok);
+    Ok(match message_index {
+        Some(index) => lines.take(index + 1).collect(),
+        None => lines.collect(),
+    })
+}
+
+/// Create a checkpoint when a streamed event matches the session's auto-checkpoint strategy
+///
+/// Only sessions whose checkpoints were opened have a manager, and so settings; streaming
+/// never creates checkpoint storage on its own.
+async fn auto_checkpoint_on_event(app: &AppHandle, session_id: &str, project_path: &str, line: &str) {
+    let state = app.state::<crate::checkpoint::state::CheckpointState>();
+    let Some(manager) = state.get_manager(session_id).await else {
+        return;
+    };
+    let project_id = project_path.replace('/', "-");
+    if !manager.observe_stream_event(line).await {
+        return;
+    }
+
+    // Snapshotting walks the whole project, so keep it off the output stream
+    let app = app.clone();
+    let session_id = session_id.to_string();
+    tauri::async_runtime::spawn(async move {
+        let result = match read_session_messages(&project_id, &session_id, None) {
+            Ok(messages) => match manager.set_messages(messages).await {
+                Ok(()) => manager
+                    .create_checkpoint(Some("Automatic checkpoint".to_string()), None)
+                    .await
+                    .map_err(|e| e.to_string()),
+                Err(e) => Err(e.to_string()),
+            },
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(result) => {
+                log::info!("Created automatic checkpoint {}", result.checkpoint.id);
+                let _ = app.emit(&format!("checkpoint-created:{}", session_id), &result);
+                let _ = app.emit("checkpoint-created", &result);
+                super::checkpoint_retention::auto_cleanup_session(&app, &project_id, &session_id)
+                    .await;
+            }
+            Err(e) => log::error!("Failed to create automatic checkpoint: {}", e),
+        }
+    });
+}
+
 /// Creates a checkpoint for the current session state
 #[tauri::command]
+#[specta::specta]
 pub async fn create_checkpoint(
     app: tauri::State<'_, crate::checkpoint::state::CheckpointState>,
     session_id: String,
@@ -1714,53 +2090,39 @@ pub async fn create_checkpoint(
         .map_err(|e| format!("Failed to get checkpoint manager: {}", e))?;
 
     // Always load current session messages from the JSONL file
-    let session_path = get_claude_dir()
-        .map_err(|e| e.to_string())?
-        .join("projects")
-        .join(&project_id)
-        .join(format!("{}.jsonl", session_id));
-
-    if session_path.exists() {
-        let file = fs::File::open(&session_path)
-            .map_err(|e| format!("Failed to open session file: {}", e))?;
-        let reader = BufReader::new(file);
-
-        let mut line_count = 0;
-        for line in reader.lines() {
-            if let Some(index) = message_index {
-                if line_count > index {
-                    break;
-                }
-            }
-            if let Ok(line) = line {
-                manager
-                    .track_message(line)
-                    .await
-                    .map_err(|e| format!("Failed to track message: {}", e))?;
-            }
-            line_count += 1;
-        }
-    }
-
+    let messages = read_session_messages(&project_id, &session_id, message_index)?;
     manager
-        .create_checkpoint(description, None)
+        .set_messages(messages)
         .await
-        .map_err(|e| format!("Failed to create checkpoint: {}", e))
+        .map_err(|e| format!("Failed to track message: {}", e))?;
+
+    let span = super::telemetry::Span::start("checkpoint.create")
+        .attr("claudia.session.id", session_id.clone());
+    span.end_with(
+        manager
+            .create_checkpoint(description, None)
+            .await
+            .map_err(|e| format!("Failed to create checkpoint: {}", e)),
+    )
 }
 
 /// Restores a session to a specific checkpoint
 #[tauri::command]
+#[specta::specta]
 pub async fn restore_checkpoint(
     app: tauri::State<'_, crate::checkpoint::state::CheckpointState>,
     checkpoint_id: String,
     session_id: String,
     project_id: String,
     project_path: String,
+    mode: Option<crate::checkpoint::RestoreMode>,
 ) -> Result<crate::checkpoint::CheckpointResult, String> {
+    let mode = mode.unwrap_or_default();
     log::info!(
-        "Restoring checkpoint: {} for session: {}",
+        "Restoring checkpoint: {} for session: {} ({:?})",
         checkpoint_id,
-        session_id
+        session_id,
+        mode
     );
 
     let manager = app
@@ -1772,10 +2134,18 @@ pub async fn restore_checkpoint(
         .await
         .map_err(|e| format!("Failed to get checkpoint manager: {}", e))?;
 
-    let result = manager
-        .restore_checkpoint(&checkpoint_id)
-        .await
-        .map_err(|e| format!("Failed to restore checkpoint: {}", e))?;
+    let span = super::telemetry::Span::start("checkpoint.restore")
+        .attr("claudia.session.id", session_id.clone())
+        .attr("claudia.checkpoint.id", checkpoint_id.clone());
+    let result = span.end_with(
+        manager
+            .restore_checkpoint(&checkpoint_id, mode)
+            .await
+            .map_err(|e| format!("Failed to restore checkpoint: {}", e)),
+    )?;
+    if !mode.restores_conversation() {
+        return Ok(result);
+    }
 
     // Update the session JSONL file with restored messages
     let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
@@ -1799,6 +2169,7 @@ pub async fn restore_checkpoint(
 
 /// Lists all checkpoints for a session
 #[tauri::command]
+#[specta::specta]
 pub async fn list_checkpoints(
     app: tauri::State<'_, crate::checkpoint::state::CheckpointState>,
     session_id: String,
@@ -1821,6 +2192,7 @@ pub async fn list_checkpoints(
 
 /// Forks a new timeline branch from a checkpoint
 #[tauri::command]
+#[specta::specta]
 pub async fn fork_from_checkpoint(
     app: tauri::State<'_, crate::checkpoint::state::CheckpointState>,
     checkpoint_id: String,
@@ -1838,39 +2210,60 @@ pub async fn fork_from_checkpoint(
 
     let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
 
-    // First, copy the session file to the new session
-    let source_session_path = claude_dir
-        .join("projects")
-        .join(&project_id)
-        .join(format!("{}.jsonl", session_id));
+    // Create manager for the new session
+    let manager = app
+        .get_or_create_manager(
+            new_session_id.clone(),
+            project_id.clone(),
+            PathBuf::from(&project_path),
+        )
+        .await
+        .map_err(|e| format!("Failed to get checkpoint manager: {}", e))?;
+
+    let result = manager
+        .fork_from_checkpoint(&session_id, &checkpoint_id, description)
+        .await
+        .map_err(|e| format!("Failed to fork checkpoint: {}", e))?;
+
+    // The new session's transcript ends where the checkpoint does
+    let (_, _, messages) = manager
+        .storage
+        .load_checkpoint(&project_id, &session_id, &checkpoint_id)
+        .map_err(|e| format!("Failed to load checkpoint data: {}", e))?;
     let new_session_path = claude_dir
         .join("projects")
         .join(&project_id)
         .join(format!("{}.jsonl", new_session_id));
+    fs::write(&new_session_path, messages)
+        .map_err(|e| format!("Failed to write forked session file: {}", e))?;
 
-    if source_session_path.exists() {
-        fs::copy(&source_session_path, &new_session_path)
-            .map_err(|e| format!("Failed to copy session file: {}", e))?;
-    }
+    Ok(result)
+}
 
-    // Create manager for the new session
+/// Gets the checkpoints from the root of a session's timeline down to a checkpoint
+#[tauri::command]
+#[specta::specta]
+pub async fn get_checkpoint_path(
+    app: tauri::State<'_, crate::checkpoint::state::CheckpointState>,
+    checkpoint_id: String,
+    session_id: String,
+    project_id: String,
+    project_path: String,
+) -> Result<Vec<crate::checkpoint::Checkpoint>, String> {
     let manager = app
-        .get_or_create_manager(
-            new_session_id.clone(),
-            project_id,
-            PathBuf::from(&project_path),
-        )
+        .get_or_create_manager(session_id, project_id, PathBuf::from(&project_path))
         .await
         .map_err(|e| format!("Failed to get checkpoint manager: {}", e))?;
 
     manager
-        .fork_from_checkpoint(&checkpoint_id, description)
+        .checkpoint_path(&checkpoint_id)
         .await
-        .map_err(|e| format!("Failed to fork checkpoint: {}", e))
+        .ok_or_else(|| format!("Checkpoint not found in timeline: {}", checkpoint_id))
 }
 
 /// Gets the timeline for a session
 #[tauri::command]
+#[specta::specta]
 pub async fn get_session_timeline(
     app: tauri::State<'_, crate::checkpoint::state::CheckpointState>,
     session_id: String,
@@ -1893,6 +2286,7 @@ pub async fn get_session_timeline(
 
 /// Updates checkpoint settings for a session
 #[tauri::command]
+#[specta::specta]
 pub async fn update_checkpoint_settings(
     app: tauri::State<'_, crate::checkpoint::state::CheckpointState>,
     session_id: String,
@@ -1900,6 +2294,7 @@ pub async fn update_checkpoint_settings(
     project_path: String,
     auto_checkpoint_enabled: bool,
     checkpoint_strategy: String,
+    token_threshold: Option<u64>,
 ) -> Result<(), String> {
     use crate::checkpoint::CheckpointStrategy;
 
@@ -1910,6 +2305,7 @@ pub async fn update_checkpoint_settings(
         "per_prompt" => CheckpointStrategy::PerPrompt,
         "per_tool_use" => CheckpointStrategy::PerToolUse,
         "smart" => CheckpointStrategy::Smart,
+        "token_threshold" => CheckpointStrategy::TokenThreshold,
         _ => {
             return Err(format!(
                 "Invalid checkpoint strategy: {}",
@@ -1924,18 +2320,31 @@ pub async fn update_checkpoint_settings(
         .map_err(|e| format!("Failed to get checkpoint manager: {}", e))?;
 
     manager
-        .update_settings(auto_checkpoint_enabled, strategy)
+        .update_settings(auto_checkpoint_enabled, strategy, token_threshold)
         .await
         .map_err(|e| format!("Failed to update settings: {}", e))
 }
 
 /// Gets diff between two checkpoints
 #[tauri::command]
+#[specta::specta]
 pub async fn get_checkpoint_diff(
     from_checkpoint_id: String,
     to_checkpoint_id: String,
     session_id: String,
     project_id: String,
+) -> Result<crate::checkpoint::CheckpointDiff, String> {
+    diff_checkpoints(from_checkpoint_id, to_checkpoint_id, session_id, project_id).await
+}
+
+/// Compares two checkpoints, with unified diffs of the modified files
+#[tauri::command]
+#[specta::specta]
+pub async fn diff_checkpoints(
+    from_checkpoint_id: String,
+    to_checkpoint_id: String,
+    session_id: String,
+    project_id: String,
 ) -> Result<crate::checkpoint::CheckpointDiff, String> {
     use crate::checkpoint::storage::CheckpointStorage;
 
@@ -1946,78 +2355,70 @@ pub async fn get_checkpoint_diff(
     );
 
     let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
-    let storage = CheckpointStorage::new(claude_dir);
-
-    // Load both checkpoints
-    let (from_checkpoint, from_files, _) = storage
-        .load_checkpoint(&project_id, &session_id, &from_checkpoint_id)
-        .map_err(|e| format!("Failed to load source checkpoint: {}", e))?;
-    let (to_checkpoint, to_files, _) = storage
-        .load_checkpoint(&project_id, &session_id, &to_checkpoint_id)
-        .map_err(|e| format!("Failed to load target checkpoint: {}", e))?;
-
-    // Build file maps
-    let mut from_map: std::collections::HashMap<PathBuf, &crate::checkpoint::FileSnapshot> =
-        std::collections::HashMap::new();
-    for file in &from_files {
-        from_map.insert(file.file_path.clone(), file);
-    }
+    CheckpointStorage::new(claude_dir)
+        .diff_checkpoints(
+            &project_id,
+            &session_id,
+            &from_checkpoint_id,
+            &to_checkpoint_id,
+        )
+        .map_err(|e| format!("Failed to diff checkpoints: {:#}", e))
+}
 
-    let mut to_map: std::collections::HashMap<PathBuf, &crate::checkpoint::FileSnapshot> =
-        std::collections::HashMap::new();
-    for file in &to_files {
-        to_map.insert(file.file_path.clone(), file);
-    }
+/// Exports a checkpoint with its files and transcript as a zip archive
+#[tauri::command]
+#[specta::specta]
+pub async fn export_checkpoint(
+    checkpoint_id: String,
+    session_id: String,
+    project_id: String,
+    file_path: String,
+) -> Result<(), String> {
+    use crate::checkpoint::storage::CheckpointStorage;
 
-    // Calculate differences
-    let mut modified_files = Vec::new();
-    let mut added_files = Vec::new();
-    let mut deleted_files = Vec::new();
-
-    // Check for modified and deleted files
-    for (path, from_file) in &from_map {
-        if let Some(to_file) = to_map.get(path) {
-            if from_file.hash != to_file.hash {
-                // File was modified
-                let additions = to_file.content.lines().count();
-                let deletions = from_file.content.lines().count();
-
-                modified_files.push(crate::checkpoint::FileDiff {
-                    path: path.clone(),
-                    additions,
-                    deletions,
-                    diff_content: None, // TODO: Generate actual diff
-                });
-            }
-        } else {
-            // File was deleted
-            deleted_files.push(path.clone());
-        }
-    }
+    log::info!("Exporting checkpoint {} to {}", checkpoint_id, file_path);
 
-    // Check for added files
-    for (path, _) in &to_map {
-        if !from_map.contains_key(path) {
-            added_files.push(path.clone());
-        }
-    }
+    let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
+    CheckpointStorage::new(claude_dir)
+        .export_checkpoint_archive(
+            &project_id,
+            &session_id,
+            &checkpoint_id,
+            std::path::Path::new(&file_path),
+        )
+        .map_err(|e| format!("Failed to export checkpoint: {:#}", e))
+}
 
-    // Calculate token delta
-    let token_delta = (to_checkpoint.metadata.total_tokens as i64)
-        - (from_checkpoint.metadata.total_tokens as i64);
+/// Imports an exported checkpoint archive into a session's timeline
+#[tauri::command]
+#[specta::specta]
+pub async fn import_checkpoint(
+    app: tauri::State<'_, crate::checkpoint::state::CheckpointState>,
+    session_id: String,
+    project_id: String,
+    file_path: String,
+) -> Result<crate::checkpoint::CheckpointResult, String> {
+    use crate::checkpoint::storage::CheckpointStorage;
 
-    Ok(crate::checkpoint::CheckpointDiff {
-        from_checkpoint_id,
-        to_checkpoint_id,
-        modified_files,
-        added_files,
-        deleted_files,
-        token_delta,
-    })
+    log::info!("Importing checkpoint from {} into session {}", file_path, session_id);
+
+    let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
+    let result = CheckpointStorage::new(claude_dir)
+        .import_checkpoint_archive(&project_id, &session_id, std::path::Path::new(&file_path))
+        .map_err(|e| format!("Failed to import checkpoint: {:#}", e))?;
+
+    if let Some(manager) = app.get_manager(&session_id).await {
+        manager
+            .reload_timeline()
+            .await
+            .map_err(|e| format!("Failed to reload timeline: {}", e))?;
+    }
+    Ok(result)
 }
 
 /// Tracks a message for checkpointing
 #[tauri::command]
+#[specta::specta]
 pub async fn track_checkpoint_message(
     app: tauri::State<'_, crate::checkpoint::state::CheckpointState>,
     session_id: String,
@@ -2040,6 +2441,7 @@ pub async fn track_checkpoint_message(
 
 /// Checks if auto-checkpoint should be triggered
 #[tauri::command]
+#[specta::specta]
 pub async fn check_auto_checkpoint(
     app: tauri::State<'_, crate::checkpoint::state::CheckpointState>,
     session_id: String,
@@ -2059,6 +2461,7 @@ pub async fn check_auto_checkpoint(
 
 /// Triggers cleanup of old checkpoints
 #[tauri::command]
+#[specta::specta]
 pub async fn cleanup_old_checkpoints(
     app: tauri::State<'_, crate::checkpoint::state::CheckpointState>,
     session_id: String,
@@ -2089,6 +2492,7 @@ pub async fn cleanup_old_checkpoints(
 
 /// Gets checkpoint settings for a session
 #[tauri::command]
+#[specta::specta]
 pub async fn get_checkpoint_settings(
     app: tauri::State<'_, crate::checkpoint::state::CheckpointState>,
     session_id: String,
@@ -2107,6 +2511,7 @@ pub async fn get_checkpoint_settings(
     Ok(serde_json::json!({
         "auto_checkpoint_enabled": timeline.auto_checkpoint_enabled,
         "checkpoint_strategy": timeline.checkpoint_strategy,
+        "token_threshold": timeline.token_threshold,
         "total_checkpoints": timeline.total_checkpoints,
         "current_checkpoint_id": timeline.current_checkpoint_id,
     }))
@@ -2114,6 +2519,7 @@ pub async fn get_checkpoint_settings(
 
 /// Clears checkpoint manager for a session (cleanup on session end)
 #[tauri::command]
+#[specta::specta]
 pub async fn clear_checkpoint_manager(
     app: tauri::State<'_, crate::checkpoint::state::CheckpointState>,
     session_id: String,
@@ -2126,6 +2532,7 @@ pub async fn clear_checkpoint_manager(
 
 /// Gets checkpoint state statistics (for debugging/monitoring)
 #[tauri::command]
+#[specta::specta]
 pub async fn get_checkpoint_state_stats(
     app: tauri::State<'_, crate::checkpoint::state::CheckpointState>,
 ) -> Result<serde_json::Value, String> {
@@ -2140,6 +2547,7 @@ pub async fn get_checkpoint_state_stats(
 
 /// Gets files modified in the last N minutes for a session
 #[tauri::command]
+#[specta::specta]
 pub async fn get_recently_modified_files(
     app: tauri::State<'_, crate::checkpoint::state::CheckpointState>,
     session_id: String,
@@ -2176,6 +2584,7 @@ pub async fn get_recently_modified_files(
 
 /// Track session messages from the frontend for checkpointing
 #[tauri::command]
+#[specta::specta]
 pub async fn track_session_messages(
     state: tauri::State<'_, crate::checkpoint::state::CheckpointState>,
     session_id: String,
@@ -2210,6 +2619,7 @@ pub async fn track_session_messages(
 
 /// Gets hooks configuration from settings at specified scope
 #[tauri::command]
+#[specta::specta]
 pub async fn get_hooks_config(scope: String, project_path: Option<String>) -> Result<serde_json::Value, String> {
     log::info!("Getting hooks config for scope: {}, project: {:?}", scope, project_path);
 
@@ -2246,6 +2656,7 @@ pub async fn get_hooks_config(scope: String, project_path: Option<String>) -> Re
 
 /// Updates hooks configuration in settings at specified scope
 #[tauri::command]
+#[specta::specta]
 pub async fn update_hooks_config(
     scope: String, 
     hooks: serde_json::Value,
@@ -2289,18 +2700,14 @@ pub async fn update_hooks_config(
     // Update hooks section
     settings["hooks"] = hooks;
 
-    // Write back with pretty formatting
-    let json_string = serde_json::to_string_pretty(&settings)
-        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
-    
-    fs::write(&settings_path, json_string)
-        .map_err(|e| format!("Failed to write settings: {}", e))?;
+    write_settings(&settings_path, &settings)?;
 
     Ok("Hooks configuration updated successfully".to_string())
 }
 
 /// Validates a hook command by dry-running it
 #[tauri::command]
+#[specta::specta]
 pub async fn validate_hook_command(command: String) -> Result<serde_json::Value, String> {
     log::info!("Validating hook command syntax");
 
@@ -0,0 +1,693 @@
+use log::{error, info, warn};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+use std::future::Future;
+use std::sync::Mutex;
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Manager, State};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::oneshot;
+
+use super::agents::{execute_agent, get_agent_run, get_setting, list_agents, set_setting, AgentDb};
+use crate::process::ProcessRegistryState;
+
+/// Port used when the user hasn't picked one
+const DEFAULT_PORT: u16 = 8787;
+
+/// Largest request body accepted, to keep a misbehaving client from exhausting memory
+const MAX_BODY_LEN: usize = 1024 * 1024;
+
+/// Settings of the built-in MCP server, stored under `claudia_mcp_server` in app_settings
+#[derive(Debug, Serialize, Deserialize, Clone, specta::Type)]
+pub struct ClaudiaMcpServerSettings {
+    pub enabled: bool,
+    pub port: u16,
+    /// Bearer token clients must send; generated the first time the server is enabled
+    #[serde(default)]
+    pub token: String,
+}
+
+impl Default for ClaudiaMcpServerSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: DEFAULT_PORT,
+            token: String::new(),
+        }
+    }
+}
+
+/// Settings plus whether the server is currently listening
+#[derive(Debug, Serialize, Deserialize, specta::Type)]
+pub struct ClaudiaMcpServerStatus {
+    pub settings: ClaudiaMcpServerSettings,
+    pub running: bool,
+    pub url: String,
+    /// Command that registers the server with Claude Code
+    pub add_command: String,
+}
+
+struct RunningServer {
+    port: u16,
+    shutdown: oneshot::Sender<()>,
+    /// Finishes once the listener is closed and the port is free again
+    task: JoinHandle<()>,
+}
+
+/// Handle of the running MCP server, if any
+#[derive(Default)]
+pub struct ClaudiaMcpServerState(Mutex<Option<RunningServer>>);
+
+fn load_server_settings(conn: &Connection) -> ClaudiaMcpServerSettings {
+    get_setting(conn, "claudia_mcp_server").unwrap_or_default()
+}
+
+fn server_status(
+    settings: ClaudiaMcpServerSettings,
+    state: &ClaudiaMcpServerState,
+) -> ClaudiaMcpServerStatus {
+    let running = state
+        .0
+        .lock()
+        .map(|server| server.as_ref().is_some_and(|s| s.port == settings.port))
+        .unwrap_or(false);
+    let url = format!("http://127.0.0.1:{}/mcp", settings.port);
+    let add_command = format!(
+        "claude mcp add --transport http claudia {} --header \"Authorization: Bearer {}\"",
+        url, settings.token
+    );
+    ClaudiaMcpServerStatus {
+        settings,
+        running,
+        url,
+        add_command,
+    }
+}
+
+/// Tool definitions advertised in tools/list
+fn tool_definitions() -> JsonValue {
+    json!([
+        {
+            "name": "list_projects",
+            "description": "List the projects Claude Code has sessions for",
+            "inputSchema": { "type": "object", "properties": {} }
+        },
+        {
+            "name": "search_sessions",
+            "description": "Find sessions whose messages contain the given text",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string" },
+                    "limit": { "type": "integer", "description": "Maximum results, default 20" }
+                },
+                "required": ["query"]
+            }
+        },
+        {
+            "name": "list_agents",
+            "description": "List the agents configured in Claudia",
+            "inputSchema": { "type": "object", "properties": {} }
+        },
+        {
+            "name": "run_agent",
+            "description": "Start a Claudia agent on a project; returns the run id",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "agent_id": { "type": "integer" },
+                    "project_path": { "type": "string" },
+                    "task": { "type": "string" },
+                    "model": { "type": "string" }
+                },
+                "required": ["agent_id", "project_path", "task"]
+            }
+        },
+        {
+            "name": "get_agent_run",
+            "description": "Get the status of an agent run",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "run_id": { "type": "integer" } },
+                "required": ["run_id"]
+            }
+        },
+        {
+            "name": "list_issues",
+            "description": "List open GitHub issues of a project using the gh CLI",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "project_path": { "type": "string" },
+                    "limit": { "type": "integer", "description": "Maximum issues, default 30" }
+                },
+                "required": ["project_path"]
+            }
+        }
+    ])
+}
+
+/// A session matching a search, with the text around the first hit
+#[derive(Debug, Serialize)]
+struct SessionMatch {
+    project_id: String,
+    project_path: Option<String>,
+    session_id: String,
+    snippet: String,
+}
+
+/// Text of a transcript line's message, whether content is a string or blocks
+fn message_text(json: &JsonValue) -> Option<String> {
+    let content = json.pointer("/message/content")?;
+    if let Some(text) = content.as_str() {
+        return Some(text.to_string());
+    }
+    let text: Vec<&str> = content
+        .as_array()?
+        .iter()
+        .filter_map(|block| block.get("text").and_then(|t| t.as_str()))
+        .collect();
+    (!text.is_empty()).then(|| text.join("\n"))
+}
+
+fn search_sessions(query: &str, limit: usize) -> Result<Vec<SessionMatch>, String> {
+    let projects_dir = dirs::home_dir()
+        .ok_or("Could not find home directory")?
+        .join(".claude")
+        .join("projects");
+    let needle = query.to_lowercase();
+    let mut matches = Vec::new();
+
+    let projects = std::fs::read_dir(&projects_dir)
+        .map_err(|e| format!("Failed to read projects directory: {}", e))?;
+    for project in projects.flatten() {
+        let project_id = project.file_name().to_string_lossy().to_string();
+        let Ok(sessions) = std::fs::read_dir(project.path()) else {
+            continue;
+        };
+        for session in sessions.flatten() {
+            let path = session.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("jsonl") {
+                continue;
+            }
+            let Ok(content) = super::jsonl_reader::JsonlFile::open(&path) else {
+                continue;
+            };
+
+            let mut project_path = None;
+            for line in content.lines() {
+                let Ok(json) = serde_json::from_str::<JsonValue>(&line) else {
+                    continue;
+                };
+                if project_path.is_none() {
+                    project_path = json
+                        .get("cwd")
+                        .and_then(|c| c.as_str())
+                        .map(|s| s.to_string());
+                }
+                let Some(text) = message_text(&json) else {
+                    continue;
+                };
+                let lower = text.to_lowercase();
+                // Lowercasing can change byte offsets, so only use them when it didn't
+                let Some(pos) = lower.find(&needle) else {
+                    continue;
+                };
+                let snippet = if lower.len() == text.len() {
+                    let start = char_boundary_before(&text, pos.saturating_sub(80));
+                    let end =
+                        char_boundary_before(&text, (pos + needle.len() + 80).min(text.len()));
+                    text[start..end].to_string()
+                } else {
+                    text.chars().take(160).collect()
+                };
+                matches.push(SessionMatch {
+                    project_id: project_id.clone(),
+                    project_path: project_path.clone(),
+                    session_id: path
+                        .file_stem()
+                        .map(|s| s.to_string_lossy().to_string())
+                        .unwrap_or_default(),
+                    snippet,
+                });
+                break;
+            }
+            if matches.len() >= limit {
+                return Ok(matches);
+            }
+        }
+    }
+    Ok(matches)
+}
+
+/// Largest char boundary at or below `index`
+fn char_boundary_before(text: &str, mut index: usize) -> usize {
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+fn list_issues(project_path: &str, limit: u64) -> Result<JsonValue, String> {
+    let output = crate::claude_binary::create_command_with_env("gh")
+        .args([
+            "issue",
+            "list",
+            "--json",
+            "number,title,state,url,labels,updatedAt",
+            "--limit",
+            &limit.to_string(),
+        ])
+        .current_dir(project_path)
+        .output()
+        .map_err(|e| format!("Failed to run gh: {}", e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    serde_json::from_slice(&output.stdout).map_err(|e| format!("Failed to parse gh output: {}", e))
+}
+
+async fn call_tool(app: &AppHandle, name: &str, args: &JsonValue) -> Result<JsonValue, String> {
+    let string_arg = |key: &str| {
+        args.get(key)
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+            .ok_or_else(|| format!("Missing argument '{}'", key))
+    };
+    let int_arg = |key: &str| {
+        args.get(key)
+            .and_then(|v| v.as_i64())
+            .ok_or_else(|| format!("Missing argument '{}'", key))
+    };
+
+    match name {
+        "list_projects" => {
+            let projects = super::claude::list_projects(app.state()).await?;
+            serde_json::to_value(projects).map_err(|e| e.to_string())
+        }
+        "search_sessions" => {
+            let query = string_arg("query")?;
+            let limit = args.get("limit").and_then(|v| v.as_u64()).unwrap_or(20) as usize;
+            let matches = tokio::task::spawn_blocking(move || search_sessions(&query, limit))
+                .await
+                .map_err(|e| e.to_string())??;
+            serde_json::to_value(matches).map_err(|e| e.to_string())
+        }
+        "list_agents" => {
+            let agents = list_agents(app.state::<AgentDb>(), app.state()).await?;
+            serde_json::to_value(agents).map_err(|e| e.to_string())
+        }
+        "run_agent" => {
+            let run_id = execute_agent(
+                app.clone(),
+                int_arg("agent_id")?,
+                string_arg("project_path")?,
+                string_arg("task")?,
+                string_arg("model").ok(),
+                None,
+                None,
+                app.state::<AgentDb>(),
+                app.state::<ProcessRegistryState>(),
+            )
+            .await?;
+            Ok(json!({ "run_id": run_id }))
+        }
+        "get_agent_run" => {
+            let run = get_agent_run(app.state::<AgentDb>(), int_arg("run_id")?).await?;
+            serde_json::to_value(run).map_err(|e| e.to_string())
+        }
+        "list_issues" => {
+            let project_path = string_arg("project_path")?;
+            let limit = args.get("limit").and_then(|v| v.as_u64()).unwrap_or(30);
+            tokio::task::spawn_blocking(move || list_issues(&project_path, limit))
+                .await
+                .map_err(|e| e.to_string())?
+        }
+        _ => Err(format!("Unknown tool '{}'", name)),
+    }
+}
+
+/// Compare secrets in time independent of where they differ
+pub(crate) fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a
+            .bytes()
+            .zip(b.bytes())
+            .fold(0u8, |diff, (x, y)| diff | (x ^ y))
+            == 0
+}
+
+/// Whether an `Authorization` header carries `token`; an empty token matches nothing
+pub(crate) fn bearer_token_matches(authorization: Option<&str>, token: &str) -> bool {
+    !token.is_empty()
+        && authorization
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|given| constant_time_eq(given, token))
+}
+
+/// Handle one JSON-RPC message; notifications get no response
+///
+/// `call_tool` runs a tool by name, so the protocol handling can be tested without an app.
+async fn handle_rpc<F, Fut>(message: &JsonValue, call_tool: F) -> Option<JsonValue>
+where
+    F: FnOnce(String, JsonValue) -> Fut,
+    Fut: Future<Output = Result<JsonValue, String>>,
+{
+    let id = message.get("id")?.clone();
+    let method = message
+        .get("method")
+        .and_then(|m| m.as_str())
+        .unwrap_or_default();
+    let params = message.get("params").cloned().unwrap_or(JsonValue::Null);
+
+    let result = match method {
+        "initialize" => Ok(json!({
+            "protocolVersion": params
+                .get("protocolVersion")
+                .and_then(|v| v.as_str())
+                .unwrap_or("2025-03-26"),
+            "capabilities": { "tools": {} },
+            "serverInfo": { "name": "claudia", "version": env!("CARGO_PKG_VERSION") },
+        })),
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(json!({ "tools": tool_definitions() })),
+        "tools/call" => {
+            let name = params
+                .get("name")
+                .and_then(|n| n.as_str())
+                .unwrap_or_default()
+                .to_string();
+            let args = params.get("arguments").cloned().unwrap_or(json!({}));
+            info!("Claudia MCP server: calling tool {}", name);
+            // Tool failures are reported to the model as results, not protocol errors
+            Ok(match call_tool(name, args).await {
+                Ok(value) => json!({
+                    "content": [{ "type": "text", "text": serde_json::to_string_pretty(&value).unwrap_or_default() }],
+                    "isError": false,
+                }),
+                Err(e) => json!({ "content": [{ "type": "text", "text": e }], "isError": true }),
+            })
+        }
+        _ => Err(json!({ "code": -32601, "message": format!("Method not found: {}", method) })),
+    };
+
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(error) => json!({ "jsonrpc": "2.0", "id": id, "error": error }),
+    })
+}
+
+async fn write_response(stream: &mut TcpStream, status: &str, body: &str) -> std::io::Result<()> {
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.flush().await
+}
+
+/// Serve one HTTP request using the MCP streamable HTTP transport (JSON responses only)
+async fn handle_connection(
+    app: AppHandle,
+    mut stream: TcpStream,
+    token: String,
+) -> std::io::Result<()> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 8192];
+    let header_end = loop {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Ok(());
+        }
+        buffer.extend_from_slice(&chunk[..n]);
+        if let Some(pos) = buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos;
+        }
+        if buffer.len() > MAX_BODY_LEN {
+            return write_response(&mut stream, "431 Request Header Fields Too Large", "").await;
+        }
+    };
+
+    let head = String::from_utf8_lossy(&buffer[..header_end]).to_string();
+    let mut lines = head.lines();
+    let request_line = lines.next().unwrap_or_default();
+    let mut content_length = 0usize;
+    let mut authorization = None;
+    for line in lines {
+        if let Some((key, value)) = line.split_once(':') {
+            match key.trim().to_ascii_lowercase().as_str() {
+                "content-length" => content_length = value.trim().parse().unwrap_or(0),
+                "authorization" => authorization = Some(value.trim().to_string()),
+                _ => {}
+            }
+        }
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let (method, path) = (
+        parts.next().unwrap_or_default(),
+        parts.next().unwrap_or_default(),
+    );
+    if path.split('?').next() != Some("/mcp") {
+        return write_response(&mut stream, "404 Not Found", "").await;
+    }
+    if !bearer_token_matches(authorization.as_deref(), &token) {
+        return write_response(&mut stream, "401 Unauthorized", "").await;
+    }
+    if method != "POST" {
+        // No server-initiated messages, so there is no event stream to open
+        return write_response(&mut stream, "405 Method Not Allowed", "").await;
+    }
+    if content_length > MAX_BODY_LEN {
+        return write_response(&mut stream, "413 Payload Too Large", "").await;
+    }
+
+    let mut body = buffer[header_end + 4..].to_vec();
+    while body.len() < content_length {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            break;
+        }
+        body.extend_from_slice(&chunk[..n]);
+    }
+
+    let Ok(message) = serde_json::from_slice::<JsonValue>(&body) else {
+        let error = json!({ "jsonrpc": "2.0", "id": null, "error": { "code": -32700, "message": "Parse error" } });
+        return write_response(&mut stream, "400 Bad Request", &error.to_string()).await;
+    };
+
+    let run_tool = |name: String, args: JsonValue| {
+        let app = app.clone();
+        async move { call_tool(&app, &name, &args).await }
+    };
+    let response = match &message {
+        JsonValue::Array(batch) => {
+            let mut responses = Vec::new();
+            for message in batch {
+                if let Some(response) = handle_rpc(message, run_tool).await {
+                    responses.push(response);
+                }
+            }
+            (!responses.is_empty()).then_some(JsonValue::Array(responses))
+        }
+        message => handle_rpc(message, run_tool).await,
+    };
+
+    match response {
+        Some(response) => write_response(&mut stream, "200 OK", &response.to_string()).await,
+        None => write_response(&mut stream, "202 Accepted", "").await,
+    }
+}
+
+/// Start listening on localhost; replaces a server that is already running
+async fn start_server(app: &AppHandle, settings: &ClaudiaMcpServerSettings) -> Result<(), String> {
+    stop_server(app).await;
+
+    let listener = std::net::TcpListener::bind(("127.0.0.1", settings.port))
+        .map_err(|e| format!("Failed to listen on port {}: {}", settings.port, e))?;
+    listener.set_nonblocking(true).map_err(|e| e.to_string())?;
+    let listener = TcpListener::from_std(listener)
+        .map_err(|e| format!("Failed to start Claudia MCP server: {}", e))?;
+
+    let (shutdown, mut shutdown_rx) = oneshot::channel();
+    let server_app = app.clone();
+    let token = settings.token.clone();
+    let port = settings.port;
+    let task = tauri::async_runtime::spawn(async move {
+        info!("Claudia MCP server listening on 127.0.0.1:{}", port);
+        loop {
+            tokio::select! {
+                _ = &mut shutdown_rx => break,
+                accepted = listener.accept() => match accepted {
+                    Ok((stream, _)) => {
+                        let app = server_app.clone();
+                        let token = token.clone();
+                        tauri::async_runtime::spawn(async move {
+                            if let Err(e) = handle_connection(app, stream, token).await {
+                                warn!("Claudia MCP server connection failed: {}", e);
+                            }
+                        });
+                    }
+                    Err(e) => warn!("Claudia MCP server failed to accept a connection: {}", e),
+                },
+            }
+        }
+        info!("Claudia MCP server on port {} stopped", port);
+    });
+
+    let state = app.state::<ClaudiaMcpServerState>();
+    *state.0.lock().map_err(|e| e.to_string())? = Some(RunningServer {
+        port: settings.port,
+        shutdown,
+        task,
+    });
+    Ok(())
+}
+
+/// Stop the server and wait until its port is released
+async fn stop_server(app: &AppHandle) {
+    let state = app.state::<ClaudiaMcpServerState>();
+    let running = state.0.lock().ok().and_then(|mut server| server.take());
+    if let Some(server) = running {
+        let _ = server.shutdown.send(());
+        let _ = server.task.await;
+    }
+}
+
+/// Start the built-in MCP server at launch if the user enabled it
+pub fn start_claudia_mcp_server(app: AppHandle) {
+    let settings = {
+        let db = app.state::<AgentDb>();
+        let Ok(conn) = db.0.lock() else {
+            return;
+        };
+        load_server_settings(&conn)
+    };
+    if settings.enabled {
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = start_server(&app, &settings).await {
+                error!("{}", e);
+            }
+        });
+    }
+}
+
+/// Get the settings and state of the built-in MCP server
+#[tauri::command]
+#[specta::specta]
+pub async fn get_claudia_mcp_server(
+    db: State<'_, AgentDb>,
+    state: State<'_, ClaudiaMcpServerState>,
+) -> Result<ClaudiaMcpServerStatus, String> {
+    let settings = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        load_server_settings(&conn)
+    };
+    Ok(server_status(settings, &state))
+}
+
+/// Enable or disable the built-in MCP server, starting or stopping it right away
+#[tauri::command]
+#[specta::specta]
+pub async fn set_claudia_mcp_server(
+    app: AppHandle,
+    db: State<'_, AgentDb>,
+    state: State<'_, ClaudiaMcpServerState>,
+    enabled: bool,
+    port: Option<u16>,
+) -> Result<ClaudiaMcpServerStatus, String> {
+    let previous = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        load_server_settings(&conn)
+    };
+    let mut settings = previous.clone();
+    settings.enabled = enabled;
+    if let Some(port) = port {
+        settings.port = port;
+    }
+    if settings.token.is_empty() {
+        settings.token = uuid::Uuid::new_v4().simple().to_string();
+    }
+
+    // Only settings the server could actually start with are saved
+    if enabled {
+        if let Err(e) = start_server(&app, &settings).await {
+            if previous.enabled {
+                if let Err(e) = start_server(&app, &previous).await {
+                    error!("Failed to restart the previous Claudia MCP server: {}", e);
+                }
+            }
+            return Err(e);
+        }
+    } else {
+        stop_server(&app).await;
+    }
+
+    {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        set_setting(&conn, "claudia_mcp_server", &settings)
+            .map_err(|e| format!("Failed to save MCP server settings: {}", e))?;
+    }
+    Ok(server_status(settings, &state))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn no_tools(name: String, _args: JsonValue) -> Result<JsonValue, String> {
+        Err(format!("Unknown tool '{}'", name))
+    }
+
+    #[tokio::test]
+    async fn answers_protocol_requests() {
+        let initialize = json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": { "protocolVersion": "2025-06-18" } });
+        let response = handle_rpc(&initialize, no_tools).await.unwrap();
+        assert_eq!(response["id"], 1);
+        assert_eq!(response["result"]["protocolVersion"], "2025-06-18");
+        assert_eq!(response["result"]["serverInfo"]["name"], "claudia");
+
+        let list = json!({ "jsonrpc": "2.0", "id": "a", "method": "tools/list" });
+        let tools = handle_rpc(&list, no_tools).await.unwrap();
+        assert!(tools["result"]["tools"].as_array().unwrap().iter().any(|t| t["name"] == "run_agent"));
+
+        let unknown = json!({ "jsonrpc": "2.0", "id": 2, "method": "resources/list" });
+        assert_eq!(handle_rpc(&unknown, no_tools).await.unwrap()["error"]["code"], -32601);
+
+        // Notifications have no id and get no response
+        let initialized = json!({ "jsonrpc": "2.0", "method": "notifications/initialized" });
+        assert!(handle_rpc(&initialized, no_tools).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn reports_tool_results_and_failures() {
+        let call = |name: &str| json!({ "jsonrpc": "2.0", "id": 3, "method": "tools/call", "params": { "name": name, "arguments": { "x": 1 } } });
+        let echo = |name: String, args: JsonValue| async move {
+            match name.as_str() {
+                "echo" => Ok(args),
+                _ => Err("no such tool".to_string()),
+            }
+        };
+
+        let ok = handle_rpc(&call("echo"), echo).await.unwrap();
+        assert_eq!(ok["result"]["isError"], false);
+        assert!(ok["result"]["content"][0]["text"].as_str().unwrap().contains("\"x\": 1"));
+
+        let failed = handle_rpc(&call("missing"), echo).await.unwrap();
+        assert_eq!(failed["result"]["isError"], true);
+        assert_eq!(failed["result"]["content"][0]["text"], "no such tool");
+    }
+
+    #[test]
+    fn checks_the_bearer_token() {
+        assert!(bearer_token_matches(Some("Bearer abc123"), "abc123"));
+        assert!(!bearer_token_matches(Some("Bearer abc124"), "abc123"));
+        assert!(!bearer_token_matches(Some("abc123"), "abc123"));
+        assert!(!bearer_token_matches(None, "abc123"));
+        assert!(!bearer_token_matches(Some("Bearer "), ""));
+    }
+}
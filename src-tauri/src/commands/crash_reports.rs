@@ -0,0 +1,317 @@
+use log::{error, info, warn};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
+use super::agents::{get_setting, set_setting, AgentDb};
+
+const SETTINGS_KEY: &str = "crash_reporting";
+
+/// Where reports are submitted, set by release builds
+const CRASH_REPORT_URL: Option<&str> = option_env!("CLAUDIA_CRASH_REPORT_URL");
+
+/// Reports kept on disk; the oldest are removed beyond this
+const MAX_REPORTS: usize = 50;
+
+/// Submitted messages are cut to this length
+const MAX_MESSAGE_CHARS: usize = 1000;
+
+/// Quoted text longer than this is treated as user content, e.g. a prompt
+const MAX_QUOTED_CHARS: usize = 40;
+
+const SUBMIT_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, specta::Type)]
+pub struct CrashReportSettings {
+    /// The user agreed to submit scrubbed reports
+    pub submit_reports: bool,
+}
+
+/// A panic or reported error, written to `~/.claude/claudia/crash-reports`
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct CrashReport {
+    pub id: String,
+    /// "panic" or "error"
+    pub kind: String,
+    pub created_at: String,
+    pub app_version: String,
+    pub os: String,
+    pub arch: String,
+    pub thread: Option<String>,
+    pub message: String,
+    /// `file:line` of a panic
+    pub location: Option<String>,
+    pub backtrace: Option<String>,
+    #[serde(default)]
+    pub submitted: bool,
+}
+
+impl CrashReport {
+    fn new(kind: &str, message: String) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            kind: kind.to_string(),
+            created_at: chrono::Utc::now().to_rfc3339(),
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            thread: std::thread::current().name().map(str::to_string),
+            message,
+            location: None,
+            backtrace: None,
+            submitted: false,
+        }
+    }
+
+    /// The report as it would be submitted: without paths, user content or long text
+    fn scrubbed(&self) -> Self {
+        let home = dirs::home_dir().map(|home| home.to_string_lossy().to_string());
+        let scrub = |text: &str| scrub_text(text, home.as_deref());
+        let mut message = scrub(&self.message);
+        if message.chars().count() > MAX_MESSAGE_CHARS {
+            message = message.chars().take(MAX_MESSAGE_CHARS).collect::<String>() + "…";
+        }
+        Self {
+            message,
+            location: self.location.as_deref().map(scrub),
+            backtrace: self.backtrace.as_deref().map(scrub),
+            ..self.clone()
+        }
+    }
+}
+
+/// Replace the home directory, absolute paths and long quoted strings
+fn scrub_text(text: &str, home: Option<&str>) -> String {
+    let mut text = text.to_string();
+    if let Some(home) = home.filter(|home| home.len() > 1) {
+        text = text.replace(home, "~");
+    }
+    // Keep file names, which help locate the crash, but not the directories around them
+    let paths = regex::Regex::new(r#"(?:~|[A-Za-z]:)?(?:[/\\][^/\\\s"'`:()]+)+[/\\]([^/\\\s"'`:()]+)"#)
+        .expect("valid path pattern");
+    text = paths.replace_all(&text, "<path>/$1").to_string();
+    let quoted = regex::Regex::new(r#""[^"]*"|'[^']*'|`[^`]*`"#).expect("valid quote pattern");
+    quoted
+        .replace_all(&text, |caps: &regex::Captures| {
+            let quoted = &caps[0];
+            if quoted.chars().count() > MAX_QUOTED_CHARS + 2 {
+                "\"<redacted>\"".to_string()
+            } else {
+                quoted.to_string()
+            }
+        })
+        .to_string()
+}
+
+fn reports_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".claude").join("claudia").join("crash-reports"))
+}
+
+fn write_report(report: &CrashReport) -> Result<PathBuf, String> {
+    let dir = reports_dir().ok_or("Could not find home directory")?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let path = dir.join(format!("{}.json", report.id));
+    let json = serde_json::to_string_pretty(report).map_err(|e| e.to_string())?;
+    fs::write(&path, json).map_err(|e| format!("Failed to write crash report: {}", e))?;
+    Ok(path)
+}
+
+/// Stored reports, newest first
+fn load_reports() -> Vec<CrashReport> {
+    let Some(entries) = reports_dir().and_then(|dir| fs::read_dir(dir).ok()) else {
+        return Vec::new();
+    };
+    let mut reports: Vec<CrashReport> = entries
+        .flatten()
+        .filter_map(|entry| fs::read_to_string(entry.path()).ok())
+        .filter_map(|json| serde_json::from_str(&json).ok())
+        .collect();
+    reports.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    reports
+}
+
+fn prune_reports() {
+    let Some(dir) = reports_dir() else {
+        return;
+    };
+    for report in load_reports().into_iter().skip(MAX_REPORTS) {
+        let _ = fs::remove_file(dir.join(format!("{}.json", report.id)));
+    }
+}
+
+/// Write a crash report for every panic, then run the previous hook
+///
+/// Installed first thing in `main` so panics during startup are caught too. Nothing is
+/// sent from here; submission needs the user's consent and happens on the next launch.
+pub fn install_panic_hook() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |panic| {
+        let message = panic
+            .payload()
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| panic.payload().downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "Box<dyn Any>".to_string());
+        let mut report = CrashReport::new("panic", message);
+        report.location = panic
+            .location()
+            .map(|location| format!("{}:{}", location.file(), location.line()));
+        report.backtrace = Some(std::backtrace::Backtrace::force_capture().to_string());
+        match write_report(&report) {
+            Ok(path) => eprintln!("Crash report written to {}", path.display()),
+            Err(e) => eprintln!("Failed to write crash report: {}", e),
+        }
+        previous(panic);
+    }));
+}
+
+fn load_settings(conn: &Connection) -> CrashReportSettings {
+    get_setting(conn, SETTINGS_KEY).unwrap_or_default()
+}
+
+/// Submit the scrubbed versions of unsent reports, returning how many were sent
+async fn submit_pending_reports() -> Result<usize, String> {
+    let url = CRASH_REPORT_URL
+        .filter(|url| !url.is_empty())
+        .ok_or("This build of Claudia has no crash report endpoint")?;
+    let client = super::proxy::http_client();
+    let mut submitted = 0;
+    for mut report in load_reports().into_iter().filter(|r| !r.submitted) {
+        let response = client
+            .post(url)
+            .timeout(SUBMIT_TIMEOUT)
+            .json(&report.scrubbed())
+            .send()
+            .await
+            .map_err(|e| format!("Failed to submit crash report: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!("Crash report endpoint answered {}", response.status()));
+        }
+        report.submitted = true;
+        write_report(&report)?;
+        submitted += 1;
+    }
+    Ok(submitted)
+}
+
+/// Prune old reports and, with consent, submit the ones from earlier sessions
+pub fn start_crash_report_submission(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        prune_reports();
+        let consent = {
+            let db = app.state::<AgentDb>();
+            let Ok(conn) = db.0.lock() else {
+                return;
+            };
+            load_settings(&conn).submit_reports
+        };
+        if !consent || CRASH_REPORT_URL.is_none() {
+            return;
+        }
+        match submit_pending_reports().await {
+            Ok(0) => {}
+            Ok(count) => info!("Submitted {} crash reports", count),
+            Err(e) => warn!("{}", e),
+        }
+    });
+}
+
+/// Get the crash reporting settings
+#[tauri::command]
+#[specta::specta]
+pub async fn get_crash_report_settings(
+    db: State<'_, AgentDb>,
+) -> Result<CrashReportSettings, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    Ok(load_settings(&conn))
+}
+
+/// Give or withdraw consent to submit crash reports
+#[tauri::command]
+#[specta::specta]
+pub async fn set_crash_report_settings(
+    db: State<'_, AgentDb>,
+    settings: CrashReportSettings,
+) -> Result<(), String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    set_setting(&conn, SETTINGS_KEY, &settings)
+        .map_err(|e| format!("Failed to save crash reporting settings: {}", e))?;
+    Ok(())
+}
+
+/// List stored crash reports, newest first
+#[tauri::command]
+#[specta::specta]
+pub async fn list_crash_reports() -> Result<Vec<CrashReport>, String> {
+    Ok(load_reports())
+}
+
+/// Show exactly what would be submitted for a report
+#[tauri::command]
+#[specta::specta]
+pub async fn preview_crash_report(id: String) -> Result<CrashReport, String> {
+    load_reports()
+        .into_iter()
+        .find(|report| report.id == id)
+        .map(|report| report.scrubbed())
+        .ok_or_else(|| format!("Crash report {} not found", id))
+}
+
+/// Record an error from the frontend, e.g. an unhandled exception
+#[tauri::command]
+#[specta::specta]
+pub async fn report_frontend_error(message: String, stack: Option<String>) -> Result<(), String> {
+    let mut report = CrashReport::new("error", message);
+    report.thread = Some("frontend".to_string());
+    report.backtrace = stack;
+    write_report(&report).map(|_| ())
+}
+
+/// Submit unsent reports now; requires consent
+#[tauri::command]
+#[specta::specta]
+pub async fn submit_crash_reports(db: State<'_, AgentDb>) -> Result<usize, String> {
+    let consent = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        load_settings(&conn).submit_reports
+    };
+    if !consent {
+        return Err("Crash report submission is turned off".to_string());
+    }
+    submit_pending_reports().await.inspect_err(|e| error!("{}", e))
+}
+
+/// Delete all stored crash reports
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_crash_reports() -> Result<usize, String> {
+    let Some(dir) = reports_dir() else {
+        return Ok(0);
+    };
+    let reports = load_reports();
+    for report in &reports {
+        let _ = fs::remove_file(dir.join(format!("{}.json", report.id)));
+    }
+    Ok(reports.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scrubs_paths_and_prompts() {
+        let text = r#"Failed to open /Users/ada/secret-project/src/main.rs with "Please refactor the billing module to use the new invoice API": 'ok'"#;
+        assert_eq!(
+            scrub_text(text, Some("/Users/ada")),
+            r#"Failed to open <path>/main.rs with "<redacted>": 'ok'"#
+        );
+        assert_eq!(
+            scrub_text(r"at C:\Users\ada\app\lib.rs:10", None),
+            "at <path>/lib.rs:10"
+        );
+    }
+}
@@ -0,0 +1,251 @@
+use log::{error, info, warn};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, State};
+
+use super::agents::{get_setting, set_setting, AgentDb};
+use super::background_tasks::{TaskPriority, TaskQueueState};
+
+/// Settings of scheduled maintenance, stored under `db_maintenance_settings`
+#[derive(Debug, Serialize, Deserialize, Clone, specta::Type)]
+pub struct DbMaintenanceSettings {
+    pub enabled: bool,
+    /// Hours between maintenance runs
+    pub interval_hours: u64,
+}
+
+impl Default for DbMaintenanceSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_hours: 24 * 7,
+        }
+    }
+}
+
+/// Outcome of a maintenance run, the latest kept under `db_maintenance_last_report`
+#[derive(Debug, Serialize, Deserialize, Clone, specta::Type)]
+pub struct MaintenanceReport {
+    pub size_before: u64,
+    pub size_after: u64,
+    pub reclaimed_bytes: u64,
+    /// `ok`, or the problems `PRAGMA integrity_check` found
+    pub integrity: Vec<String>,
+    pub fts_tables_optimized: Vec<String>,
+    pub duration_ms: u64,
+    pub ran_at: String,
+}
+
+impl MaintenanceReport {
+    pub fn is_healthy(&self) -> bool {
+        self.integrity == ["ok"]
+    }
+}
+
+fn database_size(conn: &Connection) -> Result<u64, String> {
+    let pages: i64 = conn
+        .query_row("PRAGMA page_count", [], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+    let page_size: i64 = conn
+        .query_row("PRAGMA page_size", [], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+    Ok((pages * page_size) as u64)
+}
+
+/// Check integrity, optimize full-text indexes, then vacuum
+///
+/// A damaged database is not vacuumed, as that could carry the damage into the rebuilt file.
+pub fn run_maintenance(conn: &Connection) -> Result<MaintenanceReport, String> {
+    let started = Instant::now();
+    let size_before = database_size(conn)?;
+
+    let mut stmt = conn
+        .prepare("PRAGMA integrity_check")
+        .map_err(|e| e.to_string())?;
+    let integrity = stmt
+        .query_map([], |row| row.get::<_, String>(0))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Integrity check failed: {}", e))?;
+    drop(stmt);
+
+    let mut fts_tables_optimized = Vec::new();
+    if integrity == ["ok"] {
+        let mut stmt = conn
+            .prepare(
+                "SELECT name FROM sqlite_master
+                 WHERE type = 'table' AND sql LIKE 'CREATE VIRTUAL TABLE%USING fts%'",
+            )
+            .map_err(|e| e.to_string())?;
+        let fts_tables = stmt
+            .query_map([], |row| row.get::<_, String>(0))
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?;
+        for table in fts_tables {
+            let quoted = format!("\"{}\"", table.replace('"', "\"\""));
+            match conn.execute(
+                &format!("INSERT INTO {0}({0}) VALUES ('optimize')", quoted),
+                [],
+            ) {
+                Ok(_) => fts_tables_optimized.push(table),
+                Err(e) => warn!("Failed to optimize full-text index {}: {}", table, e),
+            }
+        }
+
+        conn.execute_batch("PRAGMA optimize; VACUUM;")
+            .map_err(|e| format!("Failed to vacuum database: {}", e))?;
+        // Fold the write-ahead log back in so the file size reflects the vacuum
+        let _ = conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()));
+    }
+
+    let size_after = database_size(conn)?;
+    Ok(MaintenanceReport {
+        size_before,
+        size_after,
+        reclaimed_bytes: size_before.saturating_sub(size_after),
+        integrity,
+        fts_tables_optimized,
+        duration_ms: started.elapsed().as_millis() as u64,
+        ran_at: chrono::Utc::now().to_rfc3339(),
+    })
+}
+
+fn run_and_record(db: &AgentDb) -> Result<MaintenanceReport, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let report = run_maintenance(&conn)?;
+    set_setting(&conn, "db_maintenance_last_report", &report)
+        .map_err(|e| format!("Failed to save the maintenance report: {}", e))?;
+    if !report.is_healthy() {
+        error!(
+            "Database integrity check found problems: {:?}",
+            report.integrity
+        );
+    }
+    info!(
+        "Database maintenance reclaimed {} bytes in {} ms",
+        report.reclaimed_bytes, report.duration_ms
+    );
+    Ok(report)
+}
+
+/// Start scheduled maintenance; settings are read each round so changes apply without a restart
+pub fn start_db_maintenance_scheduler(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(Duration::from_secs(15 * 60)).await;
+
+            let (settings, last_run) = {
+                let db = app.state::<AgentDb>();
+                let Ok(conn) = db.0.lock() else {
+                    continue;
+                };
+                let settings: DbMaintenanceSettings =
+                    get_setting(&conn, "db_maintenance_settings").unwrap_or_default();
+                let last: Option<MaintenanceReport> =
+                    get_setting(&conn, "db_maintenance_last_report");
+                (settings, last.map(|report| report.ran_at))
+            };
+            let due = last_run
+                .and_then(|ran_at| chrono::DateTime::parse_from_rfc3339(&ran_at).ok())
+                .is_none_or(|ran_at| {
+                    let elapsed = chrono::Utc::now().signed_duration_since(ran_at);
+                    elapsed >= chrono::Duration::hours(settings.interval_hours.max(1) as i64)
+                });
+            if !settings.enabled || !due {
+                continue;
+            }
+
+            let queue = app.state::<TaskQueueState>();
+            let app = app.clone();
+            queue.0.submit_once(
+                "db-maintenance",
+                "Database maintenance",
+                TaskPriority::Low,
+                move |task| async move {
+                    task.progress(0.0, "Vacuuming database");
+                    tauri::async_runtime::spawn_blocking(move || {
+                        run_and_record(&app.state::<AgentDb>()).map(|_| ())
+                    })
+                    .await
+                    .map_err(|e| e.to_string())?
+                    .inspect_err(|e| error!("Database maintenance failed: {}", e))
+                },
+            );
+        }
+    });
+}
+
+/// Vacuum the database, check its integrity and optimize full-text indexes now
+#[tauri::command]
+#[specta::specta]
+pub async fn run_db_maintenance(app: AppHandle) -> Result<MaintenanceReport, String> {
+    tauri::async_runtime::spawn_blocking(move || run_and_record(&app.state::<AgentDb>()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Get the report of the latest maintenance run, if any
+#[tauri::command]
+#[specta::specta]
+pub async fn get_last_db_maintenance(
+    db: State<'_, AgentDb>,
+) -> Result<Option<MaintenanceReport>, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    Ok(get_setting(&conn, "db_maintenance_last_report"))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_db_maintenance_settings(
+    db: State<'_, AgentDb>,
+) -> Result<DbMaintenanceSettings, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    Ok(get_setting(&conn, "db_maintenance_settings").unwrap_or_default())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn set_db_maintenance_settings(
+    db: State<'_, AgentDb>,
+    settings: DbMaintenanceSettings,
+) -> Result<(), String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    set_setting(&conn, "db_maintenance_settings", &settings)
+        .map_err(|e| format!("Failed to save maintenance settings: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::params;
+
+    #[test]
+    fn vacuums_and_optimizes_full_text_indexes() {
+        let dir = tempfile::tempdir().unwrap();
+        let conn = Connection::open(dir.path().join("test.db")).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE notes (body TEXT);
+             CREATE VIRTUAL TABLE notes_fts USING fts5(body);",
+        )
+        .unwrap();
+        let body = "x".repeat(4000);
+        for _ in 0..200 {
+            conn.execute("INSERT INTO notes (body) VALUES (?1)", params![body])
+                .unwrap();
+            conn.execute("INSERT INTO notes_fts (body) VALUES ('hello world')", [])
+                .unwrap();
+        }
+        conn.execute("DELETE FROM notes", []).unwrap();
+
+        let report = run_maintenance(&conn).unwrap();
+        assert!(report.is_healthy());
+        assert_eq!(report.fts_tables_optimized, vec!["notes_fts"]);
+        assert!(report.size_after < report.size_before);
+        assert_eq!(
+            report.reclaimed_bytes,
+            report.size_before - report.size_after
+        );
+    }
+}
@@ -0,0 +1,230 @@
+use log::{info, warn};
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, State, Url};
+use tauri_plugin_deep_link::DeepLinkExt;
+
+/// URL scheme registered for Claudia links
+pub const DEEP_LINK_SCHEME: &str = "claudia";
+
+/// Where a `claudia://` link points
+///
+/// Links only navigate: `AgentRun` opens a prefilled run form instead of starting the
+/// agent, since any web page or document can open a link.
+#[derive(Debug, Clone, PartialEq, Serialize, specta::Type)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DeepLinkTarget {
+    Project {
+        project_id: String,
+    },
+    Session {
+        session_id: String,
+        project_id: Option<String>,
+    },
+    Agent {
+        agent_id: i64,
+    },
+    AgentRun {
+        name: String,
+        project: Option<String>,
+        task: Option<String>,
+    },
+    Run {
+        run_id: i64,
+    },
+}
+
+/// The last link received before the frontend asked for it
+#[derive(Default)]
+pub struct DeepLinkState(Mutex<Option<DeepLinkTarget>>);
+
+/// Parse a `claudia://` URL
+///
+/// Supported forms:
+/// - `claudia://project/<project_id>`
+/// - `claudia://session/<session_id>[?project=<project_id>]`
+/// - `claudia://agent/<agent_id>`
+/// - `claudia://agent/run?name=<name>[&project=<path>][&task=<task>]`
+/// - `claudia://run/<run_id>`
+pub fn parse_deep_link(link: &str) -> Result<DeepLinkTarget, String> {
+    let url = Url::parse(link).map_err(|e| format!("Invalid link '{}': {}", link, e))?;
+    if url.scheme() != DEEP_LINK_SCHEME {
+        return Err(format!("Not a {}:// link: {}", DEEP_LINK_SCHEME, link));
+    }
+
+    // The first segment is the host of `claudia://session/<id>`
+    let mut segments: Vec<String> = url.host_str().map(str::to_string).into_iter().collect();
+    if let Some(path) = url.path_segments() {
+        segments.extend(path.filter(|s| !s.is_empty()).map(percent_decode));
+    }
+    let query = |key: &str| {
+        url.query_pairs()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.to_string())
+            .filter(|v| !v.is_empty())
+    };
+    let parse_id = |value: &str, what: &str| {
+        value
+            .parse::<i64>()
+            .map_err(|_| format!("Invalid {} ID '{}'", what, value))
+    };
+
+    let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+    match segments.as_slice() {
+        ["project", project_id] => Ok(DeepLinkTarget::Project {
+            project_id: project_id.to_string(),
+        }),
+        ["session", session_id] => Ok(DeepLinkTarget::Session {
+            session_id: session_id.to_string(),
+            project_id: query("project"),
+        }),
+        ["agent", "run"] => Ok(DeepLinkTarget::AgentRun {
+            name: query("name").ok_or("Agent run links need a name parameter")?,
+            project: query("project"),
+            task: query("task"),
+        }),
+        ["agent", agent_id] => Ok(DeepLinkTarget::Agent {
+            agent_id: parse_id(agent_id, "agent")?,
+        }),
+        ["run", run_id] => Ok(DeepLinkTarget::Run {
+            run_id: parse_id(run_id, "run")?,
+        }),
+        _ => Err(format!("Unsupported link: {}", link)),
+    }
+}
+
+/// Decode `%XX` escapes in a path segment; malformed escapes are kept as-is
+fn percent_decode(segment: &str) -> String {
+    let bytes = segment.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+/// Bring the main window to the front
+pub fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+/// Navigate the frontend to a target, as if its link had been opened
+pub fn open_target(app: &AppHandle, target: DeepLinkTarget) {
+    if let Ok(mut pending) = app.state::<DeepLinkState>().0.lock() {
+        *pending = Some(target.clone());
+    }
+    let _ = app.emit("deep-link", &target);
+    show_main_window(app);
+}
+
+/// Route opened links to the frontend and bring the window to the front
+fn handle_deep_links(app: &AppHandle, urls: &[Url]) {
+    for url in urls {
+        match parse_deep_link(url.as_str()) {
+            Ok(target) => {
+                info!("Opening deep link {}", url);
+                open_target(app, target);
+            }
+            Err(e) => warn!("{}", e),
+        }
+    }
+}
+
+/// Handle the link Claudia was launched with and any opened while it runs
+pub fn start_deep_link_handler(app: AppHandle) {
+    // Installed builds register the scheme from the bundle; dev builds do it at runtime
+    #[cfg(all(debug_assertions, any(target_os = "linux", windows)))]
+    if let Err(e) = app.deep_link().register_all() {
+        warn!("Failed to register the {}:// scheme: {}", DEEP_LINK_SCHEME, e);
+    }
+
+    match app.deep_link().get_current() {
+        Ok(Some(urls)) => handle_deep_links(&app, &urls),
+        Ok(None) => {}
+        Err(e) => warn!("Failed to read the launch link: {}", e),
+    }
+
+    let handle = app.clone();
+    app.deep_link().on_open_url(move |event| {
+        handle_deep_links(&handle, &event.urls());
+    });
+}
+
+/// Take the link received before the frontend started listening, if any
+#[tauri::command]
+#[specta::specta]
+pub async fn take_pending_deep_link(
+    state: State<'_, DeepLinkState>,
+) -> Result<Option<DeepLinkTarget>, String> {
+    let mut pending = state.0.lock().map_err(|e| e.to_string())?;
+    Ok(pending.take())
+}
+
+/// Parse a link without opening it, e.g. to validate links pasted into the app
+#[tauri::command]
+#[specta::specta]
+pub async fn resolve_deep_link(link: String) -> Result<DeepLinkTarget, String> {
+    parse_deep_link(&link)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_navigation_links() {
+        assert_eq!(
+            parse_deep_link("claudia://session/abc-123?project=-home-me-app"),
+            Ok(DeepLinkTarget::Session {
+                session_id: "abc-123".to_string(),
+                project_id: Some("-home-me-app".to_string()),
+            })
+        );
+        assert_eq!(
+            parse_deep_link("claudia://run/42"),
+            Ok(DeepLinkTarget::Run { run_id: 42 })
+        );
+        assert_eq!(
+            parse_deep_link("claudia://agent/7"),
+            Ok(DeepLinkTarget::Agent { agent_id: 7 })
+        );
+    }
+
+    #[test]
+    fn parses_agent_run_links() {
+        assert_eq!(
+            parse_deep_link("claudia://agent/run?name=Code%20Reviewer&project=%2Ftmp%2Fapp"),
+            Ok(DeepLinkTarget::AgentRun {
+                name: "Code Reviewer".to_string(),
+                project: Some("/tmp/app".to_string()),
+                task: None,
+            })
+        );
+        assert!(parse_deep_link("claudia://agent/run").is_err());
+    }
+
+    #[test]
+    fn rejects_other_links() {
+        assert!(parse_deep_link("https://session/abc").is_err());
+        assert!(parse_deep_link("claudia://run/latest").is_err());
+        assert!(parse_deep_link("claudia://settings").is_err());
+    }
+}
@@ -0,0 +1,146 @@
+use serde_json::{json, Value as JsonValue};
+use tauri::{AppHandle, Manager, State};
+
+use super::agents::AgentDb;
+use super::chat_integrations::{
+    load_chat_settings, load_finished_run, post_message, save_chat_settings, spawn_post,
+    validate_webhook_url, ChatIntegrationSettings, FinishedRun,
+};
+use super::live_metrics::AgentLiveMetrics;
+use super::notifications::format_elapsed;
+use super::usage_budgets::BudgetStatus;
+
+const SETTINGS_KEY: &str = "discord_integration";
+const SERVICE: &str = "Discord";
+
+/// Embed side colors
+const COLOR_SUCCESS: u32 = 0x2ecc71;
+const COLOR_WARNING: u32 = 0xe67e22;
+const COLOR_FAILURE: u32 = 0xe74c3c;
+
+/// Message with a single embed, posted under Claudia's name
+fn embed_message(embed: JsonValue) -> JsonValue {
+    json!({
+        "username": "Claudia",
+        "embeds": [embed],
+    })
+}
+
+/// Embed for a finished run
+fn run_message(run: &FinishedRun, metrics: &AgentLiveMetrics) -> JsonValue {
+    let color = if run.status == "completed" { COLOR_SUCCESS } else { COLOR_FAILURE };
+    embed_message(json!({
+        "title": format!("{} {}", run.agent_name, run.status.replace('_', " ")),
+        "color": color,
+        "fields": [
+            { "name": "Cost", "value": format!("${:.2}", metrics.estimated_cost_usd), "inline": true },
+            { "name": "Tokens", "value": metrics.total_tokens.to_string(), "inline": true },
+            { "name": "Duration", "value": format_elapsed(metrics.elapsed_ms), "inline": true },
+            { "name": "Project", "value": format!("`{}`", run.project_path), "inline": false },
+        ],
+        "footer": { "text": format!("Run #{} · claudia://run/{}", run.run_id, run.run_id) },
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+    }))
+}
+
+/// Embed for a crossed budget threshold
+fn budget_message(status: &BudgetStatus, threshold: u32) -> JsonValue {
+    let (title, color) = if threshold >= 100 {
+        (format!("{} usage budget exceeded", status.period), COLOR_FAILURE)
+    } else {
+        (format!("{}% of the {} usage budget used", threshold, status.period), COLOR_WARNING)
+    };
+    embed_message(json!({
+        "title": title,
+        "description": format!(
+            "${:.2} of ${:.2} spent since {}",
+            status.spent, status.limit, status.period_start
+        ),
+        "color": color,
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+    }))
+}
+
+/// Post a finished run to Discord if the agent's runs are posted
+pub fn notify_run_finished(app: &AppHandle, run_id: i64, success: bool, metrics: &AgentLiveMetrics) {
+    let db = app.state::<AgentDb>();
+    let Ok(conn) = db.0.lock() else {
+        return;
+    };
+    let settings = load_chat_settings(&conn, SETTINGS_KEY);
+    if success && settings.only_on_failure {
+        return;
+    }
+    let Some(run) = load_finished_run(&conn, run_id, success) else {
+        return;
+    };
+    if let Some(webhook_url) = settings.run_webhook(run.agent_id) {
+        let message = run_message(&run, metrics);
+        spawn_post(SERVICE, webhook_url.to_string(), message, format!("agent run {}", run_id));
+    }
+}
+
+/// Post a crossed budget threshold to Discord if budget alerts are enabled
+pub fn notify_budget_alert(app: &AppHandle, status: &BudgetStatus, threshold: u32) {
+    let settings = {
+        let db = app.state::<AgentDb>();
+        let Ok(conn) = db.0.lock() else {
+            return;
+        };
+        load_chat_settings(&conn, SETTINGS_KEY)
+    };
+    if let Some(webhook_url) = settings.budget_webhook() {
+        spawn_post(
+            SERVICE,
+            webhook_url.to_string(),
+            budget_message(status, threshold),
+            format!("{} budget alert", status.period),
+        );
+    }
+}
+
+/// Get the Discord integration settings
+#[tauri::command]
+#[specta::specta]
+pub async fn get_discord_settings(
+    db: State<'_, AgentDb>,
+) -> Result<ChatIntegrationSettings, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    Ok(load_chat_settings(&conn, SETTINGS_KEY))
+}
+
+/// Save the Discord integration settings
+#[tauri::command]
+#[specta::specta]
+pub async fn set_discord_settings(
+    db: State<'_, AgentDb>,
+    settings: ChatIntegrationSettings,
+) -> Result<(), String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    save_chat_settings(&conn, SETTINGS_KEY, SERVICE, &settings)
+}
+
+/// Post a test message to a Discord webhook, the global one when none is given
+#[tauri::command]
+#[specta::specta]
+pub async fn test_discord_webhook(
+    db: State<'_, AgentDb>,
+    webhook_url: Option<String>,
+) -> Result<(), String> {
+    let webhook_url = match webhook_url.filter(|url| !url.is_empty()) {
+        Some(url) => url,
+        None => {
+            let conn = db.0.lock().map_err(|e| e.to_string())?;
+            load_chat_settings(&conn, SETTINGS_KEY).webhook_url
+        }
+    };
+    if webhook_url.is_empty() {
+        return Err("No Discord webhook URL configured".to_string());
+    }
+    validate_webhook_url(SERVICE, &webhook_url)?;
+    let message = embed_message(json!({
+        "title": "Claudia can post to this channel",
+        "color": COLOR_SUCCESS,
+    }));
+    post_message(SERVICE, &webhook_url, &message).await
+}
@@ -0,0 +1,243 @@
+use log::info;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use similar::TextDiff;
+use std::path::Path;
+use tauri::{AppHandle, State};
+
+use super::agents::{get_agent_run, get_session_output, launch_agent_run, AgentDb};
+use super::live_metrics::RunBudget;
+use super::pipelines::extract_run_result;
+use crate::process::ProcessRegistryState;
+
+/// A file change the agent proposed during a plan-only run
+#[derive(Debug, Serialize, Deserialize, specta::Type)]
+pub struct ProposedChange {
+    pub path: String,
+    /// Tool the agent wanted to use, e.g. "Edit" or "Write"
+    pub tool: String,
+    /// Unified diff against the file as it is now
+    pub diff: String,
+}
+
+/// Result of a plan-only run
+#[derive(Debug, Serialize, Deserialize, specta::Type)]
+pub struct AgentPlan {
+    pub run_id: i64,
+    pub status: String,
+    pub plan: Option<String>,
+    pub proposed_changes: Vec<ProposedChange>,
+    /// Run started after the plan was approved
+    pub approved_run_id: Option<i64>,
+}
+
+fn unified_diff(path: &str, old: &str, new: &str) -> String {
+    TextDiff::from_lines(old, new)
+        .unified_diff()
+        .context_radius(3)
+        .header(&format!("a/{}", path), &format!("b/{}", path))
+        .to_string()
+}
+
+/// Turn a file-writing tool call into a diff preview
+fn preview_tool_call(project_path: &str, name: &str, input: &JsonValue) -> Option<ProposedChange> {
+    let path = input.get("file_path").and_then(|p| p.as_str())?;
+    let full_path = Path::new(project_path).join(path);
+    let current = std::fs::read_to_string(&full_path).unwrap_or_default();
+
+    let proposed = match name {
+        "Write" => input.get("content").and_then(|c| c.as_str())?.to_string(),
+        "Edit" => {
+            let old = input.get("old_string").and_then(|s| s.as_str())?;
+            let new = input.get("new_string").and_then(|s| s.as_str())?;
+            if input.get("replace_all").and_then(|r| r.as_bool()) == Some(true) {
+                current.replace(old, new)
+            } else {
+                current.replacen(old, new, 1)
+            }
+        }
+        "MultiEdit" => {
+            let mut content = current.clone();
+            for edit in input.get("edits").and_then(|e| e.as_array())? {
+                let old = edit.get("old_string").and_then(|s| s.as_str())?;
+                let new = edit.get("new_string").and_then(|s| s.as_str())?;
+                content = if edit.get("replace_all").and_then(|r| r.as_bool()) == Some(true) {
+                    content.replace(old, new)
+                } else {
+                    content.replacen(old, new, 1)
+                };
+            }
+            content
+        }
+        _ => return None,
+    };
+
+    Some(ProposedChange {
+        path: path.to_string(),
+        tool: name.to_string(),
+        diff: unified_diff(path, &current, &proposed),
+    })
+}
+
+/// Pull the plan text and proposed edits out of a plan-only run's transcript
+fn parse_plan(project_path: &str, jsonl_content: &str) -> (Option<String>, Vec<ProposedChange>) {
+    let mut plan = None;
+    let mut changes = Vec::new();
+
+    for line in jsonl_content.lines() {
+        let Ok(json) = serde_json::from_str::<JsonValue>(line) else {
+            continue;
+        };
+        if json.get("type").and_then(|t| t.as_str()) != Some("assistant") {
+            continue;
+        }
+        let Some(content) = json.pointer("/message/content").and_then(|c| c.as_array()) else {
+            continue;
+        };
+
+        for item in content {
+            if item.get("type").and_then(|t| t.as_str()) != Some("tool_use") {
+                continue;
+            }
+            let name = item.get("name").and_then(|n| n.as_str()).unwrap_or_default();
+            let Some(input) = item.get("input") else {
+                continue;
+            };
+            if name == "ExitPlanMode" {
+                if let Some(text) = input.get("plan").and_then(|p| p.as_str()) {
+                    plan = Some(text.to_string());
+                }
+            } else if let Some(change) = preview_tool_call(project_path, name, input) {
+                changes.push(change);
+            }
+        }
+    }
+
+    let plan = plan.or_else(|| extract_run_result(jsonl_content).map(|(text, _)| text));
+    (plan, changes)
+}
+
+/// Run an agent in plan mode; it can read the project but not modify it
+#[tauri::command]
+#[specta::specta]
+pub async fn execute_agent_dry_run(
+    app: AppHandle,
+    agent_id: i64,
+    project_path: String,
+    task: String,
+    model: Option<String>,
+    db: State<'_, AgentDb>,
+    registry: State<'_, ProcessRegistryState>,
+) -> Result<i64, String> {
+    info!("Dry-running agent {} with task: {}", agent_id, task);
+    launch_agent_run(app, agent_id, project_path, task, model, "plan", RunBudget::default(), None, db, registry).await
+}
+
+/// Get the plan and diff preview produced by a plan-only run
+#[tauri::command]
+#[specta::specta]
+pub async fn get_agent_plan(
+    db: State<'_, AgentDb>,
+    registry: State<'_, ProcessRegistryState>,
+    run_id: i64,
+) -> Result<AgentPlan, String> {
+    let run = get_agent_run(db.clone(), run_id).await?;
+    let approved_run_id = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        let mode: Option<String> = conn
+            .query_row(
+                "SELECT execution_mode FROM agent_runs WHERE id = ?1",
+                params![run_id],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string())?;
+        if mode.as_deref() != Some("plan") {
+            return Err(format!("Agent run {} is not a dry run", run_id));
+        }
+        conn.query_row(
+            "SELECT id FROM agent_runs WHERE plan_run_id = ?1 ORDER BY id DESC LIMIT 1",
+            params![run_id],
+            |row| row.get(0),
+        )
+        .ok()
+    };
+
+    let output = get_session_output(db, registry, run_id).await.unwrap_or_default();
+    let (plan, proposed_changes) = parse_plan(&run.project_path, &output);
+
+    Ok(AgentPlan {
+        run_id,
+        status: run.status,
+        plan,
+        proposed_changes,
+        approved_run_id,
+    })
+}
+
+/// Approve a dry run's plan and start the real run that carries it out
+#[tauri::command]
+#[specta::specta]
+pub async fn approve_agent_plan(
+    app: AppHandle,
+    db: State<'_, AgentDb>,
+    registry: State<'_, ProcessRegistryState>,
+    run_id: i64,
+) -> Result<i64, String> {
+    let plan = get_agent_plan(db.clone(), registry.clone(), run_id).await?;
+    if plan.status != "completed" {
+        return Err(format!("Dry run {} has not completed yet", run_id));
+    }
+    let plan_text = plan
+        .plan
+        .ok_or_else(|| format!("Dry run {} did not produce a plan", run_id))?;
+
+    let run = get_agent_run(db.clone(), run_id).await?;
+    let task = format!(
+        "{}\n\nThe following plan has been reviewed and approved. Carry it out:\n\n{}",
+        run.task, plan_text
+    );
+
+    let new_run_id = launch_agent_run(
+        app,
+        run.agent_id,
+        run.project_path,
+        task,
+        Some(run.model),
+        "normal",
+        RunBudget::default(),
+        None,
+        db.clone(),
+        registry,
+    )
+    .await?;
+
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    conn.execute(
+        "UPDATE agent_runs SET plan_run_id = ?1 WHERE id = ?2",
+        params![run_id, new_run_id],
+    )
+    .map_err(|e| e.to_string())?;
+
+    info!("Approved plan of run {}, started run {}", run_id, new_run_id);
+    Ok(new_run_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_plan_reads_exit_plan_mode_and_edits() {
+        let transcript = [
+            r#"{"type":"assistant","message":{"content":[{"type":"tool_use","name":"Write","input":{"file_path":"notes.txt","content":"hello\n"}}]}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"tool_use","name":"ExitPlanMode","input":{"plan":"1. Add notes"}}]}}"#,
+        ]
+        .join("\n");
+
+        let (plan, changes) = parse_plan("/nonexistent", &transcript);
+        assert_eq!(plan.as_deref(), Some("1. Add notes"));
+        assert_eq!(changes.len(), 1);
+        assert!(changes[0].diff.contains("+hello"));
+    }
+}
@@ -0,0 +1,330 @@
+use lettre::message::{header::ContentType, Mailbox, MultiPart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use log::{error, info};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
+use super::agents::{get_setting, read_session_jsonl, set_setting, AgentDb};
+use super::live_metrics::AgentLiveMetrics;
+use super::notifications::format_elapsed;
+use super::secrets::{delete_keychain_value, read_keychain_value, write_keychain_value};
+use super::usage_report::UsageReport;
+
+const SETTINGS_KEY: &str = "email_notifications";
+
+/// Keychain account holding the SMTP password
+pub(crate) const PASSWORD_ACCOUNT: &str = "smtp_password";
+
+const SMTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// SMTP server and what gets emailed; the password lives in the keychain
+#[derive(Debug, Serialize, Deserialize, Clone, specta::Type)]
+pub struct EmailSettings {
+    pub enabled: bool,
+    pub host: String,
+    pub port: u16,
+    /// "starttls", "tls" (implicit, usually port 465) or "none"
+    pub security: String,
+    #[serde(default)]
+    pub username: String,
+    /// Sender, e.g. `Claudia <claudia@example.com>`
+    pub from: String,
+    pub to: Vec<String>,
+    /// Email the result of every run started by a schedule
+    pub notify_scheduled_runs: bool,
+    /// Only email scheduled runs that failed
+    #[serde(default)]
+    pub only_on_failure: bool,
+    /// Email the weekly usage report when it is generated
+    #[serde(default)]
+    pub send_usage_reports: bool,
+    /// Whether a password is stored; filled in when reading the settings
+    #[serde(default)]
+    pub has_password: bool,
+}
+
+impl Default for EmailSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            host: String::new(),
+            port: 587,
+            security: "starttls".to_string(),
+            username: String::new(),
+            from: String::new(),
+            to: Vec::new(),
+            notify_scheduled_runs: true,
+            only_on_failure: false,
+            send_usage_reports: false,
+            has_password: false,
+        }
+    }
+}
+
+fn load_settings(conn: &Connection) -> EmailSettings {
+    get_setting(conn, SETTINGS_KEY).unwrap_or_default()
+}
+
+fn validate_settings(settings: &EmailSettings) -> Result<(), String> {
+    if !["starttls", "tls", "none"].contains(&settings.security.as_str()) {
+        return Err("SMTP security must be starttls, tls or none".to_string());
+    }
+    if !settings.enabled {
+        return Ok(());
+    }
+    if settings.host.is_empty() {
+        return Err("No SMTP host configured".to_string());
+    }
+    settings
+        .from
+        .parse::<Mailbox>()
+        .map_err(|e| format!("Invalid sender address '{}': {}", settings.from, e))?;
+    if settings.to.is_empty() {
+        return Err("No recipients configured".to_string());
+    }
+    for to in &settings.to {
+        to.parse::<Mailbox>()
+            .map_err(|e| format!("Invalid recipient address '{}': {}", to, e))?;
+    }
+    Ok(())
+}
+
+/// Send an email to the configured recipients, with an HTML alternative when given
+async fn send_email(
+    settings: &EmailSettings,
+    subject: &str,
+    text: String,
+    html: Option<String>,
+) -> Result<(), String> {
+    let transport = match settings.security.as_str() {
+        "tls" => AsyncSmtpTransport::<Tokio1Executor>::relay(&settings.host),
+        "starttls" => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&settings.host),
+        _ => Ok(AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&settings.host)),
+    }
+    .map_err(|e| format!("Invalid SMTP server: {}", e))?;
+    let mut transport = transport.port(settings.port).timeout(Some(SMTP_TIMEOUT));
+    if !settings.username.is_empty() {
+        let password = read_keychain_value(PASSWORD_ACCOUNT)?.unwrap_or_default();
+        transport = transport.credentials(Credentials::new(settings.username.clone(), password));
+    }
+
+    let mut builder = Message::builder()
+        .from(settings.from.parse::<Mailbox>().map_err(|e| format!("Invalid sender address: {}", e))?)
+        .subject(subject);
+    for to in &settings.to {
+        builder = builder.to(to.parse::<Mailbox>().map_err(|e| format!("Invalid recipient address: {}", e))?);
+    }
+    let message = match html {
+        Some(html) => builder.multipart(MultiPart::alternative_plain_html(text, html)),
+        None => builder.header(ContentType::TEXT_PLAIN).body(text),
+    }
+    .map_err(|e| format!("Failed to build email: {}", e))?;
+
+    transport
+        .build()
+        .send(message)
+        .await
+        .map_err(|e| format!("Failed to send email: {}", e))?;
+    Ok(())
+}
+
+/// Final answer of a run, from the `result` entry Claude writes last
+fn run_result(jsonl: &str) -> Option<String> {
+    jsonl
+        .lines()
+        .rev()
+        .filter_map(|line| serde_json::from_str::<JsonValue>(line).ok())
+        .find(|entry| entry["type"] == "result")
+        .and_then(|entry| entry["result"].as_str().map(str::to_string))
+}
+
+/// Email the outcome of a run if it was started by a schedule
+pub fn notify_run_finished(app: &AppHandle, run_id: i64, success: bool, metrics: &AgentLiveMetrics) {
+    let db = app.state::<AgentDb>();
+    let Ok(conn) = db.0.lock() else {
+        return;
+    };
+    let settings = load_settings(&conn);
+    if !settings.enabled || !settings.notify_scheduled_runs || (success && settings.only_on_failure) {
+        return;
+    }
+    // Runs without a schedule were started by hand and need no email
+    let Ok((agent_name, schedule_name, project_path, task, session_id)) = conn.query_row(
+        "SELECT r.agent_name, s.name, r.project_path, r.task, r.session_id
+         FROM agent_runs r JOIN agent_schedules s ON s.id = r.schedule_id
+         WHERE r.id = ?1",
+        params![run_id],
+        |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, String>(4)?,
+            ))
+        },
+    ) else {
+        return;
+    };
+    drop(conn);
+
+    let outcome = if success { "completed" } else { "failed" };
+    let subject = format!("[Claudia] {} {} ({})", agent_name, outcome, schedule_name);
+    let summary = format!(
+        "{} {} on schedule \"{}\".\n\n\
+         Project:  {}\n\
+         Cost:     ${:.2}\n\
+         Tokens:   {}\n\
+         Duration: {}\n\
+         Run:      claudia://run/{}\n\n\
+         Task:\n{}\n",
+        agent_name,
+        outcome,
+        schedule_name,
+        project_path,
+        metrics.estimated_cost_usd,
+        metrics.total_tokens,
+        format_elapsed(metrics.elapsed_ms),
+        run_id,
+        task
+    );
+    tauri::async_runtime::spawn(async move {
+        let mut text = summary;
+        if let Some(result) = read_session_jsonl(&session_id, &project_path)
+            .await
+            .ok()
+            .and_then(|jsonl| run_result(&jsonl))
+        {
+            text.push_str(&format!("\nResult:\n{}\n", result));
+        }
+        match send_email(&settings, &subject, text, None).await {
+            Ok(()) => info!("Emailed result of agent run {}", run_id),
+            Err(e) => error!("Failed to email result of agent run {}: {}", run_id, e),
+        }
+    });
+}
+
+/// Email a generated weekly usage report if reports are emailed
+pub fn send_usage_report(app: &AppHandle, report: &UsageReport) {
+    let settings = {
+        let db = app.state::<AgentDb>();
+        let Ok(conn) = db.0.lock() else {
+            return;
+        };
+        load_settings(&conn)
+    };
+    if !settings.enabled || !settings.send_usage_reports {
+        return;
+    }
+    let subject = format!("[Claudia] Usage report {} to {}", report.week_start, report.week_end);
+    let (text, html) = if report.format == "html" {
+        (
+            format!("Claude usage for {} to {}: ${:.2}", report.week_start, report.week_end, report.total_cost),
+            Some(report.content.clone()),
+        )
+    } else {
+        (report.content.clone(), None)
+    };
+    let week = report.week_start.clone();
+    tauri::async_runtime::spawn(async move {
+        match send_email(&settings, &subject, text, html).await {
+            Ok(()) => info!("Emailed usage report for {}", week),
+            Err(e) => error!("Failed to email usage report for {}: {}", week, e),
+        }
+    });
+}
+
+/// Get the email settings
+#[tauri::command]
+#[specta::specta]
+pub async fn get_email_settings(db: State<'_, AgentDb>) -> Result<EmailSettings, String> {
+    let mut settings = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        load_settings(&conn)
+    };
+    settings.has_password = read_keychain_value(PASSWORD_ACCOUNT)?.is_some();
+    Ok(settings)
+}
+
+/// Save the email settings
+///
+/// `password` replaces the stored SMTP password; an empty one removes it and `None` keeps it.
+#[tauri::command]
+#[specta::specta]
+pub async fn set_email_settings(
+    db: State<'_, AgentDb>,
+    settings: EmailSettings,
+    password: Option<String>,
+) -> Result<(), String> {
+    validate_settings(&settings)?;
+    match password.as_deref() {
+        Some("") => delete_keychain_value(PASSWORD_ACCOUNT)?,
+        Some(password) => write_keychain_value(PASSWORD_ACCOUNT, password)?,
+        None => {}
+    }
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    set_setting(
+        &conn,
+        SETTINGS_KEY,
+        &EmailSettings {
+            has_password: false,
+            ..settings
+        },
+    )
+    .map_err(|e| format!("Failed to save email settings: {}", e))?;
+    Ok(())
+}
+
+/// Send a test email with the saved settings
+#[tauri::command]
+#[specta::specta]
+pub async fn send_test_email(db: State<'_, AgentDb>) -> Result<(), String> {
+    let settings = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        load_settings(&conn)
+    };
+    if settings.host.is_empty() || settings.to.is_empty() {
+        return Err("Configure an SMTP server and recipients first".to_string());
+    }
+    send_email(
+        &settings,
+        "[Claudia] Test email",
+        "Claudia can send you email with these settings.".to_string(),
+        None,
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_addresses_when_enabled() {
+        let mut settings = EmailSettings {
+            enabled: true,
+            host: "smtp.example.com".to_string(),
+            from: "Claudia <claudia@example.com>".to_string(),
+            to: vec!["me@example.com".to_string()],
+            ..Default::default()
+        };
+        assert!(validate_settings(&settings).is_ok());
+
+        settings.to.push("not an address".to_string());
+        assert!(validate_settings(&settings).is_err());
+
+        settings.enabled = false;
+        assert!(validate_settings(&settings).is_ok());
+    }
+
+    #[test]
+    fn finds_the_final_result() {
+        let jsonl = "{\"type\":\"assistant\"}\n{\"type\":\"result\",\"result\":\"All tests pass\"}\n";
+        assert_eq!(run_result(jsonl).as_deref(), Some("All tests pass"));
+        assert_eq!(run_result("{\"type\":\"user\"}"), None);
+    }
+}
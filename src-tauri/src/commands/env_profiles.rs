@@ -0,0 +1,164 @@
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+
+use super::agents::AgentDb;
+
+/// An environment variable set for every Claude process of a project
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, specta::Type)]
+pub struct EnvVar {
+    pub key: String,
+    pub value: String,
+}
+
+/// The environment variables of one project
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct ProjectEnvProfile {
+    pub project_path: String,
+    pub vars: Vec<EnvVar>,
+}
+
+/// Strip trailing separators so `/repo` and `/repo/` share a profile
+fn normalize_project_path(project_path: &str) -> String {
+    let trimmed = project_path.trim_end_matches(['/', '\\']);
+    if trimmed.is_empty() {
+        project_path.to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// Variable names must be portable shell identifiers
+fn validate_key(key: &str) -> Result<(), String> {
+    let mut chars = key.chars();
+    let valid = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid environment variable name: '{}'", key))
+    }
+}
+
+fn load_project_env(conn: &Connection, project_path: &str) -> Result<Vec<EnvVar>, String> {
+    let mut stmt = conn
+        .prepare("SELECT key, value FROM project_env_vars WHERE project_path = ?1 ORDER BY key")
+        .map_err(|e| e.to_string())?;
+    let vars = stmt
+        .query_map(params![normalize_project_path(project_path)], |row| {
+            Ok(EnvVar {
+                key: row.get(0)?,
+                value: row.get(1)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(vars)
+}
+
+/// Variables to inject into a Claude process started in `project_path`
+pub fn project_env(app: &AppHandle, project_path: &str) -> Vec<(String, String)> {
+    let db = app.state::<AgentDb>();
+    let Ok(conn) = db.0.lock() else {
+        return Vec::new();
+    };
+    match load_project_env(&conn, project_path) {
+        Ok(vars) => vars.into_iter().map(|v| (v.key, v.value)).collect(),
+        Err(e) => {
+            log::error!("Failed to load environment of {}: {}", project_path, e);
+            Vec::new()
+        }
+    }
+}
+
+/// Get the environment variables of a project
+#[tauri::command]
+#[specta::specta]
+pub async fn get_project_env(
+    db: State<'_, AgentDb>,
+    project_path: String,
+) -> Result<Vec<EnvVar>, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    load_project_env(&conn, &project_path)
+}
+
+/// Replace the environment variables of a project; an empty list removes the profile
+#[tauri::command]
+#[specta::specta]
+pub async fn set_project_env(
+    db: State<'_, AgentDb>,
+    project_path: String,
+    vars: Vec<EnvVar>,
+) -> Result<(), String> {
+    for var in &vars {
+        validate_key(&var.key)?;
+    }
+    let project_path = normalize_project_path(&project_path);
+    let mut conn = db.0.lock().map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    tx.execute(
+        "DELETE FROM project_env_vars WHERE project_path = ?1",
+        params![project_path],
+    )
+    .map_err(|e| format!("Failed to clear project environment: {}", e))?;
+    for var in &vars {
+        // Later duplicates win, like in a shell
+        tx.execute(
+            "INSERT INTO project_env_vars (project_path, key, value) VALUES (?1, ?2, ?3)
+             ON CONFLICT(project_path, key) DO UPDATE SET value = ?3",
+            params![project_path, var.key, var.value],
+        )
+        .map_err(|e| format!("Failed to save project environment: {}", e))?;
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+    log::info!(
+        "Saved {} environment variables for {}",
+        vars.len(),
+        project_path
+    );
+    Ok(())
+}
+
+/// List every project that has environment variables
+#[tauri::command]
+#[specta::specta]
+pub async fn list_project_env_profiles(
+    db: State<'_, AgentDb>,
+) -> Result<Vec<ProjectEnvProfile>, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare("SELECT DISTINCT project_path FROM project_env_vars ORDER BY project_path")
+        .map_err(|e| e.to_string())?;
+    let paths = stmt
+        .query_map([], |row| row.get::<_, String>(0))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    paths
+        .into_iter()
+        .map(|project_path| {
+            let vars = load_project_env(&conn, &project_path)?;
+            Ok(ProjectEnvProfile { project_path, vars })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_names_and_normalizes_paths() {
+        for key in ["ANTHROPIC_MODEL", "_private", "HTTPS_PROXY2"] {
+            assert!(validate_key(key).is_ok(), "{}", key);
+        }
+        for key in ["", "2FAST", "MY-VAR", "A B", "KEY="] {
+            assert!(validate_key(key).is_err(), "{}", key);
+        }
+        assert_eq!(normalize_project_path("/repo/"), "/repo");
+        assert_eq!(normalize_project_path("/"), "/");
+    }
+}
@@ -0,0 +1,229 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tauri::{AppHandle, Manager};
+
+use super::agents::AgentDb;
+use crate::claude_binary::{
+    create_command_with_env, extract_version_from_output, find_claude_binary,
+    validate_claude_binary,
+};
+
+/// Outcome of a single environment check
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Ok,
+    /// Optional tooling is missing; the related features will not work
+    Warning,
+    /// Claudia cannot run sessions until this is fixed
+    Error,
+}
+
+/// One line of the setup checklist
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct EnvironmentCheck {
+    pub id: String,
+    pub label: String,
+    pub status: CheckStatus,
+    pub version: Option<String>,
+    pub message: String,
+}
+
+/// All checks and whether none of them failed
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct EnvironmentReport {
+    pub checks: Vec<EnvironmentCheck>,
+    pub healthy: bool,
+}
+
+fn check(
+    id: &str,
+    label: &str,
+    status: CheckStatus,
+    version: Option<String>,
+    message: impl Into<String>,
+) -> EnvironmentCheck {
+    EnvironmentCheck {
+        id: id.to_string(),
+        label: label.to_string(),
+        status,
+        version,
+        message: message.into(),
+    }
+}
+
+fn check_claude(app: &AppHandle) -> EnvironmentCheck {
+    let path = match find_claude_binary(app) {
+        Ok(path) => path,
+        Err(e) => return check("claude", "Claude Code", CheckStatus::Error, None, e),
+    };
+    if path == "claude-code" {
+        return check(
+            "claude",
+            "Claude Code",
+            CheckStatus::Ok,
+            None,
+            "Using the bundled Claude Code",
+        );
+    }
+    match validate_claude_binary(&path) {
+        Ok(version) => check(
+            "claude",
+            "Claude Code",
+            CheckStatus::Ok,
+            version,
+            format!("Found at {}", path),
+        ),
+        Err(e) => check("claude", "Claude Code", CheckStatus::Error, None, e),
+    }
+}
+
+/// Check an optional command line tool by running `<program> --version`
+fn check_tool(id: &str, label: &str, program: &str, missing: &str) -> EnvironmentCheck {
+    match create_command_with_env(program).arg("--version").output() {
+        Ok(output) if output.status.success() => check(
+            id,
+            label,
+            CheckStatus::Ok,
+            extract_version_from_output(&output.stdout),
+            format!("{} is installed", program),
+        ),
+        Ok(output) => check(
+            id,
+            label,
+            CheckStatus::Warning,
+            None,
+            format!(
+                "{} --version failed: {}",
+                program,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+        ),
+        Err(_) => check(id, label, CheckStatus::Warning, None, missing),
+    }
+}
+
+fn check_claude_dir(claude_dir: &Path) -> EnvironmentCheck {
+    let label = "~/.claude directory";
+    if !claude_dir.exists() {
+        return check(
+            "claude_dir",
+            label,
+            CheckStatus::Warning,
+            None,
+            format!(
+                "{} does not exist yet; run claude once to create it",
+                claude_dir.display()
+            ),
+        );
+    }
+    if !claude_dir.is_dir() {
+        return check(
+            "claude_dir",
+            label,
+            CheckStatus::Error,
+            None,
+            format!("{} is not a directory", claude_dir.display()),
+        );
+    }
+    // Sessions, checkpoints and settings are all written here
+    match tempfile::NamedTempFile::new_in(claude_dir) {
+        Ok(_) => check(
+            "claude_dir",
+            label,
+            CheckStatus::Ok,
+            None,
+            format!("{} is writable", claude_dir.display()),
+        ),
+        Err(e) => check(
+            "claude_dir",
+            label,
+            CheckStatus::Error,
+            None,
+            format!("{} is not writable: {}", claude_dir.display(), e),
+        ),
+    }
+}
+
+fn check_database(app: &AppHandle) -> EnvironmentCheck {
+    let label = "Claudia database";
+    let db = app.state::<AgentDb>();
+    let result = db.0.lock().map_err(|e| e.to_string()).and_then(|conn| {
+        conn.query_row("PRAGMA integrity_check", [], |row| row.get::<_, String>(0))
+            .map_err(|e| e.to_string())
+    });
+    match result {
+        Ok(result) if result == "ok" => check(
+            "database",
+            label,
+            CheckStatus::Ok,
+            None,
+            "Integrity check passed",
+        ),
+        Ok(result) => check(
+            "database",
+            label,
+            CheckStatus::Error,
+            None,
+            format!("Integrity check failed: {}", result),
+        ),
+        Err(e) => check(
+            "database",
+            label,
+            CheckStatus::Error,
+            None,
+            format!("Could not check the database: {}", e),
+        ),
+    }
+}
+
+/// Verify Claude, git, gh, `~/.claude` and the database for the setup checklist
+#[tauri::command]
+#[specta::specta]
+pub async fn check_environment(app: AppHandle) -> Result<EnvironmentReport, String> {
+    tokio::task::spawn_blocking(move || {
+        let claude_dir = dirs::home_dir()
+            .ok_or("Could not find home directory")?
+            .join(".claude");
+        let checks = vec![
+            check_claude(&app),
+            check_tool(
+                "git",
+                "Git",
+                "git",
+                "git was not found; checkpoint and GitHub features need it",
+            ),
+            check_tool(
+                "gh",
+                "GitHub CLI",
+                "gh",
+                "gh was not found; GitHub issue and PR tools need it",
+            ),
+            check_claude_dir(&claude_dir),
+            check_database(&app),
+        ];
+        let healthy = checks.iter().all(|c| c.status != CheckStatus::Error);
+        Ok(EnvironmentReport { checks, healthy })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn claude_dir_check_reports_missing_and_writable() {
+        let home = tempfile::tempdir().unwrap();
+        let claude_dir = home.path().join(".claude");
+        assert_eq!(check_claude_dir(&claude_dir).status, CheckStatus::Warning);
+
+        std::fs::write(&claude_dir, "").unwrap();
+        assert_eq!(check_claude_dir(&claude_dir).status, CheckStatus::Error);
+
+        std::fs::remove_file(&claude_dir).unwrap();
+        std::fs::create_dir(&claude_dir).unwrap();
+        assert_eq!(check_claude_dir(&claude_dir).status, CheckStatus::Ok);
+    }
+}
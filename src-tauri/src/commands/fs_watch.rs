@@ -0,0 +1,348 @@
+use log::{debug, error, info};
+use notify::{EventKind, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, State};
+
+/// Quiet time after the last event before a change is reported
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Longest a change is held back while events keep arriving, e.g. during an active session
+const MAX_DELAY: Duration = Duration::from_secs(2);
+
+/// What a subscriber wants to hear about below `~/.claude/projects`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, specta::Type)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WatchScope {
+    /// Projects and session files appearing, disappearing or changing
+    Projects,
+    /// A single session's JSONL transcript
+    Session {
+        project_id: String,
+        session_id: String,
+    },
+}
+
+impl WatchScope {
+    /// Whether a change to `path` concerns this scope
+    fn matches(&self, projects_dir: &Path, path: &Path) -> bool {
+        let Ok(rel) = path.strip_prefix(projects_dir) else {
+            return false;
+        };
+        let parts: Vec<&str> = rel
+            .components()
+            .filter_map(|c| match c {
+                Component::Normal(part) => part.to_str(),
+                _ => None,
+            })
+            .collect();
+        match self {
+            // Subagent files churn constantly and do not change the lists
+            Self::Projects => match parts.as_slice() {
+                [_project] => true,
+                [_project, file] => file.ends_with(".jsonl"),
+                _ => false,
+            },
+            Self::Session {
+                project_id,
+                session_id,
+            } => {
+                parts.len() == 2
+                    && parts[0] == project_id
+                    && parts[1].strip_suffix(".jsonl") == Some(session_id.as_str())
+            }
+        }
+    }
+}
+
+/// A coalesced batch of changes for one subscription
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct FsChange {
+    pub subscription_id: u64,
+    pub scope: WatchScope,
+    pub paths: Vec<String>,
+    /// Raw filesystem events folded into this change
+    pub event_count: usize,
+}
+
+struct Pending {
+    paths: BTreeSet<PathBuf>,
+    event_count: usize,
+    first_seen: Instant,
+    last_seen: Instant,
+}
+
+/// Collects events per key until they go quiet for [`DEBOUNCE`] or are [`MAX_DELAY`] old
+#[derive(Default)]
+struct Debouncer {
+    pending: HashMap<u64, Pending>,
+}
+
+impl Debouncer {
+    fn push(&mut self, key: u64, path: &Path, now: Instant) {
+        let pending = self.pending.entry(key).or_insert_with(|| Pending {
+            paths: BTreeSet::new(),
+            event_count: 0,
+            first_seen: now,
+            last_seen: now,
+        });
+        pending.paths.insert(path.to_path_buf());
+        pending.event_count += 1;
+        pending.last_seen = now;
+    }
+
+    fn take_due(&mut self, now: Instant) -> Vec<(u64, Pending)> {
+        let due: Vec<u64> = self
+            .pending
+            .iter()
+            .filter(|(_, p)| now - p.last_seen >= DEBOUNCE || now - p.first_seen >= MAX_DELAY)
+            .map(|(key, _)| *key)
+            .collect();
+        due.into_iter()
+            .filter_map(|key| self.pending.remove(&key).map(|p| (key, p)))
+            .collect()
+    }
+}
+
+type Callback = Arc<dyn Fn(FsChange) + Send + Sync>;
+
+struct Subscription {
+    scope: WatchScope,
+    callback: Callback,
+}
+
+/// One recursive watcher on `~/.claude/projects` shared by every subscriber
+pub struct FsWatchService {
+    projects_dir: PathBuf,
+    subscriptions: Mutex<HashMap<u64, Subscription>>,
+    next_id: AtomicU64,
+    watcher: Mutex<Option<notify::RecommendedWatcher>>,
+}
+
+#[derive(Clone)]
+pub struct FsWatchState(pub Arc<FsWatchService>);
+
+impl FsWatchService {
+    pub fn new(projects_dir: PathBuf) -> Arc<Self> {
+        Arc::new(Self {
+            projects_dir,
+            subscriptions: Mutex::new(HashMap::new()),
+            next_id: AtomicU64::new(1),
+            watcher: Mutex::new(None),
+        })
+    }
+
+    /// Call `callback` with debounced changes in `scope` until unsubscribed
+    pub fn subscribe(
+        self: &Arc<Self>,
+        scope: WatchScope,
+        callback: impl Fn(FsChange) + Send + Sync + 'static,
+    ) -> Result<u64, String> {
+        self.ensure_watching()?;
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.subscriptions
+            .lock()
+            .map_err(|e| e.to_string())?
+            .insert(
+                id,
+                Subscription {
+                    scope,
+                    callback: Arc::new(callback),
+                },
+            );
+        Ok(id)
+    }
+
+    pub fn projects_dir(&self) -> &Path {
+        &self.projects_dir
+    }
+
+    pub fn unsubscribe(&self, id: u64) -> bool {
+        self.subscriptions
+            .lock()
+            .map(|mut subscriptions| subscriptions.remove(&id).is_some())
+            .unwrap_or(false)
+    }
+
+    /// Start the watcher and its debounce thread the first time someone subscribes
+    fn ensure_watching(self: &Arc<Self>) -> Result<(), String> {
+        let mut watcher = self.watcher.lock().map_err(|e| e.to_string())?;
+        if watcher.is_some() {
+            return Ok(());
+        }
+        std::fs::create_dir_all(&self.projects_dir)
+            .map_err(|e| format!("Failed to create {}: {}", self.projects_dir.display(), e))?;
+        let (sender, receiver) = mpsc::channel();
+        let mut new_watcher = notify::recommended_watcher(sender)
+            .map_err(|e| format!("Failed to start file watcher: {}", e))?;
+        new_watcher
+            .watch(&self.projects_dir, RecursiveMode::Recursive)
+            .map_err(|e| format!("Failed to watch {}: {}", self.projects_dir.display(), e))?;
+        *watcher = Some(new_watcher);
+
+        let service = Arc::downgrade(self);
+        std::thread::spawn(move || run_debounce_loop(service, receiver));
+        info!("Watching {} for changes", self.projects_dir.display());
+        Ok(())
+    }
+
+    fn scopes(&self) -> Vec<(u64, WatchScope)> {
+        self.subscriptions
+            .lock()
+            .map(|subscriptions| {
+                subscriptions
+                    .iter()
+                    .map(|(id, s)| (*id, s.scope.clone()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn deliver(&self, id: u64, pending: Pending) {
+        let subscription = self.subscriptions.lock().ok().and_then(|subscriptions| {
+            subscriptions
+                .get(&id)
+                .map(|s| (s.scope.clone(), s.callback.clone()))
+        });
+        // Unsubscribed while the change was pending
+        let Some((scope, callback)) = subscription else {
+            return;
+        };
+        callback(FsChange {
+            subscription_id: id,
+            scope,
+            paths: pending
+                .paths
+                .iter()
+                .map(|p| p.to_string_lossy().to_string())
+                .collect(),
+            event_count: pending.event_count,
+        });
+    }
+}
+
+/// Route raw events to matching subscriptions and deliver them once debounced
+///
+/// Ends when the service is dropped, which drops the watcher and disconnects the channel.
+fn run_debounce_loop(
+    service: Weak<FsWatchService>,
+    receiver: mpsc::Receiver<notify::Result<notify::Event>>,
+) {
+    let mut debouncer = Debouncer::default();
+    loop {
+        let received = receiver.recv_timeout(Duration::from_millis(50));
+        let Some(service) = service.upgrade() else {
+            return;
+        };
+        match received {
+            Ok(Ok(event)) => {
+                if matches!(event.kind, EventKind::Access(_)) {
+                    continue;
+                }
+                let now = Instant::now();
+                let scopes = service.scopes();
+                for path in &event.paths {
+                    for (id, scope) in &scopes {
+                        if scope.matches(&service.projects_dir, path) {
+                            debouncer.push(*id, path, now);
+                        }
+                    }
+                }
+            }
+            Ok(Err(e)) => error!("File watcher error: {}", e),
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => return,
+        }
+        for (id, pending) in debouncer.take_due(Instant::now()) {
+            debug!(
+                "Delivering {} file events to subscription {}",
+                pending.event_count, id
+            );
+            service.deliver(id, pending);
+        }
+    }
+}
+
+/// Create the watch service for `~/.claude/projects`; the watcher starts on the first subscription
+pub fn create_fs_watch_state() -> FsWatchState {
+    let projects_dir = dirs::home_dir()
+        .unwrap_or_default()
+        .join(".claude")
+        .join("projects");
+    FsWatchState(FsWatchService::new(projects_dir))
+}
+
+/// Subscribe the frontend to changes in a scope, emitted as `fs-changed:{subscription_id}`
+#[tauri::command]
+#[specta::specta]
+pub async fn subscribe_fs_changes(
+    app: AppHandle,
+    watch: State<'_, FsWatchState>,
+    scope: WatchScope,
+) -> Result<u64, String> {
+    watch.0.subscribe(scope, move |change| {
+        let _ = app.emit(&format!("fs-changed:{}", change.subscription_id), &change);
+    })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn unsubscribe_fs_changes(
+    watch: State<'_, FsWatchState>,
+    subscription_id: u64,
+) -> Result<bool, String> {
+    Ok(watch.0.unsubscribe(subscription_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scopes_filter_paths_and_changes_are_coalesced() {
+        let root = Path::new("/home/me/.claude/projects");
+        let session = WatchScope::Session {
+            project_id: "-repo".to_string(),
+            session_id: "abc".to_string(),
+        };
+        let transcript = root.join("-repo/abc.jsonl");
+        let subagent = root.join("-repo/abc/subagents/agent-1.jsonl");
+        assert!(session.matches(root, &transcript));
+        assert!(!session.matches(root, &root.join("-repo/other.jsonl")));
+        assert!(WatchScope::Projects.matches(root, &root.join("-new-project")));
+        assert!(WatchScope::Projects.matches(root, &transcript));
+        assert!(!WatchScope::Projects.matches(root, &subagent));
+        assert!(!WatchScope::Projects.matches(
+            root,
+            Path::new("/home/me/.claude/timelines/-repo/abc/timeline.json")
+        ));
+        assert!(!WatchScope::Projects.matches(root, Path::new("/tmp/x.jsonl")));
+
+        let start = Instant::now();
+        let mut debouncer = Debouncer::default();
+        debouncer.push(1, &transcript, start);
+        debouncer.push(1, &transcript, start + Duration::from_millis(100));
+        assert!(debouncer
+            .take_due(start + Duration::from_millis(200))
+            .is_empty());
+        let due = debouncer.take_due(start + Duration::from_millis(400));
+        assert_eq!(due.len(), 1);
+        assert_eq!((due[0].1.paths.len(), due[0].1.event_count), (1, 2));
+
+        // A steady stream is still reported once the maximum delay has passed
+        for ms in (0..=2000).step_by(100) {
+            debouncer.push(2, &transcript, start + Duration::from_millis(ms));
+        }
+        assert_eq!(
+            debouncer
+                .take_due(start + Duration::from_millis(2000))
+                .len(),
+            1
+        );
+    }
+}
@@ -0,0 +1,208 @@
+use log::info;
+use serde_json::{json, Value as JsonValue};
+use std::time::Duration;
+use tauri::State;
+
+use super::agents::{
+    get_agent_run, github_token, read_session_jsonl, AgentDb, AgentRun, AgentRunMetrics,
+};
+use super::artifacts::{load_artifacts, RunArtifact};
+
+const GISTS_URL: &str = "https://api.github.com/gists";
+
+/// Transcripts longer than this are cut to their end, where the outcome is
+const MAX_TRANSCRIPT_CHARS: usize = 60_000;
+
+/// Diffs longer than this are cut; the full change set stays in the artifact export
+const MAX_DIFF_CHARS: usize = 200_000;
+
+const UPLOAD_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Keep the last `max` characters of `text`, noting what was left out
+fn tail_chars(text: &str, max: usize, what: &str) -> String {
+    let count = text.chars().count();
+    if count <= max {
+        return text.to_string();
+    }
+    let tail: String = text.chars().skip(count - max).collect();
+    format!("_Earlier {} omitted ({} characters)._\n\n{}", what, count - max, tail)
+}
+
+fn render_summary(run: &AgentRun, metrics: &AgentRunMetrics, artifacts: &[RunArtifact]) -> String {
+    let mut md = format!(
+        "# {} {}: {}\n\n\
+         | | |\n|---|---|\n\
+         | Status | {} |\n\
+         | Model | {} |\n\
+         | Project | `{}` |\n\
+         | Started | {} |\n",
+        run.agent_icon, run.agent_name, run.status, run.status, run.model, run.project_path, run.created_at
+    );
+    if let Some(completed_at) = &run.completed_at {
+        md.push_str(&format!("| Finished | {} |\n", completed_at));
+    }
+    if let Some(duration_ms) = metrics.duration_ms {
+        md.push_str(&format!("| Duration | {:.1}s |\n", duration_ms as f64 / 1000.0));
+    }
+    if let Some(tokens) = metrics.total_tokens {
+        md.push_str(&format!("| Tokens | {} |\n", tokens));
+    }
+    if let Some(cost) = metrics.cost_usd {
+        md.push_str(&format!("| Cost | ${:.4} |\n", cost));
+    }
+    md.push_str(&format!("\n## Task\n\n{}\n", run.task));
+    if !artifacts.is_empty() {
+        md.push_str("\n## Changed files\n\n");
+        for artifact in artifacts {
+            md.push_str(&format!("- `{}` ({})\n", artifact.path, artifact.change_type));
+        }
+    }
+    md
+}
+
+/// User prompts, Claude's answers and the final result, leaving tool traffic out
+fn render_transcript(jsonl: &str) -> String {
+    let mut md = String::new();
+    for line in jsonl.lines() {
+        let Ok(entry) = serde_json::from_str::<JsonValue>(line) else {
+            continue;
+        };
+        let (heading, text) = match entry["type"].as_str() {
+            Some("user") => ("User", message_text(&entry["message"]["content"])),
+            Some("assistant") => ("Claude", message_text(&entry["message"]["content"])),
+            Some("result") => ("Result", entry["result"].as_str().unwrap_or_default().to_string()),
+            _ => continue,
+        };
+        if !text.trim().is_empty() {
+            md.push_str(&format!("### {}\n\n{}\n\n", heading, text.trim()));
+        }
+    }
+    tail_chars(&md, MAX_TRANSCRIPT_CHARS, "messages")
+}
+
+fn message_text(content: &JsonValue) -> String {
+    match content {
+        JsonValue::String(text) => text.clone(),
+        JsonValue::Array(blocks) => blocks
+            .iter()
+            .filter(|block| block["type"] == "text")
+            .filter_map(|block| block["text"].as_str())
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+        _ => String::new(),
+    }
+}
+
+/// Upload a run's summary, diff and transcript excerpt as a gist and return its URL
+///
+/// Gists are secret unless `public` is set; secret gists are unlisted but readable by
+/// anyone with the link.
+#[tauri::command]
+#[specta::specta]
+pub async fn share_run_as_gist(
+    db: State<'_, AgentDb>,
+    run_id: i64,
+    public: Option<bool>,
+) -> Result<String, String> {
+    let token = github_token().ok_or(
+        "No GitHub token found. Set GITHUB_TOKEN or log in with `gh auth login`",
+    )?;
+    let run = get_agent_run(db.clone(), run_id).await?;
+    let artifacts = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        load_artifacts(&conn, run_id, true)?
+    };
+    let jsonl = read_session_jsonl(&run.session_id, &run.project_path)
+        .await
+        .unwrap_or_default();
+    let metrics = AgentRunMetrics::from_jsonl(&jsonl);
+
+    let slug = format!("claudia-run-{}", run_id);
+    let mut files = serde_json::Map::new();
+    files.insert(
+        format!("{}-summary.md", slug),
+        json!({ "content": render_summary(&run, &metrics, &artifacts) }),
+    );
+    let diff: String = artifacts
+        .iter()
+        .filter_map(|artifact| artifact.diff.as_deref())
+        .collect::<Vec<_>>()
+        .join("\n");
+    if !diff.trim().is_empty() {
+        let diff = if diff.chars().count() > MAX_DIFF_CHARS {
+            diff.chars().take(MAX_DIFF_CHARS).collect::<String>() + "\n# Diff truncated\n"
+        } else {
+            diff
+        };
+        files.insert(format!("{}.diff", slug), json!({ "content": diff }));
+    }
+    let transcript = render_transcript(&jsonl);
+    if !transcript.is_empty() {
+        files.insert(format!("{}-transcript.md", slug), json!({ "content": transcript }));
+    }
+
+    let response = super::proxy::http_client()
+        .post(GISTS_URL)
+        .timeout(UPLOAD_TIMEOUT)
+        .header("Accept", "application/vnd.github+json")
+        .header("User-Agent", "Claudia-App")
+        .bearer_auth(token)
+        .json(&json!({
+            "description": format!("{} run #{}: {}", run.agent_name, run_id, run.task.lines().next().unwrap_or_default()),
+            "public": public.unwrap_or(false),
+            "files": files,
+        }))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to upload gist: {}", e))?;
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("GitHub answered {}: {}", status, body));
+    }
+    let gist: JsonValue = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to read GitHub response: {}", e))?;
+    let url = gist["html_url"]
+        .as_str()
+        .ok_or("GitHub did not return a gist URL")?
+        .to_string();
+
+    info!("Shared run {} as gist {}", run_id, url);
+    Ok(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transcript_leaves_out_tool_traffic() {
+        let jsonl = [
+            json!({"type": "user", "message": {"content": "Add a README"}}),
+            json!({"type": "assistant", "message": {"content": [
+                {"type": "tool_use", "name": "Write", "input": {}},
+                {"type": "text", "text": "Written."},
+            ]}}),
+            json!({"type": "user", "message": {"content": [{"type": "tool_result", "content": "ok"}]}}),
+            json!({"type": "result", "result": "README added"}),
+        ]
+        .iter()
+        .map(|l| l.to_string())
+        .collect::<Vec<_>>()
+        .join("\n");
+
+        assert_eq!(
+            render_transcript(&jsonl),
+            "### User\n\nAdd a README\n\n### Claude\n\nWritten.\n\n### Result\n\nREADME added\n\n"
+        );
+    }
+
+    #[test]
+    fn keeps_the_end_of_long_text() {
+        let cut = tail_chars("abcdef", 2, "messages");
+        assert!(cut.ends_with("\n\nef"));
+        assert!(cut.contains("4 characters"));
+    }
+}
@@ -0,0 +1,541 @@
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone, Utc};
+use log::{info, warn};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+use super::agents::AgentDb;
+use super::claude::get_claude_dir;
+use super::listing_cache::ListingCache;
+
+/// Tool a conversation is imported from
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportSource {
+    /// Markdown written by Cursor's "Export Chat"
+    Cursor,
+    /// `.aider.chat.history.md` in a project
+    Aider,
+    /// Session files in `~/.continue/sessions`
+    Continue,
+}
+
+impl ImportSource {
+    fn name(self) -> &'static str {
+        match self {
+            ImportSource::Cursor => "cursor",
+            ImportSource::Aider => "aider",
+            ImportSource::Continue => "continue",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Role {
+    User,
+    Assistant,
+}
+
+/// A conversation read from another tool's export
+#[derive(Debug, Clone)]
+struct Conversation {
+    title: String,
+    project_path: Option<String>,
+    started_at: Option<DateTime<Utc>>,
+    messages: Vec<(Role, String)>,
+}
+
+impl Conversation {
+    /// Identifies the conversation so importing it again is a no-op
+    fn source_key(&self, source: ImportSource) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.title.as_bytes());
+        for (role, text) in &self.messages {
+            hasher.update(if *role == Role::User { b"u" } else { b"a" });
+            hasher.update(text.as_bytes());
+        }
+        let hex: String = hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect();
+        format!("{}:{}", source.name(), hex)
+    }
+}
+
+/// A conversation found in an export, before importing it
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct ImportPreview {
+    pub title: String,
+    pub project_path: Option<String>,
+    pub started_at: Option<String>,
+    pub message_count: usize,
+    pub already_imported: bool,
+}
+
+/// A conversation written as a Claude session
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct ImportedSession {
+    pub session_id: String,
+    pub project_path: String,
+    pub title: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, specta::Type)]
+pub struct HistoryImportResult {
+    pub imported: Vec<ImportedSession>,
+    /// Conversations imported before
+    pub skipped: usize,
+    /// Conversations that could not be imported, with the reason
+    pub failed: Vec<String>,
+}
+
+fn push_message(messages: &mut Vec<(Role, String)>, role: Role, text: &str) {
+    let text = text.trim();
+    if !text.is_empty() {
+        messages.push((role, text.to_string()));
+    }
+}
+
+/// Parse Cursor's exported Markdown, where turns start with **User** or **Cursor**
+fn parse_cursor_export(text: &str) -> Conversation {
+    let mut conversation = Conversation {
+        title: String::new(),
+        project_path: None,
+        started_at: None,
+        messages: Vec::new(),
+    };
+    let mut role: Option<Role> = None;
+    let mut current = String::new();
+
+    for line in text.lines() {
+        let next_role = match line.trim() {
+            "**User**" => Some(Role::User),
+            "**Cursor**" | "**Assistant**" => Some(Role::Assistant),
+            _ => None,
+        };
+        if let Some(next_role) = next_role {
+            if let Some(role) = role.take() {
+                push_message(&mut conversation.messages, role, current.trim_end_matches(['-', '\n', ' ']));
+            }
+            role = Some(next_role);
+            current.clear();
+            continue;
+        }
+        if role.is_none() {
+            if let Some(title) = line.strip_prefix("# ") {
+                conversation.title = title.trim().to_string();
+            } else if let Some(exported) = line.strip_prefix("_Exported on ") {
+                // "_Exported on 5/1/2025 at 10:00:00 GMT+2 from Cursor (1.0.0)_"
+                let stamp: String = exported.split_whitespace().take(3).collect::<Vec<_>>().join(" ");
+                conversation.started_at = NaiveDateTime::parse_from_str(&stamp, "%m/%d/%Y at %H:%M:%S")
+                    .ok()
+                    .and_then(|t| Local.from_local_datetime(&t).single())
+                    .map(|t| t.with_timezone(&Utc));
+            }
+            continue;
+        }
+        current.push_str(line);
+        current.push('\n');
+    }
+    if let Some(role) = role {
+        push_message(&mut conversation.messages, role, current.trim_end_matches(['-', '\n', ' ']));
+    }
+    conversation
+}
+
+/// Parse an aider chat history, which holds one conversation per "# aider chat started at"
+///
+/// User input is written as "#### " lines and aider's own output as "> " lines, which are
+/// left out; everything else is the model's answer.
+fn parse_aider_history(text: &str) -> Vec<Conversation> {
+    let mut conversations: Vec<Conversation> = Vec::new();
+    let mut role: Option<Role> = None;
+    let mut current = String::new();
+
+    fn flush(conversations: &mut [Conversation], role: &mut Option<Role>, current: &mut String) {
+        if let (Some(conversation), Some(role)) = (conversations.last_mut(), role.take()) {
+            push_message(&mut conversation.messages, role, current);
+        }
+        current.clear();
+    }
+
+    for line in text.lines() {
+        if let Some(started) = line.strip_prefix("# aider chat started at ") {
+            flush(&mut conversations, &mut role, &mut current);
+            let started_at = NaiveDateTime::parse_from_str(started.trim(), "%Y-%m-%d %H:%M:%S")
+                .ok()
+                .and_then(|t| Local.from_local_datetime(&t).single())
+                .map(|t| t.with_timezone(&Utc));
+            conversations.push(Conversation {
+                title: String::new(),
+                project_path: None,
+                started_at,
+                messages: Vec::new(),
+            });
+            continue;
+        }
+        if conversations.is_empty() || line.starts_with("> ") || line == ">" {
+            continue;
+        }
+        let (line_role, content) = match line.strip_prefix("#### ") {
+            Some(input) => (Role::User, input),
+            None => (Role::Assistant, line),
+        };
+        if role.as_ref() != Some(&line_role) {
+            if line_role == Role::Assistant && line.trim().is_empty() {
+                // Blank lines between input lines do not start an answer
+                continue;
+            }
+            flush(&mut conversations, &mut role, &mut current);
+            role = Some(line_role);
+        }
+        current.push_str(content);
+        current.push('\n');
+    }
+    flush(&mut conversations, &mut role, &mut current);
+
+    for conversation in &mut conversations {
+        conversation.title = conversation
+            .messages
+            .iter()
+            .find(|(role, _)| *role == Role::User)
+            .and_then(|(_, text)| text.lines().next())
+            .unwrap_or("aider chat")
+            .to_string();
+    }
+    conversations.retain(|conversation| !conversation.messages.is_empty());
+    conversations
+}
+
+/// Parse a Continue session file
+fn parse_continue_session(json: &JsonValue) -> Conversation {
+    let mut messages = Vec::new();
+    for item in json["history"].as_array().into_iter().flatten() {
+        let message = &item["message"];
+        let role = match message["role"].as_str() {
+            Some("user") => Role::User,
+            Some("assistant") => Role::Assistant,
+            _ => continue,
+        };
+        let text = match &message["content"] {
+            JsonValue::String(text) => text.clone(),
+            JsonValue::Array(parts) => parts
+                .iter()
+                .filter_map(|part| part["text"].as_str())
+                .collect::<Vec<_>>()
+                .join("\n"),
+            _ => continue,
+        };
+        push_message(&mut messages, role, &text);
+    }
+    let project_path = json["workspaceDirectory"]
+        .as_str()
+        .filter(|dir| !dir.is_empty())
+        .map(|dir| dir.strip_prefix("file://").unwrap_or(dir).to_string());
+    Conversation {
+        title: json["title"].as_str().unwrap_or("Continue session").to_string(),
+        project_path,
+        started_at: None,
+        messages,
+    }
+}
+
+fn modified_at(path: &Path) -> Option<DateTime<Utc>> {
+    fs::metadata(path).and_then(|m| m.modified()).ok().map(DateTime::<Utc>::from)
+}
+
+/// Files in `path` with the given extension, or `path` itself when it is a file
+fn export_files(path: &Path, extension: &str) -> Result<Vec<PathBuf>, String> {
+    if path.is_file() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+    let mut files: Vec<PathBuf> = fs::read_dir(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|file| file.extension().and_then(|e| e.to_str()) == Some(extension))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Read every conversation of an export; `project_path` overrides where they belong
+fn read_conversations(
+    source: ImportSource,
+    path: &Path,
+    project_path: Option<&str>,
+) -> Result<Vec<Conversation>, String> {
+    let mut conversations = Vec::new();
+    match source {
+        ImportSource::Aider => {
+            let file = if path.is_dir() {
+                path.join(".aider.chat.history.md")
+            } else {
+                path.to_path_buf()
+            };
+            let text = fs::read_to_string(&file)
+                .map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+            // The history lives in the project root
+            let project = file.parent().map(|dir| dir.to_string_lossy().to_string());
+            for mut conversation in parse_aider_history(&text) {
+                conversation.project_path = project.clone();
+                conversations.push(conversation);
+            }
+        }
+        ImportSource::Cursor => {
+            for file in export_files(path, "md")? {
+                let text = fs::read_to_string(&file)
+                    .map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+                let mut conversation = parse_cursor_export(&text);
+                if conversation.messages.is_empty() {
+                    continue;
+                }
+                if conversation.title.is_empty() {
+                    conversation.title = file
+                        .file_stem()
+                        .map(|s| s.to_string_lossy().to_string())
+                        .unwrap_or_default();
+                }
+                conversation.started_at = conversation.started_at.or_else(|| modified_at(&file));
+                conversations.push(conversation);
+            }
+        }
+        ImportSource::Continue => {
+            for file in export_files(path, "json")? {
+                // sessions.json is Continue's index of the session files
+                if file.file_name().and_then(|n| n.to_str()) == Some("sessions.json") {
+                    continue;
+                }
+                let json = fs::read_to_string(&file)
+                    .ok()
+                    .and_then(|text| serde_json::from_str::<JsonValue>(&text).ok());
+                let Some(json) = json else {
+                    warn!("Skipping {}: not a Continue session", file.display());
+                    continue;
+                };
+                let mut conversation = parse_continue_session(&json);
+                if conversation.messages.is_empty() {
+                    continue;
+                }
+                conversation.started_at = modified_at(&file);
+                conversations.push(conversation);
+            }
+        }
+    }
+
+    if let Some(project_path) = project_path.filter(|p| !p.is_empty()) {
+        for conversation in &mut conversations {
+            conversation.project_path = Some(project_path.to_string());
+        }
+    }
+    Ok(conversations)
+}
+
+/// Render a conversation as the JSONL Claude Code writes, so it can be listed and resumed
+fn to_claude_jsonl(conversation: &Conversation, session_id: &str, project_path: &str) -> String {
+    let start = conversation.started_at.unwrap_or_else(Utc::now);
+    let mut parent: Option<String> = None;
+    let mut lines = Vec::new();
+
+    for (index, (role, text)) in conversation.messages.iter().enumerate() {
+        let uuid = uuid::Uuid::new_v4().to_string();
+        let timestamp = (start + chrono::Duration::seconds(index as i64))
+            .to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+        let (entry_type, message) = match role {
+            Role::User => ("user", json!({ "role": "user", "content": text })),
+            Role::Assistant => (
+                "assistant",
+                json!({
+                    "id": format!("msg_imported_{}", index),
+                    "type": "message",
+                    "role": "assistant",
+                    "model": "<synthetic>",
+                    "content": [{ "type": "text", "text": text }],
+                    "stop_reason": "end_turn",
+                    "stop_sequence": null,
+                }),
+            ),
+        };
+        lines.push(
+            json!({
+                "parentUuid": parent,
+                "isSidechain": false,
+                "userType": "external",
+                "cwd": project_path,
+                "sessionId": session_id,
+                "version": "imported",
+                "type": entry_type,
+                "message": message,
+                "uuid": uuid,
+                "timestamp": timestamp,
+            })
+            .to_string(),
+        );
+        parent = Some(uuid);
+    }
+    lines.join("\n") + "\n"
+}
+
+fn is_imported(conn: &Connection, source_key: &str) -> bool {
+    conn.query_row(
+        "SELECT 1 FROM history_imports WHERE source_key = ?1",
+        params![source_key],
+        |_| Ok(()),
+    )
+    .is_ok()
+}
+
+/// List the conversations an export holds without importing them
+#[tauri::command]
+#[specta::specta]
+pub async fn preview_history_import(
+    db: State<'_, AgentDb>,
+    source: ImportSource,
+    path: String,
+    project_path: Option<String>,
+) -> Result<Vec<ImportPreview>, String> {
+    let conversations = read_conversations(source, Path::new(&path), project_path.as_deref())?;
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    Ok(conversations
+        .iter()
+        .map(|conversation| ImportPreview {
+            title: conversation.title.clone(),
+            project_path: conversation.project_path.clone(),
+            started_at: conversation.started_at.map(|t| t.to_rfc3339()),
+            message_count: conversation.messages.len(),
+            already_imported: is_imported(&conn, &conversation.source_key(source)),
+        })
+        .collect())
+}
+
+/// Import the conversations of a Cursor, aider or Continue export as Claude sessions
+/// under their project in ~/.claude/projects
+#[tauri::command]
+#[specta::specta]
+pub async fn import_history(
+    db: State<'_, AgentDb>,
+    cache: State<'_, ListingCache>,
+    source: ImportSource,
+    path: String,
+    project_path: Option<String>,
+) -> Result<HistoryImportResult, String> {
+    let conversations = read_conversations(source, Path::new(&path), project_path.as_deref())?;
+    let projects_dir = get_claude_dir().map_err(|e| e.to_string())?.join("projects");
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let mut result = HistoryImportResult::default();
+
+    for conversation in conversations {
+        let source_key = conversation.source_key(source);
+        if is_imported(&conn, &source_key) {
+            result.skipped += 1;
+            continue;
+        }
+        let Some(project) = conversation.project_path.clone() else {
+            result.failed.push(format!("{}: no project path, choose one to import into", conversation.title));
+            continue;
+        };
+
+        let session_id = uuid::Uuid::new_v4().to_string();
+        let project_dir = projects_dir.join(project.replace('/', "-"));
+        let written = fs::create_dir_all(&project_dir).and_then(|_| {
+            fs::write(
+                project_dir.join(format!("{}.jsonl", session_id)),
+                to_claude_jsonl(&conversation, &session_id, &project),
+            )
+        });
+        if let Err(e) = written {
+            result.failed.push(format!("{}: {}", conversation.title, e));
+            continue;
+        }
+
+        conn.execute(
+            "INSERT INTO history_imports (source_key, source, session_id, project_path) VALUES (?1, ?2, ?3, ?4)",
+            params![source_key, source.name(), session_id, project],
+        )
+        .map_err(|e| format!("Failed to record import: {}", e))?;
+        result.imported.push(ImportedSession {
+            session_id,
+            project_path: project,
+            title: conversation.title,
+        });
+    }
+
+    if !result.imported.is_empty() {
+        cache.invalidate_all();
+    }
+    info!(
+        "Imported {} {} conversations, skipped {}, failed {}",
+        result.imported.len(),
+        source.name(),
+        result.skipped,
+        result.failed.len()
+    );
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_aider_history() {
+        let text = "# aider chat started at 2024-05-01 10:00:00\n\n\
+                    > Add app.py to the chat? y\n\n\
+                    #### rename the main function\n\
+                    #### and update callers\n\n\
+                    Renamed `main` to `run`.\n\n\
+                    > Applied edit to app.py\n\n\
+                    # aider chat started at 2024-05-02 09:00:00\n\n\
+                    #### add tests\n\n\
+                    Added tests.\n";
+        let conversations = parse_aider_history(text);
+        assert_eq!(conversations.len(), 2);
+        assert_eq!(conversations[0].title, "rename the main function");
+        assert_eq!(
+            conversations[0].messages,
+            vec![
+                (Role::User, "rename the main function\nand update callers".to_string()),
+                (Role::Assistant, "Renamed `main` to `run`.".to_string()),
+            ]
+        );
+        assert_eq!(conversations[1].messages.len(), 2);
+    }
+
+    #[test]
+    fn parses_cursor_export() {
+        let text = "# Fix flaky test\n_Exported on 5/1/2025 at 10:00:00 GMT+2 from Cursor (1.0.0)_\n\n\
+                    ---\n\n**User**\n\nwhy is this test flaky?\n\n---\n\n\
+                    **Cursor**\n\nIt depends on wall-clock time.\n";
+        let conversation = parse_cursor_export(text);
+        assert_eq!(conversation.title, "Fix flaky test");
+        assert!(conversation.started_at.is_some());
+        assert_eq!(
+            conversation.messages,
+            vec![
+                (Role::User, "why is this test flaky?".to_string()),
+                (Role::Assistant, "It depends on wall-clock time.".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn writes_chained_claude_entries() {
+        let conversation = parse_continue_session(&json!({
+            "title": "Explain",
+            "workspaceDirectory": "file:///work/app",
+            "history": [
+                { "message": { "role": "user", "content": "explain this" } },
+                { "message": { "role": "assistant", "content": [{ "type": "text", "text": "Sure." }] } },
+            ],
+        }));
+        assert_eq!(conversation.project_path.as_deref(), Some("/work/app"));
+
+        let jsonl = to_claude_jsonl(&conversation, "s1", "/work/app");
+        let entries: Vec<JsonValue> = jsonl.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0]["parentUuid"], JsonValue::Null);
+        assert_eq!(entries[1]["parentUuid"], entries[0]["uuid"]);
+        assert_eq!(entries[1]["message"]["content"][0]["text"], "Sure.");
+        assert_eq!(entries[0]["cwd"], "/work/app");
+    }
+}
@@ -0,0 +1,359 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::Path;
+use std::process::Command;
+
+use super::settings_file::{
+    check_hooks, describe_issues, errors, read_settings, settings_path, write_settings,
+    SettingsIssue,
+};
+
+/// Scopes in the order Claude Code reads them
+const SCOPES: &[&str] = &["user", "project", "local"];
+
+/// A single command run by a hook
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, specta::Type)]
+pub struct HookCommand {
+    #[serde(rename = "type")]
+    pub hook_type: String,
+    pub command: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+}
+
+/// The tools a group of hooks applies to
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, specta::Type)]
+pub struct HookMatcher {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub matcher: Option<String>,
+    pub hooks: Vec<HookCommand>,
+}
+
+/// A matcher together with the settings scope it comes from
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct ScopedHookMatcher {
+    pub scope: String,
+    pub event: String,
+    #[serde(flatten)]
+    pub matcher: HookMatcher,
+}
+
+/// A ready-made hook that can be installed into any scope
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct HookTemplate {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub event: String,
+    pub matcher: Option<String>,
+    pub command: String,
+}
+
+fn template(
+    id: &str,
+    name: &str,
+    description: &str,
+    event: &str,
+    matcher: Option<&str>,
+    command: &str,
+) -> HookTemplate {
+    HookTemplate {
+        id: id.to_string(),
+        name: name.to_string(),
+        description: description.to_string(),
+        event: event.to_string(),
+        matcher: matcher.map(str::to_string),
+        command: command.to_string(),
+    }
+}
+
+fn templates() -> Vec<HookTemplate> {
+    vec![
+        template(
+            "log-bash-commands",
+            "Log shell commands",
+            "Append every Bash command Claude runs to ~/.claude/bash-commands.log",
+            "PreToolUse",
+            Some("Bash"),
+            "jq -r '.tool_input.command' >> ~/.claude/bash-commands.log",
+        ),
+        template(
+            "protect-env-files",
+            "Protect .env files",
+            "Block edits to .env files",
+            "PreToolUse",
+            Some("Edit|MultiEdit|Write"),
+            "jq -r '.tool_input.file_path' | { read -r f; case \"$f\" in *.env|*/.env*) echo \"Editing $f is not allowed\" >&2; exit 2;; esac; }",
+        ),
+        template(
+            "rustfmt-on-edit",
+            "Format Rust files",
+            "Run rustfmt on Rust files after Claude edits them",
+            "PostToolUse",
+            Some("Edit|MultiEdit|Write"),
+            "jq -r '.tool_input.file_path' | { read -r f; case \"$f\" in *.rs) rustfmt --edition 2021 \"$f\";; esac; }",
+        ),
+        template(
+            "prettier-on-edit",
+            "Format web files",
+            "Run prettier on TypeScript, JavaScript and CSS files after Claude edits them",
+            "PostToolUse",
+            Some("Edit|MultiEdit|Write"),
+            "jq -r '.tool_input.file_path' | { read -r f; case \"$f\" in *.ts|*.tsx|*.js|*.jsx|*.css) npx prettier --write \"$f\";; esac; }",
+        ),
+        template(
+            "desktop-notification",
+            "Desktop notification",
+            "Show a desktop notification when Claude needs input",
+            "Notification",
+            None,
+            "jq -r '.message' | xargs -0 notify-send 'Claude Code'",
+        ),
+        template(
+            "stop-sound",
+            "Sound on finish",
+            "Play a sound when Claude finishes responding",
+            "Stop",
+            None,
+            "paplay /usr/share/sounds/freedesktop/stereo/complete.oga 2>/dev/null || afplay /System/Library/Sounds/Glass.aiff",
+        ),
+    ]
+}
+
+/// Structural, matcher and shell syntax problems in a hooks section
+pub fn validate_hooks(hooks: &Value) -> Vec<SettingsIssue> {
+    let mut issues = Vec::new();
+    check_hooks(hooks, &mut issues);
+    if issues.iter().any(|i| !i.warning) {
+        return issues;
+    }
+    let Some(events) = hooks.as_object() else {
+        return issues;
+    };
+    for (event, matchers) in events {
+        let Ok(matchers) = serde_json::from_value::<Vec<HookMatcher>>(matchers.clone()) else {
+            continue;
+        };
+        for (i, group) in matchers.iter().enumerate() {
+            let path = format!("hooks.{}[{}]", event, i);
+            // An empty matcher or "*" matches every tool
+            if let Some(matcher) = group
+                .matcher
+                .as_deref()
+                .filter(|m| !m.is_empty() && *m != "*")
+            {
+                if let Err(e) = Regex::new(matcher) {
+                    issues.push(SettingsIssue {
+                        path: format!("{}.matcher", path),
+                        message: format!("is not a valid pattern: {}", e),
+                        line: None,
+                        column: None,
+                        warning: false,
+                    });
+                }
+            }
+            for (j, hook) in group.hooks.iter().enumerate() {
+                if let Some(error) = shell_syntax_error(&hook.command) {
+                    issues.push(SettingsIssue {
+                        path: format!("{}.hooks[{}].command", path, j),
+                        message: format!("has a syntax error: {}", error),
+                        line: None,
+                        column: None,
+                        warning: false,
+                    });
+                }
+            }
+        }
+    }
+    issues
+}
+
+/// Shell syntax error of a command, checked with `bash -n` without running it
+fn shell_syntax_error(command: &str) -> Option<String> {
+    let output = Command::new("bash")
+        .arg("-n")
+        .arg("-c")
+        .arg(command)
+        .output()
+        .ok()?;
+    (!output.status.success()).then(|| String::from_utf8_lossy(&output.stderr).trim().to_string())
+}
+
+fn scope_matchers(scope: &str, settings: &Value) -> Vec<ScopedHookMatcher> {
+    let Some(events) = settings.get("hooks").and_then(|h| h.as_object()) else {
+        return Vec::new();
+    };
+    let mut merged = Vec::new();
+    for (event, matchers) in events {
+        match serde_json::from_value::<Vec<HookMatcher>>(matchers.clone()) {
+            Ok(matchers) => merged.extend(matchers.into_iter().map(|matcher| ScopedHookMatcher {
+                scope: scope.to_string(),
+                event: event.clone(),
+                matcher,
+            })),
+            Err(e) => log::warn!("Skipping malformed {} hooks for {}: {}", scope, event, e),
+        }
+    }
+    merged
+}
+
+/// Add a template's hook to a settings file unless the same command is already there
+fn install_template_at(path: &Path, template: &HookTemplate) -> Result<bool, String> {
+    let mut settings = read_settings(path)?;
+    if !settings.is_object() {
+        return Err("Settings must be a JSON object".to_string());
+    }
+    let hooks = settings
+        .as_object_mut()
+        .and_then(|s| {
+            s.entry("hooks")
+                .or_insert_with(|| json!({}))
+                .as_object_mut()
+        })
+        .ok_or("The hooks setting must be an object")?;
+    let mut matchers: Vec<HookMatcher> = match hooks.get(&template.event) {
+        Some(existing) => serde_json::from_value(existing.clone())
+            .map_err(|e| format!("Existing {} hooks are malformed: {}", template.event, e))?,
+        None => Vec::new(),
+    };
+    let installed = matchers
+        .iter()
+        .any(|m| m.hooks.iter().any(|h| h.command == template.command));
+    if installed {
+        return Ok(false);
+    }
+
+    let command = HookCommand {
+        hook_type: "command".to_string(),
+        command: template.command.clone(),
+        timeout: None,
+    };
+    match matchers.iter_mut().find(|m| m.matcher == template.matcher) {
+        Some(group) => group.hooks.push(command),
+        None => matchers.push(HookMatcher {
+            matcher: template.matcher.clone(),
+            hooks: vec![command],
+        }),
+    }
+    hooks.insert(
+        template.event.clone(),
+        serde_json::to_value(matchers).map_err(|e| e.to_string())?,
+    );
+    write_settings(path, &settings)?;
+    Ok(true)
+}
+
+/// Validate a hooks section without saving it
+#[tauri::command]
+#[specta::specta]
+pub async fn validate_hooks_config(hooks: Value) -> Result<Vec<SettingsIssue>, String> {
+    Ok(validate_hooks(&hooks))
+}
+
+/// All hooks that apply to a project, user scope first, as Claude Code runs every scope
+#[tauri::command]
+#[specta::specta]
+pub async fn get_merged_hooks(
+    project_path: Option<String>,
+) -> Result<Vec<ScopedHookMatcher>, String> {
+    let mut merged = Vec::new();
+    for scope in SCOPES {
+        if *scope != "user" && project_path.is_none() {
+            continue;
+        }
+        let path = settings_path(scope, project_path.as_deref())?;
+        merged.extend(scope_matchers(scope, &read_settings(&path)?));
+    }
+    Ok(merged)
+}
+
+/// Save a hooks section after validating it
+#[tauri::command]
+#[specta::specta]
+pub async fn save_hooks_config(
+    scope: String,
+    project_path: Option<String>,
+    hooks: Value,
+) -> Result<(), String> {
+    let errors = errors(validate_hooks(&hooks));
+    if !errors.is_empty() {
+        return Err(format!("Invalid hooks: {}", describe_issues(&errors)));
+    }
+    let path = settings_path(&scope, project_path.as_deref())?;
+    let mut settings = read_settings(&path)?;
+    settings
+        .as_object_mut()
+        .ok_or("Settings must be a JSON object")?
+        .insert("hooks".to_string(), hooks);
+    write_settings(&path, &settings)?;
+    Ok(())
+}
+
+/// List the installable hook templates
+#[tauri::command]
+#[specta::specta]
+pub async fn list_hook_templates() -> Result<Vec<HookTemplate>, String> {
+    Ok(templates())
+}
+
+/// Install a hook template into a scope; returns false when it was already installed
+#[tauri::command]
+#[specta::specta]
+pub async fn install_hook_template(
+    template_id: String,
+    scope: String,
+    project_path: Option<String>,
+) -> Result<bool, String> {
+    let template = templates()
+        .into_iter()
+        .find(|t| t.id == template_id)
+        .ok_or_else(|| format!("Unknown hook template: {}", template_id))?;
+    let path = settings_path(&scope, project_path.as_deref())?;
+    install_template_at(&path, &template)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn templates_install_once_and_validate() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        std::fs::write(&path, r#"{"model": "opus"}"#).unwrap();
+
+        for template in templates() {
+            assert!(install_template_at(&path, &template).unwrap());
+            assert!(!install_template_at(&path, &template).unwrap());
+        }
+        let settings = read_settings(&path).unwrap();
+        assert_eq!(settings["model"], "opus");
+        assert!(validate_hooks(&settings["hooks"]).is_empty());
+
+        let merged = scope_matchers("user", &settings);
+        let edit_hooks = merged
+            .iter()
+            .find(|m| {
+                m.matcher.matcher.as_deref() == Some("Edit|MultiEdit|Write")
+                    && m.event == "PostToolUse"
+            })
+            .unwrap();
+        assert_eq!(edit_hooks.matcher.hooks.len(), 2);
+
+        let broken = json!({
+            "PreToolUse": [{ "matcher": "Bash(", "hooks": [{ "type": "command", "command": "if then" }] }]
+        });
+        let paths: Vec<String> = validate_hooks(&broken)
+            .into_iter()
+            .map(|i| i.path)
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                "hooks.PreToolUse[0].matcher",
+                "hooks.PreToolUse[0].hooks[0].command"
+            ]
+        );
+    }
+}
@@ -0,0 +1,280 @@
+use log::{error, info};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutEvent, ShortcutState};
+
+use super::agents::{get_setting, set_setting, AgentDb};
+use super::claude::{get_claude_dir, get_project_path_from_sessions};
+use super::deep_link::show_main_window;
+use super::tray::latest_session;
+
+const SETTINGS_KEY: &str = "global_hotkeys";
+
+/// What a global shortcut does
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum HotkeyAction {
+    /// Open the quick prompt for the project of the latest session
+    QuickPrompt,
+    /// Show Claudia, or hide it when it is focused
+    ToggleWindow,
+}
+
+impl HotkeyAction {
+    const ALL: [HotkeyAction; 2] = [HotkeyAction::QuickPrompt, HotkeyAction::ToggleWindow];
+
+    fn label(self) -> &'static str {
+        match self {
+            HotkeyAction::QuickPrompt => "New quick prompt in last project",
+            HotkeyAction::ToggleWindow => "Show/hide Claudia",
+        }
+    }
+
+    /// Offered in the settings; shortcuts stay off until the user turns them on
+    fn suggested_accelerator(self) -> &'static str {
+        match self {
+            HotkeyAction::QuickPrompt => "CommandOrControl+Shift+Space",
+            HotkeyAction::ToggleWindow => "CommandOrControl+Shift+C",
+        }
+    }
+}
+
+/// A shortcut as shown in the settings
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct HotkeyBinding {
+    pub action: HotkeyAction,
+    pub label: String,
+    /// e.g. `CommandOrControl+Shift+Space`; `None` when the shortcut is off
+    pub accelerator: Option<String>,
+    pub suggested_accelerator: String,
+    /// Whether the shortcut is currently registered with the system
+    pub registered: bool,
+}
+
+/// Payload of the `quick-prompt` event
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct QuickPromptTarget {
+    pub project_id: Option<String>,
+    pub project_path: Option<String>,
+}
+
+/// Shortcuts currently registered, and what they do
+#[derive(Default)]
+pub struct HotkeyState(Mutex<Vec<(Shortcut, HotkeyAction)>>);
+
+fn load_bindings(conn: &Connection) -> HashMap<HotkeyAction, String> {
+    get_setting(conn, SETTINGS_KEY).unwrap_or_default()
+}
+
+fn save_bindings(conn: &Connection, bindings: &HashMap<HotkeyAction, String>) -> Result<(), String> {
+    set_setting(conn, SETTINGS_KEY, bindings)
+        .map_err(|e| format!("Failed to save shortcuts: {}", e))?;
+    Ok(())
+}
+
+/// Parse the bindings, rejecting invalid accelerators and two actions on one shortcut
+fn parse_bindings(
+    bindings: &HashMap<HotkeyAction, String>,
+) -> Result<Vec<(Shortcut, HotkeyAction)>, String> {
+    let mut parsed: Vec<(Shortcut, HotkeyAction)> = Vec::new();
+    for action in HotkeyAction::ALL {
+        let Some(accelerator) = bindings.get(&action) else {
+            continue;
+        };
+        let shortcut = accelerator
+            .parse::<Shortcut>()
+            .map_err(|e| format!("Invalid shortcut '{}': {}", accelerator, e))?;
+        if let Some((_, other)) = parsed.iter().find(|(s, _)| *s == shortcut) {
+            return Err(format!(
+                "{} is already used for \"{}\"",
+                accelerator,
+                other.label()
+            ));
+        }
+        parsed.push((shortcut, action));
+    }
+    Ok(parsed)
+}
+
+/// Register `shortcuts` in place of the current ones
+///
+/// If the system refuses one, e.g. because another application holds it, the previous
+/// shortcuts are restored and the error names the conflicting shortcut.
+fn apply_shortcuts(app: &AppHandle, shortcuts: Vec<(Shortcut, HotkeyAction)>) -> Result<(), String> {
+    let state = app.state::<HotkeyState>();
+    let mut registered = state.0.lock().map_err(|e| e.to_string())?;
+    let manager = app.global_shortcut();
+    for (shortcut, _) in registered.iter() {
+        let _ = manager.unregister(*shortcut);
+    }
+
+    for (index, (shortcut, action)) in shortcuts.iter().enumerate() {
+        if let Err(e) = manager.register(*shortcut) {
+            for (added, _) in &shortcuts[..index] {
+                let _ = manager.unregister(*added);
+            }
+            for (previous, _) in registered.iter() {
+                let _ = manager.register(*previous);
+            }
+            return Err(format!(
+                "Could not register {} for \"{}\"; another application or the system may be using it ({})",
+                shortcut.into_string(),
+                action.label(),
+                e
+            ));
+        }
+    }
+    *registered = shortcuts;
+    Ok(())
+}
+
+fn quick_prompt_target() -> QuickPromptTarget {
+    let claude_dir = get_claude_dir().ok();
+    let project_id = claude_dir
+        .as_ref()
+        .and_then(|dir| latest_session(dir))
+        .map(|(project_id, _)| project_id);
+    let project_path = match (&claude_dir, &project_id) {
+        (Some(dir), Some(id)) => get_project_path_from_sessions(&dir.join("projects").join(id)).ok(),
+        _ => None,
+    };
+    QuickPromptTarget {
+        project_id,
+        project_path,
+    }
+}
+
+fn toggle_window(app: &AppHandle) {
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    let visible = window.is_visible().unwrap_or(false) && !window.is_minimized().unwrap_or(false);
+    if visible && window.is_focused().unwrap_or(false) {
+        let _ = window.hide();
+    } else {
+        show_main_window(app);
+    }
+}
+
+/// Handler given to the global shortcut plugin
+pub fn handle_shortcut(app: &AppHandle, shortcut: &Shortcut, event: ShortcutEvent) {
+    if event.state() != ShortcutState::Pressed {
+        return;
+    }
+    let action = app.try_state::<HotkeyState>().and_then(|state| {
+        let registered = state.0.lock().ok()?;
+        registered
+            .iter()
+            .find(|(s, _)| s == shortcut)
+            .map(|(_, action)| *action)
+    });
+    match action {
+        Some(HotkeyAction::QuickPrompt) => {
+            let _ = app.emit("quick-prompt", quick_prompt_target());
+            show_main_window(app);
+        }
+        Some(HotkeyAction::ToggleWindow) => toggle_window(app),
+        None => {}
+    }
+}
+
+/// Register the saved shortcuts
+pub fn start_hotkeys(app: AppHandle) {
+    let bindings = {
+        let db = app.state::<AgentDb>();
+        let Ok(conn) = db.0.lock() else {
+            return;
+        };
+        load_bindings(&conn)
+    };
+    if bindings.is_empty() {
+        return;
+    }
+    match parse_bindings(&bindings).and_then(|shortcuts| apply_shortcuts(&app, shortcuts)) {
+        Ok(()) => info!("Registered {} global shortcuts", bindings.len()),
+        Err(e) => error!("Failed to register global shortcuts: {}", e),
+    }
+}
+
+fn list_bindings(app: &AppHandle, bindings: &HashMap<HotkeyAction, String>) -> Vec<HotkeyBinding> {
+    let registered: Vec<HotkeyAction> = app
+        .state::<HotkeyState>()
+        .0
+        .lock()
+        .map(|registered| registered.iter().map(|(_, action)| *action).collect())
+        .unwrap_or_default();
+    HotkeyAction::ALL
+        .into_iter()
+        .map(|action| HotkeyBinding {
+            action,
+            label: action.label().to_string(),
+            accelerator: bindings.get(&action).cloned(),
+            suggested_accelerator: action.suggested_accelerator().to_string(),
+            registered: registered.contains(&action),
+        })
+        .collect()
+}
+
+/// List the global shortcuts and whether they are registered
+#[tauri::command]
+#[specta::specta]
+pub async fn list_hotkeys(app: AppHandle, db: State<'_, AgentDb>) -> Result<Vec<HotkeyBinding>, String> {
+    let bindings = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        load_bindings(&conn)
+    };
+    Ok(list_bindings(&app, &bindings))
+}
+
+/// Change the shortcut of an action; `None` or an empty accelerator turns it off
+///
+/// Nothing is saved when the shortcut is invalid, used by another action or can't be
+/// registered with the system.
+#[tauri::command]
+#[specta::specta]
+pub async fn set_hotkey(
+    app: AppHandle,
+    db: State<'_, AgentDb>,
+    action: HotkeyAction,
+    accelerator: Option<String>,
+) -> Result<Vec<HotkeyBinding>, String> {
+    let mut bindings = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        load_bindings(&conn)
+    };
+    match accelerator.as_deref().map(str::trim) {
+        Some("") | None => bindings.remove(&action),
+        Some(accelerator) => bindings.insert(action, accelerator.to_string()),
+    };
+
+    apply_shortcuts(&app, parse_bindings(&bindings)?)?;
+    {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        save_bindings(&conn, &bindings)?;
+    }
+    info!("Set global shortcut for {:?} to {:?}", action, accelerator);
+    Ok(list_bindings(&app, &bindings))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_shortcuts_bound_twice() {
+        let mut bindings = HashMap::from([
+            (HotkeyAction::QuickPrompt, "Ctrl+Shift+Space".to_string()),
+            (HotkeyAction::ToggleWindow, "Control+Shift+C".to_string()),
+        ]);
+        assert_eq!(parse_bindings(&bindings).unwrap().len(), 2);
+
+        bindings.insert(HotkeyAction::ToggleWindow, "Shift+Control+Space".to_string());
+        assert!(parse_bindings(&bindings).unwrap_err().contains("already used"));
+
+        bindings.insert(HotkeyAction::ToggleWindow, "Shift+Nope".to_string());
+        assert!(parse_bindings(&bindings).is_err());
+    }
+}
@@ -0,0 +1,278 @@
+use async_trait::async_trait;
+use log::warn;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use tauri::{AppHandle, State};
+
+use super::agents::{launch_agent_run, AgentDb};
+use super::claude::load_session_history;
+use super::live_metrics::RunBudget;
+
+/// An issue from any tracker, in the shape the aggregated issue list shows
+#[derive(Debug, Serialize, Deserialize, Clone, specta::Type)]
+pub struct Issue {
+    /// Provider id, e.g. "jira"
+    pub provider: String,
+    /// Key shown to users, e.g. "PROJ-12"
+    pub key: String,
+    pub title: String,
+    pub body: Option<String>,
+    pub url: String,
+    pub state: String,
+    pub assignee: Option<String>,
+    #[serde(default)]
+    pub labels: Vec<String>,
+    pub updated_at: Option<String>,
+    /// Local repository the issue's tracker project is mapped to
+    pub project_path: Option<String>,
+}
+
+/// A tracker the aggregated issue list reads from
+#[async_trait]
+pub trait IssueProvider: Send + Sync {
+    /// Short identifier stored in `Issue::provider`
+    fn id(&self) -> &'static str;
+
+    /// Open issues relevant to the user
+    async fn list_issues(&self) -> Result<Vec<Issue>, String>;
+
+    /// File a new issue; providers that can't create issues keep the default
+    async fn create_issue(&self, draft: &IssueDraft) -> Result<Issue, String> {
+        let _ = draft;
+        Err(format!("Creating issues is not supported for {}", self.id()))
+    }
+}
+
+/// A new issue to file
+#[derive(Debug, Serialize, Deserialize, Clone, specta::Type)]
+pub struct IssueDraft {
+    pub title: String,
+    pub body: String,
+    /// Provider-specific container, e.g. a Linear team key; the provider default when unset
+    pub target: Option<String>,
+    /// Repository the issue is about, used to pick the container mapped to it
+    pub project_path: Option<String>,
+}
+
+/// A provider that could not be read
+#[derive(Debug, Serialize, Deserialize, Clone, specta::Type)]
+pub struct ProviderError {
+    pub provider: String,
+    pub error: String,
+}
+
+/// Issues of all configured providers, newest first
+#[derive(Debug, Serialize, Deserialize, Clone, Default, specta::Type)]
+pub struct AggregatedIssues {
+    pub issues: Vec<Issue>,
+    pub errors: Vec<ProviderError>,
+}
+
+/// Providers the user has configured and enabled
+fn configured_providers(conn: &Connection) -> Vec<Box<dyn IssueProvider>> {
+    let mut providers: Vec<Box<dyn IssueProvider>> = Vec::new();
+    match super::jira::JiraProvider::from_settings(conn) {
+        Ok(Some(jira)) => providers.push(Box::new(jira)),
+        Ok(None) => {}
+        Err(e) => warn!("Skipping Jira issues: {}", e),
+    }
+    match super::linear::LinearProvider::from_settings(conn) {
+        Ok(Some(linear)) => providers.push(Box::new(linear)),
+        Ok(None) => {}
+        Err(e) => warn!("Skipping Linear issues: {}", e),
+    }
+    providers
+}
+
+/// Text of a message's content, without tool calls and results
+fn message_text(content: &JsonValue) -> String {
+    match content {
+        JsonValue::String(text) => text.clone(),
+        JsonValue::Array(blocks) => blocks
+            .iter()
+            .filter(|block| block["type"] == "text")
+            .filter_map(|block| block["text"].as_str())
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+        _ => String::new(),
+    }
+}
+
+/// Issue description for a session: what was asked, where it ended and how to resume it
+fn session_issue_body(messages: &[JsonValue], session_id: &str) -> String {
+    let texts = |role: &str| {
+        messages
+            .iter()
+            .filter(|m| m["type"] == role)
+            .map(|m| message_text(&m["message"]["content"]))
+            .filter(|text| !text.trim().is_empty() && !text.starts_with('<'))
+            .collect::<Vec<_>>()
+    };
+    let mut body = String::new();
+    if let Some(request) = texts("user").first() {
+        body.push_str(&format!("## Request\n\n{}\n\n", request.trim()));
+    }
+    if let Some(answer) = texts("assistant").last() {
+        body.push_str(&format!("## Last answer\n\n{}\n\n", answer.trim()));
+    }
+    body.push_str(&format!(
+        "---\nFrom Claude session `{}`; resume with `claude --resume {}`",
+        session_id, session_id
+    ));
+    body
+}
+
+/// Task given to the agent that fixes an issue
+fn fix_task(issue: &Issue) -> String {
+    let mut task = format!("Fix issue {}: {}\n\n{}", issue.key, issue.title, issue.url);
+    if let Some(body) = issue.body.as_deref().filter(|body| !body.trim().is_empty()) {
+        task.push_str(&format!("\n\n{}", body.trim()));
+    }
+    task
+}
+
+/// List open issues from every configured provider
+///
+/// A provider that fails is reported in `errors` instead of failing the whole list.
+#[tauri::command]
+#[specta::specta]
+pub async fn list_aggregated_issues(db: State<'_, AgentDb>) -> Result<AggregatedIssues, String> {
+    let providers = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        configured_providers(&conn)
+    };
+    let results = futures::future::join_all(providers.iter().map(|p| p.list_issues())).await;
+
+    let mut aggregated = AggregatedIssues::default();
+    for (provider, result) in providers.iter().zip(results) {
+        match result {
+            Ok(issues) => aggregated.issues.extend(issues),
+            Err(error) => aggregated.errors.push(ProviderError {
+                provider: provider.id().to_string(),
+                error,
+            }),
+        }
+    }
+    aggregated
+        .issues
+        .sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+    Ok(aggregated)
+}
+
+/// Start an agent run that works on an issue in its mapped repository
+#[tauri::command]
+#[specta::specta]
+#[allow(clippy::too_many_arguments)]
+pub async fn fix_issue_with_agent(
+    app: AppHandle,
+    issue: Issue,
+    agent_id: i64,
+    project_path: Option<String>,
+    model: Option<String>,
+    db: State<'_, AgentDb>,
+    registry: State<'_, crate::process::ProcessRegistryState>,
+) -> Result<i64, String> {
+    let project_path = project_path
+        .or_else(|| issue.project_path.clone())
+        .ok_or_else(|| {
+            format!(
+                "Issue {} is not mapped to a repository; choose a project to run the agent in",
+                issue.key
+            )
+        })?;
+    launch_agent_run(
+        app,
+        agent_id,
+        project_path,
+        fix_task(&issue),
+        model,
+        "normal",
+        RunBudget::default(),
+        None,
+        db,
+        registry,
+    )
+    .await
+}
+
+/// File an issue about a session with a provider that supports creating issues
+///
+/// The title defaults to the session's first request.
+#[tauri::command]
+#[specta::specta]
+pub async fn create_issue_from_session(
+    db: State<'_, AgentDb>,
+    provider: String,
+    project_id: String,
+    session_id: String,
+    title: Option<String>,
+    target: Option<String>,
+) -> Result<Issue, String> {
+    let provider = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        configured_providers(&conn)
+            .into_iter()
+            .find(|p| p.id() == provider)
+            .ok_or_else(|| format!("Issue provider '{}' is not configured", provider))?
+    };
+    let messages = load_session_history(session_id.clone(), project_id).await?;
+    let project_path = messages
+        .iter()
+        .find_map(|m| m["cwd"].as_str().map(str::to_string));
+    let title = title.filter(|t| !t.trim().is_empty()).unwrap_or_else(|| {
+        messages
+            .iter()
+            .filter(|m| m["type"] == "user")
+            .map(|m| message_text(&m["message"]["content"]))
+            .find(|text| !text.trim().is_empty() && !text.starts_with('<'))
+            .and_then(|text| text.lines().next().map(|line| line.chars().take(120).collect()))
+            .unwrap_or_else(|| format!("Claude session {}", session_id))
+    });
+    let draft = IssueDraft {
+        title,
+        body: session_issue_body(&messages, &session_id),
+        target,
+        project_path,
+    };
+    provider.create_issue(&draft).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn session_body_has_request_answer_and_resume_hint() {
+        let messages = vec![
+            serde_json::json!({"type": "user", "message": {"content": "<command-name>/clear</command-name>"}}),
+            serde_json::json!({"type": "user", "message": {"content": "Speed up the build"}}),
+            serde_json::json!({"type": "assistant", "message": {"content": [{"type": "text", "text": "Cached deps."}]}}),
+        ];
+        let body = session_issue_body(&messages, "abc");
+        assert!(body.starts_with("## Request\n\nSpeed up the build\n\n## Last answer\n\nCached deps."));
+        assert!(body.ends_with("`claude --resume abc`"));
+    }
+
+    #[test]
+    fn fix_task_includes_issue_details() {
+        let issue = Issue {
+            provider: "jira".to_string(),
+            key: "APP-7".to_string(),
+            title: "Login fails on Safari".to_string(),
+            body: Some("Steps: open the login page\n".to_string()),
+            url: "https://example.atlassian.net/browse/APP-7".to_string(),
+            state: "To Do".to_string(),
+            assignee: None,
+            labels: Vec::new(),
+            updated_at: None,
+            project_path: None,
+        };
+        assert_eq!(
+            fix_task(&issue),
+            "Fix issue APP-7: Login fails on Safari\n\n\
+             https://example.atlassian.net/browse/APP-7\n\n\
+             Steps: open the login page"
+        );
+    }
+}
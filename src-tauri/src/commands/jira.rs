@@ -0,0 +1,261 @@
+use async_trait::async_trait;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use std::time::Duration;
+use tauri::State;
+
+use super::agents::{get_setting, set_setting, AgentDb};
+use super::issues::{Issue, IssueProvider};
+use super::secrets::{delete_keychain_value, read_keychain_value, write_keychain_value};
+
+const SETTINGS_KEY: &str = "jira_integration";
+
+/// Keychain account holding the Jira API token
+pub(crate) const TOKEN_ACCOUNT: &str = "jira_api_token";
+
+const DEFAULT_JQL: &str = "assignee = currentUser() AND statusCategory != Done ORDER BY updated DESC";
+
+/// Issues fetched per search
+const MAX_RESULTS: u32 = 100;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Jira Cloud connection; the API token lives in the keychain
+#[derive(Debug, Serialize, Deserialize, Clone, Default, specta::Type)]
+pub struct JiraSettings {
+    pub enabled: bool,
+    /// e.g. `https://example.atlassian.net`
+    pub site_url: String,
+    /// Atlassian account email the API token belongs to
+    pub email: String,
+    /// Search for the issue list; empty uses open issues assigned to the user
+    #[serde(default)]
+    pub jql: String,
+    /// Jira project key to local repository path
+    #[serde(default)]
+    pub project_repos: HashMap<String, String>,
+    /// Whether an API token is stored; filled in when reading the settings
+    #[serde(default)]
+    pub has_token: bool,
+}
+
+fn load_settings(conn: &Connection) -> JiraSettings {
+    get_setting(conn, SETTINGS_KEY).unwrap_or_default()
+}
+
+/// Plain text of an Atlassian Document Format node
+fn adf_text(node: &JsonValue, out: &mut String) {
+    if let Some(text) = node["text"].as_str() {
+        out.push_str(text);
+    }
+    for child in node["content"].as_array().into_iter().flatten() {
+        adf_text(child, out);
+    }
+    match node["type"].as_str() {
+        Some("paragraph" | "heading" | "listItem" | "codeBlock" | "blockquote") => out.push('\n'),
+        Some("hardBreak") => out.push('\n'),
+        _ => {}
+    }
+}
+
+pub struct JiraProvider {
+    settings: JiraSettings,
+    token: String,
+}
+
+impl JiraProvider {
+    /// The provider if Jira is enabled and has a token
+    pub fn from_settings(conn: &Connection) -> Result<Option<Self>, String> {
+        let settings = load_settings(conn);
+        if !settings.enabled {
+            return Ok(None);
+        }
+        let token = read_keychain_value(TOKEN_ACCOUNT)?
+            .ok_or("No Jira API token stored")?;
+        Ok(Some(Self { settings, token }))
+    }
+
+    fn get(&self, path: &str) -> reqwest::RequestBuilder {
+        super::proxy::http_client()
+            .get(format!("{}{}", self.settings.site_url.trim_end_matches('/'), path))
+            .timeout(REQUEST_TIMEOUT)
+            .basic_auth(&self.settings.email, Some(&self.token))
+            .header("Accept", "application/json")
+    }
+
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<JsonValue, String> {
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("Failed to reach Jira: {}", e))?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(format!("Jira answered {}: {}", status, body));
+        }
+        response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to read Jira response: {}", e))
+    }
+
+    fn to_issue(&self, issue: &JsonValue) -> Issue {
+        let fields = &issue["fields"];
+        let key = issue["key"].as_str().unwrap_or_default().to_string();
+        let mut body = String::new();
+        adf_text(&fields["description"], &mut body);
+        let project_key = fields["project"]["key"].as_str().unwrap_or_default();
+        Issue {
+            provider: self.id().to_string(),
+            url: format!("{}/browse/{}", self.settings.site_url.trim_end_matches('/'), key),
+            key,
+            title: fields["summary"].as_str().unwrap_or_default().to_string(),
+            body: Some(body.trim().to_string()).filter(|body| !body.is_empty()),
+            state: fields["status"]["name"].as_str().unwrap_or_default().to_string(),
+            assignee: fields["assignee"]["displayName"].as_str().map(str::to_string),
+            labels: fields["labels"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|label| label.as_str().map(str::to_string))
+                .collect(),
+            updated_at: fields["updated"].as_str().map(str::to_string),
+            project_path: self.settings.project_repos.get(project_key).cloned(),
+        }
+    }
+}
+
+#[async_trait]
+impl IssueProvider for JiraProvider {
+    fn id(&self) -> &'static str {
+        "jira"
+    }
+
+    async fn list_issues(&self) -> Result<Vec<Issue>, String> {
+        let jql = if self.settings.jql.trim().is_empty() {
+            DEFAULT_JQL
+        } else {
+            self.settings.jql.as_str()
+        };
+        let max_results = MAX_RESULTS.to_string();
+        let response = self
+            .send(self.get("/rest/api/3/search/jql").query(&[
+                ("jql", jql),
+                ("maxResults", max_results.as_str()),
+                ("fields", "summary,status,assignee,labels,updated,project,description"),
+            ]))
+            .await?;
+        Ok(response["issues"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|issue| self.to_issue(issue))
+            .collect())
+    }
+}
+
+/// Get the Jira settings
+#[tauri::command]
+#[specta::specta]
+pub async fn get_jira_settings(db: State<'_, AgentDb>) -> Result<JiraSettings, String> {
+    let mut settings = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        load_settings(&conn)
+    };
+    settings.has_token = read_keychain_value(TOKEN_ACCOUNT)?.is_some();
+    Ok(settings)
+}
+
+/// Save the Jira settings
+///
+/// `api_token` replaces the stored token; an empty one removes it and `None` keeps it.
+#[tauri::command]
+#[specta::specta]
+pub async fn set_jira_settings(
+    db: State<'_, AgentDb>,
+    settings: JiraSettings,
+    api_token: Option<String>,
+) -> Result<(), String> {
+    if settings.enabled {
+        let url = reqwest::Url::parse(&settings.site_url)
+            .map_err(|e| format!("Invalid Jira site URL: {}", e))?;
+        if url.scheme() != "https" {
+            return Err("The Jira site URL must use https".to_string());
+        }
+        if settings.email.is_empty() {
+            return Err("The Jira account email is required".to_string());
+        }
+    }
+    match api_token.as_deref() {
+        Some("") => delete_keychain_value(TOKEN_ACCOUNT)?,
+        Some(token) => write_keychain_value(TOKEN_ACCOUNT, token)?,
+        None => {}
+    }
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    set_setting(
+        &conn,
+        SETTINGS_KEY,
+        &JiraSettings {
+            has_token: false,
+            ..settings
+        },
+    )
+    .map_err(|e| format!("Failed to save Jira settings: {}", e))?;
+    Ok(())
+}
+
+/// Check the saved Jira credentials, returning the account's display name
+#[tauri::command]
+#[specta::specta]
+pub async fn test_jira_connection(db: State<'_, AgentDb>) -> Result<String, String> {
+    let provider = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        JiraProvider::from_settings(&conn)?
+    }
+    .ok_or("Jira is not enabled")?;
+    let me = provider.send(provider.get("/rest/api/3/myself")).await?;
+    Ok(me["displayName"].as_str().unwrap_or_default().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn maps_issues_to_repositories() {
+        let provider = JiraProvider {
+            settings: JiraSettings {
+                enabled: true,
+                site_url: "https://example.atlassian.net/".to_string(),
+                project_repos: HashMap::from([("APP".to_string(), "/work/app".to_string())]),
+                ..Default::default()
+            },
+            token: String::new(),
+        };
+        let issue = provider.to_issue(&json!({
+            "key": "APP-7",
+            "fields": {
+                "summary": "Login fails",
+                "status": { "name": "In Progress" },
+                "assignee": null,
+                "labels": ["bug"],
+                "project": { "key": "APP" },
+                "description": {
+                    "type": "doc",
+                    "content": [
+                        { "type": "paragraph", "content": [{ "type": "text", "text": "Open Safari" }] },
+                        { "type": "paragraph", "content": [{ "type": "text", "text": "Log in" }] },
+                    ],
+                },
+            },
+        }));
+        assert_eq!(issue.url, "https://example.atlassian.net/browse/APP-7");
+        assert_eq!(issue.project_path.as_deref(), Some("/work/app"));
+        assert_eq!(issue.body.as_deref(), Some("Open Safari\nLog in"));
+        assert_eq!(issue.assignee, None);
+        assert_eq!(issue.labels, vec!["bug"]);
+    }
+}
@@ -0,0 +1,121 @@
+use memmap2::Mmap;
+use std::borrow::Cow;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+use super::session_archive::archived_path;
+
+/// Files at least this large are memory-mapped instead of read into memory
+const MMAP_THRESHOLD: u64 = 8 * 1024 * 1024;
+
+enum Contents {
+    Mapped(Mmap),
+    Read(Vec<u8>),
+}
+
+/// A JSONL transcript opened for line-by-line parsing
+///
+/// Large files are memory-mapped so only the pages being parsed are resident, which keeps
+/// memory flat for sessions of hundreds of megabytes. Transcripts are append-only while
+/// Claude writes them; the mapping covers the length at open time.
+pub struct JsonlFile {
+    contents: Contents,
+    /// Offset into the contents where the requested range starts
+    start: usize,
+}
+
+impl JsonlFile {
+    pub fn open(path: &Path) -> std::io::Result<Self> {
+        Self::open_from(path, 0)
+    }
+
+    /// Open the part of the file after `offset`, e.g. what was appended since the last read
+    ///
+    /// A transcript that has been archived is decompressed in full instead.
+    pub fn open_from(path: &Path, offset: u64) -> std::io::Result<Self> {
+        let archive = archived_path(path);
+        if !path.exists() && archive.exists() {
+            let buffer = zstd::stream::decode_all(File::open(&archive)?)?;
+            return Ok(Self {
+                start: (offset as usize).min(buffer.len()),
+                contents: Contents::Read(buffer),
+            });
+        }
+        let mut file = File::open(path)?;
+        let len = file.metadata()?.len();
+        let offset = offset.min(len);
+        if len - offset >= MMAP_THRESHOLD {
+            // Safety: the map is read-only and transcripts are only appended to, never truncated
+            let map = unsafe { Mmap::map(&file)? };
+            return Ok(Self {
+                contents: Contents::Mapped(map),
+                start: offset as usize,
+            });
+        }
+        file.seek(SeekFrom::Start(offset))?;
+        let mut buffer = Vec::with_capacity((len - offset) as usize);
+        file.read_to_end(&mut buffer)?;
+        Ok(Self {
+            contents: Contents::Read(buffer),
+            start: 0,
+        })
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        let contents: &[u8] = match &self.contents {
+            Contents::Mapped(map) => map,
+            Contents::Read(buffer) => buffer,
+        };
+        &contents[self.start.min(contents.len())..]
+    }
+
+    /// Non-empty lines, decoded lazily
+    pub fn lines(&self) -> impl Iterator<Item = Cow<'_, str>> {
+        jsonl_lines(self.bytes())
+    }
+}
+
+/// Split bytes into non-empty lines without copying valid UTF-8
+pub fn jsonl_lines(bytes: &[u8]) -> impl Iterator<Item = Cow<'_, str>> {
+    bytes
+        .split(|b| *b == b'\n')
+        .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+        .filter(|line| !line.iter().all(u8::is_ascii_whitespace))
+        .map(String::from_utf8_lossy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn iterates_lines_of_small_and_mapped_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let small = dir.path().join("small.jsonl");
+        std::fs::write(&small, "{\"a\":1}\r\n\n  \n{\"b\":\"\u{e9}\"}").unwrap();
+        let lines: Vec<String> = JsonlFile::open(&small)
+            .unwrap()
+            .lines()
+            .map(|l| l.into_owned())
+            .collect();
+        assert_eq!(lines, vec!["{\"a\":1}", "{\"b\":\"\u{e9}\"}"]);
+
+        let large = dir.path().join("large.jsonl");
+        let line = format!("{{\"pad\":\"{}\"}}\n", "x".repeat(1000));
+        let count = (MMAP_THRESHOLD as usize / line.len()) + 10;
+        std::fs::write(&large, line.repeat(count)).unwrap();
+        let file = JsonlFile::open(&large).unwrap();
+        assert!(matches!(file.contents, Contents::Mapped(_)));
+        assert_eq!(file.lines().count(), count);
+        let tail = JsonlFile::open_from(&large, (line.len() * (count - 2)) as u64).unwrap();
+        assert_eq!(tail.lines().count(), 2);
+
+        // Archived transcripts are read through the same path
+        let archived = dir.path().join("archived.jsonl");
+        let compressed = zstd::stream::encode_all(&b"{\"a\":1}\n{\"b\":2}\n"[..], 3).unwrap();
+        std::fs::write(archived_path(&archived), compressed).unwrap();
+        let file = JsonlFile::open_from(&archived, 8).unwrap();
+        assert_eq!(file.lines().collect::<Vec<_>>(), vec!["{\"b\":2}"]);
+    }
+}
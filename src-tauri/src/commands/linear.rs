@@ -0,0 +1,315 @@
+use async_trait::async_trait;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+use std::collections::HashMap;
+use std::time::Duration;
+use tauri::State;
+
+use super::agents::{get_setting, set_setting, AgentDb};
+use super::issues::{Issue, IssueDraft, IssueProvider};
+use super::secrets::{delete_keychain_value, read_keychain_value, write_keychain_value};
+
+const SETTINGS_KEY: &str = "linear_integration";
+
+/// Keychain account holding the Linear personal API key
+pub(crate) const API_KEY_ACCOUNT: &str = "linear_api_key";
+
+const GRAPHQL_URL: &str = "https://api.linear.app/graphql";
+
+/// Issues fetched per list
+const MAX_ISSUES: u32 = 100;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(20);
+
+const ISSUE_FIELDS: &str = "identifier title description url updatedAt
+    state { name } assignee { name } labels { nodes { name } } team { key }";
+
+/// Linear teams to aggregate; the API key lives in the keychain
+#[derive(Debug, Serialize, Deserialize, Clone, Default, specta::Type)]
+pub struct LinearSettings {
+    pub enabled: bool,
+    /// Keys of the teams whose open issues are listed, e.g. "ENG"
+    pub team_keys: Vec<String>,
+    /// Team key to local repository path
+    #[serde(default)]
+    pub team_repos: HashMap<String, String>,
+    /// Team that issues created from sessions go to when no repository maps to one
+    #[serde(default)]
+    pub default_team_key: Option<String>,
+    /// Whether an API key is stored; filled in when reading the settings
+    #[serde(default)]
+    pub has_api_key: bool,
+}
+
+fn load_settings(conn: &Connection) -> LinearSettings {
+    get_setting(conn, SETTINGS_KEY).unwrap_or_default()
+}
+
+pub struct LinearProvider {
+    settings: LinearSettings,
+    api_key: String,
+}
+
+impl LinearProvider {
+    /// The provider if Linear is enabled and has an API key
+    pub fn from_settings(conn: &Connection) -> Result<Option<Self>, String> {
+        let settings = load_settings(conn);
+        if !settings.enabled {
+            return Ok(None);
+        }
+        let api_key = read_keychain_value(API_KEY_ACCOUNT)?
+            .ok_or("No Linear API key stored")?;
+        Ok(Some(Self { settings, api_key }))
+    }
+
+    async fn query(&self, query: &str, variables: JsonValue) -> Result<JsonValue, String> {
+        let response = super::proxy::http_client()
+            .post(GRAPHQL_URL)
+            .timeout(REQUEST_TIMEOUT)
+            // Personal API keys are sent as is, without a Bearer prefix
+            .header("Authorization", &self.api_key)
+            .json(&json!({ "query": query, "variables": variables }))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to reach Linear: {}", e))?;
+        let status = response.status();
+        let body: JsonValue = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to read Linear response ({}): {}", status, e))?;
+        if let Some(message) = body["errors"][0]["message"].as_str() {
+            return Err(format!("Linear returned an error: {}", message));
+        }
+        if !status.is_success() {
+            return Err(format!("Linear answered {}", status));
+        }
+        Ok(body["data"].clone())
+    }
+
+    fn to_issue(&self, node: &JsonValue) -> Issue {
+        let team_key = node["team"]["key"].as_str().unwrap_or_default();
+        Issue {
+            provider: self.id().to_string(),
+            key: node["identifier"].as_str().unwrap_or_default().to_string(),
+            title: node["title"].as_str().unwrap_or_default().to_string(),
+            body: node["description"].as_str().map(str::to_string),
+            url: node["url"].as_str().unwrap_or_default().to_string(),
+            state: node["state"]["name"].as_str().unwrap_or_default().to_string(),
+            assignee: node["assignee"]["name"].as_str().map(str::to_string),
+            labels: node["labels"]["nodes"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|label| label["name"].as_str().map(str::to_string))
+                .collect(),
+            updated_at: node["updatedAt"].as_str().map(str::to_string),
+            project_path: self.settings.team_repos.get(team_key).cloned(),
+        }
+    }
+
+    /// Team for a new issue: the requested one, the one mapped to the repository, or the default
+    fn team_for(&self, draft: &IssueDraft) -> Option<String> {
+        draft
+            .target
+            .clone()
+            .filter(|key| !key.is_empty())
+            .or_else(|| {
+                let project = draft.project_path.as_deref()?;
+                self.settings
+                    .team_repos
+                    .iter()
+                    .find(|(_, repo)| repo.as_str() == project)
+                    .map(|(key, _)| key.clone())
+            })
+            .or_else(|| self.settings.default_team_key.clone())
+    }
+}
+
+#[async_trait]
+impl IssueProvider for LinearProvider {
+    fn id(&self) -> &'static str {
+        "linear"
+    }
+
+    async fn list_issues(&self) -> Result<Vec<Issue>, String> {
+        if self.settings.team_keys.is_empty() {
+            return Ok(Vec::new());
+        }
+        let query = format!(
+            "query($teams: [String!], $first: Int!) {{
+                issues(
+                    first: $first,
+                    orderBy: updatedAt,
+                    filter: {{
+                        team: {{ key: {{ in: $teams }} }},
+                        state: {{ type: {{ nin: [\"completed\", \"canceled\"] }} }}
+                    }}
+                ) {{ nodes {{ {} }} }}
+            }}",
+            ISSUE_FIELDS
+        );
+        let data = self
+            .query(&query, json!({ "teams": self.settings.team_keys, "first": MAX_ISSUES }))
+            .await?;
+        Ok(data["issues"]["nodes"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|node| self.to_issue(node))
+            .collect())
+    }
+
+    async fn create_issue(&self, draft: &IssueDraft) -> Result<Issue, String> {
+        let team_key = self
+            .team_for(draft)
+            .ok_or("Choose a Linear team or set a default team")?;
+        let teams = self
+            .query(
+                "query($key: String!) { teams(filter: { key: { eq: $key } }) { nodes { id } } }",
+                json!({ "key": team_key }),
+            )
+            .await?;
+        let team_id = teams["teams"]["nodes"][0]["id"]
+            .as_str()
+            .ok_or_else(|| format!("Linear team '{}' not found", team_key))?
+            .to_string();
+
+        let mutation = format!(
+            "mutation($input: IssueCreateInput!) {{
+                issueCreate(input: $input) {{ success issue {{ {} }} }}
+            }}",
+            ISSUE_FIELDS
+        );
+        let data = self
+            .query(
+                &mutation,
+                json!({ "input": { "teamId": team_id, "title": draft.title, "description": draft.body } }),
+            )
+            .await?;
+        if data["issueCreate"]["success"] != true {
+            return Err("Linear did not create the issue".to_string());
+        }
+        Ok(self.to_issue(&data["issueCreate"]["issue"]))
+    }
+}
+
+/// Get the Linear settings
+#[tauri::command]
+#[specta::specta]
+pub async fn get_linear_settings(db: State<'_, AgentDb>) -> Result<LinearSettings, String> {
+    let mut settings = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        load_settings(&conn)
+    };
+    settings.has_api_key = read_keychain_value(API_KEY_ACCOUNT)?.is_some();
+    Ok(settings)
+}
+
+/// Save the Linear settings
+///
+/// `api_key` replaces the stored key; an empty one removes it and `None` keeps it.
+#[tauri::command]
+#[specta::specta]
+pub async fn set_linear_settings(
+    db: State<'_, AgentDb>,
+    settings: LinearSettings,
+    api_key: Option<String>,
+) -> Result<(), String> {
+    match api_key.as_deref() {
+        Some("") => delete_keychain_value(API_KEY_ACCOUNT)?,
+        Some(key) => write_keychain_value(API_KEY_ACCOUNT, key)?,
+        None => {}
+    }
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    set_setting(
+        &conn,
+        SETTINGS_KEY,
+        &LinearSettings {
+            has_api_key: false,
+            ..settings
+        },
+    )
+    .map_err(|e| format!("Failed to save Linear settings: {}", e))?;
+    Ok(())
+}
+
+/// A Linear team the API key can see
+#[derive(Debug, Serialize, Deserialize, Clone, specta::Type)]
+pub struct LinearTeam {
+    pub key: String,
+    pub name: String,
+}
+
+/// List the teams the saved API key can see, which also checks the key
+#[tauri::command]
+#[specta::specta]
+pub async fn list_linear_teams(db: State<'_, AgentDb>) -> Result<Vec<LinearTeam>, String> {
+    let provider = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        LinearProvider::from_settings(&conn)?
+    }
+    .ok_or("Linear is not enabled")?;
+    let data = provider
+        .query("query { teams { nodes { key name } } }", json!({}))
+        .await?;
+    Ok(data["teams"]["nodes"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|team| LinearTeam {
+            key: team["key"].as_str().unwrap_or_default().to_string(),
+            name: team["name"].as_str().unwrap_or_default().to_string(),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn provider() -> LinearProvider {
+        LinearProvider {
+            settings: LinearSettings {
+                enabled: true,
+                team_keys: vec!["ENG".to_string()],
+                team_repos: HashMap::from([("ENG".to_string(), "/work/app".to_string())]),
+                default_team_key: Some("OPS".to_string()),
+                has_api_key: true,
+            },
+            api_key: String::new(),
+        }
+    }
+
+    #[test]
+    fn picks_the_team_for_new_issues() {
+        let provider = provider();
+        let mut draft = IssueDraft {
+            title: "Flaky test".to_string(),
+            body: String::new(),
+            target: None,
+            project_path: Some("/work/app".to_string()),
+        };
+        assert_eq!(provider.team_for(&draft).as_deref(), Some("ENG"));
+        draft.project_path = Some("/work/other".to_string());
+        assert_eq!(provider.team_for(&draft).as_deref(), Some("OPS"));
+        draft.target = Some("DES".to_string());
+        assert_eq!(provider.team_for(&draft).as_deref(), Some("DES"));
+    }
+
+    #[test]
+    fn maps_issue_nodes() {
+        let issue = provider().to_issue(&json!({
+            "identifier": "ENG-42",
+            "title": "Crash on start",
+            "url": "https://linear.app/acme/issue/ENG-42",
+            "state": { "name": "Todo" },
+            "assignee": null,
+            "labels": { "nodes": [{ "name": "bug" }] },
+            "team": { "key": "ENG" },
+        }));
+        assert_eq!(issue.key, "ENG-42");
+        assert_eq!(issue.project_path.as_deref(), Some("/work/app"));
+        assert_eq!(issue.labels, vec!["bug"]);
+    }
+}
@@ -0,0 +1,293 @@
+use log::debug;
+use rusqlite::Connection;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::path::{Component, Path};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+use super::agents::Agent;
+use super::claude::{Project, Session};
+use super::fs_watch::{FsWatchState, WatchScope};
+
+/// Projects whose session lists are kept at once
+const SESSION_LISTS_CAPACITY: usize = 32;
+
+/// Fixed-size map that evicts the least recently used entry when full
+pub struct LruCache<K, V> {
+    capacity: usize,
+    entries: HashMap<K, (V, u64)>,
+    tick: u64,
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> LruCache<K, V> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            entries: HashMap::new(),
+            tick: 0,
+        }
+    }
+
+    pub fn get(&mut self, key: &K) -> Option<V> {
+        self.tick += 1;
+        let tick = self.tick;
+        self.entries.get_mut(key).map(|(value, used)| {
+            *used = tick;
+            value.clone()
+        })
+    }
+
+    pub fn insert(&mut self, key: K, value: V) {
+        self.tick += 1;
+        if !self.entries.contains_key(&key) && self.entries.len() >= self.capacity {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (_, used))| *used)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.entries.insert(key, (value, self.tick));
+    }
+
+    pub fn remove(&mut self, key: &K) {
+        self.entries.remove(key);
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+/// Agent count, latest `updated_at` and latest version id
+pub type AgentListStamp = (i64, Option<String>, i64);
+
+/// Summarise the agents table so any change to the agent list changes the stamp
+///
+/// Saves through the app record a version, so the version id catches edits within the
+/// same second; deletes lower the count and direct edits bump `updated_at`.
+pub fn agent_list_stamp(conn: &Connection) -> rusqlite::Result<AgentListStamp> {
+    conn.query_row(
+        "SELECT COUNT(*), MAX(updated_at), (SELECT COALESCE(MAX(id), 0) FROM agent_versions) FROM agents",
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    )
+}
+
+/// Results of the listing commands, kept until the files or rows behind them change
+///
+/// Project and session lists are dropped by the `~/.claude/projects` watcher. The agent
+/// list is stamped with the state of the `agents` table, see [`agent_list_stamp`].
+pub struct ListingCache {
+    projects: Mutex<Option<Vec<Project>>>,
+    sessions: Mutex<LruCache<String, Vec<Session>>>,
+    agents: Mutex<Option<(AgentListStamp, Vec<Agent>)>>,
+    /// Bumped on every invalidation so a scan that raced with a change is not stored
+    generation: AtomicU64,
+    /// Off when the watcher could not start, as nothing would invalidate the file listings
+    watching: AtomicBool,
+}
+
+impl Default for ListingCache {
+    fn default() -> Self {
+        Self {
+            projects: Mutex::new(None),
+            sessions: Mutex::new(LruCache::new(SESSION_LISTS_CAPACITY)),
+            agents: Mutex::new(None),
+            generation: AtomicU64::new(0),
+            watching: AtomicBool::new(true),
+        }
+    }
+}
+
+impl ListingCache {
+    /// Take before listing and pass to `store_*`
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::SeqCst)
+    }
+
+    fn is_current(&self, generation: u64) -> bool {
+        self.generation() == generation
+    }
+
+    pub fn projects(&self) -> Option<Vec<Project>> {
+        if !self.watching.load(Ordering::SeqCst) {
+            return None;
+        }
+        self.projects.lock().ok()?.clone()
+    }
+
+    pub fn store_projects(&self, generation: u64, projects: &[Project]) {
+        if let (true, Ok(mut cached)) = (self.is_current(generation), self.projects.lock()) {
+            *cached = Some(projects.to_vec());
+        }
+    }
+
+    pub fn sessions(&self, project_id: &str) -> Option<Vec<Session>> {
+        if !self.watching.load(Ordering::SeqCst) {
+            return None;
+        }
+        self.sessions.lock().ok()?.get(&project_id.to_string())
+    }
+
+    pub fn store_sessions(&self, generation: u64, project_id: &str, sessions: &[Session]) {
+        if let (true, Ok(mut cached)) = (self.is_current(generation), self.sessions.lock()) {
+            cached.insert(project_id.to_string(), sessions.to_vec());
+        }
+    }
+
+    /// Cached agents if the agents table has not changed since they were listed
+    pub fn agents(&self, stamp: &AgentListStamp) -> Option<Vec<Agent>> {
+        match self.agents.lock().ok()?.as_ref() {
+            Some((cached_stamp, agents)) if cached_stamp == stamp => Some(agents.clone()),
+            _ => None,
+        }
+    }
+
+    pub fn store_agents(&self, stamp: AgentListStamp, agents: &[Agent]) {
+        if let Ok(mut cached) = self.agents.lock() {
+            *cached = Some((stamp, agents.to_vec()));
+        }
+    }
+
+    /// Drop the project list and the session lists of the projects `paths` belong to
+    pub fn invalidate_paths(&self, projects_dir: &Path, paths: &[String]) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        if let Ok(mut cached) = self.projects.lock() {
+            *cached = None;
+        }
+        let Ok(mut sessions) = self.sessions.lock() else {
+            return;
+        };
+        for path in paths {
+            let project_id = Path::new(path)
+                .strip_prefix(projects_dir)
+                .ok()
+                .and_then(|rel| match rel.components().next() {
+                    Some(Component::Normal(id)) => id.to_str().map(str::to_string),
+                    _ => None,
+                });
+            match project_id {
+                Some(id) => sessions.remove(&id),
+                None => sessions.clear(),
+            }
+        }
+    }
+
+    /// Forget everything, e.g. after the database was replaced
+    pub fn invalidate_all(&self) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        if let Ok(mut cached) = self.projects.lock() {
+            *cached = None;
+        }
+        if let Ok(mut cached) = self.sessions.lock() {
+            cached.clear();
+        }
+        if let Ok(mut cached) = self.agents.lock() {
+            *cached = None;
+        }
+    }
+}
+
+/// Keep the cache in step with `~/.claude/projects` through the shared watcher
+pub fn start_listing_cache_invalidation(app: AppHandle) -> Result<(), String> {
+    let watch = app.state::<FsWatchState>().0.clone();
+    let projects_dir = watch.projects_dir().to_path_buf();
+    let cache_app = app.clone();
+    let subscribed = watch.subscribe(WatchScope::Projects, move |change| {
+        debug!(
+            "Invalidating cached listings for {} changed paths",
+            change.paths.len()
+        );
+        cache_app
+            .state::<ListingCache>()
+            .invalidate_paths(&projects_dir, &change.paths);
+    });
+    if subscribed.is_err() {
+        app.state::<ListingCache>()
+            .watching
+            .store(false, Ordering::SeqCst);
+    }
+    subscribed.map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evicts_least_recently_used_and_invalidates_by_project() {
+        let mut lru = LruCache::new(2);
+        lru.insert("a", 1);
+        lru.insert("b", 2);
+        assert_eq!(lru.get(&"a"), Some(1));
+        lru.insert("c", 3);
+        assert_eq!(lru.get(&"b"), None);
+        assert_eq!((lru.get(&"a"), lru.get(&"c")), (Some(1), Some(3)));
+
+        let cache = ListingCache::default();
+        let session = |project_id: &str| Session {
+            id: "s".to_string(),
+            project_id: project_id.to_string(),
+            project_path: String::new(),
+            todo_data: None,
+            created_at: 0,
+            first_message: None,
+            message_timestamp: None,
+            archived: false,
+        };
+        let generation = cache.generation();
+        cache.store_projects(generation, &[]);
+        cache.store_sessions(generation, "-one", &[session("-one")]);
+        cache.store_sessions(generation, "-two", &[session("-two")]);
+        let root = Path::new("/home/me/.claude/projects");
+        cache.invalidate_paths(
+            root,
+            &["/home/me/.claude/projects/-one/s.jsonl".to_string()],
+        );
+        assert!(cache.projects().is_none());
+        assert!(cache.sessions("-one").is_none());
+        assert_eq!(cache.sessions("-two").map(|s| s.len()), Some(1));
+        // A listing started before the change is not cached
+        cache.store_sessions(generation, "-one", &[session("-one")]);
+        assert!(cache.sessions("-one").is_none());
+
+        cache.store_agents((0, None, 0), &[]);
+        assert!(cache.agents(&(0, None, 0)).is_some());
+        assert!(cache.agents(&(1, None, 1)).is_none());
+    }
+
+    #[test]
+    fn agent_list_stamp_changes_with_the_agents_not_other_writes() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE agents (id INTEGER PRIMARY KEY, name TEXT, updated_at TEXT);
+             CREATE TABLE agent_versions (id INTEGER PRIMARY KEY AUTOINCREMENT, agent_id INTEGER);
+             CREATE TABLE agent_runs (id INTEGER PRIMARY KEY, status TEXT);
+             INSERT INTO agents VALUES (1, 'a', '2026-01-01 10:00:00');
+             INSERT INTO agent_versions (agent_id) VALUES (1);",
+        )
+        .unwrap();
+        let listed = agent_list_stamp(&conn).unwrap();
+
+        conn.execute("INSERT INTO agent_runs (status) VALUES ('running')", [])
+            .unwrap();
+        assert_eq!(agent_list_stamp(&conn).unwrap(), listed);
+
+        // An edit saved within the same second still records a new version
+        conn.execute_batch(
+            "UPDATE agents SET name = 'b' WHERE id = 1;
+             INSERT INTO agent_versions (agent_id) VALUES (1);",
+        )
+        .unwrap();
+        let edited = agent_list_stamp(&conn).unwrap();
+        assert_ne!(edited, listed);
+
+        conn.execute("DELETE FROM agents WHERE id = 1", []).unwrap();
+        assert_ne!(agent_list_stamp(&conn).unwrap(), edited);
+    }
+}
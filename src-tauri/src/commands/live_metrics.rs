@@ -0,0 +1,376 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use super::usage::estimate_cost;
+
+/// How often `agent:metrics` events are emitted while a run is active
+const EMIT_INTERVAL_MS: u64 = 1000;
+
+/// Optional spending limits for a single run
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, specta::Type)]
+pub struct RunBudget {
+    pub max_cost_usd: Option<f64>,
+    pub max_tokens: Option<u64>,
+}
+
+impl RunBudget {
+    pub fn is_limited(&self) -> bool {
+        self.max_cost_usd.is_some() || self.max_tokens.is_some()
+    }
+
+    /// Describe the exceeded limit, if any
+    pub fn check(&self, metrics: &AgentLiveMetrics) -> Option<String> {
+        if let Some(max_cost) = self.max_cost_usd {
+            if metrics.estimated_cost_usd >= max_cost {
+                return Some(format!(
+                    "Budget exceeded: ${:.4} spent, limit is ${:.4}",
+                    metrics.estimated_cost_usd, max_cost
+                ));
+            }
+        }
+        if let Some(max_tokens) = self.max_tokens {
+            if metrics.total_tokens >= max_tokens {
+                return Some(format!(
+                    "Budget exceeded: {} tokens used, limit is {}",
+                    metrics.total_tokens, max_tokens
+                ));
+            }
+        }
+        None
+    }
+}
+
+/// Terminate a process that went over its budget without blocking the caller
+pub fn terminate_process(pid: u32) {
+    if pid == 0 {
+        return;
+    }
+    tauri::async_runtime::spawn_blocking(move || {
+        let grace = crate::process::terminate::grace_period();
+        if let Err(e) = crate::process::terminate::terminate_blocking(pid, grace) {
+            log::error!("Failed to terminate process {}: {}", pid, e);
+        }
+    });
+}
+
+/// Token usage of one API message
+#[derive(Debug, Default, Clone, Copy)]
+struct MessageUsage {
+    input_tokens: u64,
+    output_tokens: u64,
+    cache_creation_tokens: u64,
+    cache_read_tokens: u64,
+}
+
+/// Payload of the `agent:metrics` event
+#[derive(Debug, Serialize, Deserialize, Clone, Default, specta::Type)]
+pub struct AgentLiveMetrics {
+    pub run_id: i64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub total_tokens: u64,
+    pub estimated_cost_usd: f64,
+    pub elapsed_ms: u64,
+    pub message_count: u64,
+    /// Whether the final `result` event reported an error; `None` until it arrives
+    pub is_error: Option<bool>,
+    /// True for the last event of a run
+    pub finished: bool,
+}
+
+/// Accumulates usage from a run's stream-json output
+#[derive(Debug)]
+pub struct RunMetricsAccumulator {
+    run_id: i64,
+    started_at: Instant,
+    default_model: String,
+    /// Usage per message ID; the CLI repeats a message's usage for every content block
+    messages: HashMap<String, (String, MessageUsage)>,
+    /// Total reported by the final `result` event, which replaces the estimate
+    reported_cost: Option<f64>,
+    result_is_error: Option<bool>,
+}
+
+impl RunMetricsAccumulator {
+    pub fn new(run_id: i64, default_model: String) -> Self {
+        Self {
+            run_id,
+            started_at: Instant::now(),
+            default_model,
+            messages: HashMap::new(),
+            reported_cost: None,
+            result_is_error: None,
+        }
+    }
+
+    /// Feed one line of stream-json output
+    pub fn observe_line(&mut self, line: &str) {
+        let Ok(json) = serde_json::from_str::<JsonValue>(line) else {
+            return;
+        };
+
+        match json.get("type").and_then(|t| t.as_str()) {
+            Some("assistant") => {
+                let Some(message) = json.get("message") else {
+                    return;
+                };
+                let Some(usage) = message.get("usage") else {
+                    return;
+                };
+                let read = |key: &str| usage.get(key).and_then(|v| v.as_u64()).unwrap_or(0);
+                let usage = MessageUsage {
+                    input_tokens: read("input_tokens"),
+                    output_tokens: read("output_tokens"),
+                    cache_creation_tokens: read("cache_creation_input_tokens"),
+                    cache_read_tokens: read("cache_read_input_tokens"),
+                };
+                let model = message
+                    .get("model")
+                    .and_then(|m| m.as_str())
+                    .unwrap_or(&self.default_model)
+                    .to_string();
+                let id = message
+                    .get("id")
+                    .and_then(|id| id.as_str())
+                    .map(str::to_string)
+                    .unwrap_or_else(|| format!("anonymous-{}", self.messages.len()));
+                self.messages.insert(id, (model, usage));
+            }
+            Some("result") => {
+                if let Some(cost) = json
+                    .get("total_cost_usd")
+                    .or_else(|| json.get("cost_usd"))
+                    .and_then(|c| c.as_f64())
+                {
+                    self.reported_cost = Some(cost);
+                }
+                self.result_is_error = json.get("is_error").and_then(|e| e.as_bool());
+            }
+            _ => {}
+        }
+    }
+
+    /// Current totals
+    pub fn snapshot(&self, finished: bool) -> AgentLiveMetrics {
+        let mut metrics = AgentLiveMetrics {
+            run_id: self.run_id,
+            elapsed_ms: self.started_at.elapsed().as_millis() as u64,
+            message_count: self.messages.len() as u64,
+            is_error: self.result_is_error,
+            finished,
+            ..Default::default()
+        };
+
+        let mut estimated_cost = 0.0;
+        for (model, usage) in self.messages.values() {
+            metrics.input_tokens += usage.input_tokens;
+            metrics.output_tokens += usage.output_tokens;
+            metrics.cache_creation_tokens += usage.cache_creation_tokens;
+            metrics.cache_read_tokens += usage.cache_read_tokens;
+            estimated_cost += estimate_cost(
+                model,
+                usage.input_tokens,
+                usage.output_tokens,
+                usage.cache_creation_tokens,
+                usage.cache_read_tokens,
+            );
+        }
+        metrics.total_tokens = metrics.input_tokens
+            + metrics.output_tokens
+            + metrics.cache_creation_tokens
+            + metrics.cache_read_tokens;
+        metrics.estimated_cost_usd = self.reported_cost.unwrap_or(estimated_cost);
+        metrics
+    }
+}
+
+/// Trackers of streamed executions by process registry run ID, for `get_live_cost`
+///
+/// Finished runs keep their final totals until the next run starts.
+#[derive(Default)]
+pub struct LiveCostState(pub Mutex<HashMap<i64, LiveMetricsTracker>>);
+
+impl LiveCostState {
+    fn register(&self, run_id: i64, tracker: LiveMetricsTracker) {
+        if let Ok(mut trackers) = self.0.lock() {
+            trackers.retain(|_, t| !t.finished.load(Ordering::Relaxed));
+            trackers.insert(run_id, tracker);
+        }
+    }
+
+    /// Current totals of a run, `None` when it is not tracked
+    pub fn metrics(&self, run_id: i64) -> Option<AgentLiveMetrics> {
+        let trackers = self.0.lock().ok()?;
+        trackers
+            .get(&run_id)
+            .map(|tracker| tracker.snapshot(tracker.finished.load(Ordering::Relaxed)))
+    }
+}
+
+/// Shared handle to a run's metrics that emits metrics events on a timer
+///
+/// Agent runs emit `agent:metrics`; interactive sessions emit `claude-cost`
+/// scoped by the session ID once Claude reports it.
+#[derive(Clone)]
+pub struct LiveMetricsTracker {
+    accumulator: Arc<Mutex<RunMetricsAccumulator>>,
+    finished: Arc<AtomicBool>,
+    budget: RunBudget,
+    budget_exceeded: Arc<AtomicBool>,
+    session_id: Option<Arc<Mutex<Option<String>>>>,
+    app: AppHandle,
+}
+
+impl LiveMetricsTracker {
+    /// Create a tracker for an agent run and start its emit timer
+    pub fn start(app: AppHandle, run_id: i64, model: String, budget: RunBudget) -> Self {
+        let tracker = Self::spawn(app, run_id, model, budget, None);
+        tracker
+            .app
+            .state::<LiveCostState>()
+            .register(run_id, tracker.clone());
+        tracker
+    }
+
+    /// Create a tracker for an interactive session whose ID is filled in from its init message
+    ///
+    /// The tracker becomes visible to `get_live_cost` once [`Self::attach_run`] is called.
+    pub fn start_session(
+        app: AppHandle,
+        model: String,
+        budget: RunBudget,
+        session_id: Arc<Mutex<Option<String>>>,
+    ) -> Self {
+        Self::spawn(app, 0, model, budget, Some(session_id))
+    }
+
+    /// Record the process registry run ID of a session
+    pub fn attach_run(&self, run_id: i64) {
+        if let Ok(mut accumulator) = self.accumulator.lock() {
+            accumulator.run_id = run_id;
+        }
+        self.app
+            .state::<LiveCostState>()
+            .register(run_id, self.clone());
+    }
+
+    fn spawn(
+        app: AppHandle,
+        run_id: i64,
+        model: String,
+        budget: RunBudget,
+        session_id: Option<Arc<Mutex<Option<String>>>>,
+    ) -> Self {
+        let tracker = Self {
+            accumulator: Arc::new(Mutex::new(RunMetricsAccumulator::new(run_id, model))),
+            finished: Arc::new(AtomicBool::new(false)),
+            budget,
+            budget_exceeded: Arc::new(AtomicBool::new(false)),
+            session_id,
+            app,
+        };
+
+        let ticker = tracker.clone();
+        tauri::async_runtime::spawn(async move {
+            let mut interval =
+                tokio::time::interval(std::time::Duration::from_millis(EMIT_INTERVAL_MS));
+            loop {
+                interval.tick().await;
+                if ticker.finished.load(Ordering::Relaxed) {
+                    break;
+                }
+                ticker.emit(false);
+            }
+        });
+
+        tracker
+    }
+
+    /// Feed one line of output; returns the reason the first time the budget is exceeded
+    pub fn observe_line(&self, line: &str) -> Option<String> {
+        let mut accumulator = self.accumulator.lock().ok()?;
+        accumulator.observe_line(line);
+        if !self.budget.is_limited() || self.budget_exceeded.load(Ordering::Relaxed) {
+            return None;
+        }
+        let reason = self.budget.check(&accumulator.snapshot(false))?;
+        self.budget_exceeded.store(true, Ordering::Relaxed);
+        Some(reason)
+    }
+
+    pub fn budget_exceeded(&self) -> bool {
+        self.budget_exceeded.load(Ordering::Relaxed)
+    }
+
+    /// Stop the timer, emit the final totals and return them
+    pub fn finish(&self) -> AgentLiveMetrics {
+        if !self.finished.swap(true, Ordering::Relaxed) {
+            self.emit(true)
+        } else {
+            self.snapshot(true)
+        }
+    }
+
+    fn snapshot(&self, finished: bool) -> AgentLiveMetrics {
+        self.accumulator
+            .lock()
+            .map(|accumulator| accumulator.snapshot(finished))
+            .unwrap_or_default()
+    }
+
+    fn emit(&self, finished: bool) -> AgentLiveMetrics {
+        let metrics = self.snapshot(finished);
+        if let Some(session_id) = &self.session_id {
+            if let Some(session_id) = session_id.lock().ok().and_then(|id| id.clone()) {
+                let _ = self
+                    .app
+                    .emit(&format!("claude-cost:{}", session_id), &metrics);
+            }
+            let _ = self.app.emit("claude-cost", &metrics);
+            return metrics;
+        }
+        let _ = self
+            .app
+            .emit(&format!("agent:metrics:{}", metrics.run_id), &metrics);
+        let _ = self.app.emit("agent:metrics", &metrics);
+        metrics
+    }
+}
+
+/// Get the running totals of a streamed execution by its process registry run ID
+#[tauri::command]
+#[specta::specta]
+pub fn get_live_cost(
+    state: State<'_, LiveCostState>,
+    run_id: i64,
+) -> Result<Option<AgentLiveMetrics>, String> {
+    Ok(state.metrics(run_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeated_message_usage_is_counted_once() {
+        let mut accumulator = RunMetricsAccumulator::new(1, "sonnet".to_string());
+        let line = r#"{"type":"assistant","message":{"id":"msg_1","model":"claude-sonnet-4-20250514","usage":{"input_tokens":1000,"output_tokens":500}}}"#;
+        accumulator.observe_line(line);
+        accumulator.observe_line(line);
+
+        let metrics = accumulator.snapshot(false);
+        assert_eq!(metrics.total_tokens, 1500);
+        assert_eq!(metrics.message_count, 1);
+        assert!((metrics.estimated_cost_usd - 0.0105).abs() < 1e-9);
+
+        accumulator.observe_line(r#"{"type":"result","total_cost_usd":0.02}"#);
+        assert_eq!(accumulator.snapshot(true).estimated_cost_usd, 0.02);
+    }
+}
@@ -21,7 +21,7 @@ fn find_claude_binary(app_handle: &AppHandle) -> Result<String> {
 }
 
 /// Represents an MCP server configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
 pub struct MCPServer {
     /// Server name/identifier
     pub name: String,
@@ -35,6 +35,9 @@ pub struct MCPServer {
     pub env: HashMap<String, String>,
     /// URL endpoint (for SSE)
     pub url: Option<String>,
+    /// Headers sent with every request, as `Name: value` (for SSE)
+    #[serde(default)]
+    pub headers: Vec<String>,
     /// Configuration scope: "local", "project", or "user"
     pub scope: String,
     /// Whether the server is currently active
@@ -44,7 +47,7 @@ pub struct MCPServer {
 }
 
 /// Server status information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
 pub struct ServerStatus {
     /// Whether the server is running
     pub running: bool,
@@ -55,24 +58,47 @@ pub struct ServerStatus {
 }
 
 /// MCP configuration for project scope (.mcp.json)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
 pub struct MCPProjectConfig {
     #[serde(rename = "mcpServers")]
     pub mcp_servers: HashMap<String, MCPServerConfig>,
 }
 
 /// Individual server configuration in .mcp.json
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
 pub struct MCPServerConfig {
+    /// Transport type; omitted for stdio servers
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub server_type: Option<String>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub command: String,
     #[serde(default)]
     pub args: Vec<String>,
     #[serde(default)]
     pub env: HashMap<String, String>,
+    /// URL endpoint (for SSE)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+/// Whether Claude Code may start a server defined in a project's .mcp.json
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct ProjectServerApproval {
+    pub name: String,
+    /// "approved", "rejected" or "pending" (Claude Code will ask on next start)
+    pub status: String,
+}
+
+/// Approval state of all servers in a project's .mcp.json
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct MCPProjectApprovals {
+    /// Every project server is trusted without asking
+    pub enable_all: bool,
+    pub servers: Vec<ProjectServerApproval>,
 }
 
 /// Result of adding a server
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
 pub struct AddServerResult {
     pub success: bool,
     pub message: String,
@@ -80,21 +106,78 @@ pub struct AddServerResult {
 }
 
 /// Import result for multiple servers
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
 pub struct ImportResult {
     pub imported_count: u32,
     pub failed_count: u32,
+    /// Servers left alone because their name was already configured
+    pub skipped_count: u32,
     pub servers: Vec<ImportServerResult>,
 }
 
 /// Result for individual server import
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
 pub struct ImportServerResult {
     pub name: String,
     pub success: bool,
     pub error: Option<String>,
 }
 
+/// Scopes accepted by `claude mcp add/remove -s`
+const MCP_SCOPES: [&str; 3] = ["local", "project", "user"];
+
+/// Transports accepted by `claude mcp add --transport`
+const MCP_TRANSPORTS: [&str; 2] = ["stdio", "sse"];
+
+/// Builds a server entry from one `claude mcp list` line, i.e. the text after `name:`
+///
+/// Newer CLI versions append a health suffix such as ` - ✓ Connected`.
+fn server_from_list_entry(name: &str, entry: &str) -> MCPServer {
+    let mut entry = entry.trim();
+    let mut running = false;
+    let mut error = None;
+    if let Some((definition, health)) = entry.rsplit_once(" - ") {
+        if health.contains('✓') || health.contains('✗') {
+            running = health.contains('✓');
+            if !running {
+                error = Some(health.trim_start_matches('✗').trim().to_string());
+            }
+            entry = definition.trim();
+        }
+    }
+
+    let (transport, command, args, url) =
+        if entry.starts_with("http://") || entry.starts_with("https://") {
+            let url = entry
+                .trim_end_matches("(SSE)")
+                .trim_end_matches("(HTTP)")
+                .trim()
+                .to_string();
+            ("sse".to_string(), None, vec![], Some(url))
+        } else {
+            let mut parts = entry.split_whitespace().map(|s| s.to_string());
+            let command = parts.next();
+            ("stdio".to_string(), command, parts.collect(), None)
+        };
+
+    MCPServer {
+        name: name.to_string(),
+        transport,
+        command,
+        args,
+        env: HashMap::new(),
+        url,
+        headers: vec![],
+        scope: "local".to_string(), // Not part of the list output
+        is_active: running,
+        status: ServerStatus {
+            running,
+            error,
+            last_checked: None,
+        },
+    }
+}
+
 /// Executes a claude mcp command
 fn execute_claude_mcp_command(app_handle: &AppHandle, args: Vec<&str>) -> Result<String> {
     info!("Executing claude mcp command with args: {:?}", args);
@@ -118,6 +201,7 @@ fn execute_claude_mcp_command(app_handle: &AppHandle, args: Vec<&str>) -> Result
 
 /// Adds a new MCP server
 #[tauri::command]
+#[specta::specta]
 pub async fn mcp_add(
     app: AppHandle,
     name: String,
@@ -130,6 +214,29 @@ pub async fn mcp_add(
 ) -> Result<AddServerResult, String> {
     info!("Adding MCP server: {} with transport: {}", name, transport);
 
+    if !MCP_SCOPES.contains(&scope.as_str()) {
+        return Ok(AddServerResult {
+            success: false,
+            message: format!(
+                "Unknown scope '{}', expected one of: {}",
+                scope,
+                MCP_SCOPES.join(", ")
+            ),
+            server_name: None,
+        });
+    }
+    if !MCP_TRANSPORTS.contains(&transport.as_str()) {
+        return Ok(AddServerResult {
+            success: false,
+            message: format!(
+                "Unknown transport '{}', expected one of: {}",
+                transport,
+                MCP_TRANSPORTS.join(", ")
+            ),
+            server_name: None,
+        });
+    }
+
     // Prepare owned strings for environment variables
     let env_args: Vec<String> = env
         .iter()
@@ -210,6 +317,7 @@ pub async fn mcp_add(
 
 /// Lists all configured MCP servers
 #[tauri::command]
+#[specta::specta]
 pub async fn mcp_list(app: AppHandle) -> Result<Vec<MCPServer>, String> {
     info!("Listing MCP servers");
 
@@ -285,22 +393,7 @@ pub async fn mcp_list(app: AppHandle) -> Result<Vec<MCPServer>, String> {
                         let full_command = command_parts.join(" ");
                         info!("Full command for server '{}': {:?}", name, full_command);
 
-                        // For now, we'll create a basic server entry
-                        servers.push(MCPServer {
-                            name: name.clone(),
-                            transport: "stdio".to_string(), // Default assumption
-                            command: Some(full_command),
-                            args: vec![],
-                            env: HashMap::new(),
-                            url: None,
-                            scope: "local".to_string(), // Default assumption
-                            is_active: false,
-                            status: ServerStatus {
-                                running: false,
-                                error: None,
-                                last_checked: None,
-                            },
-                        });
+                        servers.push(server_from_list_entry(&name, &full_command));
                         info!("Added server: {:?}", name);
 
                         continue;
@@ -332,79 +425,129 @@ pub async fn mcp_list(app: AppHandle) -> Result<Vec<MCPServer>, String> {
 
 /// Gets details for a specific MCP server
 #[tauri::command]
+#[specta::specta]
 pub async fn mcp_get(app: AppHandle, name: String) -> Result<MCPServer, String> {
     info!("Getting MCP server details for: {}", name);
 
     match execute_claude_mcp_command(&app, vec!["get", &name]) {
-        Ok(output) => {
-            // Parse the structured text output
-            let mut scope = "local".to_string();
-            let mut transport = "stdio".to_string();
-            let mut command = None;
-            let mut args = vec![];
-            let env = HashMap::new();
-            let mut url = None;
-
-            for line in output.lines() {
-                let line = line.trim();
-
-                if line.starts_with("Scope:") {
-                    let scope_part = line.replace("Scope:", "").trim().to_string();
-                    if scope_part.to_lowercase().contains("local") {
-                        scope = "local".to_string();
-                    } else if scope_part.to_lowercase().contains("project") {
-                        scope = "project".to_string();
-                    } else if scope_part.to_lowercase().contains("user")
-                        || scope_part.to_lowercase().contains("global")
-                    {
-                        scope = "user".to_string();
-                    }
-                } else if line.starts_with("Type:") {
-                    transport = line.replace("Type:", "").trim().to_string();
-                } else if line.starts_with("Command:") {
-                    command = Some(line.replace("Command:", "").trim().to_string());
-                } else if line.starts_with("Args:") {
-                    let args_str = line.replace("Args:", "").trim().to_string();
-                    if !args_str.is_empty() {
-                        args = args_str.split_whitespace().map(|s| s.to_string()).collect();
-                    }
-                } else if line.starts_with("URL:") {
-                    url = Some(line.replace("URL:", "").trim().to_string());
-                } else if line.starts_with("Environment:") {
-                    // TODO: Parse environment variables if they're listed
-                    // For now, we'll leave it empty
+        Ok(output) => Ok(server_from_get_output(name, &output)),
+        Err(e) => {
+            error!("Failed to get MCP server: {}", e);
+            Err(e.to_string())
+        }
+    }
+}
+
+/// Parses the structured text output of `claude mcp get`
+fn server_from_get_output(name: String, output: &str) -> MCPServer {
+    let mut scope = "local".to_string();
+    let mut transport = "stdio".to_string();
+    let mut command = None;
+    let mut args = vec![];
+    let mut env = HashMap::new();
+    let mut url = None;
+    let mut headers = vec![];
+    let mut in_environment = false;
+    let mut in_headers = false;
+
+    for line in output.lines() {
+        let line = line.trim();
+
+        // Environment variables are listed one per line below "Environment:"
+        if in_environment {
+            if let Some((key, value)) = line.split_once('=') {
+                if !key.is_empty() && !key.contains(' ') {
+                    env.insert(key.to_string(), value.to_string());
+                    continue;
                 }
             }
+            in_environment = false;
+        }
 
-            Ok(MCPServer {
-                name,
-                transport,
-                command,
-                args,
-                env,
-                url,
-                scope,
-                is_active: false,
-                status: ServerStatus {
-                    running: false,
-                    error: None,
-                    last_checked: None,
-                },
-            })
+        // Headers likewise below "Headers:", as `Name: value`
+        if in_headers {
+            if let Some((key, _)) = line.split_once(':') {
+                if !key.is_empty() && !key.contains(' ') {
+                    headers.push(line.to_string());
+                    continue;
+                }
+            }
+            in_headers = false;
         }
-        Err(e) => {
-            error!("Failed to get MCP server: {}", e);
-            Err(e.to_string())
+
+        if line.starts_with("Scope:") {
+            let scope_part = line.replace("Scope:", "").trim().to_string();
+            // "User config (available in all your projects)" mentions projects too
+            if scope_part.to_lowercase().contains("local") {
+                scope = "local".to_string();
+            } else if scope_part.to_lowercase().contains("user")
+                || scope_part.to_lowercase().contains("global")
+            {
+                scope = "user".to_string();
+            } else if scope_part.to_lowercase().contains("project") {
+                scope = "project".to_string();
+            }
+        } else if line.starts_with("Type:") {
+            transport = line.replace("Type:", "").trim().to_string();
+        } else if line.starts_with("Command:") {
+            command = Some(line.replace("Command:", "").trim().to_string());
+        } else if line.starts_with("Args:") {
+            let args_str = line.replace("Args:", "").trim().to_string();
+            if !args_str.is_empty() {
+                args = args_str.split_whitespace().map(|s| s.to_string()).collect();
+            }
+        } else if line.starts_with("URL:") {
+            url = Some(line.replace("URL:", "").trim().to_string());
+        } else if line.starts_with("Environment:") {
+            in_environment = true;
+        } else if line.starts_with("Headers:") {
+            in_headers = true;
         }
     }
+
+    MCPServer {
+        name,
+        transport,
+        command,
+        args,
+        env,
+        url,
+        headers,
+        scope,
+        is_active: false,
+        status: ServerStatus {
+            running: false,
+            error: None,
+            last_checked: None,
+        },
+    }
 }
 
-/// Removes an MCP server
+/// Removes an MCP server, optionally only from the given scope
 #[tauri::command]
-pub async fn mcp_remove(app: AppHandle, name: String) -> Result<String, String> {
-    info!("Removing MCP server: {}", name);
+#[specta::specta]
+pub async fn mcp_remove(
+    app: AppHandle,
+    name: String,
+    scope: Option<String>,
+) -> Result<String, String> {
+    info!("Removing MCP server: {} (scope: {:?})", name, scope);
 
-    match execute_claude_mcp_command(&app, vec!["remove", &name]) {
+    let mut cmd_args = vec!["remove"];
+    if let Some(scope) = &scope {
+        if !MCP_SCOPES.contains(&scope.as_str()) {
+            return Err(format!(
+                "Unknown scope '{}', expected one of: {}",
+                scope,
+                MCP_SCOPES.join(", ")
+            ));
+        }
+        cmd_args.push("-s");
+        cmd_args.push(scope);
+    }
+    cmd_args.push(&name);
+
+    match execute_claude_mcp_command(&app, cmd_args) {
         Ok(output) => {
             info!("Successfully removed MCP server: {}", name);
             Ok(output.trim().to_string())
@@ -416,8 +559,62 @@ pub async fn mcp_remove(app: AppHandle, name: String) -> Result<String, String>
     }
 }
 
+/// Arguments of `claude mcp add` registering a remote server as it is, but with `headers`
+fn remote_server_add_args(server: &MCPServer, headers: &[String]) -> Result<Vec<String>, String> {
+    let url = server
+        .url
+        .as_deref()
+        .ok_or_else(|| format!("Server '{}' has no URL", server.name))?;
+    if server.transport == "stdio" {
+        return Err(format!("Server '{}' is not a remote server", server.name));
+    }
+
+    let mut args: Vec<String> = ["add", "-s", &server.scope, "--transport", &server.transport]
+        .map(String::from)
+        .to_vec();
+    for (key, value) in &server.env {
+        args.push("-e".to_string());
+        args.push(format!("{}={}", key, value));
+    }
+    for header in headers {
+        args.push("--header".to_string());
+        args.push(header.clone());
+    }
+    args.push(server.name.clone());
+    args.push(url.to_string());
+    Ok(args)
+}
+
+/// Re-registers a remote server in its scope so every request carries the given headers
+///
+/// The CLI can't edit a server, so it is removed and added again; if adding fails, the
+/// previous registration is restored.
+pub fn set_sse_server_headers(
+    app: &AppHandle,
+    server: &MCPServer,
+    headers: &[String],
+) -> Result<(), String> {
+    let updated = remote_server_add_args(server, headers)?;
+    let previous = remote_server_add_args(server, &server.headers)?;
+    fn as_strs(args: &[String]) -> Vec<&str> {
+        args.iter().map(String::as_str).collect()
+    }
+
+    execute_claude_mcp_command(app, vec!["remove", "-s", &server.scope, &server.name])
+        .map_err(|e| format!("Failed to update server '{}': {}", server.name, e))?;
+    if let Err(e) = execute_claude_mcp_command(app, as_strs(&updated)) {
+        if let Err(restore) = execute_claude_mcp_command(app, as_strs(&previous)) {
+            error!("Failed to restore MCP server {}: {}", server.name, restore);
+        }
+        return Err(format!("Failed to update server '{}': {}", server.name, e));
+    }
+    info!("Updated headers of MCP server {}", server.name);
+    Ok(())
+}
+
 /// Adds an MCP server from JSON configuration
 #[tauri::command]
+#[specta::specta]
 pub async fn mcp_add_json(
     app: AppHandle,
     name: String,
@@ -457,36 +654,29 @@ pub async fn mcp_add_json(
     }
 }
 
-/// Imports MCP servers from Claude Desktop
-#[tauri::command]
-pub async fn mcp_add_from_claude_desktop(
-    app: AppHandle,
-    scope: String,
-) -> Result<ImportResult, String> {
-    info!(
-        "Importing MCP servers from Claude Desktop with scope: {}",
-        scope
-    );
+/// An MCP server found in Claude Desktop's configuration
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct ClaudeDesktopServer {
+    pub name: String,
+    pub command: Option<String>,
+    pub args: Vec<String>,
+    pub env: HashMap<String, String>,
+    /// A server with this name is already configured in Claude Code
+    pub already_configured: bool,
+}
 
-    // Get Claude Desktop config path based on platform
-    let config_path = if cfg!(target_os = "macos") {
-        dirs::home_dir()
-            .ok_or_else(|| "Could not find home directory".to_string())?
-            .join("Library")
-            .join("Application Support")
-            .join("Claude")
-            .join("claude_desktop_config.json")
-    } else if cfg!(target_os = "linux") {
-        // For WSL/Linux, check common locations
-        dirs::config_dir()
-            .ok_or_else(|| "Could not find config directory".to_string())?
-            .join("Claude")
-            .join("claude_desktop_config.json")
-    } else {
-        return Err(
-            "Import from Claude Desktop is only supported on macOS and Linux/WSL".to_string(),
-        );
-    };
+/// Location of Claude Desktop's `claude_desktop_config.json`
+fn claude_desktop_config_path() -> Result<PathBuf, String> {
+    // ~/Library/Application Support on macOS, %APPDATA% on Windows, ~/.config on Linux/WSL
+    Ok(dirs::config_dir()
+        .ok_or_else(|| "Could not find config directory".to_string())?
+        .join("Claude")
+        .join("claude_desktop_config.json"))
+}
+
+/// Reads the `mcpServers` object of Claude Desktop's configuration
+fn read_claude_desktop_servers() -> Result<serde_json::Map<String, serde_json::Value>, String> {
+    let config_path = claude_desktop_config_path()?;
 
     // Check if config file exists
     if !config_path.exists() {
@@ -503,18 +693,99 @@ pub async fn mcp_add_from_claude_desktop(
     let config: serde_json::Value = serde_json::from_str(&config_content)
         .map_err(|e| format!("Failed to parse Claude Desktop config: {}", e))?;
 
-    // Extract MCP servers
-    let mcp_servers = config
+    config
         .get("mcpServers")
         .and_then(|v| v.as_object())
-        .ok_or_else(|| "No MCP servers found in Claude Desktop config".to_string())?;
+        .cloned()
+        .ok_or_else(|| "No MCP servers found in Claude Desktop config".to_string())
+}
+
+/// Names of the servers Claude Code already knows about
+pub async fn configured_server_names(app: &AppHandle) -> std::collections::HashSet<String> {
+    match mcp_list(app.clone()).await {
+        Ok(servers) => servers.into_iter().map(|s| s.name).collect(),
+        Err(e) => {
+            error!("Failed to list configured MCP servers: {}", e);
+            Default::default()
+        }
+    }
+}
+
+/// Lists the MCP servers configured in Claude Desktop
+#[tauri::command]
+#[specta::specta]
+pub async fn mcp_list_claude_desktop_servers(
+    app: AppHandle,
+) -> Result<Vec<ClaudeDesktopServer>, String> {
+    let mcp_servers = read_claude_desktop_servers()?;
+    let configured = configured_server_names(&app).await;
+
+    let mut servers: Vec<ClaudeDesktopServer> = mcp_servers
+        .iter()
+        .map(|(name, config)| ClaudeDesktopServer {
+            name: name.clone(),
+            command: config
+                .get("command")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+            args: config
+                .get("args")
+                .and_then(|v| serde_json::from_value(v.clone()).ok())
+                .unwrap_or_default(),
+            env: config
+                .get("env")
+                .and_then(|v| serde_json::from_value(v.clone()).ok())
+                .unwrap_or_default(),
+            already_configured: configured.contains(name),
+        })
+        .collect();
+    servers.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(servers)
+}
+
+/// Imports MCP servers from Claude Desktop
+///
+/// Only the servers in `names` are imported when given. Servers whose name is
+/// already configured in Claude Code are skipped rather than overwritten.
+#[tauri::command]
+#[specta::specta]
+pub async fn mcp_add_from_claude_desktop(
+    app: AppHandle,
+    scope: String,
+    names: Option<Vec<String>>,
+) -> Result<ImportResult, String> {
+    info!(
+        "Importing MCP servers from Claude Desktop with scope: {}",
+        scope
+    );
+
+    let mcp_servers = read_claude_desktop_servers()?;
+    let configured = configured_server_names(&app).await;
 
     let mut imported_count = 0;
     let mut failed_count = 0;
+    let mut skipped_count = 0;
     let mut server_results = Vec::new();
 
     // Import each server using add-json
-    for (name, server_config) in mcp_servers {
+    for (name, server_config) in &mcp_servers {
+        if let Some(names) = &names {
+            if !names.contains(name) {
+                continue;
+            }
+        }
+        if configured.contains(name) {
+            skipped_count += 1;
+            server_results.push(ImportServerResult {
+                name: name.clone(),
+                success: false,
+                error: Some("A server with this name is already configured".to_string()),
+            });
+            info!("Skipping server {}: already configured", name);
+            continue;
+        }
+
         info!("Importing server: {}", name);
 
         // Convert Claude Desktop format to add-json format
@@ -599,19 +870,21 @@ pub async fn mcp_add_from_claude_desktop(
     }
 
     info!(
-        "Import complete: {} imported, {} failed",
-        imported_count, failed_count
+        "Import complete: {} imported, {} failed, {} skipped",
+        imported_count, failed_count, skipped_count
     );
 
     Ok(ImportResult {
         imported_count,
         failed_count,
+        skipped_count,
         servers: server_results,
     })
 }
 
 /// Starts Claude Code as an MCP server
 #[tauri::command]
+#[specta::specta]
 pub async fn mcp_serve(app: AppHandle) -> Result<String, String> {
     info!("Starting Claude Code as MCP server");
 
@@ -641,6 +914,7 @@ pub async fn mcp_serve(app: AppHandle) -> Result<String, String> {
 
 /// Tests connection to an MCP server
 #[tauri::command]
+#[specta::specta]
 pub async fn mcp_test_connection(app: AppHandle, name: String) -> Result<String, String> {
     info!("Testing connection to MCP server: {}", name);
 
@@ -653,6 +927,7 @@ pub async fn mcp_test_connection(app: AppHandle, name: String) -> Result<String,
 
 /// Resets project-scoped server approval choices
 #[tauri::command]
+#[specta::specta]
 pub async fn mcp_reset_project_choices(app: AppHandle) -> Result<String, String> {
     info!("Resetting MCP project choices");
 
@@ -668,18 +943,21 @@ pub async fn mcp_reset_project_choices(app: AppHandle) -> Result<String, String>
     }
 }
 
-/// Gets the status of MCP servers
+/// Gets the status of MCP servers from their latest health checks
 #[tauri::command]
-pub async fn mcp_get_server_status() -> Result<HashMap<String, ServerStatus>, String> {
+#[specta::specta]
+pub async fn mcp_get_server_status(
+    db: tauri::State<'_, super::agents::AgentDb>,
+) -> Result<HashMap<String, ServerStatus>, String> {
     info!("Getting MCP server status");
 
-    // TODO: Implement actual status checking
-    // For now, return empty status
-    Ok(HashMap::new())
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    super::mcp_health::latest_server_statuses(&conn)
 }
 
 /// Reads .mcp.json from the current project
 #[tauri::command]
+#[specta::specta]
 pub async fn mcp_read_project_config(project_path: String) -> Result<MCPProjectConfig, String> {
     info!("Reading .mcp.json from project: {}", project_path);
 
@@ -708,6 +986,7 @@ pub async fn mcp_read_project_config(project_path: String) -> Result<MCPProjectC
 
 /// Saves .mcp.json to the current project
 #[tauri::command]
+#[specta::specta]
 pub async fn mcp_save_project_config(
     project_path: String,
     config: MCPProjectConfig,
@@ -716,11 +995,264 @@ pub async fn mcp_save_project_config(
 
     let mcp_json_path = PathBuf::from(&project_path).join(".mcp.json");
 
+    for (name, server) in &config.mcp_servers {
+        let is_remote = matches!(server.server_type.as_deref(), Some("sse") | Some("http"));
+        if is_remote && server.url.as_deref().is_none_or(str::is_empty) {
+            return Err(format!("Server '{}' needs a URL", name));
+        }
+        if !is_remote && server.command.is_empty() {
+            return Err(format!("Server '{}' needs a command", name));
+        }
+    }
+
     let json_content = serde_json::to_string_pretty(&config)
         .map_err(|e| format!("Failed to serialize config: {}", e))?;
 
-    fs::write(&mcp_json_path, json_content)
+    write_file_atomically(&mcp_json_path, &json_content)
         .map_err(|e| format!("Failed to write .mcp.json: {}", e))?;
 
     Ok("Project MCP configuration saved".to_string())
 }
+
+/// Writes through a temporary file so a crash never leaves a half-written config
+pub(crate) fn write_file_atomically(path: &std::path::Path, content: &str) -> std::io::Result<()> {
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, content)?;
+    fs::rename(&tmp_path, path)
+}
+
+/// Claude Code's global state file, which records per-project .mcp.json approvals
+fn claude_state_path() -> Result<PathBuf, String> {
+    Ok(dirs::home_dir()
+        .ok_or_else(|| "Could not find home directory".to_string())?
+        .join(".claude.json"))
+}
+
+fn read_claude_state() -> Result<serde_json::Value, String> {
+    let path = claude_state_path()?;
+    if !path.exists() {
+        return Ok(serde_json::json!({}));
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+}
+
+/// User-scope servers from the `mcpServers` object of ~/.claude.json
+pub(crate) fn user_mcp_servers() -> Result<serde_json::Map<String, serde_json::Value>, String> {
+    Ok(read_claude_state()?
+        .get("mcpServers")
+        .and_then(|servers| servers.as_object())
+        .cloned()
+        .unwrap_or_default())
+}
+
+/// Every server config Claude Code loads in `project_path`
+///
+/// User and local scope come from ~/.claude.json, project scope from the project's .mcp.json.
+pub(crate) fn mcp_server_configs(project_path: &str) -> Vec<serde_json::Value> {
+    let mut configs = Vec::new();
+    if let Ok(state) = read_claude_state() {
+        let local = state
+            .get("projects")
+            .and_then(|projects| projects.get(project_path))
+            .and_then(|project| project.get("mcpServers"));
+        for servers in [state.get("mcpServers"), local].into_iter().flatten() {
+            if let Some(servers) = servers.as_object() {
+                configs.extend(servers.values().cloned());
+            }
+        }
+    }
+    let project = fs::read_to_string(PathBuf::from(project_path).join(".mcp.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok());
+    if let Some(servers) = project.as_ref().and_then(|p| p.get("mcpServers")).and_then(|s| s.as_object()) {
+        configs.extend(servers.values().cloned());
+    }
+    configs
+}
+
+/// Names listed under `key` in a project entry of ~/.claude.json
+fn project_server_list(project: Option<&serde_json::Value>, key: &str) -> Vec<String> {
+    project
+        .and_then(|p| p.get(key))
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_default()
+}
+
+/// Gets which servers of a project's .mcp.json have been approved or rejected
+#[tauri::command]
+#[specta::specta]
+pub async fn mcp_get_project_approvals(
+    project_path: String,
+) -> Result<MCPProjectApprovals, String> {
+    let config = mcp_read_project_config(project_path.clone()).await?;
+    let state = read_claude_state()?;
+    let project = state.get("projects").and_then(|p| p.get(&project_path));
+
+    let enabled = project_server_list(project, "enabledMcpjsonServers");
+    let disabled = project_server_list(project, "disabledMcpjsonServers");
+
+    // The "trust all" switch can live in the project entry or in the project's local settings
+    let local_settings = fs::read_to_string(
+        PathBuf::from(&project_path)
+            .join(".claude")
+            .join("settings.local.json"),
+    )
+    .ok()
+    .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok());
+    let enable_all = [project, local_settings.as_ref()].iter().any(|source| {
+        source
+            .and_then(|s| s.get("enableAllProjectMcpServers"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+    });
+
+    let mut servers: Vec<ProjectServerApproval> = config
+        .mcp_servers
+        .keys()
+        .map(|name| ProjectServerApproval {
+            name: name.clone(),
+            status: if disabled.contains(name) {
+                "rejected"
+            } else if enable_all || enabled.contains(name) {
+                "approved"
+            } else {
+                "pending"
+            }
+            .to_string(),
+        })
+        .collect();
+    servers.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(MCPProjectApprovals {
+        enable_all,
+        servers,
+    })
+}
+
+/// Approves or rejects a server from a project's .mcp.json; `None` resets it to pending
+#[tauri::command]
+#[specta::specta]
+pub async fn mcp_set_project_approval(
+    project_path: String,
+    name: String,
+    approved: Option<bool>,
+) -> Result<MCPProjectApprovals, String> {
+    info!(
+        "Setting approval of project MCP server {} in {} to {:?}",
+        name, project_path, approved
+    );
+
+    let mut state = read_claude_state()?;
+    let root = state
+        .as_object_mut()
+        .ok_or_else(|| "Unexpected format of ~/.claude.json".to_string())?;
+    let project = root
+        .entry("projects")
+        .or_insert_with(|| serde_json::json!({}))
+        .as_object_mut()
+        .ok_or_else(|| "Unexpected format of ~/.claude.json".to_string())?
+        .entry(project_path.clone())
+        .or_insert_with(|| serde_json::json!({}))
+        .as_object_mut()
+        .ok_or_else(|| "Unexpected format of ~/.claude.json".to_string())?;
+
+    for (key, include) in [
+        ("enabledMcpjsonServers", approved == Some(true)),
+        ("disabledMcpjsonServers", approved == Some(false)),
+    ] {
+        let mut names: Vec<String> = project
+            .get(key)
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default();
+        names.retain(|n| n != &name);
+        if include {
+            names.push(name.clone());
+        }
+        project.insert(key.to_string(), serde_json::json!(names));
+    }
+
+    let content = serde_json::to_string_pretty(&state)
+        .map_err(|e| format!("Failed to serialize ~/.claude.json: {}", e))?;
+    write_file_atomically(&claude_state_path()?, &content)
+        .map_err(|e| format!("Failed to write ~/.claude.json: {}", e))?;
+
+    mcp_get_project_approvals(project_path).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn list_entries_are_split_into_transport_command_and_health() {
+        let stdio = server_from_list_entry(
+            "fs",
+            "npx -y @modelcontextprotocol/server-filesystem /tmp - ✓ Connected",
+        );
+        assert_eq!(stdio.transport, "stdio");
+        assert_eq!(stdio.command.as_deref(), Some("npx"));
+        assert_eq!(
+            stdio.args,
+            vec!["-y", "@modelcontextprotocol/server-filesystem", "/tmp"]
+        );
+        assert!(stdio.status.running);
+
+        let sse = server_from_list_entry(
+            "remote",
+            "https://example.com/sse (SSE) - ✗ Failed to connect",
+        );
+        assert_eq!(sse.transport, "sse");
+        assert_eq!(sse.url.as_deref(), Some("https://example.com/sse"));
+        assert_eq!(sse.status.error.as_deref(), Some("Failed to connect"));
+    }
+
+    #[test]
+    fn get_output_includes_headers() {
+        let output = "remote:
+  Scope: User config (available in all your projects)
+  Status: ✓ Connected
+  Type: http
+  URL: https://example.com/mcp
+  Headers:
+    Authorization: Bearer ${TOKEN}
+    X-Team: tools
+
+To remove this server, run: claude mcp remove \"remote\" -s user
+";
+        let server = server_from_get_output("remote".to_string(), output);
+        assert_eq!(server.scope, "user");
+        assert_eq!(server.transport, "http");
+        assert_eq!(server.url.as_deref(), Some("https://example.com/mcp"));
+        assert_eq!(
+            server.headers,
+            vec!["Authorization: Bearer ${TOKEN}", "X-Team: tools"]
+        );
+    }
+
+    #[test]
+    fn re_adding_keeps_the_transport() {
+        let mut server = server_from_list_entry("remote", "https://example.com/mcp (HTTP)");
+        server.transport = "http".to_string();
+        server.scope = "project".to_string();
+        let args = remote_server_add_args(&server, &["Authorization: Bearer x".to_string()]).unwrap();
+        assert_eq!(
+            args,
+            vec![
+                "add",
+                "-s",
+                "project",
+                "--transport",
+                "http",
+                "--header",
+                "Authorization: Bearer x",
+                "remote",
+                "https://example.com/mcp"
+            ]
+        );
+
+        let stdio = server_from_list_entry("fs", "npx server");
+        assert!(remote_server_add_args(&stdio, &[]).is_err());
+    }
+}
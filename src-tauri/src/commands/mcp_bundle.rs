@@ -0,0 +1,253 @@
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use tauri::AppHandle;
+
+use super::mcp::{
+    configured_server_names, mcp_add, mcp_get, mcp_list, mcp_remove, ImportResult,
+    ImportServerResult,
+};
+use super::secrets::list_secrets;
+
+/// Bundle format version written by this build
+const BUNDLE_VERSION: u32 = 1;
+
+/// One server in an MCP bundle
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct McpBundleServer {
+    pub name: String,
+    pub transport: String,
+    #[serde(default)]
+    pub command: Option<String>,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    #[serde(default)]
+    pub url: Option<String>,
+    pub scope: String,
+}
+
+/// A portable set of MCP servers
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct McpBundle {
+    pub version: u32,
+    pub exported_at: String,
+    pub servers: Vec<McpBundleServer>,
+    /// Secrets the servers reference as `${NAME}`; they must exist on the importing machine
+    #[serde(default)]
+    pub required_secrets: Vec<String>,
+}
+
+/// Result of importing a bundle
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct McpBundleImportResult {
+    #[serde(flatten)]
+    pub result: ImportResult,
+    /// Referenced secrets not yet stored in this machine's keychain
+    pub missing_secrets: Vec<String>,
+}
+
+/// Names of the `${NAME}` references in an env value
+fn secret_references(value: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find('}') else {
+            break;
+        };
+        names.push(after[..end].to_string());
+        rest = &after[end + 1..];
+    }
+    names
+}
+
+/// Replace plain env values with `${KEY}` references so the bundle carries no secrets
+fn reference_env_values(env: &mut HashMap<String, String>) {
+    for (key, value) in env.iter_mut() {
+        if secret_references(value).is_empty() {
+            *value = format!("${{{}}}", key);
+        }
+    }
+}
+
+/// Export all configured MCP servers as a JSON bundle
+///
+/// Unless `include_values` is set, env values are replaced by `${KEY}`
+/// references that resolve to keychain secrets on the importing machine.
+#[tauri::command]
+#[specta::specta]
+pub async fn export_mcp_bundle(app: AppHandle, include_values: bool) -> Result<String, String> {
+    let mut servers = Vec::new();
+    for listed in mcp_list(app.clone()).await? {
+        let server = mcp_get(app.clone(), listed.name).await?;
+        let mut env = server.env;
+        if !include_values {
+            reference_env_values(&mut env);
+        }
+        servers.push(McpBundleServer {
+            name: server.name,
+            transport: server.transport,
+            command: server.command,
+            args: server.args,
+            env,
+            url: server.url,
+            scope: server.scope,
+        });
+    }
+    servers.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let required_secrets: BTreeSet<String> = servers
+        .iter()
+        .flat_map(|server| server.env.values())
+        .flat_map(|value| secret_references(value))
+        .collect();
+
+    let bundle = McpBundle {
+        version: BUNDLE_VERSION,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        servers,
+        required_secrets: required_secrets.into_iter().collect(),
+    };
+    info!("Exported {} MCP servers", bundle.servers.len());
+    serde_json::to_string_pretty(&bundle).map_err(|e| format!("Failed to serialize bundle: {}", e))
+}
+
+/// Export all configured MCP servers to a bundle file
+#[tauri::command]
+#[specta::specta]
+pub async fn export_mcp_bundle_to_file(
+    app: AppHandle,
+    include_values: bool,
+    file_path: String,
+) -> Result<(), String> {
+    let json_data = export_mcp_bundle(app, include_values).await?;
+    std::fs::write(&file_path, json_data).map_err(|e| format!("Failed to write file: {}", e))
+}
+
+/// Import the servers of a JSON bundle
+///
+/// `scope` overrides the scope recorded for each server. Servers that already
+/// exist are skipped unless `overwrite` is set.
+#[tauri::command]
+#[specta::specta]
+pub async fn import_mcp_bundle(
+    app: AppHandle,
+    json_data: String,
+    scope: Option<String>,
+    overwrite: bool,
+) -> Result<McpBundleImportResult, String> {
+    let bundle: McpBundle =
+        serde_json::from_str(&json_data).map_err(|e| format!("Invalid MCP bundle: {}", e))?;
+    if bundle.version > BUNDLE_VERSION {
+        return Err(format!(
+            "MCP bundle version {} is newer than this version of Claudia supports",
+            bundle.version
+        ));
+    }
+
+    let configured = configured_server_names(&app).await;
+    let mut result = ImportResult {
+        imported_count: 0,
+        failed_count: 0,
+        skipped_count: 0,
+        servers: Vec::new(),
+    };
+
+    for server in bundle.servers {
+        let name = server.name.clone();
+        let scope = scope.clone().unwrap_or(server.scope);
+        if configured.contains(&name) {
+            if !overwrite {
+                result.skipped_count += 1;
+                result.servers.push(ImportServerResult {
+                    name,
+                    success: false,
+                    error: Some("A server with this name is already configured".to_string()),
+                });
+                continue;
+            }
+            if let Err(e) = mcp_remove(app.clone(), name.clone(), None).await {
+                error!("Failed to remove {} before overwriting: {}", name, e);
+            }
+        }
+
+        let added = mcp_add(
+            app.clone(),
+            name.clone(),
+            server.transport,
+            server.command,
+            server.args,
+            server.env,
+            server.url,
+            scope,
+        )
+        .await;
+        let error = match added {
+            Ok(added) if added.success => None,
+            Ok(added) => Some(added.message),
+            Err(e) => Some(e),
+        };
+        match &error {
+            None => result.imported_count += 1,
+            Some(e) => {
+                error!("Failed to import MCP server {}: {}", name, e);
+                result.failed_count += 1;
+            }
+        }
+        result.servers.push(ImportServerResult {
+            name,
+            success: error.is_none(),
+            error,
+        });
+    }
+
+    let stored = list_secrets().await.unwrap_or_default();
+    let missing_secrets = bundle
+        .required_secrets
+        .into_iter()
+        .filter(|name| !stored.contains(name))
+        .collect();
+
+    info!(
+        "MCP bundle import complete: {} imported, {} failed, {} skipped",
+        result.imported_count, result.failed_count, result.skipped_count
+    );
+    Ok(McpBundleImportResult {
+        result,
+        missing_secrets,
+    })
+}
+
+/// Import the servers of a bundle file
+#[tauri::command]
+#[specta::specta]
+pub async fn import_mcp_bundle_from_file(
+    app: AppHandle,
+    file_path: String,
+    scope: Option<String>,
+    overwrite: bool,
+) -> Result<McpBundleImportResult, String> {
+    let json_data =
+        std::fs::read_to_string(&file_path).map_err(|e| format!("Failed to read file: {}", e))?;
+    import_mcp_bundle(app, json_data, scope, overwrite).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replaces_plain_env_values_with_references() {
+        let mut env = HashMap::from([
+            ("API_KEY".to_string(), "sk-123".to_string()),
+            ("TOKEN".to_string(), "${GITHUB_TOKEN}".to_string()),
+        ]);
+
+        reference_env_values(&mut env);
+        assert_eq!(env["API_KEY"], "${API_KEY}");
+        assert_eq!(env["TOKEN"], "${GITHUB_TOKEN}");
+        assert_eq!(secret_references("${A}:${B}"), vec!["A", "B"]);
+    }
+}
@@ -0,0 +1,468 @@
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::process::{Child, ChildStdin, ChildStdout};
+
+use super::agents::AgentDb;
+use super::mcp::{mcp_get, MCPServer};
+use super::mcp_logs::record_mcp_log;
+use super::mcp_oauth::valid_access_token;
+use super::secrets::resolve_secret_references;
+
+/// MCP protocol revision sent in the initialize request
+const PROTOCOL_VERSION: &str = "2024-11-05";
+
+/// How long a whole connection test may take, including server start-up
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// A tool advertised by an MCP server
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct McpToolInfo {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+/// Outcome of connecting to an MCP server and running the initialize handshake
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct McpConnectionTest {
+    pub name: String,
+    pub transport: String,
+    pub success: bool,
+    /// Time until the initialize response arrived
+    pub latency_ms: Option<u64>,
+    pub server_name: Option<String>,
+    pub server_version: Option<String>,
+    pub protocol_version: Option<String>,
+    pub tools: Vec<McpToolInfo>,
+    pub error: Option<String>,
+    /// Tail of the server's stderr, for stdio servers
+    pub stderr: Option<String>,
+}
+
+/// What the handshake learned about the server
+struct HandshakeInfo {
+    latency_ms: u64,
+    server_name: Option<String>,
+    server_version: Option<String>,
+    protocol_version: Option<String>,
+    tools: Vec<McpToolInfo>,
+}
+
+/// A JSON-RPC connection to an MCP server
+enum McpSession {
+    Stdio {
+        stdin: ChildStdin,
+        stdout: Lines<BufReader<ChildStdout>>,
+    },
+    Sse {
+        client: reqwest::Client,
+        endpoint: reqwest::Url,
+        events: reqwest::Response,
+        buffer: String,
+    },
+}
+
+/// Takes the next complete server-sent event off the buffer as (event, data)
+fn take_sse_event(buffer: &mut String) -> Option<(String, String)> {
+    let normalized = buffer.replace("\r\n", "\n");
+    let end = normalized.find("\n\n")?;
+    let raw = normalized[..end].to_string();
+    *buffer = normalized[end + 2..].to_string();
+
+    let mut event = "message".to_string();
+    let mut data = Vec::new();
+    for line in raw.lines() {
+        if let Some(value) = line.strip_prefix("event:") {
+            event = value.trim().to_string();
+        } else if let Some(value) = line.strip_prefix("data:") {
+            data.push(value.strip_prefix(' ').unwrap_or(value));
+        }
+    }
+    Some((event, data.join("\n")))
+}
+
+impl McpSession {
+    /// Reads messages until the response to `id` arrives
+    async fn response(&mut self, id: u64) -> Result<JsonValue, String> {
+        loop {
+            let message = match self {
+                McpSession::Stdio { stdout, .. } => stdout
+                    .next_line()
+                    .await
+                    .map_err(|e| format!("Failed to read from server: {}", e))?
+                    .ok_or_else(|| "Server exited before responding".to_string())?,
+                McpSession::Sse { events, buffer, .. } => loop {
+                    if let Some((event, data)) = take_sse_event(buffer) {
+                        if event == "message" {
+                            break data;
+                        }
+                        continue;
+                    }
+                    let chunk = events
+                        .chunk()
+                        .await
+                        .map_err(|e| format!("Failed to read event stream: {}", e))?
+                        .ok_or_else(|| "Server closed the event stream".to_string())?;
+                    buffer.push_str(&String::from_utf8_lossy(&chunk));
+                },
+            };
+
+            // Servers may log to stdout or send notifications; skip anything that isn't our response
+            let Ok(json) = serde_json::from_str::<JsonValue>(&message) else {
+                continue;
+            };
+            if json.get("id").and_then(|i| i.as_u64()) != Some(id) || json.get("method").is_some() {
+                continue;
+            }
+            if let Some(error) = json.get("error") {
+                let message = error
+                    .get("message")
+                    .and_then(|m| m.as_str())
+                    .unwrap_or("unknown error");
+                return Err(format!("Server returned an error: {}", message));
+            }
+            return Ok(json.get("result").cloned().unwrap_or(JsonValue::Null));
+        }
+    }
+
+    async fn send(&mut self, message: JsonValue) -> Result<(), String> {
+        match self {
+            McpSession::Stdio { stdin, .. } => {
+                let line = format!("{}\n", message);
+                stdin
+                    .write_all(line.as_bytes())
+                    .await
+                    .map_err(|e| format!("Failed to write to server: {}", e))?;
+                stdin
+                    .flush()
+                    .await
+                    .map_err(|e| format!("Failed to write to server: {}", e))
+            }
+            McpSession::Sse {
+                client, endpoint, ..
+            } => {
+                let response = client
+                    .post(endpoint.clone())
+                    .json(&message)
+                    .send()
+                    .await
+                    .map_err(|e| format!("Failed to post to {}: {}", endpoint, e))?;
+                if !response.status().is_success() {
+                    return Err(format!(
+                        "Server rejected message: HTTP {}",
+                        response.status()
+                    ));
+                }
+                Ok(())
+            }
+        }
+    }
+
+    async fn request(
+        &mut self,
+        id: u64,
+        method: &str,
+        params: JsonValue,
+    ) -> Result<JsonValue, String> {
+        self.send(json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }))
+            .await?;
+        self.response(id).await
+    }
+
+    async fn notify(&mut self, method: &str) -> Result<(), String> {
+        self.send(json!({ "jsonrpc": "2.0", "method": method }))
+            .await
+    }
+
+    /// initialize, notifications/initialized, then tools/list if the server has tools
+    async fn handshake(&mut self, started: Instant) -> Result<HandshakeInfo, String> {
+        let init = self
+            .request(
+                1,
+                "initialize",
+                json!({
+                    "protocolVersion": PROTOCOL_VERSION,
+                    "capabilities": {},
+                    "clientInfo": { "name": "claudia", "version": env!("CARGO_PKG_VERSION") },
+                }),
+            )
+            .await?;
+
+        let mut info = HandshakeInfo {
+            latency_ms: started.elapsed().as_millis() as u64,
+            server_name: init
+                .pointer("/serverInfo/name")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+            server_version: init
+                .pointer("/serverInfo/version")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+            protocol_version: init
+                .get("protocolVersion")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+            tools: Vec::new(),
+        };
+
+        self.notify("notifications/initialized").await?;
+
+        if init.pointer("/capabilities/tools").is_some() {
+            let tools = self.request(2, "tools/list", json!({})).await?;
+            info.tools = tools
+                .get("tools")
+                .and_then(|t| t.as_array())
+                .map(|tools| {
+                    tools
+                        .iter()
+                        .filter_map(|tool| {
+                            Some(McpToolInfo {
+                                name: tool.get("name")?.as_str()?.to_string(),
+                                description: tool
+                                    .get("description")
+                                    .and_then(|d| d.as_str())
+                                    .map(|s| s.to_string()),
+                            })
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+        }
+
+        Ok(info)
+    }
+}
+
+fn spawn_stdio_server(server: &MCPServer) -> Result<(Child, McpSession), String> {
+    let command = server
+        .command
+        .as_deref()
+        .filter(|c| !c.is_empty())
+        .ok_or_else(|| format!("Server '{}' has no command configured", server.name))?;
+
+    let mut cmd =
+        tokio::process::Command::from(crate::claude_binary::create_command_with_env(command));
+    cmd.args(&server.args)
+        .envs(&server.env)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| format!("Failed to start '{}': {}", command, e))?;
+    let stdin = child.stdin.take().ok_or("Failed to open server stdin")?;
+    let stdout = child.stdout.take().ok_or("Failed to open server stdout")?;
+
+    Ok((
+        child,
+        McpSession::Stdio {
+            stdin,
+            stdout: BufReader::new(stdout).lines(),
+        },
+    ))
+}
+
+async fn connect_sse_server(
+    server: &MCPServer,
+    bearer_token: Option<&str>,
+) -> Result<McpSession, String> {
+    let url = server
+        .url
+        .as_deref()
+        .ok_or_else(|| format!("Server '{}' has no URL configured", server.name))?;
+    let url = reqwest::Url::parse(url).map_err(|e| format!("Invalid server URL: {}", e))?;
+
+    let mut headers = reqwest::header::HeaderMap::new();
+    if let Some(token) = bearer_token {
+        let value = reqwest::header::HeaderValue::from_str(&format!("Bearer {}", token))
+            .map_err(|e| format!("Invalid access token: {}", e))?;
+        headers.insert(reqwest::header::AUTHORIZATION, value);
+    }
+    let client = super::proxy::apply_proxy(reqwest::Client::builder())
+        .default_headers(headers)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let mut events = client
+        .get(url.clone())
+        .header("Accept", "text/event-stream")
+        .send()
+        .await
+        .map_err(|e| format!("Failed to connect to {}: {}", url, e))?;
+    if !events.status().is_success() {
+        return Err(format!("Server responded with HTTP {}", events.status()));
+    }
+
+    // The first event tells us where to post messages
+    let mut buffer = String::new();
+    let endpoint = loop {
+        if let Some((event, data)) = take_sse_event(&mut buffer) {
+            if event == "endpoint" {
+                break url
+                    .join(data.trim())
+                    .map_err(|e| format!("Invalid endpoint '{}': {}", data, e))?;
+            }
+            continue;
+        }
+        let chunk = events
+            .chunk()
+            .await
+            .map_err(|e| format!("Failed to read event stream: {}", e))?
+            .ok_or_else(|| {
+                "Server closed the event stream before sending an endpoint".to_string()
+            })?;
+        buffer.push_str(&String::from_utf8_lossy(&chunk));
+    };
+
+    Ok(McpSession::Sse {
+        client,
+        endpoint,
+        events,
+        buffer,
+    })
+}
+
+/// Connect to a server as Claude Code would and run the MCP handshake
+///
+/// `bearer_token` authorizes requests to SSE servers that need OAuth.
+pub async fn probe_mcp_server(server: &MCPServer, bearer_token: Option<&str>) -> McpConnectionTest {
+    let started = Instant::now();
+    let mut child = None;
+
+    let outcome = tokio::time::timeout(HANDSHAKE_TIMEOUT, async {
+        let mut session = if server.transport == "stdio" {
+            let (spawned, session) = spawn_stdio_server(server)?;
+            child = Some(spawned);
+            session
+        } else {
+            connect_sse_server(server, bearer_token).await?
+        };
+        session.handshake(started).await
+    })
+    .await
+    .unwrap_or_else(|_| {
+        Err(format!(
+            "Timed out after {}s waiting for the server",
+            HANDSHAKE_TIMEOUT.as_secs()
+        ))
+    });
+
+    // Stop the server and keep the end of its stderr for diagnosis
+    let mut stderr = None;
+    if let Some(mut child) = child {
+        let _ = child.kill().await;
+        if let Some(mut pipe) = child.stderr.take() {
+            let mut output = String::new();
+            let _ = tokio::time::timeout(Duration::from_secs(1), pipe.read_to_string(&mut output))
+                .await;
+            let lines: Vec<&str> = output.lines().collect();
+            let tail = lines[lines.len().saturating_sub(20)..].join("\n");
+            stderr = (!tail.trim().is_empty()).then_some(tail);
+        }
+    }
+
+    let mut result = McpConnectionTest {
+        name: server.name.clone(),
+        transport: server.transport.clone(),
+        success: outcome.is_ok(),
+        latency_ms: None,
+        server_name: None,
+        server_version: None,
+        protocol_version: None,
+        tools: Vec::new(),
+        error: None,
+        stderr,
+    };
+    match outcome {
+        Ok(info) => {
+            result.latency_ms = Some(info.latency_ms);
+            result.server_name = info.server_name;
+            result.server_version = info.server_version;
+            result.protocol_version = info.protocol_version;
+            result.tools = info.tools;
+        }
+        Err(e) => {
+            warn!("MCP connection test for {} failed: {}", server.name, e);
+            result.error = Some(e);
+        }
+    }
+    result
+}
+
+/// Keep the outcome and server stderr of a test in the MCP server logs
+fn record_connection_test(app: &AppHandle, result: &McpConnectionTest) {
+    let db = app.state::<AgentDb>();
+    let Ok(conn) = db.0.lock() else {
+        return;
+    };
+    if let Some(stderr) = &result.stderr {
+        for line in stderr.lines().filter(|l| !l.trim().is_empty()) {
+            record_mcp_log(&conn, &result.name, "stderr", line);
+        }
+    }
+    match (&result.error, result.latency_ms) {
+        (Some(error), _) => record_mcp_log(&conn, &result.name, "error", error),
+        (None, Some(latency_ms)) => record_mcp_log(
+            &conn,
+            &result.name,
+            "info",
+            &format!(
+                "Connection test succeeded in {}ms, {} tools",
+                latency_ms,
+                result.tools.len()
+            ),
+        ),
+        (None, None) => {}
+    }
+}
+
+/// Launch or connect to a configured MCP server and check that it completes the handshake
+#[tauri::command]
+#[specta::specta]
+pub async fn test_mcp_connection(
+    app: AppHandle,
+    name: String,
+) -> Result<McpConnectionTest, String> {
+    info!("Testing connection to MCP server: {}", name);
+    let mut server = mcp_get(app.clone(), name).await?;
+    resolve_secret_references(&mut server.env);
+    let bearer_token = valid_access_token(&server.name).await?;
+    let result = probe_mcp_server(&server, bearer_token.as_deref()).await;
+    record_connection_test(&app, &result);
+    info!(
+        "MCP server {} test finished: success={}, latency={:?}ms, tools={}",
+        result.name,
+        result.success,
+        result.latency_ms,
+        result.tools.len()
+    );
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn take_sse_event_splits_events_and_keeps_the_rest() {
+        let mut buffer =
+            "event: endpoint\r\ndata: /messages?session=1\r\n\r\ndata: {\"id\":1}\n\ndata: partial"
+                .to_string();
+
+        assert_eq!(
+            take_sse_event(&mut buffer),
+            Some(("endpoint".to_string(), "/messages?session=1".to_string()))
+        );
+        assert_eq!(
+            take_sse_event(&mut buffer),
+            Some(("message".to_string(), "{\"id\":1}".to_string()))
+        );
+        assert_eq!(take_sse_event(&mut buffer), None);
+        assert_eq!(buffer, "data: partial");
+    }
+}
@@ -0,0 +1,303 @@
+use log::{error, info, warn};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use super::agents::{get_setting, set_setting, AgentDb};
+use super::background_tasks::{TaskContext, TaskPriority, TaskQueueState};
+use super::mcp::{mcp_get, mcp_list, ServerStatus};
+use super::mcp_connection::probe_mcp_server;
+use super::mcp_logs::record_mcp_log;
+use super::mcp_oauth::valid_access_token;
+use super::secrets::resolve_secret_references;
+
+/// Checks kept per server
+const MAX_HISTORY_PER_SERVER: i64 = 500;
+
+/// Settings of the background health checker, stored under `mcp_health_settings`
+#[derive(Debug, Serialize, Deserialize, Clone, specta::Type)]
+pub struct McpHealthSettings {
+    pub enabled: bool,
+    /// Seconds between checks of all servers
+    pub interval_secs: u64,
+}
+
+impl Default for McpHealthSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_secs: 300,
+        }
+    }
+}
+
+/// One health check of a server
+#[derive(Debug, Serialize, Deserialize, Clone, specta::Type)]
+pub struct McpHealthCheck {
+    pub server_name: String,
+    pub healthy: bool,
+    pub latency_ms: Option<u64>,
+    pub tool_count: Option<usize>,
+    pub error: Option<String>,
+    pub checked_at: String,
+}
+
+fn load_health_settings(conn: &Connection) -> McpHealthSettings {
+    get_setting(conn, "mcp_health_settings").unwrap_or_default()
+}
+
+fn row_to_check(row: &rusqlite::Row) -> rusqlite::Result<McpHealthCheck> {
+    Ok(McpHealthCheck {
+        server_name: row.get(0)?,
+        healthy: row.get(1)?,
+        latency_ms: row.get::<_, Option<i64>>(2)?.map(|l| l as u64),
+        tool_count: row.get::<_, Option<i64>>(3)?.map(|t| t as usize),
+        error: row.get(4)?,
+        checked_at: row.get(5)?,
+    })
+}
+
+/// Most recent check of every server that has been checked
+pub fn latest_health_checks(conn: &Connection) -> Result<Vec<McpHealthCheck>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT server_name, healthy, latency_ms, tool_count, error, checked_at
+             FROM mcp_server_health
+             WHERE id IN (SELECT MAX(id) FROM mcp_server_health GROUP BY server_name)
+             ORDER BY server_name",
+        )
+        .map_err(|e| e.to_string())?;
+    let checks = stmt
+        .query_map([], row_to_check)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(checks)
+}
+
+/// Latest check results in the shape the MCP server list uses
+pub fn latest_server_statuses(conn: &Connection) -> Result<HashMap<String, ServerStatus>, String> {
+    Ok(latest_health_checks(conn)?
+        .into_iter()
+        .map(|check| {
+            let last_checked = chrono::DateTime::parse_from_rfc3339(&check.checked_at)
+                .ok()
+                .map(|t| t.timestamp() as u64);
+            (
+                check.server_name,
+                ServerStatus {
+                    running: check.healthy,
+                    error: check.error,
+                    last_checked,
+                },
+            )
+        })
+        .collect())
+}
+
+/// Check every configured server once, record the results and announce status changes
+///
+/// `task` receives progress and can stop the round early.
+async fn check_all_servers(
+    app: &AppHandle,
+    task: Option<&TaskContext>,
+) -> Result<Vec<McpHealthCheck>, String> {
+    let servers = mcp_list(app.clone()).await?;
+    let total = servers.len();
+    let mut checks = Vec::new();
+
+    for (index, listed) in servers.into_iter().enumerate() {
+        let name = listed.name.clone();
+        if let Some(task) = task {
+            if task.is_cancelled() {
+                break;
+            }
+            task.progress(index as f32 / total as f32, format!("Checking {}", name));
+        }
+        let check = match mcp_get(app.clone(), name.clone()).await {
+            Ok(mut server) => {
+                resolve_secret_references(&mut server.env);
+                let token = valid_access_token(&name).await.unwrap_or_else(|e| {
+                    warn!("{}", e);
+                    None
+                });
+                let result = probe_mcp_server(&server, token.as_deref()).await;
+                McpHealthCheck {
+                    server_name: name.clone(),
+                    healthy: result.success,
+                    latency_ms: result.latency_ms,
+                    tool_count: result.success.then_some(result.tools.len()),
+                    error: result.error,
+                    checked_at: chrono::Utc::now().to_rfc3339(),
+                }
+            }
+            Err(e) => McpHealthCheck {
+                server_name: name.clone(),
+                healthy: false,
+                latency_ms: None,
+                tool_count: None,
+                error: Some(e),
+                checked_at: chrono::Utc::now().to_rfc3339(),
+            },
+        };
+
+        let was_healthy = {
+            let db = app.state::<AgentDb>();
+            let conn = db.0.lock().map_err(|e| e.to_string())?;
+            let was_healthy: Option<bool> = conn
+                .query_row(
+                    "SELECT healthy FROM mcp_server_health WHERE server_name = ?1 ORDER BY id DESC LIMIT 1",
+                    params![name],
+                    |row| row.get(0),
+                )
+                .ok();
+            record_check(&conn, &check);
+            if let Some(error) = &check.error {
+                record_mcp_log(
+                    &conn,
+                    &name,
+                    "error",
+                    &format!("Health check failed: {}", error),
+                );
+            }
+            was_healthy
+        };
+
+        match (was_healthy, check.healthy) {
+            (Some(true) | None, false) => {
+                warn!("MCP server {} is down: {:?}", name, check.error);
+                let _ = app.emit(&format!("mcp-server-down:{}", name), &check);
+                let _ = app.emit("mcp-server-down", &check);
+            }
+            (Some(false), true) => {
+                info!("MCP server {} recovered", name);
+                let _ = app.emit(&format!("mcp-server-recovered:{}", name), &check);
+                let _ = app.emit("mcp-server-recovered", &check);
+            }
+            _ => {}
+        }
+        checks.push(check);
+    }
+
+    Ok(checks)
+}
+
+fn record_check(conn: &Connection, check: &McpHealthCheck) {
+    let result = conn
+        .execute(
+            "INSERT INTO mcp_server_health (server_name, healthy, latency_ms, tool_count, error, checked_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                check.server_name,
+                check.healthy,
+                check.latency_ms.map(|l| l as i64),
+                check.tool_count.map(|t| t as i64),
+                check.error,
+                check.checked_at
+            ],
+        )
+        .and_then(|_| {
+            conn.execute(
+                "DELETE FROM mcp_server_health WHERE server_name = ?1 AND id NOT IN (
+                    SELECT id FROM mcp_server_health WHERE server_name = ?1 ORDER BY id DESC LIMIT ?2
+                 )",
+                params![check.server_name, MAX_HISTORY_PER_SERVER],
+            )
+        });
+    if let Err(e) = result {
+        error!(
+            "Failed to record health of MCP server {}: {}",
+            check.server_name, e
+        );
+    }
+}
+
+/// Start the background checker; it reads its settings each round so changes apply without a restart
+pub fn start_mcp_health_monitor(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut last_run: Option<Instant> = None;
+        loop {
+            tokio::time::sleep(Duration::from_secs(30)).await;
+
+            let settings = {
+                let db = app.state::<AgentDb>();
+                let Ok(conn) = db.0.lock() else {
+                    continue;
+                };
+                load_health_settings(&conn)
+            };
+            let due = last_run
+                .is_none_or(|t| t.elapsed() >= Duration::from_secs(settings.interval_secs.max(30)));
+            if !settings.enabled || !due {
+                continue;
+            }
+
+            last_run = Some(Instant::now());
+            let queue = app.state::<TaskQueueState>();
+            let app = app.clone();
+            queue.0.submit_once(
+                "mcp-health-check",
+                "Check MCP server health",
+                TaskPriority::Low,
+                move |task| async move {
+                    check_all_servers(&app, Some(&task)).await.map(|_| ()).inspect_err(|e| {
+                        error!("MCP health check failed: {}", e);
+                    })
+                },
+            );
+        }
+    });
+}
+
+/// Check all configured MCP servers now
+#[tauri::command]
+#[specta::specta]
+pub async fn check_mcp_servers_now(app: AppHandle) -> Result<Vec<McpHealthCheck>, String> {
+    check_all_servers(&app, None).await
+}
+
+/// Get past health checks of a server, newest first
+#[tauri::command]
+#[specta::specta]
+pub async fn get_mcp_health_history(
+    db: State<'_, AgentDb>,
+    name: String,
+    limit: Option<i64>,
+) -> Result<Vec<McpHealthCheck>, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT server_name, healthy, latency_ms, tool_count, error, checked_at
+             FROM mcp_server_health WHERE server_name = ?1 ORDER BY id DESC LIMIT ?2",
+        )
+        .map_err(|e| e.to_string())?;
+    let checks = stmt
+        .query_map(params![name, limit.unwrap_or(100)], row_to_check)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(checks)
+}
+
+/// Get the settings of the background MCP health checker
+#[tauri::command]
+#[specta::specta]
+pub async fn get_mcp_health_settings(db: State<'_, AgentDb>) -> Result<McpHealthSettings, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    Ok(load_health_settings(&conn))
+}
+
+/// Save the settings of the background MCP health checker
+#[tauri::command]
+#[specta::specta]
+pub async fn set_mcp_health_settings(
+    db: State<'_, AgentDb>,
+    settings: McpHealthSettings,
+) -> Result<(), String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    set_setting(&conn, "mcp_health_settings", &settings)
+        .map_err(|e| format!("Failed to save health settings: {}", e))?;
+    Ok(())
+}
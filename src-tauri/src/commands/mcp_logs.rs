@@ -0,0 +1,246 @@
+use log::error;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+use super::agents::AgentDb;
+
+/// Entries kept per server in Claudia's own log table
+const MAX_STORED_ENTRIES: i64 = 1000;
+
+/// Most recent Claude Code log files read per project
+const MAX_LOG_FILES: usize = 10;
+
+/// One line of MCP server output
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct McpLogEntry {
+    pub server_name: String,
+    /// "claude" for logs Claude Code wrote during sessions, "claudia" for output captured here
+    pub source: String,
+    /// "debug", "info", "error" or "stderr"
+    pub level: String,
+    pub message: String,
+    pub session_id: Option<String>,
+    pub cwd: Option<String>,
+    pub timestamp: String,
+}
+
+/// Store a line of MCP server output, keeping only the most recent entries per server
+pub fn record_mcp_log(conn: &Connection, server_name: &str, level: &str, message: &str) {
+    let result = conn
+        .execute(
+            "INSERT INTO mcp_server_logs (server_name, level, message, created_at) VALUES (?1, ?2, ?3, ?4)",
+            params![server_name, level, message, chrono::Utc::now().to_rfc3339()],
+        )
+        .and_then(|_| {
+            conn.execute(
+                "DELETE FROM mcp_server_logs WHERE server_name = ?1 AND id NOT IN (
+                    SELECT id FROM mcp_server_logs WHERE server_name = ?1 ORDER BY id DESC LIMIT ?2
+                 )",
+                params![server_name, MAX_STORED_ENTRIES],
+            )
+        });
+    if let Err(e) = result {
+        error!("Failed to store MCP log for {}: {}", server_name, e);
+    }
+}
+
+/// Directories where Claude Code keeps its per-project MCP server logs
+fn claude_log_roots() -> Vec<PathBuf> {
+    let Some(cache_dir) = dirs::cache_dir() else {
+        return Vec::new();
+    };
+    let base = cache_dir.join("claude-cli-nodejs");
+    // Windows nests the cache one level deeper
+    vec![base.join("Cache"), base]
+}
+
+/// Directory names Claude Code may have used for a server's logs
+fn log_dir_names(server_name: &str) -> Vec<String> {
+    let sanitized: String = server_name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    let mut names = vec![format!("mcp-logs-{}", server_name)];
+    if sanitized != server_name {
+        names.push(format!("mcp-logs-{}", sanitized));
+    }
+    names
+}
+
+/// Parse one Claude Code MCP log file, a JSON array of `{debug|error, timestamp, sessionId, cwd}`
+fn parse_claude_log_file(
+    server_name: &str,
+    content: &str,
+    fallback_timestamp: &str,
+) -> Vec<McpLogEntry> {
+    let Ok(JsonValue::Array(items)) = serde_json::from_str::<JsonValue>(content) else {
+        // Not the JSON format; keep the raw lines so nothing is hidden
+        return content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| McpLogEntry {
+                server_name: server_name.to_string(),
+                source: "claude".to_string(),
+                level: "debug".to_string(),
+                message: line.to_string(),
+                session_id: None,
+                cwd: None,
+                timestamp: fallback_timestamp.to_string(),
+            })
+            .collect();
+    };
+
+    items
+        .iter()
+        .filter_map(|item| {
+            let (level, message) = ["error", "info", "debug"].iter().find_map(|level| {
+                let message = item.get(*level)?;
+                let message = message
+                    .as_str()
+                    .map(|s| s.to_string())
+                    .unwrap_or_else(|| message.to_string());
+                Some((level.to_string(), message))
+            })?;
+            let field = |key: &str| {
+                item.get(key)
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.to_string())
+            };
+            Some(McpLogEntry {
+                server_name: server_name.to_string(),
+                source: "claude".to_string(),
+                level,
+                message,
+                session_id: field("sessionId"),
+                cwd: field("cwd"),
+                timestamp: field("timestamp").unwrap_or_else(|| fallback_timestamp.to_string()),
+            })
+        })
+        .collect()
+}
+
+fn read_claude_logs(server_name: &str) -> Vec<McpLogEntry> {
+    let dir_names = log_dir_names(server_name);
+    let mut entries = Vec::new();
+
+    for root in claude_log_roots() {
+        let Ok(projects) = fs::read_dir(&root) else {
+            continue;
+        };
+        for project in projects.flatten() {
+            for dir_name in &dir_names {
+                let log_dir = project.path().join(dir_name);
+                if log_dir.is_dir() {
+                    entries.extend(read_claude_log_dir(server_name, &log_dir));
+                }
+            }
+        }
+    }
+    entries
+}
+
+fn read_claude_log_dir(server_name: &str, log_dir: &Path) -> Vec<McpLogEntry> {
+    let Ok(files) = fs::read_dir(log_dir) else {
+        return Vec::new();
+    };
+    // Files are named after the time the session started, so names sort chronologically
+    let mut files: Vec<PathBuf> = files
+        .flatten()
+        .map(|f| f.path())
+        .filter(|p| p.is_file())
+        .collect();
+    files.sort();
+    files.reverse();
+    files.truncate(MAX_LOG_FILES);
+
+    let mut entries = Vec::new();
+    for file in files {
+        let fallback_timestamp = fs::metadata(&file)
+            .and_then(|m| m.modified())
+            .map(|t| chrono::DateTime::<chrono::Utc>::from(t).to_rfc3339())
+            .unwrap_or_default();
+        match fs::read_to_string(&file) {
+            Ok(content) => entries.extend(parse_claude_log_file(
+                server_name,
+                &content,
+                &fallback_timestamp,
+            )),
+            Err(e) => error!("Failed to read MCP log {:?}: {}", file, e),
+        }
+    }
+    entries
+}
+
+/// Get recent output of an MCP server, oldest first
+///
+/// Combines the logs Claude Code writes while sessions use the server with
+/// the output Claudia captured itself, e.g. during connection tests.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_mcp_logs(
+    db: State<'_, AgentDb>,
+    name: String,
+    limit: Option<usize>,
+) -> Result<Vec<McpLogEntry>, String> {
+    let mut entries = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT level, message, created_at FROM mcp_server_logs WHERE server_name = ?1 ORDER BY id",
+            )
+            .map_err(|e| e.to_string())?;
+        let stored = stmt
+            .query_map(params![name], |row| {
+                Ok(McpLogEntry {
+                    server_name: name.clone(),
+                    source: "claudia".to_string(),
+                    level: row.get(0)?,
+                    message: row.get(1)?,
+                    session_id: None,
+                    cwd: None,
+                    timestamp: row.get(2)?,
+                })
+            })
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?;
+        stored
+    };
+
+    let server_name = name.clone();
+    let claude_logs = tokio::task::spawn_blocking(move || read_claude_logs(&server_name))
+        .await
+        .map_err(|e| e.to_string())?;
+    entries.extend(claude_logs);
+
+    // RFC 3339 timestamps in UTC sort lexicographically
+    entries.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+    let limit = limit.unwrap_or(500);
+    if entries.len() > limit {
+        entries.drain(..entries.len() - limit);
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_claude_code_log_files() {
+        let content = r#"[
+            {"debug": "Starting connection", "timestamp": "2025-01-01T10:00:00.000Z", "sessionId": "abc", "cwd": "/repo"},
+            {"error": "Connection failed: spawn npx ENOENT", "timestamp": "2025-01-01T10:00:01.000Z", "sessionId": "abc", "cwd": "/repo"}
+        ]"#;
+
+        let entries = parse_claude_log_file("github", content, "");
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].level, "error");
+        assert_eq!(entries[1].message, "Connection failed: spawn npx ENOENT");
+        assert_eq!(entries[1].session_id.as_deref(), Some("abc"));
+    }
+}
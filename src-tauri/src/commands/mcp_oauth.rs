@@ -0,0 +1,558 @@
+use base64::Engine;
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+use super::mcp::{mcp_get, set_sse_server_headers};
+use super::secrets::{delete_keychain_value, read_keychain_value, write_keychain_value};
+
+/// Keychain entry listing the servers that have OAuth tokens
+const OAUTH_INDEX_ACCOUNT: &str = "__claudia_mcp_oauth_servers__";
+
+/// How long to wait for the user to finish signing in
+const AUTHORIZATION_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Tokens expiring sooner than this are refreshed before use
+const REFRESH_MARGIN_SECS: i64 = 300;
+
+/// A server's OAuth token as stored in the keychain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredOAuthToken {
+    access_token: String,
+    refresh_token: Option<String>,
+    /// Unix timestamp, if the server said when the token expires
+    expires_at: Option<i64>,
+    token_endpoint: String,
+    client_id: String,
+    client_secret: Option<String>,
+}
+
+impl StoredOAuthToken {
+    fn needs_refresh(&self) -> bool {
+        self.expires_at.is_some_and(|expires_at| {
+            expires_at - chrono::Utc::now().timestamp() < REFRESH_MARGIN_SECS
+        })
+    }
+}
+
+/// Authorization server endpoints of an MCP server
+struct AuthServerMetadata {
+    authorization_endpoint: String,
+    token_endpoint: String,
+    registration_endpoint: Option<String>,
+}
+
+/// Where to send the user to sign in
+#[derive(Debug, Serialize, Deserialize, specta::Type)]
+pub struct McpOAuthStart {
+    pub authorization_url: String,
+    pub redirect_uri: String,
+}
+
+/// Whether Claudia holds a token for a server
+#[derive(Debug, Serialize, Deserialize, specta::Type)]
+pub struct McpOAuthStatus {
+    pub name: String,
+    pub authorized: bool,
+    pub expires_at: Option<i64>,
+    /// Variable the server's Authorization header refers to
+    pub env_var: String,
+}
+
+fn token_account(name: &str) -> String {
+    format!("mcp_oauth:{}", name)
+}
+
+/// Environment variable carrying a server's access token into Claude processes
+fn token_env_var(name: &str) -> String {
+    let suffix: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("MCP_OAUTH_TOKEN_{}", suffix)
+}
+
+fn load_oauth_servers() -> Result<Vec<String>, String> {
+    Ok(read_keychain_value(OAUTH_INDEX_ACCOUNT)?
+        .and_then(|names| serde_json::from_str(&names).ok())
+        .unwrap_or_default())
+}
+
+fn load_token(name: &str) -> Result<Option<StoredOAuthToken>, String> {
+    Ok(read_keychain_value(&token_account(name))?
+        .and_then(|token| serde_json::from_str(&token).ok()))
+}
+
+fn save_token(name: &str, token: &StoredOAuthToken) -> Result<(), String> {
+    let value = serde_json::to_string(token).map_err(|e| e.to_string())?;
+    write_keychain_value(&token_account(name), &value)?;
+
+    let mut names = load_oauth_servers()?;
+    if !names.iter().any(|n| n == name) {
+        names.push(name.to_string());
+        let names = serde_json::to_string(&names).map_err(|e| e.to_string())?;
+        write_keychain_value(OAUTH_INDEX_ACCOUNT, &names)?;
+    }
+    Ok(())
+}
+
+/// Access tokens of all authorized servers as environment variables
+///
+/// Authorization headers written by `start_mcp_oauth` reference these, so
+/// Claude Code picks up the current token whenever it connects.
+pub fn oauth_token_env() -> Vec<(String, String)> {
+    let names = match load_oauth_servers() {
+        Ok(names) => names,
+        Err(e) => {
+            error!("{}", e);
+            return Vec::new();
+        }
+    };
+    names
+        .into_iter()
+        .filter_map(|name| match load_token(&name) {
+            Ok(token) => token.map(|token| (token_env_var(&name), token.access_token)),
+            Err(e) => {
+                error!("{}", e);
+                None
+            }
+        })
+        .collect()
+}
+
+async fn refresh_token(name: &str, token: &StoredOAuthToken) -> Result<StoredOAuthToken, String> {
+    let refresh_token = token.refresh_token.as_deref().ok_or_else(|| {
+        format!(
+            "Token of '{}' expired and can't be refreshed; sign in again",
+            name
+        )
+    })?;
+
+    let mut form = vec![
+        ("grant_type", "refresh_token"),
+        ("refresh_token", refresh_token),
+        ("client_id", token.client_id.as_str()),
+    ];
+    if let Some(secret) = &token.client_secret {
+        form.push(("client_secret", secret));
+    }
+    let mut refreshed = request_token(&token.token_endpoint, &form).await?;
+    refreshed.client_id = token.client_id.clone();
+    refreshed.client_secret = token.client_secret.clone();
+    if refreshed.refresh_token.is_none() {
+        refreshed.refresh_token = token.refresh_token.clone();
+    }
+
+    let stored = refreshed.clone();
+    let name = name.to_string();
+    tokio::task::spawn_blocking(move || save_token(&name, &stored))
+        .await
+        .map_err(|e| e.to_string())??;
+    Ok(refreshed)
+}
+
+/// A usable access token for a server, refreshing it first if it is about to expire
+pub async fn valid_access_token(name: &str) -> Result<Option<String>, String> {
+    let account = name.to_string();
+    let Some(token) = tokio::task::spawn_blocking(move || load_token(&account))
+        .await
+        .map_err(|e| e.to_string())??
+    else {
+        return Ok(None);
+    };
+    if !token.needs_refresh() {
+        return Ok(Some(token.access_token));
+    }
+    Ok(Some(refresh_token(name, &token).await?.access_token))
+}
+
+/// Keep stored tokens fresh so processes started later get a valid one
+pub fn start_mcp_oauth_refresher() {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            let names = tokio::task::spawn_blocking(load_oauth_servers)
+                .await
+                .unwrap_or_else(|e| Err(e.to_string()))
+                .unwrap_or_default();
+            for name in names {
+                if let Err(e) = valid_access_token(&name).await {
+                    warn!(
+                        "Failed to refresh OAuth token of MCP server {}: {}",
+                        name, e
+                    );
+                }
+            }
+        }
+    });
+}
+
+/// POST to a token endpoint and parse the token response
+async fn request_token(endpoint: &str, form: &[(&str, &str)]) -> Result<StoredOAuthToken, String> {
+    let response = super::proxy::http_client()
+        .post(endpoint)
+        .header("Accept", "application/json")
+        .form(form)
+        .send()
+        .await
+        .map_err(|e| format!("Token request failed: {}", e))?;
+    let status = response.status();
+    let body: JsonValue = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse token response: {}", e))?;
+    if !status.is_success() {
+        let reason = body
+            .get("error_description")
+            .or_else(|| body.get("error"))
+            .and_then(|e| e.as_str())
+            .unwrap_or("unknown error");
+        return Err(format!(
+            "Token request rejected (HTTP {}): {}",
+            status, reason
+        ));
+    }
+
+    Ok(StoredOAuthToken {
+        access_token: body
+            .get("access_token")
+            .and_then(|t| t.as_str())
+            .ok_or("Token response has no access_token")?
+            .to_string(),
+        refresh_token: body
+            .get("refresh_token")
+            .and_then(|t| t.as_str())
+            .map(|s| s.to_string()),
+        expires_at: body
+            .get("expires_in")
+            .and_then(|e| e.as_i64())
+            .map(|expires_in| chrono::Utc::now().timestamp() + expires_in),
+        token_endpoint: endpoint.to_string(),
+        client_id: String::new(),
+        client_secret: None,
+    })
+}
+
+async fn fetch_json(client: &reqwest::Client, url: &str) -> Option<JsonValue> {
+    let response = client.get(url).send().await.ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    response.json().await.ok()
+}
+
+/// Find the authorization server through the MCP discovery documents
+///
+/// Tries protected resource metadata first, then authorization server metadata
+/// at the server's origin, then the spec's default endpoint paths.
+async fn discover_auth_server(
+    client: &reqwest::Client,
+    server_url: &reqwest::Url,
+) -> AuthServerMetadata {
+    let origin = server_url.origin().ascii_serialization();
+
+    let mut issuer = origin.clone();
+    if let Some(resource) = fetch_json(
+        client,
+        &format!("{}/.well-known/oauth-protected-resource", origin),
+    )
+    .await
+    {
+        if let Some(server) = resource
+            .get("authorization_servers")
+            .and_then(|s| s.as_array())
+            .and_then(|s| s.first())
+            .and_then(|s| s.as_str())
+        {
+            issuer = server.trim_end_matches('/').to_string();
+        }
+    }
+
+    let metadata = fetch_json(
+        client,
+        &format!("{}/.well-known/oauth-authorization-server", issuer),
+    )
+    .await;
+    let endpoint = |key: &str, default_path: &str| {
+        metadata
+            .as_ref()
+            .and_then(|m| m.get(key))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+            .unwrap_or_else(|| format!("{}{}", issuer, default_path))
+    };
+
+    AuthServerMetadata {
+        authorization_endpoint: endpoint("authorization_endpoint", "/authorize"),
+        token_endpoint: endpoint("token_endpoint", "/token"),
+        registration_endpoint: match &metadata {
+            Some(metadata) => metadata
+                .get("registration_endpoint")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+            None => Some(format!("{}/register", issuer)),
+        },
+    }
+}
+
+/// Dynamic client registration (RFC 7591)
+async fn register_client(
+    client: &reqwest::Client,
+    endpoint: &str,
+    redirect_uri: &str,
+) -> Result<(String, Option<String>), String> {
+    let response = client
+        .post(endpoint)
+        .json(&serde_json::json!({
+            "client_name": "Claudia",
+            "redirect_uris": [redirect_uri],
+            "grant_types": ["authorization_code", "refresh_token"],
+            "response_types": ["code"],
+            "token_endpoint_auth_method": "none",
+        }))
+        .send()
+        .await
+        .map_err(|e| format!("Client registration failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "Client registration rejected: HTTP {}",
+            response.status()
+        ));
+    }
+    let body: JsonValue = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse client registration: {}", e))?;
+    let client_id = body
+        .get("client_id")
+        .and_then(|c| c.as_str())
+        .ok_or("Client registration returned no client_id")?
+        .to_string();
+    let client_secret = body
+        .get("client_secret")
+        .and_then(|c| c.as_str())
+        .map(|s| s.to_string());
+    Ok((client_id, client_secret))
+}
+
+/// Wait for the browser to hit the redirect URI; returns the query parameters
+async fn wait_for_callback(listener: TcpListener) -> Result<Vec<(String, String)>, String> {
+    loop {
+        let (mut stream, _) = listener
+            .accept()
+            .await
+            .map_err(|e| format!("Failed to accept OAuth callback: {}", e))?;
+
+        let mut buffer = Vec::new();
+        let mut chunk = [0u8; 4096];
+        while !buffer.windows(4).any(|w| w == b"\r\n\r\n") && buffer.len() < 16 * 1024 {
+            match stream.read(&mut chunk).await {
+                Ok(0) | Err(_) => break,
+                Ok(n) => buffer.extend_from_slice(&chunk[..n]),
+            }
+        }
+        let head = String::from_utf8_lossy(&buffer);
+        let path = head
+            .lines()
+            .next()
+            .and_then(|line| line.split_whitespace().nth(1))
+            .unwrap_or_default()
+            .to_string();
+
+        // Browsers also ask for favicons and the like
+        if !path.starts_with("/callback") {
+            let _ = stream
+                .write_all(
+                    b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                )
+                .await;
+            continue;
+        }
+
+        let body = "<html><body><h2>Claudia is now authorized.</h2><p>You can close this window.</p></body></html>";
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        let _ = stream.write_all(response.as_bytes()).await;
+
+        let url = reqwest::Url::parse(&format!("http://127.0.0.1{}", path))
+            .map_err(|e| format!("Invalid OAuth callback: {}", e))?;
+        return Ok(url.query_pairs().into_owned().collect());
+    }
+}
+
+/// Begin signing in to an SSE server that requires OAuth
+///
+/// Returns the URL to open in the browser. The rest of the flow finishes in the
+/// background and emits `mcp-oauth-complete:{name}` when done.
+#[tauri::command]
+#[specta::specta]
+pub async fn start_mcp_oauth(
+    app: AppHandle,
+    name: String,
+    client_id: Option<String>,
+    scopes: Option<Vec<String>>,
+) -> Result<McpOAuthStart, String> {
+    let server = mcp_get(app.clone(), name.clone()).await?;
+    let server_url = server
+        .url
+        .as_deref()
+        .filter(|_| server.transport != "stdio")
+        .ok_or_else(|| format!("Server '{}' is not an SSE server", name))?;
+    let server_url =
+        reqwest::Url::parse(server_url).map_err(|e| format!("Invalid server URL: {}", e))?;
+
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .await
+        .map_err(|e| format!("Failed to listen for the OAuth callback: {}", e))?;
+    let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+    let redirect_uri = format!("http://127.0.0.1:{}/callback", port);
+
+    let client = super::proxy::http_client();
+    let metadata = discover_auth_server(&client, &server_url).await;
+    let (client_id, client_secret) = match client_id.filter(|c| !c.is_empty()) {
+        Some(client_id) => (client_id, None),
+        None => {
+            let endpoint = metadata.registration_endpoint.as_deref().ok_or_else(|| {
+                "The server doesn't support client registration; enter a client id".to_string()
+            })?;
+            register_client(&client, endpoint, &redirect_uri).await?
+        }
+    };
+
+    // PKCE (RFC 7636)
+    let verifier = format!(
+        "{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    );
+    let challenge = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .encode(Sha256::digest(verifier.as_bytes()));
+    let state = uuid::Uuid::new_v4().simple().to_string();
+
+    let mut params = vec![
+        ("response_type", "code".to_string()),
+        ("client_id", client_id.clone()),
+        ("redirect_uri", redirect_uri.clone()),
+        ("code_challenge", challenge),
+        ("code_challenge_method", "S256".to_string()),
+        ("state", state.clone()),
+        ("resource", server_url.to_string()),
+    ];
+    if let Some(scopes) = scopes.filter(|s| !s.is_empty()) {
+        params.push(("scope", scopes.join(" ")));
+    }
+    let authorization_url =
+        reqwest::Url::parse_with_params(&metadata.authorization_endpoint, &params)
+            .map_err(|e| format!("Invalid authorization endpoint: {}", e))?
+            .to_string();
+
+    let token_endpoint = metadata.token_endpoint;
+    let callback_uri = redirect_uri.clone();
+    tauri::async_runtime::spawn(async move {
+        let outcome: Result<(), String> = async {
+            let query = tokio::time::timeout(AUTHORIZATION_TIMEOUT, wait_for_callback(listener))
+                .await
+                .map_err(|_| "Timed out waiting for sign-in".to_string())??;
+            let param = |key: &str| query.iter().find(|(k, _)| k == key).map(|(_, v)| v.clone());
+
+            if let Some(error) = param("error") {
+                return Err(format!(
+                    "Authorization denied: {}",
+                    param("error_description").unwrap_or(error)
+                ));
+            }
+            if param("state").as_deref() != Some(state.as_str()) {
+                return Err("OAuth state mismatch; please try again".to_string());
+            }
+            let code = param("code").ok_or("Authorization response has no code")?;
+
+            let mut form = vec![
+                ("grant_type", "authorization_code"),
+                ("code", code.as_str()),
+                ("redirect_uri", callback_uri.as_str()),
+                ("client_id", client_id.as_str()),
+                ("code_verifier", verifier.as_str()),
+            ];
+            if let Some(secret) = &client_secret {
+                form.push(("client_secret", secret));
+            }
+            let mut token = request_token(&token_endpoint, &form).await?;
+            token.client_id = client_id.clone();
+            token.client_secret = client_secret.clone();
+
+            let token_name = name.clone();
+            tokio::task::spawn_blocking(move || save_token(&token_name, &token))
+                .await
+                .map_err(|e| e.to_string())??;
+
+            // Point the server's Authorization header at the token variable
+            let header = format!("Authorization: Bearer ${{{}}}", token_env_var(&name));
+            set_sse_server_headers(&app, &server, &[header])
+        }
+        .await;
+
+        match &outcome {
+            Ok(()) => info!("Authorized MCP server {}", name),
+            Err(e) => error!("OAuth for MCP server {} failed: {}", name, e),
+        }
+        let payload = serde_json::json!({
+            "name": name,
+            "success": outcome.is_ok(),
+            "error": outcome.err(),
+        });
+        let _ = app.emit(&format!("mcp-oauth-complete:{}", name), &payload);
+        let _ = app.emit("mcp-oauth-complete", &payload);
+    });
+
+    Ok(McpOAuthStart {
+        authorization_url,
+        redirect_uri,
+    })
+}
+
+/// Whether Claudia holds an OAuth token for a server
+#[tauri::command]
+#[specta::specta]
+pub async fn get_mcp_oauth_status(name: String) -> Result<McpOAuthStatus, String> {
+    let account = name.clone();
+    let token = tokio::task::spawn_blocking(move || load_token(&account))
+        .await
+        .map_err(|e| e.to_string())??;
+    Ok(McpOAuthStatus {
+        env_var: token_env_var(&name),
+        authorized: token.is_some(),
+        expires_at: token.and_then(|t| t.expires_at),
+        name,
+    })
+}
+
+/// Forget a server's OAuth token; the server needs to be authorized again to connect
+#[tauri::command]
+#[specta::specta]
+pub async fn revoke_mcp_oauth(name: String) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        delete_keychain_value(&token_account(&name))?;
+        let mut names = load_oauth_servers()?;
+        names.retain(|n| n != &name);
+        let names = serde_json::to_string(&names).map_err(|e| e.to_string())?;
+        write_keychain_value(OAUTH_INDEX_ACCOUNT, &names)?;
+        info!("Removed OAuth token of MCP server {}", name);
+        Ok(())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
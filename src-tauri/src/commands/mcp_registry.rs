@@ -0,0 +1,355 @@
+use log::info;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use tauri::{AppHandle, State};
+
+use super::agents::{delete_setting, get_setting, set_setting, AgentDb};
+use super::mcp::{mcp_add, AddServerResult};
+use super::secrets::set_secret;
+
+/// Registry used unless the user configured another one
+const DEFAULT_REGISTRY_URL: &str = "https://registry.modelcontextprotocol.io/v0/servers";
+
+/// Pages fetched per search, so a huge registry can't stall the UI
+const MAX_PAGES: usize = 10;
+
+/// An environment variable a registry server needs
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct RegistryEnvVar {
+    pub name: String,
+    pub description: Option<String>,
+    pub required: bool,
+    /// Stored in the keychain instead of the MCP config when installing
+    pub secret: bool,
+}
+
+/// A server listed in an MCP registry
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct RegistryServer {
+    pub name: String,
+    pub description: Option<String>,
+    pub version: Option<String>,
+    pub repository_url: Option<String>,
+    /// "stdio" for packages, "sse" for hosted servers
+    pub transport: String,
+    pub command: Option<String>,
+    pub args: Vec<String>,
+    pub url: Option<String>,
+    pub env_vars: Vec<RegistryEnvVar>,
+}
+
+fn str_field(value: &JsonValue, keys: &[&str]) -> Option<String> {
+    keys.iter()
+        .find_map(|key| value.get(*key).and_then(|v| v.as_str()))
+        .map(|s| s.to_string())
+}
+
+fn bool_field(value: &JsonValue, keys: &[&str]) -> bool {
+    keys.iter()
+        .find_map(|key| value.get(*key).and_then(|v| v.as_bool()))
+        .unwrap_or(false)
+}
+
+/// Command line that runs a registry package locally
+fn package_command(
+    registry_type: &str,
+    identifier: &str,
+    version: Option<&str>,
+) -> Option<(String, Vec<String>)> {
+    let versioned = |separator: &str| match version {
+        Some(version) if !version.is_empty() && version != "latest" => {
+            format!("{}{}{}", identifier, separator, version)
+        }
+        _ => identifier.to_string(),
+    };
+    match registry_type {
+        "npm" => Some(("npx".to_string(), vec!["-y".to_string(), versioned("@")])),
+        "pypi" => Some(("uvx".to_string(), vec![versioned("==")])),
+        "docker" | "oci" => Some((
+            "docker".to_string(),
+            vec![
+                "run".to_string(),
+                "-i".to_string(),
+                "--rm".to_string(),
+                versioned(":"),
+            ],
+        )),
+        _ => None,
+    }
+}
+
+/// Turn one registry entry into something installable; entries we can't run are skipped
+///
+/// The registry schema has changed between revisions, so both snake_case and
+/// camelCase field names are accepted.
+fn parse_registry_entry(entry: &JsonValue) -> Option<RegistryServer> {
+    let server = entry.get("server").unwrap_or(entry);
+    let name = str_field(server, &["name"])?;
+
+    let packages = server.get("packages").and_then(|p| p.as_array());
+    let package = packages.into_iter().flatten().find_map(|package| {
+        let registry_type =
+            str_field(package, &["registry_type", "registryType", "registry_name"])?;
+        let identifier = str_field(package, &["identifier", "name"])?;
+        let version = str_field(package, &["version"]);
+        let (command, args) = package_command(&registry_type, &identifier, version.as_deref())?;
+        Some((package, command, args))
+    });
+
+    let remote = server
+        .get("remotes")
+        .and_then(|r| r.as_array())
+        .into_iter()
+        .flatten()
+        .find(|remote| str_field(remote, &["transport_type", "type"]).as_deref() == Some("sse"));
+
+    let env_source = package.as_ref().map(|(package, _, _)| *package).or(remote);
+    let env_vars = env_source
+        .and_then(|source| {
+            source
+                .get("environment_variables")
+                .or_else(|| source.get("environmentVariables"))
+        })
+        .and_then(|vars| vars.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|var| {
+            Some(RegistryEnvVar {
+                name: str_field(var, &["name"])?,
+                description: str_field(var, &["description"]),
+                required: bool_field(var, &["is_required", "isRequired"]),
+                secret: bool_field(var, &["is_secret", "isSecret"]),
+            })
+        })
+        .collect();
+
+    let (transport, command, args, url) = match (package, remote) {
+        (Some((_, command, args)), _) => ("stdio".to_string(), Some(command), args, None),
+        (None, Some(remote)) => ("sse".to_string(), None, vec![], str_field(remote, &["url"])),
+        (None, None) => return None,
+    };
+
+    Some(RegistryServer {
+        name,
+        description: str_field(server, &["description"]),
+        version: str_field(server, &["version"]).or_else(|| {
+            server
+                .pointer("/version_detail/version")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string())
+        }),
+        repository_url: server
+            .pointer("/repository/url")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string()),
+        transport,
+        command,
+        args,
+        url,
+        env_vars,
+    })
+}
+
+fn registry_url(conn: &Connection) -> String {
+    get_setting(conn, "mcp_registry_url").unwrap_or_else(|| DEFAULT_REGISTRY_URL.to_string())
+}
+
+/// Fetch the registry index, following pagination cursors
+async fn fetch_registry(url: &str, query: Option<&str>) -> Result<Vec<RegistryServer>, String> {
+    let client = super::proxy::http_client();
+    let mut servers = Vec::new();
+    let mut cursor: Option<String> = None;
+
+    for _ in 0..MAX_PAGES {
+        let mut request = client.get(url).query(&[("limit", "100")]);
+        if let Some(query) = query {
+            request = request.query(&[("search", query)]);
+        }
+        if let Some(cursor) = &cursor {
+            request = request.query(&[("cursor", cursor)]);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("Failed to fetch MCP registry: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!(
+                "MCP registry responded with HTTP {}",
+                response.status()
+            ));
+        }
+        let page: JsonValue = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse MCP registry: {}", e))?;
+
+        // Accept both a paginated `{servers: [...]}` object and a plain array index
+        let entries = page
+            .get("servers")
+            .or(Some(&page))
+            .and_then(|s| s.as_array())
+            .ok_or("Unexpected MCP registry format")?;
+        servers.extend(entries.iter().filter_map(parse_registry_entry));
+
+        cursor = page
+            .pointer("/metadata/next_cursor")
+            .or_else(|| page.pointer("/metadata/nextCursor"))
+            .and_then(|c| c.as_str())
+            .map(|s| s.to_string());
+        if cursor.is_none() {
+            break;
+        }
+    }
+
+    Ok(servers)
+}
+
+/// Search the MCP server registry; without a query every installable server is returned
+#[tauri::command]
+#[specta::specta]
+pub async fn search_mcp_registry(
+    db: State<'_, AgentDb>,
+    query: Option<String>,
+) -> Result<Vec<RegistryServer>, String> {
+    let url = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        registry_url(&conn)
+    };
+    let query = query
+        .map(|q| q.trim().to_string())
+        .filter(|q| !q.is_empty());
+    let mut servers = fetch_registry(&url, query.as_deref()).await?;
+
+    // Registries without server-side search return everything
+    if let Some(query) = &query {
+        let needle = query.to_lowercase();
+        servers.retain(|server| {
+            server.name.to_lowercase().contains(&needle)
+                || server
+                    .description
+                    .as_deref()
+                    .is_some_and(|d| d.to_lowercase().contains(&needle))
+        });
+    }
+
+    // Registries list every published version; keep the first (latest) of each
+    let mut seen = std::collections::HashSet::new();
+    servers.retain(|server| seen.insert(server.name.clone()));
+    Ok(servers)
+}
+
+/// Use a different registry index, or the default one when `None`
+#[tauri::command]
+#[specta::specta]
+pub async fn set_mcp_registry_url(
+    db: State<'_, AgentDb>,
+    url: Option<String>,
+) -> Result<(), String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    match url.filter(|u| !u.trim().is_empty()) {
+        Some(url) => set_setting(&conn, "mcp_registry_url", url.trim()),
+        None => delete_setting(&conn, "mcp_registry_url"),
+    }
+    .map_err(|e| format!("Failed to save registry URL: {}", e))
+}
+
+/// Install a registry server into Claude Code's config
+///
+/// `env` holds the values the user entered for the server's variables. Secret
+/// ones go to the keychain and are referenced as `${NAME}`.
+#[tauri::command]
+#[specta::specta]
+pub async fn install_mcp_registry_server(
+    app: AppHandle,
+    server: RegistryServer,
+    local_name: Option<String>,
+    scope: String,
+    env: HashMap<String, String>,
+) -> Result<AddServerResult, String> {
+    let missing: Vec<&str> = server
+        .env_vars
+        .iter()
+        .filter(|var| var.required && env.get(&var.name).is_none_or(|v| v.is_empty()))
+        .map(|var| var.name.as_str())
+        .collect();
+    if !missing.is_empty() {
+        return Ok(AddServerResult {
+            success: false,
+            message: format!(
+                "Missing required environment variables: {}",
+                missing.join(", ")
+            ),
+            server_name: None,
+        });
+    }
+
+    let mut config_env = HashMap::new();
+    for (key, value) in env.into_iter().filter(|(_, v)| !v.is_empty()) {
+        let secret = server
+            .env_vars
+            .iter()
+            .any(|var| var.name == key && var.secret);
+        if secret {
+            set_secret(key.clone(), value).await?;
+            config_env.insert(key.clone(), format!("${{{}}}", key));
+        } else {
+            config_env.insert(key, value);
+        }
+    }
+
+    // Registry names look like "io.github.owner/server"; Claude Code wants something short
+    let name = local_name.unwrap_or_else(|| {
+        server
+            .name
+            .rsplit('/')
+            .next()
+            .unwrap_or(&server.name)
+            .to_string()
+    });
+    info!("Installing MCP registry server {} as {}", server.name, name);
+
+    mcp_add(
+        app,
+        name,
+        server.transport,
+        server.command,
+        server.args,
+        config_env,
+        server.url,
+        scope,
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_npm_package_entries() {
+        let entry = serde_json::json!({
+            "server": {
+                "name": "io.github.example/weather",
+                "description": "Weather forecasts",
+                "version": "1.2.0",
+                "packages": [{
+                    "registry_type": "npm",
+                    "identifier": "@example/weather-mcp",
+                    "version": "1.2.0",
+                    "environment_variables": [
+                        { "name": "WEATHER_API_KEY", "is_required": true, "is_secret": true }
+                    ]
+                }]
+            }
+        });
+
+        let server = parse_registry_entry(&entry).unwrap();
+        assert_eq!(server.transport, "stdio");
+        assert_eq!(server.command.as_deref(), Some("npx"));
+        assert_eq!(server.args, vec!["-y", "@example/weather-mcp@1.2.0"]);
+        assert!(server.env_vars[0].required && server.env_vars[0].secret);
+    }
+}
@@ -0,0 +1,253 @@
+use log::{info, warn};
+use rusqlite::{params, Connection, Result as SqliteResult};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use super::agents::AgentDb;
+
+/// A forward-only schema change, applied once in version order
+pub struct Migration {
+    pub version: i64,
+    pub description: &'static str,
+    pub sql: &'static str,
+}
+
+/// Every migration, oldest first; append new ones with the next version and never edit old ones
+///
+/// Version 1 stands for the tables `init_database` creates, so databases created before
+/// migrations existed start from the same point as new ones.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "Baseline schema",
+        sql: "",
+    },
+    Migration {
+        version: 2,
+        description: "Index agent runs by status and schedule",
+        sql: "CREATE INDEX IF NOT EXISTS idx_agent_runs_status ON agent_runs(status);
+              CREATE INDEX IF NOT EXISTS idx_agent_runs_schedule_id ON agent_runs(schedule_id);",
+    },
+    Migration {
+        version: 3,
+        description: "Track archived session transcripts",
+        sql: "CREATE TABLE IF NOT EXISTS session_archives (
+                project_id TEXT NOT NULL,
+                session_id TEXT NOT NULL,
+                original_size INTEGER NOT NULL,
+                compressed_size INTEGER NOT NULL,
+                archived_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (project_id, session_id)
+              );",
+    },
+    Migration {
+        version: 4,
+        description: "Index files read and written per session",
+        sql: "CREATE TABLE IF NOT EXISTS session_files (
+                source_path TEXT NOT NULL,
+                session_id TEXT NOT NULL,
+                project_path TEXT NOT NULL,
+                file_path TEXT NOT NULL,
+                access TEXT NOT NULL,
+                tool TEXT NOT NULL,
+                touch_count INTEGER NOT NULL,
+                first_touched_at TEXT,
+                last_touched_at TEXT,
+                PRIMARY KEY (source_path, file_path, access)
+              );
+              CREATE INDEX IF NOT EXISTS idx_session_files_file_path ON session_files(file_path);
+              CREATE TABLE IF NOT EXISTS session_file_index_files (
+                path TEXT PRIMARY KEY,
+                modified_ms INTEGER NOT NULL,
+                size INTEGER NOT NULL,
+                offset INTEGER NOT NULL,
+                project_path TEXT
+              );",
+    },
+    Migration {
+        version: 5,
+        description: "Outgoing webhooks for run lifecycle events",
+        sql: "CREATE TABLE IF NOT EXISTS webhooks (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL,
+                url TEXT NOT NULL,
+                secret TEXT NOT NULL DEFAULT '',
+                events TEXT NOT NULL DEFAULT '[]',
+                enabled BOOLEAN NOT NULL DEFAULT 1,
+                last_status INTEGER,
+                last_error TEXT,
+                last_delivered_at TEXT,
+                created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+              );",
+    },
+    Migration {
+        version: 6,
+        description: "Remember conversations imported from other tools",
+        sql: "CREATE TABLE IF NOT EXISTS history_imports (
+                source_key TEXT PRIMARY KEY,
+                source TEXT NOT NULL,
+                session_id TEXT NOT NULL,
+                project_path TEXT NOT NULL,
+                imported_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+              );",
+    },
+    Migration {
+        version: 7,
+        description: "Record agents created before versioning as version 1",
+        sql: "INSERT INTO agent_versions (agent_id, version, name, icon, system_prompt, default_task, model, enable_file_read, enable_file_write, enable_network, hooks, change_note)
+              SELECT id, 1, name, icon, system_prompt, default_task, COALESCE(model, 'sonnet'), COALESCE(enable_file_read, 1), COALESCE(enable_file_write, 1), COALESCE(enable_network, 0), hooks, 'initial'
+              FROM agents WHERE id NOT IN (SELECT agent_id FROM agent_versions);",
+    },
+];
+
+/// A migration recorded as applied
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct AppliedMigration {
+    pub version: i64,
+    pub description: String,
+    pub applied_at: String,
+}
+
+/// Schema version of the database and of this build
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct SchemaVersion {
+    pub version: i64,
+    pub latest_version: i64,
+    pub applied: Vec<AppliedMigration>,
+}
+
+fn current_version(conn: &Connection) -> SqliteResult<i64> {
+    conn.query_row(
+        "SELECT COALESCE(MAX(version), 0) FROM schema_migrations",
+        [],
+        |row| row.get(0),
+    )
+}
+
+/// Apply every migration newer than the database, each in its own transaction
+///
+/// Returns the schema version afterwards.
+pub fn run_migrations(conn: &Connection, migrations: &[Migration]) -> SqliteResult<i64> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS schema_migrations (
+            version INTEGER PRIMARY KEY,
+            description TEXT NOT NULL,
+            applied_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+
+    let mut version = current_version(conn)?;
+    let latest = migrations.last().map_or(0, |m| m.version);
+    if version > latest {
+        warn!(
+            "Database schema version {} is newer than this build ({}), it may have been opened by a newer Claudia",
+            version, latest
+        );
+        return Ok(version);
+    }
+
+    let pending = version;
+    for migration in migrations.iter().filter(|m| m.version > pending) {
+        info!(
+            "Applying database migration {}: {}",
+            migration.version, migration.description
+        );
+        let tx = conn.unchecked_transaction()?;
+        tx.execute_batch(migration.sql)?;
+        tx.execute(
+            "INSERT INTO schema_migrations (version, description) VALUES (?1, ?2)",
+            params![migration.version, migration.description],
+        )?;
+        tx.commit()?;
+        version = migration.version;
+    }
+    Ok(version)
+}
+
+/// Get the schema version of the agents database and the migrations applied to it
+#[tauri::command]
+#[specta::specta]
+pub async fn get_schema_version(db: State<'_, AgentDb>) -> Result<SchemaVersion, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare("SELECT version, description, applied_at FROM schema_migrations ORDER BY version")
+        .map_err(|e| e.to_string())?;
+    let applied = stmt
+        .query_map([], |row| {
+            Ok(AppliedMigration {
+                version: row.get(0)?,
+                description: row.get(1)?,
+                applied_at: row.get(2)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(SchemaVersion {
+        version: applied.last().map_or(0, |m| m.version),
+        latest_version: MIGRATIONS.last().map_or(0, |m| m.version),
+        applied,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn applies_pending_migrations_once_and_rolls_back_failures() {
+        let conn = Connection::open_in_memory().unwrap();
+        let migrations = [
+            Migration {
+                version: 1,
+                description: "Create notes",
+                sql: "CREATE TABLE notes (id INTEGER PRIMARY KEY, body TEXT NOT NULL);",
+            },
+            Migration {
+                version: 2,
+                description: "Add note titles",
+                sql: "ALTER TABLE notes ADD COLUMN title TEXT;",
+            },
+        ];
+        assert_eq!(run_migrations(&conn, &migrations).unwrap(), 2);
+        // Running again must not re-apply the ALTER, which would fail
+        assert_eq!(run_migrations(&conn, &migrations).unwrap(), 2);
+
+        let broken = [Migration {
+            version: 3,
+            description: "Broken",
+            sql: "ALTER TABLE notes ADD COLUMN priority INTEGER; SELECT * FROM missing;",
+        }];
+        assert!(run_migrations(&conn, &broken).is_err());
+        assert_eq!(current_version(&conn).unwrap(), 2);
+        assert!(conn.prepare("SELECT priority FROM notes").is_err());
+
+        // The bundled migrations run on a fresh agents schema
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE agent_runs (id INTEGER PRIMARY KEY, status TEXT, schedule_id INTEGER);
+             CREATE TABLE agents (id INTEGER PRIMARY KEY, name TEXT, icon TEXT, system_prompt TEXT,
+                 default_task TEXT, model TEXT, enable_file_read BOOLEAN, enable_file_write BOOLEAN,
+                 enable_network BOOLEAN, hooks TEXT);
+             CREATE TABLE agent_versions (agent_id INTEGER, version INTEGER, name TEXT, icon TEXT,
+                 system_prompt TEXT, default_task TEXT, model TEXT, enable_file_read BOOLEAN,
+                 enable_file_write BOOLEAN, enable_network BOOLEAN, hooks TEXT, change_note TEXT);
+             INSERT INTO agents (id, name, icon, system_prompt) VALUES (1, 'Reviewer', 'bot', 'Review');",
+        )
+        .unwrap();
+        assert_eq!(
+            run_migrations(&conn, MIGRATIONS).unwrap(),
+            MIGRATIONS.last().unwrap().version
+        );
+        // Agents saved before versioning get their current state as version 1
+        let backfilled: (i64, String) = conn
+            .query_row(
+                "SELECT version, model FROM agent_versions WHERE agent_id = 1",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(backfilled, (1, "sonnet".to_string()));
+    }
+}
@@ -4,3 +4,72 @@ pub mod mcp;
 pub mod usage;
 pub mod storage;
 pub mod slash_commands;
+pub mod scheduler;
+pub mod pipelines;
+pub mod run_queue;
+pub mod artifacts;
+pub mod run_compare;
+pub mod agent_versions;
+pub mod run_recovery;
+pub mod live_metrics;
+pub mod notifications;
+pub mod run_logs;
+pub mod batches;
+pub mod dry_run;
+pub mod mcp_connection;
+pub mod secrets;
+pub mod mcp_logs;
+pub mod claudia_mcp;
+pub mod mcp_registry;
+pub mod mcp_oauth;
+pub mod mcp_health;
+pub mod mcp_bundle;
+pub mod usage_index;
+pub mod usage_budgets;
+pub mod usage_report;
+pub mod checkpoint_retention;
+pub mod settings_file;
+pub mod hooks;
+pub mod env_profiles;
+pub mod proxy;
+pub mod permissions;
+pub mod preferences;
+pub mod environment;
+pub mod prompt_presets;
+pub mod processes;
+pub mod pty;
+pub mod resources;
+pub mod orphans;
+pub mod run_retry;
+pub mod migrations;
+pub mod backup;
+pub mod background_tasks;
+pub mod fs_watch;
+pub mod jsonl_reader;
+pub mod listing_cache;
+pub mod session_archive;
+pub mod db_maintenance;
+pub mod session_file_index;
+pub mod rest_api;
+pub mod deep_link;
+pub mod webhooks;
+pub mod chat_integrations;
+pub mod slack;
+pub mod discord;
+pub mod telemetry;
+pub mod plugins;
+pub mod vault_export;
+pub mod history_import;
+pub mod gists;
+pub mod email;
+pub mod schedule_calendar;
+pub mod issues;
+pub mod jira;
+pub mod linear;
+pub mod api_version;
+pub mod app_bundle;
+pub mod tray;
+pub mod hotkeys;
+pub mod updater;
+pub mod crash_reports;
+pub mod app_logs;
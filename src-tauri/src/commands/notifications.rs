@@ -0,0 +1,100 @@
+use log::{error, info};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+use tauri_plugin_notification::NotificationExt;
+
+use super::agents::{get_setting, set_setting, AgentDb};
+use super::live_metrics::AgentLiveMetrics;
+
+/// Opt-in desktop notifications for finished agent runs
+#[derive(Debug, Serialize, Deserialize, Clone, Default, specta::Type)]
+pub struct AgentNotificationSettings {
+    pub enabled: bool,
+    /// Only notify when a run fails
+    #[serde(default)]
+    pub only_on_failure: bool,
+}
+
+fn load_notification_settings(conn: &Connection) -> AgentNotificationSettings {
+    get_setting(conn, "agent_notification_settings").unwrap_or_default()
+}
+
+/// Format a duration in milliseconds as e.g. "4m 05s"
+pub fn format_elapsed(elapsed_ms: u64) -> String {
+    let seconds = elapsed_ms / 1000;
+    if seconds >= 3600 {
+        format!("{}h {:02}m", seconds / 3600, (seconds % 3600) / 60)
+    } else if seconds >= 60 {
+        format!("{}m {:02}s", seconds / 60, seconds % 60)
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
+/// Show a desktop notification for a finished run, if the user enabled them
+pub fn notify_agent_run_finished(
+    app: &AppHandle,
+    run_id: i64,
+    success: bool,
+    metrics: &AgentLiveMetrics,
+) {
+    let (settings, agent_name) = {
+        let db = app.state::<AgentDb>();
+        let Ok(conn) = db.0.lock() else {
+            return;
+        };
+        let agent_name = conn
+            .query_row(
+                "SELECT agent_name FROM agent_runs WHERE id = ?1",
+                params![run_id],
+                |row| row.get::<_, String>(0),
+            )
+            .unwrap_or_else(|_| "Agent".to_string());
+        (load_notification_settings(&conn), agent_name)
+    };
+
+    if !settings.enabled || (success && settings.only_on_failure) {
+        return;
+    }
+
+    let title = if success {
+        format!("{} finished", agent_name)
+    } else {
+        format!("{} failed", agent_name)
+    };
+    let mut body = format!("Run #{} took {}", run_id, format_elapsed(metrics.elapsed_ms));
+    if metrics.estimated_cost_usd > 0.0 {
+        body.push_str(&format!(" and cost ${:.2}", metrics.estimated_cost_usd));
+    }
+
+    match app.notification().builder().title(&title).body(&body).show() {
+        Ok(()) => info!("Sent notification for agent run {}", run_id),
+        Err(e) => error!("Failed to show notification for agent run {}: {}", run_id, e),
+    }
+}
+
+/// Get the desktop notification settings for agent runs
+#[tauri::command]
+#[specta::specta]
+pub async fn get_agent_notification_settings(
+    db: State<'_, AgentDb>,
+) -> Result<AgentNotificationSettings, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    Ok(load_notification_settings(&conn))
+}
+
+/// Save the desktop notification settings for agent runs
+#[tauri::command]
+#[specta::specta]
+pub async fn set_agent_notification_settings(
+    db: State<'_, AgentDb>,
+    settings: AgentNotificationSettings,
+) -> Result<(), String> {
+
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    set_setting(&conn, "agent_notification_settings", &settings)
+        .map_err(|e| format!("Failed to save notification settings: {}", e))?;
+
+    Ok(())
+}
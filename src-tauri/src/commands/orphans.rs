@@ -0,0 +1,397 @@
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use super::agents::AgentDb;
+use crate::process::terminate::{grace_period, terminate_blocking};
+use crate::process::{ProcessInfo, ProcessRegistryState, ProcessType};
+
+/// How often the session file of an adopted process is checked for new lines
+const TAIL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// A Claude process started by an earlier run of Claudia that is still running
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, specta::Type)]
+pub struct OrphanedProcess {
+    pub pid: u32,
+    /// Start time in seconds since the epoch, telling the process apart from a reused PID
+    pub start_time: u64,
+    /// "agent" or "session"
+    pub kind: String,
+    pub run_id: Option<i64>,
+    pub session_id: Option<String>,
+    pub project_path: String,
+    pub spawned_at: String,
+}
+
+/// Survivors found at startup that were neither adopted nor terminated yet
+#[derive(Default)]
+pub struct OrphanState(pub Mutex<Vec<OrphanedProcess>>);
+
+/// Start time of a running process, in seconds since the epoch
+pub fn process_start_time(pid: u32) -> Option<u64> {
+    let pid = Pid::from_u32(pid);
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&[pid]),
+        false,
+        ProcessRefreshKind::nothing(),
+    );
+    system.process(pid).map(|p| p.start_time())
+}
+
+/// Whether the process that was spawned is still the one running under its PID
+fn is_same_process(orphan: &OrphanedProcess) -> bool {
+    crate::process::terminate::is_alive(orphan.pid)
+        && process_start_time(orphan.pid) == Some(orphan.start_time)
+}
+
+/// Remember a spawned Claude process so it can be found again if Claudia crashes
+pub fn record_spawn(
+    conn: &Connection,
+    pid: u32,
+    kind: &str,
+    run_id: Option<i64>,
+    project_path: &str,
+) -> Result<(), String> {
+    let Some(start_time) = process_start_time(pid) else {
+        // The process already exited, so there is nothing to look after
+        return Ok(());
+    };
+    conn.execute(
+        "INSERT INTO spawned_processes (pid, start_time, kind, run_id, project_path)
+         VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT(pid, start_time) DO UPDATE SET kind = ?3, run_id = ?4, project_path = ?5",
+        params![pid, start_time as i64, kind, run_id, project_path],
+    )
+    .map_err(|e| format!("Failed to record spawned process: {}", e))?;
+    Ok(())
+}
+
+/// Record a spawned session process, logging instead of failing the spawn
+pub fn record_session_spawn(app: &AppHandle, pid: u32, project_path: &str) {
+    let db = app.state::<AgentDb>();
+    let result =
+        db.0.lock()
+            .map_err(|e| e.to_string())
+            .and_then(|conn| record_spawn(&conn, pid, "session", None, project_path));
+    if let Err(e) = result {
+        log::warn!("Failed to record session process {}: {}", pid, e);
+    }
+}
+
+/// Attach the session ID, once Claude reports it, so an orphan can be tailed
+pub fn record_session_id(app: &AppHandle, pid: u32, session_id: &str) {
+    let db = app.state::<AgentDb>();
+    let Ok(conn) = db.0.lock() else {
+        return;
+    };
+    if let Err(e) = conn.execute(
+        "UPDATE spawned_processes SET session_id = ?1 WHERE pid = ?2",
+        params![session_id, pid],
+    ) {
+        log::warn!("Failed to record session ID of process {}: {}", pid, e);
+    }
+}
+
+fn forget(conn: &Connection, orphan: &OrphanedProcess) -> Result<(), String> {
+    conn.execute(
+        "DELETE FROM spawned_processes WHERE pid = ?1 AND start_time = ?2",
+        params![orphan.pid, orphan.start_time as i64],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn recorded_processes(conn: &Connection) -> Result<Vec<OrphanedProcess>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT p.pid, p.start_time, p.kind, p.run_id,
+                    COALESCE(p.session_id, NULLIF(r.session_id, '')), p.project_path, p.created_at
+             FROM spawned_processes p LEFT JOIN agent_runs r ON r.id = p.run_id
+             ORDER BY p.created_at",
+        )
+        .map_err(|e| e.to_string())?;
+    let processes = stmt
+        .query_map([], |row| {
+            Ok(OrphanedProcess {
+                pid: row.get(0)?,
+                start_time: row.get::<_, i64>(1)? as u64,
+                kind: row.get(2)?,
+                run_id: row.get(3)?,
+                session_id: row.get(4)?,
+                project_path: row.get(5)?,
+                spawned_at: row.get(6)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(processes)
+}
+
+/// Find processes from an earlier run of Claudia that are still running
+///
+/// Called once at startup, before `detect_interrupted_runs`. Records of processes that
+/// exited are dropped, and agent runs whose PID now belongs to another process lose the
+/// PID so they are treated as interrupted.
+pub fn detect_orphaned_processes(conn: &Connection) -> Result<Vec<OrphanedProcess>, String> {
+    let mut survivors = Vec::new();
+    for process in recorded_processes(conn)? {
+        if is_same_process(&process) {
+            survivors.push(process);
+            continue;
+        }
+        forget(conn, &process)?;
+        if let Some(run_id) = process.run_id {
+            conn.execute(
+                "UPDATE agent_runs SET pid = NULL WHERE id = ?1 AND pid = ?2 AND status IN ('pending', 'running')",
+                params![run_id, process.pid],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+    }
+    if !survivors.is_empty() {
+        log::warn!(
+            "Found {} Claude process(es) left running by a previous session",
+            survivors.len()
+        );
+    }
+    Ok(survivors)
+}
+
+fn take_orphan(state: &OrphanState, pid: u32) -> Result<OrphanedProcess, String> {
+    let mut orphans = state.0.lock().map_err(|e| e.to_string())?;
+    let index = orphans
+        .iter()
+        .position(|o| o.pid == pid)
+        .ok_or_else(|| format!("No orphaned process with PID {}", pid))?;
+    Ok(orphans.remove(index))
+}
+
+fn session_file(orphan: &OrphanedProcess) -> Option<PathBuf> {
+    let session_id = orphan.session_id.as_ref()?;
+    let claude_dir = super::claude::get_claude_dir().ok()?;
+    Some(
+        claude_dir
+            .join("projects")
+            .join(orphan.project_path.replace('/', "-"))
+            .join(format!("{}.jsonl", session_id)),
+    )
+}
+
+/// Emit the lines Claude appends to its session file until the process exits
+fn tail_session(app: &AppHandle, orphan: &OrphanedProcess, output_event: &str) {
+    let path = session_file(orphan);
+    let open = || {
+        path.as_ref()
+            .and_then(|path| std::fs::File::open(path).ok())
+            .map(BufReader::new)
+    };
+    let mut reader = open();
+    // Only new lines are streamed; what was written before is in the session history
+    if let Some(reader) = reader.as_mut() {
+        let _ = reader.seek(SeekFrom::End(0));
+    }
+    let mut partial = String::new();
+    loop {
+        let alive = is_same_process(orphan);
+        // A file created after adoption is read from its start
+        if reader.is_none() {
+            reader = open();
+        }
+        if let Some(reader) = reader.as_mut() {
+            while reader.read_line(&mut partial).unwrap_or(0) > 0 {
+                if !partial.ends_with('\n') {
+                    break;
+                }
+                let line = partial.trim_end();
+                if !line.is_empty() {
+                    let _ = app.emit(output_event, line);
+                }
+                partial.clear();
+            }
+        }
+        if !alive {
+            return;
+        }
+        std::thread::sleep(TAIL_INTERVAL);
+    }
+}
+
+/// List the processes left running by a previous session of Claudia
+#[tauri::command]
+#[specta::specta]
+pub async fn list_orphaned_processes(
+    state: State<'_, OrphanState>,
+) -> Result<Vec<OrphanedProcess>, String> {
+    let mut orphans = state.0.lock().map_err(|e| e.to_string())?;
+    orphans.retain(is_same_process);
+    Ok(orphans.clone())
+}
+
+/// Stop an orphaned process; its agent run becomes interrupted so it can be resumed
+#[tauri::command]
+#[specta::specta]
+pub async fn terminate_orphaned_process(
+    db: State<'_, AgentDb>,
+    state: State<'_, OrphanState>,
+    pid: u32,
+) -> Result<(), String> {
+    let orphan = take_orphan(&state, pid)?;
+    if is_same_process(&orphan) {
+        let orphan_pid = orphan.pid;
+        tokio::task::spawn_blocking(move || terminate_blocking(orphan_pid, grace_period()))
+            .await
+            .map_err(|e| format!("Failed to terminate process: {}", e))??;
+    }
+
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    forget(&conn, &orphan)?;
+    if let Some(run_id) = orphan.run_id {
+        conn.execute(
+            "UPDATE agent_runs SET status = 'interrupted', interrupted_at = CURRENT_TIMESTAMP, pid = NULL WHERE id = ?1 AND status IN ('pending', 'running')",
+            params![run_id],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    log::info!("Terminated orphaned Claude process {}", pid);
+    Ok(())
+}
+
+/// Track an orphaned process again and stream its session file until it exits
+///
+/// Agent runs keep their run ID and emit `agent-output:{run_id}`; sessions get a new
+/// registry ID and emit `claude-output:{session_id}`. Returns the registry run ID.
+#[tauri::command]
+#[specta::specta]
+pub async fn adopt_orphaned_process(
+    app: AppHandle,
+    db: State<'_, AgentDb>,
+    state: State<'_, OrphanState>,
+    registry: State<'_, ProcessRegistryState>,
+    pid: u32,
+) -> Result<i64, String> {
+    let orphan = take_orphan(&state, pid)?;
+    if !is_same_process(&orphan) {
+        return Err(format!("Process {} is no longer running", pid));
+    }
+
+    let (run_id, process_type, task, model, output_event, complete_event) = match orphan.run_id {
+        Some(run_id) => {
+            let conn = db.0.lock().map_err(|e| e.to_string())?;
+            let (agent_id, agent_name, task, model) = conn
+                .query_row(
+                    "SELECT agent_id, agent_name, task, model FROM agent_runs WHERE id = ?1",
+                    params![run_id],
+                    |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+                )
+                .map_err(|e| format!("Failed to load agent run {}: {}", run_id, e))?;
+            (
+                run_id,
+                ProcessType::AgentRun {
+                    agent_id,
+                    agent_name,
+                },
+                task,
+                model,
+                format!("agent-output:{}", run_id),
+                format!("agent-complete:{}", run_id),
+            )
+        }
+        None => {
+            let session_id = orphan
+                .session_id
+                .clone()
+                .ok_or("The orphaned session never reported its session ID")?;
+            (
+                registry.0.generate_id()?,
+                ProcessType::ClaudeSession {
+                    session_id: session_id.clone(),
+                },
+                String::new(),
+                String::new(),
+                format!("claude-output:{}", session_id),
+                format!("claude-complete:{}", session_id),
+            )
+        }
+    };
+    registry.0.register_external(ProcessInfo {
+        run_id,
+        process_type,
+        pid,
+        started_at: chrono::DateTime::from_timestamp(orphan.start_time as i64, 0)
+            .unwrap_or_else(chrono::Utc::now),
+        project_path: orphan.project_path.clone(),
+        task,
+        model,
+    })?;
+    log::info!("Adopted orphaned Claude process {} as run {}", pid, run_id);
+
+    let registry = registry.0.clone();
+    tokio::task::spawn_blocking(move || {
+        tail_session(&app, &orphan, &output_event);
+        let _ = registry.unregister_process(run_id);
+
+        let db = app.state::<AgentDb>();
+        if let Ok(conn) = db.0.lock() {
+            let _ = forget(&conn, &orphan);
+            if orphan.run_id.is_some() {
+                let _ = conn.execute(
+                    "UPDATE agent_runs SET status = 'completed', completed_at = CURRENT_TIMESTAMP WHERE id = ?1 AND status = 'running'",
+                    params![run_id],
+                );
+            }
+        }
+        let _ = app.emit(&complete_event, true);
+        log::info!("Adopted process {} exited", orphan.pid);
+    });
+    Ok(run_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_only_processes_with_matching_start_time() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE agent_runs (id INTEGER PRIMARY KEY, session_id TEXT, status TEXT, pid INTEGER);
+             CREATE TABLE spawned_processes (
+                pid INTEGER NOT NULL, start_time INTEGER NOT NULL, kind TEXT NOT NULL,
+                run_id INTEGER, session_id TEXT, project_path TEXT NOT NULL,
+                created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP, PRIMARY KEY (pid, start_time));",
+        )
+        .unwrap();
+
+        let own_pid = std::process::id();
+        record_spawn(&conn, own_pid, "session", None, "/tmp/project").unwrap();
+        // The same PID with another start time belongs to a different process
+        conn.execute(
+            "INSERT INTO agent_runs (id, session_id, status, pid) VALUES (1, 'abc', 'running', ?1)",
+            params![own_pid],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO spawned_processes (pid, start_time, kind, run_id, project_path) VALUES (?1, 1, 'agent', 1, '/tmp/project')",
+            params![own_pid],
+        )
+        .unwrap();
+
+        let survivors = detect_orphaned_processes(&conn).unwrap();
+        assert_eq!(survivors.len(), 1);
+        assert_eq!(survivors[0].kind, "session");
+        assert_eq!(recorded_processes(&conn).unwrap(), survivors);
+        let pid: Option<i64> = conn
+            .query_row("SELECT pid FROM agent_runs WHERE id = 1", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(pid, None);
+    }
+}
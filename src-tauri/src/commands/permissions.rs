@@ -0,0 +1,424 @@
+use glob::{MatchOptions, Pattern};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::settings_file::{read_settings, settings_path, write_settings};
+
+/// Built-in tools that permission rules can name
+const KNOWN_TOOLS: &[&str] = &[
+    "Bash",
+    "Edit",
+    "Glob",
+    "Grep",
+    "LS",
+    "MultiEdit",
+    "NotebookEdit",
+    "NotebookRead",
+    "Read",
+    "Task",
+    "TodoWrite",
+    "WebFetch",
+    "WebSearch",
+    "Write",
+];
+
+/// Tools whose specifier is a file path pattern
+const PATH_TOOLS: &[&str] = &[
+    "Edit",
+    "MultiEdit",
+    "NotebookEdit",
+    "NotebookRead",
+    "Read",
+    "Write",
+];
+
+/// The allow/ask/deny lists of a settings file
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, specta::Type)]
+pub struct PermissionRules {
+    #[serde(default)]
+    pub allow: Vec<String>,
+    #[serde(default)]
+    pub ask: Vec<String>,
+    #[serde(default)]
+    pub deny: Vec<String>,
+}
+
+/// A rule split into the tool and the optional specifier in parentheses
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, specta::Type)]
+pub struct ParsedRule {
+    pub tool: String,
+    pub specifier: Option<String>,
+}
+
+/// A tool use to check against a rule set, e.g. `Bash` with `npm run test`
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct ToolCall {
+    pub tool: String,
+    /// The command, file path or URL the tool is used with
+    pub input: Option<String>,
+}
+
+/// How a rule set treats a tool or tool call
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum PermissionDecision {
+    Allow,
+    Ask,
+    Deny,
+    /// No rule applies, so Claude asks as usual
+    Default,
+}
+
+/// How a rule set treats a tool as a whole
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct ToolAccess {
+    pub tool: String,
+    /// Decision for uses no specific rule covers
+    pub decision: PermissionDecision,
+    /// Rules that only cover some uses, such as `Bash(git diff:*)`
+    pub scoped_rules: Vec<String>,
+}
+
+/// Decision for one tool call and the rule behind it
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct CallDecision {
+    pub call: ToolCall,
+    pub decision: PermissionDecision,
+    pub matched_rule: Option<String>,
+}
+
+/// What a rule set would permit
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct PermissionPreview {
+    pub tools: Vec<ToolAccess>,
+    pub calls: Vec<CallDecision>,
+}
+
+/// Parse and validate a rule such as `Bash(npm run test:*)` or `mcp__github`
+pub fn parse_rule(rule: &str) -> Result<ParsedRule, String> {
+    let rule = rule.trim();
+    let (tool, specifier) = match rule.find('(') {
+        Some(open) => {
+            let Some(inner) = rule[open + 1..].strip_suffix(')') else {
+                return Err(format!("Rule '{}' is missing a closing parenthesis", rule));
+            };
+            (&rule[..open], Some(inner.trim()))
+        }
+        None if rule.contains(')') => {
+            return Err(format!("Rule '{}' has an unmatched parenthesis", rule));
+        }
+        None => (rule, None),
+    };
+
+    if tool.starts_with("mcp__") {
+        if specifier.is_some() {
+            return Err(format!(
+                "MCP rule '{}' cannot have a specifier; use mcp__server or mcp__server__tool",
+                rule
+            ));
+        }
+        if tool.trim_start_matches("mcp__").is_empty() {
+            return Err(format!("MCP rule '{}' is missing the server name", rule));
+        }
+    } else if !KNOWN_TOOLS.contains(&tool) {
+        return Err(format!("Unknown tool '{}' in rule '{}'", tool, rule));
+    }
+
+    if let Some(spec) = specifier {
+        if spec.is_empty() {
+            return Err(format!(
+                "Rule '{}' has an empty specifier; drop the parentheses to match every use",
+                rule
+            ));
+        }
+        if PATH_TOOLS.contains(&tool) {
+            Pattern::new(spec).map_err(|e| format!("Invalid path pattern in '{}': {}", rule, e))?;
+        } else if tool == "WebFetch" && !spec.starts_with("domain:") {
+            return Err(format!(
+                "WebFetch rule '{}' must use the form WebFetch(domain:example.com)",
+                rule
+            ));
+        } else if tool == "Bash" && spec.contains(":*") && !spec.ends_with(":*") {
+            return Err(format!(
+                "Bash rule '{}' may only use :* at the end as a prefix wildcard",
+                rule
+            ));
+        }
+    }
+
+    Ok(ParsedRule {
+        tool: tool.to_string(),
+        specifier: specifier.map(str::to_string),
+    })
+}
+
+/// Every invalid rule of a rule set, prefixed with the list it is in
+pub fn validate_rules(rules: &PermissionRules) -> Vec<String> {
+    [
+        ("allow", &rules.allow),
+        ("ask", &rules.ask),
+        ("deny", &rules.deny),
+    ]
+    .into_iter()
+    .flat_map(|(list, rules)| {
+        rules
+            .iter()
+            .filter_map(move |rule| parse_rule(rule).err().map(|e| format!("{}: {}", list, e)))
+    })
+    .collect()
+}
+
+fn matches_path(pattern: &str, path: &str) -> bool {
+    let options = MatchOptions {
+        require_literal_separator: true,
+        ..MatchOptions::default()
+    };
+    let pattern = pattern.trim_start_matches("./");
+    let path = path.trim_start_matches("./");
+    let Ok(glob) = Pattern::new(pattern) else {
+        return false;
+    };
+    // Relative patterns match anywhere below the project root
+    glob.matches_with(path, options)
+        || (!pattern.starts_with('/')
+            && path
+                .match_indices('/')
+                .any(|(i, _)| glob.matches_with(&path[i + 1..], options)))
+}
+
+fn matches_domain(domain: &str, url: &str) -> bool {
+    let host = reqwest::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_string))
+        .unwrap_or_else(|| url.to_string());
+    host == domain || host.ends_with(&format!(".{}", domain))
+}
+
+/// Whether a rule covers a tool call
+fn rule_matches(rule: &ParsedRule, call: &ToolCall) -> bool {
+    if rule.tool.starts_with("mcp__") {
+        // `mcp__server` covers every tool of that server
+        return call.tool == rule.tool || call.tool.starts_with(&format!("{}__", rule.tool));
+    }
+    if rule.tool != call.tool {
+        return false;
+    }
+    let Some(spec) = &rule.specifier else {
+        return true;
+    };
+    let Some(input) = call.input.as_deref() else {
+        return false;
+    };
+    if let Some(prefix) = spec.strip_suffix(":*") {
+        input == prefix || input.starts_with(&format!("{} ", prefix))
+    } else if let Some(domain) = spec.strip_prefix("domain:") {
+        matches_domain(domain, input)
+    } else if PATH_TOOLS.contains(&rule.tool.as_str()) {
+        matches_path(spec, input)
+    } else {
+        input == spec
+    }
+}
+
+/// Decide a call the way Claude Code does: deny beats ask beats allow
+pub fn decide(rules: &PermissionRules, call: &ToolCall) -> (PermissionDecision, Option<String>) {
+    for (decision, list) in [
+        (PermissionDecision::Deny, &rules.deny),
+        (PermissionDecision::Ask, &rules.ask),
+        (PermissionDecision::Allow, &rules.allow),
+    ] {
+        let matched = list.iter().find(|rule| {
+            parse_rule(rule)
+                .map(|parsed| rule_matches(&parsed, call))
+                .unwrap_or(false)
+        });
+        if let Some(rule) = matched {
+            return (decision, Some(rule.clone()));
+        }
+    }
+    (PermissionDecision::Default, None)
+}
+
+fn tool_access(rules: &PermissionRules, tool: &str) -> ToolAccess {
+    let whole_tool = ToolCall {
+        tool: tool.to_string(),
+        input: None,
+    };
+    let scoped_rules = rules
+        .allow
+        .iter()
+        .chain(&rules.ask)
+        .chain(&rules.deny)
+        .filter(|rule| {
+            parse_rule(rule).is_ok_and(|parsed| parsed.tool == tool && parsed.specifier.is_some())
+        })
+        .cloned()
+        .collect();
+    ToolAccess {
+        tool: tool.to_string(),
+        decision: decide(rules, &whole_tool).0,
+        scoped_rules,
+    }
+}
+
+fn read_rules(settings: &Value) -> PermissionRules {
+    settings
+        .get("permissions")
+        .and_then(|p| serde_json::from_value(p.clone()).ok())
+        .unwrap_or_default()
+}
+
+/// Read the permission rules of a settings scope: "user", "project" or "local"
+#[tauri::command]
+#[specta::specta]
+pub async fn get_permission_rules(
+    scope: String,
+    project_path: Option<String>,
+) -> Result<PermissionRules, String> {
+    let path = settings_path(&scope, project_path.as_deref())?;
+    Ok(read_rules(&read_settings(&path)?))
+}
+
+/// Validate and save permission rules, keeping the other permission settings
+#[tauri::command]
+#[specta::specta]
+pub async fn set_permission_rules(
+    scope: String,
+    project_path: Option<String>,
+    rules: PermissionRules,
+) -> Result<(), String> {
+    let errors = validate_rules(&rules);
+    if !errors.is_empty() {
+        return Err(errors.join("; "));
+    }
+    let path = settings_path(&scope, project_path.as_deref())?;
+    let mut settings = read_settings(&path)?;
+    let settings_object = settings
+        .as_object_mut()
+        .ok_or("Settings must be a JSON object")?;
+    let permissions = settings_object
+        .entry("permissions")
+        .or_insert_with(|| json!({}))
+        .as_object_mut()
+        .ok_or("The permissions setting must be an object")?;
+    for (list, values) in [
+        ("allow", rules.allow),
+        ("ask", rules.ask),
+        ("deny", rules.deny),
+    ] {
+        if values.is_empty() {
+            permissions.remove(list);
+        } else {
+            permissions.insert(list.to_string(), json!(values));
+        }
+    }
+    write_settings(&path, &settings)?;
+    Ok(())
+}
+
+/// Validate a single rule
+#[tauri::command]
+#[specta::specta]
+pub async fn validate_permission_rule(rule: String) -> Result<ParsedRule, String> {
+    parse_rule(&rule)
+}
+
+/// Show which tools and example calls a rule set would permit
+#[tauri::command]
+#[specta::specta]
+pub async fn preview_permissions(
+    rules: PermissionRules,
+    calls: Option<Vec<ToolCall>>,
+) -> Result<PermissionPreview, String> {
+    let errors = validate_rules(&rules);
+    if !errors.is_empty() {
+        return Err(errors.join("; "));
+    }
+    let mut tools: Vec<ToolAccess> = KNOWN_TOOLS
+        .iter()
+        .map(|tool| tool_access(&rules, tool))
+        .collect();
+    // MCP rules name servers and tools that are not built in
+    let mut mcp_tools: Vec<String> = rules
+        .allow
+        .iter()
+        .chain(&rules.ask)
+        .chain(&rules.deny)
+        .filter_map(|rule| parse_rule(rule).ok())
+        .filter(|parsed| parsed.tool.starts_with("mcp__"))
+        .map(|parsed| parsed.tool)
+        .collect();
+    mcp_tools.sort();
+    mcp_tools.dedup();
+    tools.extend(mcp_tools.iter().map(|tool| tool_access(&rules, tool)));
+
+    let calls = calls
+        .unwrap_or_default()
+        .into_iter()
+        .map(|call| {
+            let (decision, matched_rule) = decide(&rules, &call);
+            CallDecision {
+                call,
+                decision,
+                matched_rule,
+            }
+        })
+        .collect();
+    Ok(PermissionPreview { tools, calls })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(tool: &str, input: &str) -> ToolCall {
+        ToolCall {
+            tool: tool.to_string(),
+            input: Some(input.to_string()),
+        }
+    }
+
+    #[test]
+    fn rules_validate_and_decide_like_claude() {
+        assert!(parse_rule("Bash(npm run test:*)").is_ok());
+        assert!(parse_rule("Bash(npm run test").is_err());
+        assert!(parse_rule("Shell(ls)").is_err());
+        assert!(parse_rule("WebFetch(example.com)").is_err());
+        assert!(parse_rule("Bash(git:* --force)").is_err());
+
+        let rules = PermissionRules {
+            allow: vec![
+                "Bash(npm run test:*)".to_string(),
+                "Read".to_string(),
+                "WebFetch(domain:docs.rs)".to_string(),
+                "mcp__github".to_string(),
+            ],
+            ask: vec!["Edit(src/**)".to_string()],
+            deny: vec!["Read(**/.env)".to_string()],
+        };
+        let decision = |tool: &str, input: &str| decide(&rules, &call(tool, input)).0;
+        assert_eq!(
+            decision("Bash", "npm run test -- --watch"),
+            PermissionDecision::Allow
+        );
+        assert_eq!(
+            decision("Bash", "npm run test-e2e"),
+            PermissionDecision::Default
+        );
+        assert_eq!(decision("Read", "src/main.rs"), PermissionDecision::Allow);
+        assert_eq!(decision("Read", "config/.env"), PermissionDecision::Deny);
+        assert_eq!(decision("Edit", "src/lib/util.rs"), PermissionDecision::Ask);
+        assert_eq!(
+            decision("WebFetch", "https://api.docs.rs/x"),
+            PermissionDecision::Allow
+        );
+        assert_eq!(
+            decision("mcp__github__create_issue", ""),
+            PermissionDecision::Allow
+        );
+
+        let read = tool_access(&rules, "Read");
+        assert_eq!(read.decision, PermissionDecision::Allow);
+        assert_eq!(read.scoped_rules, vec!["Read(**/.env)"]);
+    }
+}
@@ -0,0 +1,648 @@
+use log::{error, info, warn};
+use rusqlite::{params, Connection, Row};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use super::agents::{execute_agent, get_session_output, AgentDb};
+use crate::process::ProcessRegistryState;
+
+/// How often a pipeline checks whether its current step has finished
+const STEP_POLL_INTERVAL_MS: u64 = 2000;
+
+/// Longest previous-step output injected into the next task
+const MAX_PASSED_OUTPUT_CHARS: usize = 20_000;
+
+const PIPELINE_COLUMNS: &str = "id, name, description, steps, created_at, updated_at";
+const PIPELINE_RUN_COLUMNS: &str = "id, pipeline_id, pipeline_name, project_path, input, steps, status, current_step, step_run_ids, step_outputs, error, created_at, completed_at";
+
+/// One agent invocation within a pipeline
+///
+/// `task` may reference `{{input}}` (the pipeline input) and `{{previous_output}}`
+/// (the result of the previous step). When a later step doesn't reference the
+/// previous output, it is appended to the task.
+#[derive(Debug, Serialize, Deserialize, Clone, specta::Type)]
+pub struct PipelineStep {
+    pub agent_id: i64,
+    pub task: String,
+    pub model: Option<String>,
+}
+
+/// A named chain of agent steps
+#[derive(Debug, Serialize, Deserialize, Clone, specta::Type)]
+pub struct AgentPipeline {
+    pub id: Option<i64>,
+    pub name: String,
+    pub description: Option<String>,
+    pub steps: Vec<PipelineStep>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// One execution of a pipeline against a project
+#[derive(Debug, Serialize, Deserialize, Clone, specta::Type)]
+pub struct PipelineRun {
+    pub id: Option<i64>,
+    pub pipeline_id: i64,
+    pub pipeline_name: String,
+    pub project_path: String,
+    pub input: Option<String>,
+    pub steps: Vec<PipelineStep>,
+    pub status: String, // 'pending', 'running', 'completed', 'failed', 'cancelled'
+    pub current_step: usize,
+    pub step_run_ids: Vec<i64>,
+    pub step_outputs: Vec<String>,
+    pub error: Option<String>,
+    pub created_at: String,
+    pub completed_at: Option<String>,
+}
+
+fn row_to_pipeline(row: &Row) -> rusqlite::Result<AgentPipeline> {
+    let steps: String = row.get(3)?;
+    Ok(AgentPipeline {
+        id: Some(row.get(0)?),
+        name: row.get(1)?,
+        description: row.get(2)?,
+        steps: serde_json::from_str(&steps).unwrap_or_default(),
+        created_at: row.get(4)?,
+        updated_at: row.get(5)?,
+    })
+}
+
+fn row_to_pipeline_run(row: &Row) -> rusqlite::Result<PipelineRun> {
+    let steps: String = row.get(5)?;
+    let step_run_ids: String = row.get(8)?;
+    let step_outputs: String = row.get(9)?;
+    Ok(PipelineRun {
+        id: Some(row.get(0)?),
+        pipeline_id: row.get(1)?,
+        pipeline_name: row.get(2)?,
+        project_path: row.get(3)?,
+        input: row.get(4)?,
+        steps: serde_json::from_str(&steps).unwrap_or_default(),
+        status: row.get(6)?,
+        current_step: row.get::<_, i64>(7)?.max(0) as usize,
+        step_run_ids: serde_json::from_str(&step_run_ids).unwrap_or_default(),
+        step_outputs: serde_json::from_str(&step_outputs).unwrap_or_default(),
+        error: row.get(10)?,
+        created_at: row.get(11)?,
+        completed_at: row.get(12)?,
+    })
+}
+
+fn load_pipeline(conn: &Connection, id: i64) -> Result<AgentPipeline, String> {
+    conn.query_row(
+        &format!("SELECT {} FROM agent_pipelines WHERE id = ?1", PIPELINE_COLUMNS),
+        params![id],
+        row_to_pipeline,
+    )
+    .map_err(|e| format!("Failed to load pipeline {}: {}", id, e))
+}
+
+fn load_pipeline_run(conn: &Connection, id: i64) -> Result<PipelineRun, String> {
+    conn.query_row(
+        &format!(
+            "SELECT {} FROM agent_pipeline_runs WHERE id = ?1",
+            PIPELINE_RUN_COLUMNS
+        ),
+        params![id],
+        row_to_pipeline_run,
+    )
+    .map_err(|e| format!("Failed to load pipeline run {}: {}", id, e))
+}
+
+fn validate_pipeline(conn: &Connection, name: &str, steps: &[PipelineStep]) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("Pipeline name must not be empty".to_string());
+    }
+    if steps.is_empty() {
+        return Err("A pipeline needs at least one step".to_string());
+    }
+    for (index, step) in steps.iter().enumerate() {
+        if step.task.trim().is_empty() {
+            return Err(format!("Step {} has an empty task", index + 1));
+        }
+        let exists: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM agents WHERE id = ?1",
+                params![step.agent_id],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string())?;
+        if exists == 0 {
+            return Err(format!(
+                "Step {} uses agent {} which does not exist",
+                index + 1,
+                step.agent_id
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Build the task for a step, injecting the pipeline input and the previous step's output
+fn render_step_task(template: &str, input: Option<&str>, previous_output: Option<&str>) -> String {
+    let previous = previous_output.map(|output| {
+        if output.chars().count() > MAX_PASSED_OUTPUT_CHARS {
+            let truncated: String = output.chars().take(MAX_PASSED_OUTPUT_CHARS).collect();
+            format!("{}\n[output truncated]", truncated)
+        } else {
+            output.to_string()
+        }
+    });
+
+    let mut task = template.replace("{{input}}", input.unwrap_or(""));
+    match previous {
+        Some(previous) if task.contains("{{previous_output}}") => {
+            task = task.replace("{{previous_output}}", &previous);
+        }
+        Some(previous) => {
+            task.push_str("\n\n## Output from the previous step\n\n");
+            task.push_str(&previous);
+        }
+        None => {
+            task = task.replace("{{previous_output}}", "");
+        }
+    }
+    task
+}
+
+/// Extract the final result text from a Claude stream-json transcript
+///
+/// Prefers the `result` message; falls back to the text of the last assistant
+/// message. The flag is true when Claude reported the run as an error.
+pub fn extract_run_result(jsonl_content: &str) -> Option<(String, bool)> {
+    let mut last_assistant_text = None;
+
+    for line in jsonl_content.lines().rev() {
+        let Ok(json) = serde_json::from_str::<JsonValue>(line) else {
+            continue;
+        };
+
+        match json.get("type").and_then(|t| t.as_str()) {
+            Some("result") => {
+                if let Some(result) = json.get("result").and_then(|r| r.as_str()) {
+                    let is_error = json
+                        .get("is_error")
+                        .and_then(|e| e.as_bool())
+                        .unwrap_or(false);
+                    return Some((result.to_string(), is_error));
+                }
+            }
+            Some("assistant") if last_assistant_text.is_none() => {
+                let text: Vec<&str> = json
+                    .pointer("/message/content")
+                    .and_then(|c| c.as_array())
+                    .map(|content| {
+                        content
+                            .iter()
+                            .filter(|item| item.get("type").and_then(|t| t.as_str()) == Some("text"))
+                            .filter_map(|item| item.get("text").and_then(|t| t.as_str()))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                if !text.is_empty() {
+                    last_assistant_text = Some(text.join("\n"));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    last_assistant_text.map(|text| (text, false))
+}
+
+/// List all pipelines
+#[tauri::command]
+#[specta::specta]
+pub async fn list_agent_pipelines(db: State<'_, AgentDb>) -> Result<Vec<AgentPipeline>, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM agent_pipelines ORDER BY created_at DESC",
+            PIPELINE_COLUMNS
+        ))
+        .map_err(|e| e.to_string())?;
+
+    let pipelines = stmt
+        .query_map([], row_to_pipeline)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(pipelines)
+}
+
+/// Create a pipeline
+#[tauri::command]
+#[specta::specta]
+pub async fn create_agent_pipeline(
+    db: State<'_, AgentDb>,
+    name: String,
+    description: Option<String>,
+    steps: Vec<PipelineStep>,
+) -> Result<AgentPipeline, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    validate_pipeline(&conn, &name, &steps)?;
+
+    let steps_json = serde_json::to_string(&steps).map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO agent_pipelines (name, description, steps) VALUES (?1, ?2, ?3)",
+        params![name, description, steps_json],
+    )
+    .map_err(|e| format!("Failed to create pipeline: {}", e))?;
+
+    load_pipeline(&conn, conn.last_insert_rowid())
+}
+
+/// Update a pipeline; runs already in progress keep the steps they started with
+#[tauri::command]
+#[specta::specta]
+pub async fn update_agent_pipeline(
+    db: State<'_, AgentDb>,
+    id: i64,
+    name: String,
+    description: Option<String>,
+    steps: Vec<PipelineStep>,
+) -> Result<AgentPipeline, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    validate_pipeline(&conn, &name, &steps)?;
+
+    let steps_json = serde_json::to_string(&steps).map_err(|e| e.to_string())?;
+    conn.execute(
+        "UPDATE agent_pipelines SET name = ?1, description = ?2, steps = ?3 WHERE id = ?4",
+        params![name, description, steps_json, id],
+    )
+    .map_err(|e| format!("Failed to update pipeline: {}", e))?;
+
+    load_pipeline(&conn, id)
+}
+
+/// Delete a pipeline and its run history
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_agent_pipeline(db: State<'_, AgentDb>, id: i64) -> Result<(), String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "DELETE FROM agent_pipeline_runs WHERE pipeline_id = ?1",
+        params![id],
+    )
+    .map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM agent_pipelines WHERE id = ?1", params![id])
+        .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// List pipeline runs, optionally for a single pipeline
+#[tauri::command]
+#[specta::specta]
+pub async fn list_pipeline_runs(
+    db: State<'_, AgentDb>,
+    pipeline_id: Option<i64>,
+) -> Result<Vec<PipelineRun>, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+
+    let query = if pipeline_id.is_some() {
+        format!(
+            "SELECT {} FROM agent_pipeline_runs WHERE pipeline_id = ?1 ORDER BY created_at DESC",
+            PIPELINE_RUN_COLUMNS
+        )
+    } else {
+        format!(
+            "SELECT {} FROM agent_pipeline_runs ORDER BY created_at DESC",
+            PIPELINE_RUN_COLUMNS
+        )
+    };
+
+    let mut stmt = conn.prepare(&query).map_err(|e| e.to_string())?;
+    let runs = if let Some(pid) = pipeline_id {
+        stmt.query_map(params![pid], row_to_pipeline_run)
+    } else {
+        stmt.query_map([], row_to_pipeline_run)
+    }
+    .map_err(|e| e.to_string())?
+    .collect::<Result<Vec<_>, _>>()
+    .map_err(|e| e.to_string())?;
+
+    Ok(runs)
+}
+
+/// Get a single pipeline run
+#[tauri::command]
+#[specta::specta]
+pub async fn get_pipeline_run(db: State<'_, AgentDb>, id: i64) -> Result<PipelineRun, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    load_pipeline_run(&conn, id)
+}
+
+/// Start a pipeline against a project; steps run in the background
+#[tauri::command]
+#[specta::specta]
+pub async fn run_agent_pipeline(
+    app: AppHandle,
+    db: State<'_, AgentDb>,
+    pipeline_id: i64,
+    project_path: String,
+    input: Option<String>,
+) -> Result<i64, String> {
+    let pipeline_run_id = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        let pipeline = load_pipeline(&conn, pipeline_id)?;
+        validate_pipeline(&conn, &pipeline.name, &pipeline.steps)?;
+
+        let steps_json = serde_json::to_string(&pipeline.steps).map_err(|e| e.to_string())?;
+        conn.execute(
+            "INSERT INTO agent_pipeline_runs (pipeline_id, pipeline_name, project_path, input, steps, status) VALUES (?1, ?2, ?3, ?4, ?5, 'running')",
+            params![pipeline_id, pipeline.name, project_path, input, steps_json],
+        )
+        .map_err(|e| format!("Failed to create pipeline run: {}", e))?;
+        conn.last_insert_rowid()
+    };
+
+    info!(
+        "Starting pipeline {} as run {} in {}",
+        pipeline_id, pipeline_run_id, project_path
+    );
+    spawn_pipeline_driver(app, pipeline_run_id);
+
+    Ok(pipeline_run_id)
+}
+
+/// Resume a failed or cancelled pipeline run from the step that didn't complete
+#[tauri::command]
+#[specta::specta]
+pub async fn resume_pipeline_run(
+    app: AppHandle,
+    db: State<'_, AgentDb>,
+    id: i64,
+) -> Result<PipelineRun, String> {
+    let run = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        let run = load_pipeline_run(&conn, id)?;
+        if run.status != "failed" && run.status != "cancelled" {
+            return Err(format!(
+                "Only failed or cancelled pipeline runs can be resumed (status: {})",
+                run.status
+            ));
+        }
+
+        conn.execute(
+            "UPDATE agent_pipeline_runs SET status = 'running', error = NULL, completed_at = NULL WHERE id = ?1",
+            params![id],
+        )
+        .map_err(|e| format!("Failed to resume pipeline run: {}", e))?;
+        load_pipeline_run(&conn, id)?
+    };
+
+    info!("Resuming pipeline run {} at step {}", id, run.current_step + 1);
+    spawn_pipeline_driver(app, id);
+
+    Ok(run)
+}
+
+/// Cancel a pipeline run, stopping the agent of the current step
+#[tauri::command]
+#[specta::specta]
+pub async fn cancel_pipeline_run(
+    app: AppHandle,
+    db: State<'_, AgentDb>,
+    registry: State<'_, ProcessRegistryState>,
+    id: i64,
+) -> Result<(), String> {
+    let current_run_id = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        let run = load_pipeline_run(&conn, id)?;
+        conn.execute(
+            "UPDATE agent_pipeline_runs SET status = 'cancelled', completed_at = CURRENT_TIMESTAMP WHERE id = ?1 AND status IN ('pending', 'running')",
+            params![id],
+        )
+        .map_err(|e| e.to_string())?;
+        run.step_run_ids.get(run.current_step).copied()
+    };
+
+    if let Some(run_id) = current_run_id {
+        if let Err(e) = registry.0.kill_process(run_id).await {
+            warn!("Failed to stop agent run {} for pipeline {}: {}", run_id, id, e);
+        }
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        let _ = conn.execute(
+            "UPDATE agent_runs SET status = 'cancelled', completed_at = CURRENT_TIMESTAMP WHERE id = ?1 AND status = 'running'",
+            params![run_id],
+        );
+    }
+
+    let _ = app.emit(&format!("pipeline-cancelled:{}", id), true);
+    Ok(())
+}
+
+fn spawn_pipeline_driver(app: AppHandle, pipeline_run_id: i64) {
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = drive_pipeline_run(&app, pipeline_run_id).await {
+            error!("Pipeline run {} failed: {}", pipeline_run_id, e);
+            finish_pipeline_run(&app, pipeline_run_id, "failed", Some(e));
+        }
+    });
+}
+
+/// Mark a pipeline run as finished (unless it was cancelled meanwhile) and notify the frontend
+fn finish_pipeline_run(app: &AppHandle, pipeline_run_id: i64, status: &str, error: Option<String>) {
+    let db = app.state::<AgentDb>();
+    if let Ok(conn) = db.0.lock() {
+        let _ = conn.execute(
+            "UPDATE agent_pipeline_runs SET status = ?1, error = ?2, completed_at = CURRENT_TIMESTAMP WHERE id = ?3 AND status = 'running'",
+            params![status, error, pipeline_run_id],
+        );
+    }
+
+    let success = status == "completed";
+    let _ = app.emit(&format!("pipeline-complete:{}", pipeline_run_id), success);
+    let _ = app.emit("pipeline-complete", pipeline_run_id);
+}
+
+/// Wait for an agent run to leave the queued/pending/running state and return its final status
+async fn wait_for_agent_run(app: &AppHandle, pipeline_run_id: i64, run_id: i64) -> Result<String, String> {
+    loop {
+        tokio::time::sleep(tokio::time::Duration::from_millis(STEP_POLL_INTERVAL_MS)).await;
+
+        let db = app.state::<AgentDb>();
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+
+        let pipeline_status: String = conn
+            .query_row(
+                "SELECT status FROM agent_pipeline_runs WHERE id = ?1",
+                params![pipeline_run_id],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string())?;
+        if pipeline_status == "cancelled" {
+            return Ok("cancelled".to_string());
+        }
+
+        let status: String = conn
+            .query_row(
+                "SELECT status FROM agent_runs WHERE id = ?1",
+                params![run_id],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string())?;
+        if status != "pending" && status != "running" && status != "queued" {
+            return Ok(status);
+        }
+    }
+}
+
+/// Execute the remaining steps of a pipeline run, one after another
+async fn drive_pipeline_run(app: &AppHandle, pipeline_run_id: i64) -> Result<(), String> {
+    loop {
+        let run = {
+            let db = app.state::<AgentDb>();
+            let conn = db.0.lock().map_err(|e| e.to_string())?;
+            load_pipeline_run(&conn, pipeline_run_id)?
+        };
+
+        if run.status != "running" {
+            info!("Pipeline run {} is {}, stopping", pipeline_run_id, run.status);
+            return Ok(());
+        }
+
+        let step_index = run.current_step;
+        let Some(step) = run.steps.get(step_index) else {
+            info!("Pipeline run {} completed", pipeline_run_id);
+            finish_pipeline_run(app, pipeline_run_id, "completed", None);
+            return Ok(());
+        };
+
+        let previous_output = step_index
+            .checked_sub(1)
+            .and_then(|i| run.step_outputs.get(i))
+            .map(|s| s.as_str());
+        let task = render_step_task(&step.task, run.input.as_deref(), previous_output);
+
+        info!(
+            "Pipeline run {} starting step {}/{} with agent {}",
+            pipeline_run_id,
+            step_index + 1,
+            run.steps.len(),
+            step.agent_id
+        );
+        let agent_run_id = execute_agent(
+            app.clone(),
+            step.agent_id,
+            run.project_path.clone(),
+            task,
+            step.model.clone(),
+            None,
+            None,
+            app.state::<AgentDb>(),
+            app.state::<ProcessRegistryState>(),
+        )
+        .await?;
+
+        // Record which agent run belongs to this step (replacing a failed attempt)
+        let mut step_run_ids = run.step_run_ids.clone();
+        step_run_ids.truncate(step_index);
+        step_run_ids.push(agent_run_id);
+        {
+            let db = app.state::<AgentDb>();
+            let conn = db.0.lock().map_err(|e| e.to_string())?;
+            conn.execute(
+                "UPDATE agent_pipeline_runs SET step_run_ids = ?1 WHERE id = ?2",
+                params![serde_json::to_string(&step_run_ids).map_err(|e| e.to_string())?, pipeline_run_id],
+            )
+            .map_err(|e| e.to_string())?;
+            let _ = conn.execute(
+                "UPDATE agent_runs SET pipeline_run_id = ?1 WHERE id = ?2",
+                params![pipeline_run_id, agent_run_id],
+            );
+        }
+        let _ = app.emit(
+            &format!("pipeline-progress:{}", pipeline_run_id),
+            serde_json::json!({ "step": step_index, "run_id": agent_run_id }),
+        );
+
+        let status = wait_for_agent_run(app, pipeline_run_id, agent_run_id).await?;
+        if status == "cancelled" {
+            return Ok(());
+        }
+
+        let output = get_session_output(
+            app.state::<AgentDb>(),
+            app.state::<ProcessRegistryState>(),
+            agent_run_id,
+        )
+        .await
+        .unwrap_or_default();
+        let result = extract_run_result(&output);
+
+        let failure = match (&status[..], &result) {
+            ("completed", Some((_, false))) => None,
+            ("completed", Some((message, true))) => Some(format!(
+                "Step {} reported an error: {}",
+                step_index + 1,
+                message
+            )),
+            ("completed", None) => Some(format!("Step {} produced no output", step_index + 1)),
+            (status, _) => Some(format!("Step {} finished with status '{}'", step_index + 1, status)),
+        };
+        if let Some(failure) = failure {
+            warn!("Pipeline run {}: {}", pipeline_run_id, failure);
+            finish_pipeline_run(app, pipeline_run_id, "failed", Some(failure));
+            return Ok(());
+        }
+
+        let mut step_outputs = run.step_outputs.clone();
+        step_outputs.truncate(step_index);
+        step_outputs.push(result.map(|(text, _)| text).unwrap_or_default());
+        {
+            let db = app.state::<AgentDb>();
+            let conn = db.0.lock().map_err(|e| e.to_string())?;
+            conn.execute(
+                "UPDATE agent_pipeline_runs SET step_outputs = ?1, current_step = ?2 WHERE id = ?3",
+                params![
+                    serde_json::to_string(&step_outputs).map_err(|e| e.to_string())?,
+                    (step_index + 1) as i64,
+                    pipeline_run_id
+                ],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_step_task() {
+        assert_eq!(
+            render_step_task("Analyze {{input}}", Some("src/"), None),
+            "Analyze src/"
+        );
+        assert_eq!(
+            render_step_task("Implement: {{previous_output}}", None, Some("add a cache")),
+            "Implement: add a cache"
+        );
+        assert_eq!(
+            render_step_task("Write tests", None, Some("done")),
+            "Write tests\n\n## Output from the previous step\n\ndone"
+        );
+    }
+
+    #[test]
+    fn test_extract_run_result() {
+        let jsonl = r#"{"type":"system","subtype":"init","session_id":"abc"}
+{"type":"assistant","message":{"content":[{"type":"text","text":"Working on it"}]}}
+{"type":"result","subtype":"success","is_error":false,"result":"All done"}"#;
+        assert_eq!(extract_run_result(jsonl), Some(("All done".to_string(), false)));
+
+        let no_result = r#"{"type":"assistant","message":{"content":[{"type":"text","text":"first"}]}}
+{"type":"assistant","message":{"content":[{"type":"tool_use","name":"Read"},{"type":"text","text":"last"}]}}"#;
+        assert_eq!(extract_run_result(no_result), Some(("last".to_string(), false)));
+
+        assert_eq!(extract_run_result(""), None);
+    }
+}
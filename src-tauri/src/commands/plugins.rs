@@ -0,0 +1,437 @@
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::State;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::process::{Child, ChildStdin, ChildStdout};
+use tokio::sync::Mutex;
+
+/// Plugin protocol revision sent in the initialize request
+const PROTOCOL_VERSION: u32 = 1;
+
+/// How long a plugin may take to start and describe itself
+const INITIALIZE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long a command or panel request may take
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// A command a plugin adds to Claudia
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct PluginCommand {
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+/// A panel a plugin renders content for
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct PluginPanel {
+    pub id: String,
+    pub title: String,
+    /// Where the frontend shows the panel, e.g. "sidebar" or "project"
+    #[serde(default)]
+    pub location: Option<String>,
+}
+
+/// What a plugin answers to `initialize`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, specta::Type)]
+pub struct PluginManifest {
+    pub name: String,
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub commands: Vec<PluginCommand>,
+    #[serde(default)]
+    pub panels: Vec<PluginPanel>,
+}
+
+/// A discovered plugin; plugins that failed to start carry the error instead of a manifest
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct PluginInfo {
+    pub id: String,
+    pub path: String,
+    pub manifest: Option<PluginManifest>,
+    pub error: Option<String>,
+}
+
+/// A running plugin speaking newline-delimited JSON-RPC over stdio
+struct PluginProcess {
+    id: String,
+    _child: Child,
+    stdin: ChildStdin,
+    stdout: Lines<BufReader<ChildStdout>>,
+    next_request_id: u64,
+}
+
+impl PluginProcess {
+    fn spawn(id: &str, path: &Path) -> Result<Self, String> {
+        let mut child = tokio::process::Command::new(path)
+            .current_dir(path.parent().unwrap_or(Path::new(".")))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| format!("Failed to start plugin '{}': {}", id, e))?;
+        let stdin = child.stdin.take().ok_or("Failed to open plugin stdin")?;
+        let stdout = child.stdout.take().ok_or("Failed to open plugin stdout")?;
+
+        // Plugins log to stderr
+        if let Some(stderr) = child.stderr.take() {
+            let plugin_id = id.to_string();
+            tokio::spawn(async move {
+                let mut lines = BufReader::new(stderr).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    info!("[plugin {}] {}", plugin_id, line);
+                }
+            });
+        }
+
+        Ok(Self {
+            id: id.to_string(),
+            _child: child,
+            stdin,
+            stdout: BufReader::new(stdout).lines(),
+            next_request_id: 1,
+        })
+    }
+
+    async fn request(&mut self, method: &str, params: JsonValue) -> Result<JsonValue, String> {
+        let id = self.next_request_id;
+        self.next_request_id += 1;
+
+        let line = format!(
+            "{}\n",
+            json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })
+        );
+        self.stdin
+            .write_all(line.as_bytes())
+            .await
+            .map_err(|e| format!("Failed to write to plugin '{}': {}", self.id, e))?;
+        self.stdin
+            .flush()
+            .await
+            .map_err(|e| format!("Failed to write to plugin '{}': {}", self.id, e))?;
+
+        loop {
+            let message = self
+                .stdout
+                .next_line()
+                .await
+                .map_err(|e| format!("Failed to read from plugin '{}': {}", self.id, e))?
+                .ok_or_else(|| format!("Plugin '{}' exited", self.id))?;
+            let Ok(json) = serde_json::from_str::<JsonValue>(&message) else {
+                continue;
+            };
+            if json.get("method").is_some() {
+                if json["method"] == "log" {
+                    info!("[plugin {}] {}", self.id, json["params"]["message"]);
+                }
+                continue;
+            }
+            if json.get("id").and_then(|i| i.as_u64()) != Some(id) {
+                continue;
+            }
+            if let Some(error) = json.get("error") {
+                let message = error
+                    .get("message")
+                    .and_then(|m| m.as_str())
+                    .unwrap_or("unknown error");
+                return Err(format!("Plugin '{}' returned an error: {}", self.id, message));
+            }
+            return Ok(json.get("result").cloned().unwrap_or(JsonValue::Null));
+        }
+    }
+
+    async fn initialize(&mut self) -> Result<PluginManifest, String> {
+        let result = self
+            .request(
+                "initialize",
+                json!({
+                    "protocolVersion": PROTOCOL_VERSION,
+                    "claudiaVersion": env!("CARGO_PKG_VERSION"),
+                }),
+            )
+            .await?;
+        serde_json::from_value(result)
+            .map_err(|e| format!("Plugin '{}' sent an invalid manifest: {}", self.id, e))
+    }
+}
+
+struct LoadedPlugin {
+    info: PluginInfo,
+    process: Option<Arc<Mutex<PluginProcess>>>,
+}
+
+/// Plugins found at the last discovery, with their running processes
+#[derive(Default)]
+pub struct PluginHostState(Mutex<Option<Vec<LoadedPlugin>>>);
+
+fn plugins_dir() -> Result<PathBuf, String> {
+    Ok(dirs::home_dir()
+        .ok_or("Could not find home directory")?
+        .join(".claude")
+        .join("claudia-plugins"))
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .map(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(windows)]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+        && path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| ["exe", "cmd", "bat"].contains(&e.to_ascii_lowercase().as_str()))
+}
+
+/// Executables directly in the plugin directory, keyed by file stem
+fn discover_plugin_paths(dir: &Path) -> Vec<(String, PathBuf)> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            let hidden = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with('.'));
+            !hidden && is_executable(path)
+        })
+        .collect();
+    paths.sort();
+
+    let mut plugins: Vec<(String, PathBuf)> = Vec::new();
+    for path in paths {
+        let Some(id) = path.file_stem().and_then(|s| s.to_str()).map(str::to_string) else {
+            continue;
+        };
+        if plugins.iter().any(|(existing, _)| *existing == id) {
+            warn!("Ignoring plugin {:?}: another plugin is named '{}'", path, id);
+            continue;
+        }
+        plugins.push((id, path));
+    }
+    plugins
+}
+
+async fn load_plugin(id: String, path: PathBuf) -> LoadedPlugin {
+    let started = async {
+        let mut process = PluginProcess::spawn(&id, &path)?;
+        let manifest = tokio::time::timeout(INITIALIZE_TIMEOUT, process.initialize())
+            .await
+            .map_err(|_| format!("Plugin '{}' did not initialize in time", id))??;
+        Ok::<_, String>((process, manifest))
+    }
+    .await;
+
+    let path_str = path.to_string_lossy().to_string();
+    match started {
+        Ok((process, manifest)) => {
+            info!("Loaded plugin '{}' ({})", id, manifest.name);
+            LoadedPlugin {
+                info: PluginInfo {
+                    id,
+                    path: path_str,
+                    manifest: Some(manifest),
+                    error: None,
+                },
+                process: Some(Arc::new(Mutex::new(process))),
+            }
+        }
+        Err(e) => {
+            warn!("{}", e);
+            LoadedPlugin {
+                info: PluginInfo {
+                    id,
+                    path: path_str,
+                    manifest: None,
+                    error: Some(e),
+                },
+                process: None,
+            }
+        }
+    }
+}
+
+/// Discover and start every plugin, stopping those that were running
+async fn load_plugins() -> Result<Vec<LoadedPlugin>, String> {
+    let dir = plugins_dir()?;
+    let mut plugins = Vec::new();
+    for (id, path) in discover_plugin_paths(&dir) {
+        plugins.push(load_plugin(id, path).await);
+    }
+    Ok(plugins)
+}
+
+/// Send a request to a loaded plugin
+async fn call_plugin(
+    state: &PluginHostState,
+    plugin_id: &str,
+    method: &str,
+    params: JsonValue,
+) -> Result<JsonValue, String> {
+    let process = {
+        let mut plugins = state.0.lock().await;
+        if plugins.is_none() {
+            *plugins = Some(load_plugins().await?);
+        }
+        let plugin = plugins
+            .as_ref()
+            .and_then(|plugins| plugins.iter().find(|p| p.info.id == plugin_id))
+            .ok_or_else(|| format!("Plugin '{}' not found", plugin_id))?;
+        plugin.process.clone().ok_or_else(|| {
+            plugin
+                .info
+                .error
+                .clone()
+                .unwrap_or_else(|| format!("Plugin '{}' is not running", plugin_id))
+        })?
+    };
+
+    let mut process = process.lock().await;
+    tokio::time::timeout(REQUEST_TIMEOUT, process.request(method, params))
+        .await
+        .map_err(|_| format!("Plugin '{}' did not answer in time", plugin_id))?
+}
+
+/// List plugins in ~/.claude/claudia-plugins, starting them on first use
+#[tauri::command]
+#[specta::specta]
+pub async fn list_plugins(state: State<'_, PluginHostState>) -> Result<Vec<PluginInfo>, String> {
+    let mut plugins = state.0.lock().await;
+    if plugins.is_none() {
+        *plugins = Some(load_plugins().await?);
+    }
+    Ok(plugins
+        .iter()
+        .flatten()
+        .map(|plugin| plugin.info.clone())
+        .collect())
+}
+
+/// Stop all plugins and discover them again, e.g. after installing one
+#[tauri::command]
+#[specta::specta]
+pub async fn reload_plugins(state: State<'_, PluginHostState>) -> Result<Vec<PluginInfo>, String> {
+    let mut plugins = state.0.lock().await;
+    // Dropping the processes kills them
+    *plugins = None;
+    let loaded = load_plugins().await?;
+    let infos = loaded.iter().map(|plugin| plugin.info.clone()).collect();
+    *plugins = Some(loaded);
+    Ok(infos)
+}
+
+/// Run a command contributed by a plugin and return its result
+#[tauri::command]
+#[specta::specta]
+pub async fn execute_plugin_command(
+    state: State<'_, PluginHostState>,
+    plugin_id: String,
+    command_id: String,
+    params: Option<JsonValue>,
+) -> Result<JsonValue, String> {
+    call_plugin(
+        &state,
+        &plugin_id,
+        "commands/execute",
+        json!({ "command": command_id, "params": params.unwrap_or(JsonValue::Null) }),
+    )
+    .await
+}
+
+/// Ask a plugin for the content of one of its panels
+///
+/// `context` tells the plugin where the panel is shown, e.g. the open project.
+#[tauri::command]
+#[specta::specta]
+pub async fn render_plugin_panel(
+    state: State<'_, PluginHostState>,
+    plugin_id: String,
+    panel_id: String,
+    context: Option<JsonValue>,
+) -> Result<JsonValue, String> {
+    call_plugin(
+        &state,
+        &plugin_id,
+        "panels/render",
+        json!({ "panel": panel_id, "context": context.unwrap_or(JsonValue::Null) }),
+    )
+    .await
+}
+
+/// Get the directory plugins are installed in, creating it if needed
+#[tauri::command]
+#[specta::specta]
+pub async fn get_plugins_directory() -> Result<String, String> {
+    let dir = plugins_dir()?;
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create plugin directory: {}", e))?;
+    Ok(dir.to_string_lossy().to_string())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    fn write_script(dir: &Path, name: &str, body: &str, mode: u32) {
+        let path = dir.join(name);
+        std::fs::write(&path, body).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode)).unwrap();
+    }
+
+    #[test]
+    fn discovers_executables_only() {
+        let dir = tempfile::tempdir().unwrap();
+        write_script(dir.path(), "issues.sh", "#!/bin/sh\n", 0o755);
+        write_script(dir.path(), "notes.txt", "not a plugin", 0o644);
+        write_script(dir.path(), ".hidden", "#!/bin/sh\n", 0o755);
+
+        let plugins = discover_plugin_paths(dir.path());
+        assert_eq!(plugins.len(), 1);
+        assert_eq!(plugins[0].0, "issues");
+    }
+
+    #[tokio::test]
+    async fn talks_json_rpc_over_stdio() {
+        let dir = tempfile::tempdir().unwrap();
+        let script = r#"#!/bin/sh
+read line
+echo '{"jsonrpc":"2.0","method":"log","params":{"message":"starting"}}'
+echo '{"jsonrpc":"2.0","id":1,"result":{"name":"Echo","commands":[{"id":"hello","title":"Say hello"}]}}'
+read line
+echo '{"jsonrpc":"2.0","id":2,"result":{"greeting":"hi"}}'
+"#;
+        write_script(dir.path(), "echo", script, 0o755);
+
+        let plugin = load_plugin("echo".to_string(), dir.path().join("echo")).await;
+        let manifest = plugin.info.manifest.expect("plugin should initialize");
+        assert_eq!(manifest.name, "Echo");
+        assert_eq!(manifest.commands[0].id, "hello");
+
+        let process = plugin.process.unwrap();
+        let result = process
+            .lock()
+            .await
+            .request("commands/execute", json!({ "command": "hello" }))
+            .await
+            .unwrap();
+        assert_eq!(result["greeting"], "hi");
+    }
+}
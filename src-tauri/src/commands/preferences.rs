@@ -0,0 +1,162 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use serde_json::Value;
+use std::collections::HashMap;
+use tauri::State;
+
+use super::agents::AgentDb;
+
+/// Longest accepted preference key
+const MAX_KEY_LENGTH: usize = 200;
+
+/// Largest accepted serialized preference value
+const MAX_VALUE_BYTES: usize = 1024 * 1024;
+
+fn validate(key: &str, value: &Value) -> Result<String, String> {
+    if key.trim().is_empty() || key.len() > MAX_KEY_LENGTH {
+        return Err(format!(
+            "Preference keys must be 1-{} characters",
+            MAX_KEY_LENGTH
+        ));
+    }
+    let serialized = serde_json::to_string(value)
+        .map_err(|e| format!("Failed to serialize preference {}: {}", key, e))?;
+    if serialized.len() > MAX_VALUE_BYTES {
+        return Err(format!("Preference {} is larger than 1 MB", key));
+    }
+    Ok(serialized)
+}
+
+pub(crate) fn write_preference(conn: &Connection, key: &str, serialized: &str) -> rusqlite::Result<usize> {
+    conn.execute(
+        "INSERT INTO ui_preferences (key, value) VALUES (?1, ?2)
+         ON CONFLICT(key) DO UPDATE SET value = ?2, updated_at = CURRENT_TIMESTAMP",
+        params![key, serialized],
+    )
+}
+
+pub(crate) fn read_preferences(conn: &Connection, prefix: &str) -> Result<HashMap<String, Value>, String> {
+    let mut stmt = conn
+        .prepare("SELECT key, value FROM ui_preferences WHERE substr(key, 1, length(?1)) = ?1")
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![prefix], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })
+        .map_err(|e| e.to_string())?;
+    let mut preferences = HashMap::new();
+    for row in rows {
+        let (key, value) = row.map_err(|e| e.to_string())?;
+        match serde_json::from_str(&value) {
+            Ok(value) => {
+                preferences.insert(key, value);
+            }
+            Err(e) => log::warn!("Ignoring unreadable preference {}: {}", key, e),
+        }
+    }
+    Ok(preferences)
+}
+
+/// Get one UI preference, `None` when it was never set
+#[tauri::command]
+#[specta::specta]
+pub async fn get_preference(db: State<'_, AgentDb>, key: String) -> Result<Option<Value>, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let value: Option<String> = conn
+        .query_row(
+            "SELECT value FROM ui_preferences WHERE key = ?1",
+            params![key],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+    Ok(value.and_then(|v| serde_json::from_str(&v).ok()))
+}
+
+/// Get all UI preferences, optionally only those whose key starts with `prefix` (e.g. "layout.")
+#[tauri::command]
+#[specta::specta]
+pub async fn get_preferences(
+    db: State<'_, AgentDb>,
+    prefix: Option<String>,
+) -> Result<HashMap<String, Value>, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    read_preferences(&conn, prefix.as_deref().unwrap_or(""))
+}
+
+/// Set one UI preference
+#[tauri::command]
+#[specta::specta]
+pub async fn set_preference(
+    db: State<'_, AgentDb>,
+    key: String,
+    value: Value,
+) -> Result<(), String> {
+    let serialized = validate(&key, &value)?;
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    write_preference(&conn, &key, &serialized)
+        .map_err(|e| format!("Failed to save preference {}: {}", key, e))?;
+    Ok(())
+}
+
+/// Set several UI preferences at once, e.g. the whole window layout on close
+#[tauri::command]
+#[specta::specta]
+pub async fn set_preferences(
+    db: State<'_, AgentDb>,
+    preferences: HashMap<String, Value>,
+) -> Result<(), String> {
+    let serialized = preferences
+        .iter()
+        .map(|(key, value)| validate(key, value).map(|s| (key, s)))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut conn = db.0.lock().map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    for (key, value) in &serialized {
+        write_preference(&tx, key, value)
+            .map_err(|e| format!("Failed to save preference {}: {}", key, e))?;
+    }
+    tx.commit().map_err(|e| e.to_string())
+}
+
+/// Remove a UI preference so the frontend falls back to its default
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_preference(db: State<'_, AgentDb>, key: String) -> Result<(), String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM ui_preferences WHERE key = ?1", params![key])
+        .map_err(|e| format!("Failed to delete preference {}: {}", key, e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn prefix_lookup_returns_matching_keys() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute(
+            "CREATE TABLE ui_preferences (key TEXT PRIMARY KEY, value TEXT NOT NULL, updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP)",
+            [],
+        )
+        .unwrap();
+        for (key, value) in [
+            ("layout.sidebar_width", json!(280)),
+            ("layout.tabs", json!(["agents", "usage"])),
+            ("last_project", json!("/repo")),
+            // LIKE would treat % and _ as wildcards
+            ("layout_legacy", json!(true)),
+        ] {
+            let serialized = validate(key, &value).unwrap();
+            write_preference(&conn, key, &serialized).unwrap();
+        }
+        write_preference(&conn, "layout.sidebar_width", "320").unwrap();
+
+        let layout = read_preferences(&conn, "layout.").unwrap();
+        assert_eq!(layout.len(), 2);
+        assert_eq!(layout["layout.sidebar_width"], json!(320));
+        assert_eq!(read_preferences(&conn, "").unwrap().len(), 4);
+        assert!(validate("", &json!(1)).is_err());
+    }
+}
@@ -0,0 +1,192 @@
+use chrono::Utc;
+use log::{info, warn};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::State;
+
+use super::agents::{get_setting, set_setting, AgentDb};
+use super::live_metrics::{AgentLiveMetrics, LiveCostState};
+use crate::process::priority::{lower_priority, BackgroundPriority, MAX_NICENESS};
+use crate::process::terminate::{grace_period, set_grace_period};
+use crate::process::{ProcessInfo, ProcessRegistryState, ProcessType};
+
+/// Longest grace period accepted, so a stop request cannot hang for minutes
+const MAX_KILL_GRACE_MS: u64 = 60_000;
+
+/// A Claude or agent process started by Claudia
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct RunningProcess {
+    pub run_id: i64,
+    pub pid: u32,
+    /// "agent" or "session"
+    pub kind: String,
+    pub agent_id: Option<i64>,
+    pub agent_name: Option<String>,
+    pub session_id: Option<String>,
+    pub project_path: String,
+    pub task: String,
+    pub model: String,
+    pub started_at: String,
+    pub elapsed_seconds: i64,
+    /// Tokens streamed so far, when the run's output is being tracked
+    pub total_tokens: Option<u64>,
+    pub estimated_cost_usd: Option<f64>,
+}
+
+fn to_running_process(info: ProcessInfo, metrics: Option<AgentLiveMetrics>) -> RunningProcess {
+    let (kind, agent_id, agent_name, session_id) = match info.process_type {
+        ProcessType::AgentRun {
+            agent_id,
+            agent_name,
+        } => ("agent", Some(agent_id), Some(agent_name), None),
+        ProcessType::ClaudeSession { session_id } => ("session", None, None, Some(session_id)),
+    };
+    RunningProcess {
+        run_id: info.run_id,
+        pid: info.pid,
+        kind: kind.to_string(),
+        agent_id,
+        agent_name,
+        session_id,
+        project_path: info.project_path,
+        task: info.task,
+        model: info.model,
+        started_at: info.started_at.to_rfc3339(),
+        elapsed_seconds: (Utc::now() - info.started_at).num_seconds().max(0),
+        total_tokens: metrics.as_ref().map(|m| m.total_tokens),
+        estimated_cost_usd: metrics.map(|m| m.estimated_cost_usd),
+    }
+}
+
+/// List every running Claude session and agent run, oldest first
+#[tauri::command]
+#[specta::specta]
+pub async fn list_running_processes(
+    registry: State<'_, ProcessRegistryState>,
+    live_cost: State<'_, LiveCostState>,
+) -> Result<Vec<RunningProcess>, String> {
+    let mut processes: Vec<RunningProcess> = registry
+        .0
+        .get_running_processes()?
+        .into_iter()
+        .map(|info| {
+            let metrics = live_cost.metrics(info.run_id);
+            to_running_process(info, metrics)
+        })
+        .collect();
+    processes.sort_by_key(|p| std::cmp::Reverse(p.elapsed_seconds));
+    Ok(processes)
+}
+
+/// Apply the saved kill grace period at startup
+pub fn init_kill_grace_period(conn: &Connection) {
+    if let Some(ms) = get_setting::<u64>(conn, "process_kill_grace_ms") {
+        set_grace_period(Duration::from_millis(ms.min(MAX_KILL_GRACE_MS)));
+    }
+}
+
+/// Milliseconds a stopped process gets to exit after the interrupt before it is killed
+#[tauri::command]
+#[specta::specta]
+pub async fn get_kill_grace_period() -> Result<u64, String> {
+    Ok(grace_period().as_millis() as u64)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn set_kill_grace_period(db: State<'_, AgentDb>, ms: u64) -> Result<(), String> {
+    if ms > MAX_KILL_GRACE_MS {
+        return Err(format!(
+            "Grace period must be at most {} ms",
+            MAX_KILL_GRACE_MS
+        ));
+    }
+    {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        set_setting(&conn, "process_kill_grace_ms", &ms)
+            .map_err(|e| format!("Failed to save grace period: {}", e))?;
+    }
+    set_grace_period(Duration::from_millis(ms));
+    Ok(())
+}
+
+fn load_background_priority(conn: &Connection) -> BackgroundPriority {
+    get_setting(conn, "background_run_priority").unwrap_or_default()
+}
+
+/// Lower the priority of a freshly spawned agent process if its run is unattended
+///
+/// Runs started by a schedule count as unattended, including their queued and retried attempts.
+pub fn apply_background_priority(conn: &Connection, run_id: i64, pid: u32) {
+    let priority = load_background_priority(conn);
+    if !priority.enabled || pid == 0 {
+        return;
+    }
+    let scheduled = conn
+        .query_row(
+            "SELECT schedule_id IS NOT NULL FROM agent_runs WHERE id = ?1",
+            params![run_id],
+            |row| row.get::<_, bool>(0),
+        )
+        .unwrap_or(false);
+    if !scheduled {
+        return;
+    }
+    match lower_priority(pid, &priority) {
+        Ok(()) => info!(
+            "Running scheduled agent run {} at background priority",
+            run_id
+        ),
+        Err(e) => warn!("{}", e),
+    }
+}
+
+/// Priority used for scheduled agent runs
+#[tauri::command]
+#[specta::specta]
+pub async fn get_background_priority(db: State<'_, AgentDb>) -> Result<BackgroundPriority, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    Ok(load_background_priority(&conn))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn set_background_priority(
+    db: State<'_, AgentDb>,
+    priority: BackgroundPriority,
+) -> Result<(), String> {
+    if !(1..=MAX_NICENESS).contains(&priority.niceness) {
+        return Err(format!("Niceness must be between 1 and {}", MAX_NICENESS));
+    }
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    set_setting(&conn, "background_run_priority", &priority)
+        .map_err(|e| format!("Failed to save background priority: {}", e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn untracked_processes_have_no_live_totals() {
+        let info = ProcessInfo {
+            run_id: 7,
+            process_type: ProcessType::AgentRun {
+                agent_id: 3,
+                agent_name: "Reviewer".to_string(),
+            },
+            pid: 4242,
+            started_at: Utc::now() - chrono::Duration::seconds(90),
+            project_path: "/repo".to_string(),
+            task: "Review the diff".to_string(),
+            model: "sonnet".to_string(),
+        };
+        let process = to_running_process(info, None);
+        assert_eq!(process.kind, "agent");
+        assert_eq!(process.agent_name.as_deref(), Some("Reviewer"));
+        assert!(process.elapsed_seconds >= 90);
+        assert!(process.total_tokens.is_none());
+    }
+}
@@ -0,0 +1,181 @@
+use rusqlite::{params, Connection, Row};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+
+use super::agents::AgentDb;
+
+/// A reusable system prompt for ad-hoc sessions
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct PromptPreset {
+    pub id: Option<i64>,
+    pub name: String,
+    pub content: String,
+    /// "append" adds to Claude's default system prompt, "replace" swaps it out
+    pub mode: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+const PRESET_COLUMNS: &str = "id, name, content, mode, created_at, updated_at";
+
+fn row_to_preset(row: &Row) -> rusqlite::Result<PromptPreset> {
+    Ok(PromptPreset {
+        id: Some(row.get(0)?),
+        name: row.get(1)?,
+        content: row.get(2)?,
+        mode: row.get(3)?,
+        created_at: row.get(4)?,
+        updated_at: row.get(5)?,
+    })
+}
+
+fn validate(name: &str, content: &str, mode: &str) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("Preset name cannot be empty".to_string());
+    }
+    if content.trim().is_empty() {
+        return Err("Preset content cannot be empty".to_string());
+    }
+    if mode != "append" && mode != "replace" {
+        return Err(format!(
+            "Unknown preset mode '{}', expected append or replace",
+            mode
+        ));
+    }
+    Ok(())
+}
+
+fn load_preset(conn: &Connection, id: i64) -> Result<PromptPreset, String> {
+    conn.query_row(
+        &format!(
+            "SELECT {} FROM prompt_presets WHERE id = ?1",
+            PRESET_COLUMNS
+        ),
+        params![id],
+        row_to_preset,
+    )
+    .map_err(|e| format!("Prompt preset {} not found: {}", id, e))
+}
+
+/// CLI arguments that attach a preset to a session
+pub fn preset_args(preset: &PromptPreset) -> Vec<String> {
+    let flag = if preset.mode == "replace" {
+        "--system-prompt"
+    } else {
+        "--append-system-prompt"
+    };
+    vec![flag.to_string(), preset.content.clone()]
+}
+
+/// Arguments for an optional preset, looked up by ID
+pub fn preset_args_for(app: &AppHandle, preset_id: Option<i64>) -> Result<Vec<String>, String> {
+    let Some(id) = preset_id else {
+        return Ok(Vec::new());
+    };
+    let db = app.state::<AgentDb>();
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let preset = load_preset(&conn, id)?;
+    log::info!(
+        "Attaching prompt preset '{}' ({})",
+        preset.name,
+        preset.mode
+    );
+    Ok(preset_args(&preset))
+}
+
+/// List all prompt presets by name
+#[tauri::command]
+#[specta::specta]
+pub async fn list_prompt_presets(db: State<'_, AgentDb>) -> Result<Vec<PromptPreset>, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM prompt_presets ORDER BY name COLLATE NOCASE",
+            PRESET_COLUMNS
+        ))
+        .map_err(|e| e.to_string())?;
+    let presets = stmt
+        .query_map([], row_to_preset)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(presets)
+}
+
+/// Create a prompt preset
+#[tauri::command]
+#[specta::specta]
+pub async fn create_prompt_preset(
+    db: State<'_, AgentDb>,
+    name: String,
+    content: String,
+    mode: Option<String>,
+) -> Result<PromptPreset, String> {
+    let mode = mode.unwrap_or_else(|| "append".to_string());
+    validate(&name, &content, &mode)?;
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO prompt_presets (name, content, mode) VALUES (?1, ?2, ?3)",
+        params![name.trim(), content, mode],
+    )
+    .map_err(|e| format!("Failed to create prompt preset: {}", e))?;
+    load_preset(&conn, conn.last_insert_rowid())
+}
+
+/// Update a prompt preset
+#[tauri::command]
+#[specta::specta]
+pub async fn update_prompt_preset(
+    db: State<'_, AgentDb>,
+    id: i64,
+    name: String,
+    content: String,
+    mode: String,
+) -> Result<PromptPreset, String> {
+    validate(&name, &content, &mode)?;
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let updated = conn
+        .execute(
+            "UPDATE prompt_presets SET name = ?1, content = ?2, mode = ?3, updated_at = CURRENT_TIMESTAMP WHERE id = ?4",
+            params![name.trim(), content, mode, id],
+        )
+        .map_err(|e| format!("Failed to update prompt preset: {}", e))?;
+    if updated == 0 {
+        return Err(format!("Prompt preset {} not found", id));
+    }
+    load_preset(&conn, id)
+}
+
+/// Delete a prompt preset
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_prompt_preset(db: State<'_, AgentDb>, id: i64) -> Result<(), String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM prompt_presets WHERE id = ?1", params![id])
+        .map_err(|e| format!("Failed to delete prompt preset: {}", e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mode_selects_the_system_prompt_flag() {
+        let mut preset = PromptPreset {
+            id: Some(1),
+            name: "Reviewer".to_string(),
+            content: "Review strictly.".to_string(),
+            mode: "append".to_string(),
+            created_at: String::new(),
+            updated_at: String::new(),
+        };
+        assert_eq!(
+            preset_args(&preset),
+            vec!["--append-system-prompt", "Review strictly."]
+        );
+        preset.mode = "replace".to_string();
+        assert_eq!(preset_args(&preset)[0], "--system-prompt");
+        assert!(validate("Reviewer", "text", "prepend").is_err());
+    }
+}
@@ -0,0 +1,168 @@
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::sync::RwLock;
+use tauri::State;
+
+use super::agents::{get_setting, set_setting, AgentDb};
+
+/// Proxy settings applied to every subprocess and HTTP request
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, specta::Type)]
+pub struct ProxySettings {
+    pub http_proxy: Option<String>,
+    pub https_proxy: Option<String>,
+    /// Comma-separated hosts that bypass the proxy
+    pub no_proxy: Option<String>,
+}
+
+/// Settings in effect, kept in memory because commands are built without database access
+static CURRENT: RwLock<Option<ProxySettings>> = RwLock::new(None);
+
+fn current() -> ProxySettings {
+    CURRENT
+        .read()
+        .ok()
+        .and_then(|settings| settings.clone())
+        .unwrap_or_default()
+}
+
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value.as_deref().map(str::trim).filter(|v| !v.is_empty())
+}
+
+fn load_settings(conn: &Connection) -> ProxySettings {
+    get_setting(conn, "proxy_settings").unwrap_or_default()
+}
+
+/// Load the saved proxy settings so later spawns and requests use them
+///
+/// Also called after something other than `set_proxy_settings` changed them, e.g. an import.
+pub fn init_proxy_settings(conn: &Connection) {
+    let settings = load_settings(conn);
+    if settings != ProxySettings::default() {
+        log::info!("Using configured proxy settings");
+    }
+    if let Ok(mut current) = CURRENT.write() {
+        *current = Some(settings);
+    }
+}
+
+/// Proxy variables in both spellings, since tools disagree on which one they read
+pub fn proxy_env() -> Vec<(String, String)> {
+    settings_env(&current())
+}
+
+fn settings_env(settings: &ProxySettings) -> Vec<(String, String)> {
+    let mut env = Vec::new();
+    for (name, value) in [
+        ("HTTP_PROXY", &settings.http_proxy),
+        ("HTTPS_PROXY", &settings.https_proxy),
+        ("NO_PROXY", &settings.no_proxy),
+    ] {
+        if let Some(value) = non_empty(value) {
+            env.push((name.to_string(), value.to_string()));
+            env.push((name.to_lowercase(), value.to_string()));
+        }
+    }
+    env
+}
+
+fn no_proxy(settings: &ProxySettings) -> Option<reqwest::NoProxy> {
+    non_empty(&settings.no_proxy).and_then(reqwest::NoProxy::from_string)
+}
+
+/// Apply the configured proxies to an HTTP client
+pub fn apply_proxy(builder: reqwest::ClientBuilder) -> reqwest::ClientBuilder {
+    apply_settings(builder, &current())
+}
+
+fn apply_settings(
+    mut builder: reqwest::ClientBuilder,
+    settings: &ProxySettings,
+) -> reqwest::ClientBuilder {
+    if let Some(url) = non_empty(&settings.http_proxy) {
+        match reqwest::Proxy::http(url) {
+            Ok(proxy) => builder = builder.proxy(proxy.no_proxy(no_proxy(settings))),
+            Err(e) => log::warn!("Ignoring invalid HTTP proxy {}: {}", url, e),
+        }
+    }
+    if let Some(url) = non_empty(&settings.https_proxy) {
+        match reqwest::Proxy::https(url) {
+            Ok(proxy) => builder = builder.proxy(proxy.no_proxy(no_proxy(settings))),
+            Err(e) => log::warn!("Ignoring invalid HTTPS proxy {}: {}", url, e),
+        }
+    }
+    builder
+}
+
+/// The configured HTTPS proxy, for clients that take a single proxy URL
+pub fn https_proxy() -> Option<String> {
+    non_empty(&current().https_proxy).map(str::to_string)
+}
+
+/// An HTTP client that goes through the configured proxies
+pub fn http_client() -> reqwest::Client {
+    apply_proxy(reqwest::Client::builder())
+        .build()
+        .unwrap_or_else(|e| {
+            log::error!("Failed to build proxied HTTP client: {}", e);
+            reqwest::Client::new()
+        })
+}
+
+/// Get the proxy settings
+#[tauri::command]
+#[specta::specta]
+pub async fn get_proxy_settings(db: State<'_, AgentDb>) -> Result<ProxySettings, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    Ok(load_settings(&conn))
+}
+
+/// Save the proxy settings and apply them to subsequent processes and requests
+#[tauri::command]
+#[specta::specta]
+pub async fn set_proxy_settings(
+    db: State<'_, AgentDb>,
+    settings: ProxySettings,
+) -> Result<(), String> {
+    for (name, value) in [
+        ("HTTP", &settings.http_proxy),
+        ("HTTPS", &settings.https_proxy),
+    ] {
+        if let Some(url) = non_empty(value) {
+            reqwest::Proxy::all(url)
+                .map_err(|e| format!("Invalid {} proxy '{}': {}", name, url, e))?;
+        }
+    }
+    {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        set_setting(&conn, "proxy_settings", &settings)
+            .map_err(|e| format!("Failed to save proxy settings: {}", e))?;
+    }
+    if let Ok(mut current) = CURRENT.write() {
+        *current = Some(settings);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exports_both_spellings_and_skips_blank_values() {
+        let settings = ProxySettings {
+            http_proxy: Some("http://proxy.corp:3128".to_string()),
+            https_proxy: Some("  ".to_string()),
+            no_proxy: Some("localhost,.corp".to_string()),
+        };
+        let env = settings_env(&settings);
+        assert_eq!(env.len(), 4);
+        assert!(env.contains(&(
+            "http_proxy".to_string(),
+            "http://proxy.corp:3128".to_string()
+        )));
+        assert!(env.contains(&("NO_PROXY".to_string(), "localhost,.corp".to_string())));
+        let client = apply_settings(reqwest::Client::builder(), &settings).build();
+        assert!(client.is_ok());
+    }
+}
@@ -0,0 +1,127 @@
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use chrono::Utc;
+use tauri::{AppHandle, Emitter, State};
+
+use super::agents::{get_agent, AgentDb};
+use super::claude::create_system_command;
+use crate::claude_binary::find_claude_binary;
+use crate::process::pty::{command_builder, PtyProcessInfo, PtyRegistryState};
+
+/// Arguments for an interactive Claude session, optionally running as an agent
+fn interactive_args(
+    model: Option<String>,
+    system_prompt: Option<String>,
+    prompt: Option<String>,
+) -> Vec<String> {
+    let mut args = Vec::new();
+    if let Some(model) = model.filter(|m| !m.is_empty()) {
+        args.extend(["--model".to_string(), model]);
+    }
+    if let Some(system_prompt) = system_prompt.filter(|p| !p.is_empty()) {
+        args.extend(["--system-prompt".to_string(), system_prompt]);
+    }
+    if let Some(prompt) = prompt.filter(|p| !p.is_empty()) {
+        args.push(prompt);
+    }
+    args
+}
+
+/// Start Claude in a pseudo-terminal for the embedded terminal view
+///
+/// Raw output is emitted base64-encoded as `pty-output:{id}`, since chunks can split
+/// multi-byte characters, and the exit code follows as `pty-exit:{id}`.
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+#[specta::specta]
+pub async fn start_pty_session(
+    app: AppHandle,
+    db: State<'_, AgentDb>,
+    pty: State<'_, PtyRegistryState>,
+    project_path: String,
+    prompt: Option<String>,
+    model: Option<String>,
+    agent_id: Option<i64>,
+    rows: u16,
+    cols: u16,
+) -> Result<PtyProcessInfo, String> {
+    let claude_path = find_claude_binary(&app)?;
+    if claude_path == "claude-code" {
+        return Err("Terminal mode requires a system installation of Claude Code".to_string());
+    }
+
+    let (model, system_prompt) = match agent_id {
+        Some(agent_id) => {
+            let agent = get_agent(db, agent_id).await?;
+            (model.or(Some(agent.model)), Some(agent.system_prompt))
+        }
+        None => (model, None),
+    };
+    let args = interactive_args(model, system_prompt, prompt);
+    let cmd = create_system_command(&app, &claude_path, args, &project_path);
+
+    let id = uuid::Uuid::new_v4().to_string();
+    log::info!("Starting PTY session {} in {}", id, project_path);
+    let info = PtyProcessInfo {
+        id: id.clone(),
+        pid: None,
+        project_path,
+        agent_id,
+        started_at: Utc::now(),
+        rows,
+        cols,
+    };
+
+    let output_app = app.clone();
+    let output_event = format!("pty-output:{}", id);
+    let exit_event = format!("pty-exit:{}", id);
+    pty.0.spawn(
+        info,
+        command_builder(cmd.as_std()),
+        move |chunk| {
+            let _ = output_app.emit(&output_event, STANDARD.encode(chunk));
+        },
+        move |code| {
+            let _ = app.emit(&exit_event, code);
+        },
+    )
+}
+
+/// Send keyboard input to a PTY session
+#[tauri::command]
+#[specta::specta]
+pub async fn write_pty_session(
+    pty: State<'_, PtyRegistryState>,
+    id: String,
+    data: String,
+) -> Result<(), String> {
+    pty.0.write(&id, data.as_bytes())
+}
+
+/// Tell a PTY session its terminal was resized
+#[tauri::command]
+#[specta::specta]
+pub async fn resize_pty_session(
+    pty: State<'_, PtyRegistryState>,
+    id: String,
+    rows: u16,
+    cols: u16,
+) -> Result<(), String> {
+    pty.0.resize(&id, rows, cols)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn kill_pty_session(pty: State<'_, PtyRegistryState>, id: String) -> Result<(), String> {
+    let registry = pty.0.clone();
+    tokio::task::spawn_blocking(move || registry.kill(&id))
+        .await
+        .map_err(|e| format!("Failed to stop PTY session: {}", e))?
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn list_pty_sessions(
+    pty: State<'_, PtyRegistryState>,
+) -> Result<Vec<PtyProcessInfo>, String> {
+    pty.0.list()
+}
@@ -0,0 +1,237 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::process::ProcessRegistryState;
+
+/// How often running processes are sampled and `run-resources` is emitted
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Processes listed individually in a sample, busiest first
+const TOP_PROCESSES: usize = 5;
+
+/// One process of a run's process tree
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, specta::Type)]
+pub struct ProcessSample {
+    pub pid: u32,
+    pub parent_pid: Option<u32>,
+    pub name: String,
+    pub cpu_percent: f32,
+    pub memory_bytes: u64,
+}
+
+/// CPU and memory of a run, summed over Claude and everything it started
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct RunResources {
+    pub run_id: i64,
+    pub pid: u32,
+    pub cpu_percent: f32,
+    pub memory_bytes: u64,
+    pub process_count: usize,
+    pub top_processes: Vec<ProcessSample>,
+    pub sampled_at: String,
+}
+
+/// Samples process trees and keeps the latest sample of every running run
+pub struct ResourceMonitor {
+    system: Mutex<System>,
+    latest: Mutex<HashMap<i64, RunResources>>,
+}
+
+#[derive(Clone)]
+pub struct ResourceMonitorState(pub Arc<ResourceMonitor>);
+
+impl Default for ResourceMonitorState {
+    fn default() -> Self {
+        Self(Arc::new(ResourceMonitor {
+            system: Mutex::new(System::new()),
+            latest: Mutex::new(HashMap::new()),
+        }))
+    }
+}
+
+/// The root process and all of its descendants
+fn process_tree(samples: &[ProcessSample], root: u32) -> Vec<ProcessSample> {
+    let mut in_tree = HashSet::from([root]);
+    let mut tree: Vec<ProcessSample> = samples.iter().filter(|s| s.pid == root).cloned().collect();
+    // Sweep until no new children are found, since parents may be listed after children
+    loop {
+        let before = tree.len();
+        for sample in samples {
+            if !in_tree.contains(&sample.pid)
+                && sample.parent_pid.is_some_and(|p| in_tree.contains(&p))
+            {
+                in_tree.insert(sample.pid);
+                tree.push(sample.clone());
+            }
+        }
+        if tree.len() == before {
+            return tree;
+        }
+    }
+}
+
+fn summarize(run_id: i64, root: u32, samples: &[ProcessSample]) -> RunResources {
+    let mut tree = process_tree(samples, root);
+    let cpu_percent = tree.iter().map(|s| s.cpu_percent).sum();
+    let memory_bytes = tree.iter().map(|s| s.memory_bytes).sum();
+    let process_count = tree.len();
+    tree.sort_by(|a, b| {
+        b.cpu_percent
+            .total_cmp(&a.cpu_percent)
+            .then(b.memory_bytes.cmp(&a.memory_bytes))
+    });
+    tree.truncate(TOP_PROCESSES);
+    RunResources {
+        run_id,
+        pid: root,
+        cpu_percent,
+        memory_bytes,
+        process_count,
+        top_processes: tree,
+        sampled_at: chrono::Utc::now().to_rfc3339(),
+    }
+}
+
+impl ResourceMonitor {
+    fn snapshot(&self) -> Result<Vec<ProcessSample>, String> {
+        let mut system = self.system.lock().map_err(|e| e.to_string())?;
+        system.refresh_processes_specifics(
+            ProcessesToUpdate::All,
+            true,
+            ProcessRefreshKind::nothing().with_cpu().with_memory(),
+        );
+        Ok(system
+            .processes()
+            .iter()
+            .map(|(pid, process)| ProcessSample {
+                pid: pid.as_u32(),
+                parent_pid: process.parent().map(Pid::as_u32),
+                name: process.name().to_string_lossy().to_string(),
+                cpu_percent: process.cpu_usage(),
+                memory_bytes: process.memory(),
+            })
+            .collect())
+    }
+
+    /// Sample every run in the registry, replacing the stored samples
+    pub fn sample(&self, runs: &[(i64, u32)]) -> Result<Vec<RunResources>, String> {
+        let samples = self.snapshot()?;
+        let resources: Vec<RunResources> = runs
+            .iter()
+            .map(|(run_id, pid)| summarize(*run_id, *pid, &samples))
+            .filter(|r| r.process_count > 0)
+            .collect();
+        let mut latest = self.latest.lock().map_err(|e| e.to_string())?;
+        *latest = resources.iter().map(|r| (r.run_id, r.clone())).collect();
+        Ok(resources)
+    }
+
+    pub fn latest(&self, run_id: i64) -> Option<RunResources> {
+        self.latest.lock().ok()?.get(&run_id).cloned()
+    }
+}
+
+fn running_pids(registry: &ProcessRegistryState) -> Result<Vec<(i64, u32)>, String> {
+    Ok(registry
+        .0
+        .get_running_processes()?
+        .into_iter()
+        .map(|info| (info.run_id, info.pid))
+        .collect())
+}
+
+/// Sample running processes periodically and emit `run-resources:{run_id}` and `run-resources`
+pub fn start_resource_monitor(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(SAMPLE_INTERVAL);
+        loop {
+            interval.tick().await;
+            let runs = match running_pids(&app.state::<ProcessRegistryState>()) {
+                Ok(runs) => runs,
+                Err(e) => {
+                    log::error!("Failed to list processes for resource sampling: {}", e);
+                    continue;
+                }
+            };
+            let monitor = app.state::<ResourceMonitorState>().0.clone();
+            match tokio::task::spawn_blocking(move || monitor.sample(&runs)).await {
+                Ok(Ok(resources)) => {
+                    for run in &resources {
+                        let _ = app.emit(&format!("run-resources:{}", run.run_id), run);
+                    }
+                    if !resources.is_empty() {
+                        let _ = app.emit("run-resources", &resources);
+                    }
+                }
+                Ok(Err(e)) => log::error!("Resource sampling failed: {}", e),
+                Err(e) => log::error!("Resource sampling panicked: {}", e),
+            }
+        }
+    });
+}
+
+/// CPU and memory of a run; returns None once the run is no longer running
+///
+/// CPU usage is measured between samples, so a run that was never sampled before is
+/// sampled twice a short moment apart.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_run_resources(
+    registry: State<'_, ProcessRegistryState>,
+    monitor: State<'_, ResourceMonitorState>,
+    run_id: i64,
+) -> Result<Option<RunResources>, String> {
+    if let Some(resources) = monitor.0.latest(run_id) {
+        return Ok(Some(resources));
+    }
+    let runs = running_pids(&registry)?;
+    if !runs.iter().any(|(id, _)| *id == run_id) {
+        return Ok(None);
+    }
+    let monitor = monitor.0.clone();
+    tokio::task::spawn_blocking(move || {
+        monitor.sample(&runs)?;
+        std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+        monitor.sample(&runs)?;
+        Ok(monitor.latest(run_id))
+    })
+    .await
+    .map_err(|e| format!("Resource sampling failed: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(pid: u32, parent: Option<u32>, cpu: f32, memory: u64) -> ProcessSample {
+        ProcessSample {
+            pid,
+            parent_pid: parent,
+            name: format!("p{}", pid),
+            cpu_percent: cpu,
+            memory_bytes: memory,
+        }
+    }
+
+    #[test]
+    fn sums_the_whole_process_tree() {
+        // A grandchild listed before its parent, and an unrelated process
+        let samples = vec![
+            sample(30, Some(20), 95.0, 300),
+            sample(10, Some(1), 2.0, 100),
+            sample(20, Some(10), 1.0, 50),
+            sample(40, Some(1), 50.0, 1000),
+        ];
+        let run = summarize(7, 10, &samples);
+        assert_eq!(run.process_count, 3);
+        assert_eq!(run.cpu_percent, 98.0);
+        assert_eq!(run.memory_bytes, 450);
+        assert_eq!(run.top_processes[0].pid, 30);
+
+        assert_eq!(summarize(7, 99, &samples).process_count, 0);
+    }
+}
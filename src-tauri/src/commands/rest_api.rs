@@ -0,0 +1,444 @@
+use axum::extract::{Path as UrlPath, Query, Request, State as ServerState};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use log::{error, info};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::path::{Component, Path};
+use std::sync::{Arc, Mutex};
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Manager, State};
+use tokio::sync::oneshot;
+
+use super::agents::{get_agent_run, get_setting, list_agent_runs, set_setting, AgentDb};
+use super::claude::{get_project_sessions, list_projects, load_session_history};
+use super::claudia_mcp::{bearer_token_matches, constant_time_eq};
+use super::schedule_calendar::{render_schedule_calendar, DEFAULT_CALENDAR_DAYS};
+use super::usage::{get_usage_by_project, get_usage_stats};
+
+/// Port used when the user hasn't picked one
+const DEFAULT_PORT: u16 = 8788;
+
+/// Settings of the REST API server, stored under `rest_api_server` in app_settings
+#[derive(Debug, Serialize, Deserialize, Clone, specta::Type)]
+pub struct RestApiSettings {
+    pub enabled: bool,
+    pub port: u16,
+    /// Bearer token clients must send; generated the first time the server is enabled
+    #[serde(default)]
+    pub token: String,
+}
+
+impl Default for RestApiSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: DEFAULT_PORT,
+            token: String::new(),
+        }
+    }
+}
+
+/// Settings plus whether the server is currently listening
+#[derive(Debug, Serialize, Deserialize, specta::Type)]
+pub struct RestApiStatus {
+    pub settings: RestApiSettings,
+    pub running: bool,
+    pub url: String,
+}
+
+struct RunningServer {
+    port: u16,
+    shutdown: oneshot::Sender<()>,
+    /// Finishes once the listener is closed and the port is free again
+    task: JoinHandle<()>,
+}
+
+/// Handle of the running REST API server, if any
+#[derive(Default)]
+pub struct RestApiState(Mutex<Option<RunningServer>>);
+
+#[derive(Clone)]
+struct ApiContext {
+    app: AppHandle,
+    token: Arc<str>,
+}
+
+fn load_api_settings(conn: &Connection) -> RestApiSettings {
+    get_setting(conn, "rest_api_server").unwrap_or_default()
+}
+
+fn api_status(settings: RestApiSettings, state: &RestApiState) -> RestApiStatus {
+    let running = state
+        .0
+        .lock()
+        .map(|server| server.as_ref().is_some_and(|s| s.port == settings.port))
+        .unwrap_or(false);
+    let url = format!("http://127.0.0.1:{}/api", settings.port);
+    RestApiStatus {
+        settings,
+        running,
+        url,
+    }
+}
+
+fn is_authorized(headers: &HeaderMap, token: &str) -> bool {
+    let authorization = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok());
+    bearer_token_matches(authorization, token)
+}
+
+async fn require_token(
+    ServerState(context): ServerState<ApiContext>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if !is_authorized(request.headers(), &context.token) {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(json!({ "error": "Missing or invalid bearer token" })),
+        )
+            .into_response();
+    }
+    next.run(request).await
+}
+
+/// Turn a command result into JSON, with errors as `{"error": ...}`
+fn respond<T: Serialize>(result: Result<T, String>) -> Response {
+    match result {
+        Ok(value) => Json(value).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({ "error": e })),
+        )
+            .into_response(),
+    }
+}
+
+/// Whether a URL parameter names one entry of a directory, so it can't reach outside it
+fn is_single_path_component(value: &str) -> bool {
+    let mut components = Path::new(value).components();
+    !value.contains(['/', '\\'])
+        && matches!(components.next(), Some(Component::Normal(name)) if name == value)
+        && components.next().is_none()
+}
+
+/// Reject ids that would be joined onto `~/.claude/projects` as anything but a file name
+fn invalid_path_params(params: &[&str]) -> Option<Response> {
+    let invalid = params.iter().find(|p| !is_single_path_component(p))?;
+    Some(
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "error": format!("Invalid id: {}", invalid) })),
+        )
+            .into_response(),
+    )
+}
+
+async fn health() -> Response {
+    Json(json!({ "status": "ok", "version": env!("CARGO_PKG_VERSION") })).into_response()
+}
+
+async fn projects(ServerState(context): ServerState<ApiContext>) -> Response {
+    respond(list_projects(context.app.state()).await)
+}
+
+async fn project_sessions(
+    ServerState(context): ServerState<ApiContext>,
+    UrlPath(project_id): UrlPath<String>,
+) -> Response {
+    if let Some(rejection) = invalid_path_params(&[&project_id]) {
+        return rejection;
+    }
+    respond(get_project_sessions(context.app.state(), project_id).await)
+}
+
+async fn session_messages(
+    UrlPath((project_id, session_id)): UrlPath<(String, String)>,
+) -> Response {
+    if let Some(rejection) = invalid_path_params(&[&project_id, &session_id]) {
+        return rejection;
+    }
+    respond(load_session_history(session_id, project_id).await)
+}
+
+#[derive(Deserialize)]
+struct RunsQuery {
+    agent_id: Option<i64>,
+}
+
+async fn runs(
+    ServerState(context): ServerState<ApiContext>,
+    Query(query): Query<RunsQuery>,
+) -> Response {
+    respond(list_agent_runs(context.app.state(), query.agent_id).await)
+}
+
+async fn run(ServerState(context): ServerState<ApiContext>, UrlPath(id): UrlPath<i64>) -> Response {
+    respond(get_agent_run(context.app.state(), id).await)
+}
+
+#[derive(Deserialize)]
+struct UsageQuery {
+    days: Option<u32>,
+    start_date: Option<String>,
+    end_date: Option<String>,
+}
+
+/// Usage reads every transcript on a cold index, so it runs off the async runtime
+async fn usage(
+    ServerState(context): ServerState<ApiContext>,
+    Query(query): Query<UsageQuery>,
+) -> Response {
+    let result =
+        tokio::task::spawn_blocking(move || get_usage_stats(context.app.state(), query.days))
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|stats| stats);
+    respond(result)
+}
+
+async fn usage_by_project(
+    ServerState(context): ServerState<ApiContext>,
+    Query(query): Query<UsageQuery>,
+) -> Response {
+    let result = tokio::task::spawn_blocking(move || {
+        get_usage_by_project(context.app.state(), query.start_date, query.end_date)
+    })
+    .await
+    .map_err(|e| e.to_string())
+    .and_then(|usage| usage);
+    respond(result)
+}
+
+#[derive(Deserialize)]
+struct CalendarQuery {
+    days: Option<u32>,
+    /// Calendar apps subscribe by URL and can't send headers, so the token may come here
+    token: Option<String>,
+}
+
+/// Upcoming scheduled runs as an iCalendar feed
+async fn schedule_calendar(
+    ServerState(context): ServerState<ApiContext>,
+    headers: HeaderMap,
+    Query(query): Query<CalendarQuery>,
+) -> Response {
+    let token_in_query = !context.token.is_empty()
+        && query
+            .token
+            .as_deref()
+            .is_some_and(|token| constant_time_eq(token, &context.token));
+    if !token_in_query && !is_authorized(&headers, &context.token) {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(json!({ "error": "Missing or invalid token" })),
+        )
+            .into_response();
+    }
+    let db = context.app.state::<AgentDb>();
+    let calendar = db
+        .0
+        .lock()
+        .map_err(|e| e.to_string())
+        .and_then(|conn| render_schedule_calendar(&conn, query.days.unwrap_or(DEFAULT_CALENDAR_DAYS)));
+    match calendar {
+        Ok(calendar) => (
+            [(header::CONTENT_TYPE, "text/calendar; charset=utf-8")],
+            calendar,
+        )
+            .into_response(),
+        Err(e) => respond::<()>(Err(e)),
+    }
+}
+
+/// Read-only routes; everything except `/api/health` needs the bearer token, which the
+/// calendar feed also accepts as a `token` query parameter
+fn router(context: ApiContext) -> Router {
+    let authenticated = Router::new()
+        .route("/api/projects", get(projects))
+        .route("/api/projects/{project_id}/sessions", get(project_sessions))
+        .route(
+            "/api/projects/{project_id}/sessions/{session_id}",
+            get(session_messages),
+        )
+        .route("/api/runs", get(runs))
+        .route("/api/runs/{id}", get(run))
+        .route("/api/usage", get(usage))
+        .route("/api/usage/projects", get(usage_by_project))
+        .route_layer(middleware::from_fn_with_state(
+            context.clone(),
+            require_token,
+        ));
+    Router::new()
+        .route("/api/health", get(health))
+        .route("/api/schedules.ics", get(schedule_calendar))
+        .merge(authenticated)
+        .with_state(context)
+}
+
+/// Start listening on localhost; replaces a server that is already running
+async fn start_server(app: &AppHandle, settings: &RestApiSettings) -> Result<(), String> {
+    stop_server(app).await;
+
+    let listener = std::net::TcpListener::bind(("127.0.0.1", settings.port))
+        .map_err(|e| format!("Failed to listen on port {}: {}", settings.port, e))?;
+    listener.set_nonblocking(true).map_err(|e| e.to_string())?;
+    let listener = tokio::net::TcpListener::from_std(listener)
+        .map_err(|e| format!("Failed to start REST API server: {}", e))?;
+
+    let (shutdown, shutdown_rx) = oneshot::channel::<()>();
+    let context = ApiContext {
+        app: app.clone(),
+        token: settings.token.as_str().into(),
+    };
+    let port = settings.port;
+    let task = tauri::async_runtime::spawn(async move {
+        info!("REST API server listening on 127.0.0.1:{}", port);
+        let served = axum::serve(listener, router(context))
+            .with_graceful_shutdown(async {
+                let _ = shutdown_rx.await;
+            })
+            .await;
+        match served {
+            Ok(()) => info!("REST API server on port {} stopped", port),
+            Err(e) => error!("REST API server on port {} failed: {}", port, e),
+        }
+    });
+
+    let state = app.state::<RestApiState>();
+    *state.0.lock().map_err(|e| e.to_string())? = Some(RunningServer {
+        port: settings.port,
+        shutdown,
+        task,
+    });
+    Ok(())
+}
+
+/// Stop the server and wait until its port is released
+async fn stop_server(app: &AppHandle) {
+    let state = app.state::<RestApiState>();
+    let running = state.0.lock().ok().and_then(|mut server| server.take());
+    if let Some(server) = running {
+        let _ = server.shutdown.send(());
+        let _ = server.task.await;
+    }
+}
+
+/// Start the REST API server at launch if the user enabled it
+pub fn start_rest_api_server(app: AppHandle) {
+    let settings = {
+        let db = app.state::<AgentDb>();
+        let Ok(conn) = db.0.lock() else {
+            return;
+        };
+        load_api_settings(&conn)
+    };
+    if settings.enabled {
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = start_server(&app, &settings).await {
+                error!("{}", e);
+            }
+        });
+    }
+}
+
+/// Get the settings and state of the REST API server
+#[tauri::command]
+#[specta::specta]
+pub async fn get_rest_api_server(
+    db: State<'_, AgentDb>,
+    state: State<'_, RestApiState>,
+) -> Result<RestApiStatus, String> {
+    let settings = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        load_api_settings(&conn)
+    };
+    Ok(api_status(settings, &state))
+}
+
+/// Enable or disable the REST API server, starting or stopping it right away
+///
+/// `regenerate_token` issues a new token, locking out clients holding the old one.
+#[tauri::command]
+#[specta::specta]
+pub async fn set_rest_api_server(
+    app: AppHandle,
+    db: State<'_, AgentDb>,
+    state: State<'_, RestApiState>,
+    enabled: bool,
+    port: Option<u16>,
+    regenerate_token: Option<bool>,
+) -> Result<RestApiStatus, String> {
+    let previous = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        load_api_settings(&conn)
+    };
+    let mut settings = previous.clone();
+    settings.enabled = enabled;
+    if let Some(port) = port {
+        settings.port = port;
+    }
+    if settings.token.is_empty() || regenerate_token.unwrap_or(false) {
+        settings.token = uuid::Uuid::new_v4().simple().to_string();
+    }
+
+    // Only settings the server could actually start with are saved, so a failed bind
+    // doesn't lock clients out with a token that was never served
+    if enabled {
+        if let Err(e) = start_server(&app, &settings).await {
+            if previous.enabled {
+                if let Err(e) = start_server(&app, &previous).await {
+                    error!("Failed to restart the previous REST API server: {}", e);
+                }
+            }
+            return Err(e);
+        }
+    } else {
+        stop_server(&app).await;
+    }
+
+    {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        set_setting(&conn, "rest_api_server", &settings)
+            .map_err(|e| format!("Failed to save REST API settings: {}", e))?;
+    }
+    Ok(api_status(settings, &state))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requires_the_exact_bearer_token() {
+        let mut headers = HeaderMap::new();
+        assert!(!is_authorized(&headers, "secret"));
+        headers.insert(header::AUTHORIZATION, "Bearer wrong".parse().unwrap());
+        assert!(!is_authorized(&headers, "secret"));
+        headers.insert(header::AUTHORIZATION, "secret".parse().unwrap());
+        assert!(!is_authorized(&headers, "secret"));
+        headers.insert(header::AUTHORIZATION, "Bearer secret".parse().unwrap());
+        assert!(is_authorized(&headers, "secret"));
+        // A server without a token yet accepts nobody
+        headers.insert(header::AUTHORIZATION, "Bearer ".parse().unwrap());
+        assert!(!is_authorized(&headers, ""));
+    }
+
+    #[test]
+    fn path_params_must_be_a_single_file_name() {
+        assert!(is_single_path_component("-Users-me-repo"));
+        assert!(is_single_path_component("6f1c2d3e-session"));
+        for bad in ["", ".", "..", "../etc", "a/b", "a\\b", "/etc", "-repo/.."] {
+            assert!(!is_single_path_component(bad), "{:?} was accepted", bad);
+        }
+        assert!(invalid_path_params(&["-repo", "session"]).is_none());
+        let rejection = invalid_path_params(&["-repo", ".."]).unwrap();
+        assert_eq!(rejection.status(), StatusCode::BAD_REQUEST);
+    }
+}
@@ -0,0 +1,129 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use tauri::State;
+
+use super::agents::{get_agent_run, get_session_output, AgentDb, AgentRun, AgentRunMetrics};
+use super::artifacts::{load_artifacts, RunArtifact};
+use crate::process::ProcessRegistryState;
+
+/// One run in a comparison
+#[derive(Debug, Serialize, Deserialize, specta::Type)]
+pub struct ComparedRun {
+    pub run: AgentRun,
+    pub metrics: AgentRunMetrics,
+    pub artifacts: Vec<RunArtifact>,
+}
+
+/// How a file changed in each of the two runs
+#[derive(Debug, Serialize, Deserialize, specta::Type)]
+pub struct FileComparison {
+    pub path: String,
+    pub change_a: Option<String>,
+    pub change_b: Option<String>,
+    /// Both runs left the file with identical content
+    pub same_result: bool,
+    pub diff_a: Option<String>,
+    pub diff_b: Option<String>,
+}
+
+/// Differences in metrics, as `b - a`
+#[derive(Debug, Serialize, Deserialize, specta::Type)]
+pub struct MetricsDelta {
+    pub duration_ms: Option<i64>,
+    pub total_tokens: Option<i64>,
+    pub cost_usd: Option<f64>,
+    pub message_count: Option<i64>,
+}
+
+/// Side-by-side comparison of two agent runs
+#[derive(Debug, Serialize, Deserialize, specta::Type)]
+pub struct RunComparison {
+    pub a: ComparedRun,
+    pub b: ComparedRun,
+    pub delta: MetricsDelta,
+    pub files: Vec<FileComparison>,
+}
+
+fn delta_i64(a: Option<i64>, b: Option<i64>) -> Option<i64> {
+    Some(b? - a?)
+}
+
+async fn load_compared_run(
+    db: &State<'_, AgentDb>,
+    registry: &State<'_, ProcessRegistryState>,
+    run_id: i64,
+) -> Result<ComparedRun, String> {
+    let run = get_agent_run(db.clone(), run_id).await?;
+    let output = get_session_output(db.clone(), registry.clone(), run_id)
+        .await
+        .unwrap_or_default();
+    let metrics = AgentRunMetrics::from_jsonl(&output);
+    let artifacts = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        load_artifacts(&conn, run_id, true)?
+    };
+
+    Ok(ComparedRun {
+        run,
+        metrics,
+        artifacts,
+    })
+}
+
+/// Compare duration, cost, tokens and changed files of two runs, e.g. to A/B test a prompt or model
+#[tauri::command]
+#[specta::specta]
+pub async fn compare_agent_runs(
+    db: State<'_, AgentDb>,
+    registry: State<'_, ProcessRegistryState>,
+    run_id_a: i64,
+    run_id_b: i64,
+) -> Result<RunComparison, String> {
+    let mut a = load_compared_run(&db, &registry, run_id_a).await?;
+    let mut b = load_compared_run(&db, &registry, run_id_b).await?;
+
+    let delta = MetricsDelta {
+        duration_ms: delta_i64(a.metrics.duration_ms, b.metrics.duration_ms),
+        total_tokens: delta_i64(a.metrics.total_tokens, b.metrics.total_tokens),
+        cost_usd: match (a.metrics.cost_usd, b.metrics.cost_usd) {
+            (Some(cost_a), Some(cost_b)) => Some(cost_b - cost_a),
+            _ => None,
+        },
+        message_count: delta_i64(a.metrics.message_count, b.metrics.message_count),
+    };
+
+    let paths: BTreeSet<&str> = a
+        .artifacts
+        .iter()
+        .chain(b.artifacts.iter())
+        .map(|artifact| artifact.path.as_str())
+        .collect();
+
+    let files = paths
+        .into_iter()
+        .map(|path| {
+            let in_a = a.artifacts.iter().find(|artifact| artifact.path == path);
+            let in_b = b.artifacts.iter().find(|artifact| artifact.path == path);
+            let same_result = match (in_a, in_b) {
+                (Some(fa), Some(fb)) => fa.change_type == fb.change_type && fa.sha256 == fb.sha256,
+                _ => false,
+            };
+
+            FileComparison {
+                path: path.to_string(),
+                change_a: in_a.map(|artifact| artifact.change_type.clone()),
+                change_b: in_b.map(|artifact| artifact.change_type.clone()),
+                same_result,
+                diff_a: in_a.and_then(|artifact| artifact.diff.clone()),
+                diff_b: in_b.and_then(|artifact| artifact.diff.clone()),
+            }
+        })
+        .collect();
+
+    // The diffs are already part of `files`
+    for artifact in a.artifacts.iter_mut().chain(b.artifacts.iter_mut()) {
+        artifact.diff = None;
+    }
+
+    Ok(RunComparison { a, b, delta, files })
+}
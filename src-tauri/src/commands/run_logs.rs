@@ -0,0 +1,282 @@
+use log::error;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tauri::{AppHandle, Manager, State};
+use tokio::sync::mpsc;
+
+use super::agents::AgentDb;
+
+/// Longest log message stored; longer stdout lines are truncated
+const MAX_MESSAGE_LEN: usize = 64 * 1024;
+
+/// One stored log line of an agent run
+#[derive(Debug, Serialize, Deserialize, Clone, specta::Type)]
+pub struct RunLogEntry {
+    pub id: i64,
+    pub run_id: i64,
+    /// "stdout", "stderr" or "lifecycle"
+    pub stream: String,
+    /// Position of the line among every line of the run, across streams
+    pub seq: Option<i64>,
+    pub message: String,
+    pub created_at: String,
+}
+
+/// One line of process output, tagged with its stream and position in the run
+#[derive(Debug, Serialize, Deserialize, Clone, specta::Type)]
+pub struct OutputLine {
+    /// "stdout", "stderr" or "lifecycle"
+    pub stream: String,
+    pub seq: u64,
+    pub line: String,
+    pub timestamp: String,
+}
+
+/// Numbers a process's lines across both streams so they can be put back in order
+#[derive(Clone, Default)]
+pub struct OutputSequence(Arc<AtomicU64>);
+
+impl OutputSequence {
+    pub fn tag(&self, stream: &str, line: &str) -> OutputLine {
+        OutputLine {
+            stream: stream.to_string(),
+            seq: self.0.fetch_add(1, Ordering::Relaxed),
+            line: line.to_string(),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+}
+
+struct PendingEntry {
+    stream: &'static str,
+    seq: u64,
+    message: String,
+    created_at: String,
+}
+
+/// Writes a run's output and lifecycle events to the database in the background
+///
+/// Entries are batched so a chatty run doesn't hold the database lock for every line.
+#[derive(Clone)]
+pub struct RunLogger {
+    sender: mpsc::UnboundedSender<PendingEntry>,
+    sequence: OutputSequence,
+}
+
+impl RunLogger {
+    /// Start the writer task; it stops once every clone of the logger is dropped
+    pub fn start(app: AppHandle, run_id: i64) -> Self {
+        let (sender, mut receiver) = mpsc::unbounded_channel::<PendingEntry>();
+
+        tauri::async_runtime::spawn(async move {
+            while let Some(first) = receiver.recv().await {
+                let mut batch = vec![first];
+                while let Ok(entry) = receiver.try_recv() {
+                    batch.push(entry);
+                }
+                if let Err(e) = write_batch(&app, run_id, &batch) {
+                    error!("Failed to store logs for agent run {}: {}", run_id, e);
+                }
+                tokio::time::sleep(std::time::Duration::from_millis(250)).await;
+            }
+        });
+
+        Self {
+            sender,
+            sequence: OutputSequence::default(),
+        }
+    }
+
+    /// Store a stdout line, returning it tagged for the `agent-stream` event
+    pub fn stdout(&self, line: &str) -> OutputLine {
+        self.push("stdout", line)
+    }
+
+    pub fn stderr(&self, line: &str) -> OutputLine {
+        self.push("stderr", line)
+    }
+
+    pub fn lifecycle(&self, message: impl Into<String>) {
+        self.push("lifecycle", &message.into());
+    }
+
+    fn push(&self, stream: &'static str, message: &str) -> OutputLine {
+        let output = self.sequence.tag(stream, message);
+        let mut message = message.to_string();
+        if message.len() > MAX_MESSAGE_LEN {
+            let mut cut = MAX_MESSAGE_LEN;
+            while !message.is_char_boundary(cut) {
+                cut -= 1;
+            }
+            message.truncate(cut);
+            message.push_str("… [truncated]");
+        }
+
+        let _ = self.sender.send(PendingEntry {
+            stream,
+            seq: output.seq,
+            message,
+            created_at: output.timestamp.clone(),
+        });
+        output
+    }
+}
+
+/// Stores a session's stderr, holding back lines that arrive before its session ID is known
+pub struct SessionErrorLog {
+    app: AppHandle,
+    pending: Vec<OutputLine>,
+}
+
+impl SessionErrorLog {
+    pub fn new(app: AppHandle) -> Self {
+        Self {
+            app,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Record a stderr line; returns the lines that can now be emitted for the session
+    pub fn record(&mut self, session_id: Option<&str>, line: OutputLine) -> Vec<OutputLine> {
+        self.pending.push(line);
+        match session_id {
+            Some(session_id) => self.flush(session_id),
+            None => Vec::new(),
+        }
+    }
+
+    /// Store the held back lines once the session ID is known, returning them
+    pub fn flush(&mut self, session_id: &str) -> Vec<OutputLine> {
+        let lines = std::mem::take(&mut self.pending);
+        if lines.is_empty() {
+            return lines;
+        }
+        if let Err(e) = write_session_errors(&self.app, session_id, &lines) {
+            error!("Failed to store stderr for session {}: {}", session_id, e);
+        }
+        lines
+    }
+}
+
+impl Drop for SessionErrorLog {
+    fn drop(&mut self) {
+        for line in &self.pending {
+            error!(
+                "Stderr from a session that never reported its ID: {}",
+                line.line
+            );
+        }
+    }
+}
+
+fn write_session_errors(
+    app: &AppHandle,
+    session_id: &str,
+    lines: &[OutputLine],
+) -> Result<(), String> {
+    let db = app.state::<AgentDb>();
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    for line in lines {
+        conn.execute(
+            "INSERT INTO session_error_logs (session_id, seq, message, created_at) VALUES (?1, ?2, ?3, ?4)",
+            params![session_id, line.seq as i64, line.line, line.timestamp],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+fn write_batch(app: &AppHandle, run_id: i64, batch: &[PendingEntry]) -> Result<(), String> {
+    let db = app.state::<AgentDb>();
+    let mut conn = db.0.lock().map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    {
+        let mut stmt = tx
+            .prepare(
+                "INSERT INTO agent_run_logs (run_id, stream, seq, message, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+            )
+            .map_err(|e| e.to_string())?;
+        for entry in batch {
+            stmt.execute(params![
+                run_id,
+                entry.stream,
+                entry.seq as i64,
+                entry.message,
+                entry.created_at
+            ])
+            .map_err(|e| e.to_string())?;
+        }
+    }
+    tx.commit().map_err(|e| e.to_string())
+}
+
+/// Get the stored logs of a run, oldest first, optionally filtered by stream
+#[tauri::command]
+#[specta::specta]
+pub async fn get_run_logs(
+    db: State<'_, AgentDb>,
+    run_id: i64,
+    stream: Option<String>,
+    limit: Option<i64>,
+) -> Result<Vec<RunLogEntry>, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, run_id, stream, seq, message, created_at FROM agent_run_logs
+             WHERE run_id = ?1 AND (?2 IS NULL OR stream = ?2)
+             ORDER BY id LIMIT ?3",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let logs = stmt
+        .query_map(params![run_id, stream, limit.unwrap_or(-1)], |row| {
+            Ok(RunLogEntry {
+                id: row.get(0)?,
+                run_id: row.get(1)?,
+                stream: row.get(2)?,
+                seq: row.get(3)?,
+                message: row.get(4)?,
+                created_at: row.get(5)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(logs)
+}
+
+/// Get the stored stderr of an interactive session, oldest first
+#[tauri::command]
+#[specta::specta]
+pub async fn get_session_error_logs(
+    db: State<'_, AgentDb>,
+    session_id: String,
+) -> Result<Vec<OutputLine>, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT seq, message, created_at FROM session_error_logs
+             WHERE session_id = ?1 ORDER BY id",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let logs = stmt
+        .query_map(params![session_id], |row| {
+            Ok(OutputLine {
+                stream: "stderr".to_string(),
+                seq: row.get::<_, i64>(0)? as u64,
+                line: row.get(1)?,
+                timestamp: row.get(2)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(logs)
+}
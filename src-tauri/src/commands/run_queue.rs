@@ -0,0 +1,265 @@
+use log::{error, info};
+use rusqlite::{params, Connection};
+use std::collections::HashSet;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use super::agents::{get_setting, set_setting, start_queued_agent_run, AgentDb, AgentRun};
+use crate::process::ProcessRegistryState;
+
+/// How often the dispatcher looks for free slots
+const DISPATCH_INTERVAL_MS: u64 = 2000;
+
+/// Tracks which agent runs hold a concurrency slot in this app session
+///
+/// Runs left in the `running` state by a previous session don't count against the limit.
+#[derive(Default)]
+pub struct AgentRunQueueState {
+    active_runs: Mutex<HashSet<i64>>,
+}
+
+impl AgentRunQueueState {
+    /// Try to take a slot for `run_id`
+    ///
+    /// New runs (`from_queue == false`) also wait when others are already queued,
+    /// so the queue stays first-in, first-out.
+    pub fn try_acquire_slot(&self, conn: &Connection, run_id: i64, from_queue: bool) -> Result<bool, String> {
+        let mut active = self.active_runs.lock().map_err(|e| e.to_string())?;
+        prune_finished_runs(conn, &mut active);
+
+        let limit = get_max_concurrent_runs_setting(conn);
+        if limit > 0 {
+            if active.len() as i64 >= limit {
+                return Ok(false);
+            }
+            if !from_queue {
+                let queued: i64 = conn
+                    .query_row(
+                        "SELECT COUNT(*) FROM agent_runs WHERE status = 'queued'",
+                        [],
+                        |row| row.get(0),
+                    )
+                    .map_err(|e| e.to_string())?;
+                if queued > 0 {
+                    return Ok(false);
+                }
+            }
+        }
+
+        active.insert(run_id);
+        Ok(true)
+    }
+
+    fn release_slot(&self, run_id: i64) {
+        if let Ok(mut active) = self.active_runs.lock() {
+            active.remove(&run_id);
+        }
+    }
+}
+
+/// Drop runs that are no longer pending or running from the active set
+fn prune_finished_runs(conn: &Connection, active: &mut HashSet<i64>) {
+    active.retain(|run_id| {
+        conn.query_row(
+            "SELECT status FROM agent_runs WHERE id = ?1",
+            params![run_id],
+            |row| row.get::<_, String>(0),
+        )
+        .map(|status| status == "pending" || status == "running")
+        .unwrap_or(false)
+    });
+}
+
+/// Read the concurrency limit; 0 means unlimited
+fn get_max_concurrent_runs_setting(conn: &Connection) -> i64 {
+    get_setting(conn, "max_concurrent_agent_runs").unwrap_or(0)
+}
+
+/// Put a run at the end of the queue
+pub fn enqueue_run(conn: &Connection, run_id: i64) -> Result<(), String> {
+    conn.execute(
+        "UPDATE agent_runs SET status = 'queued', queue_position = (SELECT COALESCE(MAX(queue_position), 0) + 1 FROM agent_runs WHERE status = 'queued') WHERE id = ?1",
+        params![run_id],
+    )
+    .map_err(|e| format!("Failed to queue agent run: {}", e))?;
+    Ok(())
+}
+
+/// Get the maximum number of concurrently running agents (0 = unlimited)
+#[tauri::command]
+#[specta::specta]
+pub async fn get_max_concurrent_runs(db: State<'_, AgentDb>) -> Result<i64, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    Ok(get_max_concurrent_runs_setting(&conn))
+}
+
+/// Set the maximum number of concurrently running agents (0 = unlimited)
+#[tauri::command]
+#[specta::specta]
+pub async fn set_max_concurrent_runs(db: State<'_, AgentDb>, limit: i64) -> Result<(), String> {
+    if limit < 0 {
+        return Err("The concurrency limit must not be negative".to_string());
+    }
+
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    set_setting(&conn, "max_concurrent_agent_runs", &limit)
+        .map_err(|e| format!("Failed to save concurrency limit: {}", e))?;
+
+    Ok(())
+}
+
+/// List queued agent runs in the order they will start
+#[tauri::command]
+#[specta::specta]
+pub async fn list_queued_agent_runs(db: State<'_, AgentDb>) -> Result<Vec<AgentRun>, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, agent_id, agent_name, agent_icon, task, model, project_path, session_id, status, pid, process_started_at, created_at, completed_at
+             FROM agent_runs WHERE status = 'queued' ORDER BY queue_position, id",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let runs = stmt
+        .query_map([], |row| {
+            Ok(AgentRun {
+                id: Some(row.get(0)?),
+                agent_id: row.get(1)?,
+                agent_name: row.get(2)?,
+                agent_icon: row.get(3)?,
+                task: row.get(4)?,
+                model: row.get(5)?,
+                project_path: row.get(6)?,
+                session_id: row.get(7)?,
+                status: row.get(8)?,
+                pid: row.get::<_, Option<i64>>(9).ok().flatten().map(|p| p as u32),
+                process_started_at: row.get(10)?,
+                created_at: row.get(11)?,
+                completed_at: row.get(12)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(runs)
+}
+
+/// Reorder the queue; listed runs move to the front in the given order
+#[tauri::command]
+#[specta::specta]
+pub async fn reorder_agent_run_queue(
+    db: State<'_, AgentDb>,
+    run_ids: Vec<i64>,
+) -> Result<(), String> {
+    let mut conn = db.0.lock().map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+
+    let offset = run_ids.len() as i64;
+    tx.execute(
+        "UPDATE agent_runs SET queue_position = queue_position + ?1 WHERE status = 'queued'",
+        params![offset],
+    )
+    .map_err(|e| e.to_string())?;
+    for (index, run_id) in run_ids.iter().enumerate() {
+        tx.execute(
+            "UPDATE agent_runs SET queue_position = ?1 WHERE id = ?2 AND status = 'queued'",
+            params![index as i64 + 1, run_id],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Remove a run from the queue without starting it
+#[tauri::command]
+#[specta::specta]
+pub async fn remove_queued_agent_run(
+    app: AppHandle,
+    db: State<'_, AgentDb>,
+    run_id: i64,
+) -> Result<bool, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let updated = conn
+        .execute(
+            "UPDATE agent_runs SET status = 'cancelled', queue_position = NULL, completed_at = CURRENT_TIMESTAMP WHERE id = ?1 AND status = 'queued'",
+            params![run_id],
+        )
+        .map_err(|e| e.to_string())?;
+
+    if updated > 0 {
+        let _ = app.emit(&format!("agent-cancelled:{}", run_id), true);
+    }
+    Ok(updated > 0)
+}
+
+/// Start the background task that moves queued runs into free slots
+pub fn start_queue_dispatcher(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval =
+            tokio::time::interval(std::time::Duration::from_millis(DISPATCH_INTERVAL_MS));
+        loop {
+            interval.tick().await;
+            if let Err(e) = dispatch_queued_runs(&app).await {
+                error!("Agent queue dispatch failed: {}", e);
+            }
+        }
+    });
+}
+
+/// Start queued runs, head first, while slots are free
+async fn dispatch_queued_runs(app: &AppHandle) -> Result<(), String> {
+    loop {
+        let next_run_id = {
+            let db = app.state::<AgentDb>();
+            let conn = db.0.lock().map_err(|e| e.to_string())?;
+
+            let head: Option<i64> = conn
+                .query_row(
+                    "SELECT id FROM agent_runs WHERE status = 'queued' ORDER BY queue_position, id LIMIT 1",
+                    [],
+                    |row| row.get(0),
+                )
+                .ok();
+            let Some(run_id) = head else {
+                return Ok(());
+            };
+
+            let queue = app.state::<AgentRunQueueState>();
+            if !queue.try_acquire_slot(&conn, run_id, true)? {
+                return Ok(());
+            }
+
+            conn.execute(
+                "UPDATE agent_runs SET status = 'pending', queue_position = NULL WHERE id = ?1",
+                params![run_id],
+            )
+            .map_err(|e| e.to_string())?;
+            run_id
+        };
+
+        info!("Dequeued agent run {}", next_run_id);
+        let started = start_queued_agent_run(
+            app.clone(),
+            next_run_id,
+            app.state::<AgentDb>(),
+            app.state::<ProcessRegistryState>(),
+        )
+        .await;
+
+        if let Err(e) = started {
+            error!("Failed to start queued agent run {}: {}", next_run_id, e);
+            app.state::<AgentRunQueueState>().release_slot(next_run_id);
+            let db = app.state::<AgentDb>();
+            let conn = db.0.lock().map_err(|e| e.to_string())?;
+            let _ = conn.execute(
+                "UPDATE agent_runs SET status = 'failed', completed_at = CURRENT_TIMESTAMP WHERE id = ?1",
+                params![next_run_id],
+            );
+            let _ = app.emit(&format!("agent-complete:{}", next_run_id), false);
+        }
+    }
+}
@@ -0,0 +1,186 @@
+use log::{info, warn};
+use rusqlite::{params, Connection};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use super::agents::{start_queued_agent_run, AgentDb, AgentRun};
+use super::run_queue::{enqueue_run, AgentRunQueueState};
+use crate::process::ProcessRegistryState;
+
+/// Check whether a process with the given PID still exists
+fn is_process_alive(pid: i64) -> bool {
+    if cfg!(target_os = "windows") {
+        match std::process::Command::new("tasklist")
+            .args(["/FI", &format!("PID eq {}", pid)])
+            .args(["/FO", "CSV"])
+            .output()
+        {
+            Ok(output) => String::from_utf8_lossy(&output.stdout).lines().count() > 1,
+            Err(_) => false,
+        }
+    } else {
+        match std::process::Command::new("kill")
+            .args(["-0", &pid.to_string()])
+            .output()
+        {
+            Ok(output) => output.status.success(),
+            Err(_) => false,
+        }
+    }
+}
+
+/// Mark runs that were pending or running when the app last exited as interrupted
+///
+/// Called once at startup, before anything new is spawned. Runs whose process is
+/// still alive are left alone so `cleanup_finished_processes` can pick them up.
+pub fn detect_interrupted_runs(conn: &Connection) -> Result<Vec<i64>, String> {
+    let mut stmt = conn
+        .prepare("SELECT id, pid FROM agent_runs WHERE status IN ('pending', 'running')")
+        .map_err(|e| e.to_string())?;
+
+    let candidates = stmt
+        .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, Option<i64>>(1)?)))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    drop(stmt);
+
+    let mut interrupted = Vec::new();
+    for (run_id, pid) in candidates {
+        if pid.is_some_and(|pid| pid > 0 && is_process_alive(pid)) {
+            continue;
+        }
+
+        conn.execute(
+            "UPDATE agent_runs SET status = 'interrupted', interrupted_at = CURRENT_TIMESTAMP, pid = NULL WHERE id = ?1",
+            params![run_id],
+        )
+        .map_err(|e| e.to_string())?;
+        interrupted.push(run_id);
+    }
+
+    if !interrupted.is_empty() {
+        warn!("Found {} interrupted agent run(s): {:?}", interrupted.len(), interrupted);
+    }
+    Ok(interrupted)
+}
+
+/// List runs that were interrupted by an app crash or restart
+#[tauri::command]
+#[specta::specta]
+pub async fn list_interrupted_agent_runs(db: State<'_, AgentDb>) -> Result<Vec<AgentRun>, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, agent_id, agent_name, agent_icon, task, model, project_path, session_id, status, pid, process_started_at, created_at, completed_at
+             FROM agent_runs WHERE status = 'interrupted' ORDER BY interrupted_at DESC, id DESC",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let runs = stmt
+        .query_map([], |row| {
+            Ok(AgentRun {
+                id: Some(row.get(0)?),
+                agent_id: row.get(1)?,
+                agent_name: row.get(2)?,
+                agent_icon: row.get(3)?,
+                task: row.get(4)?,
+                model: row.get(5)?,
+                project_path: row.get(6)?,
+                session_id: row.get(7)?,
+                status: row.get(8)?,
+                pid: row.get::<_, Option<i64>>(9).ok().flatten().map(|p| p as u32),
+                process_started_at: row.get(10)?,
+                created_at: row.get(11)?,
+                completed_at: row.get(12)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(runs)
+}
+
+/// Resume an interrupted run
+///
+/// Continues the Claude session with `--resume` when the run got far enough to have
+/// a session ID, otherwise starts the original task again. Returns the run ID.
+#[tauri::command]
+#[specta::specta]
+pub async fn resume_agent_run(
+    app: AppHandle,
+    db: State<'_, AgentDb>,
+    registry: State<'_, ProcessRegistryState>,
+    run_id: i64,
+) -> Result<i64, String> {
+    let queued = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        let updated = conn
+            .execute(
+                "UPDATE agent_runs SET status = 'pending', interrupted_at = NULL, completed_at = NULL, resume_count = resume_count + 1 WHERE id = ?1 AND status = 'interrupted'",
+                params![run_id],
+            )
+            .map_err(|e| e.to_string())?;
+        if updated == 0 {
+            return Err(format!("Agent run {} is not interrupted", run_id));
+        }
+
+        let queue = app.state::<AgentRunQueueState>();
+        let queued = queue
+            .try_acquire_slot(&conn, run_id, false)
+            .and_then(|acquired| {
+                if !acquired {
+                    enqueue_run(&conn, run_id)?;
+                }
+                Ok(!acquired)
+            });
+        match queued {
+            Ok(queued) => queued,
+            Err(e) => {
+                let _ = conn.execute(
+                    "UPDATE agent_runs SET status = 'interrupted', interrupted_at = CURRENT_TIMESTAMP WHERE id = ?1",
+                    params![run_id],
+                );
+                return Err(e);
+            }
+        }
+    };
+
+    if queued {
+        info!("Concurrency limit reached, queued resumed agent run {}", run_id);
+        let _ = app.emit(&format!("agent-queued:{}", run_id), true);
+        let _ = app.emit("agent-queued", run_id);
+        return Ok(run_id);
+    }
+
+    info!("Resuming interrupted agent run {}", run_id);
+    if let Err(e) = start_queued_agent_run(app.clone(), run_id, db.clone(), registry).await {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        let _ = conn.execute(
+            "UPDATE agent_runs SET status = 'interrupted', interrupted_at = CURRENT_TIMESTAMP WHERE id = ?1",
+            params![run_id],
+        );
+        return Err(e);
+    }
+
+    Ok(run_id)
+}
+
+/// Give up on an interrupted run and mark it as failed
+#[tauri::command]
+#[specta::specta]
+pub async fn discard_interrupted_agent_run(
+    db: State<'_, AgentDb>,
+    run_id: i64,
+) -> Result<bool, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let updated = conn
+        .execute(
+            "UPDATE agent_runs SET status = 'failed', completed_at = CURRENT_TIMESTAMP WHERE id = ?1 AND status = 'interrupted'",
+            params![run_id],
+        )
+        .map_err(|e| e.to_string())?;
+
+    Ok(updated > 0)
+}
@@ -0,0 +1,272 @@
+use log::{error, info};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use super::agents::{get_setting, set_setting, AgentDb};
+use super::run_queue::enqueue_run;
+
+/// How often runs waiting for a retry are checked
+const RETRY_CHECK_INTERVAL_MS: u64 = 5000;
+
+/// Error markers of API failures that usually succeed when tried again later
+const TRANSIENT_MARKERS: &[&str] = &[
+    "rate_limit_error",
+    "overloaded_error",
+    "API Error: 429",
+    "API Error: 529",
+    "API Error: 503",
+];
+
+/// When and how often agent runs that hit a transient API error are resumed
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, specta::Type)]
+#[serde(default)]
+pub struct RetryPolicy {
+    pub enabled: bool,
+    pub max_attempts: u32,
+    pub base_delay_secs: u64,
+    pub max_delay_secs: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_attempts: 3,
+            base_delay_secs: 30,
+            max_delay_secs: 600,
+        }
+    }
+}
+
+/// Payload of the `agent-retrying` event
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct RetryScheduled {
+    pub run_id: i64,
+    pub attempt: u32,
+    pub max_attempts: u32,
+    pub delay_secs: u64,
+    pub reason: String,
+    pub retry_at: String,
+}
+
+fn load_policy(conn: &Connection) -> RetryPolicy {
+    get_setting(conn, "agent_retry_policy").unwrap_or_default()
+}
+
+/// The transient error reported by a stream-json line, if any
+pub fn transient_error(line: &str) -> Option<String> {
+    let json: Value = serde_json::from_str(line).ok()?;
+    let text = match json["type"].as_str()? {
+        "error" => json["error"].to_string(),
+        "result" if json["is_error"] == true => json["result"].as_str()?.to_string(),
+        // API failures are also reported as an assistant message starting with "API Error"
+        "assistant" => json["message"]["content"]
+            .as_array()?
+            .iter()
+            .filter_map(|block| block["text"].as_str())
+            .find(|text| text.starts_with("API Error"))?
+            .to_string(),
+        _ => return None,
+    };
+    TRANSIENT_MARKERS
+        .iter()
+        .any(|marker| text.contains(marker))
+        .then(|| text.chars().take(500).collect())
+}
+
+/// Whether a stream-json line is a result that did not report an error
+fn is_successful_result(line: &str) -> bool {
+    serde_json::from_str::<Value>(line)
+        .is_ok_and(|json| json["type"] == "result" && json["is_error"] != true)
+}
+
+/// Remembers the last transient error seen in a run's output
+///
+/// A later successful result clears it, since Claude recovered on its own.
+#[derive(Clone, Default)]
+pub struct TransientErrorDetector(Arc<Mutex<Option<String>>>);
+
+impl TransientErrorDetector {
+    pub fn observe(&self, line: &str) {
+        let reason = transient_error(line);
+        if reason.is_none() && !is_successful_result(line) {
+            return;
+        }
+        if let Ok(mut last) = self.0.lock() {
+            *last = reason;
+        }
+    }
+
+    pub fn reason(&self) -> Option<String> {
+        self.0.lock().ok()?.clone()
+    }
+}
+
+/// Delay before the given attempt, doubling from the base delay up to the maximum
+pub fn backoff_delay(policy: &RetryPolicy, attempt: u32) -> u64 {
+    let factor = 2u64.saturating_pow(attempt.saturating_sub(1));
+    policy
+        .base_delay_secs
+        .saturating_mul(factor)
+        .min(policy.max_delay_secs)
+}
+
+/// Mark a run for another attempt if the policy allows it
+///
+/// The retry scheduler queues the run once the delay has passed, and starting it
+/// continues the session when it has one.
+pub fn schedule_retry(
+    conn: &Connection,
+    run_id: i64,
+    session_id: &str,
+    reason: &str,
+) -> Result<Option<RetryScheduled>, String> {
+    let policy = load_policy(conn);
+    if !policy.enabled {
+        return Ok(None);
+    }
+    let (status, retry_count): (String, u32) = conn
+        .query_row(
+            "SELECT status, retry_count FROM agent_runs WHERE id = ?1",
+            params![run_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|e| e.to_string())?;
+    if status != "running" || retry_count >= policy.max_attempts {
+        return Ok(None);
+    }
+
+    let attempt = retry_count + 1;
+    let delay_secs = backoff_delay(&policy, attempt);
+    conn.execute(
+        "UPDATE agent_runs SET session_id = ?1, status = 'retrying', retry_count = ?2, retry_at = datetime('now', ?3), pid = NULL WHERE id = ?4",
+        params![session_id, attempt, format!("+{} seconds", delay_secs), run_id],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(Some(RetryScheduled {
+        run_id,
+        attempt,
+        max_attempts: policy.max_attempts,
+        delay_secs,
+        reason: reason.to_string(),
+        retry_at: (chrono::Utc::now() + chrono::Duration::seconds(delay_secs as i64)).to_rfc3339(),
+    }))
+}
+
+/// Schedule a retry for a run that just finished, logging instead of failing
+pub fn schedule_retry_in(
+    db_path: &Path,
+    run_id: i64,
+    session_id: &str,
+    reason: &str,
+) -> Option<RetryScheduled> {
+    let result = Connection::open(db_path)
+        .map_err(|e| e.to_string())
+        .and_then(|conn| schedule_retry(&conn, run_id, session_id, reason));
+    match result {
+        Ok(retry) => retry,
+        Err(e) => {
+            error!("Failed to schedule retry of agent run {}: {}", run_id, e);
+            None
+        }
+    }
+}
+
+/// Start the background task that queues runs whose retry delay has passed
+pub fn start_retry_scheduler(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval =
+            tokio::time::interval(std::time::Duration::from_millis(RETRY_CHECK_INTERVAL_MS));
+        loop {
+            interval.tick().await;
+            if let Err(e) = queue_due_retries(&app) {
+                error!("Agent retry scheduling failed: {}", e);
+            }
+        }
+    });
+}
+
+fn queue_due_retries(app: &AppHandle) -> Result<(), String> {
+    let db = app.state::<AgentDb>();
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT id FROM agent_runs WHERE status = 'retrying' AND retry_at <= datetime('now') ORDER BY retry_at",
+        )
+        .map_err(|e| e.to_string())?;
+    let due = stmt
+        .query_map([], |row| row.get::<_, i64>(0))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    drop(stmt);
+
+    for run_id in due {
+        info!("Retry delay passed, queueing agent run {}", run_id);
+        enqueue_run(&conn, run_id)?;
+        let _ = app.emit(&format!("agent-queued:{}", run_id), true);
+        let _ = app.emit("agent-queued", run_id);
+    }
+    Ok(())
+}
+
+/// Get the retry policy for agent runs
+#[tauri::command]
+#[specta::specta]
+pub async fn get_retry_policy(db: State<'_, AgentDb>) -> Result<RetryPolicy, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    Ok(load_policy(&conn))
+}
+
+/// Save the retry policy for agent runs
+#[tauri::command]
+#[specta::specta]
+pub async fn set_retry_policy(db: State<'_, AgentDb>, policy: RetryPolicy) -> Result<(), String> {
+    if policy.base_delay_secs == 0 || policy.max_delay_secs < policy.base_delay_secs {
+        return Err(
+            "The maximum delay must be at least the base delay, which must be positive".to_string(),
+        );
+    }
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    set_setting(&conn, "agent_retry_policy", &policy)
+        .map_err(|e| format!("Failed to save retry policy: {}", e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_transient_errors_and_backs_off() {
+        let overloaded = r#"{"type":"result","subtype":"success","is_error":true,"result":"API Error: 529 {\"type\":\"error\",\"error\":{\"type\":\"overloaded_error\"}}"}"#;
+        assert!(transient_error(overloaded).is_some());
+        let rate_limited = r#"{"type":"assistant","message":{"content":[{"type":"text","text":"API Error: 429 rate_limit_error"}]}}"#;
+        assert!(transient_error(rate_limited).is_some());
+        let auth =
+            r#"{"type":"result","is_error":true,"result":"API Error: 401 authentication_error"}"#;
+        assert_eq!(transient_error(auth), None);
+        let chatter = r#"{"type":"assistant","message":{"content":[{"type":"text","text":"The overloaded_error handler is fine"}]}}"#;
+        assert_eq!(transient_error(chatter), None);
+
+        let detector = TransientErrorDetector::default();
+        detector.observe(rate_limited);
+        assert!(detector.reason().is_some());
+        detector.observe(r#"{"type":"result","is_error":false,"result":"Done"}"#);
+        assert_eq!(detector.reason(), None);
+
+        let policy = RetryPolicy {
+            enabled: true,
+            max_attempts: 5,
+            base_delay_secs: 30,
+            max_delay_secs: 100,
+        };
+        let delays: Vec<u64> = (1..=4).map(|a| backoff_delay(&policy, a)).collect();
+        assert_eq!(delays, vec![30, 60, 100, 100]);
+    }
+}
@@ -0,0 +1,226 @@
+use chrono::{DateTime, Duration, Local, Utc};
+use log::info;
+use rusqlite::{params, Connection};
+use std::collections::HashMap;
+use tauri::State;
+
+use super::agents::AgentDb;
+use super::scheduler::{load_schedules, AgentSchedule, CronExpression};
+
+/// How far ahead the calendar lists runs by default
+pub const DEFAULT_CALENDAR_DAYS: u32 = 30;
+
+/// Longest window a calendar can cover
+const MAX_CALENDAR_DAYS: u32 = 366;
+
+/// Occurrences listed per schedule, so an every-minute schedule can't flood the calendar
+const MAX_EVENTS_PER_SCHEDULE: usize = 200;
+
+/// Event length for schedules that have not completed a run yet
+const DEFAULT_RUN_MINUTES: i64 = 15;
+
+/// Escape a TEXT value (RFC 5545, 3.3.11)
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Fold a content line to 75 octets, continuing with a space (RFC 5545, 3.1)
+fn fold_line(line: &str) -> String {
+    let mut folded = String::new();
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+fn ics_time(time: DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Average length of the schedule's recent completed runs
+fn typical_duration(conn: &Connection, schedule_id: i64) -> Duration {
+    let seconds: Option<f64> = conn
+        .query_row(
+            "SELECT AVG((julianday(completed_at) - julianday(created_at)) * 86400)
+             FROM (SELECT created_at, completed_at FROM agent_runs
+                   WHERE schedule_id = ?1 AND status = 'completed' AND completed_at IS NOT NULL
+                   ORDER BY id DESC LIMIT 10)",
+            params![schedule_id],
+            |row| row.get(0),
+        )
+        .ok()
+        .flatten();
+    match seconds {
+        Some(seconds) if seconds > 0.0 => Duration::seconds((seconds as i64).max(300)),
+        _ => Duration::minutes(DEFAULT_RUN_MINUTES),
+    }
+}
+
+fn schedule_events(
+    schedule: &AgentSchedule,
+    agent_name: &str,
+    duration: Duration,
+    now: DateTime<Utc>,
+    until: DateTime<Utc>,
+) -> Vec<String> {
+    let (Some(id), Ok(cron)) = (schedule.id, CronExpression::parse(&schedule.cron_expression)) else {
+        return Vec::new();
+    };
+    let description = format!(
+        "Agent: {}\nProjects: {}\nCron: {}\nTask: {}",
+        agent_name,
+        schedule.project_paths.join(", "),
+        schedule.cron_expression,
+        schedule.task.as_deref().unwrap_or("(agent default)")
+    );
+
+    let mut events = Vec::new();
+    let mut after = now.with_timezone(&Local);
+    while events.len() < MAX_EVENTS_PER_SCHEDULE {
+        let Some(next) = cron.next_after(&after) else {
+            break;
+        };
+        let start = next.with_timezone(&Utc);
+        if start > until {
+            break;
+        }
+        let mut event = String::new();
+        for line in [
+            "BEGIN:VEVENT".to_string(),
+            format!("UID:schedule-{}-{}@claudia", id, start.timestamp()),
+            format!("DTSTAMP:{}", ics_time(now)),
+            format!("DTSTART:{}", ics_time(start)),
+            format!("DTEND:{}", ics_time(start + duration)),
+            format!("SUMMARY:{}", escape_text(&format!("{} ({})", schedule.name, agent_name))),
+            format!("DESCRIPTION:{}", escape_text(&description)),
+            "CATEGORIES:Claudia".to_string(),
+            "TRANSP:TRANSPARENT".to_string(),
+            "END:VEVENT".to_string(),
+        ] {
+            event.push_str(&fold_line(&line));
+        }
+        events.push(event);
+        after = next;
+    }
+    events
+}
+
+/// Render the upcoming runs of enabled schedules as an iCalendar document
+pub fn render_schedule_calendar(conn: &Connection, days: u32) -> Result<String, String> {
+    let now = Utc::now();
+    let until = now + Duration::days(days.min(MAX_CALENDAR_DAYS) as i64);
+    let mut stmt = conn
+        .prepare("SELECT id, name FROM agents")
+        .map_err(|e| e.to_string())?;
+    let agent_names: HashMap<i64, String> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<_, _>>()
+        .map_err(|e| e.to_string())?;
+
+    let mut calendar = String::new();
+    for line in [
+        "BEGIN:VCALENDAR",
+        "VERSION:2.0",
+        "PRODID:-//Claudia//Agent schedules//EN",
+        "CALSCALE:GREGORIAN",
+        "METHOD:PUBLISH",
+        "X-WR-CALNAME:Claudia agent runs",
+    ] {
+        calendar.push_str(&fold_line(line));
+    }
+    for schedule in load_schedules(conn)?.iter().filter(|s| s.enabled) {
+        let agent_name = agent_names
+            .get(&schedule.agent_id)
+            .map(String::as_str)
+            .unwrap_or("Unknown agent");
+        let duration = schedule
+            .id
+            .map(|id| typical_duration(conn, id))
+            .unwrap_or_else(|| Duration::minutes(DEFAULT_RUN_MINUTES));
+        for event in schedule_events(schedule, agent_name, duration, now, until) {
+            calendar.push_str(&event);
+        }
+    }
+    calendar.push_str(&fold_line("END:VCALENDAR"));
+    Ok(calendar)
+}
+
+/// Write the upcoming scheduled runs to an .ics file
+#[tauri::command]
+#[specta::specta]
+pub async fn export_schedule_calendar(
+    db: State<'_, AgentDb>,
+    file_path: String,
+    days: Option<u32>,
+) -> Result<String, String> {
+    let calendar = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        render_schedule_calendar(&conn, days.unwrap_or(DEFAULT_CALENDAR_DAYS))?
+    };
+    std::fs::write(&file_path, calendar)
+        .map_err(|e| format!("Failed to write calendar: {}", e))?;
+    info!("Exported schedule calendar to {}", file_path);
+    Ok(file_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule(cron_expression: &str) -> AgentSchedule {
+        AgentSchedule {
+            id: Some(7),
+            agent_id: 1,
+            name: "Nightly, triage".to_string(),
+            cron_expression: cron_expression.to_string(),
+            project_paths: vec!["/work/app".to_string()],
+            task: None,
+            model: None,
+            enabled: true,
+            last_run_at: None,
+            next_run_at: None,
+            last_error: None,
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn lists_occurrences_within_the_window() {
+        let now = Utc::now();
+        let events = schedule_events(
+            &schedule("0 3 * * *"),
+            "Triage bot",
+            Duration::minutes(15),
+            now,
+            now + Duration::days(7),
+        );
+        assert!((6..=8).contains(&events.len()));
+        assert!(events[0].contains("SUMMARY:Nightly\\, triage (Triage bot)\r\n"));
+        assert!(events[0].contains("UID:schedule-7-"));
+
+        let capped = schedule_events(&schedule("* * * * *"), "Bot", Duration::minutes(1), now, now + Duration::days(7));
+        assert_eq!(capped.len(), MAX_EVENTS_PER_SCHEDULE);
+    }
+
+    #[test]
+    fn folds_long_lines() {
+        let folded = fold_line(&format!("DESCRIPTION:{}", "x".repeat(100)));
+        let lines: Vec<&str> = folded.split("\r\n").filter(|l| !l.is_empty()).collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].len(), 75);
+        assert!(lines[1].starts_with(' '));
+    }
+}
@@ -0,0 +1,711 @@
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Timelike, Utc};
+use log::{error, info, warn};
+use rusqlite::{params, Connection, Row};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use super::agents::{get_setting, launch_agent_run, set_setting, AgentDb};
+use super::live_metrics::RunBudget;
+use crate::process::ProcessRegistryState;
+
+/// How often the scheduler checks for due schedules
+const SCHEDULER_TICK_SECS: u64 = 30;
+
+/// Runs that were missed by more than this (e.g. the app was closed) are skipped, not replayed
+const MISSED_RUN_GRACE_MINUTES: i64 = 5;
+
+/// `app_settings` key set while scheduled runs are paused
+const PAUSED_KEY: &str = "scheduler_paused";
+
+const SCHEDULE_COLUMNS: &str = "id, agent_id, name, cron_expression, project_paths, task, model, enabled, last_run_at, next_run_at, last_error, created_at, updated_at";
+
+/// A parsed five-field cron expression (minute hour day-of-month month day-of-week)
+#[derive(Debug, Clone, PartialEq)]
+pub struct CronExpression {
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    day_of_month_restricted: bool,
+    day_of_week_restricted: bool,
+}
+
+impl CronExpression {
+    /// Parse a cron expression, also accepting `@hourly`, `@daily`, `@weekly`, `@monthly` and `@yearly`
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let expanded = match expression.trim() {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            other => other,
+        };
+
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(format!(
+                "Invalid cron expression '{}': expected 5 fields (minute hour day-of-month month day-of-week)",
+                expression
+            ));
+        }
+
+        let mut days_of_week = parse_cron_field(fields[4], 0, 7, "day-of-week")?;
+        // 7 is an alias for Sunday
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week | 1) & !(1 << 7);
+        }
+
+        Ok(Self {
+            minutes: parse_cron_field(fields[0], 0, 59, "minute")?,
+            hours: parse_cron_field(fields[1], 0, 23, "hour")?,
+            days_of_month: parse_cron_field(fields[2], 1, 31, "day-of-month")?,
+            months: parse_cron_field(fields[3], 1, 12, "month")?,
+            days_of_week,
+            day_of_month_restricted: !fields[2].starts_with('*'),
+            day_of_week_restricted: !fields[4].starts_with('*'),
+        })
+    }
+
+    /// Like cron, a day matches either field when both day-of-month and day-of-week are restricted
+    fn day_matches(&self, date: NaiveDate) -> bool {
+        let dom = self.days_of_month & (1 << date.day()) != 0;
+        let dow = self.days_of_week & (1 << date.weekday().num_days_from_sunday()) != 0;
+        if self.day_of_month_restricted && self.day_of_week_restricted {
+            dom || dow
+        } else {
+            dom && dow
+        }
+    }
+
+    /// Find the first matching minute strictly after `after`
+    pub fn next_after<Tz: TimeZone>(&self, after: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        let tz = after.timezone();
+        let start = after.naive_local().with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        // Five years covers schedules that only fire on February 29th
+        let limit = start + Duration::days(366 * 5);
+        let mut t = start;
+
+        while t < limit {
+            if self.months & (1 << t.month()) == 0 {
+                let (year, month) = if t.month() == 12 {
+                    (t.year() + 1, 1)
+                } else {
+                    (t.year(), t.month() + 1)
+                };
+                t = start_of_day(NaiveDate::from_ymd_opt(year, month, 1)?)?;
+                continue;
+            }
+            if !self.day_matches(t.date()) {
+                t = start_of_day(t.date().succ_opt()?)?;
+                continue;
+            }
+            if self.hours & (1 << t.hour()) == 0 {
+                t = t.with_minute(0)? + Duration::hours(1);
+                continue;
+            }
+            if self.minutes & (1 << t.minute()) == 0 {
+                t += Duration::minutes(1);
+                continue;
+            }
+            // Local times skipped by a DST transition don't exist; keep searching
+            if let Some(next) = tz.from_local_datetime(&t).earliest() {
+                return Some(next);
+            }
+            t += Duration::minutes(1);
+        }
+
+        None
+    }
+}
+
+fn start_of_day(date: NaiveDate) -> Option<NaiveDateTime> {
+    date.and_hms_opt(0, 0, 0)
+}
+
+/// Parse one cron field (`*`, `n`, `a-b`, lists and `/step`) into a bitmask
+fn parse_cron_field(field: &str, min: u32, max: u32, name: &str) -> Result<u64, String> {
+    let invalid = || format!("Invalid {} field '{}'", name, field);
+    let parse_num = |s: &str| -> Result<u32, String> {
+        let n: u32 = s.parse().map_err(|_| invalid())?;
+        if n < min || n > max {
+            return Err(format!("{} value {} is out of range {}-{}", name, n, min, max));
+        }
+        Ok(n)
+    };
+
+    let mut mask = 0u64;
+    for item in field.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step.parse().map_err(|_| invalid())?;
+                if step == 0 {
+                    return Err(invalid());
+                }
+                (range, Some(step))
+            }
+            None => (item, None),
+        };
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((a, b)) = range.split_once('-') {
+            let (a, b) = (parse_num(a)?, parse_num(b)?);
+            if a > b {
+                return Err(invalid());
+            }
+            (a, b)
+        } else {
+            let a = parse_num(range)?;
+            // `n/step` means "from n to the end of the range"
+            (a, if step.is_some() { max } else { a })
+        };
+
+        let mut value = start;
+        while value <= end {
+            mask |= 1 << value;
+            value += step.unwrap_or(1);
+        }
+    }
+
+    Ok(mask)
+}
+
+/// A recurring agent run
+#[derive(Debug, Serialize, Deserialize, Clone, specta::Type)]
+pub struct AgentSchedule {
+    pub id: Option<i64>,
+    pub agent_id: i64,
+    pub name: String,
+    pub cron_expression: String,
+    pub project_paths: Vec<String>,
+    pub task: Option<String>, // Falls back to the agent's default task
+    pub model: Option<String>,
+    pub enabled: bool,
+    pub last_run_at: Option<String>,
+    pub next_run_at: Option<String>,
+    pub last_error: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Payload of the `agent-schedule-triggered` event
+#[derive(Debug, Serialize, Clone, specta::Type)]
+pub struct ScheduleTriggered {
+    pub schedule_id: i64,
+    pub run_ids: Vec<i64>,
+    pub errors: Vec<String>,
+}
+
+fn row_to_schedule(row: &Row) -> rusqlite::Result<AgentSchedule> {
+    let project_paths: String = row.get(4)?;
+    Ok(AgentSchedule {
+        id: Some(row.get(0)?),
+        agent_id: row.get(1)?,
+        name: row.get(2)?,
+        cron_expression: row.get(3)?,
+        project_paths: serde_json::from_str(&project_paths).unwrap_or_default(),
+        task: row.get(5)?,
+        model: row.get(6)?,
+        enabled: row.get(7)?,
+        last_run_at: row.get(8)?,
+        next_run_at: row.get(9)?,
+        last_error: row.get(10)?,
+        created_at: row.get(11)?,
+        updated_at: row.get(12)?,
+    })
+}
+
+fn load_schedule(conn: &Connection, id: i64) -> Result<AgentSchedule, String> {
+    conn.query_row(
+        &format!("SELECT {} FROM agent_schedules WHERE id = ?1", SCHEDULE_COLUMNS),
+        params![id],
+        row_to_schedule,
+    )
+    .map_err(|e| format!("Failed to load schedule {}: {}", id, e))
+}
+
+/// Compute the next run time (UTC, RFC 3339) for a cron expression evaluated in local time
+pub(crate) fn compute_next_run(cron_expression: &str, after: DateTime<Utc>) -> Result<Option<String>, String> {
+    let cron = CronExpression::parse(cron_expression)?;
+    Ok(cron
+        .next_after(&after.with_timezone(&Local))
+        .map(|next| next.with_timezone(&Utc).to_rfc3339()))
+}
+
+fn validate_schedule_input(
+    conn: &Connection,
+    agent_id: i64,
+    name: &str,
+    cron_expression: &str,
+    project_paths: &[String],
+) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("Schedule name must not be empty".to_string());
+    }
+    CronExpression::parse(cron_expression)?;
+    if project_paths.is_empty() {
+        return Err("A schedule needs at least one project".to_string());
+    }
+    let agent_exists: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM agents WHERE id = ?1",
+            params![agent_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    if agent_exists == 0 {
+        return Err(format!("Agent {} not found", agent_id));
+    }
+    Ok(())
+}
+
+/// All agent schedules, newest first
+pub fn load_schedules(conn: &Connection) -> Result<Vec<AgentSchedule>, String> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM agent_schedules ORDER BY created_at DESC",
+            SCHEDULE_COLUMNS
+        ))
+        .map_err(|e| e.to_string())?;
+
+    let schedules = stmt
+        .query_map([], row_to_schedule)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(schedules)
+}
+
+/// List all agent schedules
+#[tauri::command]
+#[specta::specta]
+pub async fn list_agent_schedules(db: State<'_, AgentDb>) -> Result<Vec<AgentSchedule>, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    load_schedules(&conn)
+}
+
+/// Create a new agent schedule
+#[tauri::command]
+#[specta::specta]
+#[allow(clippy::too_many_arguments)]
+pub async fn create_agent_schedule(
+    db: State<'_, AgentDb>,
+    agent_id: i64,
+    name: String,
+    cron_expression: String,
+    project_paths: Vec<String>,
+    task: Option<String>,
+    model: Option<String>,
+    enabled: Option<bool>,
+) -> Result<AgentSchedule, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    validate_schedule_input(&conn, agent_id, &name, &cron_expression, &project_paths)?;
+
+    let enabled = enabled.unwrap_or(true);
+    let next_run_at = if enabled {
+        compute_next_run(&cron_expression, Utc::now())?
+    } else {
+        None
+    };
+    let project_paths_json = serde_json::to_string(&project_paths).map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "INSERT INTO agent_schedules (agent_id, name, cron_expression, project_paths, task, model, enabled, next_run_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![agent_id, name, cron_expression.trim(), project_paths_json, task, model, enabled, next_run_at],
+    )
+    .map_err(|e| format!("Failed to create schedule: {}", e))?;
+
+    load_schedule(&conn, conn.last_insert_rowid())
+}
+
+/// Update an existing agent schedule
+#[tauri::command]
+#[specta::specta]
+#[allow(clippy::too_many_arguments)]
+pub async fn update_agent_schedule(
+    db: State<'_, AgentDb>,
+    id: i64,
+    agent_id: i64,
+    name: String,
+    cron_expression: String,
+    project_paths: Vec<String>,
+    task: Option<String>,
+    model: Option<String>,
+    enabled: bool,
+) -> Result<AgentSchedule, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    validate_schedule_input(&conn, agent_id, &name, &cron_expression, &project_paths)?;
+
+    let next_run_at = if enabled {
+        compute_next_run(&cron_expression, Utc::now())?
+    } else {
+        None
+    };
+    let project_paths_json = serde_json::to_string(&project_paths).map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "UPDATE agent_schedules SET agent_id = ?1, name = ?2, cron_expression = ?3, project_paths = ?4, task = ?5, model = ?6, enabled = ?7, next_run_at = ?8 WHERE id = ?9",
+        params![agent_id, name, cron_expression.trim(), project_paths_json, task, model, enabled, next_run_at, id],
+    )
+    .map_err(|e| format!("Failed to update schedule: {}", e))?;
+
+    load_schedule(&conn, id)
+}
+
+/// Enable or disable an agent schedule
+#[tauri::command]
+#[specta::specta]
+pub async fn set_agent_schedule_enabled(
+    db: State<'_, AgentDb>,
+    id: i64,
+    enabled: bool,
+) -> Result<AgentSchedule, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let schedule = load_schedule(&conn, id)?;
+
+    let next_run_at = if enabled {
+        compute_next_run(&schedule.cron_expression, Utc::now())?
+    } else {
+        None
+    };
+
+    conn.execute(
+        "UPDATE agent_schedules SET enabled = ?1, next_run_at = ?2 WHERE id = ?3",
+        params![enabled, next_run_at, id],
+    )
+    .map_err(|e| format!("Failed to update schedule: {}", e))?;
+
+    load_schedule(&conn, id)
+}
+
+/// Delete an agent schedule
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_agent_schedule(db: State<'_, AgentDb>, id: i64) -> Result<(), String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+
+    conn.execute("DELETE FROM agent_schedules WHERE id = ?1", params![id])
+        .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// Run a schedule immediately, without changing its next scheduled time
+#[tauri::command]
+#[specta::specta]
+pub async fn run_agent_schedule_now(app: AppHandle, id: i64) -> Result<Vec<i64>, String> {
+    let schedule = {
+        let db = app.state::<AgentDb>();
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        load_schedule(&conn, id)?
+    };
+
+    let triggered = trigger_schedule(&app, &schedule, false).await?;
+    Ok(triggered.run_ids)
+}
+
+/// Preview the next times a cron expression will fire, in local time
+#[tauri::command]
+#[specta::specta]
+pub async fn preview_cron_schedule(
+    cron_expression: String,
+    count: Option<usize>,
+) -> Result<Vec<String>, String> {
+    let cron = CronExpression::parse(&cron_expression)?;
+
+    let mut times = Vec::new();
+    let mut cursor = Local::now();
+    for _ in 0..count.unwrap_or(5).min(50) {
+        match cron.next_after(&cursor) {
+            Some(next) => {
+                times.push(next.to_rfc3339());
+                cursor = next;
+            }
+            None => break,
+        }
+    }
+
+    Ok(times)
+}
+
+/// Whether scheduled runs are paused; schedules keep their next run times meanwhile
+pub fn schedules_paused(conn: &Connection) -> bool {
+    get_setting(conn, PAUSED_KEY).unwrap_or(false)
+}
+
+/// Pause or resume all scheduled runs
+///
+/// Runs that fall due while paused are skipped once resumed, unless they are still
+/// within the missed-run grace period.
+pub fn set_schedules_paused(app: &AppHandle, paused: bool) -> Result<(), String> {
+    {
+        let db = app.state::<AgentDb>();
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        set_setting(&conn, PAUSED_KEY, &paused)
+            .map_err(|e| format!("Failed to save scheduler state: {}", e))?;
+    }
+    info!("Scheduled runs {}", if paused { "paused" } else { "resumed" });
+    let _ = app.emit("scheduler-paused", paused);
+    super::tray::refresh_tray(app);
+    Ok(())
+}
+
+/// Whether scheduled runs are paused
+#[tauri::command]
+#[specta::specta]
+pub async fn get_scheduler_paused(db: State<'_, AgentDb>) -> Result<bool, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    Ok(schedules_paused(&conn))
+}
+
+/// Pause or resume all scheduled runs
+#[tauri::command]
+#[specta::specta]
+pub async fn set_scheduler_paused(app: AppHandle, paused: bool) -> Result<(), String> {
+    set_schedules_paused(&app, paused)
+}
+
+/// Start the background task that runs due schedules
+pub fn start_scheduler(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        info!("Starting agent scheduler (tick every {}s)", SCHEDULER_TICK_SECS);
+        let mut interval =
+            tokio::time::interval(std::time::Duration::from_secs(SCHEDULER_TICK_SECS));
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = run_due_schedules(&app).await {
+                error!("Scheduler tick failed: {}", e);
+            }
+        }
+    });
+}
+
+/// Trigger every enabled schedule whose next run time has passed
+async fn run_due_schedules(app: &AppHandle) -> Result<(), String> {
+    let now = Utc::now();
+
+    let schedules = {
+        let db = app.state::<AgentDb>();
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        if schedules_paused(&conn) {
+            return Ok(());
+        }
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT {} FROM agent_schedules WHERE enabled = 1",
+                SCHEDULE_COLUMNS
+            ))
+            .map_err(|e| e.to_string())?;
+        let schedules = stmt
+            .query_map([], row_to_schedule)
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?;
+        schedules
+    };
+
+    for schedule in schedules {
+        let schedule_id = schedule.id.unwrap_or_default();
+        let next_run = schedule
+            .next_run_at
+            .as_deref()
+            .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+            .map(|dt| dt.with_timezone(&Utc));
+
+        let rescheduled = match next_run {
+            Some(next_run) if next_run > now => Ok(()),
+            Some(next_run) if now - next_run > Duration::minutes(MISSED_RUN_GRACE_MINUTES) => {
+                warn!(
+                    "Schedule {} missed its run at {}, skipping to the next one",
+                    schedule_id, next_run
+                );
+                reschedule(app, &schedule, now, None, None)
+            }
+            Some(_) => {
+                info!("Schedule {} ('{}') is due", schedule_id, schedule.name);
+                match trigger_schedule(app, &schedule, true).await {
+                    Ok(_) => Ok(()),
+                    Err(e) => {
+                        error!("Failed to trigger schedule {}: {}", schedule_id, e);
+                        reschedule(app, &schedule, now, None, Some(e))
+                    }
+                }
+            }
+            // Never computed (or unparseable); fix it up without running
+            None => reschedule(app, &schedule, now, None, None),
+        };
+        // One broken schedule, e.g. with an invalid cron expression, must not hold up the rest
+        if let Err(e) = rescheduled {
+            error!("Failed to reschedule schedule {}: {}", schedule_id, e);
+        }
+    }
+
+    Ok(())
+}
+
+/// Store the next run time and, when a run happened, its timestamp and error
+fn reschedule(
+    app: &AppHandle,
+    schedule: &AgentSchedule,
+    now: DateTime<Utc>,
+    last_run_at: Option<String>,
+    last_error: Option<String>,
+) -> Result<(), String> {
+    let next_run_at = compute_next_run(&schedule.cron_expression, now)?;
+    let db = app.state::<AgentDb>();
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "UPDATE agent_schedules SET next_run_at = ?1, last_run_at = COALESCE(?2, last_run_at), last_error = CASE WHEN ?2 IS NULL AND ?3 IS NULL THEN last_error ELSE ?3 END WHERE id = ?4",
+        params![next_run_at, last_run_at, last_error, schedule.id],
+    )
+    .map_err(|e| format!("Failed to update schedule: {}", e))?;
+
+    Ok(())
+}
+
+/// Start one agent run per project of the schedule
+async fn trigger_schedule(
+    app: &AppHandle,
+    schedule: &AgentSchedule,
+    advance: bool,
+) -> Result<ScheduleTriggered, String> {
+    let schedule_id = schedule.id.ok_or("Schedule has no id")?;
+
+    let task = match schedule.task.as_deref() {
+        Some(task) if !task.trim().is_empty() => task.to_string(),
+        _ => {
+            let db = app.state::<AgentDb>();
+            let conn = db.0.lock().map_err(|e| e.to_string())?;
+            conn.query_row(
+                "SELECT default_task FROM agents WHERE id = ?1",
+                params![schedule.agent_id],
+                |row| row.get::<_, Option<String>>(0),
+            )
+            .map_err(|e| format!("Failed to load agent {}: {}", schedule.agent_id, e))?
+            .filter(|task| !task.trim().is_empty())
+            .ok_or_else(|| {
+                format!(
+                    "Schedule '{}' has no task and its agent has no default task",
+                    schedule.name
+                )
+            })?
+        }
+    };
+
+    let mut run_ids = Vec::new();
+    let mut errors = Vec::new();
+    for project_path in &schedule.project_paths {
+        let result = launch_agent_run(
+            app.clone(),
+            schedule.agent_id,
+            project_path.clone(),
+            task.clone(),
+            schedule.model.clone(),
+            "normal",
+            RunBudget::default(),
+            Some(schedule_id),
+            app.state::<AgentDb>(),
+            app.state::<ProcessRegistryState>(),
+        )
+        .await;
+
+        match result {
+            Ok(run_id) => {
+                info!(
+                    "Schedule {} started run {} in {}",
+                    schedule_id, run_id, project_path
+                );
+                run_ids.push(run_id);
+            }
+            Err(e) => {
+                error!(
+                    "Schedule {} failed to start in {}: {}",
+                    schedule_id, project_path, e
+                );
+                errors.push(format!("{}: {}", project_path, e));
+            }
+        }
+    }
+
+    let now = Utc::now();
+    let last_error = if errors.is_empty() {
+        None
+    } else {
+        Some(errors.join("\n"))
+    };
+    if advance {
+        reschedule(app, schedule, now, Some(now.to_rfc3339()), last_error)?;
+    } else {
+        let db = app.state::<AgentDb>();
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        conn.execute(
+            "UPDATE agent_schedules SET last_run_at = ?1, last_error = ?2 WHERE id = ?3",
+            params![now.to_rfc3339(), last_error, schedule_id],
+        )
+        .map_err(|e| format!("Failed to update schedule: {}", e))?;
+    }
+
+    let triggered = ScheduleTriggered {
+        schedule_id,
+        run_ids,
+        errors,
+    };
+    let _ = app.emit("agent-schedule-triggered", &triggered);
+
+    Ok(triggered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_cron_parse_rejects_invalid() {
+        assert!(CronExpression::parse("* * * *").is_err());
+        assert!(CronExpression::parse("60 * * * *").is_err());
+        assert!(CronExpression::parse("*/0 * * * *").is_err());
+        assert!(CronExpression::parse("5-1 * * * *").is_err());
+        assert!(CronExpression::parse("@daily").is_ok());
+    }
+
+    #[test]
+    fn test_cron_next_after() {
+        let nightly = CronExpression::parse("30 2 * * *").unwrap();
+        assert_eq!(
+            nightly.next_after(&utc("2024-03-10T02:30:00Z")),
+            Some(utc("2024-03-11T02:30:00Z"))
+        );
+
+        let every_15 = CronExpression::parse("*/15 9-17 * * 1-5").unwrap();
+        // Friday evening rolls over to Monday morning
+        assert_eq!(
+            every_15.next_after(&utc("2024-03-08T17:50:00Z")),
+            Some(utc("2024-03-11T09:00:00Z"))
+        );
+
+        let leap_day = CronExpression::parse("0 0 29 2 *").unwrap();
+        assert_eq!(
+            leap_day.next_after(&utc("2024-03-01T00:00:00Z")),
+            Some(utc("2028-02-29T00:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn test_cron_day_of_month_or_day_of_week() {
+        // Both restricted: the 1st of the month or any Sunday (7 is Sunday too)
+        let cron = CronExpression::parse("0 0 1 * 7").unwrap();
+        assert_eq!(
+            cron.next_after(&utc("2024-03-01T00:00:00Z")),
+            Some(utc("2024-03-03T00:00:00Z"))
+        );
+    }
+}
@@ -0,0 +1,244 @@
+use log::{error, info};
+use std::collections::{HashMap, HashSet};
+
+/// Keychain service all Claudia secrets are stored under
+const KEYCHAIN_SERVICE: &str = "claudia";
+
+/// Keychain entry holding the secret names, since keychains can't be enumerated portably
+const NAME_INDEX_ACCOUNT: &str = "__claudia_secret_names__";
+
+/// Accounts Claudia keeps its own credentials under, which secrets must not replace
+const RESERVED_NAMES: &[&str] = &[
+    NAME_INDEX_ACCOUNT,
+    super::email::PASSWORD_ACCOUNT,
+    super::jira::TOKEN_ACCOUNT,
+    super::linear::API_KEY_ACCOUNT,
+];
+
+/// Prefixes of internal index accounts and of legacy webhook signing key accounts
+const RESERVED_PREFIXES: &[&str] = &["__claudia_", "webhook_secret_"];
+
+fn keychain_entry(account: &str) -> Result<keyring::Entry, String> {
+    keyring::Entry::new(KEYCHAIN_SERVICE, account)
+        .map_err(|e| format!("Failed to open keychain entry: {}", e))
+}
+
+/// Secret names double as environment variable names, so they follow the same rules
+fn validate_secret_name(name: &str) -> Result<(), String> {
+    let mut chars = name.chars();
+    let valid_start = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_');
+    if !valid_start || !chars.all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(format!(
+            "Invalid secret name '{}': use letters, digits and underscores, not starting with a digit",
+            name
+        ));
+    }
+    if RESERVED_NAMES.contains(&name) || RESERVED_PREFIXES.iter().any(|p| name.starts_with(p)) {
+        return Err(format!("'{}' is reserved for Claudia's own credentials", name));
+    }
+    Ok(())
+}
+
+/// Read a keychain value of Claudia's service; `None` if it was never stored
+pub fn read_keychain_value(account: &str) -> Result<Option<String>, String> {
+    match keychain_entry(account)?.get_password() {
+        Ok(value) => Ok(Some(value)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(format!("Failed to read {} from keychain: {}", account, e)),
+    }
+}
+
+pub fn write_keychain_value(account: &str, value: &str) -> Result<(), String> {
+    keychain_entry(account)?
+        .set_password(value)
+        .map_err(|e| format!("Failed to store {} in keychain: {}", account, e))
+}
+
+pub fn delete_keychain_value(account: &str) -> Result<(), String> {
+    match keychain_entry(account)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(format!("Failed to delete {} from keychain: {}", account, e)),
+    }
+}
+
+pub(crate) fn load_secret_names() -> Result<Vec<String>, String> {
+    match read_keychain_value(NAME_INDEX_ACCOUNT)? {
+        Some(names) => {
+            serde_json::from_str(&names).map_err(|e| format!("Failed to parse secret index: {}", e))
+        }
+        None => Ok(Vec::new()),
+    }
+}
+
+fn save_secret_names(names: &[String]) -> Result<(), String> {
+    let names = serde_json::to_string(names).map_err(|e| e.to_string())?;
+    write_keychain_value(NAME_INDEX_ACCOUNT, &names)
+}
+
+/// Write a secret's value and add it to the name index
+pub(crate) fn store_secret(name: &str, value: &str) -> Result<(), String> {
+    validate_secret_name(name)?;
+    write_keychain_value(name, value)?;
+
+    let mut names = load_secret_names()?;
+    if !names.iter().any(|n| n == name) {
+        names.push(name.to_string());
+        names.sort();
+        save_secret_names(&names)?;
+    }
+    info!("Stored secret {}", name);
+    Ok(())
+}
+
+/// Names referenced as `${NAME}` or `${NAME:-default}` in `texts`
+fn referenced_names<'a>(texts: impl IntoIterator<Item = &'a str>) -> HashSet<String> {
+    let reference = regex::Regex::new(r"\$\{([A-Za-z_][A-Za-z0-9_]*)(?::-[^}]*)?\}")
+        .expect("valid reference pattern");
+    texts
+        .into_iter()
+        .flat_map(|text| reference.captures_iter(text))
+        .map(|caps| caps[1].to_string())
+        .collect()
+}
+
+/// Stored secrets and MCP OAuth tokens whose names are in `referenced`
+fn referenced_secrets(referenced: &HashSet<String>) -> Vec<(String, String)> {
+    if referenced.is_empty() {
+        return Vec::new();
+    }
+    let names = match load_secret_names() {
+        Ok(names) => names,
+        Err(e) => {
+            error!("{}", e);
+            return Vec::new();
+        }
+    };
+
+    let mut env: Vec<(String, String)> = names
+        .into_iter()
+        .filter(|name| referenced.contains(name))
+        .filter_map(|name| match read_keychain_value(&name) {
+            Ok(value) => value.map(|value| (name, value)),
+            Err(e) => {
+                error!("{}", e);
+                None
+            }
+        })
+        .collect();
+    env.extend(
+        super::mcp_oauth::oauth_token_env()
+            .into_iter()
+            .filter(|(name, _)| referenced.contains(name)),
+    );
+    env
+}
+
+/// Secrets a Claude process started in `project_path` needs, as environment variables
+///
+/// Claude Code expands `${NAME}` in MCP server env values, so the secrets referenced by
+/// the servers it loads, or by the project's environment profile, are exported. Other
+/// secrets stay in the keychain.
+pub fn secret_env(project_path: &str, project_env: &[(String, String)]) -> Vec<(String, String)> {
+    let configs: Vec<String> = super::mcp::mcp_server_configs(project_path)
+        .iter()
+        .map(|config| config.to_string())
+        .collect();
+    let referenced = referenced_names(
+        configs
+            .iter()
+            .map(String::as_str)
+            .chain(project_env.iter().map(|(_, value)| value.as_str())),
+    );
+    referenced_secrets(&referenced)
+}
+
+/// Replace `${NAME}` references to stored secrets in env values
+///
+/// Used when Claudia itself launches an MCP server, e.g. for connection tests.
+pub fn resolve_secret_references(env: &mut HashMap<String, String>) {
+    let referenced = referenced_names(env.values().map(String::as_str));
+    let secrets = referenced_secrets(&referenced);
+    for value in env.values_mut() {
+        for (name, secret) in &secrets {
+            *value = value.replace(&format!("${{{}}}", name), secret);
+        }
+    }
+}
+
+/// List the names of stored secrets; values never leave the keychain through this
+#[tauri::command]
+#[specta::specta]
+pub async fn list_secrets() -> Result<Vec<String>, String> {
+    tokio::task::spawn_blocking(load_secret_names)
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Store a secret, replacing (rotating) its value if it already exists
+///
+/// Reference it from an MCP server's environment as `${NAME}`.
+#[tauri::command]
+#[specta::specta]
+pub async fn set_secret(name: String, value: String) -> Result<(), String> {
+    validate_secret_name(&name)?;
+    if value.is_empty() {
+        return Err("Secret value cannot be empty".to_string());
+    }
+
+    tokio::task::spawn_blocking(move || store_secret(&name, &value))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Delete a secret from the keychain
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_secret(name: String) -> Result<(), String> {
+    validate_secret_name(&name)?;
+    tokio::task::spawn_blocking(move || {
+        delete_keychain_value(&name)?;
+
+        let mut names = load_secret_names()?;
+        names.retain(|n| n != &name);
+        save_secret_names(&names)?;
+        info!("Deleted secret {}", name);
+        Ok(())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_referenced_names() {
+        let names = referenced_names([
+            r#"{"env":{"TOKEN":"${GITHUB_TOKEN}","URL":"${API_URL:-http://localhost}"}}"#,
+            "plain $HOME and ${not-a-name}",
+        ]);
+        assert_eq!(
+            names,
+            HashSet::from(["GITHUB_TOKEN".to_string(), "API_URL".to_string()])
+        );
+    }
+
+    #[test]
+    fn rejects_reserved_names() {
+        for name in [
+            "__claudia_secret_names__",
+            "__claudia_mcp_oauth_servers__",
+            "jira_api_token",
+            "linear_api_key",
+            "smtp_password",
+            "webhook_secret_1",
+        ] {
+            assert!(validate_secret_name(name).unwrap_err().contains("reserved"), "{}", name);
+        }
+        assert!(validate_secret_name("GITHUB_TOKEN").is_ok());
+        assert!(validate_secret_name("webhook:1").is_err());
+    }
+}
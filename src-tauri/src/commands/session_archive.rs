@@ -0,0 +1,358 @@
+use log::info;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tauri::State;
+
+use super::agents::AgentDb;
+use super::claude::get_claude_dir;
+
+/// zstd level for archived transcripts; they are written once and rarely read
+const ARCHIVE_COMPRESSION_LEVEL: i32 = 9;
+
+/// Extension appended to a transcript when it is archived
+const ARCHIVE_EXTENSION: &str = "zst";
+
+/// An archived session transcript
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct SessionArchive {
+    pub project_id: String,
+    pub session_id: String,
+    pub original_size: u64,
+    pub compressed_size: u64,
+    pub archived_at: String,
+}
+
+/// Space taken by archived sessions compared to their original transcripts
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct ArchiveStats {
+    pub archived_sessions: u64,
+    pub original_bytes: u64,
+    pub compressed_bytes: u64,
+    pub saved_bytes: u64,
+    /// Compressed size as a fraction of the original size
+    pub ratio: f64,
+}
+
+/// Where the archived copy of a transcript lives, e.g. `abc.jsonl.zst` for `abc.jsonl`
+pub fn archived_path(transcript: &Path) -> PathBuf {
+    let mut name = transcript.as_os_str().to_os_string();
+    name.push(".");
+    name.push(ARCHIVE_EXTENSION);
+    PathBuf::from(name)
+}
+
+/// Session id of an archived transcript's file name
+pub fn archived_session_id(path: &Path) -> Option<&str> {
+    path.file_name()?
+        .to_str()?
+        .strip_suffix(&format!(".jsonl.{}", ARCHIVE_EXTENSION))
+}
+
+fn transcript_path(project_id: &str, session_id: &str) -> Result<PathBuf, String> {
+    Ok(get_claude_dir()
+        .map_err(|e| e.to_string())?
+        .join("projects")
+        .join(project_id)
+        .join(format!("{}.jsonl", session_id)))
+}
+
+/// Compress `source` into `target`, keeping its modification time
+///
+/// Writes to a temporary file first so an interrupted run never leaves a truncated archive.
+fn compress_file(source: &Path, target: &Path) -> Result<(u64, u64), String> {
+    let metadata =
+        fs::metadata(source).map_err(|e| format!("Failed to read {:?}: {}", source, e))?;
+    let partial = target.with_extension("partial");
+    let result = (|| -> std::io::Result<u64> {
+        let mut input = BufReader::new(File::open(source)?);
+        let mut encoder = zstd::stream::Encoder::new(
+            BufWriter::new(File::create(&partial)?),
+            ARCHIVE_COMPRESSION_LEVEL,
+        )?;
+        encoder.include_contentsize(true)?;
+        encoder.set_pledged_src_size(Some(metadata.len()))?;
+        std::io::copy(&mut input, &mut encoder)?;
+        let file = encoder.finish()?.into_inner().map_err(|e| e.into_error())?;
+        file.sync_all()?;
+        if let Ok(modified) = metadata.modified() {
+            file.set_modified(modified)?;
+        }
+        Ok(file.metadata()?.len())
+    })();
+    let compressed_size = match result {
+        Ok(size) => size,
+        Err(e) => {
+            let _ = fs::remove_file(&partial);
+            return Err(format!("Failed to compress {:?}: {}", source, e));
+        }
+    };
+    fs::rename(&partial, target)
+        .map_err(|e| format!("Failed to move archive into place: {}", e))?;
+    Ok((metadata.len(), compressed_size))
+}
+
+fn decompress_file(
+    source: &Path,
+    target: &Path,
+    modified: Option<SystemTime>,
+) -> Result<(), String> {
+    let partial = target.with_extension("partial");
+    let result = (|| -> std::io::Result<()> {
+        let mut decoder = zstd::stream::Decoder::new(File::open(source)?)?;
+        let mut output = BufWriter::new(File::create(&partial)?);
+        std::io::copy(&mut decoder, &mut output)?;
+        let file = output.into_inner().map_err(|e| e.into_error())?;
+        file.sync_all()?;
+        if let Some(modified) = modified {
+            file.set_modified(modified)?;
+        }
+        Ok(())
+    })();
+    if let Err(e) = result {
+        let _ = fs::remove_file(&partial);
+        return Err(format!("Failed to decompress {:?}: {}", source, e));
+    }
+    fs::rename(&partial, target).map_err(|e| format!("Failed to restore transcript: {}", e))
+}
+
+/// Compress a transcript and record it; the database is only locked once the file is written
+fn archive_one(db: &AgentDb, project_id: &str, session_id: &str) -> Result<SessionArchive, String> {
+    let transcript = transcript_path(project_id, session_id)?;
+    if !transcript.exists() {
+        return Err(format!("Session file not found: {}", session_id));
+    }
+    let archive = archived_path(&transcript);
+    let (original_size, compressed_size) = compress_file(&transcript, &archive)?;
+
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO session_archives (project_id, session_id, original_size, compressed_size)
+         VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT(project_id, session_id) DO UPDATE SET
+            original_size = ?3, compressed_size = ?4, archived_at = CURRENT_TIMESTAMP",
+        params![
+            project_id,
+            session_id,
+            original_size as i64,
+            compressed_size as i64
+        ],
+    )
+    .map_err(|e| format!("Failed to record archive: {}", e))?;
+    fs::remove_file(&transcript)
+        .map_err(|e| format!("Failed to remove archived transcript: {}", e))?;
+    info!(
+        "Archived session {} ({} -> {} bytes)",
+        session_id, original_size, compressed_size
+    );
+    load_archive(&conn, project_id, session_id)
+}
+
+fn load_archive(
+    conn: &Connection,
+    project_id: &str,
+    session_id: &str,
+) -> Result<SessionArchive, String> {
+    conn.query_row(
+        "SELECT project_id, session_id, original_size, compressed_size, archived_at
+         FROM session_archives WHERE project_id = ?1 AND session_id = ?2",
+        params![project_id, session_id],
+        row_to_archive,
+    )
+    .map_err(|e| format!("Archive not found: {}", e))
+}
+
+fn row_to_archive(row: &rusqlite::Row) -> rusqlite::Result<SessionArchive> {
+    Ok(SessionArchive {
+        project_id: row.get(0)?,
+        session_id: row.get(1)?,
+        original_size: row.get::<_, i64>(2)? as u64,
+        compressed_size: row.get::<_, i64>(3)? as u64,
+        archived_at: row.get(4)?,
+    })
+}
+
+/// Compress a session transcript to `<session>.jsonl.zst`; it keeps loading as before
+#[tauri::command]
+#[specta::specta]
+pub async fn archive_session(
+    db: State<'_, AgentDb>,
+    project_id: String,
+    session_id: String,
+) -> Result<SessionArchive, String> {
+    archive_one(&db, &project_id, &session_id)
+}
+
+/// Archive every session not written to for `older_than_days` days
+#[tauri::command]
+#[specta::specta]
+pub async fn archive_old_sessions(
+    db: State<'_, AgentDb>,
+    older_than_days: u64,
+) -> Result<Vec<SessionArchive>, String> {
+    let cutoff = SystemTime::now() - Duration::from_secs(older_than_days.max(1) * 24 * 60 * 60);
+    let projects_dir = get_claude_dir()
+        .map_err(|e| e.to_string())?
+        .join("projects");
+    let mut stale = Vec::new();
+    for project in fs::read_dir(&projects_dir)
+        .map_err(|e| e.to_string())?
+        .flatten()
+    {
+        let project_id = project.file_name().to_string_lossy().to_string();
+        let Ok(entries) = fs::read_dir(project.path()) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|s| s.to_str()) != Some("jsonl") {
+                continue;
+            }
+            let modified = entry.metadata().and_then(|m| m.modified());
+            if modified.is_ok_and(|m| m < cutoff) {
+                if let Some(session_id) = path.file_stem().and_then(|s| s.to_str()) {
+                    stale.push((project_id.clone(), session_id.to_string()));
+                }
+            }
+        }
+    }
+
+    let mut archived = Vec::new();
+    for (project_id, session_id) in stale {
+        match archive_one(&db, &project_id, &session_id) {
+            Ok(archive) => archived.push(archive),
+            Err(e) => log::warn!("Skipping session {}: {}", session_id, e),
+        }
+    }
+    Ok(archived)
+}
+
+fn unarchive_one(db: &AgentDb, project_id: &str, session_id: &str) -> Result<(), String> {
+    let transcript = transcript_path(project_id, session_id)?;
+    let archive = archived_path(&transcript);
+    if !archive.exists() {
+        return Err(format!("Session {} is not archived", session_id));
+    }
+    // Restoring the original time lets the usage index recognise the file as unchanged
+    let modified = fs::metadata(&archive).and_then(|m| m.modified()).ok();
+    decompress_file(&archive, &transcript, modified)?;
+    fs::remove_file(&archive).map_err(|e| format!("Failed to remove archive: {}", e))?;
+
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    conn.execute(
+        "DELETE FROM session_archives WHERE project_id = ?1 AND session_id = ?2",
+        params![project_id, session_id],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Decompress a session that is about to be resumed, since Claude only reads plain transcripts
+pub fn unarchive_for_resume(db: &AgentDb, session_id: &str) -> Result<(), String> {
+    let projects_dir = get_claude_dir()
+        .map_err(|e| e.to_string())?
+        .join("projects");
+    let Ok(projects) = fs::read_dir(&projects_dir) else {
+        return Ok(());
+    };
+    for project in projects.flatten() {
+        let transcript = project.path().join(format!("{}.jsonl", session_id));
+        if !transcript.exists() && archived_path(&transcript).exists() {
+            let project_id = project.file_name().to_string_lossy().to_string();
+            return unarchive_one(db, &project_id, session_id);
+        }
+    }
+    Ok(())
+}
+
+/// Decompress an archived session back to a plain transcript
+#[tauri::command]
+#[specta::specta]
+pub async fn unarchive_session(
+    db: State<'_, AgentDb>,
+    project_id: String,
+    session_id: String,
+) -> Result<(), String> {
+    unarchive_one(&db, &project_id, &session_id)
+}
+
+/// List archived sessions, most recently archived first
+#[tauri::command]
+#[specta::specta]
+pub async fn list_archived_sessions(db: State<'_, AgentDb>) -> Result<Vec<SessionArchive>, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT project_id, session_id, original_size, compressed_size, archived_at
+             FROM session_archives ORDER BY archived_at DESC",
+        )
+        .map_err(|e| e.to_string())?;
+    let archives = stmt
+        .query_map([], row_to_archive)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(archives)
+}
+
+/// Space saved by archiving sessions
+#[tauri::command]
+#[specta::specta]
+pub async fn get_archive_stats(db: State<'_, AgentDb>) -> Result<ArchiveStats, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let (archived_sessions, original_bytes, compressed_bytes): (i64, i64, i64) = conn
+        .query_row(
+            "SELECT COUNT(*), COALESCE(SUM(original_size), 0), COALESCE(SUM(compressed_size), 0)
+             FROM session_archives",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .map_err(|e| e.to_string())?;
+    let (original_bytes, compressed_bytes) = (original_bytes as u64, compressed_bytes as u64);
+    Ok(ArchiveStats {
+        archived_sessions: archived_sessions as u64,
+        original_bytes,
+        compressed_bytes,
+        saved_bytes: original_bytes.saturating_sub(compressed_bytes),
+        ratio: if original_bytes == 0 {
+            1.0
+        } else {
+            compressed_bytes as f64 / original_bytes as f64
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compresses_and_restores_transcripts() {
+        let dir = tempfile::tempdir().unwrap();
+        let transcript = dir.path().join("abc.jsonl");
+        let content = "{\"type\":\"user\",\"message\":{\"content\":\"hello\"}}\n".repeat(500);
+        fs::write(&transcript, &content).unwrap();
+        let modified = fs::metadata(&transcript).unwrap().modified().unwrap();
+
+        let archive = archived_path(&transcript);
+        assert_eq!(archived_session_id(&archive), Some("abc"));
+        let (original, compressed) = compress_file(&transcript, &archive).unwrap();
+        assert_eq!(original, content.len() as u64);
+        assert!(compressed < original / 10);
+        assert_eq!(
+            fs::metadata(&archive).unwrap().modified().unwrap(),
+            modified
+        );
+
+        let restored = dir.path().join("restored.jsonl");
+        decompress_file(&archive, &restored, Some(modified)).unwrap();
+        assert_eq!(fs::read_to_string(&restored).unwrap(), content);
+        assert_eq!(
+            fs::metadata(&restored).unwrap().modified().unwrap(),
+            modified
+        );
+    }
+}
@@ -0,0 +1,379 @@
+use log::{info, warn};
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+use tauri::State;
+
+use super::agents::AgentDb;
+use super::jsonl_reader::{jsonl_lines, JsonlFile};
+use super::session_archive::archived_path;
+use super::usage_index::{complete_lines, transcript_files};
+
+/// A file a tool call read or wrote
+#[derive(Debug, Clone, PartialEq)]
+struct FileTouch {
+    session_id: String,
+    file_path: String,
+    access: &'static str,
+    tool: String,
+    timestamp: Option<String>,
+}
+
+/// A session that touched a file
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct FileSessionMatch {
+    pub session_id: String,
+    pub project_path: String,
+    pub file_path: String,
+    pub read: bool,
+    pub written: bool,
+    pub touch_count: i64,
+    pub first_touched_at: Option<String>,
+    pub last_touched_at: Option<String>,
+}
+
+/// Whether a tool reads or writes the file in its input, and the input field naming it
+fn tool_access(tool: &str) -> Option<(&'static str, &'static str)> {
+    match tool {
+        "Read" => Some(("read", "file_path")),
+        "NotebookRead" => Some(("read", "notebook_path")),
+        "Write" | "Edit" | "MultiEdit" => Some(("write", "file_path")),
+        "NotebookEdit" => Some(("write", "notebook_path")),
+        _ => None,
+    }
+}
+
+/// Files read or written by tool calls in transcript lines
+///
+/// Relative paths are resolved against the session's `cwd`, which `project_path`
+/// carries across appended chunks like the usage index does.
+fn parse_file_touches(
+    content: &[u8],
+    session_id: &str,
+    project_path: &mut Option<String>,
+) -> Vec<FileTouch> {
+    let mut touches = Vec::new();
+    for line in jsonl_lines(content) {
+        let Ok(entry) = serde_json::from_str::<serde_json::Value>(&line) else {
+            continue;
+        };
+        if project_path.is_none() {
+            if let Some(cwd) = entry.get("cwd").and_then(|v| v.as_str()) {
+                *project_path = Some(cwd.to_string());
+            }
+        }
+        let Some(blocks) = entry.pointer("/message/content").and_then(|c| c.as_array()) else {
+            continue;
+        };
+        for block in blocks {
+            if block.get("type").and_then(|t| t.as_str()) != Some("tool_use") {
+                continue;
+            }
+            let tool = block.get("name").and_then(|n| n.as_str()).unwrap_or("");
+            let Some((access, field)) = tool_access(tool) else {
+                continue;
+            };
+            let Some(file) = block
+                .pointer(&format!("/input/{}", field))
+                .and_then(|p| p.as_str())
+            else {
+                continue;
+            };
+            let file_path = match project_path {
+                Some(cwd) if Path::new(file).is_relative() => {
+                    Path::new(cwd).join(file).to_string_lossy().to_string()
+                }
+                _ => file.to_string(),
+            };
+            touches.push(FileTouch {
+                session_id: entry
+                    .get("sessionId")
+                    .and_then(|s| s.as_str())
+                    .unwrap_or(session_id)
+                    .to_string(),
+                file_path,
+                access,
+                tool: tool.to_string(),
+                timestamp: entry
+                    .get("timestamp")
+                    .and_then(|t| t.as_str())
+                    .map(str::to_string),
+            });
+        }
+    }
+    touches
+}
+
+/// How far a transcript has been indexed
+struct IndexedFile {
+    modified_ms: i64,
+    size: i64,
+    offset: i64,
+    project_path: Option<String>,
+}
+
+/// New touches read from one transcript
+struct FileUpdate {
+    path: String,
+    modified_ms: i64,
+    size: i64,
+    offset: i64,
+    project_path: Option<String>,
+    reset: bool,
+    touches: Vec<FileTouch>,
+}
+
+fn read_file_update(path: &Path, indexed: Option<&IndexedFile>) -> Option<FileUpdate> {
+    let metadata = fs::metadata(path).ok()?;
+    let size = metadata.len() as i64;
+    let modified_ms = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0);
+    if let Some(indexed) = indexed {
+        if indexed.size == size && indexed.modified_ms == modified_ms {
+            return None;
+        }
+    }
+    let reset = indexed.is_none_or(|i| size < i.offset);
+    let (start, mut project_path) = match indexed {
+        Some(indexed) if !reset => (indexed.offset, indexed.project_path.clone()),
+        _ => (0, None),
+    };
+
+    let chunk = match JsonlFile::open_from(path, start as u64) {
+        Ok(chunk) => chunk,
+        Err(e) => {
+            warn!("Failed to read {:?}: {}", path, e);
+            return None;
+        }
+    };
+    let (lines, consumed) = complete_lines(chunk.bytes());
+    let session_id = path
+        .file_stem()
+        .and_then(|n| n.to_str())
+        .unwrap_or("unknown");
+    let touches = parse_file_touches(lines, session_id, &mut project_path);
+    Some(FileUpdate {
+        path: path.to_string_lossy().to_string(),
+        modified_ms,
+        size,
+        offset: start + consumed as i64,
+        project_path,
+        reset,
+        touches,
+    })
+}
+
+/// Bring the index of touched files up to date, parsing only what was appended since the last pass
+pub fn update_session_file_index(db: &AgentDb, claude_path: &Path) -> Result<(), String> {
+    let indexed: HashMap<String, IndexedFile> = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        let mut stmt = conn
+            .prepare("SELECT path, modified_ms, size, offset, project_path FROM session_file_index_files")
+            .map_err(|e| e.to_string())?;
+        let files = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    IndexedFile {
+                        modified_ms: row.get(1)?,
+                        size: row.get(2)?,
+                        offset: row.get(3)?,
+                        project_path: row.get(4)?,
+                    },
+                ))
+            })
+            .map_err(|e| e.to_string())?
+            .collect::<Result<HashMap<_, _>, _>>()
+            .map_err(|e| e.to_string())?;
+        files
+    };
+
+    let files = transcript_files(claude_path);
+    let on_disk: HashSet<String> = files
+        .iter()
+        .map(|(path, _)| path.to_string_lossy().to_string())
+        .collect();
+    let removed: Vec<&String> = indexed
+        .keys()
+        .filter(|p| !on_disk.contains(*p) && !archived_path(Path::new(p)).exists())
+        .collect();
+    let updates: Vec<FileUpdate> = files
+        .iter()
+        .filter_map(|(path, _)| {
+            read_file_update(path, indexed.get(path.to_string_lossy().as_ref()))
+        })
+        .collect();
+    if updates.is_empty() && removed.is_empty() {
+        return Ok(());
+    }
+
+    let mut conn = db.0.lock().map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    for path in &removed {
+        tx.execute(
+            "DELETE FROM session_files WHERE source_path = ?1",
+            params![path],
+        )
+        .map_err(|e| e.to_string())?;
+        tx.execute(
+            "DELETE FROM session_file_index_files WHERE path = ?1",
+            params![path],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    let mut touch_count = 0;
+    for update in &updates {
+        if update.reset {
+            tx.execute(
+                "DELETE FROM session_files WHERE source_path = ?1",
+                params![update.path],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+        let project_path = update.project_path.clone().unwrap_or_default();
+        for touch in &update.touches {
+            tx.execute(
+                "INSERT INTO session_files (
+                    source_path, session_id, project_path, file_path, access, tool,
+                    touch_count, first_touched_at, last_touched_at
+                 ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, 1, ?7, ?7)
+                 ON CONFLICT(source_path, file_path, access) DO UPDATE SET
+                    tool = ?6,
+                    touch_count = touch_count + 1,
+                    first_touched_at = COALESCE(first_touched_at, ?7),
+                    last_touched_at = COALESCE(?7, last_touched_at)",
+                params![
+                    update.path,
+                    touch.session_id,
+                    project_path,
+                    touch.file_path,
+                    touch.access,
+                    touch.tool,
+                    touch.timestamp
+                ],
+            )
+            .map_err(|e| e.to_string())?;
+            touch_count += 1;
+        }
+        tx.execute(
+            "INSERT INTO session_file_index_files (path, modified_ms, size, offset, project_path)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(path) DO UPDATE SET
+                modified_ms = ?2, size = ?3, offset = ?4, project_path = ?5",
+            params![
+                update.path,
+                update.modified_ms,
+                update.size,
+                update.offset,
+                update.project_path
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+
+    info!(
+        "Session file index updated: {} files read, {} removed, {} tool calls",
+        updates.len(),
+        removed.len(),
+        touch_count
+    );
+    Ok(())
+}
+
+/// Find the sessions whose tool calls read or wrote a file, most recent first
+///
+/// An absolute path must match exactly; a relative one matches any file ending with it.
+#[tauri::command]
+#[specta::specta]
+pub async fn find_sessions_for_file(
+    db: State<'_, AgentDb>,
+    path: String,
+    limit: Option<i64>,
+) -> Result<Vec<FileSessionMatch>, String> {
+    let claude_path = dirs::home_dir()
+        .ok_or("Failed to get home directory")?
+        .join(".claude");
+    update_session_file_index(&db, &claude_path)?;
+
+    let relative = Path::new(&path).is_relative();
+    let pattern = format!(
+        "%/{}",
+        path.trim_start_matches("./")
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_")
+    );
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT session_id, project_path, file_path,
+                    MAX(access = 'read'), MAX(access = 'write'), SUM(touch_count),
+                    MIN(first_touched_at), MAX(last_touched_at)
+             FROM session_files
+             WHERE file_path = ?1 OR (?2 AND file_path LIKE ?3 ESCAPE '\\')
+             GROUP BY session_id, file_path
+             ORDER BY MAX(last_touched_at) DESC
+             LIMIT ?4",
+        )
+        .map_err(|e| e.to_string())?;
+    let matches = stmt
+        .query_map(
+            params![path, relative, pattern, limit.unwrap_or(100)],
+            |row| {
+                Ok(FileSessionMatch {
+                    session_id: row.get(0)?,
+                    project_path: row.get(1)?,
+                    file_path: row.get(2)?,
+                    read: row.get(3)?,
+                    written: row.get(4)?,
+                    touch_count: row.get(5)?,
+                    first_touched_at: row.get(6)?,
+                    last_touched_at: row.get(7)?,
+                })
+            },
+        )
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(matches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_reads_and_writes_from_tool_calls() {
+        let transcript = concat!(
+            r#"{"cwd":"/repo","sessionId":"s1","timestamp":"2025-01-01T10:00:00Z","message":{"role":"assistant","content":[{"type":"text","text":"Reading"},{"type":"tool_use","name":"Read","input":{"file_path":"/repo/src/main.rs"}}]}}"#,
+            "\n",
+            r#"{"timestamp":"2025-01-01T10:00:05Z","message":{"role":"assistant","content":[{"type":"tool_use","name":"Edit","input":{"file_path":"src/lib.rs"}},{"type":"tool_use","name":"Bash","input":{"command":"ls"}}]}}"#,
+            "\n"
+        );
+        let mut project_path = None;
+        let touches = parse_file_touches(transcript.as_bytes(), "fallback", &mut project_path);
+        assert_eq!(touches.len(), 2);
+        assert_eq!(touches[0].session_id, "s1");
+        assert_eq!(
+            (touches[0].access, touches[0].file_path.as_str()),
+            ("read", "/repo/src/main.rs")
+        );
+        assert_eq!(touches[1].session_id, "fallback");
+        assert_eq!(
+            (touches[1].access, touches[1].file_path.as_str()),
+            ("write", "/repo/src/lib.rs")
+        );
+        assert_eq!(
+            touches[1].timestamp.as_deref(),
+            Some("2025-01-01T10:00:05Z")
+        );
+    }
+}
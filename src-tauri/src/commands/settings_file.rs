@@ -0,0 +1,485 @@
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::claude::get_claude_dir;
+use super::mcp::write_file_atomically;
+
+/// Backups kept per settings file; older ones are pruned after each write
+const MAX_BACKUPS: usize = 10;
+
+/// Hook events Claude Code fires
+const HOOK_EVENTS: &[&str] = &[
+    "PreToolUse",
+    "PostToolUse",
+    "Notification",
+    "UserPromptSubmit",
+    "Stop",
+    "SubagentStop",
+    "PreCompact",
+    "SessionStart",
+    "SessionEnd",
+];
+
+/// A problem found in a settings file
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, specta::Type)]
+pub struct SettingsIssue {
+    /// JSON path of the offending value, e.g. `permissions.allow[2]`
+    pub path: String,
+    pub message: String,
+    /// Position of a JSON syntax error
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    /// An unknown key or value, e.g. from a newer Claude Code; reported but not rejected
+    #[serde(default)]
+    pub warning: bool,
+}
+
+/// A settings file as read from disk
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct SettingsFile {
+    pub path: String,
+    pub exists: bool,
+    /// The raw file content, so the editor keeps the user's formatting
+    pub content: String,
+    /// The parsed settings, absent when the file is not valid JSON
+    pub data: Option<Value>,
+    pub issues: Vec<SettingsIssue>,
+}
+
+/// Outcome of writing a settings file
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct SettingsWriteResult {
+    pub path: String,
+    /// Copy of the previous content, absent when the file did not exist
+    pub backup_path: Option<String>,
+}
+
+fn issue(path: &str, message: impl Into<String>) -> SettingsIssue {
+    SettingsIssue {
+        path: path.to_string(),
+        message: message.into(),
+        line: None,
+        column: None,
+        warning: false,
+    }
+}
+
+fn warning(path: &str, message: impl Into<String>) -> SettingsIssue {
+    SettingsIssue {
+        warning: true,
+        ..issue(path, message)
+    }
+}
+
+/// The issues that make settings invalid, leaving out warnings
+pub(crate) fn errors(issues: Vec<SettingsIssue>) -> Vec<SettingsIssue> {
+    issues.into_iter().filter(|i| !i.warning).collect()
+}
+
+fn check_string_array(value: &Value, path: &str, issues: &mut Vec<SettingsIssue>) {
+    let Some(items) = value.as_array() else {
+        issues.push(issue(path, "must be an array of strings"));
+        return;
+    };
+    for (i, item) in items.iter().enumerate() {
+        if !item.is_string() {
+            issues.push(issue(&format!("{}[{}]", path, i), "must be a string"));
+        }
+    }
+}
+
+fn check_env(env: &Value, issues: &mut Vec<SettingsIssue>) {
+    let Some(vars) = env.as_object() else {
+        issues.push(issue(
+            "env",
+            "must be an object of variable names to values",
+        ));
+        return;
+    };
+    for (name, value) in vars {
+        if name.is_empty() || name.contains('=') {
+            issues.push(issue(
+                &format!("env.{}", name),
+                "is not a valid variable name",
+            ));
+        } else if !value.is_string() {
+            issues.push(issue(&format!("env.{}", name), "must be a string"));
+        }
+    }
+}
+
+fn check_permissions(permissions: &Value, issues: &mut Vec<SettingsIssue>) {
+    let Some(fields) = permissions.as_object() else {
+        issues.push(issue("permissions", "must be an object"));
+        return;
+    };
+    for (key, value) in fields {
+        let path = format!("permissions.{}", key);
+        match key.as_str() {
+            "allow" | "deny" | "ask" | "additionalDirectories" => {
+                check_string_array(value, &path, issues)
+            }
+            "defaultMode" => match value.as_str() {
+                Some("default" | "acceptEdits" | "plan" | "bypassPermissions") => {}
+                Some(_) => issues.push(warning(
+                    &path,
+                    "is not one of default, acceptEdits, plan or bypassPermissions",
+                )),
+                None => issues.push(issue(&path, "must be a string")),
+            },
+            "disableBypassPermissionsMode" => match value.as_str() {
+                Some("disable") => {}
+                Some(_) => issues.push(warning(&path, "is not \"disable\"")),
+                None => issues.push(issue(&path, "must be a string")),
+            },
+            _ => issues.push(warning(&path, "is not a known permissions setting")),
+        }
+    }
+}
+
+pub(crate) fn check_hooks(hooks: &Value, issues: &mut Vec<SettingsIssue>) {
+    let Some(events) = hooks.as_object() else {
+        issues.push(issue("hooks", "must be an object of hook events"));
+        return;
+    };
+    for (event, matchers) in events {
+        let event_path = format!("hooks.{}", event);
+        if !HOOK_EVENTS.contains(&event.as_str()) {
+            issues.push(warning(&event_path, "is not a known hook event"));
+        }
+        let Some(matchers) = matchers.as_array() else {
+            issues.push(issue(&event_path, "must be an array of matchers"));
+            continue;
+        };
+        for (i, matcher) in matchers.iter().enumerate() {
+            let matcher_path = format!("{}[{}]", event_path, i);
+            if matcher.get("matcher").is_some_and(|m| !m.is_string()) {
+                issues.push(issue(
+                    &format!("{}.matcher", matcher_path),
+                    "must be a string",
+                ));
+            }
+            let Some(commands) = matcher.get("hooks").and_then(|h| h.as_array()) else {
+                issues.push(issue(
+                    &format!("{}.hooks", matcher_path),
+                    "must be an array of hook commands",
+                ));
+                continue;
+            };
+            for (j, hook) in commands.iter().enumerate() {
+                let hook_path = format!("{}.hooks[{}]", matcher_path, j);
+                if hook.get("type").and_then(|t| t.as_str()) != Some("command") {
+                    issues.push(issue(&format!("{}.type", hook_path), "must be \"command\""));
+                }
+                if hook
+                    .get("command")
+                    .and_then(|c| c.as_str())
+                    .is_none_or(|c| c.trim().is_empty())
+                {
+                    issues.push(issue(
+                        &format!("{}.command", hook_path),
+                        "must be a non-empty string",
+                    ));
+                }
+                if hook.get("timeout").is_some_and(|t| !t.is_u64()) {
+                    issues.push(issue(
+                        &format!("{}.timeout", hook_path),
+                        "must be a positive number of seconds",
+                    ));
+                }
+            }
+        }
+    }
+}
+
+/// Check the known keys of a settings document; unknown top-level keys are left alone
+///
+/// Values of the wrong type are errors. Unknown nested keys and values are only warnings,
+/// since Claude Code may support them in a newer version.
+pub fn validate_settings(settings: &Value) -> Vec<SettingsIssue> {
+    let mut issues = Vec::new();
+    let Some(fields) = settings.as_object() else {
+        issues.push(issue("", "settings must be a JSON object"));
+        return issues;
+    };
+    for (key, value) in fields {
+        match key.as_str() {
+            "env" => check_env(value, &mut issues),
+            "permissions" => check_permissions(value, &mut issues),
+            "hooks" => check_hooks(value, &mut issues),
+            "model" | "apiKeyHelper" | "outputStyle" if !value.is_string() => {
+                issues.push(issue(key, "must be a string"));
+            }
+            "includeCoAuthoredBy" | "enableAllProjectMcpServers" if !value.is_boolean() => {
+                issues.push(issue(key, "must be true or false"));
+            }
+            "cleanupPeriodDays" if !value.is_u64() => {
+                issues.push(issue(key, "must be a whole number of days"));
+            }
+            _ => {}
+        }
+    }
+    issues
+}
+
+/// Parse and validate settings text, reporting syntax errors with their position
+pub fn parse_settings(content: &str) -> Result<Value, Vec<SettingsIssue>> {
+    let value: Value = serde_json::from_str(content).map_err(|e| {
+        vec![SettingsIssue {
+            path: String::new(),
+            message: format!("Invalid JSON: {}", e),
+            line: Some(e.line()),
+            column: Some(e.column()),
+            warning: false,
+        }]
+    })?;
+    let errors = errors(validate_settings(&value));
+    if errors.is_empty() {
+        Ok(value)
+    } else {
+        Err(errors)
+    }
+}
+
+pub(crate) fn describe_issues(issues: &[SettingsIssue]) -> String {
+    issues
+        .iter()
+        .map(|i| match (i.line, i.column, i.path.is_empty()) {
+            (Some(line), Some(column), _) => {
+                format!("line {}, column {}: {}", line, column, i.message)
+            }
+            (_, _, true) => i.message.clone(),
+            _ => format!("{}: {}", i.path, i.message),
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Path of the settings file for a scope: "user", "project" or "local"
+pub(crate) fn settings_path(scope: &str, project_path: Option<&str>) -> Result<PathBuf, String> {
+    match scope {
+        "user" => Ok(get_claude_dir()
+            .map_err(|e| e.to_string())?
+            .join("settings.json")),
+        "project" | "local" => {
+            let project =
+                project_path.ok_or_else(|| format!("Project path required for {} scope", scope))?;
+            let file = if scope == "project" {
+                "settings.json"
+            } else {
+                "settings.local.json"
+            };
+            Ok(PathBuf::from(project).join(".claude").join(file))
+        }
+        _ => Err(format!("Invalid settings scope: {}", scope)),
+    }
+}
+
+/// Parsed contents of a settings file; a missing file reads as `{}`
+pub(crate) fn read_settings(path: &Path) -> Result<Value, String> {
+    if !path.exists() {
+        return Ok(serde_json::json!({}));
+    }
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("Failed to read settings file: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| {
+        format!(
+            "Invalid JSON in {} at line {}, column {}: {}",
+            path.display(),
+            e.line(),
+            e.column(),
+            e
+        )
+    })
+}
+
+/// Copy the current file to `<name>.<timestamp>.bak` and prune old backups
+pub(crate) fn backup_settings(path: &Path) -> Result<Option<PathBuf>, String> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let backup = dir.join(format!(
+        "{}.{}.bak",
+        name,
+        Local::now().format("%Y%m%d-%H%M%S%.3f")
+    ));
+    fs::copy(path, &backup).map_err(|e| format!("Failed to back up settings: {}", e))?;
+
+    // Timestamps sort lexically, so the oldest backups come first
+    let prefix = format!("{}.", name);
+    let mut backups: Vec<PathBuf> = fs::read_dir(dir)
+        .map_err(|e| format!("Failed to list settings backups: {}", e))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with(&prefix) && n.ends_with(".bak"))
+        })
+        .collect();
+    backups.sort();
+    let excess = backups.len().saturating_sub(MAX_BACKUPS);
+    for old in &backups[..excess] {
+        if let Err(e) = fs::remove_file(old) {
+            log::warn!("Failed to remove old settings backup {:?}: {}", old, e);
+        }
+    }
+    Ok(Some(backup))
+}
+
+/// Back up, then atomically replace a settings file with `settings`
+pub(crate) fn write_settings(path: &Path, settings: &Value) -> Result<SettingsWriteResult, String> {
+    let errors = errors(validate_settings(settings));
+    if !errors.is_empty() {
+        return Err(format!("Invalid settings: {}", describe_issues(&errors)));
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create settings directory: {}", e))?;
+    }
+    let backup = backup_settings(path)?;
+    let json = serde_json::to_string_pretty(settings)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    write_file_atomically(path, &json)
+        .map_err(|e| format!("Failed to write settings file: {}", e))?;
+    Ok(SettingsWriteResult {
+        path: path.to_string_lossy().to_string(),
+        backup_path: backup.map(|b| b.to_string_lossy().to_string()),
+    })
+}
+
+/// Read a settings file along with any validation issues
+#[tauri::command]
+#[specta::specta]
+pub async fn read_settings_file(
+    scope: String,
+    project_path: Option<String>,
+) -> Result<SettingsFile, String> {
+    let path = settings_path(&scope, project_path.as_deref())?;
+    if !path.exists() {
+        return Ok(SettingsFile {
+            path: path.to_string_lossy().to_string(),
+            exists: false,
+            content: "{}".to_string(),
+            data: Some(serde_json::json!({})),
+            issues: Vec::new(),
+        });
+    }
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read settings file: {}", e))?;
+    let (data, issues) = match serde_json::from_str::<Value>(&content) {
+        Ok(value) => {
+            let issues = validate_settings(&value);
+            (Some(value), issues)
+        }
+        Err(_) => (None, parse_settings(&content).unwrap_err()),
+    };
+    Ok(SettingsFile {
+        path: path.to_string_lossy().to_string(),
+        exists: true,
+        content,
+        data,
+        issues,
+    })
+}
+
+/// Validate settings text without saving it, including warnings
+#[tauri::command]
+#[specta::specta]
+pub async fn validate_settings_content(content: String) -> Result<Vec<SettingsIssue>, String> {
+    Ok(match serde_json::from_str::<Value>(&content) {
+        Ok(value) => validate_settings(&value),
+        Err(_) => parse_settings(&content).unwrap_err(),
+    })
+}
+
+/// Validate and save a settings file, keeping a timestamped backup of the previous version
+#[tauri::command]
+#[specta::specta]
+pub async fn write_settings_file(
+    scope: String,
+    project_path: Option<String>,
+    content: String,
+) -> Result<SettingsWriteResult, String> {
+    let path = settings_path(&scope, project_path.as_deref())?;
+    let settings = parse_settings(&content)
+        .map_err(|issues| format!("Invalid settings: {}", describe_issues(&issues)))?;
+    let result = write_settings(&path, &settings)?;
+    log::info!("Saved {} settings to {}", scope, result.path);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn validation_points_at_offending_values() {
+        let settings = json!({
+            "env": { "API_URL": "https://example.com", "RETRIES": 3 },
+            "permissions": { "allow": ["Bash(ls)", 1], "defaultMode": "yolo" },
+            "hooks": {
+                "PreToolUse": [{ "matcher": "Bash", "hooks": [{ "type": "command", "command": "" }] }],
+                "OnSave": []
+            },
+            "statusLine": { "type": "command" }
+        });
+        let paths: Vec<(String, bool)> = validate_settings(&settings)
+            .into_iter()
+            .map(|i| (i.path, i.warning))
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                ("env.RETRIES".to_string(), false),
+                ("hooks.OnSave".to_string(), true),
+                ("hooks.PreToolUse[0].hooks[0].command".to_string(), false),
+                ("permissions.allow[1]".to_string(), false),
+                ("permissions.defaultMode".to_string(), true),
+            ]
+        );
+
+        let syntax = parse_settings("{\n  \"env\": {,\n}").unwrap_err();
+        assert_eq!(syntax[0].line, Some(2));
+        assert!(parse_settings("{\"model\": \"opus\"}").is_ok());
+    }
+
+    #[test]
+    fn unknown_keys_are_warnings() {
+        let settings = json!({
+            "permissions": { "defaultMode": "dontAsk", "newSetting": true },
+            "hooks": { "PostCompact": [{ "hooks": [{ "type": "command", "command": "echo" }] }] }
+        });
+        let issues = validate_settings(&settings);
+        assert_eq!(issues.len(), 3);
+        assert!(issues.iter().all(|i| i.warning));
+        assert!(parse_settings(&settings.to_string()).is_ok());
+
+        let malformed = json!({ "permissions": { "defaultMode": 1 }, "hooks": { "PostCompact": {} } });
+        let errors = parse_settings(&malformed.to_string()).unwrap_err();
+        let paths: Vec<&str> = errors.iter().map(|i| i.path.as_str()).collect();
+        assert_eq!(paths, vec!["hooks.PostCompact", "permissions.defaultMode"]);
+    }
+
+    #[test]
+    fn writes_keep_a_backup_of_the_previous_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        let first = write_settings(&path, &json!({ "model": "sonnet" })).unwrap();
+        assert!(first.backup_path.is_none());
+
+        let second = write_settings(&path, &json!({ "model": "opus" })).unwrap();
+        let backup = second.backup_path.unwrap();
+        assert!(fs::read_to_string(backup).unwrap().contains("sonnet"));
+        assert!(fs::read_to_string(&path).unwrap().contains("opus"));
+        assert!(write_settings(&path, &json!({ "env": [] })).is_err());
+    }
+}
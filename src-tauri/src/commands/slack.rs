@@ -0,0 +1,151 @@
+use serde_json::{json, Value as JsonValue};
+use tauri::{AppHandle, Manager, State};
+
+use super::agents::AgentDb;
+use super::chat_integrations::{
+    load_chat_settings, load_finished_run, post_message, save_chat_settings, spawn_post,
+    validate_webhook_url, ChatIntegrationSettings, FinishedRun,
+};
+use super::live_metrics::AgentLiveMetrics;
+use super::notifications::format_elapsed;
+use super::usage_budgets::BudgetStatus;
+
+const SETTINGS_KEY: &str = "slack_integration";
+const SERVICE: &str = "Slack";
+
+/// Block Kit message for a finished run
+fn run_message(run: &FinishedRun, metrics: &AgentLiveMetrics) -> JsonValue {
+    let icon = if run.status == "completed" { ":white_check_mark:" } else { ":x:" };
+    let title = format!("{} {} {}", icon, run.agent_name, run.status.replace('_', " "));
+    json!({
+        "text": title,
+        "blocks": [
+            {
+                "type": "section",
+                "text": { "type": "mrkdwn", "text": format!("*{}*", title) },
+            },
+            {
+                "type": "section",
+                "fields": [
+                    { "type": "mrkdwn", "text": format!("*Cost*\n${:.2}", metrics.estimated_cost_usd) },
+                    { "type": "mrkdwn", "text": format!("*Tokens*\n{}", metrics.total_tokens) },
+                    { "type": "mrkdwn", "text": format!("*Duration*\n{}", format_elapsed(metrics.elapsed_ms)) },
+                    { "type": "mrkdwn", "text": format!("*Project*\n`{}`", run.project_path) },
+                ],
+            },
+            {
+                "type": "context",
+                "elements": [
+                    { "type": "mrkdwn", "text": format!("Run #{} · open with `claudia://run/{}`", run.run_id, run.run_id) },
+                ],
+            },
+        ],
+    })
+}
+
+/// Block Kit message for a crossed budget threshold
+fn budget_message(status: &BudgetStatus, threshold: u32) -> JsonValue {
+    let title = if threshold >= 100 {
+        format!(":rotating_light: {} usage budget exceeded", status.period)
+    } else {
+        format!(":warning: {}% of the {} usage budget used", threshold, status.period)
+    };
+    json!({
+        "text": title,
+        "blocks": [
+            {
+                "type": "section",
+                "text": {
+                    "type": "mrkdwn",
+                    "text": format!(
+                        "*{}*\n${:.2} of ${:.2} spent since {}",
+                        title, status.spent, status.limit, status.period_start
+                    ),
+                },
+            },
+        ],
+    })
+}
+
+/// Post a finished run to Slack if the agent's runs are posted
+pub fn notify_run_finished(app: &AppHandle, run_id: i64, success: bool, metrics: &AgentLiveMetrics) {
+    let db = app.state::<AgentDb>();
+    let Ok(conn) = db.0.lock() else {
+        return;
+    };
+    let settings = load_chat_settings(&conn, SETTINGS_KEY);
+    if success && settings.only_on_failure {
+        return;
+    }
+    let Some(run) = load_finished_run(&conn, run_id, success) else {
+        return;
+    };
+    if let Some(webhook_url) = settings.run_webhook(run.agent_id) {
+        let message = run_message(&run, metrics);
+        spawn_post(SERVICE, webhook_url.to_string(), message, format!("agent run {}", run_id));
+    }
+}
+
+/// Post a crossed budget threshold to Slack if budget alerts are enabled
+pub fn notify_budget_alert(app: &AppHandle, status: &BudgetStatus, threshold: u32) {
+    let settings = {
+        let db = app.state::<AgentDb>();
+        let Ok(conn) = db.0.lock() else {
+            return;
+        };
+        load_chat_settings(&conn, SETTINGS_KEY)
+    };
+    if let Some(webhook_url) = settings.budget_webhook() {
+        spawn_post(
+            SERVICE,
+            webhook_url.to_string(),
+            budget_message(status, threshold),
+            format!("{} budget alert", status.period),
+        );
+    }
+}
+
+/// Get the Slack integration settings
+#[tauri::command]
+#[specta::specta]
+pub async fn get_slack_settings(db: State<'_, AgentDb>) -> Result<ChatIntegrationSettings, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    Ok(load_chat_settings(&conn, SETTINGS_KEY))
+}
+
+/// Save the Slack integration settings
+#[tauri::command]
+#[specta::specta]
+pub async fn set_slack_settings(
+    db: State<'_, AgentDb>,
+    settings: ChatIntegrationSettings,
+) -> Result<(), String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    save_chat_settings(&conn, SETTINGS_KEY, SERVICE, &settings)
+}
+
+/// Post a test message to a Slack webhook, the global one when none is given
+#[tauri::command]
+#[specta::specta]
+pub async fn test_slack_webhook(
+    db: State<'_, AgentDb>,
+    webhook_url: Option<String>,
+) -> Result<(), String> {
+    let webhook_url = match webhook_url.filter(|url| !url.is_empty()) {
+        Some(url) => url,
+        None => {
+            let conn = db.0.lock().map_err(|e| e.to_string())?;
+            load_chat_settings(&conn, SETTINGS_KEY).webhook_url
+        }
+    };
+    if webhook_url.is_empty() {
+        return Err("No Slack webhook URL configured".to_string());
+    }
+    validate_webhook_url(SERVICE, &webhook_url)?;
+    post_message(
+        SERVICE,
+        &webhook_url,
+        &json!({ "text": ":wave: Claudia can post to this channel" }),
+    )
+    .await
+}
@@ -6,7 +6,7 @@ use std::fs;
 use std::path::{Path, PathBuf};
 
 /// Represents a custom slash command
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
 pub struct SlashCommand {
     /// Unique identifier for the command (derived from file path)
     pub id: String,
@@ -26,6 +26,12 @@ pub struct SlashCommand {
     pub description: Option<String>,
     /// Allowed tools from frontmatter
     pub allowed_tools: Vec<String>,
+    /// Hint for the arguments shown after the command name
+    #[serde(default)]
+    pub argument_hint: Option<String>,
+    /// Model the command runs with, from frontmatter
+    #[serde(default)]
+    pub model: Option<String>,
     /// Whether the command has bash commands (!)
     pub has_bash_commands: bool,
     /// Whether the command has file references (@)
@@ -35,11 +41,103 @@ pub struct SlashCommand {
 }
 
 /// YAML frontmatter structure
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 struct CommandFrontmatter {
-    #[serde(rename = "allowed-tools")]
+    #[serde(
+        rename = "allowed-tools",
+        default,
+        deserialize_with = "tools_from_string_or_list",
+        skip_serializing_if = "Option::is_none"
+    )]
     allowed_tools: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     description: Option<String>,
+    #[serde(
+        rename = "argument-hint",
+        default,
+        deserialize_with = "hint_from_string_or_list",
+        skip_serializing_if = "Option::is_none"
+    )]
+    argument_hint: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    model: Option<String>,
+}
+
+/// Claude Code accepts allowed-tools both as a list and as a comma-separated string
+fn tools_from_string_or_list<'de, D>(deserializer: D) -> std::result::Result<Option<Vec<String>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Tools {
+        List(Vec<String>),
+        Text(String),
+    }
+    Ok(Option::<Tools>::deserialize(deserializer)?.map(|tools| match tools {
+        Tools::List(list) => list,
+        Tools::Text(text) => split_tools(&text),
+    }))
+}
+
+/// An unquoted hint such as `[message]` is read by YAML as a list, so turn it back into text
+fn hint_from_string_or_list<'de, D>(deserializer: D) -> std::result::Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(
+        Option::<serde_yaml::Value>::deserialize(deserializer)?.and_then(|value| match value {
+            serde_yaml::Value::String(text) => Some(text),
+            serde_yaml::Value::Sequence(items) => Some(
+                items
+                    .iter()
+                    .filter_map(|item| item.as_str())
+                    .map(|item| format!("[{}]", item))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            ),
+            _ => None,
+        }),
+    )
+}
+
+/// Split a comma-separated tool list, keeping commas inside parentheses such as `Bash(git add:*, git commit:*)`
+fn split_tools(text: &str) -> Vec<String> {
+    let mut tools = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0usize;
+    for c in text.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                tools.push(std::mem::take(&mut current));
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    tools.push(current);
+    tools
+        .into_iter()
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+/// Reject names that would escape the commands directory or clash with the `:` namespace separator
+fn validate_segment(segment: &str, what: &str) -> std::result::Result<(), String> {
+    if segment.is_empty()
+        || segment == "."
+        || segment == ".."
+        || segment.starts_with('.')
+        || segment.contains(['/', '\\', ':'])
+        || segment.chars().any(char::is_whitespace)
+    {
+        return Err(format!("Invalid {}: '{}'", what, segment));
+    }
+    Ok(())
 }
 
 /// Parse a markdown file with optional YAML frontmatter
@@ -94,7 +192,7 @@ fn extract_command_info(file_path: &Path, base_path: &Path) -> Result<(String, O
         .to_string();
     
     // Split into components
-    let components: Vec<&str> = path_without_ext.split('/').collect();
+    let components: Vec<&str> = path_without_ext.split(['/', '\\']).collect();
     
     if components.is_empty() {
         return Err(anyhow::anyhow!("Invalid command path"));
@@ -144,11 +242,9 @@ fn load_command_from_file(
     let accepts_arguments = body.contains("$ARGUMENTS");
     
     // Extract metadata from frontmatter
-    let (description, allowed_tools) = if let Some(fm) = frontmatter {
-        (fm.description, fm.allowed_tools.unwrap_or_default())
-    } else {
-        (None, Vec::new())
-    };
+    let frontmatter = frontmatter.unwrap_or_default();
+    let description = frontmatter.description;
+    let allowed_tools = frontmatter.allowed_tools.unwrap_or_default();
     
     Ok(SlashCommand {
         id,
@@ -160,6 +256,8 @@ fn load_command_from_file(
         content: body,
         description,
         allowed_tools,
+        argument_hint: frontmatter.argument_hint,
+        model: frontmatter.model,
         has_bash_commands,
         has_file_references,
         accepts_arguments,
@@ -210,6 +308,8 @@ fn create_default_commands() -> Vec<SlashCommand> {
             content: "Add additional working directories".to_string(),
             description: Some("Add additional working directories".to_string()),
             allowed_tools: vec![],
+            argument_hint: None,
+            model: None,
             has_bash_commands: false,
             has_file_references: false,
             accepts_arguments: false,
@@ -224,6 +324,8 @@ fn create_default_commands() -> Vec<SlashCommand> {
             content: "Initialize project with CLAUDE.md guide".to_string(),
             description: Some("Initialize project with CLAUDE.md guide".to_string()),
             allowed_tools: vec![],
+            argument_hint: None,
+            model: None,
             has_bash_commands: false,
             has_file_references: false,
             accepts_arguments: false,
@@ -238,6 +340,8 @@ fn create_default_commands() -> Vec<SlashCommand> {
             content: "Request code review".to_string(),
             description: Some("Request code review".to_string()),
             allowed_tools: vec![],
+            argument_hint: None,
+            model: None,
             has_bash_commands: false,
             has_file_references: false,
             accepts_arguments: false,
@@ -247,6 +351,7 @@ fn create_default_commands() -> Vec<SlashCommand> {
 
 /// Discover all custom slash commands
 #[tauri::command]
+#[specta::specta]
 pub async fn slash_commands_list(
     project_path: Option<String>,
 ) -> Result<Vec<SlashCommand>, String> {
@@ -312,7 +417,11 @@ pub async fn slash_commands_list(
 
 /// Get a single slash command by ID
 #[tauri::command]
-pub async fn slash_command_get(command_id: String) -> Result<SlashCommand, String> {
+#[specta::specta]
+pub async fn slash_command_get(
+    command_id: String,
+    project_path: Option<String>,
+) -> Result<SlashCommand, String> {
     debug!("Getting slash command: {}", command_id);
     
     // Parse the ID to determine scope and reconstruct file path
@@ -323,7 +432,7 @@ pub async fn slash_command_get(command_id: String) -> Result<SlashCommand, Strin
     
     // The actual implementation would need to reconstruct the path and reload the command
     // For now, we'll list all commands and find the matching one
-    let commands = slash_commands_list(None).await?;
+    let commands = slash_commands_list(project_path).await?;
     
     commands
         .into_iter()
@@ -333,6 +442,8 @@ pub async fn slash_command_get(command_id: String) -> Result<SlashCommand, Strin
 
 /// Create or update a slash command
 #[tauri::command]
+#[specta::specta]
+#[allow(clippy::too_many_arguments)]
 pub async fn slash_command_save(
     scope: String,
     name: String,
@@ -341,6 +452,8 @@ pub async fn slash_command_save(
     description: Option<String>,
     allowed_tools: Vec<String>,
     project_path: Option<String>,
+    argument_hint: Option<String>,
+    model: Option<String>,
 ) -> Result<SlashCommand, String> {
     info!("Saving slash command: {} in scope: {}", name, scope);
     
@@ -348,6 +461,13 @@ pub async fn slash_command_save(
     if name.is_empty() {
         return Err("Command name cannot be empty".to_string());
     }
+    validate_segment(&name, "command name")?;
+    let namespace = namespace.filter(|ns| !ns.is_empty());
+    if let Some(ns) = &namespace {
+        for component in ns.split(':') {
+            validate_segment(component, "namespace")?;
+        }
+    }
     
     if !["project", "user"].contains(&scope.as_str()) {
         return Err("Invalid scope. Must be 'project' or 'user'".to_string());
@@ -385,21 +505,22 @@ pub async fn slash_command_save(
     // Build content with frontmatter
     let mut full_content = String::new();
     
-    // Add frontmatter if we have metadata
-    if description.is_some() || !allowed_tools.is_empty() {
+    // Add frontmatter if we have metadata; serde_yaml quotes values that need it
+    let frontmatter = CommandFrontmatter {
+        allowed_tools: (!allowed_tools.is_empty()).then_some(allowed_tools),
+        description: description.filter(|d| !d.is_empty()),
+        argument_hint: argument_hint.filter(|h| !h.is_empty()),
+        model: model.filter(|m| !m.is_empty()),
+    };
+    if frontmatter.allowed_tools.is_some()
+        || frontmatter.description.is_some()
+        || frontmatter.argument_hint.is_some()
+        || frontmatter.model.is_some()
+    {
+        let yaml = serde_yaml::to_string(&frontmatter)
+            .map_err(|e| format!("Failed to serialize frontmatter: {}", e))?;
         full_content.push_str("---\n");
-        
-        if let Some(desc) = &description {
-            full_content.push_str(&format!("description: {}\n", desc));
-        }
-        
-        if !allowed_tools.is_empty() {
-            full_content.push_str("allowed-tools:\n");
-            for tool in &allowed_tools {
-                full_content.push_str(&format!("  - {}\n", tool));
-            }
-        }
-        
+        full_content.push_str(yaml.trim_start_matches("---\n"));
         full_content.push_str("---\n\n");
     }
     
@@ -416,6 +537,7 @@ pub async fn slash_command_save(
 
 /// Delete a slash command
 #[tauri::command]
+#[specta::specta]
 pub async fn slash_command_delete(command_id: String, project_path: Option<String>) -> Result<String, String> {
     info!("Deleting slash command: {}", command_id);
     
@@ -440,7 +562,7 @@ pub async fn slash_command_delete(command_id: String, project_path: Option<Strin
     fs::remove_file(&command.file_path)
         .map_err(|e| format!("Failed to delete command file: {}", e))?;
     
-    // Clean up empty directories
+    // Clean up empty namespace directories, stopping at the commands directory
     if let Some(parent) = Path::new(&command.file_path).parent() {
         let _ = remove_empty_dirs(parent);
     }
@@ -448,9 +570,9 @@ pub async fn slash_command_delete(command_id: String, project_path: Option<Strin
     Ok(format!("Deleted command: {}", command.full_command))
 }
 
-/// Remove empty directories recursively
+/// Remove empty directories recursively, up to the nearest `commands` directory
 fn remove_empty_dirs(dir: &Path) -> Result<()> {
-    if !dir.exists() {
+    if !dir.exists() || dir.file_name().is_some_and(|name| name == "commands") {
         return Ok(());
     }
     
@@ -468,3 +590,30 @@ fn remove_empty_dirs(dir: &Path) -> Result<()> {
     
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_frontmatter_and_namespaces() {
+        let content = "---\nallowed-tools: Bash(git add:*, git status:*), Read\ndescription: Commit staged work\nargument-hint: [message]\n---\nCommit with $ARGUMENTS";
+        let (frontmatter, body) = parse_markdown_with_frontmatter(content).unwrap();
+        let frontmatter = frontmatter.unwrap();
+        assert_eq!(
+            frontmatter.allowed_tools.unwrap(),
+            vec!["Bash(git add:*, git status:*)", "Read"]
+        );
+        assert_eq!(frontmatter.argument_hint.as_deref(), Some("[message]"));
+        assert_eq!(body, "Commit with $ARGUMENTS");
+
+        let base = Path::new("/home/me/.claude/commands");
+        let (name, namespace) =
+            extract_command_info(&base.join("frontend/react/component.md"), base).unwrap();
+        assert_eq!(name, "component");
+        assert_eq!(namespace.as_deref(), Some("frontend:react"));
+
+        assert!(validate_segment("../escape", "command name").is_err());
+        assert!(validate_segment("deploy-prod", "command name").is_ok());
+    }
+}
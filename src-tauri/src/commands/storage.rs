@@ -7,7 +7,7 @@ use tauri::{AppHandle, Manager, State};
 use super::agents::AgentDb;
 
 /// Represents metadata about a database table
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, specta::Type)]
 pub struct TableInfo {
     pub name: String,
     pub row_count: i64,
@@ -15,7 +15,7 @@ pub struct TableInfo {
 }
 
 /// Represents metadata about a table column
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, specta::Type)]
 pub struct ColumnInfo {
     pub cid: i32,
     pub name: String,
@@ -26,7 +26,7 @@ pub struct ColumnInfo {
 }
 
 /// Represents a page of table data
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, specta::Type)]
 pub struct TableData {
     pub table_name: String,
     pub columns: Vec<ColumnInfo>,
@@ -38,7 +38,7 @@ pub struct TableData {
 }
 
 /// SQL query result
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, specta::Type)]
 pub struct QueryResult {
     pub columns: Vec<String>,
     pub rows: Vec<Vec<JsonValue>>,
@@ -48,6 +48,7 @@ pub struct QueryResult {
 
 /// List all tables in the database
 #[tauri::command]
+#[specta::specta]
 pub async fn storage_list_tables(db: State<'_, AgentDb>) -> Result<Vec<TableInfo>, String> {
     let conn = db.0.lock().map_err(|e| e.to_string())?;
     
@@ -108,6 +109,7 @@ pub async fn storage_list_tables(db: State<'_, AgentDb>) -> Result<Vec<TableInfo
 
 /// Read table data with pagination
 #[tauri::command]
+#[specta::specta]
 #[allow(non_snake_case)]
 pub async fn storage_read_table(
     db: State<'_, AgentDb>,
@@ -227,6 +229,7 @@ pub async fn storage_read_table(
 
 /// Update a row in a table
 #[tauri::command]
+#[specta::specta]
 #[allow(non_snake_case)]
 pub async fn storage_update_row(
     db: State<'_, AgentDb>,
@@ -283,6 +286,7 @@ pub async fn storage_update_row(
 
 /// Delete a row from a table
 #[tauri::command]
+#[specta::specta]
 #[allow(non_snake_case)]
 pub async fn storage_delete_row(
     db: State<'_, AgentDb>,
@@ -324,6 +328,7 @@ pub async fn storage_delete_row(
 
 /// Insert a new row into a table
 #[tauri::command]
+#[specta::specta]
 #[allow(non_snake_case)]
 pub async fn storage_insert_row(
     db: State<'_, AgentDb>,
@@ -365,6 +370,7 @@ pub async fn storage_insert_row(
 
 /// Execute a raw SQL query
 #[tauri::command]
+#[specta::specta]
 pub async fn storage_execute_sql(
     db: State<'_, AgentDb>,
     query: String,
@@ -431,6 +437,7 @@ pub async fn storage_execute_sql(
 
 /// Reset the entire database (with confirmation)
 #[tauri::command]
+#[specta::specta]
 pub async fn storage_reset_database(app: AppHandle) -> Result<(), String> {
     {
         // Drop all existing tables within a scoped block
@@ -443,12 +450,56 @@ pub async fn storage_reset_database(app: AppHandle) -> Result<(), String> {
             .map_err(|e| format!("Failed to disable foreign keys: {}", e))?;
         
         // Drop tables - order doesn't matter with foreign keys disabled
+        conn.execute("DROP TABLE IF EXISTS agent_run_artifacts", [])
+            .map_err(|e| format!("Failed to drop agent_run_artifacts table: {}", e))?;
+        conn.execute("DROP TABLE IF EXISTS agent_schedules", [])
+            .map_err(|e| format!("Failed to drop agent_schedules table: {}", e))?;
+        conn.execute("DROP TABLE IF EXISTS agent_pipeline_runs", [])
+            .map_err(|e| format!("Failed to drop agent_pipeline_runs table: {}", e))?;
+        conn.execute("DROP TABLE IF EXISTS agent_pipelines", [])
+            .map_err(|e| format!("Failed to drop agent_pipelines table: {}", e))?;
+        conn.execute("DROP TABLE IF EXISTS agent_versions", [])
+            .map_err(|e| format!("Failed to drop agent_versions table: {}", e))?;
+        conn.execute("DROP TABLE IF EXISTS spawned_processes", [])
+            .map_err(|e| format!("Failed to drop spawned_processes table: {}", e))?;
+        conn.execute("DROP TABLE IF EXISTS session_error_logs", [])
+            .map_err(|e| format!("Failed to drop session_error_logs table: {}", e))?;
+        conn.execute("DROP TABLE IF EXISTS agent_run_logs", [])
+            .map_err(|e| format!("Failed to drop agent_run_logs table: {}", e))?;
+        conn.execute("DROP TABLE IF EXISTS agent_batches", [])
+            .map_err(|e| format!("Failed to drop agent_batches table: {}", e))?;
+        conn.execute("DROP TABLE IF EXISTS mcp_server_logs", [])
+            .map_err(|e| format!("Failed to drop mcp_server_logs table: {}", e))?;
+        conn.execute("DROP TABLE IF EXISTS mcp_server_health", [])
+            .map_err(|e| format!("Failed to drop mcp_server_health table: {}", e))?;
+        conn.execute("DROP TABLE IF EXISTS prompt_presets", [])
+            .map_err(|e| format!("Failed to drop prompt_presets table: {}", e))?;
+        conn.execute("DROP TABLE IF EXISTS ui_preferences", [])
+            .map_err(|e| format!("Failed to drop ui_preferences table: {}", e))?;
+        conn.execute("DROP TABLE IF EXISTS project_env_vars", [])
+            .map_err(|e| format!("Failed to drop project_env_vars table: {}", e))?;
+        conn.execute("DROP TABLE IF EXISTS usage_messages", [])
+            .map_err(|e| format!("Failed to drop usage_messages table: {}", e))?;
+        conn.execute("DROP TABLE IF EXISTS usage_index_files", [])
+            .map_err(|e| format!("Failed to drop usage_index_files table: {}", e))?;
         conn.execute("DROP TABLE IF EXISTS agent_runs", [])
             .map_err(|e| format!("Failed to drop agent_runs table: {}", e))?;
         conn.execute("DROP TABLE IF EXISTS agents", [])
             .map_err(|e| format!("Failed to drop agents table: {}", e))?;
         conn.execute("DROP TABLE IF EXISTS app_settings", [])
             .map_err(|e| format!("Failed to drop app_settings table: {}", e))?;
+        conn.execute("DROP TABLE IF EXISTS session_files", [])
+            .map_err(|e| format!("Failed to drop session_files table: {}", e))?;
+        conn.execute("DROP TABLE IF EXISTS session_file_index_files", [])
+            .map_err(|e| format!("Failed to drop session_file_index_files table: {}", e))?;
+        conn.execute("DROP TABLE IF EXISTS webhooks", [])
+            .map_err(|e| format!("Failed to drop webhooks table: {}", e))?;
+        conn.execute("DROP TABLE IF EXISTS history_imports", [])
+            .map_err(|e| format!("Failed to drop history_imports table: {}", e))?;
+        conn.execute("DROP TABLE IF EXISTS session_archives", [])
+            .map_err(|e| format!("Failed to drop session_archives table: {}", e))?;
+        conn.execute("DROP TABLE IF EXISTS schema_migrations", [])
+            .map_err(|e| format!("Failed to drop schema_migrations table: {}", e))?;
         
         // Re-enable foreign key constraints
         conn.execute("PRAGMA foreign_keys = ON", [])
@@ -0,0 +1,449 @@
+use log::{error, info, warn};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+use std::collections::HashMap;
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager, State};
+
+use super::agents::{get_setting, set_setting, AgentDb};
+use super::live_metrics::AgentLiveMetrics;
+
+/// Seconds between exports of finished spans
+const EXPORT_INTERVAL_SECS: u64 = 5;
+
+/// Spans kept while the collector is unreachable; the oldest are dropped first
+const MAX_PENDING_SPANS: usize = 2048;
+
+/// Optional OTLP/HTTP export of traces, stored under `otlp_export` in app_settings
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, specta::Type)]
+pub struct TelemetrySettings {
+    pub enabled: bool,
+    /// Collector base URL, e.g. `http://localhost:4318`; `/v1/traces` is appended
+    pub endpoint: String,
+    /// Extra request headers, such as `x-honeycomb-team` for hosted collectors
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    #[serde(default = "default_service_name")]
+    pub service_name: String,
+}
+
+fn default_service_name() -> String {
+    "claudia".to_string()
+}
+
+impl Default for TelemetrySettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: "http://localhost:4318".to_string(),
+            headers: HashMap::new(),
+            service_name: default_service_name(),
+        }
+    }
+}
+
+/// Settings in effect, kept in memory so spans can be recorded without database access
+static CURRENT: RwLock<Option<TelemetrySettings>> = RwLock::new(None);
+
+/// Spans finished since the last export
+static PENDING: Mutex<Vec<JsonValue>> = Mutex::new(Vec::new());
+
+fn current() -> Option<TelemetrySettings> {
+    CURRENT
+        .read()
+        .ok()
+        .and_then(|settings| settings.clone())
+        .filter(|settings| settings.enabled)
+}
+
+fn load_settings(conn: &Connection) -> TelemetrySettings {
+    get_setting(conn, "otlp_export").unwrap_or_default()
+}
+
+/// Load the saved export settings so spans are recorded from startup
+pub fn init_telemetry_settings(conn: &Connection) {
+    let settings = load_settings(conn);
+    if settings.enabled {
+        info!("Exporting traces to {}", settings.endpoint);
+    }
+    if let Ok(mut current) = CURRENT.write() {
+        *current = Some(settings);
+    }
+}
+
+/// Value of a span attribute
+#[derive(Debug, Clone, PartialEq)]
+pub enum AttributeValue {
+    String(String),
+    Int(i64),
+    Double(f64),
+    Bool(bool),
+}
+
+impl From<&str> for AttributeValue {
+    fn from(value: &str) -> Self {
+        AttributeValue::String(value.to_string())
+    }
+}
+
+impl From<String> for AttributeValue {
+    fn from(value: String) -> Self {
+        AttributeValue::String(value)
+    }
+}
+
+impl From<i64> for AttributeValue {
+    fn from(value: i64) -> Self {
+        AttributeValue::Int(value)
+    }
+}
+
+impl From<u64> for AttributeValue {
+    fn from(value: u64) -> Self {
+        AttributeValue::Int(value as i64)
+    }
+}
+
+impl From<f64> for AttributeValue {
+    fn from(value: f64) -> Self {
+        AttributeValue::Double(value)
+    }
+}
+
+impl From<bool> for AttributeValue {
+    fn from(value: bool) -> Self {
+        AttributeValue::Bool(value)
+    }
+}
+
+fn attribute_json(key: &str, value: &AttributeValue) -> JsonValue {
+    // OTLP/JSON encodes 64-bit integers as strings
+    let value = match value {
+        AttributeValue::String(v) => json!({ "stringValue": v }),
+        AttributeValue::Int(v) => json!({ "intValue": v.to_string() }),
+        AttributeValue::Double(v) => json!({ "doubleValue": v }),
+        AttributeValue::Bool(v) => json!({ "boolValue": v }),
+    };
+    json!({ "key": key, "value": value })
+}
+
+fn unix_nanos(time: SystemTime) -> String {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos()
+        .to_string()
+}
+
+fn random_hex_id(bytes: usize) -> String {
+    uuid::Uuid::new_v4().as_bytes()[..bytes]
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// A traced operation, exported when dropped if export is enabled
+///
+/// Spans are recorded whether or not the operation returns early, so commands only
+/// need `end_with` where they want failures marked as errors.
+pub struct Span {
+    name: &'static str,
+    start: SystemTime,
+    attributes: Vec<(&'static str, AttributeValue)>,
+    error: Option<String>,
+    /// Set once the span was exported directly instead of queued
+    taken: bool,
+}
+
+impl Span {
+    pub fn start(name: &'static str) -> Self {
+        Self {
+            name,
+            start: SystemTime::now(),
+            attributes: Vec::new(),
+            error: None,
+            taken: false,
+        }
+    }
+
+    /// A span for an operation that began `elapsed` ago, e.g. a run measured elsewhere
+    pub fn started_ago(name: &'static str, elapsed: Duration) -> Self {
+        let mut span = Self::start(name);
+        span.start = span.start.checked_sub(elapsed).unwrap_or(span.start);
+        span
+    }
+
+    pub fn attr(mut self, key: &'static str, value: impl Into<AttributeValue>) -> Self {
+        self.set_attr(key, value);
+        self
+    }
+
+    pub fn set_attr(&mut self, key: &'static str, value: impl Into<AttributeValue>) {
+        self.attributes.push((key, value.into()));
+    }
+
+    pub fn fail(&mut self, message: impl Into<String>) {
+        self.error = Some(message.into());
+    }
+
+    /// End the span with the outcome of the operation and pass the result through
+    pub fn end_with<T>(mut self, result: Result<T, String>) -> Result<T, String> {
+        if let Err(e) = &result {
+            self.fail(e.clone());
+        }
+        result
+    }
+
+    /// Encode the span now instead of queueing it when dropped
+    fn take_json(mut self) -> JsonValue {
+        self.taken = true;
+        self.to_json(SystemTime::now())
+    }
+
+    fn to_json(&self, end: SystemTime) -> JsonValue {
+        let status = match &self.error {
+            Some(message) => json!({ "code": 2, "message": message }),
+            None => json!({ "code": 1 }),
+        };
+        json!({
+            "traceId": random_hex_id(16),
+            "spanId": random_hex_id(8),
+            "name": self.name,
+            "kind": 1,
+            "startTimeUnixNano": unix_nanos(self.start),
+            "endTimeUnixNano": unix_nanos(end),
+            "attributes": self
+                .attributes
+                .iter()
+                .map(|(key, value)| attribute_json(key, value))
+                .collect::<Vec<_>>(),
+            "status": status,
+        })
+    }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        if self.taken || current().is_none() {
+            return;
+        }
+        let span = self.to_json(SystemTime::now());
+        if let Ok(mut pending) = PENDING.lock() {
+            if pending.len() >= MAX_PENDING_SPANS {
+                pending.remove(0);
+            }
+            pending.push(span);
+        }
+    }
+}
+
+/// Attributes shared by agent runs and interactive sessions
+fn set_metrics_attrs(span: &mut Span, metrics: &AgentLiveMetrics) {
+    span.set_attr("gen_ai.usage.input_tokens", metrics.input_tokens);
+    span.set_attr("gen_ai.usage.output_tokens", metrics.output_tokens);
+    span.set_attr("claudia.usage.cache_creation_tokens", metrics.cache_creation_tokens);
+    span.set_attr("claudia.usage.cache_read_tokens", metrics.cache_read_tokens);
+    span.set_attr("claudia.usage.total_tokens", metrics.total_tokens);
+    span.set_attr("claudia.cost_usd", metrics.estimated_cost_usd);
+    span.set_attr("claudia.message_count", metrics.message_count);
+}
+
+/// Record a finished agent run as an `agent.run` span
+pub fn record_agent_run(app: &AppHandle, run_id: i64, success: bool, metrics: &AgentLiveMetrics) {
+    if current().is_none() {
+        return;
+    }
+    let mut span = Span::started_ago("agent.run", Duration::from_millis(metrics.elapsed_ms))
+        .attr("claudia.run.id", run_id);
+    {
+        let db = app.state::<AgentDb>();
+        if let Ok(conn) = db.0.lock() {
+            let run = conn.query_row(
+                "SELECT agent_id, agent_name, model, project_path, session_id, status FROM agent_runs WHERE id = ?1",
+                params![run_id],
+                |row| {
+                    Ok((
+                        row.get::<_, i64>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, String>(2)?,
+                        row.get::<_, String>(3)?,
+                        row.get::<_, String>(4)?,
+                        row.get::<_, String>(5)?,
+                    ))
+                },
+            );
+            if let Ok((agent_id, agent_name, model, project_path, session_id, status)) = run {
+                span.set_attr("claudia.agent.id", agent_id);
+                span.set_attr("claudia.agent.name", agent_name);
+                span.set_attr("gen_ai.request.model", model);
+                span.set_attr("claudia.project.path", project_path);
+                span.set_attr("claudia.session.id", session_id);
+                span.set_attr("claudia.run.status", status);
+            }
+        };
+    }
+    set_metrics_attrs(&mut span, metrics);
+    if !success {
+        span.fail("Agent run failed");
+    }
+}
+
+/// Record a finished interactive Claude Code execution as a `claude.execution` span
+pub fn record_claude_execution(model: &str, project_path: &str, metrics: &AgentLiveMetrics) {
+    if current().is_none() {
+        return;
+    }
+    let mut span = Span::started_ago("claude.execution", Duration::from_millis(metrics.elapsed_ms))
+        .attr("gen_ai.request.model", model)
+        .attr("claudia.project.path", project_path);
+    set_metrics_attrs(&mut span, metrics);
+    if metrics.is_error == Some(true) {
+        span.fail("Claude reported an error");
+    }
+}
+
+fn traces_url(endpoint: &str) -> String {
+    let endpoint = endpoint.trim_end_matches('/');
+    if endpoint.ends_with("/v1/traces") {
+        endpoint.to_string()
+    } else {
+        format!("{}/v1/traces", endpoint)
+    }
+}
+
+/// Send spans as an OTLP/JSON export request
+async fn export_spans(settings: &TelemetrySettings, spans: Vec<JsonValue>) -> Result<(), String> {
+    let body = json!({
+        "resourceSpans": [{
+            "resource": {
+                "attributes": [
+                    attribute_json("service.name", &settings.service_name.clone().into()),
+                    attribute_json("service.version", &env!("CARGO_PKG_VERSION").into()),
+                ],
+            },
+            "scopeSpans": [{
+                "scope": { "name": "claudia", "version": env!("CARGO_PKG_VERSION") },
+                "spans": spans,
+            }],
+        }],
+    });
+
+    let mut request = super::proxy::http_client()
+        .post(traces_url(&settings.endpoint))
+        .timeout(Duration::from_secs(10))
+        .json(&body);
+    for (name, value) in &settings.headers {
+        request = request.header(name, value);
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach collector: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Collector answered {}", response.status()));
+    }
+    Ok(())
+}
+
+/// Export finished spans periodically while export is enabled
+pub fn start_telemetry_exporter() {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(EXPORT_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+            let Some(settings) = current() else {
+                continue;
+            };
+            let spans = match PENDING.lock() {
+                Ok(mut pending) if !pending.is_empty() => std::mem::take(&mut *pending),
+                _ => continue,
+            };
+            let count = spans.len();
+            if let Err(e) = export_spans(&settings, spans).await {
+                warn!("Dropped {} spans: {}", count, e);
+            }
+        }
+    });
+}
+
+/// Get the OTLP export settings
+#[tauri::command]
+#[specta::specta]
+pub async fn get_telemetry_settings(db: State<'_, AgentDb>) -> Result<TelemetrySettings, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    Ok(load_settings(&conn))
+}
+
+/// Save the OTLP export settings; spans are recorded from now on when enabled
+#[tauri::command]
+#[specta::specta]
+pub async fn set_telemetry_settings(
+    db: State<'_, AgentDb>,
+    settings: TelemetrySettings,
+) -> Result<(), String> {
+    if settings.enabled {
+        let url = reqwest::Url::parse(&settings.endpoint)
+            .map_err(|e| format!("Invalid collector endpoint: {}", e))?;
+        if url.scheme() != "http" && url.scheme() != "https" {
+            return Err("Collector endpoints must use http or https".to_string());
+        }
+    }
+    {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        set_setting(&conn, "otlp_export", &settings)
+            .map_err(|e| format!("Failed to save telemetry settings: {}", e))?;
+    }
+    if !settings.enabled {
+        if let Ok(mut pending) = PENDING.lock() {
+            pending.clear();
+        }
+    }
+    if let Ok(mut current) = CURRENT.write() {
+        *current = Some(settings);
+    }
+    Ok(())
+}
+
+/// Send a test span to the collector and report whether it was accepted
+#[tauri::command]
+#[specta::specta]
+pub async fn test_telemetry_export(settings: TelemetrySettings) -> Result<(), String> {
+    let span = Span::start("claudia.test").attr("claudia.test", true);
+    export_spans(&settings, vec![span.take_json()]).await.map_err(|e| {
+        error!("Telemetry test export failed: {}", e);
+        e
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_spans_as_otlp_json() {
+        let mut span = Span::started_ago("agent.run", Duration::from_secs(2))
+            .attr("claudia.run.id", 7i64)
+            .attr("claudia.cost_usd", 0.25);
+        span.fail("boom");
+        let json = span.take_json();
+
+        assert_eq!(json["traceId"].as_str().map(str::len), Some(32));
+        assert_eq!(json["spanId"].as_str().map(str::len), Some(16));
+        assert_eq!(json["attributes"][0]["value"]["intValue"], "7");
+        assert_eq!(json["attributes"][1]["value"]["doubleValue"], 0.25);
+        assert_eq!(json["status"]["code"], 2);
+        let start: u128 = json["startTimeUnixNano"].as_str().unwrap().parse().unwrap();
+        let end: u128 = json["endTimeUnixNano"].as_str().unwrap().parse().unwrap();
+        assert!(end - start >= 2_000_000_000);
+    }
+
+    #[test]
+    fn appends_the_traces_path() {
+        assert_eq!(traces_url("http://localhost:4318/"), "http://localhost:4318/v1/traces");
+        assert_eq!(
+            traces_url("https://api.honeycomb.io/v1/traces"),
+            "https://api.honeycomb.io/v1/traces"
+        );
+    }
+}
@@ -0,0 +1,307 @@
+use log::{error, info, warn};
+use rusqlite::Connection;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use tauri::menu::{Menu, MenuBuilder, MenuItemBuilder, SubmenuBuilder};
+use tauri::tray::TrayIconBuilder;
+use tauri::{AppHandle, Manager, Wry};
+
+use super::agents::AgentDb;
+use super::claude::get_claude_dir;
+use super::deep_link::{open_target, show_main_window, DeepLinkTarget};
+use super::scheduler::{schedules_paused, set_schedules_paused};
+
+const TRAY_ID: &str = "main";
+
+/// How often the tray checks for run and agent changes
+const REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Agents listed under "Run agent…"; the rest are reached through the window
+const MAX_MENU_AGENTS: usize = 20;
+
+const RUN_AGENT_PREFIX: &str = "tray:run-agent:";
+const OPEN_LATEST_SESSION: &str = "tray:open-latest-session";
+const TOGGLE_SCHEDULES: &str = "tray:toggle-schedules";
+const SHOW_WINDOW: &str = "tray:show";
+const QUIT: &str = "tray:quit";
+
+/// What the tray menu shows; the menu is only rebuilt when this changes
+#[derive(Debug, Clone, PartialEq, Default)]
+struct TrayStatus {
+    running_runs: usize,
+    running_agents: Vec<String>,
+    /// (id, name) of the agents offered in "Run agent…"
+    agents: Vec<(i64, String)>,
+    schedules_paused: bool,
+}
+
+impl TrayStatus {
+    fn load(conn: &Connection) -> Result<Self, String> {
+        let mut stmt = conn
+            .prepare("SELECT agent_name FROM agent_runs WHERE status = 'running' ORDER BY process_started_at")
+            .map_err(|e| e.to_string())?;
+        let running_agents = stmt
+            .query_map([], |row| row.get::<_, String>(0))
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?;
+
+        let mut stmt = conn
+            .prepare("SELECT id, name FROM agents ORDER BY name COLLATE NOCASE LIMIT ?1")
+            .map_err(|e| e.to_string())?;
+        let agents = stmt
+            .query_map([MAX_MENU_AGENTS as i64], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?;
+
+        Ok(Self {
+            running_runs: running_agents.len(),
+            running_agents,
+            agents,
+            schedules_paused: schedules_paused(conn),
+        })
+    }
+
+    fn summary(&self) -> String {
+        match self.running_runs {
+            0 => "No agents running".to_string(),
+            1 => format!("Running: {}", self.running_agents[0]),
+            n => format!("{} agents running", n),
+        }
+    }
+
+    fn tooltip(&self) -> String {
+        let mut tooltip = format!("Claudia — {}", self.summary());
+        if self.schedules_paused {
+            tooltip.push_str(" (schedules paused)");
+        }
+        tooltip
+    }
+}
+
+/// Last status shown in the tray
+#[derive(Default)]
+pub struct TrayState(Mutex<Option<TrayStatus>>);
+
+fn build_menu(app: &AppHandle, status: &TrayStatus) -> tauri::Result<Menu<Wry>> {
+    let summary = MenuItemBuilder::with_id("tray:status", status.summary())
+        .enabled(false)
+        .build(app)?;
+
+    let mut run_agent = SubmenuBuilder::with_id(app, "tray:run-agent", "Run agent…");
+    if status.agents.is_empty() {
+        run_agent = run_agent.item(
+            &MenuItemBuilder::with_id("tray:no-agents", "No agents yet")
+                .enabled(false)
+                .build(app)?,
+        );
+    }
+    for (id, name) in &status.agents {
+        run_agent = run_agent.text(format!("{}{}", RUN_AGENT_PREFIX, id), name);
+    }
+
+    let toggle = if status.schedules_paused {
+        "Resume scheduled runs"
+    } else {
+        "Pause scheduled runs"
+    };
+    MenuBuilder::new(app)
+        .item(&summary)
+        .separator()
+        .item(&run_agent.build()?)
+        .text(OPEN_LATEST_SESSION, "Open latest session")
+        .text(TOGGLE_SCHEDULES, toggle)
+        .separator()
+        .text(SHOW_WINDOW, "Show Claudia")
+        .text(QUIT, "Quit Claudia")
+        .build()
+}
+
+/// The most recently written session transcript as (project id, session id)
+pub(crate) fn latest_session(claude_dir: &Path) -> Option<(String, String)> {
+    let mut latest: Option<(SystemTime, String, String)> = None;
+    for project in std::fs::read_dir(claude_dir.join("projects")).ok()?.flatten() {
+        let Ok(sessions) = std::fs::read_dir(project.path()) else {
+            continue;
+        };
+        for session in sessions.flatten() {
+            let path = session.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("jsonl") {
+                continue;
+            }
+            let Ok(modified) = session.metadata().and_then(|m| m.modified()) else {
+                continue;
+            };
+            if latest.as_ref().is_some_and(|(time, _, _)| *time >= modified) {
+                continue;
+            }
+            let (Some(project_id), Some(session_id)) = (
+                project.file_name().to_str().map(str::to_string),
+                path.file_stem().and_then(|s| s.to_str()).map(str::to_string),
+            ) else {
+                continue;
+            };
+            latest = Some((modified, project_id, session_id));
+        }
+    }
+    latest.map(|(_, project_id, session_id)| (project_id, session_id))
+}
+
+fn handle_menu_event(app: &AppHandle, id: &str) {
+    match id {
+        OPEN_LATEST_SESSION => {
+            let latest = get_claude_dir().ok().and_then(|dir| latest_session(&dir));
+            match latest {
+                Some((project_id, session_id)) => open_target(
+                    app,
+                    DeepLinkTarget::Session {
+                        session_id,
+                        project_id: Some(project_id),
+                    },
+                ),
+                None => show_main_window(app),
+            }
+        }
+        TOGGLE_SCHEDULES => {
+            let paused = {
+                let db = app.state::<AgentDb>();
+                let Ok(conn) = db.0.lock() else {
+                    return;
+                };
+                schedules_paused(&conn)
+            };
+            if let Err(e) = set_schedules_paused(app, !paused) {
+                error!("{}", e);
+            }
+        }
+        SHOW_WINDOW => show_main_window(app),
+        QUIT => app.exit(0),
+        id => {
+            let Some(agent_id) = id
+                .strip_prefix(RUN_AGENT_PREFIX)
+                .and_then(|id| id.parse::<i64>().ok())
+            else {
+                return;
+            };
+            // Like `claudia://agent/run`, open the prefilled run form instead of starting it
+            let name = {
+                let state = app.state::<TrayState>();
+                let shown = state.0.lock().ok();
+                shown.as_ref().and_then(|status| {
+                    let agents = &status.as_ref()?.agents;
+                    agents
+                        .iter()
+                        .find(|(id, _)| *id == agent_id)
+                        .map(|(_, name)| name.clone())
+                })
+            };
+            if let Some(name) = name {
+                open_target(
+                    app,
+                    DeepLinkTarget::AgentRun {
+                        name,
+                        project: None,
+                        task: None,
+                    },
+                );
+            }
+        }
+    }
+}
+
+/// Update the tray menu and tooltip if runs, agents or the scheduler changed
+pub fn refresh_tray(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    let status = {
+        let db = app.state::<AgentDb>();
+        let Ok(conn) = db.0.lock() else {
+            return;
+        };
+        match TrayStatus::load(&conn) {
+            Ok(status) => status,
+            Err(e) => {
+                warn!("Failed to read tray status: {}", e);
+                return;
+            }
+        }
+    };
+    let state = app.state::<TrayState>();
+    let Ok(mut shown) = state.0.lock() else {
+        return;
+    };
+    if shown.as_ref() == Some(&status) {
+        return;
+    }
+    match build_menu(app, &status) {
+        Ok(menu) => {
+            let _ = tray.set_menu(Some(menu));
+            let _ = tray.set_tooltip(Some(status.tooltip()));
+            *shown = Some(status);
+        }
+        Err(e) => warn!("Failed to build tray menu: {}", e),
+    }
+}
+
+/// Create the tray icon and keep its menu in step with the app
+pub fn start_tray(app: AppHandle) {
+    let Some(icon) = app.default_window_icon().cloned() else {
+        warn!("No app icon, not creating a tray icon");
+        return;
+    };
+    let menu = match build_menu(&app, &TrayStatus::default()) {
+        Ok(menu) => menu,
+        Err(e) => {
+            error!("Failed to build tray menu: {}", e);
+            return;
+        }
+    };
+    let tray = TrayIconBuilder::with_id(TRAY_ID)
+        .icon(icon)
+        .tooltip("Claudia")
+        .menu(&menu)
+        .show_menu_on_left_click(true)
+        .on_menu_event(|app, event| handle_menu_event(app, event.id().as_ref()))
+        .build(&app);
+    if let Err(e) = tray {
+        error!("Failed to create tray icon: {}", e);
+        return;
+    }
+    info!("Created tray icon");
+
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(REFRESH_INTERVAL);
+        loop {
+            interval.tick().await;
+            refresh_tray(&app);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn finds_the_most_recent_session() {
+        let dir = tempfile::tempdir().unwrap();
+        let old = dir.path().join("projects/-work-old");
+        let new = dir.path().join("projects/-work-new");
+        fs::create_dir_all(&old).unwrap();
+        fs::create_dir_all(&new).unwrap();
+        fs::write(old.join("aaa.jsonl"), "{}").unwrap();
+        std::thread::sleep(Duration::from_millis(20));
+        fs::write(new.join("bbb.jsonl"), "{}").unwrap();
+        fs::write(new.join("notes.txt"), "").unwrap();
+
+        assert_eq!(
+            latest_session(dir.path()),
+            Some(("-work-new".to_string(), "bbb".to_string()))
+        );
+        assert_eq!(latest_session(&dir.path().join("missing")), None);
+    }
+}
@@ -0,0 +1,331 @@
+use log::{error, info, warn};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State, Url};
+use tauri_plugin_updater::{Update, UpdaterExt};
+use tokio::sync::Mutex;
+
+use super::agents::{get_setting, set_setting, AgentDb};
+
+const SETTINGS_KEY: &str = "update_settings";
+
+/// Public key release artifacts are signed with, provided by the release build
+///
+/// `plugins.updater` in tauri.conf.json only exists so the plugin can start; its empty
+/// `pubkey` is replaced with this one for every check.
+const UPDATER_PUBKEY: Option<&str> = option_env!("CLAUDIA_UPDATER_PUBKEY");
+
+/// Update manifest of the latest stable release
+const STABLE_ENDPOINT: &str =
+    "https://github.com/getAsterisk/claudia/releases/latest/download/latest.json";
+
+/// Update manifest of the newest pre-release, re-uploaded to a fixed tag by the release build
+const BETA_ENDPOINT: &str =
+    "https://github.com/getAsterisk/claudia/releases/download/updater-beta/latest.json";
+
+/// Delay before the startup check, so it does not compete with loading the app
+const STARTUP_CHECK_DELAY: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateChannel {
+    #[default]
+    Stable,
+    Beta,
+}
+
+impl UpdateChannel {
+    fn endpoint(self) -> &'static str {
+        match self {
+            UpdateChannel::Stable => STABLE_ENDPOINT,
+            UpdateChannel::Beta => BETA_ENDPOINT,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct UpdateSettings {
+    pub channel: UpdateChannel,
+    /// Check for an update shortly after startup and emit `update-available`
+    pub check_on_startup: bool,
+}
+
+impl Default for UpdateSettings {
+    fn default() -> Self {
+        Self {
+            channel: UpdateChannel::Stable,
+            check_on_startup: true,
+        }
+    }
+}
+
+/// An available update
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct UpdateInfo {
+    pub version: String,
+    pub current_version: String,
+    pub channel: UpdateChannel,
+    /// Release notes, usually Markdown
+    pub notes: Option<String>,
+    pub published_at: Option<String>,
+    /// Downloaded and waiting to be installed
+    pub downloaded: bool,
+}
+
+/// Payload of the `update-download-progress` event
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct UpdateDownloadProgress {
+    pub downloaded: u64,
+    pub total: Option<u64>,
+}
+
+struct PendingUpdate {
+    update: Update,
+    channel: UpdateChannel,
+    /// The signed package, once downloaded
+    package: Option<Vec<u8>>,
+}
+
+impl PendingUpdate {
+    fn info(&self) -> UpdateInfo {
+        UpdateInfo {
+            version: self.update.version.clone(),
+            current_version: self.update.current_version.clone(),
+            channel: self.channel,
+            notes: self.update.body.clone(),
+            published_at: self.update.date.map(|date| date.to_string()),
+            downloaded: self.package.is_some(),
+        }
+    }
+}
+
+/// The update found by the last check, kept so it can be downloaded now and installed later
+#[derive(Default)]
+pub struct UpdaterState(Mutex<Option<PendingUpdate>>);
+
+fn load_settings(conn: &Connection) -> UpdateSettings {
+    get_setting(conn, SETTINGS_KEY).unwrap_or_default()
+}
+
+async fn check(app: &AppHandle, channel: UpdateChannel) -> Result<Option<Update>, String> {
+    let pubkey = UPDATER_PUBKEY
+        .filter(|key| !key.is_empty())
+        .ok_or("This build of Claudia was made without an update signing key")?;
+    let endpoint = Url::parse(channel.endpoint()).map_err(|e| e.to_string())?;
+    let mut builder = app
+        .updater_builder()
+        .pubkey(pubkey)
+        .endpoints(vec![endpoint])
+        .map_err(|e| e.to_string())?;
+    if let Some(proxy) = super::proxy::https_proxy().and_then(|url| Url::parse(&url).ok()) {
+        builder = builder.proxy(proxy);
+    }
+    builder
+        .build()
+        .map_err(|e| format!("Failed to set up the updater: {}", e))?
+        .check()
+        .await
+        .map_err(|e| format!("Failed to check for updates: {}", e))
+}
+
+/// Check the configured channel, remembering the update for download and install
+async fn check_and_remember(app: &AppHandle) -> Result<Option<UpdateInfo>, String> {
+    let settings = {
+        let db = app.state::<AgentDb>();
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        load_settings(&conn)
+    };
+    let update = check(app, settings.channel).await?;
+
+    let state = app.state::<UpdaterState>();
+    let mut pending = state.0.lock().await;
+    let Some(update) = update else {
+        *pending = None;
+        return Ok(None);
+    };
+    // Keep an already downloaded package if the check found the same release again
+    let keep = pending
+        .as_ref()
+        .is_some_and(|p| p.update.version == update.version && p.channel == settings.channel);
+    if !keep {
+        *pending = Some(PendingUpdate {
+            update,
+            channel: settings.channel,
+            package: None,
+        });
+    }
+    Ok(pending.as_ref().map(PendingUpdate::info))
+}
+
+/// Get the update settings
+#[tauri::command]
+#[specta::specta]
+pub async fn get_update_settings(db: State<'_, AgentDb>) -> Result<UpdateSettings, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    Ok(load_settings(&conn))
+}
+
+/// Save the update settings; switching channels forgets an update found on the old one
+#[tauri::command]
+#[specta::specta]
+pub async fn set_update_settings(
+    db: State<'_, AgentDb>,
+    state: State<'_, UpdaterState>,
+    settings: UpdateSettings,
+) -> Result<(), String> {
+    {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        set_setting(&conn, SETTINGS_KEY, &settings)
+            .map_err(|e| format!("Failed to save update settings: {}", e))?;
+    }
+    let mut pending = state.0.lock().await;
+    if pending.as_ref().is_some_and(|p| p.channel != settings.channel) {
+        *pending = None;
+    }
+    Ok(())
+}
+
+/// Check the configured channel for a newer release, returning it with its release notes
+#[tauri::command]
+#[specta::specta]
+pub async fn check_for_updates(app: AppHandle) -> Result<Option<UpdateInfo>, String> {
+    check_and_remember(&app).await
+}
+
+/// Download the update found by the last check without installing it
+///
+/// Emits `update-download-progress` while downloading. The package is installed by
+/// `install_update`, or when Claudia quits.
+#[tauri::command]
+#[specta::specta]
+pub async fn download_update(
+    app: AppHandle,
+    state: State<'_, UpdaterState>,
+) -> Result<UpdateInfo, String> {
+    let update = {
+        let pending = state.0.lock().await;
+        let pending = pending.as_ref().ok_or("No update found; check for updates first")?;
+        if pending.package.is_some() {
+            return Ok(pending.info());
+        }
+        pending.update.clone()
+    };
+
+    let mut downloaded = 0u64;
+    let emitter = app.clone();
+    let package = update
+        .download(
+            move |chunk, total| {
+                downloaded += chunk as u64;
+                let _ = emitter.emit(
+                    "update-download-progress",
+                    UpdateDownloadProgress { downloaded, total },
+                );
+            },
+            || {},
+        )
+        .await
+        .map_err(|e| format!("Failed to download update: {}", e))?;
+
+    let mut pending = state.0.lock().await;
+    let pending = pending
+        .as_mut()
+        .filter(|p| p.update.version == update.version)
+        .ok_or("The update changed while downloading; check again")?;
+    pending.package = Some(package);
+    info!("Downloaded update {}", update.version);
+    Ok(pending.info())
+}
+
+/// Install the downloaded update, restarting Claudia when `restart` is set
+///
+/// Without a restart the new version starts the next time Claudia is opened.
+#[tauri::command]
+#[specta::specta]
+pub async fn install_update(
+    app: AppHandle,
+    state: State<'_, UpdaterState>,
+    restart: bool,
+) -> Result<(), String> {
+    {
+        let mut pending = state.0.lock().await;
+        let update = pending
+            .take_if(|p| p.package.is_some())
+            .ok_or("No downloaded update to install")?;
+        let package = update.package.as_deref().unwrap_or_default();
+        update
+            .update
+            .install(package)
+            .map_err(|e| format!("Failed to install update: {}", e))?;
+        info!("Installed update {}", update.update.version);
+    }
+    if restart {
+        app.restart();
+    }
+    Ok(())
+}
+
+/// Install a downloaded update when Claudia exits, so deferred updates apply on next launch
+pub fn install_pending_update_on_exit(app: &AppHandle) {
+    let state = app.state::<UpdaterState>();
+    let Ok(mut pending) = state.0.try_lock() else {
+        return;
+    };
+    let Some(update) = pending.take_if(|p| p.package.is_some()) else {
+        return;
+    };
+    let package = update.package.as_deref().unwrap_or_default();
+    match update.update.install(package) {
+        Ok(()) => info!("Installed update {} on exit", update.update.version),
+        Err(e) => error!("Failed to install update {} on exit: {}", update.update.version, e),
+    }
+}
+
+/// Check for an update after startup if enabled, emitting `update-available` when found
+pub fn start_update_checker(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(STARTUP_CHECK_DELAY).await;
+        let enabled = {
+            let db = app.state::<AgentDb>();
+            let Ok(conn) = db.0.lock() else {
+                return;
+            };
+            load_settings(&conn).check_on_startup
+        };
+        if !enabled || UPDATER_PUBKEY.is_none() {
+            return;
+        }
+        match check_and_remember(&app).await {
+            Ok(Some(update)) => {
+                info!("Update {} is available", update.version);
+                let _ = app.emit("update-available", &update);
+            }
+            Ok(None) => {}
+            Err(e) => warn!("{}", e),
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_default_to_the_stable_channel() {
+        let settings: UpdateSettings =
+            serde_json::from_str("{\"channel\":\"beta\",\"check_on_startup\":false}").unwrap();
+        assert_eq!(settings.channel, UpdateChannel::Beta);
+        assert_eq!(UpdateSettings::default().channel, UpdateChannel::Stable);
+        assert!(Url::parse(UpdateChannel::Beta.endpoint()).is_ok());
+    }
+
+    #[test]
+    fn plugin_config_is_valid() {
+        let conf: serde_json::Value =
+            serde_json::from_str(include_str!("../../tauri.conf.json")).unwrap();
+        let config: tauri_plugin_updater::Config =
+            serde_json::from_value(conf["plugins"]["updater"].clone()).unwrap();
+        assert_eq!(config.endpoints[0].as_str(), STABLE_ENDPOINT);
+    }
+}
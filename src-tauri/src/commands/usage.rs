@@ -1,12 +1,14 @@
 use chrono::{DateTime, Local, NaiveDate};
+use rusqlite::Connection;
 use serde::{Deserialize, Serialize};
-use serde_json;
 use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
-use tauri::command;
+use tauri::{command, State};
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+use super::agents::{get_setting, set_setting, AgentDb};
+use super::usage_index::{load_indexed_messages, update_usage_index};
+
+#[derive(Debug, Serialize, Deserialize, Clone, specta::Type)]
 pub struct UsageEntry {
     timestamp: String,
     model: String,
@@ -15,507 +17,403 @@ pub struct UsageEntry {
     cache_creation_tokens: u64,
     cache_read_tokens: u64,
     cost: f64,
+    /// What prompt caching saved on this message
+    cache_savings: f64,
     session_id: String,
     project_path: String,
+    /// Set when the session was started by an agent run
+    #[serde(default)]
+    agent_id: Option<i64>,
+    #[serde(default)]
+    agent_name: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, specta::Type)]
 pub struct UsageStats {
-    total_cost: f64,
-    total_tokens: u64,
-    total_input_tokens: u64,
-    total_output_tokens: u64,
-    total_cache_creation_tokens: u64,
-    total_cache_read_tokens: u64,
-    total_sessions: u64,
-    by_model: Vec<ModelUsage>,
-    by_date: Vec<DailyUsage>,
-    by_project: Vec<ProjectUsage>,
+    pub total_cost: f64,
+    pub total_tokens: u64,
+    pub total_input_tokens: u64,
+    pub total_output_tokens: u64,
+    pub total_cache_creation_tokens: u64,
+    pub total_cache_read_tokens: u64,
+    /// Net saving from prompt caching: cheaper cache reads minus the cache write premium
+    pub cache_savings: f64,
+    /// What the same usage would have cost without caching
+    pub cost_without_cache: f64,
+    /// Share of prompt tokens served from cache
+    pub cache_hit_rate: f64,
+    pub total_sessions: u64,
+    pub by_model: Vec<ModelUsage>,
+    pub by_date: Vec<DailyUsage>,
+    pub by_project: Vec<ProjectUsage>,
+    /// Usage of sessions started by agent runs
+    pub by_agent: Vec<AgentUsage>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, specta::Type)]
 pub struct ModelUsage {
-    model: String,
-    total_cost: f64,
-    total_tokens: u64,
-    input_tokens: u64,
-    output_tokens: u64,
-    cache_creation_tokens: u64,
-    cache_read_tokens: u64,
-    session_count: u64,
+    pub model: String,
+    pub total_cost: f64,
+    pub total_tokens: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub cache_savings: f64,
+    pub session_count: u64,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, specta::Type)]
 pub struct DailyUsage {
-    date: String,
-    total_cost: f64,
-    total_tokens: u64,
-    models_used: Vec<String>,
+    pub date: String,
+    pub total_cost: f64,
+    pub total_tokens: u64,
+    pub models_used: Vec<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, specta::Type)]
 pub struct ProjectUsage {
-    project_path: String,
-    project_name: String,
-    total_cost: f64,
-    total_tokens: u64,
-    session_count: u64,
-    last_used: String,
+    pub project_path: String,
+    pub project_name: String,
+    pub total_cost: f64,
+    pub total_tokens: u64,
+    pub session_count: u64,
+    pub last_used: String,
 }
 
-// Claude 4 pricing constants (per million tokens)
-const OPUS_4_INPUT_PRICE: f64 = 15.0;
-const OPUS_4_OUTPUT_PRICE: f64 = 75.0;
-const OPUS_4_CACHE_WRITE_PRICE: f64 = 18.75;
-const OPUS_4_CACHE_READ_PRICE: f64 = 1.50;
-
-const SONNET_4_INPUT_PRICE: f64 = 3.0;
-const SONNET_4_OUTPUT_PRICE: f64 = 15.0;
-const SONNET_4_CACHE_WRITE_PRICE: f64 = 3.75;
-const SONNET_4_CACHE_READ_PRICE: f64 = 0.30;
-
-#[derive(Debug, Deserialize)]
-struct JsonlEntry {
-    timestamp: String,
-    message: Option<MessageData>,
-    #[serde(rename = "sessionId")]
-    session_id: Option<String>,
-    #[serde(rename = "requestId")]
-    request_id: Option<String>,
-    #[serde(rename = "costUSD")]
-    cost_usd: Option<f64>,
+#[derive(Debug, Serialize, Deserialize, specta::Type)]
+pub struct AgentUsage {
+    pub agent_id: i64,
+    pub agent_name: String,
+    pub total_cost: f64,
+    pub total_tokens: u64,
+    /// Distinct sessions, i.e. runs
+    pub run_count: u64,
+    pub last_used: String,
 }
 
-#[derive(Debug, Deserialize)]
-struct MessageData {
-    id: Option<String>,
-    model: Option<String>,
-    usage: Option<UsageData>,
+/// How one model performed on a group's sessions
+#[derive(Debug, Serialize, Deserialize, specta::Type)]
+pub struct ModelEfficiency {
+    pub model: String,
+    pub session_count: u64,
+    pub total_cost: f64,
+    pub total_tokens: u64,
+    pub output_tokens: u64,
+    pub avg_cost_per_session: f64,
+    pub avg_tokens_per_session: f64,
+    /// Cost per thousand output tokens, comparable across task sizes
+    pub cost_per_1k_output_tokens: f64,
+    /// Average session cost relative to the cheapest model of the group
+    pub relative_cost: f64,
 }
 
-#[derive(Debug, Deserialize)]
-struct UsageData {
-    input_tokens: Option<u64>,
-    output_tokens: Option<u64>,
-    cache_creation_input_tokens: Option<u64>,
-    cache_read_input_tokens: Option<u64>,
+/// Models used for the same agent or project, cheapest first
+#[derive(Debug, Serialize, Deserialize, specta::Type)]
+pub struct ModelComparisonGroup {
+    /// Agent name or project path
+    pub group: String,
+    pub models: Vec<ModelEfficiency>,
 }
 
-fn calculate_cost(model: &str, usage: &UsageData) -> f64 {
-    let input_tokens = usage.input_tokens.unwrap_or(0) as f64;
-    let output_tokens = usage.output_tokens.unwrap_or(0) as f64;
-    let cache_creation_tokens = usage.cache_creation_input_tokens.unwrap_or(0) as f64;
-    let cache_read_tokens = usage.cache_read_input_tokens.unwrap_or(0) as f64;
-
-    // Calculate cost based on model
-    let (input_price, output_price, cache_write_price, cache_read_price) =
-        if model.contains("opus-4") || model.contains("claude-opus-4") {
-            (
-                OPUS_4_INPUT_PRICE,
-                OPUS_4_OUTPUT_PRICE,
-                OPUS_4_CACHE_WRITE_PRICE,
-                OPUS_4_CACHE_READ_PRICE,
-            )
-        } else if model.contains("sonnet-4") || model.contains("claude-sonnet-4") {
-            (
-                SONNET_4_INPUT_PRICE,
-                SONNET_4_OUTPUT_PRICE,
-                SONNET_4_CACHE_WRITE_PRICE,
-                SONNET_4_CACHE_READ_PRICE,
-            )
-        } else {
-            // Return 0 for unknown models to avoid incorrect cost estimations.
-            (0.0, 0.0, 0.0, 0.0)
-        };
-
-    // Calculate cost (prices are per million tokens)
-    let cost = (input_tokens * input_price / 1_000_000.0)
-        + (output_tokens * output_price / 1_000_000.0)
-        + (cache_creation_tokens * cache_write_price / 1_000_000.0)
-        + (cache_read_tokens * cache_read_price / 1_000_000.0);
-
-    cost
+/// A project's totals with its daily series and model breakdown
+#[derive(Debug, Serialize, Deserialize, specta::Type)]
+pub struct ProjectUsageBreakdown {
+    #[serde(flatten)]
+    project: ProjectUsage,
+    by_date: Vec<DailyUsage>,
+    by_model: Vec<ModelUsage>,
 }
 
-fn parse_jsonl_file(
-    path: &PathBuf,
-    encoded_project_name: &str,
-    processed_hashes: &mut HashSet<String>,
-) -> Vec<UsageEntry> {
-    let mut entries = Vec::new();
-    let mut actual_project_path: Option<String> = None;
-
-    if let Ok(content) = fs::read_to_string(path) {
-        // Extract session ID from the file path
-        let session_id = path
-            .parent()
-            .and_then(|p| p.file_name())
-            .and_then(|n| n.to_str())
-            .unwrap_or("unknown")
-            .to_string();
-
-        for line in content.lines() {
-            if line.trim().is_empty() {
-                continue;
-            }
-
-            if let Ok(json_value) = serde_json::from_str::<serde_json::Value>(line) {
-                // Extract the actual project path from cwd if we haven't already
-                if actual_project_path.is_none() {
-                    if let Some(cwd) = json_value.get("cwd").and_then(|v| v.as_str()) {
-                        actual_project_path = Some(cwd.to_string());
-                    }
-                }
-
-                // Try to parse as JsonlEntry for usage data
-                if let Ok(entry) = serde_json::from_value::<JsonlEntry>(json_value) {
-                    if let Some(message) = &entry.message {
-                        // Deduplication based on message ID and request ID
-                        if let (Some(msg_id), Some(req_id)) = (&message.id, &entry.request_id) {
-                            let unique_hash = format!("{}:{}", msg_id, req_id);
-                            if processed_hashes.contains(&unique_hash) {
-                                continue; // Skip duplicate entry
-                            }
-                            processed_hashes.insert(unique_hash);
-                        }
-
-                        if let Some(usage) = &message.usage {
-                            // Skip entries without meaningful token usage
-                            if usage.input_tokens.unwrap_or(0) == 0
-                                && usage.output_tokens.unwrap_or(0) == 0
-                                && usage.cache_creation_input_tokens.unwrap_or(0) == 0
-                                && usage.cache_read_input_tokens.unwrap_or(0) == 0
-                            {
-                                continue;
-                            }
-
-                            let cost = entry.cost_usd.unwrap_or_else(|| {
-                                if let Some(model_str) = &message.model {
-                                    calculate_cost(model_str, usage)
-                                } else {
-                                    0.0
-                                }
-                            });
-
-                            // Use actual project path if found, otherwise use encoded name
-                            let project_path = actual_project_path
-                                .clone()
-                                .unwrap_or_else(|| encoded_project_name.to_string());
-
-                            entries.push(UsageEntry {
-                                timestamp: entry.timestamp,
-                                model: message
-                                    .model
-                                    .clone()
-                                    .unwrap_or_else(|| "unknown".to_string()),
-                                input_tokens: usage.input_tokens.unwrap_or(0),
-                                output_tokens: usage.output_tokens.unwrap_or(0),
-                                cache_creation_tokens: usage
-                                    .cache_creation_input_tokens
-                                    .unwrap_or(0),
-                                cache_read_tokens: usage.cache_read_input_tokens.unwrap_or(0),
-                                cost,
-                                session_id: entry.session_id.unwrap_or_else(|| session_id.clone()),
-                                project_path,
-                            });
-                        }
-                    }
-                }
-            }
-        }
-    }
-
-    entries
+/// Prices in USD per million tokens
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, specta::Type)]
+pub struct ModelPricing {
+    pub input: f64,
+    pub output: f64,
+    pub cache_write: f64,
+    pub cache_read: f64,
 }
 
-fn get_earliest_timestamp(path: &PathBuf) -> Option<String> {
-    if let Ok(content) = fs::read_to_string(path) {
-        let mut earliest_timestamp: Option<String> = None;
-        for line in content.lines() {
-            if let Ok(json_value) = serde_json::from_str::<serde_json::Value>(line) {
-                if let Some(timestamp_str) = json_value.get("timestamp").and_then(|v| v.as_str()) {
-                    if let Some(current_earliest) = &earliest_timestamp {
-                        if timestamp_str < current_earliest.as_str() {
-                            earliest_timestamp = Some(timestamp_str.to_string());
-                        }
-                    } else {
-                        earliest_timestamp = Some(timestamp_str.to_string());
-                    }
-                }
-            }
-        }
-        return earliest_timestamp;
+const fn pricing(input: f64, output: f64, cache_write: f64, cache_read: f64) -> ModelPricing {
+    ModelPricing {
+        input,
+        output,
+        cache_write,
+        cache_read,
     }
-    None
 }
 
-fn get_all_usage_entries(claude_path: &PathBuf) -> Vec<UsageEntry> {
-    let mut all_entries = Vec::new();
-    let mut processed_hashes = HashSet::new();
-    let projects_dir = claude_path.join("projects");
+/// Built-in prices, keyed by a substring of the model name
+const DEFAULT_PRICING: &[(&str, ModelPricing)] = &[
+    ("opus-4-5", pricing(5.0, 25.0, 6.25, 0.50)),
+    ("opus-4", pricing(15.0, 75.0, 18.75, 1.50)),
+    ("sonnet-4", pricing(3.0, 15.0, 3.75, 0.30)),
+    ("haiku-4-5", pricing(1.0, 5.0, 1.25, 0.10)),
+    ("claude-3-opus", pricing(15.0, 75.0, 18.75, 1.50)),
+    ("claude-3-7-sonnet", pricing(3.0, 15.0, 3.75, 0.30)),
+    ("claude-3-5-sonnet", pricing(3.0, 15.0, 3.75, 0.30)),
+    ("claude-3-5-haiku", pricing(0.80, 4.0, 1.0, 0.08)),
+    ("claude-3-haiku", pricing(0.25, 1.25, 0.30, 0.03)),
+];
+
+/// Settings key holding user price overrides as `{pattern: ModelPricing}`
+const PRICING_OVERRIDES_KEY: &str = "model_pricing_overrides";
+
+/// The price list used for cost calculation: built-in prices plus user overrides
+#[derive(Debug, Clone, Default)]
+pub struct PricingTable {
+    overrides: HashMap<String, ModelPricing>,
+}
 
-    let mut files_to_process: Vec<(PathBuf, String)> = Vec::new();
+impl PricingTable {
+    /// Load the user's overrides from app settings
+    pub fn load(conn: &Connection) -> Self {
+        let overrides = get_setting(conn, PRICING_OVERRIDES_KEY).unwrap_or_default();
+        Self { overrides }
+    }
 
-    if let Ok(projects) = fs::read_dir(&projects_dir) {
-        for project in projects.flatten() {
-            if project.file_type().map(|t| t.is_dir()).unwrap_or(false) {
-                let project_name = project.file_name().to_string_lossy().to_string();
-                let project_path = project.path();
+    /// The pattern whose prices apply to a model; the longest matching pattern wins
+    fn matching_pattern<'a>(&'a self, model: &str) -> Option<(&'a str, ModelPricing, bool)> {
+        let overrides = self
+            .overrides
+            .iter()
+            .map(|(pattern, pricing)| (pattern.as_str(), *pricing, true));
+        let defaults = DEFAULT_PRICING
+            .iter()
+            .filter(|(pattern, _)| !self.overrides.contains_key(*pattern))
+            .map(|(pattern, pricing)| (*pattern, *pricing, false));
+        overrides
+            .chain(defaults)
+            .filter(|(pattern, _, _)| model.contains(pattern))
+            .max_by_key(|(pattern, _, _)| pattern.len())
+    }
 
-                walkdir::WalkDir::new(&project_path)
-                    .into_iter()
-                    .filter_map(Result::ok)
-                    .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some("jsonl"))
-                    .for_each(|entry| {
-                        files_to_process.push((entry.path().to_path_buf(), project_name.clone()));
-                    });
-            }
-        }
+    /// Whether a user override decides the price of a model
+    fn is_overridden(&self, model: &str) -> bool {
+        self.matching_pattern(model)
+            .is_some_and(|(_, _, overridden)| overridden)
     }
 
-    // Sort files by their earliest timestamp to ensure chronological processing
-    // and deterministic deduplication.
-    files_to_process.sort_by_cached_key(|(path, _)| get_earliest_timestamp(path));
+    fn cost(&self, model: &str, usage: &UsageData) -> f64 {
+        // Unknown models cost 0 to avoid incorrect cost estimations
+        let Some((_, prices, _)) = self.matching_pattern(model) else {
+            return 0.0;
+        };
+        let tokens =
+            |count: Option<u64>, price: f64| count.unwrap_or(0) as f64 * price / 1_000_000.0;
+        tokens(usage.input_tokens, prices.input)
+            + tokens(usage.output_tokens, prices.output)
+            + tokens(usage.cache_creation_input_tokens, prices.cache_write)
+            + tokens(usage.cache_read_input_tokens, prices.cache_read)
+    }
 
-    for (path, project_name) in files_to_process {
-        let entries = parse_jsonl_file(&path, &project_name, &mut processed_hashes);
-        all_entries.extend(entries);
+    /// Cost difference between sending cached prompt tokens as regular input and
+    /// the cache reads and writes that were actually billed
+    fn cache_savings(&self, model: &str, usage: &UsageData) -> f64 {
+        let Some((_, prices, _)) = self.matching_pattern(model) else {
+            return 0.0;
+        };
+        let read = usage.cache_read_input_tokens.unwrap_or(0) as f64;
+        let written = usage.cache_creation_input_tokens.unwrap_or(0) as f64;
+        (read * (prices.input - prices.cache_read) - written * (prices.cache_write - prices.input))
+            / 1_000_000.0
     }
+}
+
+/// A row of the price list shown in settings
+#[derive(Debug, Serialize, Deserialize, specta::Type)]
+pub struct ModelPricingEntry {
+    pub pattern: String,
+    pub pricing: ModelPricing,
+    /// Set by the user rather than built in
+    pub overridden: bool,
+}
 
-    // Sort by timestamp
-    all_entries.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+#[derive(Debug)]
+struct UsageData {
+    input_tokens: Option<u64>,
+    output_tokens: Option<u64>,
+    cache_creation_input_tokens: Option<u64>,
+    cache_read_input_tokens: Option<u64>,
+}
 
-    all_entries
+/// Estimate the cost in USD of a single request's token usage with built-in prices
+pub fn estimate_cost(
+    model: &str,
+    input_tokens: u64,
+    output_tokens: u64,
+    cache_creation_tokens: u64,
+    cache_read_tokens: u64,
+) -> f64 {
+    PricingTable::default().cost(
+        model,
+        &UsageData {
+            input_tokens: Some(input_tokens),
+            output_tokens: Some(output_tokens),
+            cache_creation_input_tokens: Some(cache_creation_tokens),
+            cache_read_input_tokens: Some(cache_read_tokens),
+        },
+    )
 }
 
-#[command]
-pub fn get_usage_stats(days: Option<u32>) -> Result<UsageStats, String> {
+/// All usage entries under `~/.claude`, priced with the user's current price list
+fn load_usage_entries(db: &AgentDb) -> Result<Vec<UsageEntry>, String> {
     let claude_path = dirs::home_dir()
         .ok_or("Failed to get home directory")?
         .join(".claude");
+    update_usage_index(db, &claude_path)?;
+
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let pricing = PricingTable::load(&conn);
+    let messages = load_indexed_messages(&conn)?;
+
+    // Agent runs record the session they created, which attributes its usage to the agent
+    let mut stmt = conn
+        .prepare("SELECT session_id, agent_id, agent_name FROM agent_runs WHERE session_id != ''")
+        .map_err(|e| e.to_string())?;
+    let agent_sessions: HashMap<String, (i64, String)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, (row.get(1)?, row.get(2)?))))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<_, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(messages
+        .into_iter()
+        .map(|message| {
+            let usage = UsageData {
+                input_tokens: Some(message.input_tokens),
+                output_tokens: Some(message.output_tokens),
+                cache_creation_input_tokens: Some(message.cache_creation_tokens),
+                cache_read_input_tokens: Some(message.cache_read_tokens),
+            };
+            // A reported cost wins unless the user overrode the model's prices
+            let cost = message
+                .reported_cost
+                .filter(|_| !pricing.is_overridden(&message.model))
+                .unwrap_or_else(|| pricing.cost(&message.model, &usage));
+            let agent = agent_sessions.get(&message.session_id);
+            UsageEntry {
+                agent_id: agent.map(|(id, _)| *id),
+                agent_name: agent.map(|(_, name)| name.clone()),
+                cache_savings: pricing.cache_savings(&message.model, &usage),
+                timestamp: message.timestamp,
+                model: message.model,
+                input_tokens: message.input_tokens,
+                output_tokens: message.output_tokens,
+                cache_creation_tokens: message.cache_creation_tokens,
+                cache_read_tokens: message.cache_read_tokens,
+                cost,
+                session_id: message.session_id,
+                project_path: message.project_path,
+            }
+        })
+        .collect())
+}
 
-    let all_entries = get_all_usage_entries(&claude_path);
-
-    if all_entries.is_empty() {
-        return Ok(UsageStats {
-            total_cost: 0.0,
-            total_tokens: 0,
-            total_input_tokens: 0,
-            total_output_tokens: 0,
-            total_cache_creation_tokens: 0,
-            total_cache_read_tokens: 0,
-            total_sessions: 0,
-            by_model: vec![],
-            by_date: vec![],
-            by_project: vec![],
-        });
-    }
-
-    // Filter by days if specified
-    let filtered_entries = if let Some(days) = days {
-        let cutoff = Local::now().naive_local().date() - chrono::Duration::days(days as i64);
-        all_entries
-            .into_iter()
-            .filter(|e| {
-                if let Ok(dt) = DateTime::parse_from_rfc3339(&e.timestamp) {
-                    dt.naive_local().date() >= cutoff
-                } else {
-                    false
-                }
-            })
-            .collect()
-    } else {
-        all_entries
-    };
-
-    // Calculate aggregated stats
-    let mut total_cost = 0.0;
-    let mut total_input_tokens = 0u64;
-    let mut total_output_tokens = 0u64;
-    let mut total_cache_creation_tokens = 0u64;
-    let mut total_cache_read_tokens = 0u64;
-
-    let mut model_stats: HashMap<String, ModelUsage> = HashMap::new();
-    let mut daily_stats: HashMap<String, DailyUsage> = HashMap::new();
-    let mut project_stats: HashMap<String, ProjectUsage> = HashMap::new();
-
-    for entry in &filtered_entries {
-        // Update totals
-        total_cost += entry.cost;
-        total_input_tokens += entry.input_tokens;
-        total_output_tokens += entry.output_tokens;
-        total_cache_creation_tokens += entry.cache_creation_tokens;
-        total_cache_read_tokens += entry.cache_read_tokens;
-
-        // Update model stats
-        let model_stat = model_stats
-            .entry(entry.model.clone())
-            .or_insert(ModelUsage {
-                model: entry.model.clone(),
-                total_cost: 0.0,
-                total_tokens: 0,
-                input_tokens: 0,
-                output_tokens: 0,
-                cache_creation_tokens: 0,
-                cache_read_tokens: 0,
-                session_count: 0,
-            });
-        model_stat.total_cost += entry.cost;
-        model_stat.input_tokens += entry.input_tokens;
-        model_stat.output_tokens += entry.output_tokens;
-        model_stat.cache_creation_tokens += entry.cache_creation_tokens;
-        model_stat.cache_read_tokens += entry.cache_read_tokens;
-        model_stat.total_tokens = model_stat.input_tokens + model_stat.output_tokens;
-        model_stat.session_count += 1;
-
-        // Update daily stats
-        let date = entry
-            .timestamp
-            .split('T')
-            .next()
-            .unwrap_or(&entry.timestamp)
-            .to_string();
-        let daily_stat = daily_stats.entry(date.clone()).or_insert(DailyUsage {
-            date,
-            total_cost: 0.0,
-            total_tokens: 0,
-            models_used: vec![],
-        });
-        daily_stat.total_cost += entry.cost;
-        daily_stat.total_tokens += entry.input_tokens
-            + entry.output_tokens
-            + entry.cache_creation_tokens
-            + entry.cache_read_tokens;
-        if !daily_stat.models_used.contains(&entry.model) {
-            daily_stat.models_used.push(entry.model.clone());
-        }
-
-        // Update project stats
-        let project_stat =
-            project_stats
-                .entry(entry.project_path.clone())
-                .or_insert(ProjectUsage {
-                    project_path: entry.project_path.clone(),
-                    project_name: entry
-                        .project_path
-                        .split('/')
-                        .last()
-                        .unwrap_or(&entry.project_path)
-                        .to_string(),
-                    total_cost: 0.0,
-                    total_tokens: 0,
-                    session_count: 0,
-                    last_used: entry.timestamp.clone(),
-                });
-        project_stat.total_cost += entry.cost;
-        project_stat.total_tokens += entry.input_tokens
-            + entry.output_tokens
-            + entry.cache_creation_tokens
-            + entry.cache_read_tokens;
-        project_stat.session_count += 1;
-        if entry.timestamp > project_stat.last_used {
-            project_stat.last_used = entry.timestamp.clone();
-        }
+/// Total cost of each session, keyed by session id
+pub fn session_costs(db: &AgentDb) -> Result<HashMap<String, f64>, String> {
+    let mut costs = HashMap::new();
+    for entry in load_usage_entries(db)? {
+        *costs.entry(entry.session_id).or_insert(0.0) += entry.cost;
     }
+    Ok(costs)
+}
 
-    let total_tokens = total_input_tokens
-        + total_output_tokens
-        + total_cache_creation_tokens
-        + total_cache_read_tokens;
-    let total_sessions = filtered_entries.len() as u64;
-
-    // Convert hashmaps to sorted vectors
-    let mut by_model: Vec<ModelUsage> = model_stats.into_values().collect();
-    by_model.sort_by(|a, b| b.total_cost.partial_cmp(&a.total_cost).unwrap());
-
-    let mut by_date: Vec<DailyUsage> = daily_stats.into_values().collect();
-    by_date.sort_by(|a, b| b.date.cmp(&a.date));
+/// Aggregated usage between two local dates, inclusive
+pub fn usage_stats_between(
+    db: &AgentDb,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<UsageStats, String> {
+    let entries = filter_by_date_range(load_usage_entries(db)?, Some(start), Some(end));
+    Ok(aggregate_usage(&entries))
+}
 
-    let mut by_project: Vec<ProjectUsage> = project_stats.into_values().collect();
-    by_project.sort_by(|a, b| b.total_cost.partial_cmp(&a.total_cost).unwrap());
+/// Total cost of usage on or after a local date
+pub fn cost_since(db: &AgentDb, since: NaiveDate) -> Result<f64, String> {
+    let entries = filter_by_date_range(load_usage_entries(db)?, Some(since), None);
+    Ok(entries.iter().map(|e| e.cost).sum())
+}
 
-    Ok(UsageStats {
-        total_cost,
-        total_tokens,
-        total_input_tokens,
-        total_output_tokens,
-        total_cache_creation_tokens,
-        total_cache_read_tokens,
-        total_sessions,
-        by_model,
-        by_date,
-        by_project,
-    })
+/// Parse a `YYYY-MM-DD`, `YYYYMMDD` (as ccusage takes them) or RFC 3339 date bound
+fn parse_usage_date(value: &str, bound: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .or_else(|_| NaiveDate::parse_from_str(value, "%Y%m%d"))
+        .or_else(|_| {
+            // Try parsing ISO datetime format
+            DateTime::parse_from_rfc3339(value)
+                .map(|dt| dt.naive_local().date())
+                .map_err(|e| format!("Invalid {} date: {}", bound, e))
+        })
 }
 
-#[command]
-pub fn get_usage_by_date_range(start_date: String, end_date: String) -> Result<UsageStats, String> {
-    let claude_path = dirs::home_dir()
-        .ok_or("Failed to get home directory")?
-        .join(".claude");
+fn parse_optional_usage_date(
+    value: Option<String>,
+    bound: &str,
+) -> Result<Option<NaiveDate>, String> {
+    value
+        .filter(|v| !v.is_empty())
+        .map(|v| parse_usage_date(&v, bound))
+        .transpose()
+}
 
-    let all_entries = get_all_usage_entries(&claude_path);
-
-    // Parse dates
-    let start = NaiveDate::parse_from_str(&start_date, "%Y-%m-%d").or_else(|_| {
-        // Try parsing ISO datetime format
-        DateTime::parse_from_rfc3339(&start_date)
-            .map(|dt| dt.naive_local().date())
-            .map_err(|e| format!("Invalid start date: {}", e))
-    })?;
-    let end = NaiveDate::parse_from_str(&end_date, "%Y-%m-%d").or_else(|_| {
-        // Try parsing ISO datetime format
-        DateTime::parse_from_rfc3339(&end_date)
-            .map(|dt| dt.naive_local().date())
-            .map_err(|e| format!("Invalid end date: {}", e))
-    })?;
-
-    // Filter entries by date range
-    let filtered_entries: Vec<_> = all_entries
+/// Keep entries whose local date lies within the inclusive range
+fn filter_by_date_range(
+    entries: Vec<UsageEntry>,
+    start: Option<NaiveDate>,
+    end: Option<NaiveDate>,
+) -> Vec<UsageEntry> {
+    if start.is_none() && end.is_none() {
+        return entries;
+    }
+    entries
         .into_iter()
         .filter(|e| {
             if let Ok(dt) = DateTime::parse_from_rfc3339(&e.timestamp) {
                 let date = dt.naive_local().date();
-                date >= start && date <= end
+                start.is_none_or(|s| date >= s) && end.is_none_or(|e| date <= e)
             } else {
                 false
             }
         })
-        .collect();
-
-    if filtered_entries.is_empty() {
-        return Ok(UsageStats {
-            total_cost: 0.0,
-            total_tokens: 0,
-            total_input_tokens: 0,
-            total_output_tokens: 0,
-            total_cache_creation_tokens: 0,
-            total_cache_read_tokens: 0,
-            total_sessions: 0,
-            by_model: vec![],
-            by_date: vec![],
-            by_project: vec![],
-        });
-    }
+        .collect()
+}
 
-    // Calculate aggregated stats (same logic as get_usage_stats)
+/// Aggregate usage entries into totals and per-model, per-day and per-project breakdowns
+///
+/// Session counts are distinct sessions, not messages.
+fn aggregate_usage(entries: &[UsageEntry]) -> UsageStats {
     let mut total_cost = 0.0;
     let mut total_input_tokens = 0u64;
     let mut total_output_tokens = 0u64;
     let mut total_cache_creation_tokens = 0u64;
     let mut total_cache_read_tokens = 0u64;
+    let mut cache_savings = 0.0;
+
+    let mut sessions: HashSet<&str> = HashSet::new();
+    let mut model_sessions: HashSet<(&str, &str)> = HashSet::new();
+    let mut project_sessions: HashSet<(&str, &str)> = HashSet::new();
 
     let mut model_stats: HashMap<String, ModelUsage> = HashMap::new();
     let mut daily_stats: HashMap<String, DailyUsage> = HashMap::new();
     let mut project_stats: HashMap<String, ProjectUsage> = HashMap::new();
+    let mut agent_stats: HashMap<i64, AgentUsage> = HashMap::new();
+    let mut agent_sessions: HashSet<(i64, &str)> = HashSet::new();
+
+    for entry in entries {
+        let entry_tokens = entry.input_tokens
+            + entry.output_tokens
+            + entry.cache_creation_tokens
+            + entry.cache_read_tokens;
 
-    for entry in &filtered_entries {
         // Update totals
         total_cost += entry.cost;
         total_input_tokens += entry.input_tokens;
         total_output_tokens += entry.output_tokens;
         total_cache_creation_tokens += entry.cache_creation_tokens;
         total_cache_read_tokens += entry.cache_read_tokens;
+        cache_savings += entry.cache_savings;
+        sessions.insert(&entry.session_id);
 
         // Update model stats
         let model_stat = model_stats
@@ -528,15 +426,19 @@ pub fn get_usage_by_date_range(start_date: String, end_date: String) -> Result<U
                 output_tokens: 0,
                 cache_creation_tokens: 0,
                 cache_read_tokens: 0,
+                cache_savings: 0.0,
                 session_count: 0,
             });
         model_stat.total_cost += entry.cost;
+        model_stat.cache_savings += entry.cache_savings;
         model_stat.input_tokens += entry.input_tokens;
         model_stat.output_tokens += entry.output_tokens;
         model_stat.cache_creation_tokens += entry.cache_creation_tokens;
         model_stat.cache_read_tokens += entry.cache_read_tokens;
         model_stat.total_tokens = model_stat.input_tokens + model_stat.output_tokens;
-        model_stat.session_count += 1;
+        if model_sessions.insert((&entry.model, &entry.session_id)) {
+            model_stat.session_count += 1;
+        }
 
         // Update daily stats
         let date = entry
@@ -552,10 +454,7 @@ pub fn get_usage_by_date_range(start_date: String, end_date: String) -> Result<U
             models_used: vec![],
         });
         daily_stat.total_cost += entry.cost;
-        daily_stat.total_tokens += entry.input_tokens
-            + entry.output_tokens
-            + entry.cache_creation_tokens
-            + entry.cache_read_tokens;
+        daily_stat.total_tokens += entry_tokens;
         if !daily_stat.models_used.contains(&entry.model) {
             daily_stat.models_used.push(entry.model.clone());
         }
@@ -578,21 +477,41 @@ pub fn get_usage_by_date_range(start_date: String, end_date: String) -> Result<U
                     last_used: entry.timestamp.clone(),
                 });
         project_stat.total_cost += entry.cost;
-        project_stat.total_tokens += entry.input_tokens
-            + entry.output_tokens
-            + entry.cache_creation_tokens
-            + entry.cache_read_tokens;
-        project_stat.session_count += 1;
+        project_stat.total_tokens += entry_tokens;
+        if project_sessions.insert((&entry.project_path, &entry.session_id)) {
+            project_stat.session_count += 1;
+        }
         if entry.timestamp > project_stat.last_used {
             project_stat.last_used = entry.timestamp.clone();
         }
+
+        // Update agent stats
+        if let Some(agent_id) = entry.agent_id {
+            let agent_stat = agent_stats.entry(agent_id).or_insert_with(|| AgentUsage {
+                agent_id,
+                agent_name: entry.agent_name.clone().unwrap_or_default(),
+                total_cost: 0.0,
+                total_tokens: 0,
+                run_count: 0,
+                last_used: entry.timestamp.clone(),
+            });
+            agent_stat.total_cost += entry.cost;
+            agent_stat.total_tokens += entry_tokens;
+            if agent_sessions.insert((agent_id, &entry.session_id)) {
+                agent_stat.run_count += 1;
+            }
+            if entry.timestamp > agent_stat.last_used {
+                agent_stat.last_used = entry.timestamp.clone();
+            }
+        }
     }
 
     let total_tokens = total_input_tokens
         + total_output_tokens
         + total_cache_creation_tokens
         + total_cache_read_tokens;
-    let total_sessions = filtered_entries.len() as u64;
+
+    let prompt_tokens = total_input_tokens + total_cache_creation_tokens + total_cache_read_tokens;
 
     // Convert hashmaps to sorted vectors
     let mut by_model: Vec<ModelUsage> = model_stats.into_values().collect();
@@ -604,30 +523,85 @@ pub fn get_usage_by_date_range(start_date: String, end_date: String) -> Result<U
     let mut by_project: Vec<ProjectUsage> = project_stats.into_values().collect();
     by_project.sort_by(|a, b| b.total_cost.partial_cmp(&a.total_cost).unwrap());
 
-    Ok(UsageStats {
+    let mut by_agent: Vec<AgentUsage> = agent_stats.into_values().collect();
+    by_agent.sort_by(|a, b| b.total_cost.total_cmp(&a.total_cost));
+
+    UsageStats {
         total_cost,
         total_tokens,
         total_input_tokens,
         total_output_tokens,
         total_cache_creation_tokens,
         total_cache_read_tokens,
-        total_sessions,
+        cache_savings,
+        cost_without_cache: total_cost + cache_savings,
+        cache_hit_rate: if prompt_tokens > 0 {
+            total_cache_read_tokens as f64 / prompt_tokens as f64
+        } else {
+            0.0
+        },
+        total_sessions: sessions.len() as u64,
         by_model,
         by_date,
         by_project,
-    })
+        by_agent,
+    }
+}
+
+#[command]
+#[specta::specta]
+pub fn get_usage_stats(db: State<'_, AgentDb>, days: Option<u32>) -> Result<UsageStats, String> {
+    let all_entries = load_usage_entries(&db)?;
+
+    // Filter by days if specified
+    let filtered_entries = if let Some(days) = days {
+        let cutoff = Local::now().naive_local().date() - chrono::Duration::days(days as i64);
+        all_entries
+            .into_iter()
+            .filter(|e| {
+                if let Ok(dt) = DateTime::parse_from_rfc3339(&e.timestamp) {
+                    dt.naive_local().date() >= cutoff
+                } else {
+                    false
+                }
+            })
+            .collect()
+    } else {
+        all_entries
+    };
+
+    Ok(aggregate_usage(&filtered_entries))
+}
+
+#[command]
+#[specta::specta]
+pub fn get_usage_by_date_range(
+    db: State<'_, AgentDb>,
+    start_date: String,
+    end_date: String,
+) -> Result<UsageStats, String> {
+    let all_entries = load_usage_entries(&db)?;
+
+    let start = parse_usage_date(&start_date, "start")?;
+    let end = parse_usage_date(&end_date, "end")?;
+    let filtered_entries = filter_by_date_range(all_entries, Some(start), Some(end));
+
+    Ok(aggregate_usage(&filtered_entries))
 }
 
+/// Entries of one project and/or day or date range, oldest first
 #[command]
+#[specta::specta]
 pub fn get_usage_details(
+    db: State<'_, AgentDb>,
     project_path: Option<String>,
     date: Option<String>,
+    start_date: Option<String>,
+    end_date: Option<String>,
 ) -> Result<Vec<UsageEntry>, String> {
-    let claude_path = dirs::home_dir()
-        .ok_or("Failed to get home directory")?
-        .join(".claude");
-
-    let mut all_entries = get_all_usage_entries(&claude_path);
+    let start = parse_optional_usage_date(start_date, "start")?;
+    let end = parse_optional_usage_date(end_date, "end")?;
+    let mut all_entries = filter_by_date_range(load_usage_entries(&db)?, start, end);
 
     // Filter by project if specified
     if let Some(project) = project_path {
@@ -642,17 +616,184 @@ pub fn get_usage_details(
     Ok(all_entries)
 }
 
+/// Totals, daily series and model breakdown of every project within a date range
+#[command]
+#[specta::specta]
+pub fn get_usage_by_project(
+    db: State<'_, AgentDb>,
+    start_date: Option<String>,
+    end_date: Option<String>,
+) -> Result<Vec<ProjectUsageBreakdown>, String> {
+    let start = parse_optional_usage_date(start_date, "start")?;
+    let end = parse_optional_usage_date(end_date, "end")?;
+    let entries = filter_by_date_range(load_usage_entries(&db)?, start, end);
+
+    let mut by_project: HashMap<String, Vec<UsageEntry>> = HashMap::new();
+    for entry in entries {
+        by_project
+            .entry(entry.project_path.clone())
+            .or_default()
+            .push(entry);
+    }
+
+    let mut breakdowns: Vec<ProjectUsageBreakdown> = by_project
+        .into_values()
+        .filter_map(|entries| {
+            let mut stats = aggregate_usage(&entries);
+            Some(ProjectUsageBreakdown {
+                project: stats.by_project.pop()?,
+                by_date: stats.by_date,
+                by_model: stats.by_model,
+            })
+        })
+        .collect();
+    breakdowns.sort_by(|a, b| b.project.total_cost.total_cmp(&a.project.total_cost));
+    Ok(breakdowns)
+}
+
+/// Usage of a single project within a date range
+#[command]
+#[specta::specta]
+pub fn get_project_usage(
+    db: State<'_, AgentDb>,
+    project_path: String,
+    start_date: Option<String>,
+    end_date: Option<String>,
+) -> Result<UsageStats, String> {
+    let start = parse_optional_usage_date(start_date, "start")?;
+    let end = parse_optional_usage_date(end_date, "end")?;
+    let mut entries = filter_by_date_range(load_usage_entries(&db)?, start, end);
+    entries.retain(|e| e.project_path == project_path);
+    Ok(aggregate_usage(&entries))
+}
+
+/// Spend of each agent within a date range
+#[command]
+#[specta::specta]
+pub fn get_usage_by_agent(
+    db: State<'_, AgentDb>,
+    start_date: Option<String>,
+    end_date: Option<String>,
+) -> Result<Vec<AgentUsage>, String> {
+    let start = parse_optional_usage_date(start_date, "start")?;
+    let end = parse_optional_usage_date(end_date, "end")?;
+    let mut entries = filter_by_date_range(load_usage_entries(&db)?, start, end);
+    entries.retain(|e| e.agent_id.is_some());
+    Ok(aggregate_usage(&entries).by_agent)
+}
+
+/// Compare cost and token efficiency of models on the same agent's or project's sessions
+///
+/// `group_by` is "agent" or "project". With `only_multi_model`, groups that only
+/// ever used one model are left out since there's nothing to compare.
+#[command]
+#[specta::specta]
+pub fn compare_model_costs(
+    db: State<'_, AgentDb>,
+    group_by: String,
+    start_date: Option<String>,
+    end_date: Option<String>,
+    only_multi_model: Option<bool>,
+) -> Result<Vec<ModelComparisonGroup>, String> {
+    if group_by != "agent" && group_by != "project" {
+        return Err(format!(
+            "Unknown grouping '{}', expected agent or project",
+            group_by
+        ));
+    }
+    let start = parse_optional_usage_date(start_date, "start")?;
+    let end = parse_optional_usage_date(end_date, "end")?;
+    let entries = filter_by_date_range(load_usage_entries(&db)?, start, end);
+
+    let mut groups: HashMap<String, HashMap<String, ModelEfficiency>> = HashMap::new();
+    let mut sessions: HashSet<(String, &str, &str)> = HashSet::new();
+    for entry in &entries {
+        let group = if group_by == "agent" {
+            match &entry.agent_name {
+                Some(name) => name.clone(),
+                None => continue,
+            }
+        } else {
+            entry.project_path.clone()
+        };
+        let efficiency = groups
+            .entry(group.clone())
+            .or_default()
+            .entry(entry.model.clone())
+            .or_insert_with(|| ModelEfficiency {
+                model: entry.model.clone(),
+                session_count: 0,
+                total_cost: 0.0,
+                total_tokens: 0,
+                output_tokens: 0,
+                avg_cost_per_session: 0.0,
+                avg_tokens_per_session: 0.0,
+                cost_per_1k_output_tokens: 0.0,
+                relative_cost: 1.0,
+            });
+        efficiency.total_cost += entry.cost;
+        efficiency.output_tokens += entry.output_tokens;
+        efficiency.total_tokens += entry.input_tokens
+            + entry.output_tokens
+            + entry.cache_creation_tokens
+            + entry.cache_read_tokens;
+        if sessions.insert((group, &entry.model, &entry.session_id)) {
+            efficiency.session_count += 1;
+        }
+    }
+
+    let mut comparison: Vec<ModelComparisonGroup> = groups
+        .into_iter()
+        .filter(|(_, models)| !only_multi_model.unwrap_or(false) || models.len() > 1)
+        .map(|(group, models)| {
+            let mut models: Vec<ModelEfficiency> = models
+                .into_values()
+                .map(|mut m| {
+                    let sessions = m.session_count.max(1) as f64;
+                    m.avg_cost_per_session = m.total_cost / sessions;
+                    m.avg_tokens_per_session = m.total_tokens as f64 / sessions;
+                    if m.output_tokens > 0 {
+                        m.cost_per_1k_output_tokens =
+                            m.total_cost / m.output_tokens as f64 * 1000.0;
+                    }
+                    m
+                })
+                .collect();
+            models.sort_by(|a, b| a.avg_cost_per_session.total_cmp(&b.avg_cost_per_session));
+            let cheapest = models
+                .iter()
+                .map(|m| m.avg_cost_per_session)
+                .find(|c| *c > 0.0);
+            if let Some(cheapest) = cheapest {
+                for m in &mut models {
+                    m.relative_cost = m.avg_cost_per_session / cheapest;
+                }
+            }
+            ModelComparisonGroup { group, models }
+        })
+        .collect();
+    comparison.sort_by(|a, b| a.group.cmp(&b.group));
+    Ok(comparison)
+}
+
+/// Usage of a single session
+#[command]
+#[specta::specta]
+pub fn get_session_usage(db: State<'_, AgentDb>, session_id: String) -> Result<UsageStats, String> {
+    let mut entries = load_usage_entries(&db)?;
+    entries.retain(|e| e.session_id == session_id);
+    Ok(aggregate_usage(&entries))
+}
+
 #[command]
+#[specta::specta]
 pub fn get_session_stats(
+    db: State<'_, AgentDb>,
     since: Option<String>,
     until: Option<String>,
     order: Option<String>,
 ) -> Result<Vec<ProjectUsage>, String> {
-    let claude_path = dirs::home_dir()
-        .ok_or("Failed to get home directory")?
-        .join(".claude");
-
-    let all_entries = get_all_usage_entries(&claude_path);
+    let all_entries = load_usage_entries(&db)?;
 
     let since_date = since.and_then(|s| NaiveDate::parse_from_str(&s, "%Y%m%d").ok());
     let until_date = until.and_then(|s| NaiveDate::parse_from_str(&s, "%Y%m%d").ok());
@@ -712,3 +853,453 @@ pub fn get_session_stats(
 
     Ok(by_session)
 }
+
+/// One row of a usage export: a model's usage in a project on a day
+#[derive(Debug, Serialize, Deserialize, specta::Type)]
+pub struct UsageExportRow {
+    date: String,
+    project_path: String,
+    model: String,
+    input_tokens: u64,
+    output_tokens: u64,
+    cache_creation_tokens: u64,
+    cache_read_tokens: u64,
+    total_tokens: u64,
+    cost: f64,
+    session_count: u64,
+    message_count: u64,
+}
+
+/// Group entries into per-day, per-project, per-model rows sorted by date
+fn usage_export_rows(entries: &[UsageEntry]) -> Vec<UsageExportRow> {
+    let mut rows: HashMap<(String, &str, &str), UsageExportRow> = HashMap::new();
+    let mut sessions: HashSet<(String, &str, &str, &str)> = HashSet::new();
+
+    for entry in entries {
+        let date = DateTime::parse_from_rfc3339(&entry.timestamp)
+            .map(|dt| dt.naive_local().date().to_string())
+            .unwrap_or_else(|_| entry.timestamp.split('T').next().unwrap_or("").to_string());
+        let key = (
+            date.clone(),
+            entry.project_path.as_str(),
+            entry.model.as_str(),
+        );
+        let row = rows.entry(key).or_insert_with(|| UsageExportRow {
+            date: date.clone(),
+            project_path: entry.project_path.clone(),
+            model: entry.model.clone(),
+            input_tokens: 0,
+            output_tokens: 0,
+            cache_creation_tokens: 0,
+            cache_read_tokens: 0,
+            total_tokens: 0,
+            cost: 0.0,
+            session_count: 0,
+            message_count: 0,
+        });
+        row.input_tokens += entry.input_tokens;
+        row.output_tokens += entry.output_tokens;
+        row.cache_creation_tokens += entry.cache_creation_tokens;
+        row.cache_read_tokens += entry.cache_read_tokens;
+        row.total_tokens += entry.input_tokens
+            + entry.output_tokens
+            + entry.cache_creation_tokens
+            + entry.cache_read_tokens;
+        row.cost += entry.cost;
+        row.message_count += 1;
+        if sessions.insert((date, &entry.project_path, &entry.model, &entry.session_id)) {
+            row.session_count += 1;
+        }
+    }
+
+    let mut rows: Vec<UsageExportRow> = rows.into_values().collect();
+    rows.sort_by(|a, b| {
+        (&a.date, &a.project_path, &a.model).cmp(&(&b.date, &b.project_path, &b.model))
+    });
+    rows
+}
+
+/// Quote a CSV field when it contains a delimiter, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn usage_rows_to_csv(rows: &[UsageExportRow]) -> String {
+    let mut csv = String::from(
+        "date,project_path,model,input_tokens,output_tokens,cache_creation_tokens,cache_read_tokens,total_tokens,cost_usd,session_count,message_count\n",
+    );
+    for row in rows {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{},{},{:.6},{},{}\n",
+            row.date,
+            csv_field(&row.project_path),
+            csv_field(&row.model),
+            row.input_tokens,
+            row.output_tokens,
+            row.cache_creation_tokens,
+            row.cache_read_tokens,
+            row.total_tokens,
+            row.cost,
+            row.session_count,
+            row.message_count
+        ));
+    }
+    csv
+}
+
+/// Token counts and cost in ccusage's field names
+#[derive(Debug, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct CcusageTotals {
+    input_tokens: u64,
+    output_tokens: u64,
+    cache_creation_tokens: u64,
+    cache_read_tokens: u64,
+    total_tokens: u64,
+    total_cost: f64,
+}
+
+impl CcusageTotals {
+    fn add(&mut self, entry: &UsageEntry) {
+        self.input_tokens += entry.input_tokens;
+        self.output_tokens += entry.output_tokens;
+        self.cache_creation_tokens += entry.cache_creation_tokens;
+        self.cache_read_tokens += entry.cache_read_tokens;
+        self.total_tokens += entry.input_tokens
+            + entry.output_tokens
+            + entry.cache_creation_tokens
+            + entry.cache_read_tokens;
+        self.total_cost += entry.cost;
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CcusageModelBreakdown {
+    model_name: String,
+    input_tokens: u64,
+    output_tokens: u64,
+    cache_creation_tokens: u64,
+    cache_read_tokens: u64,
+    cost: f64,
+}
+
+/// One row of a ccusage report: a day, a month or a session
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CcusageRow {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    month: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    session_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    project_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_activity: Option<String>,
+    #[serde(flatten)]
+    totals: CcusageTotals,
+    models_used: Vec<String>,
+    model_breakdowns: Vec<CcusageModelBreakdown>,
+}
+
+/// Build a ccusage `daily`, `monthly` or `session` JSON report
+fn ccusage_report(entries: &[UsageEntry], report: &str) -> Result<serde_json::Value, String> {
+    let key_of = |entry: &UsageEntry| -> String {
+        let date = DateTime::parse_from_rfc3339(&entry.timestamp)
+            .map(|dt| dt.naive_local().date().to_string())
+            .unwrap_or_else(|_| entry.timestamp.chars().take(10).collect());
+        match report {
+            "monthly" => date.chars().take(7).collect(),
+            "session" => format!("{}\n{}", entry.project_path, entry.session_id),
+            _ => date,
+        }
+    };
+    if !["daily", "monthly", "session"].contains(&report) {
+        return Err(format!(
+            "Unknown ccusage report '{}', expected daily, monthly or session",
+            report
+        ));
+    }
+
+    // BTreeMap keeps days and months in order, like ccusage prints them
+    let mut groups: std::collections::BTreeMap<String, Vec<&UsageEntry>> = Default::default();
+    for entry in entries {
+        groups.entry(key_of(entry)).or_default().push(entry);
+    }
+
+    let mut totals = CcusageTotals::default();
+    let mut rows = Vec::new();
+    for (key, entries) in groups {
+        let mut row_totals = CcusageTotals::default();
+        let mut models: Vec<CcusageModelBreakdown> = Vec::new();
+        for entry in &entries {
+            row_totals.add(entry);
+            totals.add(entry);
+            let breakdown = match models.iter().position(|m| m.model_name == entry.model) {
+                Some(i) => &mut models[i],
+                None => {
+                    models.push(CcusageModelBreakdown {
+                        model_name: entry.model.clone(),
+                        input_tokens: 0,
+                        output_tokens: 0,
+                        cache_creation_tokens: 0,
+                        cache_read_tokens: 0,
+                        cost: 0.0,
+                    });
+                    models.last_mut().unwrap()
+                }
+            };
+            breakdown.input_tokens += entry.input_tokens;
+            breakdown.output_tokens += entry.output_tokens;
+            breakdown.cache_creation_tokens += entry.cache_creation_tokens;
+            breakdown.cache_read_tokens += entry.cache_read_tokens;
+            breakdown.cost += entry.cost;
+        }
+        models.sort_by(|a, b| b.cost.total_cmp(&a.cost));
+
+        let last = entries.iter().map(|e| e.timestamp.as_str()).max();
+        let mut row = CcusageRow {
+            date: None,
+            month: None,
+            session_id: None,
+            project_path: None,
+            last_activity: None,
+            totals: row_totals,
+            models_used: models.iter().map(|m| m.model_name.clone()).collect(),
+            model_breakdowns: models,
+        };
+        match report {
+            "monthly" => row.month = Some(key),
+            "session" => {
+                row.session_id = Some(entries[0].session_id.clone());
+                row.project_path = Some(entries[0].project_path.clone());
+                row.last_activity = last.map(|t| t.chars().take(10).collect());
+            }
+            _ => row.date = Some(key),
+        }
+        rows.push(row);
+    }
+    if report == "session" {
+        rows.sort_by(|a, b| b.last_activity.cmp(&a.last_activity));
+    }
+
+    let list_key = match report {
+        "monthly" => "monthly",
+        "session" => "sessions",
+        _ => "daily",
+    };
+    Ok(serde_json::json!({ list_key: rows, "totals": totals }))
+}
+
+/// Export usage in the JSON schema of ccusage's `daily`, `monthly` or `session` reports
+#[command]
+#[specta::specta]
+pub fn export_usage_ccusage(
+    db: State<'_, AgentDb>,
+    report: Option<String>,
+    since: Option<String>,
+    until: Option<String>,
+) -> Result<String, String> {
+    let start = parse_optional_usage_date(since, "since")?;
+    let end = parse_optional_usage_date(until, "until")?;
+    let entries = filter_by_date_range(load_usage_entries(&db)?, start, end);
+    let value = ccusage_report(&entries, report.as_deref().unwrap_or("daily"))?;
+    serde_json::to_string_pretty(&value).map_err(|e| format!("Failed to serialize usage: {}", e))
+}
+
+/// Export usage as per-day, per-project, per-model rows in "csv" or "json",
+/// or as a ccusage-compatible daily report with "ccusage"
+#[command]
+#[specta::specta]
+pub fn export_usage(
+    db: State<'_, AgentDb>,
+    format: String,
+    start_date: Option<String>,
+    end_date: Option<String>,
+) -> Result<String, String> {
+    let start = parse_optional_usage_date(start_date, "start")?;
+    let end = parse_optional_usage_date(end_date, "end")?;
+    let entries = filter_by_date_range(load_usage_entries(&db)?, start, end);
+
+    match format.as_str() {
+        "csv" => Ok(usage_rows_to_csv(&usage_export_rows(&entries))),
+        "json" => serde_json::to_string_pretty(&usage_export_rows(&entries))
+            .map_err(|e| format!("Failed to serialize usage: {}", e)),
+        "ccusage" => serde_json::to_string_pretty(&ccusage_report(&entries, "daily")?)
+            .map_err(|e| format!("Failed to serialize usage: {}", e)),
+        other => Err(format!(
+            "Unknown export format '{}', expected csv, json or ccusage",
+            other
+        )),
+    }
+}
+
+/// Export usage rows to a file
+#[command]
+#[specta::specta]
+pub fn export_usage_to_file(
+    db: State<'_, AgentDb>,
+    format: String,
+    start_date: Option<String>,
+    end_date: Option<String>,
+    file_path: String,
+) -> Result<(), String> {
+    let data = export_usage(db, format, start_date, end_date)?;
+    fs::write(&file_path, data).map_err(|e| format!("Failed to write file: {}", e))
+}
+
+/// Get the price list used for cost calculation, including user overrides
+#[command]
+#[specta::specta]
+pub fn get_model_pricing(db: State<'_, AgentDb>) -> Result<Vec<ModelPricingEntry>, String> {
+    let table = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        PricingTable::load(&conn)
+    };
+    let mut entries: Vec<ModelPricingEntry> = DEFAULT_PRICING
+        .iter()
+        .filter(|(pattern, _)| !table.overrides.contains_key(*pattern))
+        .map(|(pattern, pricing)| ModelPricingEntry {
+            pattern: pattern.to_string(),
+            pricing: *pricing,
+            overridden: false,
+        })
+        .collect();
+    entries.extend(
+        table
+            .overrides
+            .into_iter()
+            .map(|(pattern, pricing)| ModelPricingEntry {
+                pattern,
+                pricing,
+                overridden: true,
+            }),
+    );
+    entries.sort_by(|a, b| a.pattern.cmp(&b.pattern));
+    Ok(entries)
+}
+
+/// Override the prices of models whose name contains `pattern`, or drop the override when `None`
+#[command]
+#[specta::specta]
+pub fn set_model_pricing_override(
+    db: State<'_, AgentDb>,
+    pattern: String,
+    pricing: Option<ModelPricing>,
+) -> Result<(), String> {
+    let pattern = pattern.trim().to_string();
+    if pattern.is_empty() {
+        return Err("Model pattern cannot be empty".to_string());
+    }
+    if let Some(pricing) = &pricing {
+        let prices = [
+            pricing.input,
+            pricing.output,
+            pricing.cache_write,
+            pricing.cache_read,
+        ];
+        if prices.iter().any(|p| !p.is_finite() || *p < 0.0) {
+            return Err("Prices must be non-negative numbers".to_string());
+        }
+    }
+
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let mut table = PricingTable::load(&conn);
+    match pricing {
+        Some(pricing) => {
+            table.overrides.insert(pattern, pricing);
+        }
+        None => {
+            table.overrides.remove(&pattern);
+        }
+    }
+    set_setting(&conn, PRICING_OVERRIDES_KEY, &table.overrides)
+        .map_err(|e| format!("Failed to save pricing: {}", e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(session_id: &str, model: &str, input_tokens: u64) -> UsageEntry {
+        UsageEntry {
+            timestamp: "2025-01-01T10:00:00Z".to_string(),
+            model: model.to_string(),
+            input_tokens,
+            output_tokens: 0,
+            cache_creation_tokens: 0,
+            cache_read_tokens: 0,
+            cost: 1.0,
+            cache_savings: 0.0,
+            session_id: session_id.to_string(),
+            project_path: "/repo".to_string(),
+            agent_id: None,
+            agent_name: None,
+        }
+    }
+
+    #[test]
+    fn counts_distinct_sessions() {
+        let entries = vec![
+            entry("a", "claude-sonnet-4", 10),
+            entry("a", "claude-sonnet-4", 20),
+            entry("b", "claude-opus-4", 5),
+        ];
+
+        let stats = aggregate_usage(&entries);
+        assert_eq!(stats.total_sessions, 2);
+        assert_eq!(stats.total_input_tokens, 35);
+        assert_eq!(stats.by_project[0].session_count, 2);
+        let sonnet = stats
+            .by_model
+            .iter()
+            .find(|m| m.model == "claude-sonnet-4")
+            .unwrap();
+        assert_eq!(sonnet.session_count, 1);
+    }
+
+    #[test]
+    fn overrides_take_precedence_over_built_in_prices() {
+        let mut table = PricingTable::default();
+        let usage = UsageData {
+            input_tokens: Some(1_000_000),
+            output_tokens: None,
+            cache_creation_input_tokens: None,
+            cache_read_input_tokens: None,
+        };
+        assert_eq!(table.cost("claude-opus-4-20250514", &usage), 15.0);
+        assert_eq!(table.cost("claude-opus-4-5-20251101", &usage), 5.0);
+        assert_eq!(table.cost("gpt-4", &usage), 0.0);
+
+        table
+            .overrides
+            .insert("opus-4".to_string(), pricing(10.0, 50.0, 12.5, 1.0));
+        assert_eq!(table.cost("claude-opus-4-20250514", &usage), 10.0);
+        assert!(table.is_overridden("claude-opus-4-1"));
+    }
+
+    #[test]
+    fn exports_quoted_csv_rows() {
+        let mut other_project = entry("b", "claude-sonnet-4", 5);
+        other_project.project_path = "/work/a,b".to_string();
+        let entries = vec![
+            entry("a", "claude-sonnet-4", 10),
+            entry("a", "claude-sonnet-4", 20),
+            other_project,
+        ];
+
+        let csv = usage_rows_to_csv(&usage_export_rows(&entries));
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[1].starts_with("2025-01-01,/repo,claude-sonnet-4,30,"));
+        assert!(lines[1].ends_with(",1,2"));
+        assert!(lines[2].contains("\"/work/a,b\""));
+    }
+}
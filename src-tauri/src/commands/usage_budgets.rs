@@ -0,0 +1,233 @@
+use chrono::{Datelike, Local, NaiveDate};
+use log::{error, info, warn};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tauri_plugin_notification::NotificationExt;
+
+use super::agents::{get_setting, set_setting, AgentDb};
+use super::usage::cost_since;
+
+/// Seconds between budget checks
+const BUDGET_CHECK_SECS: u64 = 300;
+
+/// Share of a budget at which alerts fire, in percent
+const ALERT_THRESHOLDS: [u32; 2] = [80, 100];
+
+/// Spending limits in USD; a period without a limit is not checked
+#[derive(Debug, Serialize, Deserialize, Clone, Default, specta::Type)]
+pub struct UsageBudgets {
+    pub daily: Option<f64>,
+    pub weekly: Option<f64>,
+    pub monthly: Option<f64>,
+    /// Also show a desktop notification when a threshold is crossed
+    #[serde(default)]
+    pub notify: bool,
+}
+
+/// Spend against one budget
+#[derive(Debug, Serialize, Deserialize, Clone, specta::Type)]
+pub struct BudgetStatus {
+    /// "daily", "weekly" or "monthly"
+    pub period: String,
+    pub period_start: String,
+    pub limit: f64,
+    pub spent: f64,
+    pub percent: f64,
+}
+
+/// Payload of `usage-budget-alert` events
+#[derive(Debug, Serialize, Clone, specta::Type)]
+pub struct BudgetAlert {
+    #[serde(flatten)]
+    pub status: BudgetStatus,
+    /// The threshold that was crossed, 80 or 100
+    pub threshold: u32,
+}
+
+fn load_budgets(conn: &Connection) -> UsageBudgets {
+    get_setting(conn, "usage_budgets").unwrap_or_default()
+}
+
+/// Highest threshold already announced per `period:period_start`, so alerts fire once per period
+fn load_alerted(conn: &Connection) -> HashMap<String, u32> {
+    get_setting(conn, "usage_budget_alerts").unwrap_or_default()
+}
+
+/// First day of each budget period containing `today`; weeks start on Monday
+fn period_starts(today: NaiveDate) -> [(&'static str, NaiveDate); 3] {
+    let week_start = today - chrono::Duration::days(today.weekday().num_days_from_monday() as i64);
+    let month_start = today.with_day(1).unwrap_or(today);
+    [
+        ("daily", today),
+        ("weekly", week_start),
+        ("monthly", month_start),
+    ]
+}
+
+fn budget_statuses(db: &AgentDb, budgets: &UsageBudgets) -> Result<Vec<BudgetStatus>, String> {
+    let mut statuses = Vec::new();
+    for (period, start) in period_starts(Local::now().date_naive()) {
+        let limit = match period {
+            "daily" => budgets.daily,
+            "weekly" => budgets.weekly,
+            _ => budgets.monthly,
+        };
+        let Some(limit) = limit.filter(|l| *l > 0.0) else {
+            continue;
+        };
+        let spent = cost_since(db, start)?;
+        statuses.push(BudgetStatus {
+            period: period.to_string(),
+            period_start: start.to_string(),
+            limit,
+            spent,
+            percent: spent / limit * 100.0,
+        });
+    }
+    Ok(statuses)
+}
+
+/// Compare spend with the budgets and announce newly crossed thresholds
+fn check_budgets(app: &AppHandle) -> Result<(), String> {
+    let db = app.state::<AgentDb>();
+    let (budgets, mut alerted) = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        (load_budgets(&conn), load_alerted(&conn))
+    };
+    if budgets.daily.is_none() && budgets.weekly.is_none() && budgets.monthly.is_none() {
+        return Ok(());
+    }
+
+    let statuses = budget_statuses(&db, &budgets)?;
+    // Forget alerts of periods that are over
+    alerted.retain(|key, _| {
+        statuses
+            .iter()
+            .any(|s| *key == format!("{}:{}", s.period, s.period_start))
+    });
+
+    for status in statuses {
+        let key = format!("{}:{}", status.period, status.period_start);
+        let already = alerted.get(&key).copied().unwrap_or(0);
+        let Some(threshold) = ALERT_THRESHOLDS
+            .iter()
+            .rev()
+            .copied()
+            .find(|t| status.percent >= *t as f64)
+            .filter(|t| *t > already)
+        else {
+            continue;
+        };
+
+        warn!(
+            "{} usage budget at {:.0}% (${:.2} of ${:.2})",
+            status.period, status.percent, status.spent, status.limit
+        );
+        alerted.insert(key, threshold);
+        if budgets.notify {
+            let title = if threshold >= 100 {
+                format!("{} budget exceeded", capitalize(&status.period))
+            } else {
+                format!("{}% of {} budget used", threshold, status.period)
+            };
+            let body = format!("${:.2} of ${:.2} spent", status.spent, status.limit);
+            if let Err(e) = app
+                .notification()
+                .builder()
+                .title(&title)
+                .body(&body)
+                .show()
+            {
+                error!("Failed to show budget notification: {}", e);
+            }
+        }
+        super::slack::notify_budget_alert(app, &status, threshold);
+        super::discord::notify_budget_alert(app, &status, threshold);
+        let _ = app.emit("usage-budget-alert", BudgetAlert { status, threshold });
+    }
+
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    set_setting(&conn, "usage_budget_alerts", &alerted).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Start the background budget check
+pub fn start_budget_monitor(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        info!(
+            "Starting usage budget monitor (every {}s)",
+            BUDGET_CHECK_SECS
+        );
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(BUDGET_CHECK_SECS));
+        loop {
+            interval.tick().await;
+            let app = app.clone();
+            // Usage indexing reads files, so keep it off the async workers
+            match tokio::task::spawn_blocking(move || check_budgets(&app)).await {
+                Ok(Err(e)) => error!("Usage budget check failed: {}", e),
+                Err(e) => error!("Usage budget check panicked: {}", e),
+                Ok(Ok(())) => {}
+            }
+        }
+    });
+}
+
+/// Get the configured usage budgets
+#[tauri::command]
+#[specta::specta]
+pub async fn get_usage_budgets(db: State<'_, AgentDb>) -> Result<UsageBudgets, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    Ok(load_budgets(&conn))
+}
+
+/// Save the usage budgets
+#[tauri::command]
+#[specta::specta]
+pub async fn set_usage_budgets(
+    db: State<'_, AgentDb>,
+    budgets: UsageBudgets,
+) -> Result<(), String> {
+    let limits = [budgets.daily, budgets.weekly, budgets.monthly];
+    if limits.iter().flatten().any(|l| !l.is_finite() || *l < 0.0) {
+        return Err("Budgets must be non-negative amounts".to_string());
+    }
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    set_setting(&conn, "usage_budgets", &budgets)
+        .map_err(|e| format!("Failed to save budgets: {}", e))?;
+    Ok(())
+}
+
+/// Get current spend against each configured budget
+#[tauri::command]
+#[specta::specta]
+pub fn get_budget_status(db: State<'_, AgentDb>) -> Result<Vec<BudgetStatus>, String> {
+    let budgets = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        load_budgets(&conn)
+    };
+    budget_statuses(&db, &budgets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weeks_start_on_monday() {
+        // 2025-01-01 was a Wednesday
+        let today = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let starts = period_starts(today);
+        assert_eq!(starts[1].1, NaiveDate::from_ymd_opt(2024, 12, 30).unwrap());
+        assert_eq!(starts[2].1, today);
+    }
+}
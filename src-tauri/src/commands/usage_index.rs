@@ -0,0 +1,436 @@
+use log::{info, warn};
+use rusqlite::{params, Connection};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use tauri::State;
+
+use super::agents::AgentDb;
+use super::jsonl_reader::{jsonl_lines, JsonlFile};
+use super::session_archive::archived_path;
+
+#[derive(Debug, Deserialize)]
+struct JsonlEntry {
+    timestamp: String,
+    message: Option<MessageData>,
+    #[serde(rename = "sessionId")]
+    session_id: Option<String>,
+    #[serde(rename = "requestId")]
+    request_id: Option<String>,
+    #[serde(rename = "costUSD")]
+    cost_usd: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MessageData {
+    id: Option<String>,
+    model: Option<String>,
+    usage: Option<MessageUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MessageUsage {
+    input_tokens: Option<u64>,
+    output_tokens: Option<u64>,
+    cache_creation_input_tokens: Option<u64>,
+    cache_read_input_tokens: Option<u64>,
+}
+
+/// A message with token usage, as stored in the usage index
+#[derive(Debug, Clone)]
+pub struct IndexedMessage {
+    /// `message id:request id`, used to count messages repeated across files once
+    pub message_key: Option<String>,
+    pub timestamp: String,
+    pub model: String,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    /// Cost Claude Code wrote into the transcript, if any
+    pub reported_cost: Option<f64>,
+    pub session_id: String,
+    pub project_path: String,
+}
+
+/// How far a transcript has been indexed
+struct IndexedFile {
+    modified_ms: i64,
+    size: i64,
+    offset: i64,
+    project_path: Option<String>,
+}
+
+/// New data read from one transcript
+struct FileUpdate {
+    path: String,
+    modified_ms: i64,
+    size: i64,
+    /// Offset after the last complete line
+    offset: i64,
+    project_path: Option<String>,
+    /// The file shrank or was replaced, so its earlier messages are dropped
+    reset: bool,
+    messages: Vec<IndexedMessage>,
+}
+
+/// Split a chunk into its complete lines and the byte length they cover
+pub(crate) fn complete_lines(chunk: &[u8]) -> (&[u8], usize) {
+    match chunk.iter().rposition(|b| *b == b'\n') {
+        Some(last_newline) => (&chunk[..=last_newline], last_newline + 1),
+        None => (&[], 0),
+    }
+}
+
+/// Parse usage messages from transcript lines
+///
+/// `project_path` carries the first `cwd` seen in the file across appended chunks.
+fn parse_usage_lines(
+    content: &[u8],
+    session_id: &str,
+    encoded_project_name: &str,
+    project_path: &mut Option<String>,
+) -> Vec<IndexedMessage> {
+    let mut messages = Vec::new();
+
+    for line in jsonl_lines(content) {
+        let Ok(json_value) = serde_json::from_str::<serde_json::Value>(&line) else {
+            continue;
+        };
+
+        // Extract the actual project path from cwd if we haven't already
+        if project_path.is_none() {
+            if let Some(cwd) = json_value.get("cwd").and_then(|v| v.as_str()) {
+                *project_path = Some(cwd.to_string());
+            }
+        }
+
+        let Ok(entry) = serde_json::from_value::<JsonlEntry>(json_value) else {
+            continue;
+        };
+        let Some(message) = entry.message else {
+            continue;
+        };
+        let Some(usage) = message.usage else {
+            continue;
+        };
+
+        let input_tokens = usage.input_tokens.unwrap_or(0);
+        let output_tokens = usage.output_tokens.unwrap_or(0);
+        let cache_creation_tokens = usage.cache_creation_input_tokens.unwrap_or(0);
+        let cache_read_tokens = usage.cache_read_input_tokens.unwrap_or(0);
+        // Skip entries without meaningful token usage
+        if input_tokens + output_tokens + cache_creation_tokens + cache_read_tokens == 0 {
+            continue;
+        }
+
+        messages.push(IndexedMessage {
+            message_key: message
+                .id
+                .zip(entry.request_id)
+                .map(|(msg_id, req_id)| format!("{}:{}", msg_id, req_id)),
+            timestamp: entry.timestamp,
+            model: message.model.unwrap_or_else(|| "unknown".to_string()),
+            input_tokens,
+            output_tokens,
+            cache_creation_tokens,
+            cache_read_tokens,
+            reported_cost: entry.cost_usd,
+            session_id: entry.session_id.unwrap_or_else(|| session_id.to_string()),
+            // Use actual project path if found, otherwise use encoded name
+            project_path: project_path
+                .clone()
+                .unwrap_or_else(|| encoded_project_name.to_string()),
+        });
+    }
+
+    messages
+}
+
+/// All transcripts under `~/.claude/projects` with the encoded project directory they live in
+pub(crate) fn transcript_files(claude_path: &Path) -> Vec<(PathBuf, String)> {
+    let mut files = Vec::new();
+    let Ok(projects) = fs::read_dir(claude_path.join("projects")) else {
+        return files;
+    };
+    for project in projects.flatten() {
+        if !project.file_type().map(|t| t.is_dir()).unwrap_or(false) {
+            continue;
+        }
+        let project_name = project.file_name().to_string_lossy().to_string();
+        walkdir::WalkDir::new(project.path())
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some("jsonl"))
+            .for_each(|entry| files.push((entry.path().to_path_buf(), project_name.clone())));
+    }
+    files
+}
+
+/// Read whatever was appended to a transcript since it was last indexed
+fn read_file_update(
+    path: &Path,
+    encoded_project_name: &str,
+    indexed: Option<&IndexedFile>,
+) -> Option<FileUpdate> {
+    let metadata = fs::metadata(path).ok()?;
+    let size = metadata.len() as i64;
+    let modified_ms = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0);
+
+    if let Some(indexed) = indexed {
+        if indexed.size == size && indexed.modified_ms == modified_ms {
+            return None;
+        }
+    }
+    // Transcripts only grow; anything else means the file was rewritten
+    let reset = indexed.is_none_or(|i| size < i.offset);
+    let (start, mut project_path) = match indexed {
+        Some(indexed) if !reset => (indexed.offset, indexed.project_path.clone()),
+        _ => (0, None),
+    };
+
+    let chunk = match JsonlFile::open_from(path, start as u64) {
+        Ok(chunk) => chunk,
+        Err(e) => {
+            warn!("Failed to read {:?}: {}", path, e);
+            return None;
+        }
+    };
+
+    // A line still being written is picked up on the next pass
+    let (lines, consumed) = complete_lines(chunk.bytes());
+    // Session files are named <session id>.jsonl
+    let session_id = path
+        .file_stem()
+        .and_then(|n| n.to_str())
+        .unwrap_or("unknown");
+    let messages = parse_usage_lines(
+        lines,
+        session_id,
+        encoded_project_name,
+        &mut project_path,
+    );
+
+    Some(FileUpdate {
+        path: path.to_string_lossy().to_string(),
+        modified_ms,
+        size,
+        offset: start + consumed as i64,
+        project_path,
+        reset,
+        messages,
+    })
+}
+
+/// Bring the usage index up to date with the transcripts on disk
+///
+/// Only data appended since the previous pass is parsed. Files are read
+/// without holding the database lock.
+pub fn update_usage_index(db: &AgentDb, claude_path: &Path) -> Result<(), String> {
+    let indexed: HashMap<String, IndexedFile> = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        let mut stmt = conn
+            .prepare("SELECT path, modified_ms, size, offset, project_path FROM usage_index_files")
+            .map_err(|e| e.to_string())?;
+        let files = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    IndexedFile {
+                        modified_ms: row.get(1)?,
+                        size: row.get(2)?,
+                        offset: row.get(3)?,
+                        project_path: row.get(4)?,
+                    },
+                ))
+            })
+            .map_err(|e| e.to_string())?
+            .collect::<Result<HashMap<_, _>, _>>()
+            .map_err(|e| e.to_string())?;
+        files
+    };
+
+    let files = transcript_files(claude_path);
+    let on_disk: HashSet<String> = files
+        .iter()
+        .map(|(path, _)| path.to_string_lossy().to_string())
+        .collect();
+    // Archived transcripts keep their indexed usage
+    let removed: Vec<&String> = indexed
+        .keys()
+        .filter(|p| !on_disk.contains(*p) && !archived_path(Path::new(p)).exists())
+        .collect();
+
+    let mut updates: Vec<FileUpdate> = files
+        .iter()
+        .filter_map(|(path, project_name)| {
+            read_file_update(
+                path,
+                project_name,
+                indexed.get(path.to_string_lossy().as_ref()),
+            )
+        })
+        .collect();
+    if updates.is_empty() && removed.is_empty() {
+        return Ok(());
+    }
+
+    // Index files in chronological order so a message repeated in a resumed
+    // session is attributed to the session it first appeared in
+    updates.sort_by_cached_key(|update| update.messages.iter().map(|m| m.timestamp.clone()).min());
+
+    let mut conn = db.0.lock().map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    for path in &removed {
+        tx.execute(
+            "DELETE FROM usage_messages WHERE file_path = ?1",
+            params![path],
+        )
+        .map_err(|e| e.to_string())?;
+        tx.execute(
+            "DELETE FROM usage_index_files WHERE path = ?1",
+            params![path],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    let mut message_count = 0;
+    for update in &updates {
+        if update.reset {
+            tx.execute(
+                "DELETE FROM usage_messages WHERE file_path = ?1",
+                params![update.path],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+        for message in &update.messages {
+            message_count += tx
+                .execute(
+                    "INSERT OR IGNORE INTO usage_messages (
+                        file_path, message_key, timestamp, model, input_tokens, output_tokens,
+                        cache_creation_tokens, cache_read_tokens, reported_cost, session_id, project_path
+                     ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                    params![
+                        update.path,
+                        message.message_key,
+                        message.timestamp,
+                        message.model,
+                        message.input_tokens as i64,
+                        message.output_tokens as i64,
+                        message.cache_creation_tokens as i64,
+                        message.cache_read_tokens as i64,
+                        message.reported_cost,
+                        message.session_id,
+                        message.project_path
+                    ],
+                )
+                .map_err(|e| e.to_string())?;
+        }
+        tx.execute(
+            "INSERT INTO usage_index_files (path, modified_ms, size, offset, project_path)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(path) DO UPDATE SET
+                modified_ms = ?2, size = ?3, offset = ?4, project_path = ?5",
+            params![
+                update.path,
+                update.modified_ms,
+                update.size,
+                update.offset,
+                update.project_path
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+
+    info!(
+        "Usage index updated: {} files read, {} removed, {} new messages",
+        updates.len(),
+        removed.len(),
+        message_count
+    );
+    Ok(())
+}
+
+/// Every indexed message, oldest first
+pub fn load_indexed_messages(conn: &Connection) -> Result<Vec<IndexedMessage>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT message_key, timestamp, model, input_tokens, output_tokens,
+                    cache_creation_tokens, cache_read_tokens, reported_cost, session_id, project_path
+             FROM usage_messages ORDER BY timestamp",
+        )
+        .map_err(|e| e.to_string())?;
+    let messages = stmt
+        .query_map([], |row| {
+            Ok(IndexedMessage {
+                message_key: row.get(0)?,
+                timestamp: row.get(1)?,
+                model: row.get(2)?,
+                input_tokens: row.get::<_, i64>(3)? as u64,
+                output_tokens: row.get::<_, i64>(4)? as u64,
+                cache_creation_tokens: row.get::<_, i64>(5)? as u64,
+                cache_read_tokens: row.get::<_, i64>(6)? as u64,
+                reported_cost: row.get(7)?,
+                session_id: row.get(8)?,
+                project_path: row.get(9)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(messages)
+}
+
+/// Drop the usage index so the next query rescans every transcript
+#[tauri::command]
+#[specta::specta]
+pub fn rebuild_usage_index(db: State<'_, AgentDb>) -> Result<(), String> {
+    {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        conn.execute("DELETE FROM usage_messages", [])
+            .map_err(|e| format!("Failed to clear usage index: {}", e))?;
+        conn.execute("DELETE FROM usage_index_files", [])
+            .map_err(|e| format!("Failed to clear usage index: {}", e))?;
+    }
+    let claude_path = dirs::home_dir()
+        .ok_or("Failed to get home directory")?
+        .join(".claude");
+    update_usage_index(&db, &claude_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leaves_partial_lines_for_the_next_pass() {
+        let chunk = concat!(
+            r#"{"cwd":"/repo","timestamp":"2025-01-01T10:00:00Z","requestId":"r1","message":{"id":"m1","model":"claude-sonnet-4","usage":{"input_tokens":10,"output_tokens":5}}}"#,
+            "\n",
+            r#"{"timestamp":"2025-01-01T10:00:01Z","message":{"usa"#
+        );
+
+        let (lines, consumed) = complete_lines(chunk.as_bytes());
+        assert_eq!(consumed, chunk.find('\n').unwrap() + 1);
+
+        let mut project_path = None;
+        let messages = parse_usage_lines(
+            lines,
+            "session",
+            "-repo",
+            &mut project_path,
+        );
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].message_key.as_deref(), Some("m1:r1"));
+        assert_eq!(messages[0].project_path, "/repo");
+        assert_eq!(project_path.as_deref(), Some("/repo"));
+    }
+}
@@ -0,0 +1,365 @@
+use chrono::{Datelike, Duration, Local, NaiveDate, Timelike};
+use log::{error, info};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tauri_plugin_notification::NotificationExt;
+
+use super::agents::{get_setting, set_setting, AgentDb};
+use super::usage::{usage_stats_between, UsageStats};
+
+/// Entries listed in the top projects and top models sections
+const TOP_ENTRIES: usize = 5;
+
+/// When the weekly report is generated automatically
+#[derive(Debug, Serialize, Deserialize, Clone, specta::Type)]
+pub struct UsageReportSchedule {
+    pub enabled: bool,
+    /// Day of the week, 0 = Monday
+    pub weekday: u32,
+    /// Local hour of the day, 0-23
+    pub hour: u32,
+    /// "markdown" or "html"
+    pub format: String,
+    /// Also show a desktop notification when a report is ready
+    #[serde(default)]
+    pub notify: bool,
+}
+
+impl Default for UsageReportSchedule {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            weekday: 0,
+            hour: 9,
+            format: "markdown".to_string(),
+            notify: false,
+        }
+    }
+}
+
+/// A project or model line of the report
+#[derive(Debug, Serialize, Deserialize, Clone, specta::Type)]
+pub struct ReportLine {
+    pub name: String,
+    pub cost: f64,
+    pub tokens: u64,
+}
+
+/// Usage of one week compared with the week before
+#[derive(Debug, Serialize, Deserialize, Clone, specta::Type)]
+pub struct UsageReport {
+    pub week_start: String,
+    pub week_end: String,
+    pub total_cost: f64,
+    pub total_tokens: u64,
+    pub sessions: u64,
+    pub previous_total_cost: f64,
+    /// Change in cost vs. the previous week in percent; `None` when that week had no spend
+    pub cost_change_percent: Option<f64>,
+    pub cache_savings: f64,
+    pub top_projects: Vec<ReportLine>,
+    pub top_models: Vec<ReportLine>,
+    /// "markdown" or "html"
+    pub format: String,
+    /// The rendered report
+    pub content: String,
+}
+
+fn load_schedule(conn: &Connection) -> UsageReportSchedule {
+    get_setting(conn, "usage_report_schedule").unwrap_or_default()
+}
+
+/// Monday of the week containing `date`
+fn week_start_of(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(date.weekday().num_days_from_monday() as i64)
+}
+
+fn top_lines(stats: &UsageStats) -> (Vec<ReportLine>, Vec<ReportLine>) {
+    // Both lists are already sorted by cost
+    let projects = stats
+        .by_project
+        .iter()
+        .take(TOP_ENTRIES)
+        .map(|p| ReportLine {
+            name: p.project_name.clone(),
+            cost: p.total_cost,
+            tokens: p.total_tokens,
+        })
+        .collect();
+    let models = stats
+        .by_model
+        .iter()
+        .take(TOP_ENTRIES)
+        .map(|m| ReportLine {
+            name: m.model.clone(),
+            cost: m.total_cost,
+            tokens: m.input_tokens
+                + m.output_tokens
+                + m.cache_creation_tokens
+                + m.cache_read_tokens,
+        })
+        .collect();
+    (projects, models)
+}
+
+fn format_change(change: Option<f64>) -> String {
+    match change {
+        Some(change) if change >= 0.0 => format!("+{:.0}%", change),
+        Some(change) => format!("{:.0}%", change),
+        None => "n/a".to_string(),
+    }
+}
+
+fn render_markdown(report: &UsageReport) -> String {
+    let mut md = format!(
+        "# Claude usage: {} to {}\n\n\
+         - **Spend:** ${:.2} ({} vs. previous week's ${:.2})\n\
+         - **Tokens:** {}\n\
+         - **Sessions:** {}\n\
+         - **Saved by caching:** ${:.2}\n",
+        report.week_start,
+        report.week_end,
+        report.total_cost,
+        format_change(report.cost_change_percent),
+        report.previous_total_cost,
+        report.total_tokens,
+        report.sessions,
+        report.cache_savings
+    );
+    for (title, lines) in [
+        ("Top projects", &report.top_projects),
+        ("Top models", &report.top_models),
+    ] {
+        md.push_str(&format!("\n## {}\n\n", title));
+        if lines.is_empty() {
+            md.push_str("No usage.\n");
+            continue;
+        }
+        md.push_str("| Name | Cost | Tokens |\n| --- | ---: | ---: |\n");
+        for line in lines {
+            md.push_str(&format!(
+                "| {} | ${:.2} | {} |\n",
+                line.name.replace('|', "\\|"),
+                line.cost,
+                line.tokens
+            ));
+        }
+    }
+    md
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn render_html(report: &UsageReport) -> String {
+    let mut html = format!(
+        "<h1>Claude usage: {} to {}</h1>\n<ul>\n\
+         <li><strong>Spend:</strong> ${:.2} ({} vs. previous week's ${:.2})</li>\n\
+         <li><strong>Tokens:</strong> {}</li>\n\
+         <li><strong>Sessions:</strong> {}</li>\n\
+         <li><strong>Saved by caching:</strong> ${:.2}</li>\n</ul>\n",
+        report.week_start,
+        report.week_end,
+        report.total_cost,
+        format_change(report.cost_change_percent),
+        report.previous_total_cost,
+        report.total_tokens,
+        report.sessions,
+        report.cache_savings
+    );
+    for (title, lines) in [
+        ("Top projects", &report.top_projects),
+        ("Top models", &report.top_models),
+    ] {
+        html.push_str(&format!("<h2>{}</h2>\n", title));
+        if lines.is_empty() {
+            html.push_str("<p>No usage.</p>\n");
+            continue;
+        }
+        html.push_str("<table>\n<tr><th>Name</th><th>Cost</th><th>Tokens</th></tr>\n");
+        for line in lines {
+            html.push_str(&format!(
+                "<tr><td>{}</td><td>${:.2}</td><td>{}</td></tr>\n",
+                escape_html(&line.name),
+                line.cost,
+                line.tokens
+            ));
+        }
+        html.push_str("</table>\n");
+    }
+    html
+}
+
+/// Build the report for the week starting on `week_start`
+fn build_report(db: &AgentDb, week_start: NaiveDate, format: &str) -> Result<UsageReport, String> {
+    if format != "markdown" && format != "html" {
+        return Err(format!(
+            "Unknown report format '{}', expected markdown or html",
+            format
+        ));
+    }
+    let week_end = week_start + Duration::days(6);
+    let current = usage_stats_between(db, week_start, week_end)?;
+    let previous = usage_stats_between(
+        db,
+        week_start - Duration::days(7),
+        week_start - Duration::days(1),
+    )?;
+    let (top_projects, top_models) = top_lines(&current);
+
+    let mut report = UsageReport {
+        week_start: week_start.to_string(),
+        week_end: week_end.to_string(),
+        total_cost: current.total_cost,
+        total_tokens: current.total_tokens,
+        sessions: current.total_sessions,
+        previous_total_cost: previous.total_cost,
+        cost_change_percent: (previous.total_cost > 0.0)
+            .then(|| (current.total_cost - previous.total_cost) / previous.total_cost * 100.0),
+        cache_savings: current.cache_savings,
+        top_projects,
+        top_models,
+        format: format.to_string(),
+        content: String::new(),
+    };
+    report.content = if format == "html" {
+        render_html(&report)
+    } else {
+        render_markdown(&report)
+    };
+    Ok(report)
+}
+
+/// Generate this week's scheduled report once its day and hour have passed
+fn run_scheduled_report(app: &AppHandle) -> Result<(), String> {
+    let db = app.state::<AgentDb>();
+    let (schedule, last_week) = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        let last_week: Option<String> = get_setting(&conn, "usage_report_last_week");
+        (load_schedule(&conn), last_week)
+    };
+    if !schedule.enabled {
+        return Ok(());
+    }
+
+    let now = Local::now();
+    let this_week = week_start_of(now.date_naive());
+    let due = this_week + Duration::days(schedule.weekday.min(6) as i64);
+    if now.date_naive() < due || (now.date_naive() == due && now.hour() < schedule.hour) {
+        return Ok(());
+    }
+    // Report on the last full week
+    let report_week = this_week - Duration::days(7);
+    if last_week.as_deref() == Some(report_week.to_string().as_str()) {
+        return Ok(());
+    }
+
+    let report = build_report(&db, report_week, &schedule.format)?;
+    {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        set_setting(&conn, "usage_report_last_week", &report.week_start)
+            .map_err(|e| e.to_string())?;
+    }
+    info!("Generated weekly usage report for {}", report.week_start);
+
+    if schedule.notify {
+        let body = format!(
+            "${:.2} spent ({} vs. previous week)",
+            report.total_cost,
+            format_change(report.cost_change_percent)
+        );
+        if let Err(e) = app
+            .notification()
+            .builder()
+            .title("Weekly usage report")
+            .body(&body)
+            .show()
+        {
+            error!("Failed to show report notification: {}", e);
+        }
+    }
+    super::email::send_usage_report(app, &report);
+    let _ = app.emit("usage-report-ready", &report);
+    Ok(())
+}
+
+/// Start the background check for scheduled weekly reports
+pub fn start_usage_report_scheduler(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(600));
+        loop {
+            interval.tick().await;
+            let app = app.clone();
+            match tokio::task::spawn_blocking(move || run_scheduled_report(&app)).await {
+                Ok(Err(e)) => error!("Scheduled usage report failed: {}", e),
+                Err(e) => error!("Scheduled usage report panicked: {}", e),
+                Ok(Ok(())) => {}
+            }
+        }
+    });
+}
+
+/// Render the usage report of a week; defaults to the last full week
+#[tauri::command]
+#[specta::specta]
+pub fn generate_usage_report(
+    db: State<'_, AgentDb>,
+    week_start: Option<String>,
+    format: Option<String>,
+) -> Result<UsageReport, String> {
+    let week_start = match week_start {
+        Some(date) => week_start_of(
+            NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+                .map_err(|e| format!("Invalid week start: {}", e))?,
+        ),
+        None => week_start_of(Local::now().date_naive()) - Duration::days(7),
+    };
+    build_report(&db, week_start, format.as_deref().unwrap_or("markdown"))
+}
+
+/// Render a week's usage report into a file
+#[tauri::command]
+#[specta::specta]
+pub fn export_usage_report(
+    db: State<'_, AgentDb>,
+    week_start: Option<String>,
+    format: Option<String>,
+    file_path: String,
+) -> Result<(), String> {
+    let report = generate_usage_report(db, week_start, format)?;
+    std::fs::write(&file_path, report.content).map_err(|e| format!("Failed to write file: {}", e))
+}
+
+/// Get the weekly report schedule
+#[tauri::command]
+#[specta::specta]
+pub async fn get_usage_report_schedule(
+    db: State<'_, AgentDb>,
+) -> Result<UsageReportSchedule, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    Ok(load_schedule(&conn))
+}
+
+/// Save the weekly report schedule
+#[tauri::command]
+#[specta::specta]
+pub async fn set_usage_report_schedule(
+    db: State<'_, AgentDb>,
+    schedule: UsageReportSchedule,
+) -> Result<(), String> {
+    if schedule.weekday > 6 || schedule.hour > 23 {
+        return Err("Weekday must be 0-6 and hour 0-23".to_string());
+    }
+    if schedule.format != "markdown" && schedule.format != "html" {
+        return Err("Report format must be markdown or html".to_string());
+    }
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    set_setting(&conn, "usage_report_schedule", &schedule)
+        .map_err(|e| format!("Failed to save report schedule: {}", e))?;
+    Ok(())
+}
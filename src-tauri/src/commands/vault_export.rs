@@ -0,0 +1,472 @@
+use log::{info, warn};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager, State};
+
+use super::agents::{get_setting, set_setting, AgentDb};
+use super::claude::get_claude_dir;
+use super::jsonl_reader::JsonlFile;
+use super::session_archive::archived_session_id;
+use super::usage::session_costs;
+
+const SETTINGS_KEY: &str = "vault_export";
+
+/// Tool inputs longer than this are cut in notes
+const MAX_TOOL_INPUT_CHARS: usize = 2000;
+
+/// Markers around the generated session list of a project note; text outside them is kept
+const SESSIONS_START: &str = "<!-- claudia:sessions -->";
+const SESSIONS_END: &str = "<!-- /claudia:sessions -->";
+
+const DEFAULT_TEMPLATE: &str = "# {{title}}\n\nProject: {{project}}\n\n{{transcript}}\n";
+
+/// Where and how sessions are exported as Markdown notes, e.g. into an Obsidian vault
+#[derive(Debug, Serialize, Deserialize, Clone, specta::Type)]
+pub struct VaultExportSettings {
+    /// Root of the vault; empty turns the export off
+    pub vault_path: String,
+    /// Folder inside the vault that holds the Sessions and Projects notes
+    pub folder: String,
+    /// Note body; {{title}}, {{date}}, {{project}}, {{session_id}}, {{cost}} and
+    /// {{transcript}} are replaced
+    pub note_template: String,
+    /// Tags added to every note's frontmatter
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Include tool calls as collapsed callouts
+    #[serde(default)]
+    pub include_tool_calls: bool,
+}
+
+impl Default for VaultExportSettings {
+    fn default() -> Self {
+        Self {
+            vault_path: String::new(),
+            folder: "Claudia".to_string(),
+            note_template: DEFAULT_TEMPLATE.to_string(),
+            tags: vec!["claude".to_string()],
+            include_tool_calls: false,
+        }
+    }
+}
+
+/// Outcome of a vault sync
+#[derive(Debug, Serialize, Deserialize, Clone, Default, specta::Type)]
+pub struct VaultSyncResult {
+    pub written: usize,
+    pub unchanged: usize,
+    /// Sessions that could not be exported, with the reason
+    pub failed: Vec<String>,
+}
+
+fn load_settings(conn: &Connection) -> VaultExportSettings {
+    get_setting(conn, SETTINGS_KEY).unwrap_or_default()
+}
+
+/// A session read from its transcript
+struct ExportedSession {
+    id: String,
+    project_path: String,
+    title: String,
+    /// Timestamp of the first message, RFC 3339
+    started_at: String,
+    models: Vec<String>,
+    transcript: String,
+}
+
+impl ExportedSession {
+    fn date(&self) -> &str {
+        self.started_at.get(..10).unwrap_or(&self.started_at)
+    }
+
+    fn note_name(&self) -> String {
+        let title: String = self.title.chars().take(50).collect();
+        let short_id: String = self.id.chars().take(8).collect();
+        note_file_name(&format!("{} {} {}", self.date(), title.trim(), short_id))
+    }
+}
+
+/// Strip characters Obsidian does not allow in note names or links
+fn note_file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' | '#' | '^' | '[' | ']' => ' ',
+            c if c.is_control() => ' ',
+            c => c,
+        })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn project_note_name(project_path: &str) -> String {
+    let name = Path::new(project_path)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or(project_path);
+    note_file_name(name)
+}
+
+/// Text of a message's content, rendering tool calls when asked for
+fn render_content(content: &JsonValue, include_tool_calls: bool) -> String {
+    let blocks = match content {
+        JsonValue::String(text) => return text.trim().to_string(),
+        JsonValue::Array(blocks) => blocks,
+        _ => return String::new(),
+    };
+    let mut parts = Vec::new();
+    for block in blocks {
+        match block["type"].as_str() {
+            Some("text") => {
+                if let Some(text) = block["text"].as_str() {
+                    parts.push(text.trim().to_string());
+                }
+            }
+            Some("tool_use") if include_tool_calls => {
+                let mut input = serde_json::to_string_pretty(&block["input"]).unwrap_or_default();
+                if input.chars().count() > MAX_TOOL_INPUT_CHARS {
+                    input = input.chars().take(MAX_TOOL_INPUT_CHARS).collect::<String>() + "\n…";
+                }
+                let body: Vec<String> = input.lines().map(|line| format!("> {}", line)).collect();
+                parts.push(format!(
+                    "> [!note]- Tool: {}\n> ```json\n{}\n> ```",
+                    block["name"].as_str().unwrap_or("unknown"),
+                    body.join("\n")
+                ));
+            }
+            _ => {}
+        }
+    }
+    parts.retain(|part| !part.is_empty());
+    parts.join("\n\n")
+}
+
+/// Messages Claude Code writes for local commands rather than the conversation
+fn is_local_command_output(text: &str) -> bool {
+    text.starts_with("<command-name>")
+        || text.starts_with("<local-command-stdout>")
+        || text.starts_with("<command-message>")
+        || text.contains("Caveat: The messages below were generated by the user while running local commands")
+}
+
+fn read_session(path: &Path, session_id: &str, include_tool_calls: bool) -> Result<ExportedSession, String> {
+    let file = JsonlFile::open(path).map_err(|e| format!("Failed to open transcript: {}", e))?;
+    let mut session = ExportedSession {
+        id: session_id.to_string(),
+        project_path: String::new(),
+        title: String::new(),
+        started_at: String::new(),
+        models: Vec::new(),
+        transcript: String::new(),
+    };
+
+    for line in file.lines() {
+        let Ok(entry) = serde_json::from_str::<JsonValue>(&line) else {
+            continue;
+        };
+        if session.project_path.is_empty() {
+            if let Some(cwd) = entry["cwd"].as_str() {
+                session.project_path = cwd.to_string();
+            }
+        }
+        let role = match entry["type"].as_str() {
+            Some("user") => "User",
+            Some("assistant") => "Claude",
+            _ => continue,
+        };
+        if entry["isMeta"].as_bool() == Some(true) {
+            continue;
+        }
+        let text = render_content(&entry["message"]["content"], include_tool_calls);
+        if text.is_empty() || is_local_command_output(&text) {
+            continue;
+        }
+
+        let timestamp = entry["timestamp"].as_str().unwrap_or_default();
+        if session.started_at.is_empty() {
+            session.started_at = timestamp.to_string();
+        }
+        if role == "User" && session.title.is_empty() {
+            session.title = text.lines().next().unwrap_or_default().to_string();
+        }
+        if let Some(model) = entry["message"]["model"].as_str() {
+            if !session.models.iter().any(|m| m == model) {
+                session.models.push(model.to_string());
+            }
+        }
+
+        let time = chrono::DateTime::parse_from_rfc3339(timestamp)
+            .map(|t| t.with_timezone(&chrono::Local).format(" · %H:%M").to_string())
+            .unwrap_or_default();
+        session.transcript.push_str(&format!("### {}{}\n\n{}\n\n", role, time, text));
+    }
+
+    if session.started_at.is_empty() {
+        return Err("Transcript has no messages".to_string());
+    }
+    if session.title.is_empty() {
+        session.title = "Untitled session".to_string();
+    }
+    Ok(session)
+}
+
+/// JSON strings are valid YAML scalars, which saves escaping by hand
+fn yaml_string(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
+
+fn render_session_note(
+    session: &ExportedSession,
+    settings: &VaultExportSettings,
+    cost: f64,
+) -> String {
+    let project_link = format!(
+        "[[{}/Projects/{}|{}]]",
+        settings.folder,
+        project_note_name(&session.project_path),
+        project_note_name(&session.project_path)
+    );
+    let tags: Vec<String> = settings.tags.iter().map(|tag| yaml_string(tag)).collect();
+    let models: Vec<String> = session.models.iter().map(|model| yaml_string(model)).collect();
+    let frontmatter = format!(
+        "---\ntitle: {}\nsession_id: {}\nproject: {}\nproject_path: {}\ndate: {}\ncost: {:.4}\nmodels: [{}]\ntags: [{}]\n---\n\n",
+        yaml_string(&session.title),
+        yaml_string(&session.id),
+        yaml_string(&project_link),
+        yaml_string(&session.project_path),
+        session.started_at,
+        cost,
+        models.join(", "),
+        tags.join(", ")
+    );
+    let body = settings
+        .note_template
+        .replace("{{title}}", &session.title)
+        .replace("{{date}}", session.date())
+        .replace("{{project}}", &project_link)
+        .replace("{{session_id}}", &session.id)
+        .replace("{{cost}}", &format!("${:.2}", cost))
+        .replace("{{transcript}}", session.transcript.trim_end());
+    frontmatter + &body
+}
+
+/// Replace the generated session list of a project note, creating the note if needed
+fn render_project_note(existing: Option<&str>, project_path: &str, session_links: &[String]) -> String {
+    let list = format!(
+        "{}\n{}\n{}",
+        SESSIONS_START,
+        session_links.join("\n"),
+        SESSIONS_END
+    );
+    if let Some(existing) = existing {
+        if let (Some(start), Some(end)) = (existing.find(SESSIONS_START), existing.find(SESSIONS_END)) {
+            if start < end {
+                return format!(
+                    "{}{}{}",
+                    &existing[..start],
+                    list,
+                    &existing[end + SESSIONS_END.len()..]
+                );
+            }
+        }
+        return format!("{}\n\n## Sessions\n\n{}\n", existing.trim_end(), list);
+    }
+    format!(
+        "---\nproject_path: {}\ntags: [\"project\"]\n---\n\n# {}\n\n## Sessions\n\n{}\n",
+        yaml_string(project_path),
+        project_note_name(project_path),
+        list
+    )
+}
+
+/// Write a note unless it already has this content; returns whether it was written
+fn write_note(path: &Path, content: &str) -> Result<bool, String> {
+    if fs::read_to_string(path).is_ok_and(|existing| existing == content) {
+        return Ok(false);
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create {:?}: {}", parent, e))?;
+    }
+    fs::write(path, content).map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+    Ok(true)
+}
+
+/// Every transcript under ~/.claude/projects as (session id, path)
+fn transcript_files() -> Result<Vec<(String, PathBuf)>, String> {
+    let projects_dir = get_claude_dir().map_err(|e| e.to_string())?.join("projects");
+    let mut files = Vec::new();
+    let Ok(projects) = fs::read_dir(&projects_dir) else {
+        return Ok(files);
+    };
+    for project in projects.filter_map(|entry| entry.ok()).map(|entry| entry.path()) {
+        let Ok(entries) = fs::read_dir(&project) else {
+            continue;
+        };
+        for path in entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()) {
+            if let Some(session_id) = archived_session_id(&path) {
+                // JsonlFile opens the archive when given the transcript's own path
+                files.push((session_id.to_string(), project.join(format!("{}.jsonl", session_id))));
+            } else if path.extension().and_then(|e| e.to_str()) == Some("jsonl") {
+                if let Some(session_id) = path.file_stem().and_then(|s| s.to_str()) {
+                    files.push((session_id.to_string(), path.clone()));
+                }
+            }
+        }
+    }
+    Ok(files)
+}
+
+fn validate_settings(settings: &VaultExportSettings) -> Result<PathBuf, String> {
+    if settings.vault_path.is_empty() {
+        return Err("No vault path configured".to_string());
+    }
+    let vault = PathBuf::from(&settings.vault_path);
+    if !vault.is_dir() {
+        return Err(format!("Vault directory not found: {}", settings.vault_path));
+    }
+    Ok(vault.join(&settings.folder))
+}
+
+fn sync_vault(db: &AgentDb) -> Result<VaultSyncResult, String> {
+    let settings = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        load_settings(&conn)
+    };
+    let root = validate_settings(&settings)?;
+    let costs = session_costs(db).unwrap_or_else(|e| {
+        warn!("Exporting sessions without costs: {}", e);
+        HashMap::new()
+    });
+
+    let mut result = VaultSyncResult::default();
+    // Session links per project, sorted by note name so the newest come last
+    let mut project_sessions: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+    for (session_id, path) in transcript_files()? {
+        let session = match read_session(&path, &session_id, settings.include_tool_calls) {
+            Ok(session) => session,
+            Err(e) => {
+                result.failed.push(format!("{}: {}", session_id, e));
+                continue;
+            }
+        };
+        let cost = costs.get(&session_id).copied().unwrap_or(0.0);
+        let note_name = session.note_name();
+        let note = render_session_note(&session, &settings, cost);
+        match write_note(&root.join("Sessions").join(format!("{}.md", note_name)), &note) {
+            Ok(true) => result.written += 1,
+            Ok(false) => result.unchanged += 1,
+            Err(e) => {
+                result.failed.push(format!("{}: {}", session_id, e));
+                continue;
+            }
+        }
+        project_sessions
+            .entry(session.project_path.clone())
+            .or_default()
+            .push(format!("- [[{}/Sessions/{}|{}]]", settings.folder, note_name, note_name));
+    }
+
+    for (project_path, mut links) in project_sessions {
+        links.sort();
+        let path = root
+            .join("Projects")
+            .join(format!("{}.md", project_note_name(&project_path)));
+        let existing = fs::read_to_string(&path).ok();
+        let note = render_project_note(existing.as_deref(), &project_path, &links);
+        if let Err(e) = write_note(&path, &note) {
+            result.failed.push(format!("{}: {}", project_path, e));
+        }
+    }
+
+    info!(
+        "Vault sync wrote {} notes, {} unchanged, {} failed",
+        result.written,
+        result.unchanged,
+        result.failed.len()
+    );
+    Ok(result)
+}
+
+/// Get the vault export settings
+#[tauri::command]
+#[specta::specta]
+pub async fn get_vault_export_settings(db: State<'_, AgentDb>) -> Result<VaultExportSettings, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    Ok(load_settings(&conn))
+}
+
+/// Save the vault export settings
+#[tauri::command]
+#[specta::specta]
+pub async fn set_vault_export_settings(
+    db: State<'_, AgentDb>,
+    settings: VaultExportSettings,
+) -> Result<(), String> {
+    if !settings.vault_path.is_empty() {
+        validate_settings(&settings)?;
+    }
+    if settings.folder.contains("..") {
+        return Err("The vault folder must stay inside the vault".to_string());
+    }
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    set_setting(&conn, SETTINGS_KEY, &settings)
+        .map_err(|e| format!("Failed to save vault export settings: {}", e))?;
+    Ok(())
+}
+
+/// Write every session as a note into the vault, with a note per project linking them
+#[tauri::command]
+#[specta::specta]
+pub async fn sync_vault_export(app: AppHandle) -> Result<VaultSyncResult, String> {
+    tauri::async_runtime::spawn_blocking(move || sync_vault(&app.state::<AgentDb>()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn renders_transcript_without_local_commands() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("abc.jsonl");
+        let lines = [
+            json!({"type": "user", "cwd": "/work/app", "timestamp": "2026-03-01T10:00:00Z",
+                   "message": {"role": "user", "content": "<command-name>/clear</command-name>"}}),
+            json!({"type": "user", "cwd": "/work/app", "timestamp": "2026-03-01T10:01:00Z",
+                   "message": {"role": "user", "content": "Fix the login bug\nIt fails on Safari"}}),
+            json!({"type": "assistant", "timestamp": "2026-03-01T10:02:00Z",
+                   "message": {"role": "assistant", "model": "claude-sonnet-4",
+                               "content": [{"type": "text", "text": "Done."},
+                                           {"type": "tool_use", "name": "Edit", "input": {}}]}}),
+        ];
+        let contents: Vec<String> = lines.iter().map(|l| l.to_string()).collect();
+        fs::write(&path, contents.join("\n")).unwrap();
+
+        let session = read_session(&path, "abc", false).unwrap();
+        assert_eq!(session.title, "Fix the login bug");
+        assert_eq!(session.project_path, "/work/app");
+        assert_eq!(session.models, vec!["claude-sonnet-4"]);
+        assert!(!session.transcript.contains("/clear"));
+        assert!(!session.transcript.contains("Tool: Edit"));
+        assert_eq!(session.note_name(), "2026-03-01 Fix the login bug abc");
+    }
+
+    #[test]
+    fn keeps_text_around_generated_session_list() {
+        let first = render_project_note(None, "/work/app", &["- [[a]]".to_string()]);
+        let edited = first.replace("## Sessions", "My notes\n\n## Sessions");
+        let updated = render_project_note(Some(&edited), "/work/app", &["- [[b]]".to_string()]);
+        assert!(updated.contains("My notes"));
+        assert!(updated.contains("- [[b]]"));
+        assert!(!updated.contains("- [[a]]"));
+    }
+}
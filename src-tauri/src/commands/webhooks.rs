@@ -0,0 +1,463 @@
+use hmac::{Hmac, Mac};
+use log::{error, info, warn};
+use rusqlite::{params, Connection, Row};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+use sha2::Sha256;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
+use super::agents::AgentDb;
+use super::live_metrics::AgentLiveMetrics;
+use super::secrets::{delete_keychain_value, read_keychain_value, write_keychain_value};
+
+/// Events a webhook can subscribe to
+pub const WEBHOOK_EVENTS: &[&str] = &["run.started", "run.completed", "run.failed"];
+
+/// How long a receiver has to answer before the delivery counts as failed
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Point in an agent run's lifecycle that outgoing integrations are told about
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RunLifecycleEvent {
+    Started,
+    Completed,
+    Failed,
+}
+
+impl RunLifecycleEvent {
+    pub fn name(self) -> &'static str {
+        match self {
+            RunLifecycleEvent::Started => "run.started",
+            RunLifecycleEvent::Completed => "run.completed",
+            RunLifecycleEvent::Failed => "run.failed",
+        }
+    }
+}
+
+/// An outgoing webhook called on run lifecycle events
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct Webhook {
+    pub id: Option<i64>,
+    pub name: String,
+    pub url: String,
+    /// Whether payloads are signed; the key itself stays in the keychain
+    pub has_secret: bool,
+    /// Subscribed events; empty means all of them
+    pub events: Vec<String>,
+    pub enabled: bool,
+    pub last_status: Option<i64>,
+    pub last_error: Option<String>,
+    pub last_delivered_at: Option<String>,
+    pub created_at: String,
+}
+
+impl Webhook {
+    fn wants(&self, event: &str) -> bool {
+        self.enabled && (self.events.is_empty() || self.events.iter().any(|e| e == event))
+    }
+}
+
+const WEBHOOK_COLUMNS: &str =
+    "id, name, url, secret, events, enabled, last_status, last_error, last_delivered_at, created_at";
+
+/// Keychain account of a webhook's signing key; the `secret` column only holds this name
+///
+/// The colon keeps it apart from user secrets, whose names are environment variable names.
+fn secret_account(id: i64) -> String {
+    format!("webhook:{}", id)
+}
+
+/// Account signing keys were first stored under, among the user secret names
+fn legacy_secret_account(id: i64) -> String {
+    format!("webhook_secret_{}", id)
+}
+
+fn row_to_webhook(row: &Row) -> rusqlite::Result<Webhook> {
+    let events: String = row.get(4)?;
+    Ok(Webhook {
+        id: Some(row.get(0)?),
+        name: row.get(1)?,
+        url: row.get(2)?,
+        has_secret: !row.get::<_, String>(3)?.is_empty(),
+        events: serde_json::from_str(&events).unwrap_or_default(),
+        enabled: row.get(5)?,
+        last_status: row.get(6)?,
+        last_error: row.get(7)?,
+        last_delivered_at: row.get(8)?,
+        created_at: row.get(9)?,
+    })
+}
+
+fn load_webhook(conn: &Connection, id: i64) -> Result<Webhook, String> {
+    conn.query_row(
+        &format!("SELECT {} FROM webhooks WHERE id = ?1", WEBHOOK_COLUMNS),
+        params![id],
+        row_to_webhook,
+    )
+    .map_err(|e| format!("Webhook {} not found: {}", id, e))
+}
+
+fn load_webhooks(conn: &Connection) -> Result<Vec<Webhook>, String> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM webhooks ORDER BY name COLLATE NOCASE",
+            WEBHOOK_COLUMNS
+        ))
+        .map_err(|e| e.to_string())?;
+    let webhooks = stmt
+        .query_map([], row_to_webhook)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(webhooks)
+}
+
+fn validate(name: &str, url: &str, events: &[String]) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("Webhook name cannot be empty".to_string());
+    }
+    let parsed = reqwest::Url::parse(url).map_err(|e| format!("Invalid webhook URL: {}", e))?;
+    if parsed.scheme() != "http" && parsed.scheme() != "https" {
+        return Err("Webhook URLs must use http or https".to_string());
+    }
+    if let Some(event) = events.iter().find(|e| !WEBHOOK_EVENTS.contains(&e.as_str())) {
+        return Err(format!(
+            "Unknown webhook event '{}', expected one of {}",
+            event,
+            WEBHOOK_EVENTS.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+/// Hex HMAC-SHA256 of a payload, sent as `X-Claudia-Signature: sha256=<hex>`
+pub fn sign_payload(secret: &str, payload: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(payload);
+    mac.finalize()
+        .into_bytes()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// JSON describing a run and, once it has finished, what it used
+pub fn run_event_payload(
+    conn: &Connection,
+    run_id: i64,
+    event: RunLifecycleEvent,
+    metrics: Option<&AgentLiveMetrics>,
+) -> Result<JsonValue, String> {
+    let run = conn
+        .query_row(
+            "SELECT agent_id, agent_name, task, model, project_path, session_id, status, created_at, completed_at FROM agent_runs WHERE id = ?1",
+            params![run_id],
+            |row| {
+                Ok(json!({
+                    "id": run_id,
+                    "agent_id": row.get::<_, i64>(0)?,
+                    "agent_name": row.get::<_, String>(1)?,
+                    "task": row.get::<_, String>(2)?,
+                    "model": row.get::<_, String>(3)?,
+                    "project_path": row.get::<_, String>(4)?,
+                    "session_id": row.get::<_, String>(5)?,
+                    "status": row.get::<_, String>(6)?,
+                    "created_at": row.get::<_, String>(7)?,
+                    "completed_at": row.get::<_, Option<String>>(8)?,
+                }))
+            },
+        )
+        .map_err(|e| format!("Agent run {} not found: {}", run_id, e))?;
+
+    Ok(json!({
+        "event": event.name(),
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+        "run": run,
+        "metrics": metrics.map(|m| json!({
+            "total_tokens": m.total_tokens,
+            "input_tokens": m.input_tokens,
+            "output_tokens": m.output_tokens,
+            "cost_usd": m.estimated_cost_usd,
+            "elapsed_ms": m.elapsed_ms,
+        })),
+    }))
+}
+
+/// Store, replace or, when empty, remove a webhook's signing key, keeping the reference
+/// in the table in step
+fn save_secret(conn: &Connection, id: i64, secret: &str) -> Result<(), String> {
+    let account = secret_account(id);
+    let reference = if secret.is_empty() {
+        delete_keychain_value(&account)?;
+        ""
+    } else {
+        write_keychain_value(&account, secret)?;
+        account.as_str()
+    };
+    conn.execute(
+        "UPDATE webhooks SET secret = ?1 WHERE id = ?2",
+        params![reference, id],
+    )
+    .map_err(|e| format!("Failed to update webhook: {}", e))?;
+    Ok(())
+}
+
+/// Move a signing key from its legacy keychain account to the current one
+fn move_legacy_secret(conn: &Connection, id: i64) -> Result<(), String> {
+    let secret = read_keychain_value(&legacy_secret_account(id))?.unwrap_or_default();
+    save_secret(conn, id, &secret)?;
+    delete_keychain_value(&legacy_secret_account(id))
+}
+
+/// Move signing keys stored in the table or under legacy accounts by earlier versions to
+/// their keychain account
+pub fn move_webhook_secrets_to_keychain(conn: &Connection) {
+    let misplaced: Vec<(i64, String)> = conn
+        .prepare("SELECT id, secret FROM webhooks WHERE secret != ''")
+        .and_then(|mut stmt| {
+            stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|(id, secret)| *secret != secret_account(*id))
+        .collect();
+    for (id, secret) in misplaced {
+        let result = if secret == legacy_secret_account(id) {
+            move_legacy_secret(conn, id)
+        } else {
+            save_secret(conn, id, &secret)
+        };
+        match result {
+            Ok(()) => info!("Moved the secret of webhook {} to the keychain", id),
+            Err(e) => error!("Failed to move the secret of webhook {} to the keychain: {}", id, e),
+        }
+    }
+}
+
+/// POST a payload to a webhook; returns the HTTP status
+async fn deliver(webhook: &Webhook, event: &str, payload: &JsonValue) -> Result<u16, String> {
+    let secret = match webhook.id.filter(|_| webhook.has_secret) {
+        Some(id) => read_keychain_value(&secret_account(id))?
+            .ok_or("The webhook's secret is missing from the keychain")?,
+        None => String::new(),
+    };
+    let body = serde_json::to_vec(payload).map_err(|e| e.to_string())?;
+    let mut request = super::proxy::http_client()
+        .post(&webhook.url)
+        .timeout(DELIVERY_TIMEOUT)
+        .header("Content-Type", "application/json")
+        .header("User-Agent", concat!("Claudia/", env!("CARGO_PKG_VERSION")))
+        .header("X-Claudia-Event", event)
+        .header("X-Claudia-Delivery", uuid::Uuid::new_v4().to_string());
+    if !secret.is_empty() {
+        request = request.header(
+            "X-Claudia-Signature",
+            format!("sha256={}", sign_payload(&secret, &body)),
+        );
+    }
+
+    let response = request
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to call webhook: {}", e))?;
+    let status = response.status();
+    if status.is_success() {
+        Ok(status.as_u16())
+    } else {
+        Err(format!("Webhook answered {}", status))
+    }
+}
+
+fn record_delivery(app: &AppHandle, id: i64, result: &Result<u16, String>) {
+    let db = app.state::<AgentDb>();
+    let Ok(conn) = db.0.lock() else {
+        return;
+    };
+    let (status, error) = match result {
+        Ok(status) => (Some(*status as i64), None),
+        Err(e) => (None, Some(e.as_str())),
+    };
+    let _ = conn.execute(
+        "UPDATE webhooks SET last_status = ?1, last_error = ?2, last_delivered_at = CURRENT_TIMESTAMP WHERE id = ?3",
+        params![status, error, id],
+    );
+}
+
+/// Call the webhooks subscribed to a run event in the background
+pub fn fire_run_event(
+    app: &AppHandle,
+    run_id: i64,
+    event: RunLifecycleEvent,
+    metrics: Option<&AgentLiveMetrics>,
+) {
+    let (webhooks, payload) = {
+        let db = app.state::<AgentDb>();
+        let Ok(conn) = db.0.lock() else {
+            return;
+        };
+        let webhooks: Vec<Webhook> = match load_webhooks(&conn) {
+            Ok(webhooks) => webhooks.into_iter().filter(|w| w.wants(event.name())).collect(),
+            Err(e) => {
+                error!("Failed to load webhooks: {}", e);
+                return;
+            }
+        };
+        if webhooks.is_empty() {
+            return;
+        }
+        match run_event_payload(&conn, run_id, event, metrics) {
+            Ok(payload) => (webhooks, payload),
+            Err(e) => {
+                warn!("Skipping {} webhooks: {}", event.name(), e);
+                return;
+            }
+        }
+    };
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        for webhook in webhooks {
+            let result = deliver(&webhook, event.name(), &payload).await;
+            match &result {
+                Ok(_) => info!("Delivered {} to webhook '{}'", event.name(), webhook.name),
+                Err(e) => warn!("Webhook '{}' failed for {}: {}", webhook.name, event.name(), e),
+            }
+            if let Some(id) = webhook.id {
+                record_delivery(&app, id, &result);
+            }
+        }
+    });
+}
+
+/// List all webhooks by name
+#[tauri::command]
+#[specta::specta]
+pub async fn list_webhooks(db: State<'_, AgentDb>) -> Result<Vec<Webhook>, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    load_webhooks(&conn)
+}
+
+/// Create a webhook
+#[tauri::command]
+#[specta::specta]
+pub async fn create_webhook(
+    db: State<'_, AgentDb>,
+    name: String,
+    url: String,
+    secret: Option<String>,
+    events: Vec<String>,
+) -> Result<Webhook, String> {
+    validate(&name, &url, &events)?;
+    let events = serde_json::to_string(&events).map_err(|e| e.to_string())?;
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO webhooks (name, url, events) VALUES (?1, ?2, ?3)",
+        params![name.trim(), url.trim(), events],
+    )
+    .map_err(|e| format!("Failed to create webhook: {}", e))?;
+    let id = conn.last_insert_rowid();
+    if let Some(secret) = secret.filter(|s| !s.is_empty()) {
+        if let Err(e) = save_secret(&conn, id, &secret) {
+            let _ = conn.execute("DELETE FROM webhooks WHERE id = ?1", params![id]);
+            return Err(e);
+        }
+    }
+    load_webhook(&conn, id)
+}
+
+/// Update a webhook
+///
+/// `secret` replaces the signing key; an empty one removes it and `None` keeps it.
+#[tauri::command]
+#[specta::specta]
+pub async fn update_webhook(
+    db: State<'_, AgentDb>,
+    id: i64,
+    name: String,
+    url: String,
+    secret: Option<String>,
+    events: Vec<String>,
+    enabled: bool,
+) -> Result<Webhook, String> {
+    validate(&name, &url, &events)?;
+    let events = serde_json::to_string(&events).map_err(|e| e.to_string())?;
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let updated = conn
+        .execute(
+            "UPDATE webhooks SET name = ?1, url = ?2, events = ?3, enabled = ?4 WHERE id = ?5",
+            params![name.trim(), url.trim(), events, enabled, id],
+        )
+        .map_err(|e| format!("Failed to update webhook: {}", e))?;
+    if updated == 0 {
+        return Err(format!("Webhook {} not found", id));
+    }
+    if let Some(secret) = secret {
+        save_secret(&conn, id, &secret)?;
+    }
+    load_webhook(&conn, id)
+}
+
+/// Delete a webhook
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_webhook(db: State<'_, AgentDb>, id: i64) -> Result<(), String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM webhooks WHERE id = ?1", params![id])
+        .map_err(|e| format!("Failed to delete webhook: {}", e))?;
+    if let Err(e) = delete_keychain_value(&secret_account(id)) {
+        warn!("{}", e);
+    }
+    Ok(())
+}
+
+/// Send a sample `run.completed` payload to a webhook and report the HTTP status
+#[tauri::command]
+#[specta::specta]
+pub async fn test_webhook(app: AppHandle, db: State<'_, AgentDb>, id: i64) -> Result<u16, String> {
+    let webhook = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        load_webhook(&conn, id)?
+    };
+    let payload = json!({
+        "event": "run.completed",
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+        "test": true,
+        "run": {
+            "id": 0,
+            "agent_name": "Test agent",
+            "task": "Webhook test from Claudia",
+            "status": "completed",
+        },
+        "metrics": null,
+    });
+    let result = deliver(&webhook, "run.completed", &payload).await;
+    record_delivery(&app, id, &result);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signs_with_hmac_sha256() {
+        // RFC 4231, test case 2
+        assert_eq!(
+            sign_payload("Jefe", b"what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn validates_url_and_events() {
+        let events = vec!["run.failed".to_string()];
+        assert!(validate("CI", "https://example.com/hook", &events).is_ok());
+        assert!(validate("CI", "ftp://example.com/hook", &events).is_err());
+        assert!(validate("CI", "https://example.com/hook", &["run.paused".to_string()]).is_err());
+        assert!(validate(" ", "https://example.com/hook", &events).is_err());
+    }
+}
@@ -3,14 +3,18 @@
 
 mod checkpoint;
 mod claude_binary;
+mod cli;
 mod commands;
 mod process;
 
 use checkpoint::state::CheckpointState;
+use claude_binary::check_pinned_claude_binary;
 use commands::agents::{
     cleanup_finished_processes, create_agent, delete_agent, execute_agent, execute_feature, export_agent,
     export_agent_to_file, fetch_github_agent_content, fetch_github_agents, get_agent,
+    get_github_agent_repo, import_agents_from_github, set_github_agent_repo,
     get_agent_run, get_agent_run_with_real_time_metrics, get_claude_binary_path,
+    get_claude_binary_status,
     get_live_session_output, get_session_output, get_session_status, import_agent,
     import_agent_from_file, import_agent_from_github, init_database, kill_agent_session,
     list_agent_runs, list_agent_runs_with_metrics, list_agents, list_claude_installations,
@@ -18,11 +22,13 @@ use commands::agents::{
 };
 use commands::claude::{
     cancel_claude_execution, check_auto_checkpoint, check_claude_version, cleanup_old_checkpoints,
-    clear_checkpoint_manager, continue_claude_code, create_checkpoint, execute_claude_code,
-    find_claude_md_files, fork_from_checkpoint, get_checkpoint_diff, get_checkpoint_settings,
+    clear_checkpoint_manager, continue_claude_code, create_checkpoint, diff_checkpoints,
+    execute_claude_code, export_checkpoint, import_checkpoint,
+    find_all_claude_md_files, find_claude_md_files, fork_from_checkpoint, get_checkpoint_diff, get_checkpoint_path,
+    get_checkpoint_settings,
     get_checkpoint_state_stats, get_claude_session_output, get_claude_settings, get_project_sessions,
     get_recently_modified_files, get_session_timeline, get_system_prompt, list_checkpoints,
-    list_directory_contents, list_projects, list_running_claude_sessions, load_session_history,
+    list_directory_contents, list_projects, scan_projects_streaming, list_running_claude_sessions, load_session_history,
     open_new_session, read_claude_md_file, restore_checkpoint, resume_claude_code,
     save_claude_md_file, save_claude_settings, save_system_prompt, search_files,
     track_checkpoint_message, track_session_messages, update_checkpoint_settings,
@@ -31,66 +37,276 @@ use commands::claude::{
 };
 use commands::mcp::{
     mcp_add, mcp_add_from_claude_desktop, mcp_add_json, mcp_get, mcp_get_server_status, mcp_list,
-    mcp_read_project_config, mcp_remove, mcp_reset_project_choices, mcp_save_project_config,
-    mcp_serve, mcp_test_connection,
+    mcp_get_project_approvals, mcp_list_claude_desktop_servers, mcp_read_project_config,
+    mcp_remove, mcp_reset_project_choices, mcp_save_project_config, mcp_serve,
+    mcp_set_project_approval, mcp_test_connection,
 };
+use commands::mcp_connection::test_mcp_connection;
+use commands::mcp_bundle::{
+    export_mcp_bundle, export_mcp_bundle_to_file, import_mcp_bundle, import_mcp_bundle_from_file,
+};
+use commands::mcp_health::{
+    check_mcp_servers_now, get_mcp_health_history, get_mcp_health_settings,
+    set_mcp_health_settings, start_mcp_health_monitor,
+};
+use commands::mcp_logs::get_mcp_logs;
+use commands::mcp_oauth::{
+    get_mcp_oauth_status, revoke_mcp_oauth, start_mcp_oauth, start_mcp_oauth_refresher,
+};
+use commands::mcp_registry::{
+    install_mcp_registry_server, search_mcp_registry, set_mcp_registry_url,
+};
+use commands::claudia_mcp::{
+    get_claudia_mcp_server, set_claudia_mcp_server, start_claudia_mcp_server,
+    ClaudiaMcpServerState,
+};
+use commands::secrets::{delete_secret, list_secrets, set_secret};
 
+use commands::artifacts::{collect_run_artifacts, export_run_artifacts, get_run_artifacts};
+use commands::pipelines::{
+    cancel_pipeline_run, create_agent_pipeline, delete_agent_pipeline, get_pipeline_run,
+    list_agent_pipelines, list_pipeline_runs, resume_pipeline_run, run_agent_pipeline,
+    update_agent_pipeline,
+};
+use commands::run_compare::compare_agent_runs;
+use commands::agent_versions::{diff_agent_versions, list_agent_versions, rollback_agent};
+use commands::notifications::{get_agent_notification_settings, set_agent_notification_settings};
+use commands::run_logs::{get_run_logs, get_session_error_logs};
+use commands::dry_run::{approve_agent_plan, execute_agent_dry_run, get_agent_plan};
+use commands::batches::{execute_agent_batch, get_agent_batch, list_agent_batches};
+use commands::run_recovery::{
+    detect_interrupted_runs, discard_interrupted_agent_run, list_interrupted_agent_runs,
+    resume_agent_run,
+};
+use commands::run_queue::{
+    get_max_concurrent_runs, list_queued_agent_runs, remove_queued_agent_run,
+    reorder_agent_run_queue, set_max_concurrent_runs, start_queue_dispatcher, AgentRunQueueState,
+};
+use commands::checkpoint_retention::{
+    cleanup_checkpoints, get_checkpoint_retention, set_checkpoint_retention,
+};
+use commands::live_metrics::{get_live_cost, LiveCostState};
+use commands::settings_file::{read_settings_file, validate_settings_content, write_settings_file};
+use commands::env_profiles::{get_project_env, list_project_env_profiles, set_project_env};
+use commands::proxy::{get_proxy_settings, init_proxy_settings, set_proxy_settings};
+use commands::environment::check_environment;
+use commands::background_tasks::{
+    cancel_background_task, create_task_queue, list_background_tasks, start_task_workers,
+    TaskQueueState,
+};
+use commands::rest_api::{
+    get_rest_api_server, set_rest_api_server, start_rest_api_server, RestApiState,
+};
+use commands::deep_link::{
+    resolve_deep_link, start_deep_link_handler, take_pending_deep_link, DeepLinkState,
+};
+use commands::session_file_index::find_sessions_for_file;
+use commands::discord::{get_discord_settings, set_discord_settings, test_discord_webhook};
+use commands::slack::{get_slack_settings, set_slack_settings, test_slack_webhook};
+use commands::telemetry::{
+    get_telemetry_settings, init_telemetry_settings, set_telemetry_settings,
+    start_telemetry_exporter, test_telemetry_export,
+};
+use commands::plugins::{
+    execute_plugin_command, get_plugins_directory, list_plugins, reload_plugins,
+    render_plugin_panel, PluginHostState,
+};
+use commands::vault_export::{
+    get_vault_export_settings, set_vault_export_settings, sync_vault_export,
+};
+use commands::history_import::{import_history, preview_history_import};
+use commands::gists::share_run_as_gist;
+use commands::email::{get_email_settings, send_test_email, set_email_settings};
+use commands::schedule_calendar::export_schedule_calendar;
+use commands::issues::{create_issue_from_session, fix_issue_with_agent, list_aggregated_issues};
+use commands::jira::{get_jira_settings, set_jira_settings, test_jira_connection};
+use commands::linear::{get_linear_settings, list_linear_teams, set_linear_settings};
+use commands::api_version::{api_handshake, COMMAND_API_VERSION};
+use commands::app_bundle::{export_app_bundle, import_app_bundle, inspect_app_bundle};
+use commands::webhooks::{
+    create_webhook, delete_webhook, list_webhooks, move_webhook_secrets_to_keychain,
+    test_webhook, update_webhook,
+};
+use commands::db_maintenance::{
+    get_db_maintenance_settings, get_last_db_maintenance, run_db_maintenance,
+    set_db_maintenance_settings, start_db_maintenance_scheduler,
+};
+use commands::session_archive::{
+    archive_old_sessions, archive_session, get_archive_stats, list_archived_sessions,
+    unarchive_session,
+};
+use commands::listing_cache::{start_listing_cache_invalidation, ListingCache};
+use commands::fs_watch::{create_fs_watch_state, subscribe_fs_changes, unsubscribe_fs_changes};
+use commands::backup::{create_backup, inspect_backup, restore_backup};
+use commands::migrations::get_schema_version;
+use commands::run_retry::{get_retry_policy, set_retry_policy, start_retry_scheduler};
+use commands::orphans::{
+    adopt_orphaned_process, detect_orphaned_processes, list_orphaned_processes,
+    terminate_orphaned_process, OrphanState,
+};
+use commands::resources::{get_run_resources, start_resource_monitor, ResourceMonitorState};
+use commands::pty::{
+    kill_pty_session, list_pty_sessions, resize_pty_session, start_pty_session,
+    write_pty_session,
+};
+use commands::processes::{
+    get_background_priority, get_kill_grace_period, init_kill_grace_period,
+    list_running_processes, set_background_priority, set_kill_grace_period,
+};
+use commands::prompt_presets::{
+    create_prompt_preset, delete_prompt_preset, list_prompt_presets, update_prompt_preset,
+};
+use commands::preferences::{
+    delete_preference, get_preference, get_preferences, set_preference, set_preferences,
+};
+use commands::permissions::{
+    get_permission_rules, preview_permissions, set_permission_rules, validate_permission_rule,
+};
+use commands::hooks::{
+    get_merged_hooks, install_hook_template, list_hook_templates, save_hooks_config,
+    validate_hooks_config,
+};
+use commands::scheduler::{
+    create_agent_schedule, delete_agent_schedule, get_scheduler_paused, list_agent_schedules,
+    preview_cron_schedule, run_agent_schedule_now, set_agent_schedule_enabled,
+    set_scheduler_paused, start_scheduler, update_agent_schedule,
+};
+use commands::tray::{start_tray, TrayState};
+use commands::hotkeys::{handle_shortcut, list_hotkeys, set_hotkey, start_hotkeys, HotkeyState};
+use commands::updater::{
+    check_for_updates, download_update, get_update_settings, install_pending_update_on_exit,
+    install_update, set_update_settings, start_update_checker, UpdaterState,
+};
+use commands::crash_reports::{
+    delete_crash_reports, get_crash_report_settings, install_panic_hook, list_crash_reports,
+    preview_crash_report, report_frontend_error, set_crash_report_settings,
+    start_crash_report_submission, submit_crash_reports,
+};
+use commands::app_logs::{get_app_logs, init_logging, open_log_directory};
 use commands::usage::{
-    get_session_stats, get_usage_by_date_range, get_usage_details, get_usage_stats,
+    compare_model_costs, export_usage, export_usage_ccusage, export_usage_to_file,
+    get_model_pricing, get_project_usage, get_session_stats, get_session_usage,
+    get_usage_by_agent, get_usage_by_date_range, get_usage_by_project, get_usage_details,
+    get_usage_stats, set_model_pricing_override,
+};
+use commands::usage_budgets::{
+    get_budget_status, get_usage_budgets, set_usage_budgets, start_budget_monitor,
+};
+use commands::usage_index::rebuild_usage_index;
+use commands::usage_report::{
+    export_usage_report, generate_usage_report, get_usage_report_schedule,
+    set_usage_report_schedule, start_usage_report_scheduler,
 };
 use commands::storage::{
     storage_list_tables, storage_read_table, storage_update_row, storage_delete_row,
     storage_insert_row, storage_execute_sql, storage_reset_database,
 };
+use process::pty::PtyRegistryState;
 use process::ProcessRegistryState;
 use std::sync::Mutex;
 use tauri::Manager;
 
-fn main() {
-    // Initialize logger
-    env_logger::init();
+/// Manage the state the commands rely on, shared by the GUI and the headless CLI
+///
+/// Only the GUI recovers processes and runs left over from an earlier session; the CLI
+/// may run next to it and must not mark the GUI's runs as interrupted.
+fn manage_app_state(app: &tauri::App, recover_previous_session: bool) -> TaskQueueState {
+    // Initialize agents database
+    let conn = init_database(&app.handle()).expect("Failed to initialize agents database");
+    let mut orphans = Vec::new();
+    if recover_previous_session {
+        orphans = detect_orphaned_processes(&conn).unwrap_or_else(|e| {
+            log::error!("Failed to detect orphaned processes: {}", e);
+            Vec::new()
+        });
+        if let Err(e) = detect_interrupted_runs(&conn) {
+            log::error!("Failed to detect interrupted agent runs: {}", e);
+        }
+    }
+    app.manage(OrphanState(Mutex::new(orphans)));
+    init_proxy_settings(&conn);
+    init_telemetry_settings(&conn);
+    init_kill_grace_period(&conn);
+    move_webhook_secrets_to_keychain(&conn);
+    app.manage(AgentDb(Mutex::new(conn)));
 
+    // Initialize checkpoint state
+    let checkpoint_state = CheckpointState::new();
 
-    tauri::Builder::default()
-        .plugin(tauri_plugin_dialog::init())
-        .plugin(tauri_plugin_shell::init())
-        .setup(|app| {
-            // Initialize agents database
-            let conn = init_database(&app.handle()).expect("Failed to initialize agents database");
-            app.manage(AgentDb(Mutex::new(conn)));
-
-            // Initialize checkpoint state
-            let checkpoint_state = CheckpointState::new();
-
-            // Set the Claude directory path
-            if let Ok(claude_dir) = dirs::home_dir()
-                .ok_or_else(|| "Could not find home directory")
-                .and_then(|home| {
-                    let claude_path = home.join(".claude");
-                    claude_path
-                        .canonicalize()
-                        .map_err(|_| "Could not find ~/.claude directory")
-                })
-            {
-                let state_clone = checkpoint_state.clone();
-                tauri::async_runtime::spawn(async move {
-                    state_clone.set_claude_dir(claude_dir).await;
-                });
+    // Set the Claude directory path
+    if let Ok(claude_dir) = dirs::home_dir()
+        .ok_or_else(|| "Could not find home directory")
+        .and_then(|home| {
+            let claude_path = home.join(".claude");
+            claude_path
+                .canonicalize()
+                .map_err(|_| "Could not find ~/.claude directory")
+        })
+    {
+        let state_clone = checkpoint_state.clone();
+        tauri::async_runtime::spawn(async move {
+            match checkpoint::migrate_legacy_timelines(&claude_dir) {
+                Ok(0) => {}
+                Ok(moved) => log::info!("Moved {} checkpoint timelines to ~/.claude/timelines", moved),
+                Err(e) => log::warn!("Failed to move checkpoint timelines: {}", e),
             }
+            state_clone.set_claude_dir(claude_dir).await;
+        });
+    }
 
-            app.manage(checkpoint_state);
+    app.manage(checkpoint_state);
 
-            // Initialize process registry
-            app.manage(ProcessRegistryState::default());
+    // Initialize process registry
+    app.manage(ProcessRegistryState::default());
+    app.manage(PtyRegistryState::default());
+    app.manage(ResourceMonitorState::default());
 
-            // Initialize Claude process state
-            app.manage(ClaudeProcessState::default());
+    // Initialize Claude process state
+    app.manage(ClaudeProcessState::default());
 
-            Ok(())
-        })
-        .invoke_handler(tauri::generate_handler![
+    // Initialize live cost tracking of streamed executions
+    app.manage(LiveCostState::default());
+
+    // Initialize agent run queue state
+    app.manage(AgentRunQueueState::default());
+
+    // Initialize built-in MCP server state
+    app.manage(ClaudiaMcpServerState::default());
+
+    // Initialize REST API server state
+    app.manage(RestApiState::default());
+
+    // Initialize the link waiting for the frontend
+    app.manage(DeepLinkState::default());
+    app.manage(TrayState::default());
+    app.manage(HotkeyState::default());
+    app.manage(UpdaterState::default());
+
+    // Initialize plugin host, plugins start on first use
+    app.manage(PluginHostState::default());
+
+    // Initialize the queue for background work
+    let task_queue = create_task_queue(app.handle().clone());
+    app.manage(task_queue.clone());
+
+    // Initialize the shared watcher of ~/.claude/projects
+    app.manage(create_fs_watch_state());
+
+    // Initialize the cache of project, session and agent listings
+    app.manage(ListingCache::default());
+
+    task_queue
+}
+
+/// Every command the frontend can invoke; tauri-specta exports their argument and result
+/// types to TypeScript from this list
+fn command_api() -> tauri_specta::Builder<tauri::Wry> {
+    tauri_specta::Builder::<tauri::Wry>::new()
+        .constant("COMMAND_API_VERSION", COMMAND_API_VERSION)
+        .commands(tauri_specta::collect_commands![
             // Claude & Project Management
             list_projects,
+            scan_projects_streaming,
             get_project_sessions,
             get_claude_settings,
             open_new_session,
@@ -99,6 +315,7 @@ fn main() {
             save_system_prompt,
             save_claude_settings,
             find_claude_md_files,
+            find_all_claude_md_files,
             read_claude_md_file,
             save_claude_md_file,
             load_session_history,
@@ -114,23 +331,138 @@ fn main() {
             get_hooks_config,
             update_hooks_config,
             validate_hook_command,
-            
+        
             // Checkpoint Management
             create_checkpoint,
             restore_checkpoint,
             list_checkpoints,
             fork_from_checkpoint,
+            get_checkpoint_path,
             get_session_timeline,
             update_checkpoint_settings,
             get_checkpoint_diff,
+            diff_checkpoints,
+            export_checkpoint,
+            import_checkpoint,
             track_checkpoint_message,
             track_session_messages,
             check_auto_checkpoint,
             cleanup_old_checkpoints,
+            get_checkpoint_retention,
+            set_checkpoint_retention,
+            cleanup_checkpoints,
+            read_settings_file,
+            validate_settings_content,
+            write_settings_file,
+            validate_hooks_config,
+            get_merged_hooks,
+            save_hooks_config,
+            list_hook_templates,
+            install_hook_template,
+            get_project_env,
+            set_project_env,
+            list_project_env_profiles,
+            get_proxy_settings,
+            set_proxy_settings,
+            get_permission_rules,
+            set_permission_rules,
+            validate_permission_rule,
+            preview_permissions,
+            get_preference,
+            get_preferences,
+            set_preference,
+            set_preferences,
+            delete_preference,
+            check_environment,
+            list_prompt_presets,
+            create_prompt_preset,
+            update_prompt_preset,
+            delete_prompt_preset,
+            list_running_processes,
+            get_kill_grace_period,
+            set_kill_grace_period,
+            get_background_priority,
+            set_background_priority,
+            start_pty_session,
+            write_pty_session,
+            resize_pty_session,
+            kill_pty_session,
+            list_pty_sessions,
+            get_session_error_logs,
+            get_run_resources,
+            list_orphaned_processes,
+            adopt_orphaned_process,
+            terminate_orphaned_process,
+            get_retry_policy,
+            set_retry_policy,
+            get_schema_version,
+            create_backup,
+            inspect_backup,
+            restore_backup,
+            list_background_tasks,
+            cancel_background_task,
+            subscribe_fs_changes,
+            unsubscribe_fs_changes,
+            archive_session,
+            archive_old_sessions,
+            unarchive_session,
+            list_archived_sessions,
+            get_archive_stats,
+            run_db_maintenance,
+            get_last_db_maintenance,
+            get_db_maintenance_settings,
+            set_db_maintenance_settings,
+            find_sessions_for_file,
+            get_rest_api_server,
+            set_rest_api_server,
+            take_pending_deep_link,
+            resolve_deep_link,
+            list_webhooks,
+            create_webhook,
+            update_webhook,
+            delete_webhook,
+            test_webhook,
+            get_slack_settings,
+            set_slack_settings,
+            test_slack_webhook,
+            get_discord_settings,
+            set_discord_settings,
+            test_discord_webhook,
+            get_telemetry_settings,
+            set_telemetry_settings,
+            test_telemetry_export,
+            list_plugins,
+            reload_plugins,
+            execute_plugin_command,
+            render_plugin_panel,
+            get_plugins_directory,
+            get_vault_export_settings,
+            set_vault_export_settings,
+            sync_vault_export,
+            preview_history_import,
+            import_history,
+            share_run_as_gist,
+            get_email_settings,
+            set_email_settings,
+            send_test_email,
+            export_schedule_calendar,
+            list_aggregated_issues,
+            fix_issue_with_agent,
+            get_jira_settings,
+            set_jira_settings,
+            test_jira_connection,
+            create_issue_from_session,
+            get_linear_settings,
+            set_linear_settings,
+            list_linear_teams,
+            api_handshake,
+            export_app_bundle,
+            inspect_app_bundle,
+            import_app_bundle,
             get_checkpoint_settings,
             clear_checkpoint_manager,
             get_checkpoint_state_stats,
-            
+        
             // Agent Management
             list_agents,
             create_agent,
@@ -153,6 +485,7 @@ fn main() {
             load_agent_session_history,
             get_claude_binary_path,
             set_claude_binary_path,
+            get_claude_binary_status,
             list_claude_installations,
             export_agent,
             export_agent_to_file,
@@ -161,13 +494,93 @@ fn main() {
             fetch_github_agents,
             fetch_github_agent_content,
             import_agent_from_github,
-            
+            import_agents_from_github,
+            get_github_agent_repo,
+            set_github_agent_repo,
+            list_agent_schedules,
+            create_agent_schedule,
+            update_agent_schedule,
+            set_agent_schedule_enabled,
+            delete_agent_schedule,
+            run_agent_schedule_now,
+            preview_cron_schedule,
+            get_scheduler_paused,
+            set_scheduler_paused,
+            list_hotkeys,
+            set_hotkey,
+            get_update_settings,
+            set_update_settings,
+            check_for_updates,
+            download_update,
+            install_update,
+            get_crash_report_settings,
+            set_crash_report_settings,
+            list_crash_reports,
+            preview_crash_report,
+            report_frontend_error,
+            submit_crash_reports,
+            delete_crash_reports,
+            get_app_logs,
+            open_log_directory,
+            list_agent_pipelines,
+            create_agent_pipeline,
+            update_agent_pipeline,
+            delete_agent_pipeline,
+            run_agent_pipeline,
+            list_pipeline_runs,
+            get_pipeline_run,
+            resume_pipeline_run,
+            cancel_pipeline_run,
+            get_max_concurrent_runs,
+            set_max_concurrent_runs,
+            list_queued_agent_runs,
+            reorder_agent_run_queue,
+            remove_queued_agent_run,
+            get_run_artifacts,
+            collect_run_artifacts,
+            export_run_artifacts,
+            compare_agent_runs,
+            list_agent_versions,
+            diff_agent_versions,
+            rollback_agent,
+            list_interrupted_agent_runs,
+            resume_agent_run,
+            discard_interrupted_agent_run,
+            get_agent_notification_settings,
+            set_agent_notification_settings,
+            get_run_logs,
+            execute_agent_batch,
+            list_agent_batches,
+            get_agent_batch,
+            execute_agent_dry_run,
+            get_agent_plan,
+            approve_agent_plan,
+        
             // Usage & Analytics
             get_usage_stats,
+            get_live_cost,
             get_usage_by_date_range,
             get_usage_details,
             get_session_stats,
-            
+            get_usage_by_project,
+            get_usage_by_agent,
+            compare_model_costs,
+            get_project_usage,
+            get_session_usage,
+            export_usage,
+            export_usage_to_file,
+            export_usage_ccusage,
+            rebuild_usage_index,
+            get_usage_budgets,
+            set_usage_budgets,
+            get_budget_status,
+            generate_usage_report,
+            export_usage_report,
+            get_usage_report_schedule,
+            set_usage_report_schedule,
+            get_model_pricing,
+            set_model_pricing_override,
+        
             // MCP (Model Context Protocol)
             mcp_add,
             mcp_list,
@@ -175,13 +588,37 @@ fn main() {
             mcp_remove,
             mcp_add_json,
             mcp_add_from_claude_desktop,
+            mcp_list_claude_desktop_servers,
             mcp_serve,
             mcp_test_connection,
+            test_mcp_connection,
+            get_mcp_logs,
+            search_mcp_registry,
+            set_mcp_registry_url,
+            install_mcp_registry_server,
+            start_mcp_oauth,
+            get_mcp_oauth_status,
+            revoke_mcp_oauth,
+            check_mcp_servers_now,
+            get_mcp_health_history,
+            get_mcp_health_settings,
+            set_mcp_health_settings,
+            export_mcp_bundle,
+            export_mcp_bundle_to_file,
+            import_mcp_bundle,
+            import_mcp_bundle_from_file,
+            get_claudia_mcp_server,
+            set_claudia_mcp_server,
             mcp_reset_project_choices,
             mcp_get_server_status,
             mcp_read_project_config,
             mcp_save_project_config,
-            
+            mcp_get_project_approvals,
+            mcp_set_project_approval,
+            list_secrets,
+            set_secret,
+            delete_secret,
+        
             // Storage Management
             storage_list_tables,
             storage_read_table,
@@ -190,13 +627,128 @@ fn main() {
             storage_insert_row,
             storage_execute_sql,
             storage_reset_database,
-            
+        
             // Slash Commands
             commands::slash_commands::slash_commands_list,
             commands::slash_commands::slash_command_get,
             commands::slash_commands::slash_command_save,
             commands::slash_commands::slash_command_delete,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+}
+
+/// Generated bindings, imported by the frontend instead of hand-written types
+const BINDINGS_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../src/lib/bindings.ts");
+
+fn typescript_bindings(api: &tauri_specta::Builder<tauri::Wry>) -> Result<String, String> {
+    api.export_str(
+        specta_typescript::Typescript::default()
+            .bigint(specta_typescript::BigIntExportBehavior::Number),
+    )
+    .map_err(|e| format!("Failed to export TypeScript bindings: {}", e))
+}
+
+fn export_bindings(api: &tauri_specta::Builder<tauri::Wry>) -> Result<(), String> {
+    let bindings = typescript_bindings(api)?;
+    // Rewriting an unchanged file would retrigger the frontend dev server on every launch
+    if std::fs::read_to_string(BINDINGS_PATH).ok().as_deref() == Some(bindings.as_str()) {
+        return Ok(());
+    }
+    std::fs::write(BINDINGS_PATH, bindings)
+        .map_err(|e| format!("Failed to write TypeScript bindings: {}", e))
+}
+
+fn main() {
+    // Log to stderr and to the rotating log file
+    init_logging();
+    install_panic_hook();
+
+    // GUI launches miss the PATH set up by shell profiles, so load it before anything spawns
+    process::shell_env::apply_login_shell_env();
+
+    let context = tauri::generate_context!();
+    match cli::parse_args(std::env::args().skip(1)) {
+        Ok(Some(command)) => std::process::exit(cli::run(command, context)),
+        Ok(None) => {}
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, cli::USAGE);
+            std::process::exit(2);
+        }
+    }
+
+    let api = command_api();
+    // Debug builds keep the frontend's bindings in step with the commands
+    #[cfg(debug_assertions)]
+    if let Err(e) = export_bindings(&api) {
+        log::warn!("{}", e);
+    }
+
+    tauri::Builder::default()
+        // Must come first: a second launch hands its `claudia://` link to this instance
+        .plugin(tauri_plugin_single_instance::init(|app, _argv, _cwd| {
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.set_focus();
+            }
+        }))
+        .plugin(tauri_plugin_deep_link::init())
+        .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_shell::init())
+        .plugin(tauri_plugin_notification::init())
+        .plugin(
+            tauri_plugin_global_shortcut::Builder::new()
+                .with_handler(handle_shortcut)
+                .build(),
+        )
+        .plugin(tauri_plugin_updater::Builder::new().build())
+        .setup(|app| {
+            let task_queue = manage_app_state(app, true);
+            if let Err(e) = start_listing_cache_invalidation(app.handle().clone()) {
+                log::warn!("Listing cache will not see file changes: {}", e);
+            }
+
+            // Start background tasks once the state they depend on is managed
+            start_task_workers(task_queue);
+            start_scheduler(app.handle().clone());
+            start_queue_dispatcher(app.handle().clone());
+            start_claudia_mcp_server(app.handle().clone());
+            start_rest_api_server(app.handle().clone());
+            start_deep_link_handler(app.handle().clone());
+            start_tray(app.handle().clone());
+            start_hotkeys(app.handle().clone());
+            start_update_checker(app.handle().clone());
+            start_crash_report_submission(app.handle().clone());
+            start_telemetry_exporter();
+            start_mcp_oauth_refresher();
+            start_mcp_health_monitor(app.handle().clone());
+            start_db_maintenance_scheduler(app.handle().clone());
+            start_budget_monitor(app.handle().clone());
+            start_usage_report_scheduler(app.handle().clone());
+            check_pinned_claude_binary(app.handle().clone());
+            start_resource_monitor(app.handle().clone());
+            start_retry_scheduler(app.handle().clone());
+
+            Ok(())
+        })
+        .invoke_handler(api.invoke_handler())
+        .build(context)
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            // An update downloaded but not yet installed applies on the next launch
+            if let tauri::RunEvent::Exit = event {
+                install_pending_update_on_exit(app);
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    /// The committed bindings must match the commands; a debug launch regenerates them
+    #[test]
+    fn committed_typescript_bindings_are_current() {
+        let generated = super::typescript_bindings(&super::command_api()).unwrap();
+        let committed = std::fs::read_to_string(super::BINDINGS_PATH).unwrap();
+        assert!(
+            generated == committed,
+            "src/lib/bindings.ts is out of date; run the app in a debug build to regenerate it"
+        );
+    }
 }
@@ -1,3 +1,8 @@
+pub mod priority;
+pub mod pty;
 pub mod registry;
+pub mod shell_env;
+pub mod terminate;
+pub mod tree;
 
 pub use registry::*;
@@ -0,0 +1,125 @@
+use serde::{Deserialize, Serialize};
+
+/// Highest niceness accepted; 19 is the lowest priority on Unix
+pub const MAX_NICENESS: i32 = 19;
+
+/// CPU and I/O priority of unattended agent runs
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, specta::Type)]
+#[serde(default)]
+pub struct BackgroundPriority {
+    pub enabled: bool,
+    /// Unix niceness from 1 to 19; Windows always uses BELOW_NORMAL
+    pub niceness: i32,
+    /// Only schedule disk I/O when the disk is otherwise idle (Linux only)
+    pub idle_io: bool,
+}
+
+impl Default for BackgroundPriority {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            niceness: 10,
+            idle_io: true,
+        }
+    }
+}
+
+/// Lower the priority of a process that was just spawned
+///
+/// On Unix the whole process group is reniced when the process leads one; processes
+/// it starts later inherit the priority either way.
+#[cfg(unix)]
+pub fn lower_priority(pid: u32, priority: &BackgroundPriority) -> Result<(), String> {
+    let leads_group = unsafe { libc::getpgid(pid as libc::pid_t) } == pid as libc::pid_t;
+    let which = if leads_group {
+        libc::PRIO_PGRP
+    } else {
+        libc::PRIO_PROCESS
+    };
+    let niceness = priority.niceness.clamp(1, MAX_NICENESS);
+    if unsafe { libc::setpriority(which as _, pid as libc::id_t, niceness) } != 0 {
+        return Err(format!(
+            "Failed to renice process {}: {}",
+            pid,
+            std::io::Error::last_os_error()
+        ));
+    }
+
+    #[cfg(target_os = "linux")]
+    if priority.idle_io {
+        const IOPRIO_WHO_PROCESS: libc::c_long = 1;
+        const IOPRIO_WHO_PGRP: libc::c_long = 2;
+        const IOPRIO_CLASS_IDLE: libc::c_long = 3;
+        let who = if leads_group {
+            IOPRIO_WHO_PGRP
+        } else {
+            IOPRIO_WHO_PROCESS
+        };
+        let result = unsafe {
+            libc::syscall(
+                libc::SYS_ioprio_set,
+                who,
+                pid as libc::c_long,
+                IOPRIO_CLASS_IDLE << 13,
+            )
+        };
+        if result != 0 {
+            return Err(format!(
+                "Failed to set the I/O priority of process {}: {}",
+                pid,
+                std::io::Error::last_os_error()
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Lower the priority of a process that was just spawned
+///
+/// Processes it starts later inherit the BELOW_NORMAL priority class.
+#[cfg(windows)]
+pub fn lower_priority(pid: u32, _priority: &BackgroundPriority) -> Result<(), String> {
+    use windows_sys::Win32::Foundation::CloseHandle;
+    use windows_sys::Win32::System::Threading::{
+        OpenProcess, SetPriorityClass, BELOW_NORMAL_PRIORITY_CLASS, PROCESS_SET_INFORMATION,
+    };
+
+    unsafe {
+        let process = OpenProcess(PROCESS_SET_INFORMATION, 0, pid);
+        if process.is_null() {
+            return Err(format!("Failed to open process {}", pid));
+        }
+        let lowered = SetPriorityClass(process, BELOW_NORMAL_PRIORITY_CLASS) != 0;
+        CloseHandle(process);
+        if !lowered {
+            return Err(format!("Failed to lower the priority of process {}", pid));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renices_a_spawned_process() {
+        let mut child = std::process::Command::new("sleep")
+            .arg("30")
+            .spawn()
+            .unwrap();
+        let pid = child.id();
+        let priority = BackgroundPriority {
+            enabled: true,
+            niceness: MAX_NICENESS,
+            idle_io: false,
+        };
+        let result = lower_priority(pid, &priority);
+        let niceness = unsafe { libc::getpriority(libc::PRIO_PROCESS as _, pid as libc::id_t) };
+        child.kill().unwrap();
+        child.wait().unwrap();
+
+        result.unwrap();
+        assert_eq!(niceness, MAX_NICENESS);
+    }
+}
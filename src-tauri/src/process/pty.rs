@@ -0,0 +1,248 @@
+use chrono::{DateTime, Utc};
+use portable_pty::{native_pty_system, ChildKiller, CommandBuilder, MasterPty, PtySize};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::sync::{Arc, Mutex};
+
+/// Information about a process running in a pseudo-terminal
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct PtyProcessInfo {
+    pub id: String,
+    pub pid: Option<u32>,
+    pub project_path: String,
+    pub agent_id: Option<i64>,
+    pub started_at: DateTime<Utc>,
+    pub rows: u16,
+    pub cols: u16,
+}
+
+struct PtyHandle {
+    info: PtyProcessInfo,
+    master: Box<dyn MasterPty + Send>,
+    writer: Box<dyn Write + Send>,
+    killer: Box<dyn ChildKiller + Send + Sync>,
+}
+
+/// Registry of processes attached to a pseudo-terminal
+///
+/// The reader thread of each process owns its child and removes the entry once it exits.
+#[derive(Default)]
+pub struct PtyRegistry {
+    processes: Mutex<HashMap<String, PtyHandle>>,
+}
+
+/// Build a PTY command with the program, arguments, directory and environment of `cmd`
+pub fn command_builder(cmd: &std::process::Command) -> CommandBuilder {
+    let mut builder = CommandBuilder::new(cmd.get_program());
+    builder.args(cmd.get_args());
+    if let Some(dir) = cmd.get_current_dir() {
+        builder.cwd(dir);
+    }
+    for (key, value) in cmd.get_envs() {
+        match value {
+            Some(value) => builder.env(key, value),
+            None => builder.env_remove(key),
+        }
+    }
+    // Tools only emit colors and progress bars for terminals they recognise
+    if builder.get_env("TERM").is_none() {
+        builder.env("TERM", "xterm-256color");
+    }
+    builder
+}
+
+fn pty_size(rows: u16, cols: u16) -> PtySize {
+    PtySize {
+        rows,
+        cols,
+        pixel_width: 0,
+        pixel_height: 0,
+    }
+}
+
+impl PtyRegistry {
+    /// Spawn a command in a new pseudo-terminal
+    ///
+    /// Raw output chunks are passed to `on_output` from a background thread, and `on_exit`
+    /// receives the exit code once the output ends and the process has been reaped.
+    pub fn spawn<O, E>(
+        self: &Arc<Self>,
+        mut info: PtyProcessInfo,
+        cmd: CommandBuilder,
+        mut on_output: O,
+        on_exit: E,
+    ) -> Result<PtyProcessInfo, String>
+    where
+        O: FnMut(&[u8]) + Send + 'static,
+        E: FnOnce(Option<u32>) + Send + 'static,
+    {
+        let pair = native_pty_system()
+            .openpty(pty_size(info.rows, info.cols))
+            .map_err(|e| format!("Failed to open a pseudo-terminal: {}", e))?;
+        let mut child = pair
+            .slave
+            .spawn_command(cmd)
+            .map_err(|e| format!("Failed to spawn process in pseudo-terminal: {}", e))?;
+        // Only the child should hold the slave side, so reads end when it exits
+        drop(pair.slave);
+
+        let mut reader = pair
+            .master
+            .try_clone_reader()
+            .map_err(|e| format!("Failed to read from pseudo-terminal: {}", e))?;
+        let writer = pair
+            .master
+            .take_writer()
+            .map_err(|e| format!("Failed to write to pseudo-terminal: {}", e))?;
+        info.pid = child.process_id();
+
+        {
+            let mut processes = self.processes.lock().map_err(|e| e.to_string())?;
+            processes.insert(
+                info.id.clone(),
+                PtyHandle {
+                    info: info.clone(),
+                    master: pair.master,
+                    writer,
+                    killer: child.clone_killer(),
+                },
+            );
+        }
+
+        let registry = Arc::clone(self);
+        let id = info.id.clone();
+        std::thread::spawn(move || {
+            let mut buffer = [0u8; 8192];
+            loop {
+                match reader.read(&mut buffer) {
+                    Ok(0) => break,
+                    Ok(n) => on_output(&buffer[..n]),
+                    // Linux reports EIO on the master once the slave side is closed
+                    Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                    Err(_) => break,
+                }
+            }
+            let exit_code = match child.wait() {
+                Ok(status) => Some(status.exit_code()),
+                Err(e) => {
+                    log::error!("Failed to wait for PTY process {}: {}", id, e);
+                    None
+                }
+            };
+            if let Ok(mut processes) = registry.processes.lock() {
+                processes.remove(&id);
+            }
+            log::info!("PTY process {} exited with code {:?}", id, exit_code);
+            on_exit(exit_code);
+        });
+
+        Ok(info)
+    }
+
+    /// Send input to a process as if it was typed into its terminal
+    pub fn write(&self, id: &str, data: &[u8]) -> Result<(), String> {
+        let mut processes = self.processes.lock().map_err(|e| e.to_string())?;
+        let handle = processes
+            .get_mut(id)
+            .ok_or_else(|| format!("No PTY process with ID {}", id))?;
+        handle
+            .writer
+            .write_all(data)
+            .and_then(|_| handle.writer.flush())
+            .map_err(|e| format!("Failed to write to PTY process {}: {}", id, e))
+    }
+
+    /// Change the terminal size reported to a process
+    pub fn resize(&self, id: &str, rows: u16, cols: u16) -> Result<(), String> {
+        let mut processes = self.processes.lock().map_err(|e| e.to_string())?;
+        let handle = processes
+            .get_mut(id)
+            .ok_or_else(|| format!("No PTY process with ID {}", id))?;
+        handle
+            .master
+            .resize(pty_size(rows, cols))
+            .map_err(|e| format!("Failed to resize PTY process {}: {}", id, e))?;
+        handle.info.rows = rows;
+        handle.info.cols = cols;
+        Ok(())
+    }
+
+    /// Stop a process, interrupting it before killing it
+    pub fn kill(&self, id: &str) -> Result<(), String> {
+        let (pid, mut killer) = {
+            let processes = self.processes.lock().map_err(|e| e.to_string())?;
+            let handle = processes
+                .get(id)
+                .ok_or_else(|| format!("No PTY process with ID {}", id))?;
+            (handle.info.pid, handle.killer.clone_killer())
+        };
+        match pid {
+            Some(pid) => {
+                super::terminate::terminate_blocking(pid, super::terminate::grace_period())?;
+            }
+            None => killer
+                .kill()
+                .map_err(|e| format!("Failed to kill PTY process {}: {}", id, e))?,
+        }
+        Ok(())
+    }
+
+    pub fn list(&self) -> Result<Vec<PtyProcessInfo>, String> {
+        let processes = self.processes.lock().map_err(|e| e.to_string())?;
+        let mut list: Vec<PtyProcessInfo> = processes.values().map(|h| h.info.clone()).collect();
+        list.sort_by_key(|info| info.started_at);
+        Ok(list)
+    }
+}
+
+pub struct PtyRegistryState(pub Arc<PtyRegistry>);
+
+impl Default for PtyRegistryState {
+    fn default() -> Self {
+        Self(Arc::new(PtyRegistry::default()))
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    #[test]
+    fn streams_output_and_reports_terminal() {
+        let registry = Arc::new(PtyRegistry::default());
+        let mut cmd = std::process::Command::new("sh");
+        cmd.args(["-c", "stty size; test -t 1 && echo tty; exit 3"]);
+        let info = PtyProcessInfo {
+            id: "test".to_string(),
+            pid: None,
+            project_path: String::new(),
+            agent_id: None,
+            started_at: Utc::now(),
+            rows: 24,
+            cols: 100,
+        };
+
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&output);
+        let (tx, rx) = mpsc::channel();
+        let info = registry
+            .spawn(
+                info,
+                command_builder(&cmd),
+                move |chunk| sink.lock().unwrap().extend_from_slice(chunk),
+                move |code| tx.send(code).unwrap(),
+            )
+            .unwrap();
+        assert!(info.pid.is_some());
+
+        let code = rx.recv_timeout(Duration::from_secs(10)).unwrap();
+        assert_eq!(code, Some(3));
+        let output = String::from_utf8_lossy(&output.lock().unwrap()).to_string();
+        assert!(output.contains("24 100"), "{}", output);
+        assert!(output.contains("tty"), "{}", output);
+        assert!(registry.list().unwrap().is_empty());
+    }
+}
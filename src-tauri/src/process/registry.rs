@@ -5,7 +5,7 @@ use std::sync::{Arc, Mutex};
 use tokio::process::Child;
 
 /// Type of process being tracked
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
 pub enum ProcessType {
     AgentRun {
         agent_id: i64,
@@ -17,7 +17,7 @@ pub enum ProcessType {
 }
 
 /// Information about a running agent process
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
 pub struct ProcessInfo {
     pub run_id: i64,
     pub process_type: ProcessType,
@@ -172,8 +172,22 @@ impl ProcessRegistry {
         Ok(())
     }
 
+    /// Track a process that was not spawned by this registry, such as an adopted orphan
+    pub fn register_external(&self, process_info: ProcessInfo) -> Result<(), String> {
+        let mut processes = self.processes.lock().map_err(|e| e.to_string())?;
+        let run_id = process_info.run_id;
+        processes.insert(
+            run_id,
+            ProcessHandle {
+                info: process_info,
+                child: Arc::new(Mutex::new(None)),
+                live_output: Arc::new(Mutex::new(String::new())),
+            },
+        );
+        Ok(())
+    }
+
     /// Get all running processes
-    #[allow(dead_code)]
     pub fn get_running_processes(&self) -> Result<Vec<ProcessInfo>, String> {
         let processes = self.processes.lock().map_err(|e| e.to_string())?;
         Ok(processes
@@ -223,92 +237,32 @@ impl ProcessRegistry {
             run_id, pid
         );
 
-        // Send kill signal to the process
-        let kill_sent = {
+        // Interrupt first so Claude can flush its session file, then escalate
+        let result = super::terminate::terminate(pid, super::terminate::grace_period()).await;
+
+        // Reap the child so it does not linger as a zombie
+        {
             let mut child_guard = child_arc.lock().map_err(|e| e.to_string())?;
             if let Some(child) = child_guard.as_mut() {
-                match child.start_kill() {
-                    Ok(_) => {
-                        info!("Successfully sent kill signal to process {}", run_id);
-                        true
+                match child.try_wait() {
+                    Ok(Some(status)) => {
+                        info!("Process {} exited with status: {:?}", run_id, status);
+                        *child_guard = None;
                     }
+                    Ok(None) => warn!("Process {} is still running after termination", run_id),
                     Err(e) => {
-                        error!("Failed to send kill signal to process {}: {}", run_id, e);
-                        // Don't return error here, try fallback method
-                        false
+                        error!("Error checking process status: {}", e);
+                        *child_guard = None;
                     }
                 }
-            } else {
-                warn!("No child handle available for process {} (PID: {}), attempting system kill", run_id, pid);
-                false // Process handle not available, try fallback
             }
-        };
-
-        // If direct kill didn't work, try system command as fallback
-        if !kill_sent {
-            info!("Attempting fallback kill for process {} (PID: {})", run_id, pid);
-            match self.kill_process_by_pid(run_id, pid) {
-                Ok(true) => return Ok(true),
-                Ok(false) => warn!("Fallback kill also failed for process {} (PID: {})", run_id, pid),
-                Err(e) => error!("Error during fallback kill: {}", e),
-            }
-            // Continue with the rest of the cleanup even if fallback failed
         }
 
-        // Wait for the process to exit (with timeout)
-        let wait_result = tokio::time::timeout(tokio::time::Duration::from_secs(5), async {
-            loop {
-                // Check if process has exited
-                let status = {
-                    let mut child_guard = child_arc.lock().map_err(|e| e.to_string())?;
-                    if let Some(child) = child_guard.as_mut() {
-                        match child.try_wait() {
-                            Ok(Some(status)) => {
-                                info!("Process {} exited with status: {:?}", run_id, status);
-                                *child_guard = None; // Clear the child handle
-                                Some(Ok::<(), String>(()))
-                            }
-                            Ok(None) => {
-                                // Still running
-                                None
-                            }
-                            Err(e) => {
-                                error!("Error checking process status: {}", e);
-                                Some(Err(e.to_string()))
-                            }
-                        }
-                    } else {
-                        // Process already gone
-                        Some(Ok(()))
-                    }
-                };
-
-                match status {
-                    Some(result) => return result,
-                    None => {
-                        // Still running, wait a bit
-                        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-                    }
-                }
-            }
-        })
-        .await;
-
-        match wait_result {
-            Ok(Ok(_)) => {
-                info!("Process {} exited gracefully", run_id);
-            }
-            Ok(Err(e)) => {
-                error!("Error waiting for process {}: {}", run_id, e);
-            }
-            Err(_) => {
-                warn!("Process {} didn't exit within 5 seconds after kill", run_id);
-                // Force clear the handle
-                if let Ok(mut child_guard) = child_arc.lock() {
-                    *child_guard = None;
-                }
-                // One more attempt with system kill
-                let _ = self.kill_process_by_pid(run_id, pid);
+        match result {
+            Ok(termination) => info!("Process {} stopped: {:?}", run_id, termination),
+            Err(e) => {
+                error!("Failed to terminate process {}: {}", run_id, e);
+                return Ok(false);
             }
         }
 
@@ -320,74 +274,20 @@ impl ProcessRegistry {
 
     /// Kill a process by PID using system commands (fallback method)
     pub fn kill_process_by_pid(&self, run_id: i64, pid: u32) -> Result<bool, String> {
-        use log::{error, info, warn};
+        use log::{info, warn};
 
         info!("Attempting to kill process {} by PID {}", run_id, pid);
 
-        let kill_result = if cfg!(target_os = "windows") {
-            std::process::Command::new("taskkill")
-                .args(["/F", "/PID", &pid.to_string()])
-                .output()
-        } else {
-            // First try SIGTERM
-            let term_result = std::process::Command::new("kill")
-                .args(["-TERM", &pid.to_string()])
-                .output();
-
-            match &term_result {
-                Ok(output) if output.status.success() => {
-                    info!("Sent SIGTERM to PID {}", pid);
-                    // Give it 2 seconds to exit gracefully
-                    std::thread::sleep(std::time::Duration::from_secs(2));
-
-                    // Check if still running
-                    let check_result = std::process::Command::new("kill")
-                        .args(["-0", &pid.to_string()])
-                        .output();
-
-                    if let Ok(output) = check_result {
-                        if output.status.success() {
-                            // Still running, send SIGKILL
-                            warn!(
-                                "Process {} still running after SIGTERM, sending SIGKILL",
-                                pid
-                            );
-                            std::process::Command::new("kill")
-                                .args(["-KILL", &pid.to_string()])
-                                .output()
-                        } else {
-                            term_result
-                        }
-                    } else {
-                        term_result
-                    }
-                }
-                _ => {
-                    // SIGTERM failed, try SIGKILL directly
-                    warn!("SIGTERM failed for PID {}, trying SIGKILL", pid);
-                    std::process::Command::new("kill")
-                        .args(["-KILL", &pid.to_string()])
-                        .output()
-                }
-            }
-        };
-
-        match kill_result {
-            Ok(output) => {
-                if output.status.success() {
-                    info!("Successfully killed process with PID {}", pid);
-                    // Remove from registry
-                    self.unregister_process(run_id)?;
-                    Ok(true)
-                } else {
-                    let error_msg = String::from_utf8_lossy(&output.stderr);
-                    warn!("Failed to kill PID {}: {}", pid, error_msg);
-                    Ok(false)
-                }
+        match super::terminate::terminate_blocking(pid, super::terminate::grace_period()) {
+            Ok(termination) => {
+                info!("Process with PID {} stopped: {:?}", pid, termination);
+                // Remove from registry
+                self.unregister_process(run_id)?;
+                Ok(true)
             }
             Err(e) => {
-                error!("Failed to execute kill command for PID {}: {}", pid, e);
-                Err(format!("Failed to execute kill command: {}", e))
+                warn!("Failed to kill PID {}: {}", pid, e);
+                Ok(false)
             }
         }
     }
@@ -0,0 +1,203 @@
+use log::{debug, info, warn};
+use std::collections::HashMap;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// Longest time a slow shell profile may take before its environment is ignored
+const LOAD_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Separates the environment from anything the shell profile prints
+const MARKER: &str = "__CLAUDIA_SHELL_ENV__";
+
+/// Variables that belong to the shell session rather than the user's setup
+const IGNORED_VARS: &[&str] = &["_", "PWD", "OLDPWD", "SHLVL", "TERM", "TERM_PROGRAM", "PS1"];
+
+/// Split `env -0` output framed by markers into variables
+fn parse_env_output(output: &[u8]) -> HashMap<String, String> {
+    let output = String::from_utf8_lossy(output);
+    let Some(body) = output
+        .split_once(MARKER)
+        .and_then(|(_, rest)| rest.rsplit_once(MARKER))
+        .map(|(body, _)| body)
+    else {
+        return HashMap::new();
+    };
+    body.split('\0')
+        .filter_map(|entry| entry.split_once('='))
+        .filter(|(key, _)| {
+            !key.is_empty()
+                && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+                && !IGNORED_VARS.contains(key)
+        })
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+/// The login PATH followed by any current entries it does not already contain
+fn merge_path(current: &str, login: &str) -> String {
+    let mut entries: Vec<&str> = login.split(':').filter(|e| !e.is_empty()).collect();
+    for entry in current.split(':').filter(|e| !e.is_empty()) {
+        if !entries.contains(&entry) {
+            entries.push(entry);
+        }
+    }
+    entries.join(":")
+}
+
+/// Whether PATH already carries what shell profiles add: a directory in the user's home,
+/// e.g. `~/.cargo/bin` or an nvm node, or Homebrew's
+fn looks_like_login_path(path: &str, home: Option<&Path>) -> bool {
+    path.split(':').filter(|e| !e.is_empty()).any(|entry| {
+        entry.starts_with("/opt/homebrew/")
+            || home.is_some_and(|home| Path::new(entry).starts_with(home))
+    })
+}
+
+fn user_shell() -> Option<PathBuf> {
+    std::env::var_os("SHELL")
+        .map(PathBuf::from)
+        .filter(|shell| shell.is_file())
+        .or_else(|| {
+            ["/bin/zsh", "/bin/bash"]
+                .iter()
+                .map(PathBuf::from)
+                .find(|shell| shell.is_file())
+        })
+}
+
+/// Run the user's shell as an interactive login shell and capture its environment
+fn load_login_shell_env() -> Result<HashMap<String, String>, String> {
+    let shell = user_shell().ok_or("No login shell found")?;
+    let script = format!(
+        "printf '%s' {m}; command env -0; printf '%s' {m}",
+        m = MARKER
+    );
+    let mut child = Command::new(&shell)
+        .args(["-l", "-i", "-c", &script])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Failed to start {}: {}", shell.display(), e))?;
+
+    // Read on another thread so a large environment cannot fill the pipe and stall the shell
+    let mut stdout = child
+        .stdout
+        .take()
+        .ok_or("Failed to capture shell output")?;
+    let (sender, receiver) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let mut output = Vec::new();
+        let _ = std::io::Read::read_to_end(&mut stdout, &mut output);
+        let _ = sender.send(output);
+    });
+
+    let deadline = Instant::now() + LOAD_TIMEOUT;
+    loop {
+        match child.try_wait() {
+            Ok(Some(_)) => break,
+            Ok(None) if Instant::now() < deadline => std::thread::sleep(Duration::from_millis(50)),
+            Ok(None) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!(
+                    "{} did not finish within {} s",
+                    shell.display(),
+                    LOAD_TIMEOUT.as_secs()
+                ));
+            }
+            Err(e) => return Err(format!("Failed to wait for shell: {}", e)),
+        }
+    }
+    // A daemon started by the profile can keep the pipe open after the shell exits
+    let output = receiver
+        .recv_timeout(
+            deadline
+                .saturating_duration_since(Instant::now())
+                .max(Duration::from_secs(1)),
+        )
+        .map_err(|_| "Shell output did not end after the shell exited")?;
+    let env = parse_env_output(&output);
+    if env.is_empty() {
+        return Err(format!("{} printed no environment", shell.display()));
+    }
+    Ok(env)
+}
+
+/// Adopt the login shell environment so every spawned process finds the user's tools
+///
+/// Apps launched from Finder or a desktop launcher only get a minimal PATH, without the
+/// directories nvm, Homebrew and similar tools add in shell profiles. PATH is merged and
+/// other variables are only added when missing, so the launch environment still wins.
+/// Must run at the start of `main`, before any other thread reads the environment.
+///
+/// Starting a login shell can take seconds, so it is skipped when Claudia was started
+/// from a terminal or PATH already shows the profile was applied.
+pub fn apply_login_shell_env() {
+    if cfg!(windows) || std::env::var_os("CLAUDIA_SKIP_SHELL_ENV").is_some() {
+        return;
+    }
+    if std::io::stdin().is_terminal() {
+        debug!("Started from a terminal, keeping its environment");
+        return;
+    }
+    let path = std::env::var("PATH").unwrap_or_default();
+    if looks_like_login_path(&path, dirs::home_dir().as_deref()) {
+        debug!("PATH already includes the login shell's directories");
+        return;
+    }
+    let env = match load_login_shell_env() {
+        Ok(env) => env,
+        Err(e) => {
+            warn!("Using the launch environment, login shell failed: {}", e);
+            return;
+        }
+    };
+
+    let mut added = 0;
+    for (key, value) in env {
+        if key == "PATH" {
+            let current = std::env::var("PATH").unwrap_or_default();
+            std::env::set_var("PATH", merge_path(&current, &value));
+        } else if std::env::var_os(&key).is_none() {
+            debug!("Adding {} from the login shell", key);
+            std::env::set_var(&key, value);
+            added += 1;
+        }
+    }
+    info!("Loaded login shell environment ({} new variables)", added);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_framed_env_and_merges_path() {
+        let output = format!(
+            "Welcome!\n{m}PATH=/opt/homebrew/bin:/usr/bin\0NVM_DIR=/Users/me/.nvm\0MULTI=a\nb\0PWD=/\0{m}bye",
+            m = MARKER
+        );
+        let env = parse_env_output(output.as_bytes());
+        assert_eq!(env.len(), 3);
+        assert_eq!(env["NVM_DIR"], "/Users/me/.nvm");
+        assert_eq!(env["MULTI"], "a\nb");
+        assert!(parse_env_output(b"no markers").is_empty());
+
+        assert_eq!(
+            merge_path("/usr/bin:/bin", &env["PATH"]),
+            "/opt/homebrew/bin:/usr/bin:/bin"
+        );
+    }
+
+    #[test]
+    fn recognizes_a_login_path() {
+        let home = Path::new("/Users/me");
+        assert!(!looks_like_login_path("/usr/bin:/bin:/usr/sbin:/sbin", Some(home)));
+        assert!(looks_like_login_path("/Users/me/.cargo/bin:/usr/bin", Some(home)));
+        assert!(looks_like_login_path("/opt/homebrew/bin:/usr/bin", None));
+        assert!(!looks_like_login_path("/Users/meg/bin:/usr/bin", Some(home)));
+    }
+}
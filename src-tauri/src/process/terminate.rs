@@ -0,0 +1,139 @@
+use log::{debug, info, warn};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use super::tree::ProcessTree;
+
+/// Default time Claude gets to flush its session JSONL before it is killed
+pub const DEFAULT_GRACE_PERIOD_MS: u64 = 5000;
+
+/// How often a stopping process is checked for exit
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+static GRACE_PERIOD_MS: AtomicU64 = AtomicU64::new(DEFAULT_GRACE_PERIOD_MS);
+
+/// How a terminated process ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Termination {
+    /// The process was gone before any signal was sent
+    AlreadyExited,
+    /// The process exited on its own after the interrupt
+    Graceful,
+    /// The process ignored the interrupt and was killed
+    Forced,
+}
+
+/// The configured grace period between the interrupt and the kill
+pub fn grace_period() -> Duration {
+    Duration::from_millis(GRACE_PERIOD_MS.load(Ordering::Relaxed))
+}
+
+pub fn set_grace_period(grace: Duration) {
+    GRACE_PERIOD_MS.store(grace.as_millis() as u64, Ordering::Relaxed);
+}
+
+/// Whether a process with this PID is still running
+#[cfg(unix)]
+pub fn is_alive(pid: u32) -> bool {
+    // Signal 0 only checks for existence; EPERM means it exists under another user
+    let result = unsafe { libc::kill(pid as libc::pid_t, 0) };
+    let exists = result == 0 || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM);
+    exists && !is_zombie(pid)
+}
+
+/// An exited child that was not reaped yet still answers signal 0
+#[cfg(unix)]
+fn is_zombie(pid: u32) -> bool {
+    std::fs::read_to_string(format!("/proc/{}/stat", pid))
+        .ok()
+        .and_then(|stat| {
+            stat.rsplit_once(')')
+                .map(|(_, rest)| rest.trim_start().starts_with('Z'))
+        })
+        .unwrap_or(false)
+}
+
+#[cfg(windows)]
+pub fn is_alive(pid: u32) -> bool {
+    std::process::Command::new("tasklist")
+        .args(["/FI", &format!("PID eq {}", pid), "/NH"])
+        .output()
+        .map(|output| String::from_utf8_lossy(&output.stdout).contains(&pid.to_string()))
+        .unwrap_or(false)
+}
+
+fn wait_for_exit(pid: u32, timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    while Instant::now() < deadline {
+        if !is_alive(pid) {
+            return true;
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+    !is_alive(pid)
+}
+
+/// Interrupt a process and what it started, wait up to `grace` for it to exit, then kill it
+///
+/// Blocks the calling thread; use [`terminate`] from async code.
+pub fn terminate_blocking(pid: u32, grace: Duration) -> Result<Termination, String> {
+    if pid == 0 || !is_alive(pid) {
+        return Ok(Termination::AlreadyExited);
+    }
+
+    // Resolved before the signal, while the process still leads its group
+    let tree = ProcessTree::of(pid);
+    info!(
+        "Interrupting process {} ({} ms grace period)",
+        pid,
+        grace.as_millis()
+    );
+    if tree.interrupt() && wait_for_exit(pid, grace) {
+        info!("Process {} exited after the interrupt", pid);
+        tree.kill_remaining();
+        return Ok(Termination::Graceful);
+    }
+
+    warn!("Process {} did not exit in time, killing it", pid);
+    if !tree.kill() && is_alive(pid) {
+        return Err(format!("Failed to kill process {}", pid));
+    }
+    // A killed process can linger briefly until the kernel reaps it
+    if !wait_for_exit(pid, Duration::from_secs(2)) {
+        debug!("Process {} is still listed after the kill", pid);
+    }
+    Ok(Termination::Forced)
+}
+
+/// Interrupt a process, wait up to `grace` for it to exit, then kill it
+pub async fn terminate(pid: u32, grace: Duration) -> Result<Termination, String> {
+    tokio::task::spawn_blocking(move || terminate_blocking(pid, grace))
+        .await
+        .map_err(|e| format!("Termination task failed: {}", e))?
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interrupt_stops_a_cooperative_process() {
+        let mut child = std::process::Command::new("sleep")
+            .arg("30")
+            .spawn()
+            .unwrap();
+        let pid = child.id();
+        // Reap the child in the background so it does not linger as a zombie
+        let waiter = std::thread::spawn(move || child.wait());
+
+        assert_eq!(
+            terminate_blocking(pid, Duration::from_secs(5)).unwrap(),
+            Termination::Graceful
+        );
+        waiter.join().unwrap().unwrap();
+        assert_eq!(
+            terminate_blocking(pid, Duration::from_secs(1)).unwrap(),
+            Termination::AlreadyExited
+        );
+    }
+}
@@ -0,0 +1,212 @@
+//! Tear down everything a spawned process started, not just the process itself
+//!
+//! Claude runs tools through node, shells and build commands that outlive it when only
+//! its PID is killed. On Unix spawned processes lead their own process group, which
+//! signals are sent to; on Windows they are assigned to a Job Object.
+
+/// Make the command start in its own process group so its children can be signalled together
+pub fn isolate(cmd: &mut tokio::process::Command) {
+    #[cfg(unix)]
+    cmd.process_group(0);
+    #[cfg(not(unix))]
+    let _ = cmd;
+}
+
+/// Track the tree of a process that was just spawned
+///
+/// A no-op on Unix, where [`isolate`] already did the work; on Windows the process is
+/// put in a Job Object that is killed as a whole.
+pub fn adopt(pid: u32) {
+    #[cfg(windows)]
+    windows_jobs::assign(pid);
+    #[cfg(not(windows))]
+    let _ = pid;
+}
+
+/// The processes started by one spawned process
+pub(crate) struct ProcessTree {
+    pid: u32,
+    /// Whether the process leads its own group, so the whole group can be signalled
+    #[cfg(unix)]
+    group: bool,
+}
+
+#[cfg(unix)]
+impl ProcessTree {
+    pub(crate) fn of(pid: u32) -> Self {
+        let group = unsafe { libc::getpgid(pid as libc::pid_t) } == pid as libc::pid_t;
+        Self { pid, group }
+    }
+
+    fn signal(&self, signal: libc::c_int) -> bool {
+        let target = if self.group {
+            -(self.pid as libc::pid_t)
+        } else {
+            self.pid as libc::pid_t
+        };
+        unsafe { libc::kill(target, signal) == 0 }
+    }
+
+    /// SIGINT lets Claude finish writing its session file
+    pub(crate) fn interrupt(&self) -> bool {
+        self.signal(libc::SIGINT)
+    }
+
+    pub(crate) fn kill(&self) -> bool {
+        self.signal(libc::SIGKILL)
+    }
+
+    /// Kill processes of the group that are still running after the root exited
+    pub(crate) fn kill_remaining(&self) {
+        if self.group {
+            self.signal(libc::SIGKILL);
+        }
+    }
+}
+
+#[cfg(windows)]
+impl ProcessTree {
+    pub(crate) fn of(pid: u32) -> Self {
+        Self { pid }
+    }
+
+    /// Without /F taskkill asks the process tree to close
+    pub(crate) fn interrupt(&self) -> bool {
+        std::process::Command::new("taskkill")
+            .args(["/T", "/PID", &self.pid.to_string()])
+            .output()
+            .map(|output| output.status.success())
+            .unwrap_or(false)
+    }
+
+    pub(crate) fn kill(&self) -> bool {
+        if windows_jobs::terminate(self.pid) {
+            return true;
+        }
+        // taskkill /F uses TerminateProcess on every process it finds in the tree
+        std::process::Command::new("taskkill")
+            .args(["/F", "/T", "/PID", &self.pid.to_string()])
+            .output()
+            .map(|output| output.status.success())
+            .unwrap_or(false)
+    }
+
+    pub(crate) fn kill_remaining(&self) {
+        windows_jobs::terminate(self.pid);
+    }
+}
+
+#[cfg(windows)]
+mod windows_jobs {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+    use windows_sys::Win32::Foundation::{CloseHandle, HANDLE};
+    use windows_sys::Win32::System::JobObjects::{
+        AssignProcessToJobObject, CreateJobObjectW, JobObjectExtendedLimitInformation,
+        SetInformationJobObject, TerminateJobObject, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+        JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+    };
+    use windows_sys::Win32::System::Threading::{
+        OpenProcess, PROCESS_SET_QUOTA, PROCESS_TERMINATE,
+    };
+
+    /// Job handles by the PID of the process they were created for
+    static JOBS: Mutex<Option<HashMap<u32, usize>>> = Mutex::new(None);
+
+    pub(super) fn assign(pid: u32) {
+        // Close the jobs of processes that exited in the meantime
+        if let Ok(mut jobs) = JOBS.lock() {
+            let jobs = jobs.get_or_insert_with(HashMap::new);
+            jobs.retain(|pid, job| {
+                let alive = super::super::terminate::is_alive(*pid);
+                if !alive {
+                    unsafe { CloseHandle(*job as HANDLE) };
+                }
+                alive
+            });
+        }
+
+        unsafe {
+            let job = CreateJobObjectW(std::ptr::null(), std::ptr::null());
+            if job.is_null() {
+                log::warn!("Failed to create a job object for process {}", pid);
+                return;
+            }
+            // Closing the last handle, including when Claudia exits, kills the whole tree
+            let mut limits: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = std::mem::zeroed();
+            limits.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+            SetInformationJobObject(
+                job,
+                JobObjectExtendedLimitInformation,
+                &limits as *const _ as *const core::ffi::c_void,
+                std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+            );
+
+            let process = OpenProcess(PROCESS_SET_QUOTA | PROCESS_TERMINATE, 0, pid);
+            let assigned = !process.is_null() && AssignProcessToJobObject(job, process) != 0;
+            if !process.is_null() {
+                CloseHandle(process);
+            }
+            if !assigned {
+                log::warn!("Failed to assign process {} to a job object", pid);
+                CloseHandle(job);
+                return;
+            }
+            if let Ok(mut jobs) = JOBS.lock() {
+                jobs.get_or_insert_with(HashMap::new)
+                    .insert(pid, job as usize);
+            }
+        }
+    }
+
+    /// Kill every process in the job of `pid`; false when it has no job
+    pub(super) fn terminate(pid: u32) -> bool {
+        let job = JOBS
+            .lock()
+            .ok()
+            .and_then(|mut jobs| jobs.as_mut()?.remove(&pid));
+        let Some(job) = job else {
+            return false;
+        };
+        unsafe {
+            let terminated = TerminateJobObject(job as HANDLE, 1) != 0;
+            CloseHandle(job as HANDLE);
+            terminated
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::super::terminate::{is_alive, terminate_blocking};
+    use std::io::BufRead;
+    use std::os::unix::process::CommandExt;
+    use std::time::Duration;
+
+    #[test]
+    fn terminating_the_root_stops_its_children() {
+        // Background jobs of a non-interactive shell ignore SIGINT, so the sleep outlives sh
+        let mut child = std::process::Command::new("sh")
+            .args(["-c", "sleep 30 & echo $!; wait"])
+            .stdout(std::process::Stdio::piped())
+            .process_group(0)
+            .spawn()
+            .unwrap();
+        let mut line = String::new();
+        std::io::BufReader::new(child.stdout.take().unwrap())
+            .read_line(&mut line)
+            .unwrap();
+        let grandchild: u32 = line.trim().parse().unwrap();
+        let pid = child.id();
+        let waiter = std::thread::spawn(move || child.wait());
+
+        assert!(is_alive(grandchild));
+        terminate_blocking(pid, Duration::from_secs(5)).unwrap();
+        waiter.join().unwrap().unwrap();
+        let deadline = std::time::Instant::now() + Duration::from_secs(2);
+        while is_alive(grandchild) && std::time::Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        assert!(!is_alive(grandchild));
+    }
+}
@@ -59,6 +59,13 @@ function App() {
   const [projectForSettings, setProjectForSettings] = useState<Project | null>(null);
   const [previousView, setPreviousView] = useState<View>("welcome");
 
+  // Make sure the backend speaks the command API these bindings were generated for
+  useEffect(() => {
+    api.apiHandshake().catch((err) => {
+      setToast({ message: `${err}`, type: "error" });
+    });
+  }, []);
+
   // Load projects on mount when in projects view
   useEffect(() => {
     if (view === "projects") {
@@ -1,36 +1,56 @@
-import { invoke } from "@tauri-apps/api/core";
 import type { HooksConfiguration } from '@/types/hooks';
+import {
+  commands,
+  COMMAND_API_VERSION,
+  type ApiVersion,
+  type CheckpointMetadata,
+  type ClaudeMdFile,
+  type DailyUsage,
+  type GitHubAgentFile,
+  type JsonValue,
+  type ProcessInfo,
+  type ProcessType,
+  type Project,
+  type ProjectUsage,
+  type Result,
+} from "./bindings";
+
+// Generated from the Rust types; the hand-written types below are views the UI still uses
+export type {
+  ApiVersion,
+  CheckpointMetadata,
+  ClaudeMdFile,
+  DailyUsage,
+  GitHubAgentFile,
+  ProcessInfo,
+  ProcessType,
+  Project,
+  ProjectUsage,
+};
 
-/** Process type for tracking in ProcessRegistry */
-export type ProcessType = 
-  | { AgentRun: { agent_id: number; agent_name: string } }
-  | { ClaudeSession: { session_id: string } };
-
-/** Information about a running process */
-export interface ProcessInfo {
-  run_id: number;
-  process_type: ProcessType;
-  pid: number;
-  started_at: string;
-  project_path: string;
-  task: string;
-  model: string;
+/**
+ * Unwraps a command result, rejecting with the backend's error message like `invoke` does
+ */
+async function unwrap<T>(result: Promise<Result<T, string>>): Promise<T> {
+  const response = await result;
+  if (response.status === "error") {
+    throw response.error;
+  }
+  return response.data;
 }
 
 /**
- * Represents a project in the ~/.claude/projects directory
+ * Unwraps a command result into one of the hand-written view types
  */
-export interface Project {
-  /** The project ID (derived from the directory name) */
-  id: string;
-  /** The original project path (decoded from the directory name) */
-  path: string;
-  /** List of session IDs (JSONL file names without extension) */
-  sessions: string[];
-  /** Unix timestamp when the project directory was created */
-  created_at: number;
-  /** Whether the project directory is a git repository */
-  is_git_repo?: boolean;
+async function unwrapAs<T>(result: Promise<Result<unknown, string>>): Promise<T> {
+  return (await unwrap(result)) as T;
+}
+
+/**
+ * Passes a typed object to a command that takes arbitrary JSON
+ */
+function asJson(value: object): JsonValue {
+  return value as unknown as JsonValue;
 }
 
 /**
@@ -72,19 +92,6 @@ export interface ClaudeVersionStatus {
   output: string;
 }
 
-/**
- * Represents a CLAUDE.md file found in the project
- */
-export interface ClaudeMdFile {
-  /** Relative path from the project root */
-  relative_path: string;
-  /** Absolute path to the file */
-  absolute_path: string;
-  /** File size in bytes */
-  size: number;
-  /** Last modified timestamp */
-  modified: number;
-}
 
 /**
  * Represents a file or directory entry
@@ -137,13 +144,6 @@ export interface AgentExport {
   };
 }
 
-export interface GitHubAgentFile {
-  name: string;
-  path: string;
-  download_url: string;
-  size: number;
-  sha: string;
-}
 
 export interface AgentRun {
   id?: number;
@@ -209,22 +209,6 @@ export interface ModelUsage {
   session_count: number;
 }
 
-export interface DailyUsage {
-  date: string;
-  total_cost: number;
-  total_tokens: number;
-  models_used: string[];
-}
-
-export interface ProjectUsage {
-  project_path: string;
-  project_name: string;
-  total_cost: number;
-  total_tokens: number;
-  session_count: number;
-  last_used: string;
-}
-
 export interface UsageStats {
   total_cost: number;
   total_tokens: number;
@@ -252,16 +236,6 @@ export interface Checkpoint {
   metadata: CheckpointMetadata;
 }
 
-/**
- * Metadata associated with a checkpoint
- */
-export interface CheckpointMetadata {
-  totalTokens: number;
-  modelUsed: string;
-  userPrompt: string;
-  fileChanges: number;
-  snapshotSize: number;
-}
 
 /**
  * Represents a file snapshot at a checkpoint
@@ -349,6 +323,8 @@ export interface MCPServer {
   env: Record<string, string>;
   /** URL endpoint (for SSE) */
   url?: string;
+  /** Headers sent with every request, as "Name: value" (for SSE) */
+  headers?: string[];
   /** Configuration scope: "local", "project", or "user" */
   scope: string;
   /** Whether the server is currently active */
@@ -446,13 +422,26 @@ export interface ImportServerResult {
  * API client for interacting with the Rust backend
  */
 export const api = {
+  /**
+   * Checks that the backend serves the command API version the bindings were generated for
+   * @returns Promise resolving to the backend's API version
+   */
+  async apiHandshake(): Promise<ApiVersion> {
+    try {
+      return await unwrap(commands.apiHandshake(COMMAND_API_VERSION));
+    } catch (error) {
+      console.error("Command API handshake failed:", error);
+      throw error;
+    }
+  },
+
   /**
    * Lists all projects in the ~/.claude/projects directory
    * @returns Promise resolving to an array of projects
    */
   async listProjects(): Promise<Project[]> {
     try {
-      return await invoke<Project[]>("list_projects");
+      return await unwrap(commands.listProjects());
     } catch (error) {
       console.error("Failed to list projects:", error);
       throw error;
@@ -466,7 +455,7 @@ export const api = {
    */
   async getProjectSessions(projectId: string): Promise<Session[]> {
     try {
-      return await invoke<Session[]>('get_project_sessions', { projectId });
+      return await unwrapAs<Session[]>(commands.getProjectSessions(projectId));
     } catch (error) {
       console.error("Failed to get project sessions:", error);
       throw error;
@@ -479,7 +468,7 @@ export const api = {
    */
   async fetchGitHubAgents(): Promise<GitHubAgentFile[]> {
     try {
-      return await invoke<GitHubAgentFile[]>('fetch_github_agents');
+      return await unwrap(commands.fetchGithubAgents(null));
     } catch (error) {
       console.error("Failed to fetch GitHub agents:", error);
       throw error;
@@ -493,7 +482,7 @@ export const api = {
    */
   async fetchGitHubAgentContent(downloadUrl: string): Promise<AgentExport> {
     try {
-      return await invoke<AgentExport>('fetch_github_agent_content', { downloadUrl });
+      return await unwrapAs<AgentExport>(commands.fetchGithubAgentContent(downloadUrl));
     } catch (error) {
       console.error("Failed to fetch GitHub agent content:", error);
       throw error;
@@ -507,7 +496,7 @@ export const api = {
    */
   async importAgentFromGitHub(downloadUrl: string): Promise<Agent> {
     try {
-      return await invoke<Agent>('import_agent_from_github', { downloadUrl });
+      return await unwrapAs<Agent>(commands.importAgentFromGithub(downloadUrl));
     } catch (error) {
       console.error("Failed to import agent from GitHub:", error);
       throw error;
@@ -520,7 +509,7 @@ export const api = {
    */
   async getClaudeSettings(): Promise<ClaudeSettings> {
     try {
-      const result = await invoke<{ data: ClaudeSettings }>("get_claude_settings");
+      const result = await unwrapAs<{ data: ClaudeSettings }>(commands.getClaudeSettings());
       console.log("Raw result from get_claude_settings:", result);
       
       // The Rust backend returns ClaudeSettings { data: ... }
@@ -544,7 +533,7 @@ export const api = {
    */
   async openNewSession(path?: string): Promise<string> {
     try {
-      return await invoke<string>("open_new_session", { path });
+      return await unwrap(commands.openNewSession(path ?? null));
     } catch (error) {
       console.error("Failed to open new session:", error);
       throw error;
@@ -557,7 +546,7 @@ export const api = {
    */
   async getSystemPrompt(): Promise<string> {
     try {
-      return await invoke<string>("get_system_prompt");
+      return await unwrap(commands.getSystemPrompt());
     } catch (error) {
       console.error("Failed to get system prompt:", error);
       throw error;
@@ -570,7 +559,7 @@ export const api = {
    */
   async checkClaudeVersion(): Promise<ClaudeVersionStatus> {
     try {
-      return await invoke<ClaudeVersionStatus>("check_claude_version");
+      return await unwrapAs<ClaudeVersionStatus>(commands.checkClaudeVersion());
     } catch (error) {
       console.error("Failed to check Claude version:", error);
       throw error;
@@ -584,7 +573,7 @@ export const api = {
    */
   async saveSystemPrompt(content: string): Promise<string> {
     try {
-      return await invoke<string>("save_system_prompt", { content });
+      return await unwrap(commands.saveSystemPrompt(content));
     } catch (error) {
       console.error("Failed to save system prompt:", error);
       throw error;
@@ -598,7 +587,7 @@ export const api = {
    */
   async saveClaudeSettings(settings: ClaudeSettings): Promise<string> {
     try {
-      return await invoke<string>("save_claude_settings", { settings });
+      return await unwrap(commands.saveClaudeSettings(asJson(settings)));
     } catch (error) {
       console.error("Failed to save Claude settings:", error);
       throw error;
@@ -612,7 +601,7 @@ export const api = {
    */
   async findClaudeMdFiles(projectPath: string): Promise<ClaudeMdFile[]> {
     try {
-      return await invoke<ClaudeMdFile[]>("find_claude_md_files", { projectPath });
+      return await unwrap(commands.findClaudeMdFiles(projectPath));
     } catch (error) {
       console.error("Failed to find CLAUDE.md files:", error);
       throw error;
@@ -626,7 +615,7 @@ export const api = {
    */
   async readClaudeMdFile(filePath: string): Promise<string> {
     try {
-      return await invoke<string>("read_claude_md_file", { filePath });
+      return await unwrap(commands.readClaudeMdFile(filePath));
     } catch (error) {
       console.error("Failed to read CLAUDE.md file:", error);
       throw error;
@@ -641,7 +630,7 @@ export const api = {
    */
   async saveClaudeMdFile(filePath: string, content: string): Promise<string> {
     try {
-      return await invoke<string>("save_claude_md_file", { filePath, content });
+      return await unwrap(commands.saveClaudeMdFile(filePath, content));
     } catch (error) {
       console.error("Failed to save CLAUDE.md file:", error);
       throw error;
@@ -656,7 +645,7 @@ export const api = {
    */
   async listAgents(): Promise<Agent[]> {
     try {
-      return await invoke<Agent[]>('list_agents');
+      return await unwrapAs<Agent[]>(commands.listAgents());
     } catch (error) {
       console.error("Failed to list agents:", error);
       throw error;
@@ -682,14 +671,17 @@ export const api = {
     hooks?: string
   ): Promise<Agent> {
     try {
-      return await invoke<Agent>('create_agent', { 
-        name, 
-        icon, 
-        systemPrompt: system_prompt,
-        defaultTask: default_task,
-        model,
-        hooks
-      });
+      return await unwrapAs<Agent>(commands.createAgent(
+        name,
+        icon,
+        system_prompt,
+        default_task ?? null,
+        model ?? null,
+        null,
+        null,
+        null,
+        hooks ?? null
+      ));
     } catch (error) {
       console.error("Failed to create agent:", error);
       throw error;
@@ -717,15 +709,16 @@ export const api = {
     hooks?: string
   ): Promise<Agent> {
     try {
-      return await invoke<Agent>('update_agent', { 
-        id, 
-        name, 
-        icon, 
-        systemPrompt: system_prompt,
-        defaultTask: default_task,
-        model,
-        hooks
-      });
+      return await unwrapAs<Agent>(commands.updateAgent(
+        id,
+        name,
+        icon,
+        system_prompt,
+        default_task ?? null,
+        model ?? null,
+        null,
+        hooks ?? null
+      ));
     } catch (error) {
       console.error("Failed to update agent:", error);
       throw error;
@@ -739,7 +732,7 @@ export const api = {
    */
   async deleteAgent(id: number): Promise<void> {
     try {
-      return await invoke('delete_agent', { id });
+      await unwrap(commands.deleteAgent(id));
     } catch (error) {
       console.error("Failed to delete agent:", error);
       throw error;
@@ -753,7 +746,7 @@ export const api = {
    */
   async getAgent(id: number): Promise<Agent> {
     try {
-      return await invoke<Agent>('get_agent', { id });
+      return await unwrapAs<Agent>(commands.getAgent(id));
     } catch (error) {
       console.error("Failed to get agent:", error);
       throw error;
@@ -767,7 +760,7 @@ export const api = {
    */
   async exportAgent(id: number): Promise<string> {
     try {
-      return await invoke<string>('export_agent', { id });
+      return await unwrap(commands.exportAgent(id));
     } catch (error) {
       console.error("Failed to export agent:", error);
       throw error;
@@ -781,7 +774,7 @@ export const api = {
    */
   async importAgent(jsonData: string): Promise<Agent> {
     try {
-      return await invoke<Agent>('import_agent', { jsonData });
+      return await unwrapAs<Agent>(commands.importAgent(jsonData, null));
     } catch (error) {
       console.error("Failed to import agent:", error);
       throw error;
@@ -795,7 +788,7 @@ export const api = {
    */
   async importAgentFromFile(filePath: string): Promise<Agent> {
     try {
-      return await invoke<Agent>('import_agent_from_file', { filePath });
+      return await unwrapAs<Agent>(commands.importAgentFromFile(filePath, null));
     } catch (error) {
       console.error("Failed to import agent from file:", error);
       throw error;
@@ -812,7 +805,7 @@ export const api = {
    */
   async executeAgent(agentId: number, projectPath: string, task: string, model?: string): Promise<number> {
     try {
-      return await invoke<number>('execute_agent', { agentId, projectPath, task, model });
+      return await unwrap(commands.executeAgent(agentId, projectPath, task, model ?? null, null, null));
     } catch (error) {
       console.error("Failed to execute agent:", error);
       // Return a sentinel value to indicate error
@@ -827,7 +820,7 @@ export const api = {
    */
   async listAgentRuns(agentId?: number): Promise<AgentRunWithMetrics[]> {
     try {
-      return await invoke<AgentRunWithMetrics[]>('list_agent_runs', { agentId });
+      return await unwrapAs<AgentRunWithMetrics[]>(commands.listAgentRuns(agentId ?? null));
     } catch (error) {
       console.error("Failed to list agent runs:", error);
       // Return empty array instead of throwing to prevent UI crashes
@@ -842,7 +835,7 @@ export const api = {
    */
   async getAgentRun(id: number): Promise<AgentRunWithMetrics> {
     try {
-      return await invoke<AgentRunWithMetrics>('get_agent_run', { id });
+      return await unwrapAs<AgentRunWithMetrics>(commands.getAgentRun(id));
     } catch (error) {
       console.error("Failed to get agent run:", error);
       throw new Error(`Failed to get agent run: ${error instanceof Error ? error.message : 'Unknown error'}`);
@@ -856,7 +849,7 @@ export const api = {
    */
   async getAgentRunWithRealTimeMetrics(id: number): Promise<AgentRunWithMetrics> {
     try {
-      return await invoke<AgentRunWithMetrics>('get_agent_run_with_real_time_metrics', { id });
+      return await unwrapAs<AgentRunWithMetrics>(commands.getAgentRunWithRealTimeMetrics(id));
     } catch (error) {
       console.error("Failed to get agent run with real-time metrics:", error);
       throw new Error(`Failed to get agent run with real-time metrics: ${error instanceof Error ? error.message : 'Unknown error'}`);
@@ -869,7 +862,7 @@ export const api = {
    */
   async listRunningAgentSessions(): Promise<AgentRun[]> {
     try {
-      return await invoke<AgentRun[]>('list_running_sessions');
+      return await unwrapAs<AgentRun[]>(commands.listRunningSessions());
     } catch (error) {
       console.error("Failed to list running agent sessions:", error);
       throw new Error(`Failed to list running agent sessions: ${error instanceof Error ? error.message : 'Unknown error'}`);
@@ -883,7 +876,7 @@ export const api = {
    */
   async killAgentSession(runId: number): Promise<boolean> {
     try {
-      return await invoke<boolean>('kill_agent_session', { runId });
+      return await unwrap(commands.killAgentSession(runId));
     } catch (error) {
       console.error("Failed to kill agent session:", error);
       throw new Error(`Failed to kill agent session: ${error instanceof Error ? error.message : 'Unknown error'}`);
@@ -897,7 +890,7 @@ export const api = {
    */
   async getSessionStatus(runId: number): Promise<string | null> {
     try {
-      return await invoke<string | null>('get_session_status', { runId });
+      return await unwrap(commands.getSessionStatus(runId));
     } catch (error) {
       console.error("Failed to get session status:", error);
       throw new Error(`Failed to get session status: ${error instanceof Error ? error.message : 'Unknown error'}`);
@@ -910,7 +903,7 @@ export const api = {
    */
   async cleanupFinishedProcesses(): Promise<number[]> {
     try {
-      return await invoke<number[]>('cleanup_finished_processes');
+      return await unwrap(commands.cleanupFinishedProcesses());
     } catch (error) {
       console.error("Failed to cleanup finished processes:", error);
       throw new Error(`Failed to cleanup finished processes: ${error instanceof Error ? error.message : 'Unknown error'}`);
@@ -924,7 +917,7 @@ export const api = {
    */
   async getSessionOutput(runId: number): Promise<string> {
     try {
-      return await invoke<string>('get_session_output', { runId });
+      return await unwrap(commands.getSessionOutput(runId));
     } catch (error) {
       console.error("Failed to get session output:", error);
       throw new Error(`Failed to get session output: ${error instanceof Error ? error.message : 'Unknown error'}`);
@@ -938,7 +931,7 @@ export const api = {
    */
   async getLiveSessionOutput(runId: number): Promise<string> {
     try {
-      return await invoke<string>('get_live_session_output', { runId });
+      return await unwrap(commands.getLiveSessionOutput(runId));
     } catch (error) {
       console.error("Failed to get live session output:", error);
       throw new Error(`Failed to get live session output: ${error instanceof Error ? error.message : 'Unknown error'}`);
@@ -952,7 +945,7 @@ export const api = {
    */
   async streamSessionOutput(runId: number): Promise<void> {
     try {
-      return await invoke<void>('stream_session_output', { runId });
+      await unwrap(commands.streamSessionOutput(runId));
     } catch (error) {
       console.error("Failed to start streaming session output:", error);
       throw new Error(`Failed to start streaming session output: ${error instanceof Error ? error.message : 'Unknown error'}`);
@@ -963,7 +956,7 @@ export const api = {
    * Loads the JSONL history for a specific session
    */
   async loadSessionHistory(sessionId: string, projectId: string): Promise<any[]> {
-    return invoke("load_session_history", { sessionId, projectId });
+    return unwrap(commands.loadSessionHistory(sessionId, projectId));
   },
 
   /**
@@ -974,7 +967,7 @@ export const api = {
    */
   async loadAgentSessionHistory(sessionId: string): Promise<any[]> {
     try {
-      return await invoke<any[]>('load_agent_session_history', { sessionId });
+      return await unwrap(commands.loadAgentSessionHistory(sessionId));
     } catch (error) {
       console.error("Failed to load agent session history:", error);
       throw error;
@@ -985,21 +978,21 @@ export const api = {
    * Executes a new interactive Claude Code session with streaming output
    */
   async executeClaudeCode(projectPath: string, prompt: string, model: string): Promise<void> {
-    return invoke("execute_claude_code", { projectPath, prompt, model });
+    await unwrap(commands.executeClaudeCode(projectPath, prompt, model, null, null, null));
   },
 
   /**
    * Continues an existing Claude Code conversation with streaming output
    */
   async continueClaudeCode(projectPath: string, prompt: string, model: string): Promise<void> {
-    return invoke("continue_claude_code", { projectPath, prompt, model });
+    await unwrap(commands.continueClaudeCode(projectPath, prompt, model, null, null, null));
   },
 
   /**
    * Resumes an existing Claude Code session by ID with streaming output
    */
   async resumeClaudeCode(projectPath: string, sessionId: string, prompt: string, model: string): Promise<void> {
-    return invoke("resume_claude_code", { projectPath, sessionId, prompt, model });
+    await unwrap(commands.resumeClaudeCode(projectPath, sessionId, prompt, model, null, null, null));
   },
 
   /**
@@ -1007,7 +1000,7 @@ export const api = {
    * @param sessionId - Optional session ID to cancel a specific session
    */
   async cancelClaudeExecution(sessionId?: string): Promise<void> {
-    return invoke("cancel_claude_execution", { sessionId });
+    await unwrap(commands.cancelClaudeExecution(sessionId ?? null));
   },
 
   /**
@@ -1015,7 +1008,7 @@ export const api = {
    * @returns Promise resolving to list of running Claude sessions
    */
   async listRunningClaudeSessions(): Promise<any[]> {
-    return invoke("list_running_claude_sessions");
+    return unwrap(commands.listRunningClaudeSessions());
   },
 
   /**
@@ -1024,21 +1017,21 @@ export const api = {
    * @returns Promise resolving to the current live output
    */
   async getClaudeSessionOutput(sessionId: string): Promise<string> {
-    return invoke("get_claude_session_output", { sessionId });
+    return unwrap(commands.getClaudeSessionOutput(sessionId));
   },
 
   /**
    * Lists files and directories in a given path
    */
   async listDirectoryContents(directoryPath: string): Promise<FileEntry[]> {
-    return invoke("list_directory_contents", { directoryPath });
+    return unwrapAs<FileEntry[]>(commands.listDirectoryContents(directoryPath));
   },
 
   /**
    * Searches for files and directories matching a pattern
    */
   async searchFiles(basePath: string, query: string): Promise<FileEntry[]> {
-    return invoke("search_files", { basePath, query });
+    return unwrapAs<FileEntry[]>(commands.searchFiles(basePath, query));
   },
 
   /**
@@ -1047,7 +1040,7 @@ export const api = {
    */
   async getUsageStats(): Promise<UsageStats> {
     try {
-      return await invoke<UsageStats>("get_usage_stats");
+      return await unwrap(commands.getUsageStats(null));
     } catch (error) {
       console.error("Failed to get usage stats:", error);
       throw error;
@@ -1062,7 +1055,7 @@ export const api = {
    */
   async getUsageByDateRange(startDate: string, endDate: string): Promise<UsageStats> {
     try {
-      return await invoke<UsageStats>("get_usage_by_date_range", { startDate, endDate });
+      return await unwrap(commands.getUsageByDateRange(startDate, endDate));
     } catch (error) {
       console.error("Failed to get usage by date range:", error);
       throw error;
@@ -1082,11 +1075,7 @@ export const api = {
     order?: "asc" | "desc"
   ): Promise<ProjectUsage[]> {
     try {
-      return await invoke<ProjectUsage[]>("get_session_stats", {
-        since,
-        until,
-        order,
-      });
+      return await unwrap(commands.getSessionStats(since ?? null, until ?? null, order ?? null));
     } catch (error) {
       console.error("Failed to get session stats:", error);
       throw error;
@@ -1095,12 +1084,15 @@ export const api = {
 
   /**
    * Gets detailed usage entries with optional filtering
-   * @param limit - Optional limit for number of entries
+   * @param projectPath - Optional project path to filter by
+   * @param date - Optional date (YYYY-MM-DD) to filter by
    * @returns Promise resolving to array of usage entries
    */
-  async getUsageDetails(limit?: number): Promise<UsageEntry[]> {
+  async getUsageDetails(projectPath?: string, date?: string): Promise<UsageEntry[]> {
     try {
-      return await invoke<UsageEntry[]>("get_usage_details", { limit });
+      return await unwrapAs<UsageEntry[]>(
+        commands.getUsageDetails(projectPath ?? null, date ?? null, null, null)
+      );
     } catch (error) {
       console.error("Failed to get usage details:", error);
       throw error;
@@ -1117,13 +1109,13 @@ export const api = {
     messageIndex?: number,
     description?: string
   ): Promise<CheckpointResult> {
-    return invoke("create_checkpoint", {
+    return unwrapAs<CheckpointResult>(commands.createCheckpoint(
       sessionId,
       projectId,
       projectPath,
-      messageIndex,
-      description
-    });
+      messageIndex ?? null,
+      description ?? null
+    ));
   },
 
   /**
@@ -1135,12 +1127,13 @@ export const api = {
     projectId: string,
     projectPath: string
   ): Promise<CheckpointResult> {
-    return invoke("restore_checkpoint", {
+    return unwrapAs<CheckpointResult>(commands.restoreCheckpoint(
       checkpointId,
       sessionId,
       projectId,
-      projectPath
-    });
+      projectPath,
+      null
+    ));
   },
 
   /**
@@ -1151,11 +1144,7 @@ export const api = {
     projectId: string,
     projectPath: string
   ): Promise<Checkpoint[]> {
-    return invoke("list_checkpoints", {
-      sessionId,
-      projectId,
-      projectPath
-    });
+    return unwrapAs<Checkpoint[]>(commands.listCheckpoints(sessionId, projectId, projectPath));
   },
 
   /**
@@ -1169,14 +1158,14 @@ export const api = {
     newSessionId: string,
     description?: string
   ): Promise<CheckpointResult> {
-    return invoke("fork_from_checkpoint", {
+    return unwrapAs<CheckpointResult>(commands.forkFromCheckpoint(
       checkpointId,
       sessionId,
       projectId,
       projectPath,
       newSessionId,
-      description
-    });
+      description ?? null
+    ));
   },
 
   /**
@@ -1187,11 +1176,7 @@ export const api = {
     projectId: string,
     projectPath: string
   ): Promise<SessionTimeline> {
-    return invoke("get_session_timeline", {
-      sessionId,
-      projectId,
-      projectPath
-    });
+    return unwrapAs<SessionTimeline>(commands.getSessionTimeline(sessionId, projectId, projectPath));
   },
 
   /**
@@ -1204,13 +1189,14 @@ export const api = {
     autoCheckpointEnabled: boolean,
     checkpointStrategy: CheckpointStrategy
   ): Promise<void> {
-    return invoke("update_checkpoint_settings", {
+    await unwrap(commands.updateCheckpointSettings(
       sessionId,
       projectId,
       projectPath,
       autoCheckpointEnabled,
-      checkpointStrategy
-    });
+      checkpointStrategy,
+      null
+    ));
   },
 
   /**
@@ -1223,12 +1209,9 @@ export const api = {
     projectId: string
   ): Promise<CheckpointDiff> {
     try {
-      return await invoke<CheckpointDiff>("get_checkpoint_diff", {
-        fromCheckpointId,
-        toCheckpointId,
-        sessionId,
-        projectId
-      });
+      return await unwrapAs<CheckpointDiff>(
+        commands.getCheckpointDiff(fromCheckpointId, toCheckpointId, sessionId, projectId)
+      );
     } catch (error) {
       console.error("Failed to get checkpoint diff:", error);
       throw error;
@@ -1245,12 +1228,7 @@ export const api = {
     message: string
   ): Promise<void> {
     try {
-      await invoke("track_checkpoint_message", {
-        sessionId,
-        projectId,
-        projectPath,
-        message
-      });
+      await unwrap(commands.trackCheckpointMessage(sessionId, projectId, projectPath, message));
     } catch (error) {
       console.error("Failed to track checkpoint message:", error);
       throw error;
@@ -1267,12 +1245,7 @@ export const api = {
     message: string
   ): Promise<boolean> {
     try {
-      return await invoke<boolean>("check_auto_checkpoint", {
-        sessionId,
-        projectId,
-        projectPath,
-        message
-      });
+      return await unwrap(commands.checkAutoCheckpoint(sessionId, projectId, projectPath, message));
     } catch (error) {
       console.error("Failed to check auto checkpoint:", error);
       throw error;
@@ -1289,12 +1262,9 @@ export const api = {
     keepCount: number
   ): Promise<number> {
     try {
-      return await invoke<number>("cleanup_old_checkpoints", {
-        sessionId,
-        projectId,
-        projectPath,
-        keepCount
-      });
+      return await unwrap(
+        commands.cleanupOldCheckpoints(sessionId, projectId, projectPath, keepCount)
+      );
     } catch (error) {
       console.error("Failed to cleanup old checkpoints:", error);
       throw error;
@@ -1315,11 +1285,12 @@ export const api = {
     current_checkpoint_id?: string;
   }> {
     try {
-      return await invoke("get_checkpoint_settings", {
-        sessionId,
-        projectId,
-        projectPath
-      });
+      return await unwrapAs<{
+        auto_checkpoint_enabled: boolean;
+        checkpoint_strategy: CheckpointStrategy;
+        total_checkpoints: number;
+        current_checkpoint_id?: string;
+      }>(commands.getCheckpointSettings(sessionId, projectId, projectPath));
     } catch (error) {
       console.error("Failed to get checkpoint settings:", error);
       throw error;
@@ -1331,7 +1302,7 @@ export const api = {
    */
   async clearCheckpointManager(sessionId: string): Promise<void> {
     try {
-      await invoke("clear_checkpoint_manager", { sessionId });
+      await unwrap(commands.clearCheckpointManager(sessionId));
     } catch (error) {
       console.error("Failed to clear checkpoint manager:", error);
       throw error;
@@ -1341,13 +1312,14 @@ export const api = {
   /**
    * Tracks a batch of messages for a session for checkpointing
    */
-  trackSessionMessages: (
-    sessionId: string, 
-    projectId: string, 
-    projectPath: string, 
+  async trackSessionMessages(
+    sessionId: string,
+    projectId: string,
+    projectPath: string,
     messages: string[]
-  ): Promise<void> =>
-    invoke("track_session_messages", { sessionId, projectId, projectPath, messages }),
+  ): Promise<void> {
+    await unwrap(commands.trackSessionMessages(sessionId, projectId, projectPath, messages));
+  },
 
   /**
    * Adds a new MCP server
@@ -1362,15 +1334,9 @@ export const api = {
     scope: string = "local"
   ): Promise<AddServerResult> {
     try {
-      return await invoke<AddServerResult>("mcp_add", {
-        name,
-        transport,
-        command,
-        args,
-        env,
-        url,
-        scope
-      });
+      return await unwrapAs<AddServerResult>(
+        commands.mcpAdd(name, transport, command ?? null, args, env, url ?? null, scope)
+      );
     } catch (error) {
       console.error("Failed to add MCP server:", error);
       throw error;
@@ -1383,7 +1349,7 @@ export const api = {
   async mcpList(): Promise<MCPServer[]> {
     try {
       console.log("API: Calling mcp_list...");
-      const result = await invoke<MCPServer[]>("mcp_list");
+      const result = await unwrapAs<MCPServer[]>(commands.mcpList());
       console.log("API: mcp_list returned:", result);
       return result;
     } catch (error) {
@@ -1397,7 +1363,7 @@ export const api = {
    */
   async mcpGet(name: string): Promise<MCPServer> {
     try {
-      return await invoke<MCPServer>("mcp_get", { name });
+      return await unwrapAs<MCPServer>(commands.mcpGet(name));
     } catch (error) {
       console.error("Failed to get MCP server:", error);
       throw error;
@@ -1409,7 +1375,7 @@ export const api = {
    */
   async mcpRemove(name: string): Promise<string> {
     try {
-      return await invoke<string>("mcp_remove", { name });
+      return await unwrap(commands.mcpRemove(name, null));
     } catch (error) {
       console.error("Failed to remove MCP server:", error);
       throw error;
@@ -1421,7 +1387,7 @@ export const api = {
    */
   async mcpAddJson(name: string, jsonConfig: string, scope: string = "local"): Promise<AddServerResult> {
     try {
-      return await invoke<AddServerResult>("mcp_add_json", { name, jsonConfig, scope });
+      return await unwrapAs<AddServerResult>(commands.mcpAddJson(name, jsonConfig, scope));
     } catch (error) {
       console.error("Failed to add MCP server from JSON:", error);
       throw error;
@@ -1433,7 +1399,7 @@ export const api = {
    */
   async mcpAddFromClaudeDesktop(scope: string = "local"): Promise<ImportResult> {
     try {
-      return await invoke<ImportResult>("mcp_add_from_claude_desktop", { scope });
+      return await unwrapAs<ImportResult>(commands.mcpAddFromClaudeDesktop(scope, null));
     } catch (error) {
       console.error("Failed to import from Claude Desktop:", error);
       throw error;
@@ -1445,7 +1411,7 @@ export const api = {
    */
   async mcpServe(): Promise<string> {
     try {
-      return await invoke<string>("mcp_serve");
+      return await unwrap(commands.mcpServe());
     } catch (error) {
       console.error("Failed to start MCP server:", error);
       throw error;
@@ -1457,7 +1423,7 @@ export const api = {
    */
   async mcpTestConnection(name: string): Promise<string> {
     try {
-      return await invoke<string>("mcp_test_connection", { name });
+      return await unwrap(commands.mcpTestConnection(name));
     } catch (error) {
       console.error("Failed to test MCP connection:", error);
       throw error;
@@ -1469,7 +1435,7 @@ export const api = {
    */
   async mcpResetProjectChoices(): Promise<string> {
     try {
-      return await invoke<string>("mcp_reset_project_choices");
+      return await unwrap(commands.mcpResetProjectChoices());
     } catch (error) {
       console.error("Failed to reset project choices:", error);
       throw error;
@@ -1481,7 +1447,7 @@ export const api = {
    */
   async mcpGetServerStatus(): Promise<Record<string, ServerStatus>> {
     try {
-      return await invoke<Record<string, ServerStatus>>("mcp_get_server_status");
+      return await unwrapAs<Record<string, ServerStatus>>(commands.mcpGetServerStatus());
     } catch (error) {
       console.error("Failed to get server status:", error);
       throw error;
@@ -1493,7 +1459,7 @@ export const api = {
    */
   async mcpReadProjectConfig(projectPath: string): Promise<MCPProjectConfig> {
     try {
-      return await invoke<MCPProjectConfig>("mcp_read_project_config", { projectPath });
+      return await unwrapAs<MCPProjectConfig>(commands.mcpReadProjectConfig(projectPath));
     } catch (error) {
       console.error("Failed to read project MCP config:", error);
       throw error;
@@ -1505,7 +1471,7 @@ export const api = {
    */
   async mcpSaveProjectConfig(projectPath: string, config: MCPProjectConfig): Promise<string> {
     try {
-      return await invoke<string>("mcp_save_project_config", { projectPath, config });
+      return await unwrap(commands.mcpSaveProjectConfig(projectPath, config));
     } catch (error) {
       console.error("Failed to save project MCP config:", error);
       throw error;
@@ -1518,7 +1484,7 @@ export const api = {
    */
   async getClaudeBinaryPath(): Promise<string | null> {
     try {
-      return await invoke<string | null>("get_claude_binary_path");
+      return await unwrap(commands.getClaudeBinaryPath());
     } catch (error) {
       console.error("Failed to get Claude binary path:", error);
       throw error;
@@ -1532,7 +1498,7 @@ export const api = {
    */
   async setClaudeBinaryPath(path: string): Promise<void> {
     try {
-      return await invoke<void>("set_claude_binary_path", { path });
+      await unwrap(commands.setClaudeBinaryPath(path));
     } catch (error) {
       console.error("Failed to set Claude binary path:", error);
       throw error;
@@ -1545,7 +1511,7 @@ export const api = {
    */
   async listClaudeInstallations(): Promise<ClaudeInstallation[]> {
     try {
-      return await invoke<ClaudeInstallation[]>("list_claude_installations");
+      return await unwrapAs<ClaudeInstallation[]>(commands.listClaudeInstallations());
     } catch (error) {
       console.error("Failed to list Claude installations:", error);
       throw error;
@@ -1560,7 +1526,7 @@ export const api = {
    */
   async storageListTables(): Promise<any[]> {
     try {
-      return await invoke<any[]>("storage_list_tables");
+      return await unwrap(commands.storageListTables());
     } catch (error) {
       console.error("Failed to list tables:", error);
       throw error;
@@ -1582,12 +1548,9 @@ export const api = {
     searchQuery?: string
   ): Promise<any> {
     try {
-      return await invoke<any>("storage_read_table", {
-        tableName,
-        page,
-        pageSize,
-        searchQuery,
-      });
+      return await unwrap(
+        commands.storageReadTable(tableName, page, pageSize, searchQuery ?? null)
+      );
     } catch (error) {
       console.error("Failed to read table:", error);
       throw error;
@@ -1607,11 +1570,7 @@ export const api = {
     updates: Record<string, any>
   ): Promise<void> {
     try {
-      return await invoke<void>("storage_update_row", {
-        tableName,
-        primaryKeyValues,
-        updates,
-      });
+      await unwrap(commands.storageUpdateRow(tableName, primaryKeyValues, updates));
     } catch (error) {
       console.error("Failed to update row:", error);
       throw error;
@@ -1629,10 +1588,7 @@ export const api = {
     primaryKeyValues: Record<string, any>
   ): Promise<void> {
     try {
-      return await invoke<void>("storage_delete_row", {
-        tableName,
-        primaryKeyValues,
-      });
+      await unwrap(commands.storageDeleteRow(tableName, primaryKeyValues));
     } catch (error) {
       console.error("Failed to delete row:", error);
       throw error;
@@ -1650,10 +1606,7 @@ export const api = {
     values: Record<string, any>
   ): Promise<number> {
     try {
-      return await invoke<number>("storage_insert_row", {
-        tableName,
-        values,
-      });
+      return await unwrap(commands.storageInsertRow(tableName, values));
     } catch (error) {
       console.error("Failed to insert row:", error);
       throw error;
@@ -1667,7 +1620,7 @@ export const api = {
    */
   async storageExecuteSql(query: string): Promise<any> {
     try {
-      return await invoke<any>("storage_execute_sql", { query });
+      return await unwrap(commands.storageExecuteSql(query));
     } catch (error) {
       console.error("Failed to execute SQL:", error);
       throw error;
@@ -1680,7 +1633,7 @@ export const api = {
    */
   async storageResetDatabase(): Promise<void> {
     try {
-      return await invoke<void>("storage_reset_database");
+      await unwrap(commands.storageResetDatabase());
     } catch (error) {
       console.error("Failed to reset database:", error);
       throw error;
@@ -1695,7 +1648,7 @@ export const api = {
    */
   async getHooksConfig(scope: 'user' | 'project' | 'local', projectPath?: string): Promise<HooksConfiguration> {
     try {
-      return await invoke<HooksConfiguration>("get_hooks_config", { scope, projectPath });
+      return await unwrapAs<HooksConfiguration>(commands.getHooksConfig(scope, projectPath ?? null));
     } catch (error) {
       console.error("Failed to get hooks config:", error);
       throw error;
@@ -1715,7 +1668,7 @@ export const api = {
     projectPath?: string
   ): Promise<string> {
     try {
-      return await invoke<string>("update_hooks_config", { scope, projectPath, hooks });
+      return await unwrap(commands.updateHooksConfig(scope, asJson(hooks), projectPath ?? null));
     } catch (error) {
       console.error("Failed to update hooks config:", error);
       throw error;
@@ -1729,7 +1682,7 @@ export const api = {
    */
   async validateHookCommand(command: string): Promise<{ valid: boolean; message: string }> {
     try {
-      return await invoke<{ valid: boolean; message: string }>("validate_hook_command", { command });
+      return await unwrapAs<{ valid: boolean; message: string }>(commands.validateHookCommand(command));
     } catch (error) {
       console.error("Failed to validate hook command:", error);
       throw error;
@@ -1767,7 +1720,7 @@ export const api = {
    */
   async slashCommandsList(projectPath?: string): Promise<SlashCommand[]> {
     try {
-      return await invoke<SlashCommand[]>("slash_commands_list", { projectPath });
+      return await unwrapAs<SlashCommand[]>(commands.slashCommandsList(projectPath ?? null));
     } catch (error) {
       console.error("Failed to list slash commands:", error);
       throw error;
@@ -1781,7 +1734,7 @@ export const api = {
    */
   async slashCommandGet(commandId: string): Promise<SlashCommand> {
     try {
-      return await invoke<SlashCommand>("slash_command_get", { commandId });
+      return await unwrapAs<SlashCommand>(commands.slashCommandGet(commandId, null));
     } catch (error) {
       console.error("Failed to get slash command:", error);
       throw error;
@@ -1809,15 +1762,17 @@ export const api = {
     projectPath?: string
   ): Promise<SlashCommand> {
     try {
-      return await invoke<SlashCommand>("slash_command_save", {
+      return await unwrapAs<SlashCommand>(commands.slashCommandSave(
         scope,
         name,
-        namespace,
+        namespace ?? null,
         content,
-        description,
+        description ?? null,
         allowedTools,
-        projectPath
-      });
+        projectPath ?? null,
+        null,
+        null
+      ));
     } catch (error) {
       console.error("Failed to save slash command:", error);
       throw error;
@@ -1832,7 +1787,7 @@ export const api = {
    */
   async slashCommandDelete(commandId: string, projectPath?: string): Promise<string> {
     try {
-      return await invoke<string>("slash_command_delete", { commandId, projectPath });
+      return await unwrap(commands.slashCommandDelete(commandId, projectPath ?? null));
     } catch (error) {
       console.error("Failed to delete slash command:", error);
       throw error;
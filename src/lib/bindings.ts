@@ -0,0 +1,5308 @@
+
+// This file was generated by [tauri-specta](https://github.com/oscartbeaumont/tauri-specta). Do not edit this file manually.
+
+/** user-defined commands **/
+
+
+export const commands = {
+/**
+ * Lists all projects in the ~/.claude/projects directory
+ */
+async listProjects() : Promise<Result<Project[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_projects") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Lists all projects like `list_projects`, emitting `project-scanned` for each one as soon
+ * as it has been read so the project list can fill in progressively
+ */
+async scanProjectsStreaming() : Promise<Result<Project[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("scan_projects_streaming") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Gets sessions for a specific project
+ */
+async getProjectSessions(projectId: string) : Promise<Result<Session[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_project_sessions", { projectId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Reads the Claude settings file
+ */
+async getClaudeSettings() : Promise<Result<ClaudeSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_claude_settings") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Opens a new Claude Code session by executing the claude command
+ */
+async openNewSession(path: string | null) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("open_new_session", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Reads the CLAUDE.md system prompt file
+ */
+async getSystemPrompt() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_system_prompt") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Checks if Claude Code is installed and gets its version
+ */
+async checkClaudeVersion() : Promise<Result<ClaudeVersionStatus, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("check_claude_version") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Saves the CLAUDE.md system prompt file
+ */
+async saveSystemPrompt(content: string) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("save_system_prompt", { content }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Saves the Claude settings file
+ */
+async saveClaudeSettings(settings: JsonValue) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("save_claude_settings", { settings }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Recursively finds all CLAUDE.md files in a project directory
+ */
+async findClaudeMdFiles(projectPath: string) : Promise<Result<ClaudeMdFile[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("find_claude_md_files", { projectPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Finds the global CLAUDE.md and the CLAUDE.md files of every known project
+ */
+async findAllClaudeMdFiles() : Promise<Result<ClaudeMdLocation[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("find_all_claude_md_files") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Reads a specific CLAUDE.md file by its absolute path
+ */
+async readClaudeMdFile(filePath: string) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("read_claude_md_file", { filePath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Saves a specific CLAUDE.md file by its absolute path
+ */
+async saveClaudeMdFile(filePath: string, content: string) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("save_claude_md_file", { filePath, content }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Loads the JSONL history for a specific session
+ */
+async loadSessionHistory(sessionId: string, projectId: string) : Promise<Result<JsonValue[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("load_session_history", { sessionId, projectId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Execute a new interactive Claude Code session with streaming output
+ */
+async executeClaudeCode(projectPath: string, prompt: string, model: string, maxCostUsd: number | null, maxTokens: number | null, promptPresetId: number | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("execute_claude_code", { projectPath, prompt, model, maxCostUsd, maxTokens, promptPresetId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Continue an existing Claude Code conversation with streaming output
+ */
+async continueClaudeCode(projectPath: string, prompt: string, model: string, maxCostUsd: number | null, maxTokens: number | null, promptPresetId: number | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("continue_claude_code", { projectPath, prompt, model, maxCostUsd, maxTokens, promptPresetId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Resume an existing Claude Code session by ID with streaming output
+ */
+async resumeClaudeCode(projectPath: string, sessionId: string, prompt: string, model: string, maxCostUsd: number | null, maxTokens: number | null, promptPresetId: number | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("resume_claude_code", { projectPath, sessionId, prompt, model, maxCostUsd, maxTokens, promptPresetId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Cancel the currently running Claude Code execution
+ */
+async cancelClaudeExecution(sessionId: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("cancel_claude_execution", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get all running Claude sessions
+ */
+async listRunningClaudeSessions() : Promise<Result<ProcessInfo[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_running_claude_sessions") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get live output from a Claude session
+ */
+async getClaudeSessionOutput(sessionId: string) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_claude_session_output", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Lists files and directories in a given path
+ */
+async listDirectoryContents(directoryPath: string) : Promise<Result<FileEntry[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_directory_contents", { directoryPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Search for files and directories matching a pattern
+ */
+async searchFiles(basePath: string, query: string) : Promise<Result<FileEntry[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("search_files", { basePath, query }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Gets files modified in the last N minutes for a session
+ */
+async getRecentlyModifiedFiles(sessionId: string, projectId: string, projectPath: string, minutes: number) : Promise<Result<string[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_recently_modified_files", { sessionId, projectId, projectPath, minutes }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Gets hooks configuration from settings at specified scope
+ */
+async getHooksConfig(scope: string, projectPath: string | null) : Promise<Result<JsonValue, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_hooks_config", { scope, projectPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Updates hooks configuration in settings at specified scope
+ */
+async updateHooksConfig(scope: string, hooks: JsonValue, projectPath: string | null) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_hooks_config", { scope, hooks, projectPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Validates a hook command by dry-running it
+ */
+async validateHookCommand(command: string) : Promise<Result<JsonValue, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("validate_hook_command", { command }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Creates a checkpoint for the current session state
+ */
+async createCheckpoint(sessionId: string, projectId: string, projectPath: string, messageIndex: number | null, description: string | null) : Promise<Result<CheckpointResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_checkpoint", { sessionId, projectId, projectPath, messageIndex, description }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Restores a session to a specific checkpoint
+ */
+async restoreCheckpoint(checkpointId: string, sessionId: string, projectId: string, projectPath: string, mode: RestoreMode | null) : Promise<Result<CheckpointResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("restore_checkpoint", { checkpointId, sessionId, projectId, projectPath, mode }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Lists all checkpoints for a session
+ */
+async listCheckpoints(sessionId: string, projectId: string, projectPath: string) : Promise<Result<Checkpoint[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_checkpoints", { sessionId, projectId, projectPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Forks a new timeline branch from a checkpoint
+ */
+async forkFromCheckpoint(checkpointId: string, sessionId: string, projectId: string, projectPath: string, newSessionId: string, description: string | null) : Promise<Result<CheckpointResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("fork_from_checkpoint", { checkpointId, sessionId, projectId, projectPath, newSessionId, description }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Gets the checkpoints from the root of a session's timeline down to a checkpoint
+ */
+async getCheckpointPath(checkpointId: string, sessionId: string, projectId: string, projectPath: string) : Promise<Result<Checkpoint[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_checkpoint_path", { checkpointId, sessionId, projectId, projectPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Gets the timeline for a session
+ */
+async getSessionTimeline(sessionId: string, projectId: string, projectPath: string) : Promise<Result<SessionTimeline, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_session_timeline", { sessionId, projectId, projectPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Updates checkpoint settings for a session
+ */
+async updateCheckpointSettings(sessionId: string, projectId: string, projectPath: string, autoCheckpointEnabled: boolean, checkpointStrategy: string, tokenThreshold: number | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_checkpoint_settings", { sessionId, projectId, projectPath, autoCheckpointEnabled, checkpointStrategy, tokenThreshold }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Gets diff between two checkpoints
+ */
+async getCheckpointDiff(fromCheckpointId: string, toCheckpointId: string, sessionId: string, projectId: string) : Promise<Result<CheckpointDiff, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_checkpoint_diff", { fromCheckpointId, toCheckpointId, sessionId, projectId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Compares two checkpoints, with unified diffs of the modified files
+ */
+async diffCheckpoints(fromCheckpointId: string, toCheckpointId: string, sessionId: string, projectId: string) : Promise<Result<CheckpointDiff, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("diff_checkpoints", { fromCheckpointId, toCheckpointId, sessionId, projectId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exports a checkpoint with its files and transcript as a zip archive
+ */
+async exportCheckpoint(checkpointId: string, sessionId: string, projectId: string, filePath: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_checkpoint", { checkpointId, sessionId, projectId, filePath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Imports an exported checkpoint archive into a session's timeline
+ */
+async importCheckpoint(sessionId: string, projectId: string, filePath: string) : Promise<Result<CheckpointResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("import_checkpoint", { sessionId, projectId, filePath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tracks a message for checkpointing
+ */
+async trackCheckpointMessage(sessionId: string, projectId: string, projectPath: string, message: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("track_checkpoint_message", { sessionId, projectId, projectPath, message }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Track session messages from the frontend for checkpointing
+ */
+async trackSessionMessages(sessionId: string, projectId: string, projectPath: string, messages: string[]) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("track_session_messages", { sessionId, projectId, projectPath, messages }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Checks if auto-checkpoint should be triggered
+ */
+async checkAutoCheckpoint(sessionId: string, projectId: string, projectPath: string, message: string) : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("check_auto_checkpoint", { sessionId, projectId, projectPath, message }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Triggers cleanup of old checkpoints
+ */
+async cleanupOldCheckpoints(sessionId: string, projectId: string, projectPath: string, keepCount: number) : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("cleanup_old_checkpoints", { sessionId, projectId, projectPath, keepCount }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get the checkpoint retention policy
+ */
+async getCheckpointRetention() : Promise<Result<RetentionPolicy, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_checkpoint_retention") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Save the checkpoint retention policy
+ */
+async setCheckpointRetention(policy: RetentionPolicy) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_checkpoint_retention", { policy }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Remove old checkpoints by the saved policy, or `policy` when given
+ * 
+ * Cleans one session when `session_id` is given, otherwise every session of
+ * `project_id`, or of all projects when that is omitted too.
+ */
+async cleanupCheckpoints(projectId: string | null, sessionId: string | null, policy: RetentionPolicy | null) : Promise<Result<CleanupReport, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("cleanup_checkpoints", { projectId, sessionId, policy }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Read a settings file along with any validation issues
+ */
+async readSettingsFile(scope: string, projectPath: string | null) : Promise<Result<SettingsFile, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("read_settings_file", { scope, projectPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Validate settings text without saving it, including warnings
+ */
+async validateSettingsContent(content: string) : Promise<Result<SettingsIssue[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("validate_settings_content", { content }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Validate and save a settings file, keeping a timestamped backup of the previous version
+ */
+async writeSettingsFile(scope: string, projectPath: string | null, content: string) : Promise<Result<SettingsWriteResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("write_settings_file", { scope, projectPath, content }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Validate a hooks section without saving it
+ */
+async validateHooksConfig(hooks: JsonValue) : Promise<Result<SettingsIssue[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("validate_hooks_config", { hooks }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * All hooks that apply to a project, user scope first, as Claude Code runs every scope
+ */
+async getMergedHooks(projectPath: string | null) : Promise<Result<ScopedHookMatcher[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_merged_hooks", { projectPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Save a hooks section after validating it
+ */
+async saveHooksConfig(scope: string, projectPath: string | null, hooks: JsonValue) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("save_hooks_config", { scope, projectPath, hooks }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List the installable hook templates
+ */
+async listHookTemplates() : Promise<Result<HookTemplate[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_hook_templates") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Install a hook template into a scope; returns false when it was already installed
+ */
+async installHookTemplate(templateId: string, scope: string, projectPath: string | null) : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("install_hook_template", { templateId, scope, projectPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get the environment variables of a project
+ */
+async getProjectEnv(projectPath: string) : Promise<Result<EnvVar[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_project_env", { projectPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Replace the environment variables of a project; an empty list removes the profile
+ */
+async setProjectEnv(projectPath: string, vars: EnvVar[]) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_project_env", { projectPath, vars }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List every project that has environment variables
+ */
+async listProjectEnvProfiles() : Promise<Result<ProjectEnvProfile[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_project_env_profiles") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get the proxy settings
+ */
+async getProxySettings() : Promise<Result<ProxySettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_proxy_settings") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Save the proxy settings and apply them to subsequent processes and requests
+ */
+async setProxySettings(settings: ProxySettings) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_proxy_settings", { settings }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Read the permission rules of a settings scope: "user", "project" or "local"
+ */
+async getPermissionRules(scope: string, projectPath: string | null) : Promise<Result<PermissionRules, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_permission_rules", { scope, projectPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Validate and save permission rules, keeping the other permission settings
+ */
+async setPermissionRules(scope: string, projectPath: string | null, rules: PermissionRules) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_permission_rules", { scope, projectPath, rules }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Validate a single rule
+ */
+async validatePermissionRule(rule: string) : Promise<Result<ParsedRule, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("validate_permission_rule", { rule }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Show which tools and example calls a rule set would permit
+ */
+async previewPermissions(rules: PermissionRules, calls: ToolCall[] | null) : Promise<Result<PermissionPreview, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("preview_permissions", { rules, calls }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get one UI preference, `None` when it was never set
+ */
+async getPreference(key: string) : Promise<Result<JsonValue | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_preference", { key }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get all UI preferences, optionally only those whose key starts with `prefix` (e.g. "layout.")
+ */
+async getPreferences(prefix: string | null) : Promise<Result<Partial<{ [key in string]: JsonValue }>, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_preferences", { prefix }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Set one UI preference
+ */
+async setPreference(key: string, value: JsonValue) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_preference", { key, value }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Set several UI preferences at once, e.g. the whole window layout on close
+ */
+async setPreferences(preferences: Partial<{ [key in string]: JsonValue }>) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_preferences", { preferences }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Remove a UI preference so the frontend falls back to its default
+ */
+async deletePreference(key: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_preference", { key }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Verify Claude, git, gh, `~/.claude` and the database for the setup checklist
+ */
+async checkEnvironment() : Promise<Result<EnvironmentReport, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("check_environment") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List all prompt presets by name
+ */
+async listPromptPresets() : Promise<Result<PromptPreset[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_prompt_presets") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Create a prompt preset
+ */
+async createPromptPreset(name: string, content: string, mode: string | null) : Promise<Result<PromptPreset, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_prompt_preset", { name, content, mode }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Update a prompt preset
+ */
+async updatePromptPreset(id: number, name: string, content: string, mode: string) : Promise<Result<PromptPreset, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_prompt_preset", { id, name, content, mode }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Delete a prompt preset
+ */
+async deletePromptPreset(id: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_prompt_preset", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List every running Claude session and agent run, oldest first
+ */
+async listRunningProcesses() : Promise<Result<RunningProcess[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_running_processes") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Milliseconds a stopped process gets to exit after the interrupt before it is killed
+ */
+async getKillGracePeriod() : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_kill_grace_period") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setKillGracePeriod(ms: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_kill_grace_period", { ms }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Priority used for scheduled agent runs
+ */
+async getBackgroundPriority() : Promise<Result<BackgroundPriority, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_background_priority") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setBackgroundPriority(priority: BackgroundPriority) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_background_priority", { priority }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Start Claude in a pseudo-terminal for the embedded terminal view
+ * 
+ * Raw output is emitted base64-encoded as `pty-output:{id}`, since chunks can split
+ * multi-byte characters, and the exit code follows as `pty-exit:{id}`.
+ */
+async startPtySession(projectPath: string, prompt: string | null, model: string | null, agentId: number | null, rows: number, cols: number) : Promise<Result<PtyProcessInfo, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("start_pty_session", { projectPath, prompt, model, agentId, rows, cols }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Send keyboard input to a PTY session
+ */
+async writePtySession(id: string, data: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("write_pty_session", { id, data }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tell a PTY session its terminal was resized
+ */
+async resizePtySession(id: string, rows: number, cols: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("resize_pty_session", { id, rows, cols }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async killPtySession(id: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("kill_pty_session", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async listPtySessions() : Promise<Result<PtyProcessInfo[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_pty_sessions") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get the stored stderr of an interactive session, oldest first
+ */
+async getSessionErrorLogs(sessionId: string) : Promise<Result<OutputLine[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_session_error_logs", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * CPU and memory of a run; returns None once the run is no longer running
+ * 
+ * CPU usage is measured between samples, so a run that was never sampled before is
+ * sampled twice a short moment apart.
+ */
+async getRunResources(runId: number) : Promise<Result<RunResources | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_run_resources", { runId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List the processes left running by a previous session of Claudia
+ */
+async listOrphanedProcesses() : Promise<Result<OrphanedProcess[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_orphaned_processes") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Track an orphaned process again and stream its session file until it exits
+ * 
+ * Agent runs keep their run ID and emit `agent-output:{run_id}`; sessions get a new
+ * registry ID and emit `claude-output:{session_id}`. Returns the registry run ID.
+ */
+async adoptOrphanedProcess(pid: number) : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("adopt_orphaned_process", { pid }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Stop an orphaned process; its agent run becomes interrupted so it can be resumed
+ */
+async terminateOrphanedProcess(pid: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("terminate_orphaned_process", { pid }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get the retry policy for agent runs
+ */
+async getRetryPolicy() : Promise<Result<RetryPolicy, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_retry_policy") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Save the retry policy for agent runs
+ */
+async setRetryPolicy(policy: RetryPolicy) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_retry_policy", { policy }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get the schema version of the agents database and the migrations applied to it
+ */
+async getSchemaVersion() : Promise<Result<SchemaVersion, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_schema_version") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Back up the database, agent definitions, settings files and checkpoint metadata into one archive
+ */
+async createBackup(archivePath: string) : Promise<Result<BackupManifest, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_backup", { archivePath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Read the manifest of a backup archive without restoring it
+ */
+async inspectBackup(archivePath: string) : Promise<Result<BackupManifest, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("inspect_backup", { archivePath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Replace the database with the one in a backup and restore its `~/.claude` files
+ * 
+ * The current database is kept next to it as `agents.db.before-restore`. Agent
+ * definitions are part of the database, so the exported agent files are not read.
+ */
+async restoreBackup(archivePath: string) : Promise<Result<RestoreSummary, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("restore_backup", { archivePath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List queued, running and recently finished background tasks
+ */
+async listBackgroundTasks() : Promise<Result<BackgroundTask[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_background_tasks") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async cancelBackgroundTask(id: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("cancel_background_task", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Subscribe the frontend to changes in a scope, emitted as `fs-changed:{subscription_id}`
+ */
+async subscribeFsChanges(scope: WatchScope) : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("subscribe_fs_changes", { scope }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async unsubscribeFsChanges(subscriptionId: number) : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("unsubscribe_fs_changes", { subscriptionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Compress a session transcript to `<session>.jsonl.zst`; it keeps loading as before
+ */
+async archiveSession(projectId: string, sessionId: string) : Promise<Result<SessionArchive, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("archive_session", { projectId, sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Archive every session not written to for `older_than_days` days
+ */
+async archiveOldSessions(olderThanDays: number) : Promise<Result<SessionArchive[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("archive_old_sessions", { olderThanDays }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Decompress an archived session back to a plain transcript
+ */
+async unarchiveSession(projectId: string, sessionId: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("unarchive_session", { projectId, sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List archived sessions, most recently archived first
+ */
+async listArchivedSessions() : Promise<Result<SessionArchive[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_archived_sessions") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Space saved by archiving sessions
+ */
+async getArchiveStats() : Promise<Result<ArchiveStats, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_archive_stats") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Vacuum the database, check its integrity and optimize full-text indexes now
+ */
+async runDbMaintenance() : Promise<Result<MaintenanceReport, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("run_db_maintenance") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get the report of the latest maintenance run, if any
+ */
+async getLastDbMaintenance() : Promise<Result<MaintenanceReport | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_last_db_maintenance") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getDbMaintenanceSettings() : Promise<Result<DbMaintenanceSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_db_maintenance_settings") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setDbMaintenanceSettings(settings: DbMaintenanceSettings) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_db_maintenance_settings", { settings }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Find the sessions whose tool calls read or wrote a file, most recent first
+ * 
+ * An absolute path must match exactly; a relative one matches any file ending with it.
+ */
+async findSessionsForFile(path: string, limit: number | null) : Promise<Result<FileSessionMatch[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("find_sessions_for_file", { path, limit }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get the settings and state of the REST API server
+ */
+async getRestApiServer() : Promise<Result<RestApiStatus, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_rest_api_server") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Enable or disable the REST API server, starting or stopping it right away
+ * 
+ * `regenerate_token` issues a new token, locking out clients holding the old one.
+ */
+async setRestApiServer(enabled: boolean, port: number | null, regenerateToken: boolean | null) : Promise<Result<RestApiStatus, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_rest_api_server", { enabled, port, regenerateToken }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Take the link received before the frontend started listening, if any
+ */
+async takePendingDeepLink() : Promise<Result<DeepLinkTarget | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("take_pending_deep_link") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Parse a link without opening it, e.g. to validate links pasted into the app
+ */
+async resolveDeepLink(link: string) : Promise<Result<DeepLinkTarget, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("resolve_deep_link", { link }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List all webhooks by name
+ */
+async listWebhooks() : Promise<Result<Webhook[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_webhooks") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Create a webhook
+ */
+async createWebhook(name: string, url: string, secret: string | null, events: string[]) : Promise<Result<Webhook, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_webhook", { name, url, secret, events }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Update a webhook
+ * 
+ * `secret` replaces the signing key; an empty one removes it and `None` keeps it.
+ */
+async updateWebhook(id: number, name: string, url: string, secret: string | null, events: string[], enabled: boolean) : Promise<Result<Webhook, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_webhook", { id, name, url, secret, events, enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Delete a webhook
+ */
+async deleteWebhook(id: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_webhook", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Send a sample `run.completed` payload to a webhook and report the HTTP status
+ */
+async testWebhook(id: number) : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("test_webhook", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get the Slack integration settings
+ */
+async getSlackSettings() : Promise<Result<ChatIntegrationSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_slack_settings") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Save the Slack integration settings
+ */
+async setSlackSettings(settings: ChatIntegrationSettings) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_slack_settings", { settings }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Post a test message to a Slack webhook, the global one when none is given
+ */
+async testSlackWebhook(webhookUrl: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("test_slack_webhook", { webhookUrl }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get the Discord integration settings
+ */
+async getDiscordSettings() : Promise<Result<ChatIntegrationSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_discord_settings") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Save the Discord integration settings
+ */
+async setDiscordSettings(settings: ChatIntegrationSettings) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_discord_settings", { settings }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Post a test message to a Discord webhook, the global one when none is given
+ */
+async testDiscordWebhook(webhookUrl: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("test_discord_webhook", { webhookUrl }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get the OTLP export settings
+ */
+async getTelemetrySettings() : Promise<Result<TelemetrySettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_telemetry_settings") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Save the OTLP export settings; spans are recorded from now on when enabled
+ */
+async setTelemetrySettings(settings: TelemetrySettings) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_telemetry_settings", { settings }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Send a test span to the collector and report whether it was accepted
+ */
+async testTelemetryExport(settings: TelemetrySettings) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("test_telemetry_export", { settings }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List plugins in ~/.claude/claudia-plugins, starting them on first use
+ */
+async listPlugins() : Promise<Result<PluginInfo[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_plugins") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Stop all plugins and discover them again, e.g. after installing one
+ */
+async reloadPlugins() : Promise<Result<PluginInfo[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("reload_plugins") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Run a command contributed by a plugin and return its result
+ */
+async executePluginCommand(pluginId: string, commandId: string, params: JsonValue | null) : Promise<Result<JsonValue, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("execute_plugin_command", { pluginId, commandId, params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Ask a plugin for the content of one of its panels
+ * 
+ * `context` tells the plugin where the panel is shown, e.g. the open project.
+ */
+async renderPluginPanel(pluginId: string, panelId: string, context: JsonValue | null) : Promise<Result<JsonValue, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("render_plugin_panel", { pluginId, panelId, context }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get the directory plugins are installed in, creating it if needed
+ */
+async getPluginsDirectory() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_plugins_directory") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get the vault export settings
+ */
+async getVaultExportSettings() : Promise<Result<VaultExportSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_vault_export_settings") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Save the vault export settings
+ */
+async setVaultExportSettings(settings: VaultExportSettings) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_vault_export_settings", { settings }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Write every session as a note into the vault, with a note per project linking them
+ */
+async syncVaultExport() : Promise<Result<VaultSyncResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("sync_vault_export") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List the conversations an export holds without importing them
+ */
+async previewHistoryImport(source: ImportSource, path: string, projectPath: string | null) : Promise<Result<ImportPreview[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("preview_history_import", { source, path, projectPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Import the conversations of a Cursor, aider or Continue export as Claude sessions
+ * under their project in ~/.claude/projects
+ */
+async importHistory(source: ImportSource, path: string, projectPath: string | null) : Promise<Result<HistoryImportResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("import_history", { source, path, projectPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Upload a run's summary, diff and transcript excerpt as a gist and return its URL
+ * 
+ * Gists are secret unless `public` is set; secret gists are unlisted but readable by
+ * anyone with the link.
+ */
+async shareRunAsGist(runId: number, public: boolean | null) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("share_run_as_gist", { runId, public }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get the email settings
+ */
+async getEmailSettings() : Promise<Result<EmailSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_email_settings") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Save the email settings
+ * 
+ * `password` replaces the stored SMTP password; an empty one removes it and `None` keeps it.
+ */
+async setEmailSettings(settings: EmailSettings, password: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_email_settings", { settings, password }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Send a test email with the saved settings
+ */
+async sendTestEmail() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("send_test_email") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Write the upcoming scheduled runs to an .ics file
+ */
+async exportScheduleCalendar(filePath: string, days: number | null) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_schedule_calendar", { filePath, days }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List open issues from every configured provider
+ * 
+ * A provider that fails is reported in `errors` instead of failing the whole list.
+ */
+async listAggregatedIssues() : Promise<Result<AggregatedIssues, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_aggregated_issues") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Start an agent run that works on an issue in its mapped repository
+ */
+async fixIssueWithAgent(issue: Issue, agentId: number, projectPath: string | null, model: string | null) : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("fix_issue_with_agent", { issue, agentId, projectPath, model }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get the Jira settings
+ */
+async getJiraSettings() : Promise<Result<JiraSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_jira_settings") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Save the Jira settings
+ * 
+ * `api_token` replaces the stored token; an empty one removes it and `None` keeps it.
+ */
+async setJiraSettings(settings: JiraSettings, apiToken: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_jira_settings", { settings, apiToken }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Check the saved Jira credentials, returning the account's display name
+ */
+async testJiraConnection() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("test_jira_connection") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * File an issue about a session with a provider that supports creating issues
+ * 
+ * The title defaults to the session's first request.
+ */
+async createIssueFromSession(provider: string, projectId: string, sessionId: string, title: string | null, target: string | null) : Promise<Result<Issue, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_issue_from_session", { provider, projectId, sessionId, title, target }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get the Linear settings
+ */
+async getLinearSettings() : Promise<Result<LinearSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_linear_settings") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Save the Linear settings
+ * 
+ * `api_key` replaces the stored key; an empty one removes it and `None` keeps it.
+ */
+async setLinearSettings(settings: LinearSettings, apiKey: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_linear_settings", { settings, apiKey }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List the teams the saved API key can see, which also checks the key
+ */
+async listLinearTeams() : Promise<Result<LinearTeam[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_linear_teams") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Called by the frontend on startup with the version its bindings were generated for
+ */
+async apiHandshake(clientApiVersion: number) : Promise<Result<ApiVersion, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("api_handshake", { clientApiVersion }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Export agents, schedules, settings, preferences, MCP servers and integration
+ * settings into one bundle encrypted with `passphrase`
+ * 
+ * Secrets stay in the keychain unless `include_secrets` is set, and so do settings that
+ * hold credentials, like chat webhook URLs.
+ */
+async exportAppBundle(bundlePath: string, passphrase: string, includeSecrets: boolean | null) : Promise<Result<BundleSummary, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_app_bundle", { bundlePath, passphrase, includeSecrets }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Decrypt a bundle and report what it contains without importing it
+ */
+async inspectAppBundle(bundlePath: string, passphrase: string) : Promise<Result<BundleSummary, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("inspect_app_bundle", { bundlePath, passphrase }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Import a bundle into this machine
+ * 
+ * Agents and schedules that already exist by name are kept; settings, preferences and
+ * `~/.claude` settings files are replaced by the bundled ones; MCP servers are added when
+ * missing. A part that fails is reported in the summary and the rest is still imported.
+ */
+async importAppBundle(bundlePath: string, passphrase: string) : Promise<Result<BundleImportSummary, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("import_app_bundle", { bundlePath, passphrase }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Gets checkpoint settings for a session
+ */
+async getCheckpointSettings(sessionId: string, projectId: string, projectPath: string) : Promise<Result<JsonValue, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_checkpoint_settings", { sessionId, projectId, projectPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Clears checkpoint manager for a session (cleanup on session end)
+ */
+async clearCheckpointManager(sessionId: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("clear_checkpoint_manager", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Gets checkpoint state statistics (for debugging/monitoring)
+ */
+async getCheckpointStateStats() : Promise<Result<JsonValue, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_checkpoint_state_stats") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List all agents
+ */
+async listAgents() : Promise<Result<Agent[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_agents") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Create a new agent
+ */
+async createAgent(name: string, icon: string, systemPrompt: string, defaultTask: string | null, model: string | null, enableFileRead: boolean | null, enableFileWrite: boolean | null, enableNetwork: boolean | null, hooks: string | null) : Promise<Result<Agent, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_agent", { name, icon, systemPrompt, defaultTask, model, enableFileRead, enableFileWrite, enableNetwork, hooks }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Update an existing agent; its permissions are left as they are when `permissions` is `None`
+ */
+async updateAgent(id: number, name: string, icon: string, systemPrompt: string, defaultTask: string | null, model: string | null, permissions: AgentPermissions | null, hooks: string | null) : Promise<Result<Agent, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_agent", { id, name, icon, systemPrompt, defaultTask, model, permissions, hooks }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Delete an agent
+ */
+async deleteAgent(id: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_agent", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get a single agent by ID
+ */
+async getAgent(id: number) : Promise<Result<Agent, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_agent", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Execute a CC agent with streaming output
+ */
+async executeAgent(agentId: number, projectPath: string, task: string, model: string | null, maxCostUsd: number | null, maxTokens: number | null) : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("execute_agent", { agentId, projectPath, task, model, maxCostUsd, maxTokens }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Execute a feature with multiple agents working in parallel
+ */
+async executeFeature(request: FeatureExecutionRequest) : Promise<Result<FeatureExecutionResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("execute_feature", { request }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List agent runs (optionally filtered by agent_id)
+ */
+async listAgentRuns(agentId: number | null) : Promise<Result<AgentRun[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_agent_runs", { agentId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get a single agent run by ID
+ */
+async getAgentRun(id: number) : Promise<Result<AgentRun, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_agent_run", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List agent runs with real-time metrics from JSONL
+ */
+async listAgentRunsWithMetrics(agentId: number | null) : Promise<Result<AgentRunWithMetrics[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_agent_runs_with_metrics", { agentId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get agent run with real-time metrics from JSONL
+ */
+async getAgentRunWithRealTimeMetrics(id: number) : Promise<Result<AgentRunWithMetrics, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_agent_run_with_real_time_metrics", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List all currently running agent sessions
+ */
+async listRunningSessions() : Promise<Result<AgentRun[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_running_sessions") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Kill a running agent session
+ */
+async killAgentSession(runId: number) : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("kill_agent_session", { runId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get the status of a specific agent session
+ */
+async getSessionStatus(runId: number) : Promise<Result<string | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_session_status", { runId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Cleanup finished processes and update their status
+ */
+async cleanupFinishedProcesses() : Promise<Result<number[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("cleanup_finished_processes") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get real-time output for a running session by reading its JSONL file with live output fallback
+ */
+async getSessionOutput(runId: number) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_session_output", { runId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get live output from a running process
+ */
+async getLiveSessionOutput(runId: number) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_live_session_output", { runId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Stream real-time session output by watching the JSONL file
+ */
+async streamSessionOutput(runId: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("stream_session_output", { runId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Load agent session history from JSONL file
+ * Similar to Claude Code's load_session_history, but searches across all project directories
+ */
+async loadAgentSessionHistory(sessionId: string) : Promise<Result<JsonValue[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("load_agent_session_history", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get the stored Claude binary path from settings
+ */
+async getClaudeBinaryPath() : Promise<Result<string | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_claude_binary_path") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Set the Claude binary path in settings
+ */
+async setClaudeBinaryPath(path: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_claude_binary_path", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Check whether the pinned Claude installation still exists and runs
+ */
+async getClaudeBinaryStatus() : Promise<Result<ClaudeBinaryStatus, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_claude_binary_status") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List all available Claude installations on the system
+ */
+async listClaudeInstallations() : Promise<Result<ClaudeInstallation[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_claude_installations") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Export a single agent to the versioned `.claudia.json` format
+ */
+async exportAgent(id: number) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_agent", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Export agent to file with native dialog
+ */
+async exportAgentToFile(id: number, filePath: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_agent_to_file", { id, filePath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Import an agent from JSON data
+ * 
+ * `on_conflict` controls what happens when an agent with the same name exists:
+ * `"rename"` (default) imports under a suffixed name, `"replace"` overwrites the
+ * existing agent and `"skip"` returns the existing agent unchanged.
+ */
+async importAgent(jsonData: string, onConflict: string | null) : Promise<Result<Agent, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("import_agent", { jsonData, onConflict }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Import agent from file
+ */
+async importAgentFromFile(filePath: string, onConflict: string | null) : Promise<Result<Agent, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("import_agent_from_file", { filePath, onConflict }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Fetch list of agents from a GitHub repository
+ * 
+ * Uses `repo` when given, otherwise the repository configured with `set_github_agent_repo`.
+ */
+async fetchGithubAgents(repo: string | null) : Promise<Result<GitHubAgentFile[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("fetch_github_agents", { repo }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Fetch and preview a specific agent from GitHub
+ */
+async fetchGithubAgentContent(downloadUrl: string) : Promise<Result<AgentExport, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("fetch_github_agent_content", { downloadUrl }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Import an agent directly from GitHub
+ */
+async importAgentFromGithub(downloadUrl: string) : Promise<Result<Agent, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("import_agent_from_github", { downloadUrl }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Import several selected agents from GitHub, continuing past individual failures
+ */
+async importAgentsFromGithub(downloadUrls: string[], onConflict: string | null) : Promise<Result<GitHubImportResult[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("import_agents_from_github", { downloadUrls, onConflict }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get the configured GitHub repository for agent imports
+ */
+async getGithubAgentRepo() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_github_agent_repo") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Set the GitHub repository used for agent imports (empty resets to the default)
+ */
+async setGithubAgentRepo(repo: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_github_agent_repo", { repo }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List all agent schedules
+ */
+async listAgentSchedules() : Promise<Result<AgentSchedule[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_agent_schedules") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Create a new agent schedule
+ */
+async createAgentSchedule(agentId: number, name: string, cronExpression: string, projectPaths: string[], task: string | null, model: string | null, enabled: boolean | null) : Promise<Result<AgentSchedule, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_agent_schedule", { agentId, name, cronExpression, projectPaths, task, model, enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Update an existing agent schedule
+ */
+async updateAgentSchedule(id: number, agentId: number, name: string, cronExpression: string, projectPaths: string[], task: string | null, model: string | null, enabled: boolean) : Promise<Result<AgentSchedule, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_agent_schedule", { id, agentId, name, cronExpression, projectPaths, task, model, enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Enable or disable an agent schedule
+ */
+async setAgentScheduleEnabled(id: number, enabled: boolean) : Promise<Result<AgentSchedule, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_agent_schedule_enabled", { id, enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Delete an agent schedule
+ */
+async deleteAgentSchedule(id: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_agent_schedule", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Run a schedule immediately, without changing its next scheduled time
+ */
+async runAgentScheduleNow(id: number) : Promise<Result<number[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("run_agent_schedule_now", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Preview the next times a cron expression will fire, in local time
+ */
+async previewCronSchedule(cronExpression: string, count: number | null) : Promise<Result<string[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("preview_cron_schedule", { cronExpression, count }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Whether scheduled runs are paused
+ */
+async getSchedulerPaused() : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_scheduler_paused") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Pause or resume all scheduled runs
+ */
+async setSchedulerPaused(paused: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_scheduler_paused", { paused }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List the global shortcuts and whether they are registered
+ */
+async listHotkeys() : Promise<Result<HotkeyBinding[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_hotkeys") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Change the shortcut of an action; `None` or an empty accelerator turns it off
+ * 
+ * Nothing is saved when the shortcut is invalid, used by another action or can't be
+ * registered with the system.
+ */
+async setHotkey(action: HotkeyAction, accelerator: string | null) : Promise<Result<HotkeyBinding[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_hotkey", { action, accelerator }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get the update settings
+ */
+async getUpdateSettings() : Promise<Result<UpdateSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_update_settings") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Save the update settings; switching channels forgets an update found on the old one
+ */
+async setUpdateSettings(settings: UpdateSettings) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_update_settings", { settings }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Check the configured channel for a newer release, returning it with its release notes
+ */
+async checkForUpdates() : Promise<Result<UpdateInfo | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("check_for_updates") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Download the update found by the last check without installing it
+ * 
+ * Emits `update-download-progress` while downloading. The package is installed by
+ * `install_update`, or when Claudia quits.
+ */
+async downloadUpdate() : Promise<Result<UpdateInfo, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("download_update") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Install the downloaded update, restarting Claudia when `restart` is set
+ * 
+ * Without a restart the new version starts the next time Claudia is opened.
+ */
+async installUpdate(restart: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("install_update", { restart }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get the crash reporting settings
+ */
+async getCrashReportSettings() : Promise<Result<CrashReportSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_crash_report_settings") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Give or withdraw consent to submit crash reports
+ */
+async setCrashReportSettings(settings: CrashReportSettings) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_crash_report_settings", { settings }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List stored crash reports, newest first
+ */
+async listCrashReports() : Promise<Result<CrashReport[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_crash_reports") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Show exactly what would be submitted for a report
+ */
+async previewCrashReport(id: string) : Promise<Result<CrashReport, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("preview_crash_report", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Record an error from the frontend, e.g. an unhandled exception
+ */
+async reportFrontendError(message: string, stack: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("report_frontend_error", { message, stack }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Submit unsent reports now; requires consent
+ */
+async submitCrashReports() : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("submit_crash_reports") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Delete all stored crash reports
+ */
+async deleteCrashReports() : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_crash_reports") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Read the app's log entries, oldest first
+ * 
+ * `level` is the least severe level to include ("error", "warn", "info"; default "info"),
+ * `since` an RFC 3339 timestamp, and `limit` caps the result to the newest entries.
+ */
+async getAppLogs(level: string | null, since: string | null, limit: number | null) : Promise<Result<LogEntry[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_app_logs", { level, since, limit }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Open the log directory in the system file manager
+ */
+async openLogDirectory() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("open_log_directory") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List all pipelines
+ */
+async listAgentPipelines() : Promise<Result<AgentPipeline[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_agent_pipelines") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Create a pipeline
+ */
+async createAgentPipeline(name: string, description: string | null, steps: PipelineStep[]) : Promise<Result<AgentPipeline, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_agent_pipeline", { name, description, steps }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Update a pipeline; runs already in progress keep the steps they started with
+ */
+async updateAgentPipeline(id: number, name: string, description: string | null, steps: PipelineStep[]) : Promise<Result<AgentPipeline, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_agent_pipeline", { id, name, description, steps }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Delete a pipeline and its run history
+ */
+async deleteAgentPipeline(id: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_agent_pipeline", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Start a pipeline against a project; steps run in the background
+ */
+async runAgentPipeline(pipelineId: number, projectPath: string, input: string | null) : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("run_agent_pipeline", { pipelineId, projectPath, input }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List pipeline runs, optionally for a single pipeline
+ */
+async listPipelineRuns(pipelineId: number | null) : Promise<Result<PipelineRun[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_pipeline_runs", { pipelineId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get a single pipeline run
+ */
+async getPipelineRun(id: number) : Promise<Result<PipelineRun, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_pipeline_run", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Resume a failed or cancelled pipeline run from the step that didn't complete
+ */
+async resumePipelineRun(id: number) : Promise<Result<PipelineRun, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("resume_pipeline_run", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Cancel a pipeline run, stopping the agent of the current step
+ */
+async cancelPipelineRun(id: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("cancel_pipeline_run", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get the maximum number of concurrently running agents (0 = unlimited)
+ */
+async getMaxConcurrentRuns() : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_max_concurrent_runs") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Set the maximum number of concurrently running agents (0 = unlimited)
+ */
+async setMaxConcurrentRuns(limit: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_max_concurrent_runs", { limit }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List queued agent runs in the order they will start
+ */
+async listQueuedAgentRuns() : Promise<Result<AgentRun[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_queued_agent_runs") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Reorder the queue; listed runs move to the front in the given order
+ */
+async reorderAgentRunQueue(runIds: number[]) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("reorder_agent_run_queue", { runIds }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Remove a run from the queue without starting it
+ */
+async removeQueuedAgentRun(runId: number) : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("remove_queued_agent_run", { runId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get the stored artifact manifest of a run
+ */
+async getRunArtifacts(runId: number) : Promise<Result<RunArtifact[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_run_artifacts", { runId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Re-collect the artifact manifest of a run
+ */
+async collectRunArtifacts(runId: number) : Promise<Result<RunArtifact[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("collect_run_artifacts", { runId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Export a run's artifacts as a `zip` archive or a git `patch`
+ */
+async exportRunArtifacts(runId: number, format: string, outputPath: string) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_run_artifacts", { runId, format, outputPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Compare duration, cost, tokens and changed files of two runs, e.g. to A/B test a prompt or model
+ */
+async compareAgentRuns(runIdA: number, runIdB: number) : Promise<Result<RunComparison, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("compare_agent_runs", { runIdA, runIdB }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List all versions of an agent, newest first
+ */
+async listAgentVersions(agentId: number) : Promise<Result<AgentVersion[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_agent_versions", { agentId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Diff two versions of an agent
+ */
+async diffAgentVersions(agentId: number, fromVersion: number, toVersion: number) : Promise<Result<AgentVersionDiff, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("diff_agent_versions", { agentId, fromVersion, toVersion }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Restore an agent to an earlier version; the rollback itself becomes a new version
+ */
+async rollbackAgent(agentId: number, version: number) : Promise<Result<Agent, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("rollback_agent", { agentId, version }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List runs that were interrupted by an app crash or restart
+ */
+async listInterruptedAgentRuns() : Promise<Result<AgentRun[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_interrupted_agent_runs") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Resume an interrupted run
+ * 
+ * Continues the Claude session with `--resume` when the run got far enough to have
+ * a session ID, otherwise starts the original task again. Returns the run ID.
+ */
+async resumeAgentRun(runId: number) : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("resume_agent_run", { runId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Give up on an interrupted run and mark it as failed
+ */
+async discardInterruptedAgentRun(runId: number) : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("discard_interrupted_agent_run", { runId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get the desktop notification settings for agent runs
+ */
+async getAgentNotificationSettings() : Promise<Result<AgentNotificationSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_agent_notification_settings") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Save the desktop notification settings for agent runs
+ */
+async setAgentNotificationSettings(settings: AgentNotificationSettings) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_agent_notification_settings", { settings }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get the stored logs of a run, oldest first, optionally filtered by stream
+ */
+async getRunLogs(runId: number, stream: string | null, limit: number | null) : Promise<Result<RunLogEntry[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_run_logs", { runId, stream, limit }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Run one agent task in several projects; runs past the concurrency limit are queued
+ * 
+ * The optional cost and token limits apply to each run separately.
+ */
+async executeAgentBatch(agentId: number, projectPaths: string[], task: string, model: string | null, maxCostUsd: number | null, maxTokens: number | null) : Promise<Result<AgentBatchStatus, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("execute_agent_batch", { agentId, projectPaths, task, model, maxCostUsd, maxTokens }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List batches, newest first
+ */
+async listAgentBatches() : Promise<Result<AgentBatch[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_agent_batches") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get a batch with per-project results
+ */
+async getAgentBatch(batchId: number) : Promise<Result<AgentBatchStatus, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_agent_batch", { batchId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Run an agent in plan mode; it can read the project but not modify it
+ */
+async executeAgentDryRun(agentId: number, projectPath: string, task: string, model: string | null) : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("execute_agent_dry_run", { agentId, projectPath, task, model }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get the plan and diff preview produced by a plan-only run
+ */
+async getAgentPlan(runId: number) : Promise<Result<AgentPlan, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_agent_plan", { runId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Approve a dry run's plan and start the real run that carries it out
+ */
+async approveAgentPlan(runId: number) : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("approve_agent_plan", { runId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getUsageStats(days: number | null) : Promise<Result<UsageStats, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_usage_stats", { days }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get the running totals of a streamed execution by its process registry run ID
+ */
+async getLiveCost(runId: number) : Promise<Result<AgentLiveMetrics | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_live_cost", { runId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getUsageByDateRange(startDate: string, endDate: string) : Promise<Result<UsageStats, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_usage_by_date_range", { startDate, endDate }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Entries of one project and/or day or date range, oldest first
+ */
+async getUsageDetails(projectPath: string | null, date: string | null, startDate: string | null, endDate: string | null) : Promise<Result<UsageEntry[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_usage_details", { projectPath, date, startDate, endDate }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getSessionStats(since: string | null, until: string | null, order: string | null) : Promise<Result<ProjectUsage[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_session_stats", { since, until, order }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Totals, daily series and model breakdown of every project within a date range
+ */
+async getUsageByProject(startDate: string | null, endDate: string | null) : Promise<Result<ProjectUsageBreakdown[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_usage_by_project", { startDate, endDate }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Spend of each agent within a date range
+ */
+async getUsageByAgent(startDate: string | null, endDate: string | null) : Promise<Result<AgentUsage[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_usage_by_agent", { startDate, endDate }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Compare cost and token efficiency of models on the same agent's or project's sessions
+ * 
+ * `group_by` is "agent" or "project". With `only_multi_model`, groups that only
+ * ever used one model are left out since there's nothing to compare.
+ */
+async compareModelCosts(groupBy: string, startDate: string | null, endDate: string | null, onlyMultiModel: boolean | null) : Promise<Result<ModelComparisonGroup[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("compare_model_costs", { groupBy, startDate, endDate, onlyMultiModel }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Usage of a single project within a date range
+ */
+async getProjectUsage(projectPath: string, startDate: string | null, endDate: string | null) : Promise<Result<UsageStats, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_project_usage", { projectPath, startDate, endDate }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Usage of a single session
+ */
+async getSessionUsage(sessionId: string) : Promise<Result<UsageStats, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_session_usage", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Export usage as per-day, per-project, per-model rows in "csv" or "json",
+ * or as a ccusage-compatible daily report with "ccusage"
+ */
+async exportUsage(format: string, startDate: string | null, endDate: string | null) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_usage", { format, startDate, endDate }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Export usage rows to a file
+ */
+async exportUsageToFile(format: string, startDate: string | null, endDate: string | null, filePath: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_usage_to_file", { format, startDate, endDate, filePath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Export usage in the JSON schema of ccusage's `daily`, `monthly` or `session` reports
+ */
+async exportUsageCcusage(report: string | null, since: string | null, until: string | null) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_usage_ccusage", { report, since, until }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Drop the usage index so the next query rescans every transcript
+ */
+async rebuildUsageIndex() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("rebuild_usage_index") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get the configured usage budgets
+ */
+async getUsageBudgets() : Promise<Result<UsageBudgets, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_usage_budgets") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Save the usage budgets
+ */
+async setUsageBudgets(budgets: UsageBudgets) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_usage_budgets", { budgets }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get current spend against each configured budget
+ */
+async getBudgetStatus() : Promise<Result<BudgetStatus[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_budget_status") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Render the usage report of a week; defaults to the last full week
+ */
+async generateUsageReport(weekStart: string | null, format: string | null) : Promise<Result<UsageReport, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("generate_usage_report", { weekStart, format }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Render a week's usage report into a file
+ */
+async exportUsageReport(weekStart: string | null, format: string | null, filePath: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_usage_report", { weekStart, format, filePath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get the weekly report schedule
+ */
+async getUsageReportSchedule() : Promise<Result<UsageReportSchedule, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_usage_report_schedule") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Save the weekly report schedule
+ */
+async setUsageReportSchedule(schedule: UsageReportSchedule) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_usage_report_schedule", { schedule }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get the price list used for cost calculation, including user overrides
+ */
+async getModelPricing() : Promise<Result<ModelPricingEntry[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_model_pricing") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Override the prices of models whose name contains `pattern`, or drop the override when `None`
+ */
+async setModelPricingOverride(pattern: string, pricing: ModelPricing | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_model_pricing_override", { pattern, pricing }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Adds a new MCP server
+ */
+async mcpAdd(name: string, transport: string, command: string | null, args: string[], env: Partial<{ [key in string]: string }>, url: string | null, scope: string) : Promise<Result<AddServerResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("mcp_add", { name, transport, command, args, env, url, scope }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Lists all configured MCP servers
+ */
+async mcpList() : Promise<Result<MCPServer[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("mcp_list") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Gets details for a specific MCP server
+ */
+async mcpGet(name: string) : Promise<Result<MCPServer, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("mcp_get", { name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Removes an MCP server, optionally only from the given scope
+ */
+async mcpRemove(name: string, scope: string | null) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("mcp_remove", { name, scope }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Adds an MCP server from JSON configuration
+ */
+async mcpAddJson(name: string, jsonConfig: string, scope: string) : Promise<Result<AddServerResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("mcp_add_json", { name, jsonConfig, scope }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Imports MCP servers from Claude Desktop
+ * 
+ * Only the servers in `names` are imported when given. Servers whose name is
+ * already configured in Claude Code are skipped rather than overwritten.
+ */
+async mcpAddFromClaudeDesktop(scope: string, names: string[] | null) : Promise<Result<ImportResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("mcp_add_from_claude_desktop", { scope, names }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Lists the MCP servers configured in Claude Desktop
+ */
+async mcpListClaudeDesktopServers() : Promise<Result<ClaudeDesktopServer[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("mcp_list_claude_desktop_servers") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Starts Claude Code as an MCP server
+ */
+async mcpServe() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("mcp_serve") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tests connection to an MCP server
+ */
+async mcpTestConnection(name: string) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("mcp_test_connection", { name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Launch or connect to a configured MCP server and check that it completes the handshake
+ */
+async testMcpConnection(name: string) : Promise<Result<McpConnectionTest, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("test_mcp_connection", { name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get recent output of an MCP server, oldest first
+ * 
+ * Combines the logs Claude Code writes while sessions use the server with
+ * the output Claudia captured itself, e.g. during connection tests.
+ */
+async getMcpLogs(name: string, limit: number | null) : Promise<Result<McpLogEntry[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_mcp_logs", { name, limit }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Search the MCP server registry; without a query every installable server is returned
+ */
+async searchMcpRegistry(query: string | null) : Promise<Result<RegistryServer[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("search_mcp_registry", { query }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Use a different registry index, or the default one when `None`
+ */
+async setMcpRegistryUrl(url: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_mcp_registry_url", { url }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Install a registry server into Claude Code's config
+ * 
+ * `env` holds the values the user entered for the server's variables. Secret
+ * ones go to the keychain and are referenced as `${NAME}`.
+ */
+async installMcpRegistryServer(server: RegistryServer, localName: string | null, scope: string, env: Partial<{ [key in string]: string }>) : Promise<Result<AddServerResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("install_mcp_registry_server", { server, localName, scope, env }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Begin signing in to an SSE server that requires OAuth
+ * 
+ * Returns the URL to open in the browser. The rest of the flow finishes in the
+ * background and emits `mcp-oauth-complete:{name}` when done.
+ */
+async startMcpOauth(name: string, clientId: string | null, scopes: string[] | null) : Promise<Result<McpOAuthStart, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("start_mcp_oauth", { name, clientId, scopes }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Whether Claudia holds an OAuth token for a server
+ */
+async getMcpOauthStatus(name: string) : Promise<Result<McpOAuthStatus, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_mcp_oauth_status", { name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Forget a server's OAuth token; the server needs to be authorized again to connect
+ */
+async revokeMcpOauth(name: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("revoke_mcp_oauth", { name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Check all configured MCP servers now
+ */
+async checkMcpServersNow() : Promise<Result<McpHealthCheck[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("check_mcp_servers_now") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get past health checks of a server, newest first
+ */
+async getMcpHealthHistory(name: string, limit: number | null) : Promise<Result<McpHealthCheck[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_mcp_health_history", { name, limit }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get the settings of the background MCP health checker
+ */
+async getMcpHealthSettings() : Promise<Result<McpHealthSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_mcp_health_settings") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Save the settings of the background MCP health checker
+ */
+async setMcpHealthSettings(settings: McpHealthSettings) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_mcp_health_settings", { settings }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Export all configured MCP servers as a JSON bundle
+ * 
+ * Unless `include_values` is set, env values are replaced by `${KEY}`
+ * references that resolve to keychain secrets on the importing machine.
+ */
+async exportMcpBundle(includeValues: boolean) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_mcp_bundle", { includeValues }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Export all configured MCP servers to a bundle file
+ */
+async exportMcpBundleToFile(includeValues: boolean, filePath: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_mcp_bundle_to_file", { includeValues, filePath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Import the servers of a JSON bundle
+ * 
+ * `scope` overrides the scope recorded for each server. Servers that already
+ * exist are skipped unless `overwrite` is set.
+ */
+async importMcpBundle(jsonData: string, scope: string | null, overwrite: boolean) : Promise<Result<McpBundleImportResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("import_mcp_bundle", { jsonData, scope, overwrite }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Import the servers of a bundle file
+ */
+async importMcpBundleFromFile(filePath: string, scope: string | null, overwrite: boolean) : Promise<Result<McpBundleImportResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("import_mcp_bundle_from_file", { filePath, scope, overwrite }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get the settings and state of the built-in MCP server
+ */
+async getClaudiaMcpServer() : Promise<Result<ClaudiaMcpServerStatus, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_claudia_mcp_server") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Enable or disable the built-in MCP server, starting or stopping it right away
+ */
+async setClaudiaMcpServer(enabled: boolean, port: number | null) : Promise<Result<ClaudiaMcpServerStatus, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_claudia_mcp_server", { enabled, port }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Resets project-scoped server approval choices
+ */
+async mcpResetProjectChoices() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("mcp_reset_project_choices") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Gets the status of MCP servers from their latest health checks
+ */
+async mcpGetServerStatus() : Promise<Result<Partial<{ [key in string]: ServerStatus }>, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("mcp_get_server_status") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Reads .mcp.json from the current project
+ */
+async mcpReadProjectConfig(projectPath: string) : Promise<Result<MCPProjectConfig, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("mcp_read_project_config", { projectPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Saves .mcp.json to the current project
+ */
+async mcpSaveProjectConfig(projectPath: string, config: MCPProjectConfig) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("mcp_save_project_config", { projectPath, config }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Gets which servers of a project's .mcp.json have been approved or rejected
+ */
+async mcpGetProjectApprovals(projectPath: string) : Promise<Result<MCPProjectApprovals, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("mcp_get_project_approvals", { projectPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Approves or rejects a server from a project's .mcp.json; `None` resets it to pending
+ */
+async mcpSetProjectApproval(projectPath: string, name: string, approved: boolean | null) : Promise<Result<MCPProjectApprovals, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("mcp_set_project_approval", { projectPath, name, approved }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List the names of stored secrets; values never leave the keychain through this
+ */
+async listSecrets() : Promise<Result<string[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_secrets") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Store a secret, replacing (rotating) its value if it already exists
+ * 
+ * Reference it from an MCP server's environment as `${NAME}`.
+ */
+async setSecret(name: string, value: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_secret", { name, value }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Delete a secret from the keychain
+ */
+async deleteSecret(name: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_secret", { name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List all tables in the database
+ */
+async storageListTables() : Promise<Result<TableInfo[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("storage_list_tables") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Read table data with pagination
+ */
+async storageReadTable(tableName: string, page: number, pageSize: number, searchQuery: string | null) : Promise<Result<TableData, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("storage_read_table", { tableName, page, pageSize, searchQuery }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Update a row in a table
+ */
+async storageUpdateRow(tableName: string, primaryKeyValues: Partial<{ [key in string]: JsonValue }>, updates: Partial<{ [key in string]: JsonValue }>) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("storage_update_row", { tableName, primaryKeyValues, updates }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Delete a row from a table
+ */
+async storageDeleteRow(tableName: string, primaryKeyValues: Partial<{ [key in string]: JsonValue }>) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("storage_delete_row", { tableName, primaryKeyValues }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Insert a new row into a table
+ */
+async storageInsertRow(tableName: string, values: Partial<{ [key in string]: JsonValue }>) : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("storage_insert_row", { tableName, values }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Execute a raw SQL query
+ */
+async storageExecuteSql(query: string) : Promise<Result<QueryResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("storage_execute_sql", { query }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Reset the entire database (with confirmation)
+ */
+async storageResetDatabase() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("storage_reset_database") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Discover all custom slash commands
+ */
+async slashCommandsList(projectPath: string | null) : Promise<Result<SlashCommand[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("slash_commands_list", { projectPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get a single slash command by ID
+ */
+async slashCommandGet(commandId: string, projectPath: string | null) : Promise<Result<SlashCommand, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("slash_command_get", { commandId, projectPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Create or update a slash command
+ */
+async slashCommandSave(scope: string, name: string, namespace: string | null, content: string, description: string | null, allowedTools: string[], projectPath: string | null, argumentHint: string | null, model: string | null) : Promise<Result<SlashCommand, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("slash_command_save", { scope, name, namespace, content, description, allowedTools, projectPath, argumentHint, model }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Delete a slash command
+ */
+async slashCommandDelete(commandId: string, projectPath: string | null) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("slash_command_delete", { commandId, projectPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+}
+}
+
+/** user-defined events **/
+
+
+
+/** user-defined constants **/
+
+export const COMMAND_API_VERSION = 1 as const;
+
+/** user-defined types **/
+
+/**
+ * Result of adding a server
+ */
+export type AddServerResult = { success: boolean; message: string; server_name: string | null }
+/**
+ * Represents a CC Agent stored in the database
+ */
+export type Agent = { id: number | null; name: string; icon: string; system_prompt: string; default_task: string | null; model: string; enable_file_read: boolean; enable_file_write: boolean; enable_network: boolean; hooks: string | null; created_at: string; updated_at: string }
+/**
+ * The same agent task fanned out across several projects
+ */
+export type AgentBatch = { id: number; agent_id: number; agent_name: string; task: string; model: string | null; project_paths: string[]; created_at: string }
+/**
+ * A batch with the aggregated results of its runs
+ */
+export type AgentBatchStatus = { batch: AgentBatch; results: BatchProjectResult[]; succeeded: number; failed: number; 
+/**
+ * Runs that are queued, pending or running
+ */
+in_progress: number; total_cost_usd: number }
+/**
+ * Agent data within export
+ */
+export type AgentData = { name: string; icon: string; system_prompt: string; default_task: string | null; model: string; hooks: string | null; 
+/**
+ * Permissions, absent in version 1 exports (defaults match `create_agent`)
+ */
+permissions?: AgentPermissions | null }
+/**
+ * Agent export format
+ */
+export type AgentExport = { version: number; exported_at: string; agent: AgentData; 
+/**
+ * Provenance information, absent in version 1 exports
+ */
+metadata?: AgentExportMetadata | null }
+/**
+ * Metadata describing where an exported agent came from
+ */
+export type AgentExportMetadata = { app_version: string | null; created_at: string | null; updated_at: string | null }
+/**
+ * One field that differs between two versions
+ */
+export type AgentFieldChange = { field: string; old_value: string; new_value: string; 
+/**
+ * Unified diff for multi-line fields such as the system prompt
+ */
+diff: string | null }
+/**
+ * Payload of the `agent:metrics` event
+ */
+export type AgentLiveMetrics = { run_id: number; input_tokens: number; output_tokens: number; cache_creation_tokens: number; cache_read_tokens: number; total_tokens: number; estimated_cost_usd: number; elapsed_ms: number; message_count: number; 
+/**
+ * Whether the final `result` event reported an error; `None` until it arrives
+ */
+is_error: boolean | null; 
+/**
+ * True for the last event of a run
+ */
+finished: boolean }
+/**
+ * Opt-in desktop notifications for finished agent runs
+ */
+export type AgentNotificationSettings = { enabled: boolean; 
+/**
+ * Only notify when a run fails
+ */
+only_on_failure?: boolean }
+/**
+ * Permission flags carried in an agent export
+ */
+export type AgentPermissions = { enable_file_read: boolean; enable_file_write: boolean; enable_network: boolean }
+/**
+ * A named chain of agent steps
+ */
+export type AgentPipeline = { id: number | null; name: string; description: string | null; steps: PipelineStep[]; created_at: string; updated_at: string }
+/**
+ * Result of a plan-only run
+ */
+export type AgentPlan = { run_id: number; status: string; plan: string | null; proposed_changes: ProposedChange[]; 
+/**
+ * Run started after the plan was approved
+ */
+approved_run_id: number | null }
+/**
+ * Represents an agent execution run
+ */
+export type AgentRun = { id: number | null; agent_id: number; agent_name: string; agent_icon: string; task: string; model: string; project_path: string; session_id: string; status: string; pid: number | null; process_started_at: string | null; created_at: string; completed_at: string | null }
+/**
+ * Represents runtime metrics calculated from JSONL
+ */
+export type AgentRunMetrics = { duration_ms: number | null; total_tokens: number | null; cost_usd: number | null; message_count: number | null }
+/**
+ * Combined agent run with real-time metrics
+ */
+export type AgentRunWithMetrics = ({ id: number | null; agent_id: number; agent_name: string; agent_icon: string; task: string; model: string; project_path: string; session_id: string; status: string; pid: number | null; process_started_at: string | null; created_at: string; completed_at: string | null }) & { metrics: AgentRunMetrics | null; output: string | null }
+/**
+ * A recurring agent run
+ */
+export type AgentSchedule = { id: number | null; agent_id: number; name: string; cron_expression: string; project_paths: string[]; task: string | null; model: string | null; enabled: boolean; last_run_at: string | null; next_run_at: string | null; last_error: string | null; created_at: string; updated_at: string }
+export type AgentUsage = { agent_id: number; agent_name: string; total_cost: number; total_tokens: number; 
+/**
+ * Distinct sessions, i.e. runs
+ */
+run_count: number; last_used: string }
+/**
+ * A saved state of an agent
+ */
+export type AgentVersion = { id: number; agent_id: number; version: number; name: string; icon: string; system_prompt: string; default_task: string | null; model: string; enable_file_read: boolean; enable_file_write: boolean; enable_network: boolean; hooks: string | null; change_note: string | null; created_at: string }
+/**
+ * Differences between two versions of an agent
+ */
+export type AgentVersionDiff = { agent_id: number; from_version: number; to_version: number; changes: AgentFieldChange[] }
+/**
+ * Issues of all configured providers, newest first
+ */
+export type AggregatedIssues = { issues: Issue[]; errors: ProviderError[] }
+/**
+ * What the backend reports during the handshake
+ */
+export type ApiVersion = { api_version: number; min_client_api_version: number; app_version: string }
+/**
+ * A migration recorded as applied
+ */
+export type AppliedMigration = { version: number; description: string; applied_at: string }
+/**
+ * Space taken by archived sessions compared to their original transcripts
+ */
+export type ArchiveStats = { archived_sessions: number; original_bytes: number; compressed_bytes: number; saved_bytes: number; 
+/**
+ * Compressed size as a fraction of the original size
+ */
+ratio: number }
+/**
+ * CPU and I/O priority of unattended agent runs
+ */
+export type BackgroundPriority = { enabled: boolean; 
+/**
+ * Unix niceness from 1 to 19; Windows always uses BELOW_NORMAL
+ */
+niceness: number; 
+/**
+ * Only schedule disk I/O when the disk is otherwise idle (Linux only)
+ */
+idle_io: boolean }
+/**
+ * A unit of background work, as reported by `background-task` events
+ */
+export type BackgroundTask = { id: string; 
+/**
+ * What the task does, e.g. "mcp-health-check"; at most one task per kind is
+ * pending when submitted with [`TaskQueue::submit_once`]
+ */
+kind: string; label: string; priority: TaskPriority; status: TaskStatus; 
+/**
+ * Fraction done from 0 to 1, when the task reports it
+ */
+progress: number | null; message: string | null; error: string | null; created_at: string; started_at: string | null; finished_at: string | null }
+/**
+ * Contents of a backup archive, stored as `manifest.json`
+ */
+export type BackupManifest = { version: number; app_version: string; created_at: string; agents: number; settings_files: number; checkpoint_files: number }
+/**
+ * Outcome of one project in a batch
+ */
+export type BatchProjectResult = { project_path: string; run_id: number | null; 
+/**
+ * Run status, or "not_started" if the run could not be created
+ */
+status: string; 
+/**
+ * Why the run could not be started
+ */
+start_error: string | null; 
+/**
+ * Final message of the run
+ */
+result: string | null; is_error: boolean; metrics: AgentRunMetrics | null }
+/**
+ * Spend against one budget
+ */
+export type BudgetStatus = { 
+/**
+ * "daily", "weekly" or "monthly"
+ */
+period: string; period_start: string; limit: number; spent: number; percent: number }
+/**
+ * What an import changed
+ */
+export type BundleImportSummary = { agents_imported: number; 
+/**
+ * Agents kept as they are because one with the same name exists
+ */
+agents_skipped: number; schedules_imported: number; settings_applied: number; preferences_applied: number; claude_settings_files_restored: number; mcp_servers_added: number; secrets_restored: number; 
+/**
+ * Parts that could not be imported, with the reason
+ */
+errors: string[] }
+/**
+ * What a bundle contains
+ */
+export type BundleSummary = { app_version: string; created_at: string; agents: number; schedules: number; settings: number; preferences: number; claude_settings_files: number; mcp_servers: number; secrets: number }
+/**
+ * Decision for one tool call and the rule behind it
+ */
+export type CallDecision = { call: ToolCall; decision: PermissionDecision; matched_rule: string | null }
+/**
+ * Per-agent settings of a chat integration that take precedence over the global ones
+ */
+export type ChatAgentOverride = { 
+/**
+ * Post about this agent's runs even when they are off globally, or silence it
+ */
+enabled: boolean; 
+/**
+ * Post to another channel's webhook instead of the global one
+ */
+webhook_url?: string | null }
+/**
+ * Settings of a chat webhook integration (Slack, Discord), stored as JSON in app_settings
+ */
+export type ChatIntegrationSettings = { webhook_url: string; 
+/**
+ * Post when agent runs finish, unless an agent override says otherwise
+ */
+notify_runs: boolean; 
+/**
+ * Only post about runs that failed
+ */
+only_on_failure?: boolean; 
+/**
+ * Post when a usage budget threshold is crossed
+ */
+notify_budget_alerts?: boolean; agent_overrides?: Partial<{ [key in number]: ChatAgentOverride }> }
+/**
+ * Outcome of a single environment check
+ */
+export type CheckStatus = "ok" | 
+/**
+ * Optional tooling is missing; the related features will not work
+ */
+"warning" | 
+/**
+ * Claudia cannot run sessions until this is fixed
+ */
+"error"
+/**
+ * Represents a checkpoint in the session timeline
+ */
+export type Checkpoint = { 
+/**
+ * Unique identifier for the checkpoint
+ */
+id: string; 
+/**
+ * Session ID this checkpoint belongs to
+ */
+sessionId: string; 
+/**
+ * Project ID for the session
+ */
+projectId: string; 
+/**
+ * Index of the last message in this checkpoint
+ */
+messageIndex: number; 
+/**
+ * Timestamp when checkpoint was created
+ */
+timestamp: string; 
+/**
+ * User-provided description
+ */
+description: string | null; 
+/**
+ * Parent checkpoint ID for fork tracking
+ */
+parentCheckpointId: string | null; 
+/**
+ * Metadata about the checkpoint
+ */
+metadata: CheckpointMetadata }
+/**
+ * Diff between two checkpoints
+ */
+export type CheckpointDiff = { 
+/**
+ * Source checkpoint ID
+ */
+from_checkpoint_id: string; 
+/**
+ * Target checkpoint ID
+ */
+to_checkpoint_id: string; 
+/**
+ * Files that were modified
+ */
+modified_files: FileDiff[]; 
+/**
+ * Files that were added
+ */
+added_files: string[]; 
+/**
+ * Files that were deleted
+ */
+deleted_files: string[]; 
+/**
+ * Token usage difference
+ */
+token_delta: number }
+/**
+ * Metadata associated with a checkpoint
+ */
+export type CheckpointMetadata = { 
+/**
+ * Total tokens used up to this point
+ */
+totalTokens: number; 
+/**
+ * Model used for the last operation
+ */
+modelUsed: string; 
+/**
+ * The user prompt that led to this state
+ */
+userPrompt: string; 
+/**
+ * Number of file changes in this checkpoint
+ */
+fileChanges: number; 
+/**
+ * Size of all file snapshots in bytes
+ */
+snapshotSize: number }
+/**
+ * Result of a checkpoint operation
+ */
+export type CheckpointResult = { 
+/**
+ * The created/restored checkpoint
+ */
+checkpoint: Checkpoint; 
+/**
+ * Number of files snapshot/restored
+ */
+files_processed: number; 
+/**
+ * Any warnings during the operation
+ */
+warnings: string[] }
+/**
+ * Strategy for automatic checkpoint creation
+ */
+export type CheckpointStrategy = 
+/**
+ * Only create checkpoints manually
+ */
+"manual" | 
+/**
+ * Create checkpoint after each user prompt
+ */
+"per_prompt" | 
+/**
+ * Create checkpoint after each tool use
+ */
+"per_tool_use" | 
+/**
+ * Create checkpoint after destructive operations
+ */
+"smart" | 
+/**
+ * Create checkpoint once a number of tokens was used since the last one
+ */
+"token_threshold"
+/**
+ * Whether the pinned Claude installation can still be launched
+ */
+export type ClaudeBinaryStatus = { 
+/**
+ * The pinned path, `None` when no installation is pinned
+ */
+path: string | null; valid: boolean; version: string | null; error: string | null }
+/**
+ * An MCP server found in Claude Desktop's configuration
+ */
+export type ClaudeDesktopServer = { name: string; command: string | null; args: string[]; env: Partial<{ [key in string]: string }>; 
+/**
+ * A server with this name is already configured in Claude Code
+ */
+already_configured: boolean }
+/**
+ * Represents a Claude installation with metadata
+ */
+export type ClaudeInstallation = { 
+/**
+ * Full path to the Claude binary (or "claude-code" for sidecar)
+ */
+path: string; 
+/**
+ * Version string if available
+ */
+version: string | null; 
+/**
+ * Source of discovery (e.g., "nvm", "system", "homebrew", "which", "bundled")
+ */
+source: string; 
+/**
+ * Type of installation
+ */
+installation_type: InstallationType }
+/**
+ * Represents a CLAUDE.md file found in the project
+ */
+export type ClaudeMdFile = { 
+/**
+ * Relative path from the project root
+ */
+relative_path: string; 
+/**
+ * Absolute path to the file
+ */
+absolute_path: string; 
+/**
+ * File size in bytes
+ */
+size: number; 
+/**
+ * Last modified timestamp
+ */
+modified: number }
+/**
+ * A CLAUDE.md file and where it applies
+ */
+export type ClaudeMdLocation = ({ 
+/**
+ * Relative path from the project root
+ */
+relative_path: string; 
+/**
+ * Absolute path to the file
+ */
+absolute_path: string; 
+/**
+ * File size in bytes
+ */
+size: number; 
+/**
+ * Last modified timestamp
+ */
+modified: number }) & { 
+/**
+ * "global" for ~/.claude/CLAUDE.md, otherwise "project"
+ */
+scope: string; 
+/**
+ * Root of the project the file belongs to
+ */
+project_path: string | null }
+/**
+ * Represents the settings from ~/.claude/settings.json
+ */
+export type ClaudeSettings = (null | boolean | number | string | JsonValue[] | Partial<{ [key in string]: JsonValue }>)
+/**
+ * Represents the Claude Code version status
+ */
+export type ClaudeVersionStatus = { 
+/**
+ * Whether Claude Code is installed and working
+ */
+is_installed: boolean; 
+/**
+ * The version string if available
+ */
+version: string | null; 
+/**
+ * The full output from the command
+ */
+output: string }
+/**
+ * Settings of the built-in MCP server, stored under `claudia_mcp_server` in app_settings
+ */
+export type ClaudiaMcpServerSettings = { enabled: boolean; port: number; 
+/**
+ * Bearer token clients must send; generated the first time the server is enabled
+ */
+token?: string }
+/**
+ * Settings plus whether the server is currently listening
+ */
+export type ClaudiaMcpServerStatus = { settings: ClaudiaMcpServerSettings; running: boolean; url: string; 
+/**
+ * Command that registers the server with Claude Code
+ */
+add_command: string }
+/**
+ * Outcome of a checkpoint cleanup
+ */
+export type CleanupReport = { removedCheckpoints: number; 
+/**
+ * Snapshot contents no checkpoint references anymore
+ */
+removedContentFiles: number; reclaimedBytes: number }
+/**
+ * Represents metadata about a table column
+ */
+export type ColumnInfo = { cid: number; name: string; type_name: string; notnull: boolean; dflt_value: string | null; pk: boolean }
+/**
+ * One run in a comparison
+ */
+export type ComparedRun = { run: AgentRun; metrics: AgentRunMetrics; artifacts: RunArtifact[] }
+/**
+ * A panic or reported error, written to `~/.claude/claudia/crash-reports`
+ */
+export type CrashReport = { id: string; 
+/**
+ * "panic" or "error"
+ */
+kind: string; created_at: string; app_version: string; os: string; arch: string; thread: string | null; message: string; 
+/**
+ * `file:line` of a panic
+ */
+location: string | null; backtrace: string | null; submitted?: boolean }
+export type CrashReportSettings = { 
+/**
+ * The user agreed to submit scrubbed reports
+ */
+submit_reports: boolean }
+export type DailyUsage = { date: string; total_cost: number; total_tokens: number; models_used: string[] }
+/**
+ * Settings of scheduled maintenance, stored under `db_maintenance_settings`
+ */
+export type DbMaintenanceSettings = { enabled: boolean; 
+/**
+ * Hours between maintenance runs
+ */
+interval_hours: number }
+/**
+ * Where a `claudia://` link points
+ * 
+ * Links only navigate: `AgentRun` opens a prefilled run form instead of starting the
+ * agent, since any web page or document can open a link.
+ */
+export type DeepLinkTarget = { kind: "project"; project_id: string } | { kind: "session"; session_id: string; project_id: string | null } | { kind: "agent"; agent_id: number } | { kind: "agent_run"; name: string; project: string | null; task: string | null } | { kind: "run"; run_id: number }
+/**
+ * SMTP server and what gets emailed; the password lives in the keychain
+ */
+export type EmailSettings = { enabled: boolean; host: string; port: number; 
+/**
+ * "starttls", "tls" (implicit, usually port 465) or "none"
+ */
+security: string; username?: string; 
+/**
+ * Sender, e.g. `Claudia <claudia@example.com>`
+ */
+from: string; to: string[]; 
+/**
+ * Email the result of every run started by a schedule
+ */
+notify_scheduled_runs: boolean; 
+/**
+ * Only email scheduled runs that failed
+ */
+only_on_failure?: boolean; 
+/**
+ * Email the weekly usage report when it is generated
+ */
+send_usage_reports?: boolean; 
+/**
+ * Whether a password is stored; filled in when reading the settings
+ */
+has_password?: boolean }
+/**
+ * An environment variable set for every Claude process of a project
+ */
+export type EnvVar = { key: string; value: string }
+/**
+ * One line of the setup checklist
+ */
+export type EnvironmentCheck = { id: string; label: string; status: CheckStatus; version: string | null; message: string }
+/**
+ * All checks and whether none of them failed
+ */
+export type EnvironmentReport = { checks: EnvironmentCheck[]; healthy: boolean }
+/**
+ * Structure for feature execution request
+ */
+export type FeatureExecutionRequest = { directory: string; ticket: string; agent_count: number }
+/**
+ * Structure for feature execution result
+ */
+export type FeatureExecutionResult = { branch_names: string[]; run_ids: number[]; issue_number: string | null; issue_url: string | null }
+/**
+ * How a file changed in each of the two runs
+ */
+export type FileComparison = { path: string; change_a: string | null; change_b: string | null; 
+/**
+ * Both runs left the file with identical content
+ */
+same_result: boolean; diff_a: string | null; diff_b: string | null }
+/**
+ * Diff for a single file
+ */
+export type FileDiff = { 
+/**
+ * File path
+ */
+path: string; 
+/**
+ * Number of additions
+ */
+additions: number; 
+/**
+ * Number of deletions
+ */
+deletions: number; 
+/**
+ * Unified diff content (optional)
+ */
+diff_content: string | null }
+/**
+ * Represents a file or directory entry
+ */
+export type FileEntry = { 
+/**
+ * The name of the file or directory
+ */
+name: string; 
+/**
+ * The full path
+ */
+path: string; 
+/**
+ * Whether this is a directory
+ */
+is_directory: boolean; 
+/**
+ * File size in bytes (0 for directories)
+ */
+size: number; 
+/**
+ * File extension (if applicable)
+ */
+extension: string | null }
+/**
+ * A session that touched a file
+ */
+export type FileSessionMatch = { session_id: string; project_path: string; file_path: string; read: boolean; written: boolean; touch_count: number; first_touched_at: string | null; last_touched_at: string | null }
+/**
+ * Represents a GitHub agent file from the API
+ */
+export type GitHubAgentFile = { name: string; path: string; download_url: string; size: number; sha: string }
+/**
+ * Outcome of importing one agent from GitHub
+ */
+export type GitHubImportResult = { download_url: string; agent: Agent | null; error: string | null }
+export type HistoryImportResult = { imported: ImportedSession[]; 
+/**
+ * Conversations imported before
+ */
+skipped: number; 
+/**
+ * Conversations that could not be imported, with the reason
+ */
+failed: string[] }
+/**
+ * A single command run by a hook
+ */
+export type HookCommand = { type: string; command: string; timeout?: number | null }
+/**
+ * A ready-made hook that can be installed into any scope
+ */
+export type HookTemplate = { id: string; name: string; description: string; event: string; matcher: string | null; command: string }
+/**
+ * What a global shortcut does
+ */
+export type HotkeyAction = 
+/**
+ * Open the quick prompt for the project of the latest session
+ */
+"quick_prompt" | 
+/**
+ * Show Claudia, or hide it when it is focused
+ */
+"toggle_window"
+/**
+ * A shortcut as shown in the settings
+ */
+export type HotkeyBinding = { action: HotkeyAction; label: string; 
+/**
+ * e.g. `CommandOrControl+Shift+Space`; `None` when the shortcut is off
+ */
+accelerator: string | null; suggested_accelerator: string; 
+/**
+ * Whether the shortcut is currently registered with the system
+ */
+registered: boolean }
+/**
+ * A conversation found in an export, before importing it
+ */
+export type ImportPreview = { title: string; project_path: string | null; started_at: string | null; message_count: number; already_imported: boolean }
+/**
+ * Import result for multiple servers
+ */
+export type ImportResult = { imported_count: number; failed_count: number; 
+/**
+ * Servers left alone because their name was already configured
+ */
+skipped_count: number; servers: ImportServerResult[] }
+/**
+ * Result for individual server import
+ */
+export type ImportServerResult = { name: string; success: boolean; error: string | null }
+/**
+ * Tool a conversation is imported from
+ */
+export type ImportSource = 
+/**
+ * Markdown written by Cursor's "Export Chat"
+ */
+"cursor" | 
+/**
+ * `.aider.chat.history.md` in a project
+ */
+"aider" | 
+/**
+ * Session files in `~/.continue/sessions`
+ */
+"continue"
+/**
+ * A conversation written as a Claude session
+ */
+export type ImportedSession = { session_id: string; project_path: string; title: string }
+/**
+ * Type of Claude installation
+ */
+export type InstallationType = 
+/**
+ * Bundled sidecar binary (preferred)
+ */
+"Bundled" | 
+/**
+ * System-installed binary
+ */
+"System" | 
+/**
+ * Custom path specified by user
+ */
+"Custom"
+/**
+ * An issue from any tracker, in the shape the aggregated issue list shows
+ */
+export type Issue = { 
+/**
+ * Provider id, e.g. "jira"
+ */
+provider: string; 
+/**
+ * Key shown to users, e.g. "PROJ-12"
+ */
+key: string; title: string; body: string | null; url: string; state: string; assignee: string | null; labels?: string[]; updated_at: string | null; 
+/**
+ * Local repository the issue's tracker project is mapped to
+ */
+project_path: string | null }
+/**
+ * Jira Cloud connection; the API token lives in the keychain
+ */
+export type JiraSettings = { enabled: boolean; 
+/**
+ * e.g. `https://example.atlassian.net`
+ */
+site_url: string; 
+/**
+ * Atlassian account email the API token belongs to
+ */
+email: string; 
+/**
+ * Search for the issue list; empty uses open issues assigned to the user
+ */
+jql?: string; 
+/**
+ * Jira project key to local repository path
+ */
+project_repos?: Partial<{ [key in string]: string }>; 
+/**
+ * Whether an API token is stored; filled in when reading the settings
+ */
+has_token?: boolean }
+export type JsonValue = null | boolean | number | string | JsonValue[] | Partial<{ [key in string]: JsonValue }>
+/**
+ * Linear teams to aggregate; the API key lives in the keychain
+ */
+export type LinearSettings = { enabled: boolean; 
+/**
+ * Keys of the teams whose open issues are listed, e.g. "ENG"
+ */
+team_keys: string[]; 
+/**
+ * Team key to local repository path
+ */
+team_repos?: Partial<{ [key in string]: string }>; 
+/**
+ * Team that issues created from sessions go to when no repository maps to one
+ */
+default_team_key?: string | null; 
+/**
+ * Whether an API key is stored; filled in when reading the settings
+ */
+has_api_key?: boolean }
+/**
+ * A Linear team the API key can see
+ */
+export type LinearTeam = { key: string; name: string }
+/**
+ * One line of the log file
+ */
+export type LogEntry = { 
+/**
+ * RFC 3339
+ */
+timestamp: string; level: string; 
+/**
+ * Module the entry came from, e.g. `claudia_lib::commands::scheduler`
+ */
+target: string; message: string }
+/**
+ * Approval state of all servers in a project's .mcp.json
+ */
+export type MCPProjectApprovals = { 
+/**
+ * Every project server is trusted without asking
+ */
+enable_all: boolean; servers: ProjectServerApproval[] }
+/**
+ * MCP configuration for project scope (.mcp.json)
+ */
+export type MCPProjectConfig = { mcpServers: Partial<{ [key in string]: MCPServerConfig }> }
+/**
+ * Represents an MCP server configuration
+ */
+export type MCPServer = { 
+/**
+ * Server name/identifier
+ */
+name: string; 
+/**
+ * Transport type: "stdio" or "sse"
+ */
+transport: string; 
+/**
+ * Command to execute (for stdio)
+ */
+command: string | null; 
+/**
+ * Command arguments (for stdio)
+ */
+args: string[]; 
+/**
+ * Environment variables
+ */
+env: Partial<{ [key in string]: string }>; 
+/**
+ * URL endpoint (for SSE)
+ */
+url: string | null; 
+/**
+ * Headers sent with every request, as `Name: value` (for SSE)
+ */
+headers?: string[]; 
+/**
+ * Configuration scope: "local", "project", or "user"
+ */
+scope: string; 
+/**
+ * Whether the server is currently active
+ */
+is_active: boolean; 
+/**
+ * Server status
+ */
+status: ServerStatus }
+/**
+ * Individual server configuration in .mcp.json
+ */
+export type MCPServerConfig = { 
+/**
+ * Transport type; omitted for stdio servers
+ */
+type?: string | null; command: string; args?: string[]; env?: Partial<{ [key in string]: string }>; 
+/**
+ * URL endpoint (for SSE)
+ */
+url?: string | null }
+/**
+ * Outcome of a maintenance run, the latest kept under `db_maintenance_last_report`
+ */
+export type MaintenanceReport = { size_before: number; size_after: number; reclaimed_bytes: number; 
+/**
+ * `ok`, or the problems `PRAGMA integrity_check` found
+ */
+integrity: string[]; fts_tables_optimized: string[]; duration_ms: number; ran_at: string }
+/**
+ * Result of importing a bundle
+ */
+export type McpBundleImportResult = ({ imported_count: number; failed_count: number; 
+/**
+ * Servers left alone because their name was already configured
+ */
+skipped_count: number; servers: ImportServerResult[] }) & { 
+/**
+ * Referenced secrets not yet stored in this machine's keychain
+ */
+missing_secrets: string[] }
+/**
+ * Outcome of connecting to an MCP server and running the initialize handshake
+ */
+export type McpConnectionTest = { name: string; transport: string; success: boolean; 
+/**
+ * Time until the initialize response arrived
+ */
+latency_ms: number | null; server_name: string | null; server_version: string | null; protocol_version: string | null; tools: McpToolInfo[]; error: string | null; 
+/**
+ * Tail of the server's stderr, for stdio servers
+ */
+stderr: string | null }
+/**
+ * One health check of a server
+ */
+export type McpHealthCheck = { server_name: string; healthy: boolean; latency_ms: number | null; tool_count: number | null; error: string | null; checked_at: string }
+/**
+ * Settings of the background health checker, stored under `mcp_health_settings`
+ */
+export type McpHealthSettings = { enabled: boolean; 
+/**
+ * Seconds between checks of all servers
+ */
+interval_secs: number }
+/**
+ * One line of MCP server output
+ */
+export type McpLogEntry = { server_name: string; 
+/**
+ * "claude" for logs Claude Code wrote during sessions, "claudia" for output captured here
+ */
+source: string; 
+/**
+ * "debug", "info", "error" or "stderr"
+ */
+level: string; message: string; session_id: string | null; cwd: string | null; timestamp: string }
+/**
+ * Where to send the user to sign in
+ */
+export type McpOAuthStart = { authorization_url: string; redirect_uri: string }
+/**
+ * Whether Claudia holds a token for a server
+ */
+export type McpOAuthStatus = { name: string; authorized: boolean; expires_at: number | null; 
+/**
+ * Variable the server's Authorization header refers to
+ */
+env_var: string }
+/**
+ * A tool advertised by an MCP server
+ */
+export type McpToolInfo = { name: string; description: string | null }
+/**
+ * Differences in metrics, as `b - a`
+ */
+export type MetricsDelta = { duration_ms: number | null; total_tokens: number | null; cost_usd: number | null; message_count: number | null }
+/**
+ * Models used for the same agent or project, cheapest first
+ */
+export type ModelComparisonGroup = { 
+/**
+ * Agent name or project path
+ */
+group: string; models: ModelEfficiency[] }
+/**
+ * How one model performed on a group's sessions
+ */
+export type ModelEfficiency = { model: string; session_count: number; total_cost: number; total_tokens: number; output_tokens: number; avg_cost_per_session: number; avg_tokens_per_session: number; 
+/**
+ * Cost per thousand output tokens, comparable across task sizes
+ */
+cost_per_1k_output_tokens: number; 
+/**
+ * Average session cost relative to the cheapest model of the group
+ */
+relative_cost: number }
+/**
+ * Prices in USD per million tokens
+ */
+export type ModelPricing = { input: number; output: number; cache_write: number; cache_read: number }
+/**
+ * A row of the price list shown in settings
+ */
+export type ModelPricingEntry = { pattern: string; pricing: ModelPricing; 
+/**
+ * Set by the user rather than built in
+ */
+overridden: boolean }
+export type ModelUsage = { model: string; total_cost: number; total_tokens: number; input_tokens: number; output_tokens: number; cache_creation_tokens: number; cache_read_tokens: number; cache_savings: number; session_count: number }
+/**
+ * A Claude process started by an earlier run of Claudia that is still running
+ */
+export type OrphanedProcess = { pid: number; 
+/**
+ * Start time in seconds since the epoch, telling the process apart from a reused PID
+ */
+start_time: number; 
+/**
+ * "agent" or "session"
+ */
+kind: string; run_id: number | null; session_id: string | null; project_path: string; spawned_at: string }
+/**
+ * One line of process output, tagged with its stream and position in the run
+ */
+export type OutputLine = { 
+/**
+ * "stdout", "stderr" or "lifecycle"
+ */
+stream: string; seq: number; line: string; timestamp: string }
+/**
+ * A rule split into the tool and the optional specifier in parentheses
+ */
+export type ParsedRule = { tool: string; specifier: string | null }
+/**
+ * How a rule set treats a tool or tool call
+ */
+export type PermissionDecision = "allow" | "ask" | "deny" | 
+/**
+ * No rule applies, so Claude asks as usual
+ */
+"default"
+/**
+ * What a rule set would permit
+ */
+export type PermissionPreview = { tools: ToolAccess[]; calls: CallDecision[] }
+/**
+ * The allow/ask/deny lists of a settings file
+ */
+export type PermissionRules = { allow?: string[]; ask?: string[]; deny?: string[] }
+/**
+ * One execution of a pipeline against a project
+ */
+export type PipelineRun = { id: number | null; pipeline_id: number; pipeline_name: string; project_path: string; input: string | null; steps: PipelineStep[]; status: string; current_step: number; step_run_ids: number[]; step_outputs: string[]; error: string | null; created_at: string; completed_at: string | null }
+/**
+ * One agent invocation within a pipeline
+ * 
+ * `task` may reference `{{input}}` (the pipeline input) and `{{previous_output}}`
+ * (the result of the previous step). When a later step doesn't reference the
+ * previous output, it is appended to the task.
+ */
+export type PipelineStep = { agent_id: number; task: string; model: string | null }
+/**
+ * A command a plugin adds to Claudia
+ */
+export type PluginCommand = { id: string; title: string; description?: string | null }
+/**
+ * A discovered plugin; plugins that failed to start carry the error instead of a manifest
+ */
+export type PluginInfo = { id: string; path: string; manifest: PluginManifest | null; error: string | null }
+/**
+ * What a plugin answers to `initialize`
+ */
+export type PluginManifest = { name: string; version?: string | null; description?: string | null; commands?: PluginCommand[]; panels?: PluginPanel[] }
+/**
+ * A panel a plugin renders content for
+ */
+export type PluginPanel = { id: string; title: string; 
+/**
+ * Where the frontend shows the panel, e.g. "sidebar" or "project"
+ */
+location?: string | null }
+/**
+ * Information about a running agent process
+ */
+export type ProcessInfo = { run_id: number; process_type: ProcessType; pid: number; started_at: string; project_path: string; task: string; model: string }
+/**
+ * One process of a run's process tree
+ */
+export type ProcessSample = { pid: number; parent_pid: number | null; name: string; cpu_percent: number; memory_bytes: number }
+/**
+ * Type of process being tracked
+ */
+export type ProcessType = { AgentRun: { agent_id: number; agent_name: string } } | { ClaudeSession: { session_id: string } }
+/**
+ * Represents a project in the ~/.claude/projects directory
+ */
+export type Project = { 
+/**
+ * The project ID (derived from the directory name)
+ */
+id: string; 
+/**
+ * The original project path (decoded from the directory name)
+ */
+path: string; 
+/**
+ * List of session IDs (JSONL file names without extension)
+ */
+sessions: string[]; 
+/**
+ * Unix timestamp when the project directory was created
+ */
+created_at: number; 
+/**
+ * Whether the project directory is a git repository
+ */
+is_git_repo?: boolean }
+/**
+ * The environment variables of one project
+ */
+export type ProjectEnvProfile = { project_path: string; vars: EnvVar[] }
+/**
+ * Whether Claude Code may start a server defined in a project's .mcp.json
+ */
+export type ProjectServerApproval = { name: string; 
+/**
+ * "approved", "rejected" or "pending" (Claude Code will ask on next start)
+ */
+status: string }
+export type ProjectUsage = { project_path: string; project_name: string; total_cost: number; total_tokens: number; session_count: number; last_used: string }
+/**
+ * A project's totals with its daily series and model breakdown
+ */
+export type ProjectUsageBreakdown = ({ project_path: string; project_name: string; total_cost: number; total_tokens: number; session_count: number; last_used: string }) & { by_date: DailyUsage[]; by_model: ModelUsage[] }
+/**
+ * A reusable system prompt for ad-hoc sessions
+ */
+export type PromptPreset = { id: number | null; name: string; content: string; 
+/**
+ * "append" adds to Claude's default system prompt, "replace" swaps it out
+ */
+mode: string; created_at: string; updated_at: string }
+/**
+ * A file change the agent proposed during a plan-only run
+ */
+export type ProposedChange = { path: string; 
+/**
+ * Tool the agent wanted to use, e.g. "Edit" or "Write"
+ */
+tool: string; 
+/**
+ * Unified diff against the file as it is now
+ */
+diff: string }
+/**
+ * A provider that could not be read
+ */
+export type ProviderError = { provider: string; error: string }
+/**
+ * Proxy settings applied to every subprocess and HTTP request
+ */
+export type ProxySettings = { http_proxy: string | null; https_proxy: string | null; 
+/**
+ * Comma-separated hosts that bypass the proxy
+ */
+no_proxy: string | null }
+/**
+ * Information about a process running in a pseudo-terminal
+ */
+export type PtyProcessInfo = { id: string; pid: number | null; project_path: string; agent_id: number | null; started_at: string; rows: number; cols: number }
+/**
+ * SQL query result
+ */
+export type QueryResult = { columns: string[]; rows: JsonValue[][]; rows_affected: number | null; last_insert_rowid: number | null }
+/**
+ * An environment variable a registry server needs
+ */
+export type RegistryEnvVar = { name: string; description: string | null; required: boolean; 
+/**
+ * Stored in the keychain instead of the MCP config when installing
+ */
+secret: boolean }
+/**
+ * A server listed in an MCP registry
+ */
+export type RegistryServer = { name: string; description: string | null; version: string | null; repository_url: string | null; 
+/**
+ * "stdio" for packages, "sse" for hosted servers
+ */
+transport: string; command: string | null; args: string[]; url: string | null; env_vars: RegistryEnvVar[] }
+/**
+ * A project or model line of the report
+ */
+export type ReportLine = { name: string; cost: number; tokens: number }
+/**
+ * Settings of the REST API server, stored under `rest_api_server` in app_settings
+ */
+export type RestApiSettings = { enabled: boolean; port: number; 
+/**
+ * Bearer token clients must send; generated the first time the server is enabled
+ */
+token?: string }
+/**
+ * Settings plus whether the server is currently listening
+ */
+export type RestApiStatus = { settings: RestApiSettings; running: boolean; url: string }
+/**
+ * What a restore rewinds
+ */
+export type RestoreMode = 
+/**
+ * Files and conversation
+ */
+"full" | 
+/**
+ * Only the files; the conversation continues from where it is
+ */
+"files_only" | 
+/**
+ * Only the conversation position; the current files are kept
+ */
+"conversation_only"
+/**
+ * What a restore changed
+ */
+export type RestoreSummary = { manifest: BackupManifest; 
+/**
+ * Files written below `~/.claude`; existing checkpoint files are kept as they are
+ */
+files_restored: number; files_skipped: number; 
+/**
+ * Where the database that was replaced was moved to
+ */
+previous_database: string | null }
+/**
+ * Which checkpoints of a session survive a cleanup
+ * 
+ * A checkpoint is kept when either rule keeps it; the current checkpoint is
+ * always kept. Without any rule nothing is removed.
+ */
+export type RetentionPolicy = { 
+/**
+ * Keep this many of the newest checkpoints
+ */
+keepLast: number | null; 
+/**
+ * Keep the newest checkpoint of each of the last this many days that have checkpoints
+ */
+keepDaily: number | null; 
+/**
+ * Apply the policy after every automatic checkpoint
+ */
+autoCleanup?: boolean }
+/**
+ * When and how often agent runs that hit a transient API error are resumed
+ */
+export type RetryPolicy = { enabled: boolean; max_attempts: number; base_delay_secs: number; max_delay_secs: number }
+/**
+ * A file created, modified or deleted by an agent run
+ */
+export type RunArtifact = { id: number | null; run_id: number; path: string; change_type: string; source: string; size: number | null; sha256: string | null; collected_at: string; 
+/**
+ * Unified diff captured at collection time, only loaded on request
+ */
+diff?: string | null }
+/**
+ * Side-by-side comparison of two agent runs
+ */
+export type RunComparison = { a: ComparedRun; b: ComparedRun; delta: MetricsDelta; files: FileComparison[] }
+/**
+ * One stored log line of an agent run
+ */
+export type RunLogEntry = { id: number; run_id: number; 
+/**
+ * "stdout", "stderr" or "lifecycle"
+ */
+stream: string; 
+/**
+ * Position of the line among every line of the run, across streams
+ */
+seq: number | null; message: string; created_at: string }
+/**
+ * CPU and memory of a run, summed over Claude and everything it started
+ */
+export type RunResources = { run_id: number; pid: number; cpu_percent: number; memory_bytes: number; process_count: number; top_processes: ProcessSample[]; sampled_at: string }
+/**
+ * A Claude or agent process started by Claudia
+ */
+export type RunningProcess = { run_id: number; pid: number; 
+/**
+ * "agent" or "session"
+ */
+kind: string; agent_id: number | null; agent_name: string | null; session_id: string | null; project_path: string; task: string; model: string; started_at: string; elapsed_seconds: number; 
+/**
+ * Tokens streamed so far, when the run's output is being tracked
+ */
+total_tokens: number | null; estimated_cost_usd: number | null }
+/**
+ * Schema version of the database and of this build
+ */
+export type SchemaVersion = { version: number; latest_version: number; applied: AppliedMigration[] }
+/**
+ * A matcher together with the settings scope it comes from
+ */
+export type ScopedHookMatcher = ({ matcher?: string | null; hooks: HookCommand[] }) & { scope: string; event: string }
+/**
+ * Server status information
+ */
+export type ServerStatus = { 
+/**
+ * Whether the server is running
+ */
+running: boolean; 
+/**
+ * Last error message if any
+ */
+error: string | null; 
+/**
+ * Last checked timestamp
+ */
+last_checked: number | null }
+/**
+ * Represents a session with its metadata
+ */
+export type Session = { 
+/**
+ * The session ID (UUID)
+ */
+id: string; 
+/**
+ * The project ID this session belongs to
+ */
+project_id: string; 
+/**
+ * The project path
+ */
+project_path: string; 
+/**
+ * Optional todo data associated with this session
+ */
+todo_data: JsonValue | null; 
+/**
+ * Unix timestamp when the session file was created
+ */
+created_at: number; 
+/**
+ * First user message content (if available)
+ */
+first_message: string | null; 
+/**
+ * Timestamp of the first user message (if available)
+ */
+message_timestamp: string | null; 
+/**
+ * Whether the transcript has been compressed into the session archive
+ */
+archived?: boolean }
+/**
+ * An archived session transcript
+ */
+export type SessionArchive = { project_id: string; session_id: string; original_size: number; compressed_size: number; archived_at: string }
+/**
+ * The complete timeline for a session
+ */
+export type SessionTimeline = { 
+/**
+ * Session ID this timeline belongs to
+ */
+sessionId: string; 
+/**
+ * Root node of the timeline tree
+ */
+rootNode: TimelineNode | null; 
+/**
+ * ID of the current active checkpoint
+ */
+currentCheckpointId: string | null; 
+/**
+ * Whether auto-checkpointing is enabled
+ */
+autoCheckpointEnabled: boolean; 
+/**
+ * Strategy for automatic checkpoints
+ */
+checkpointStrategy: CheckpointStrategy; 
+/**
+ * Tokens between checkpoints of the token threshold strategy
+ */
+tokenThreshold?: number; 
+/**
+ * Total number of checkpoints in timeline
+ */
+totalCheckpoints: number; 
+/**
+ * Checkpoint of another session this timeline was forked from
+ */
+forkedFrom?: TimelineFork | null }
+/**
+ * A settings file as read from disk
+ */
+export type SettingsFile = { path: string; exists: boolean; 
+/**
+ * The raw file content, so the editor keeps the user's formatting
+ */
+content: string; 
+/**
+ * The parsed settings, absent when the file is not valid JSON
+ */
+data: JsonValue | null; issues: SettingsIssue[] }
+/**
+ * A problem found in a settings file
+ */
+export type SettingsIssue = { 
+/**
+ * JSON path of the offending value, e.g. `permissions.allow[2]`
+ */
+path: string; message: string; 
+/**
+ * Position of a JSON syntax error
+ */
+line: number | null; column: number | null; 
+/**
+ * An unknown key or value, e.g. from a newer Claude Code; reported but not rejected
+ */
+warning?: boolean }
+/**
+ * Outcome of writing a settings file
+ */
+export type SettingsWriteResult = { path: string; 
+/**
+ * Copy of the previous content, absent when the file did not exist
+ */
+backup_path: string | null }
+/**
+ * Represents a custom slash command
+ */
+export type SlashCommand = { 
+/**
+ * Unique identifier for the command (derived from file path)
+ */
+id: string; 
+/**
+ * Command name (without prefix)
+ */
+name: string; 
+/**
+ * Full command with prefix (e.g., "/project:optimize")
+ */
+full_command: string; 
+/**
+ * Command scope: "project" or "user"
+ */
+scope: string; 
+/**
+ * Optional namespace (e.g., "frontend" in "/project:frontend:component")
+ */
+namespace: string | null; 
+/**
+ * Path to the markdown file
+ */
+file_path: string; 
+/**
+ * Command content (markdown body)
+ */
+content: string; 
+/**
+ * Optional description from frontmatter
+ */
+description: string | null; 
+/**
+ * Allowed tools from frontmatter
+ */
+allowed_tools: string[]; 
+/**
+ * Hint for the arguments shown after the command name
+ */
+argument_hint?: string | null; 
+/**
+ * Model the command runs with, from frontmatter
+ */
+model?: string | null; 
+/**
+ * Whether the command has bash commands (!)
+ */
+has_bash_commands: boolean; 
+/**
+ * Whether the command has file references (@)
+ */
+has_file_references: boolean; 
+/**
+ * Whether the command uses $ARGUMENTS placeholder
+ */
+accepts_arguments: boolean }
+/**
+ * Represents a page of table data
+ */
+export type TableData = { table_name: string; columns: ColumnInfo[]; rows: (Partial<{ [key in string]: JsonValue }>)[]; total_rows: number; page: number; page_size: number; total_pages: number }
+/**
+ * Represents metadata about a database table
+ */
+export type TableInfo = { name: string; row_count: number; columns: ColumnInfo[] }
+export type TaskPriority = "low" | "normal" | "high"
+export type TaskStatus = "queued" | "running" | "completed" | "failed" | "cancelled"
+/**
+ * Optional OTLP/HTTP export of traces, stored under `otlp_export` in app_settings
+ */
+export type TelemetrySettings = { enabled: boolean; 
+/**
+ * Collector base URL, e.g. `http://localhost:4318`; `/v1/traces` is appended
+ */
+endpoint: string; 
+/**
+ * Extra request headers, such as `x-honeycomb-team` for hosted collectors
+ */
+headers?: Partial<{ [key in string]: string }>; service_name?: string }
+/**
+ * Origin of a session forked from another session's checkpoint
+ */
+export type TimelineFork = { sessionId: string; checkpointId: string }
+/**
+ * Represents a node in the timeline tree
+ */
+export type TimelineNode = { 
+/**
+ * The checkpoint at this node
+ */
+checkpoint: Checkpoint; 
+/**
+ * Child nodes (for branches/forks)
+ */
+children: TimelineNode[]; 
+/**
+ * IDs of file snapshots associated with this checkpoint
+ */
+fileSnapshotIds: string[] }
+/**
+ * How a rule set treats a tool as a whole
+ */
+export type ToolAccess = { tool: string; 
+/**
+ * Decision for uses no specific rule covers
+ */
+decision: PermissionDecision; 
+/**
+ * Rules that only cover some uses, such as `Bash(git diff:*)`
+ */
+scoped_rules: string[] }
+/**
+ * A tool use to check against a rule set, e.g. `Bash` with `npm run test`
+ */
+export type ToolCall = { tool: string; 
+/**
+ * The command, file path or URL the tool is used with
+ */
+input: string | null }
+export type UpdateChannel = "stable" | "beta"
+/**
+ * An available update
+ */
+export type UpdateInfo = { version: string; current_version: string; channel: UpdateChannel; 
+/**
+ * Release notes, usually Markdown
+ */
+notes: string | null; published_at: string | null; 
+/**
+ * Downloaded and waiting to be installed
+ */
+downloaded: boolean }
+export type UpdateSettings = { channel: UpdateChannel; 
+/**
+ * Check for an update shortly after startup and emit `update-available`
+ */
+check_on_startup: boolean }
+/**
+ * Spending limits in USD; a period without a limit is not checked
+ */
+export type UsageBudgets = { daily: number | null; weekly: number | null; monthly: number | null; 
+/**
+ * Also show a desktop notification when a threshold is crossed
+ */
+notify?: boolean }
+export type UsageEntry = { timestamp: string; model: string; input_tokens: number; output_tokens: number; cache_creation_tokens: number; cache_read_tokens: number; cost: number; 
+/**
+ * What prompt caching saved on this message
+ */
+cache_savings: number; session_id: string; project_path: string; 
+/**
+ * Set when the session was started by an agent run
+ */
+agent_id?: number | null; agent_name?: string | null }
+/**
+ * Usage of one week compared with the week before
+ */
+export type UsageReport = { week_start: string; week_end: string; total_cost: number; total_tokens: number; sessions: number; previous_total_cost: number; 
+/**
+ * Change in cost vs. the previous week in percent; `None` when that week had no spend
+ */
+cost_change_percent: number | null; cache_savings: number; top_projects: ReportLine[]; top_models: ReportLine[]; 
+/**
+ * "markdown" or "html"
+ */
+format: string; 
+/**
+ * The rendered report
+ */
+content: string }
+/**
+ * When the weekly report is generated automatically
+ */
+export type UsageReportSchedule = { enabled: boolean; 
+/**
+ * Day of the week, 0 = Monday
+ */
+weekday: number; 
+/**
+ * Local hour of the day, 0-23
+ */
+hour: number; 
+/**
+ * "markdown" or "html"
+ */
+format: string; 
+/**
+ * Also show a desktop notification when a report is ready
+ */
+notify?: boolean }
+export type UsageStats = { total_cost: number; total_tokens: number; total_input_tokens: number; total_output_tokens: number; total_cache_creation_tokens: number; total_cache_read_tokens: number; 
+/**
+ * Net saving from prompt caching: cheaper cache reads minus the cache write premium
+ */
+cache_savings: number; 
+/**
+ * What the same usage would have cost without caching
+ */
+cost_without_cache: number; 
+/**
+ * Share of prompt tokens served from cache
+ */
+cache_hit_rate: number; total_sessions: number; by_model: ModelUsage[]; by_date: DailyUsage[]; by_project: ProjectUsage[]; 
+/**
+ * Usage of sessions started by agent runs
+ */
+by_agent: AgentUsage[] }
+/**
+ * Where and how sessions are exported as Markdown notes, e.g. into an Obsidian vault
+ */
+export type VaultExportSettings = { 
+/**
+ * Root of the vault; empty turns the export off
+ */
+vault_path: string; 
+/**
+ * Folder inside the vault that holds the Sessions and Projects notes
+ */
+folder: string; 
+/**
+ * Note body; {{title}}, {{date}}, {{project}}, {{session_id}}, {{cost}} and
+ * {{transcript}} are replaced
+ */
+note_template: string; 
+/**
+ * Tags added to every note's frontmatter
+ */
+tags?: string[]; 
+/**
+ * Include tool calls as collapsed callouts
+ */
+include_tool_calls?: boolean }
+/**
+ * Outcome of a vault sync
+ */
+export type VaultSyncResult = { written: number; unchanged: number; 
+/**
+ * Sessions that could not be exported, with the reason
+ */
+failed: string[] }
+/**
+ * What a subscriber wants to hear about below `~/.claude/projects`
+ */
+export type WatchScope = 
+/**
+ * Projects and session files appearing, disappearing or changing
+ */
+{ type: "projects" } | 
+/**
+ * A single session's JSONL transcript
+ */
+{ type: "session"; project_id: string; session_id: string }
+/**
+ * An outgoing webhook called on run lifecycle events
+ */
+export type Webhook = { id: number | null; name: string; url: string; 
+/**
+ * Whether payloads are signed; the key itself stays in the keychain
+ */
+has_secret: boolean; 
+/**
+ * Subscribed events; empty means all of them
+ */
+events: string[]; enabled: boolean; last_status: number | null; last_error: string | null; last_delivered_at: string | null; created_at: string }
+
+/** tauri-specta globals **/
+
+import {
+	invoke as TAURI_INVOKE,
+	Channel as TAURI_CHANNEL,
+} from "@tauri-apps/api/core";
+import * as TAURI_API_EVENT from "@tauri-apps/api/event";
+import { type WebviewWindow as __WebviewWindow__ } from "@tauri-apps/api/webviewWindow";
+
+type __EventObj__<T> = {
+	listen: (
+		cb: TAURI_API_EVENT.EventCallback<T>,
+	) => ReturnType<typeof TAURI_API_EVENT.listen<T>>;
+	once: (
+		cb: TAURI_API_EVENT.EventCallback<T>,
+	) => ReturnType<typeof TAURI_API_EVENT.once<T>>;
+	emit: null extends T
+		? (payload?: T) => ReturnType<typeof TAURI_API_EVENT.emit>
+		: (payload: T) => ReturnType<typeof TAURI_API_EVENT.emit>;
+};
+
+export type Result<T, E> =
+	| { status: "ok"; data: T }
+	| { status: "error"; error: E };
+
+function __makeEvents__<T extends Record<string, any>>(
+	mappings: Record<keyof T, string>,
+) {
+	return new Proxy(
+		{} as unknown as {
+			[K in keyof T]: __EventObj__<T[K]> & {
+				(handle: __WebviewWindow__): __EventObj__<T[K]>;
+			};
+		},
+		{
+			get: (_, event) => {
+				const name = mappings[event as keyof T];
+
+				return new Proxy((() => {}) as any, {
+					apply: (_, __, [window]: [__WebviewWindow__]) => ({
+						listen: (arg: any) => window.listen(name, arg),
+						once: (arg: any) => window.once(name, arg),
+						emit: (arg: any) => window.emit(name, arg),
+					}),
+					get: (_, command: keyof __EventObj__<any>) => {
+						switch (command) {
+							case "listen":
+								return (arg: any) => TAURI_API_EVENT.listen(name, arg);
+							case "once":
+								return (arg: any) => TAURI_API_EVENT.once(name, arg);
+							case "emit":
+								return (arg: any) => TAURI_API_EVENT.emit(name, arg);
+						}
+					},
+				});
+			},
+		},
+	);
+}